@@ -0,0 +1,111 @@
+//! measure the Dijkstra's algorithm of [`CompleteGraph`] on circuit-level planar codes with d = 11 and d = 15, which
+//! underpins the verification and the subgraph expansion:
+//!
+//! ```sh
+//! cargo bench --bench complete_graph
+//! ```
+//!
+//! the construction is measured once per code, and the others both on the original weights and with every 20th edge
+//! erased; the number of popped vertices per call is printed so that an algorithmic regression can be told apart from
+//! noise in the wall clock time
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fusion_blossom::complete_graph::CompleteGraph;
+use fusion_blossom::example_codes::*;
+use fusion_blossom::util::*;
+use fusion_blossom::visualize::VisualizePosition;
+
+const P: f64 = 0.01;
+const ERASURE_INTERVAL: usize = 20;
+
+/// a vertex in the middle of the lattice and a real vertex next to a virtual one
+#[allow(clippy::unnecessary_cast)]
+fn bulk_and_boundary_vertices(code: &CircuitLevelPlanarCode, initializer: &SolverInitializer) -> (VertexIndex, VertexIndex) {
+    let positions = code.get_positions();
+    let is_virtual = |vertex_index: VertexIndex| initializer.virtual_vertices.contains(&vertex_index);
+    let center = |axis: fn(&VisualizePosition) -> f64| {
+        let (min, max) = positions
+            .iter()
+            .map(axis)
+            .fold((f64::MAX, f64::MIN), |(min, max), x| (min.min(x), max.max(x)));
+        (min + max) / 2.
+    };
+    let (i, j, t) = (center(|p| p.i), center(|p| p.j), center(|p| p.t));
+    let distance = |p: &VisualizePosition| (p.i - i).powi(2) + (p.j - j).powi(2) + (p.t - t).powi(2);
+    let bulk = (0..initializer.vertex_num as VertexIndex)
+        .filter(|&vertex_index| !is_virtual(vertex_index))
+        .min_by(|&a, &b| distance(&positions[a as usize]).total_cmp(&distance(&positions[b as usize])))
+        .unwrap();
+    let boundary = (initializer.weighted_edges.iter())
+        .find_map(
+            |&(vertex_1, vertex_2, _)| match (is_virtual(vertex_1), is_virtual(vertex_2)) {
+                (true, false) => Some(vertex_2),
+                (false, true) => Some(vertex_1),
+                _ => None,
+            },
+        )
+        .unwrap();
+    (bulk, boundary)
+}
+
+fn complete_graph(c: &mut Criterion) {
+    for d in [11, 15] {
+        let code = CircuitLevelPlanarCode::new(d, d - 1, P, 500);
+        let initializer = code.get_initializer();
+        let (bulk, boundary) = bulk_and_boundary_vertices(&code, &initializer);
+        let mut group = c.benchmark_group(format!("circuit-level d={d}"));
+        group.bench_function("new", |b| {
+            b.iter(|| CompleteGraph::new(initializer.vertex_num, black_box(&initializer.weighted_edges)))
+        });
+        let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+        // the nearest and the farthest real vertices from the bulk vertex, and one in between to terminate early
+        let mut peers: Vec<(Weight, VertexIndex)> = (complete_graph.all_edges(bulk).iter())
+            .filter(|(peer, _)| !initializer.virtual_vertices.contains(peer))
+            .map(|(&peer, &(_, weight))| (weight, peer))
+            .collect();
+        peers.sort();
+        let (near, middle, far) = (peers[0].1, peers[peers.len() / 2].1, peers[peers.len() - 1].1);
+        let erasures: Vec<EdgeIndex> = (0..initializer.weighted_edges.len() as EdgeIndex)
+            .step_by(ERASURE_INTERVAL)
+            .collect();
+        for is_erased in [false, true] {
+            if is_erased {
+                complete_graph.load_erasures(&erasures);
+            }
+            let suffix = if is_erased { ", erased" } else { "" };
+            let mut bench = |name: &str, operation: &dyn Fn(&mut CompleteGraph)| {
+                let popped_vertices = complete_graph.popped_vertices();
+                operation(&mut complete_graph);
+                println!(
+                    "{name}{suffix}: {} popped vertices",
+                    complete_graph.popped_vertices() - popped_vertices
+                );
+                group.bench_function(format!("{name}{suffix}"), |b| b.iter(|| operation(&mut complete_graph)));
+            };
+            bench("all_edges bulk", &|graph| {
+                black_box(graph.all_edges(bulk));
+            });
+            bench("all_edges boundary", &|graph| {
+                black_box(graph.all_edges(boundary));
+            });
+            bench("all_edges_with_terminate middle", &|graph| {
+                black_box(graph.all_edges_with_terminate(bulk, middle));
+            });
+            bench("get_path near", &|graph| {
+                black_box(graph.get_path(bulk, near));
+            });
+            bench("get_path far", &|graph| {
+                black_box(graph.get_path(bulk, far));
+            });
+        }
+        complete_graph.reset();
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = complete_graph
+}
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+//! decode with the parallel solver on a phenomenological planar code split into 32 partitions along the time axis,
+//! where each shot goes through 31 fusions:
+//!
+//! ```sh
+//! cargo bench --bench fusion_interface -- --save-baseline before  # on the revision to compare against
+//! cargo bench --bench fusion_interface -- --baseline before
+//! ```
+//!
+//! the throughput is reported per fusion operation. `decode` times the whole shots, while `fusion` only sums up the time
+//! each fusion unit spends fusing its children and breaking the matchings to its interface, see
+//! [`PrimalModuleParallelUnitEventTime::fused`], which is where the boundary node summaries are exchanged
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use fusion_blossom::dual_module::*;
+use fusion_blossom::dual_module_parallel::*;
+use fusion_blossom::dual_module_serial::*;
+use fusion_blossom::example_codes::*;
+use fusion_blossom::example_partition::*;
+use fusion_blossom::mwpm_solver::*;
+use fusion_blossom::primal_module::*;
+use fusion_blossom::primal_module_parallel::*;
+use fusion_blossom::util::*;
+use std::time::Duration;
+
+const D: VertexNum = 7;
+const P: f64 = 0.01;
+const PARTITION_NUM: usize = 32;
+/// 2 rounds per partition
+const NOISY_MEASUREMENTS: VertexNum = 2 * PARTITION_NUM as VertexNum - 1;
+const SHOTS: usize = 20;
+
+fn fusion_interface(c: &mut Criterion) {
+    let mut code = PhenomenologicalPlanarCode::new(D, NOISY_MEASUREMENTS, P, 500);
+    let mut partition = PhenomenologicalPlanarCodeTimePartition::new_tree(D, NOISY_MEASUREMENTS, PARTITION_NUM, true, 8);
+    let partition_config = partition.build_apply(&mut code);
+    let partition_info = partition_config.info();
+    let partition_num = partition_info.config.partitions.len();
+    let fusion_num = partition_info.units.len() - partition_num;
+    let initializer = code.get_initializer();
+    let syndrome_patterns: Vec<_> = (0..SHOTS as u64).map(|seed| code.generate_random_errors(seed)).collect();
+    let mut group = c.benchmark_group(format!(
+        "phenomenological d={D} p={P}, {PARTITION_NUM} partitions, {SHOTS} shots"
+    ));
+    group.throughput(Throughput::Elements((fusion_num * SHOTS) as u64));
+    let mut solver = SolverParallel::new(&initializer, &partition_info, serde_json::json!({}));
+    group.bench_function("decode", |b| {
+        b.iter(|| {
+            for syndrome_pattern in syndrome_patterns.iter() {
+                solver.solve(syndrome_pattern);
+                criterion::black_box(solver.subgraph());
+                solver.clear();
+            }
+        })
+    });
+    let mut dual_module = DualModuleParallel::<DualModuleSerial>::new_config(
+        &initializer,
+        &partition_info,
+        DualModuleParallelConfig::default(),
+    );
+    let mut primal_module =
+        PrimalModuleParallel::new_config(&initializer, &partition_info, PrimalModuleParallelConfig::default());
+    group.bench_function("fusion", |b| {
+        b.iter_custom(|iterations| {
+            let mut fusion_time = 0.;
+            for _ in 0..iterations {
+                for syndrome_pattern in syndrome_patterns.iter() {
+                    primal_module.parallel_solve(syndrome_pattern, &mut dual_module);
+                    for unit_ptr in primal_module.units[partition_num..].iter() {
+                        let event_time = unit_ptr.read_recursive().event_time.clone().unwrap();
+                        fusion_time += event_time.fused - event_time.start;
+                    }
+                    primal_module.clear();
+                    dual_module.clear();
+                }
+            }
+            Duration::from_secs_f64(fusion_time)
+        })
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = fusion_interface
+}
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+//! compare the serial solver with and without the `slab_nodes` feature on a circuit-level planar code with d = 11 and
+//! p = 0.01; run both and compare the reports:
+//!
+//! ```sh
+//! cargo bench --bench slab_nodes
+//! cargo bench --bench slab_nodes --features slab_nodes
+//! ```
+//!
+//! besides the decoding time measured by criterion, the number of heap allocations and the peak of the allocated bytes
+//! while decoding the same syndrome patterns are printed, counted by a global allocator; note that the serial modules
+//! already reuse their internal node pointers across shots when not in fusion mode, so the allocation count mostly comes
+//! from other buffers and the slabs mainly help locality and the fusion mode where nodes are created afresh
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fusion_blossom::example_codes::*;
+use fusion_blossom::mwpm_solver::*;
+use fusion_blossom::util::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK_ALLOCATED.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const D: VertexNum = 11;
+const P: f64 = 0.01;
+const SHOTS: usize = 100;
+
+fn syndrome_patterns() -> (SolverInitializer, Vec<SyndromePattern>) {
+    let mut code = CircuitLevelPlanarCode::new(D, D - 1, P, 500);
+    let syndrome_patterns = (0..SHOTS as u64).map(|seed| code.generate_random_errors(seed)).collect();
+    (code.get_initializer(), syndrome_patterns)
+}
+
+fn decode_all(solver: &mut SolverSerial, syndrome_patterns: &[SyndromePattern]) {
+    for syndrome_pattern in syndrome_patterns.iter() {
+        solver.solve(syndrome_pattern);
+        criterion::black_box(solver.subgraph());
+        solver.clear();
+    }
+}
+
+fn slab_nodes(c: &mut Criterion) {
+    let (initializer, syndrome_patterns) = syndrome_patterns();
+    let mut solver = SolverSerial::new(&initializer);
+    decode_all(&mut solver, &syndrome_patterns); // warm up, e.g. to fill the slabs
+    let (allocations, base) = (ALLOCATIONS.load(Ordering::Relaxed), ALLOCATED.load(Ordering::Relaxed));
+    PEAK_ALLOCATED.store(base, Ordering::Relaxed);
+    decode_all(&mut solver, &syndrome_patterns);
+    println!(
+        "slab_nodes {}: {:.1} allocations per shot, peak {} bytes above the solver",
+        if cfg!(feature = "slab_nodes") { "enabled" } else { "disabled" },
+        (ALLOCATIONS.load(Ordering::Relaxed) - allocations) as f64 / SHOTS as f64,
+        PEAK_ALLOCATED.load(Ordering::Relaxed) - base,
+    );
+    c.bench_function(&format!("circuit-level d={D} p={P}, {SHOTS} shots"), |b| {
+        b.iter(|| decode_all(&mut solver, &syndrome_patterns))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = slab_nodes
+}
+criterion_main!(benches);
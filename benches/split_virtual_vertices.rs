@@ -0,0 +1,79 @@
+//! decode dense-boundary syndromes where all the boundary edges on each side of a code-capacity planar code with d = 21
+//! and p = 0.05 share a single virtual vertex, before and after [`SolverInitializer::split_virtual_vertices`]:
+//!
+//! ```sh
+//! cargo bench --bench split_virtual_vertices
+//! ```
+//!
+//! the matching weights are checked to be the same before measuring
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fusion_blossom::example_codes::*;
+use fusion_blossom::mwpm_solver::*;
+use fusion_blossom::util::*;
+
+const D: VertexNum = 21;
+const P: f64 = 0.05;
+const SHOTS: usize = 100;
+const MAX_DEGREE: usize = 4;
+
+/// route the boundary edges on each side to the first virtual vertex of that side
+fn merged_boundary_initializer(code: &CodeCapacityPlanarCode) -> SolverInitializer {
+    let mut initializer = code.get_initializer();
+    let (left, right) = code.logical_boundaries();
+    for edge in initializer.weighted_edges.iter_mut() {
+        for vertex in [&mut edge.0, &mut edge.1] {
+            if left.contains(vertex) {
+                *vertex = left[0];
+            } else if right.contains(vertex) {
+                *vertex = right[0];
+            }
+        }
+    }
+    initializer
+}
+
+fn decode_all(solver: &mut SolverSerial, syndrome_patterns: &[SyndromePattern]) -> Weight {
+    let mut weight = 0;
+    for syndrome_pattern in syndrome_patterns.iter() {
+        solver.solve(syndrome_pattern);
+        weight += solver.sum_dual_variables();
+        black_box(solver.subgraph());
+        solver.clear();
+    }
+    weight
+}
+
+fn split_virtual_vertices(c: &mut Criterion) {
+    let mut code = CodeCapacityPlanarCode::new(D, P, 500);
+    let syndrome_patterns: Vec<_> = (0..SHOTS as u64).map(|seed| code.generate_random_errors(seed)).collect();
+    let merged = merged_boundary_initializer(&code);
+    let mut split = merged.clone();
+    let split_map = split.split_virtual_vertices(MAX_DEGREE);
+    println!(
+        "{} copies of virtual vertices with at most {MAX_DEGREE} edges each",
+        split_map.copy_of.len()
+    );
+    let mut merged_solver = SolverSerial::new(&merged);
+    let mut split_solver = SolverSerial::new(&split);
+    assert_eq!(
+        decode_all(&mut merged_solver, &syndrome_patterns),
+        decode_all(&mut split_solver, &syndrome_patterns),
+        "matching weights must be the same"
+    );
+    let mut group = c.benchmark_group(format!("code-capacity d={D} p={P}, {SHOTS} shots"));
+    group.bench_function("merged boundary", |b| {
+        b.iter(|| decode_all(&mut merged_solver, &syndrome_patterns))
+    });
+    group.bench_function(format!("split boundary (max degree {MAX_DEGREE})"), |b| {
+        b.iter(|| decode_all(&mut split_solver, &syndrome_patterns))
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = split_virtual_vertices
+}
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+//! compare loading the syndrome as a [`SyndromePattern`] and as a [`SyndromeBitmap`] on a circuit-level planar code with
+//! d = 7 and p = 0.05, where about a fifth of the vertices are defects:
+//!
+//! ```sh
+//! cargo bench --bench syndrome_bitmap
+//! ```
+//!
+//! both start from the dense measurement results; `to sparse` and `to bitmap` only build the representation and
+//! enumerate the defects, while `decode` also solves and extracts the subgraph
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fusion_blossom::example_codes::*;
+use fusion_blossom::mwpm_solver::*;
+use fusion_blossom::util::*;
+
+const D: VertexNum = 7;
+const P: f64 = 0.05;
+const SHOTS: usize = 100;
+
+#[allow(clippy::unnecessary_cast)]
+fn dense_measurements() -> (SolverInitializer, Vec<Vec<bool>>) {
+    let mut code = CircuitLevelPlanarCode::new(D, D - 1, P, 500);
+    let initializer = code.get_initializer();
+    let measurements = (0..SHOTS as u64)
+        .map(|seed| {
+            let mut dense = vec![false; initializer.vertex_num as usize];
+            for &vertex_index in code.generate_random_errors(seed).defect_vertices.iter() {
+                dense[vertex_index as usize] = true;
+            }
+            dense
+        })
+        .collect();
+    (initializer, measurements)
+}
+
+fn to_bitmap(dense: &[bool], edge_num: usize) -> SyndromeBitmap {
+    let mut bitmap = SyndromeBitmap::new(dense.len() as VertexNum, edge_num);
+    for (vertex_index, _) in dense.iter().enumerate().filter(|(_, &is_defect)| is_defect) {
+        bitmap.defects.set(vertex_index);
+    }
+    bitmap
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn syndrome_bitmap(c: &mut Criterion) {
+    let (initializer, measurements) = dense_measurements();
+    let edge_num = initializer.weighted_edges.len();
+    let defect_num: usize = measurements.iter().map(|dense| dense.iter().filter(|&&x| x).count()).sum();
+    println!(
+        "{} vertices, defect density {:.3}",
+        initializer.vertex_num,
+        defect_num as f64 / (SHOTS * initializer.vertex_num as usize) as f64
+    );
+    let mut group = c.benchmark_group(format!("circuit-level d={D} p={P}, {SHOTS} shots"));
+    group.bench_function("to sparse", |b| {
+        b.iter(|| {
+            for dense in measurements.iter() {
+                black_box(SyndromePattern::from_dense(dense).defect_vertices.iter().sum::<VertexIndex>());
+            }
+        })
+    });
+    group.bench_function("to bitmap", |b| {
+        b.iter(|| {
+            for dense in measurements.iter() {
+                black_box(to_bitmap(dense, edge_num).defect_vertices().sum::<VertexIndex>());
+            }
+        })
+    });
+    let mut solver = SolverSerial::new(&initializer);
+    group.bench_function("decode sparse", |b| {
+        b.iter(|| {
+            for dense in measurements.iter() {
+                solver.solve(&SyndromePattern::from_dense(dense));
+                black_box(solver.subgraph());
+                solver.clear();
+            }
+        })
+    });
+    group.bench_function("decode bitmap", |b| {
+        b.iter(|| {
+            for dense in measurements.iter() {
+                solver.solve_bitmap(&to_bitmap(dense, edge_num));
+                black_box(solver.subgraph());
+                solver.clear();
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = syndrome_bitmap
+}
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+//! compare the single-threaded serial solver with and without the `unsafe_fast` feature on a circuit-level planar code
+//! with d = 11 and p = 0.01; run both and compare the reports:
+//!
+//! ```sh
+//! cargo bench --bench unsafe_fast
+//! cargo bench --bench unsafe_fast --features unsafe_fast
+//! ```
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fusion_blossom::example_codes::*;
+use fusion_blossom::mwpm_solver::*;
+use fusion_blossom::util::*;
+
+const D: VertexNum = 11;
+const P: f64 = 0.01;
+const SHOTS: usize = 100;
+
+fn unsafe_fast(c: &mut Criterion) {
+    let mut code = CircuitLevelPlanarCode::new(D, D - 1, P, 500);
+    let syndrome_patterns: Vec<_> = (0..SHOTS as u64).map(|seed| code.generate_random_errors(seed)).collect();
+    let mut solver = SolverSerial::new(&code.get_initializer());
+    let name = format!(
+        "circuit-level d={D} p={P}, {SHOTS} shots, unsafe_fast {}",
+        if cfg!(feature = "unsafe_fast") {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    c.bench_function(&name, |b| {
+        b.iter(|| {
+            for syndrome_pattern in syndrome_patterns.iter() {
+                solver.solve(syndrome_pattern);
+                criterion::black_box(solver.subgraph());
+                solver.clear();
+            }
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = unsafe_fast
+}
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+"""
+compare the decoding time of the same syndrome patterns before and after reordering the vertices along a Hilbert curve:
+deterministic seeds generate the same errors because reordering the vertices doesn't change the order of edges
+"""
+
+import os, sys
+import subprocess, sys
+git_root_dir = subprocess.run("git rev-parse --show-toplevel", cwd=os.path.dirname(os.path.abspath(__file__))
+    , shell=True, check=True, capture_output=True).stdout.decode(sys.stdout.encoding).strip(" \r\n")
+# useful folders
+rust_dir = git_root_dir
+benchmark_dir = os.path.join(git_root_dir, "benchmark")
+script_dir = os.path.dirname(__file__)
+tmp_dir = os.path.join(script_dir, "tmp")
+os.makedirs(tmp_dir, exist_ok=True)  # make sure tmp directory exists
+sys.path.insert(0, benchmark_dir)
+
+import util
+from util import *
+util.FUSION_BLOSSOM_ENABLE_UNSAFE_POINTER = True  # better performance, still safe
+compile_code_if_necessary()
+import numpy as np
+
+d = 15
+p = 0.005
+total_rounds = 1000
+noisy_measurements = d - 1
+ordering_vec = [None, "HilbertCurve"]
+
+data_file = os.path.join(script_dir, "data.txt")
+with open(data_file, "w", encoding="utf8") as f:
+    f.write("<ordering> <median_time> <average_time> <stddev_time> <speedup>\n")
+
+    baseline_average_time = None
+    for ordering in ordering_vec:
+        name = "Original" if ordering is None else ordering
+        benchmark_profile_path = os.path.join(tmp_dir, f"{name}.profile")
+        code_config = {} if ordering is None else {"vertex_ordering": ordering}
+        command = fusion_blossom_benchmark_command(d=d, p=p, total_rounds=total_rounds, noisy_measurements=noisy_measurements)
+        command += ["--code-type", "circuit-level-planar-code"]
+        command += ["--code-config", json.dumps(code_config)]
+        command += ["--primal-dual-type", "serial"]
+        command += ["--verifier", "none"]
+        command += ["--use-deterministic-seed"]
+        command += ["--benchmark-profiler-output", benchmark_profile_path]
+        print(command)
+        stdout, returncode = run_command_get_stdout(command)
+        print("\n" + stdout)
+        assert returncode == 0, "command fails..."
+
+        profile = Profile(benchmark_profile_path)
+        time_vec = [entry["events"]["decoded"] for entry in profile.entries]
+        median_time = np.median(time_vec)
+        average_time = sum(time_vec) / len(time_vec)
+        stddev_time = math.sqrt(sum([(time - average_time) ** 2 for time in time_vec]) / len(time_vec))
+        if baseline_average_time is None:
+            baseline_average_time = average_time
+        speedup = baseline_average_time / average_time
+        print(f"{name}: median {median_time}, average {average_time}, stddev {stddev_time}, speedup {speedup:.3f}x")
+        f.write("%s %.5e %.5e %.3e %.3f\n" % (
+            name,
+            median_time,
+            average_time,
+            stddev_time,
+            speedup,
+        ))
+        f.flush()
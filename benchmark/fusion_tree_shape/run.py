@@ -0,0 +1,82 @@
+"""
+compare the decoding latency of different fusion tree shapes on the same syndrome patterns:
+a linear chain minimizes the peak memory while a balanced binary tree minimizes the fusion depth
+"""
+
+import os, sys
+import subprocess, sys
+git_root_dir = subprocess.run("git rev-parse --show-toplevel", cwd=os.path.dirname(os.path.abspath(__file__))
+    , shell=True, check=True, capture_output=True).stdout.decode(sys.stdout.encoding).strip(" \r\n")
+# useful folders
+rust_dir = git_root_dir
+benchmark_dir = os.path.join(git_root_dir, "benchmark")
+script_dir = os.path.dirname(__file__)
+tmp_dir = os.path.join(script_dir, "tmp")
+os.makedirs(tmp_dir, exist_ok=True)  # make sure tmp directory exists
+sys.path.insert(0, benchmark_dir)
+
+import util
+from util import *
+util.FUSION_BLOSSOM_ENABLE_UNSAFE_POINTER = True  # better performance, still safe
+compile_code_if_necessary()
+import numpy as np
+
+d = 11
+p = 0.005
+total_rounds = 200
+benchmark_total_run = 2 * total_rounds  # run benchmark longer to get rid of cold start
+noisy_measurements = 160 - 1  # 160 rounds of measurement
+partition_num = 16
+thread_pool_size = 16
+shape_vec = ["LinearChain", "BalancedBinary"]
+
+syndrome_file_path = os.path.join(tmp_dir, "generated.syndromes")
+if os.path.exists(syndrome_file_path):
+    print("[warning] use existing syndrome data (if you think it's stale, delete it and rerun)")
+else:
+    command = fusion_blossom_benchmark_command(d=d, p=p, total_rounds=total_rounds, noisy_measurements=noisy_measurements)
+    command += ["--code-type", "phenomenological-planar-code-parallel"]
+    command += ["--code-config", '{"code_count":10}']
+    command += ["--primal-dual-type", "error-pattern-logger"]
+    command += ["--primal-dual-config", f'{{"filename":"{syndrome_file_path}"}}']
+    command += ["--verifier", "none"]
+    print(command)
+    stdout, returncode = run_command_get_stdout(command)
+    print("\n" + stdout)
+    assert returncode == 0, "command fails..."
+
+data_file = os.path.join(script_dir, "data.txt")
+with open(data_file, "w", encoding="utf8") as f:
+    f.write("<shape> <fusion_depth> <median_latency> <average_latency> <stddev_latency>\n")
+
+    for shape in shape_vec:
+        benchmark_profile_path = os.path.join(tmp_dir, f"{shape}.profile")
+        command = fusion_blossom_benchmark_command(d=d, p=p, total_rounds=benchmark_total_run, noisy_measurements=noisy_measurements)
+        command += ["--code-type", "error-pattern-reader"]
+        command += ["--code-config", f'{{"filename":"{syndrome_file_path}","cyclic_syndrome":true}}']
+        command += ["--primal-dual-type", "parallel"]
+        command += ["--primal-dual-config", f'{{"thread_pool_size":{thread_pool_size}}}']
+        command += ["--partition-strategy", "phenomenological-planar-code-time-partition"]
+        command += ["--partition-config", f'{{"partition_num":{partition_num},"fusion_tree_shape":"{shape}"}}']
+        command += ["--verifier", "none"]
+        command += ["--benchmark-profiler-output", benchmark_profile_path]
+        print(command)
+        stdout, returncode = run_command_get_stdout(command)
+        print("\n" + stdout)
+        assert returncode == 0, "command fails..."
+
+        profile = Profile(benchmark_profile_path, benchmark_total_run-total_rounds)
+        fusion_depth = profile.benchmark_config["fusion_depth"]
+        latency_vec = [entry["events"]["decoded"] for entry in profile.entries]
+        median_latency = np.median(latency_vec)
+        average_latency = sum(latency_vec) / len(latency_vec)
+        stddev_latency = math.sqrt(sum([(time - average_latency) ** 2 for time in latency_vec]) / len(latency_vec))
+        print(f"{shape} (fusion depth {fusion_depth}): median {median_latency}, average {average_latency}, stddev {stddev_latency}")
+        f.write("%s %d %.5e %.5e %.3e\n" % (
+            shape,
+            fusion_depth,
+            median_latency,
+            average_latency,
+            stddev_latency,
+        ))
+        f.flush()
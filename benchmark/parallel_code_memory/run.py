@@ -0,0 +1,54 @@
+"""
+the peak memory of generating syndrome patterns with `circuit-level-planar-code-parallel` for different `code_count`:
+the workers share the decoding graph, so the memory should stay almost constant instead of growing with `code_count`
+"""
+
+import os, sys
+import subprocess, sys
+git_root_dir = subprocess.run("git rev-parse --show-toplevel", cwd=os.path.dirname(os.path.abspath(__file__))
+    , shell=True, check=True, capture_output=True).stdout.decode(sys.stdout.encoding).strip(" \r\n")
+# useful folders
+rust_dir = git_root_dir
+benchmark_dir = os.path.join(git_root_dir, "benchmark")
+script_dir = os.path.dirname(__file__)
+tmp_dir = os.path.join(script_dir, "tmp")
+os.makedirs(tmp_dir, exist_ok=True)  # make sure tmp directory exists
+sys.path.insert(0, benchmark_dir)
+
+import util
+from util import *
+compile_code_if_necessary()
+
+d = 21
+p = 0.001
+total_rounds = 200
+noisy_measurements = d - 1
+code_count_vec = [1, 4, 16, 64]
+
+# the maximum resident set size of a child process in KB, measured in a fresh python process so that it only covers one run
+measure_peak_memory = "import resource, subprocess, sys; subprocess.run(sys.argv[1:], check=True, stdout=subprocess.DEVNULL);" \
+    + " print(resource.getrusage(resource.RUSAGE_CHILDREN).ru_maxrss)"
+
+data_file = os.path.join(script_dir, "data.txt")
+with open(data_file, "w", encoding="utf8") as f:
+    f.write("<code_count> <peak_memory_kb> <ratio>\n")
+
+    baseline_memory = None
+    for code_count in code_count_vec:
+        syndrome_file = os.path.join(tmp_dir, f"code_count_{code_count}.syndromes")
+        command = fusion_blossom_benchmark_command(d=d, p=p, total_rounds=total_rounds, noisy_measurements=noisy_measurements)
+        command += ["--code-type", "circuit-level-planar-code-parallel"]
+        command += ["--code-config", json.dumps({"code_count": code_count})]
+        command += ["--primal-dual-type", "error-pattern-logger"]
+        command += ["--primal-dual-config", json.dumps({"filename": syndrome_file})]
+        command += ["--verifier", "none"]
+        print(command)
+        stdout, returncode = run_command_get_stdout([sys.executable, "-c", measure_peak_memory] + command)
+        assert returncode == 0, "command fails..."
+        peak_memory = int(stdout.strip(" \r\n").split("\n")[-1])
+        if baseline_memory is None:
+            baseline_memory = peak_memory
+        ratio = peak_memory / baseline_memory
+        print(f"code_count {code_count}: peak memory {peak_memory} KB, {ratio:.3f}x")
+        f.write("%d %d %.3f\n" % (code_count, peak_memory, ratio))
+        f.flush()
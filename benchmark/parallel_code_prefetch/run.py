@@ -0,0 +1,54 @@
+"""
+the total time of decoding the syndrome patterns of `circuit-level-planar-code-parallel` with and without prefetching:
+without prefetching, the decoder stalls whenever a batch of `code_count` syndrome patterns runs out and the next batch is
+generated; with prefetching the next batches are generated in the background while decoding. deterministic seeds are
+required for prefetching, and the decoded syndrome patterns are the same in all cases
+"""
+
+import os, sys, time
+import subprocess, sys
+git_root_dir = subprocess.run("git rev-parse --show-toplevel", cwd=os.path.dirname(os.path.abspath(__file__))
+    , shell=True, check=True, capture_output=True).stdout.decode(sys.stdout.encoding).strip(" \r\n")
+# useful folders
+rust_dir = git_root_dir
+benchmark_dir = os.path.join(git_root_dir, "benchmark")
+script_dir = os.path.dirname(__file__)
+tmp_dir = os.path.join(script_dir, "tmp")
+os.makedirs(tmp_dir, exist_ok=True)  # make sure tmp directory exists
+sys.path.insert(0, benchmark_dir)
+
+import util
+from util import *
+util.FUSION_BLOSSOM_ENABLE_UNSAFE_POINTER = True  # better performance, still safe
+compile_code_if_necessary()
+
+d = 15
+p = 0.001
+total_rounds = 5000
+noisy_measurements = d - 1
+code_count = 16
+prefetch_depth_vec = [0, 1, 4]
+
+data_file = os.path.join(script_dir, "data.txt")
+with open(data_file, "w", encoding="utf8") as f:
+    f.write("<prefetch_depth> <total_time> <speedup>\n")
+
+    baseline_time = None
+    for prefetch_depth in prefetch_depth_vec:
+        command = fusion_blossom_benchmark_command(d=d, p=p, total_rounds=total_rounds, noisy_measurements=noisy_measurements)
+        command += ["--code-type", "circuit-level-planar-code-parallel"]
+        command += ["--code-config", json.dumps({"code_count": code_count, "prefetch_depth": prefetch_depth})]
+        command += ["--primal-dual-type", "serial"]
+        command += ["--verifier", "none"]
+        command += ["--use-deterministic-seed"]
+        print(command)
+        start = time.time()
+        stdout, returncode = run_command_get_stdout(command)
+        total_time = time.time() - start
+        assert returncode == 0, "command fails..."
+        if baseline_time is None:
+            baseline_time = total_time
+        speedup = baseline_time / total_time
+        print(f"prefetch_depth {prefetch_depth}: total time {total_time:.3f}s, speedup {speedup:.3f}x")
+        f.write("%d %.5e %.3f\n" % (prefetch_depth, total_time, speedup))
+        f.flush()
@@ -7,11 +7,33 @@ fn main() {
         println!("cargo:allow=dropping_references");
     }
 
+    // generate the C header of the `ffi` module, configured by `cbindgen.toml`
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        // parse only the `ffi` module, because cbindgen skips the whole module behind `#[cfg(feature = "ffi")]`
+        let config = cbindgen::Config::from_file(Path::new(&crate_dir).join("cbindgen.toml")).unwrap();
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(Path::new(&crate_dir).join("src/ffi.rs"))
+            .generate()
+            .expect("failed to generate the C header")
+            .write_to_file(Path::new(&crate_dir).join("include/fusion_blossom.h"));
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        // the C smoke test in `tests/ffi.rs` compiles for the same target
+        println!("cargo:rustc-env=FUSION_BLOSSOM_TARGET={}", env::var("TARGET").unwrap());
+    }
+
     // even if the blossom V library exists, sometimes we don't want to compile it
     let mut try_include_blossom_v = true;
     if cfg!(feature = "remove_blossom_v") {
         try_include_blossom_v = false;
     }
+    // the C++ library cannot be linked into WebAssembly
+    if env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32") {
+        try_include_blossom_v = false;
+    }
 
     if try_include_blossom_v && Path::new("./blossomV/PerfectMatching.h").exists() {
         println!("cargo:rustc-cfg=feature=\"blossom_v\"");
@@ -0,0 +1,23 @@
+//! decode a fixed syndrome with the serial solver on a decoding graph given as a static table, which is all that an
+//! embedded application needs; it also builds without the optional dependencies:
+//! `cargo run --example minimal_decode --no-default-features --features minimal`
+
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverSerial};
+use fusion_blossom::util::*;
+
+/// the distance-5 repetition code: 0 - 1 - 2 - 3 - 4 - 5, where the vertices 0 and 5 are virtual
+const VERTEX_NUM: VertexNum = 6;
+const WEIGHTED_EDGES: [(VertexIndex, VertexIndex, Weight); 5] =
+    [(0, 1, 100), (1, 2, 100), (2, 3, 100), (3, 4, 100), (4, 5, 100)];
+const VIRTUAL_VERTICES: [VertexIndex; 2] = [0, 5];
+/// data qubits 1 and 2 flipped
+const DEFECT_VERTICES: [VertexIndex; 2] = [1, 3];
+
+fn main() {
+    let initializer = SolverInitializer::new(VERTEX_NUM, WEIGHTED_EDGES.to_vec(), VIRTUAL_VERTICES.to_vec());
+    let mut solver = SolverSerial::new(&initializer);
+    solver.solve(&SyndromePattern::new_vertices(DEFECT_VERTICES.to_vec()));
+    let subgraph = solver.subgraph();
+    println!("subgraph: {subgraph:?}, weight: {}", solver.sum_dual_variables());
+    assert_eq!(subgraph, vec![1, 2]);
+}
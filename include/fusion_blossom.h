@@ -0,0 +1,128 @@
+#ifndef FUSION_BLOSSOM_H
+#define FUSION_BLOSSOM_H
+
+/* generated by cbindgen from src/ffi.rs with `cargo build --features ffi`, do not edit manually */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum FbErrorCode {
+  FB_ERROR_CODE_OK = 0,
+  /**
+   * a required pointer is `NULL`
+   */
+  FB_ERROR_CODE_NULL_POINTER = 1,
+  /**
+   * an index is out of range or a weight is invalid, see [`fb_last_error_message`]
+   */
+  FB_ERROR_CODE_INVALID_ARGUMENT = 2,
+  /**
+   * the solver panicked; the solver is cleared and can be used for the next syndrome
+   */
+  FB_ERROR_CODE_PANIC = 3,
+} FbErrorCode;
+
+/**
+ * opaque handle of a [`SolverInitializer`]
+ */
+typedef struct FbInitializer FbInitializer;
+
+/**
+ * opaque handle of a [`SolverSerial`]
+ */
+typedef struct FbSolver FbSolver;
+
+/**
+ * an edge of the decoding graph, the same as an element of [`SolverInitializer::weighted_edges`]
+ */
+typedef struct FbWeightedEdge {
+  size_t vertex_1;
+  size_t vertex_2;
+  /**
+   * must be a non-negative even number
+   */
+  int64_t weight;
+} FbWeightedEdge;
+
+/**
+ * the code of the last failed call on this thread, or [`FbErrorCode::Ok`] if no call has failed
+ */
+enum FbErrorCode fb_last_error_code(void);
+
+/**
+ * the message of the last failed call on this thread, or `NULL` if no call has failed;
+ * the string is owned by the library and stays valid until the next failed call on this thread
+ */
+const char *fb_last_error_message(void);
+
+/**
+ * create a decoding graph with `vertex_num` vertices, returning `NULL` on error; free it with [`fb_initializer_free`]
+ *
+ * # Safety
+ * `edges_ptr` and `virtual_ptr` must point to arrays of at least `edges_len` and `virtual_len` elements
+ */
+struct FbInitializer *fb_initializer_new(size_t vertex_num,
+                                         const struct FbWeightedEdge *edges_ptr,
+                                         size_t edges_len,
+                                         const size_t *virtual_ptr,
+                                         size_t virtual_len);
+
+/**
+ * # Safety
+ * `initializer` must be `NULL` or returned by [`fb_initializer_new`], and is invalid afterwards
+ */
+void fb_initializer_free(struct FbInitializer *initializer);
+
+/**
+ * create a solver, returning `NULL` on error; the initializer can be freed right after; free the solver with
+ * [`fb_solver_free`]
+ *
+ * # Safety
+ * `initializer` must be returned by [`fb_initializer_new`]
+ */
+struct FbSolver *fb_solver_new(const struct FbInitializer *initializer);
+
+/**
+ * # Safety
+ * `solver` must be `NULL` or returned by [`fb_solver_new`], and is invalid afterwards
+ */
+void fb_solver_free(struct FbSolver *solver);
+
+/**
+ * solve the defect vertices with the given edges erased (set to zero weight) for this syndrome only;
+ * the solver must be cleared with [`fb_solver_clear`] before solving the next syndrome
+ *
+ * # Safety
+ * `solver` must be returned by [`fb_solver_new`]; `defects_ptr` and `erasures_ptr` must point to arrays of at least
+ * `defects_len` and `erasures_len` elements
+ */
+enum FbErrorCode fb_solver_solve(struct FbSolver *solver,
+                                 const size_t *defects_ptr,
+                                 size_t defects_len,
+                                 const size_t *erasures_ptr,
+                                 size_t erasures_len);
+
+/**
+ * write the edge indices of the minimum-weight parity subgraph of the last solved syndrome to `out_ptr` and return
+ * the number of edges, or `-1` on error; if the number of edges is larger than `out_cap`, only the first `out_cap`
+ * edges are written and the caller should retry with a larger buffer
+ *
+ * # Safety
+ * `solver` must be returned by [`fb_solver_new`]; `out_ptr` must point to an array of at least `out_cap` elements
+ */
+ptrdiff_t fb_solver_subgraph(struct FbSolver *solver,
+                             size_t *out_ptr,
+                             size_t out_cap);
+
+/**
+ * clear the solver for the next syndrome
+ *
+ * # Safety
+ * `solver` must be returned by [`fb_solver_new`]
+ */
+enum FbErrorCode fb_solver_clear(struct FbSolver *solver);
+
+#endif /* FUSION_BLOSSOM_H */
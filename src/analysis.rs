@@ -0,0 +1,413 @@
+//! Analysis
+//!
+//! Offline analysis of the matching structure of a syndrome, e.g. the pairwise distances between the defects that the
+//! minimum-weight perfect matching is computed on, and of a whole syndrome pattern file.
+//!
+
+use super::complete_graph::CompleteGraph;
+use super::error::*;
+#[cfg(not(target_arch = "wasm32"))]
+use super::example_codes::ErrorPatternReader;
+use super::util::*;
+use super::visualize::VisualizePosition;
+use crate::rayon::prelude::*;
+#[cfg(feature = "python_binding")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// the minimum weights between every pair of defects and from each defect to the closest virtual vertex
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct DistanceMatrix {
+    /// the defect vertices, in the order of the rows and columns
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub defect_vertices: Vec<VertexIndex>,
+    /// `distances[i][j]` is the minimum weight between the `i`-th and the `j`-th defect, `None` if they're disconnected
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub distances: Vec<Vec<Option<Weight>>>,
+    /// the minimum weight from each defect to any virtual vertex, `None` if no virtual vertex is reachable
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub boundary_distances: Vec<Option<Weight>>,
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl DistanceMatrix {
+    /// one row per defect: the defect vertex, its distances to all the defects and to the boundary; a disconnected pair
+    /// is left empty
+    pub fn to_csv(&self) -> String {
+        let cell = |distance: &Option<Weight>| distance.map(|weight| weight.to_string()).unwrap_or_default();
+        let mut csv = String::from("defect");
+        for defect_vertex in self.defect_vertices.iter() {
+            csv.push_str(&format!(",{defect_vertex}"));
+        }
+        csv.push_str(",boundary\n");
+        for (i, defect_vertex) in self.defect_vertices.iter().enumerate() {
+            csv.push_str(&defect_vertex.to_string());
+            for distance in self.distances[i].iter().chain(std::iter::once(&self.boundary_distances[i])) {
+                csv.push(',');
+                csv.push_str(&cell(distance));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+    #[cfg(feature = "python_binding")]
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// compute the [`DistanceMatrix`] of the defects on the decoding graph with the erasures, dynamic weights and blocked
+/// edges of the syndrome applied; each defect runs its own Dijkstra's algorithm in parallel
+#[cfg_attr(feature = "python_binding", pyfunction)]
+#[allow(clippy::unnecessary_cast)]
+pub fn defect_distance_matrix(initializer: &SolverInitializer, syndrome_pattern: &SyndromePattern) -> DistanceMatrix {
+    let resolved_syndrome_pattern =
+        (!syndrome_pattern.blocked_edges.is_empty()).then(|| initializer.resolve_blocked_edges(syndrome_pattern));
+    let resolved = resolved_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+    let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+    // a single modifier, since the erasures and the dynamic weights cannot be loaded one after another
+    let edge_modifier: Vec<(EdgeIndex, Weight)> = (resolved.erasures.iter().map(|&edge_index| (edge_index, 0)))
+        .chain(resolved.dynamic_weights.iter().cloned())
+        .collect();
+    complete_graph.load_dynamic_weights(&edge_modifier);
+    let mut is_virtual = vec![false; initializer.vertex_num as usize];
+    for &virtual_vertex in initializer.virtual_vertices.iter() {
+        is_virtual[virtual_vertex as usize] = true;
+    }
+    let defect_vertices = syndrome_pattern.defect_vertices.clone();
+    let rows: Vec<(Vec<Option<Weight>>, Option<Weight>)> = defect_vertices
+        .par_iter()
+        .map_init(
+            || complete_graph.clone(),
+            |complete_graph, &defect_vertex| {
+                let edges = complete_graph.all_edges(defect_vertex);
+                let distances = (defect_vertices.iter())
+                    .map(|peer| {
+                        if *peer == defect_vertex {
+                            Some(0)
+                        } else {
+                            edges.get(peer).map(|&(_, weight)| weight)
+                        }
+                    })
+                    .collect();
+                let boundary_distance = (edges.iter())
+                    .filter(|(peer, _)| is_virtual[**peer as usize])
+                    .map(|(_, &(_, weight))| weight)
+                    .min();
+                (distances, boundary_distance)
+            },
+        )
+        .collect();
+    let (distances, boundary_distances) = rows.into_iter().unzip();
+    DistanceMatrix {
+        defect_vertices,
+        distances,
+        boundary_distances,
+    }
+}
+
+/// the defect density of the real vertices sharing the same time coordinate `t` of their positions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerStatistics {
+    pub t: f64,
+    /// the number of real vertices in this layer
+    pub vertex_num: usize,
+    /// the total number of defects in this layer over all the shots
+    pub defect_num: usize,
+    /// the probability of a real vertex in this layer to be a defect
+    pub density: f64,
+}
+
+/// sanity statistics of the syndrome patterns of a file, see [`dataset_statistics`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetStatistics {
+    pub shots: usize,
+    /// `defect_histogram[k]` is the number of shots with `k` defects
+    pub defect_histogram: Vec<usize>,
+    pub mean_defects: f64,
+    pub std_defects: f64,
+    /// `erasure_histogram[k]` is the number of shots with `k` erasures
+    pub erasure_histogram: Vec<usize>,
+    pub mean_erasures: f64,
+    /// the fraction of shots without any defect
+    pub empty_fraction: f64,
+    /// ordered by `t`, empty if the positions are not given
+    pub layers: Vec<LayerStatistics>,
+}
+
+impl DatasetStatistics {
+    /// one line of the per-shot statistics followed by one line per layer
+    pub fn brief(&self) -> String {
+        let histogram = |histogram: &[usize]| {
+            (histogram.iter().enumerate())
+                .filter(|(_, &count)| count > 0)
+                .map(|(value, count)| format!("{value}:{count}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut brief = format!(
+            "shots: {}, empty: {:.4}, defects: {:.3} +- {:.3} [{}], erasures: {:.3} [{}]\n",
+            self.shots,
+            self.empty_fraction,
+            self.mean_defects,
+            self.std_defects,
+            histogram(&self.defect_histogram),
+            self.mean_erasures,
+            histogram(&self.erasure_histogram),
+        );
+        for layer in self.layers.iter() {
+            brief.push_str(&format!(
+                "t: {}, vertices: {}, defects: {}, density: {:.3e}\n",
+                layer.t, layer.vertex_num, layer.defect_num, layer.density
+            ));
+        }
+        brief
+    }
+}
+
+/// panics if the file cannot be read, see [`try_dataset_statistics`]
+#[cfg(not(target_arch = "wasm32"))]
+pub fn dataset_statistics(reader: &mut ErrorPatternReader, positions: Option<&[VisualizePosition]>) -> DatasetStatistics {
+    or_panic(try_dataset_statistics(reader, positions))
+}
+
+/// the statistics of the syndrome patterns from the cursor of the reader to the end of the file, streamed one at a time
+/// so that the memory doesn't grow with the file; the defects are binned into layers by the `t` of `positions`
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::unnecessary_cast)]
+pub fn try_dataset_statistics(
+    reader: &mut ErrorPatternReader,
+    positions: Option<&[VisualizePosition]>,
+) -> Result<DatasetStatistics, FusionError> {
+    let is_virtual: Vec<bool> = reader.vertices.iter().map(|vertex| vertex.is_virtual).collect();
+    // the layer of each vertex, `None` for the virtual ones
+    let mut layer_ts: Vec<f64> = vec![];
+    let mut vertex_layers: Vec<Option<usize>> = vec![];
+    if let Some(positions) = positions {
+        if positions.len() != is_virtual.len() {
+            return Err(FusionError::InvalidInput(format!(
+                "{} positions are given for {} vertices",
+                positions.len(),
+                is_virtual.len()
+            )));
+        }
+        layer_ts = (positions.iter().zip(is_virtual.iter()))
+            .filter(|(_, &is_virtual)| !is_virtual)
+            .map(|(position, _)| position.t)
+            .collect();
+        layer_ts.sort_by(f64::total_cmp);
+        layer_ts.dedup();
+        vertex_layers = (positions.iter().zip(is_virtual.iter()))
+            .map(|(position, &is_virtual)| {
+                (!is_virtual).then(|| layer_ts.binary_search_by(|t| t.total_cmp(&position.t)).unwrap())
+            })
+            .collect();
+    }
+    let mut layer_vertex_nums = vec![0; layer_ts.len()];
+    for layer in vertex_layers.iter().flatten() {
+        layer_vertex_nums[*layer] += 1;
+    }
+    let mut layer_defect_nums = vec![0; layer_ts.len()];
+    let mut defect_histogram = vec![];
+    let mut erasure_histogram = vec![];
+    let count = |histogram: &mut Vec<usize>, value: usize| {
+        if histogram.len() <= value {
+            histogram.resize(value + 1, 0);
+        }
+        histogram[value] += 1;
+    };
+    // a cyclic reader never reaches the end
+    let cyclic_syndrome = std::mem::replace(&mut reader.cyclic_syndrome, false);
+    let mut shots = 0;
+    let result = loop {
+        let syndrome_pattern = match reader.next_syndrome_pattern() {
+            Ok((syndrome_pattern, _)) => syndrome_pattern,
+            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => break Ok(()),
+            Err(error) => break Err(error),
+        };
+        shots += 1;
+        count(&mut defect_histogram, syndrome_pattern.defect_vertices.len());
+        count(&mut erasure_histogram, syndrome_pattern.erasures.len());
+        for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+            if let Some(Some(layer)) = vertex_layers.get(defect_vertex as usize) {
+                layer_defect_nums[*layer] += 1;
+            }
+        }
+    };
+    reader.cyclic_syndrome = cyclic_syndrome;
+    result?;
+    let mean = |histogram: &[usize]| {
+        let sum: usize = histogram.iter().enumerate().map(|(value, count)| value * count).sum();
+        sum as f64 / shots.max(1) as f64
+    };
+    let mean_defects = mean(&defect_histogram);
+    let variance = (defect_histogram.iter().enumerate())
+        .map(|(value, &count)| (value as f64 - mean_defects).powi(2) * count as f64)
+        .sum::<f64>()
+        / shots.max(1) as f64;
+    let layers = (layer_ts.iter().zip(layer_vertex_nums.iter().zip(layer_defect_nums.iter())))
+        .map(|(&t, (&vertex_num, &defect_num))| LayerStatistics {
+            t,
+            vertex_num,
+            defect_num,
+            density: defect_num as f64 / (vertex_num * shots.max(1)) as f64,
+        })
+        .collect();
+    Ok(DatasetStatistics {
+        shots,
+        mean_defects,
+        std_defects: variance.sqrt(),
+        mean_erasures: mean(&erasure_histogram),
+        empty_fraction: defect_histogram.first().map_or(0., |&empty| empty as f64 / shots as f64),
+        defect_histogram,
+        erasure_histogram,
+        layers,
+    })
+}
+
+#[cfg(feature = "python_binding")]
+#[pyfunction]
+pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<DistanceMatrix>()?;
+    m.add_function(wrap_pyfunction!(defect_distance_matrix, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example_codes::*;
+    use serde_json::json;
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn analysis_defect_distance_matrix_1() {
+        // cargo test analysis_defect_distance_matrix_1 -- --nocapture
+        let mut code = CircuitLevelPlanarCode::new(5, 4, 0.02, 500);
+        let initializer = code.get_initializer();
+        let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+        for seed in 0..5 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let matrix = defect_distance_matrix(&initializer, &syndrome_pattern);
+            let defect_num = syndrome_pattern.defect_vertices.len();
+            assert!(defect_num > 2);
+            assert_eq!(matrix.defect_vertices, syndrome_pattern.defect_vertices);
+            assert_eq!(matrix.distances.len(), defect_num);
+            assert_eq!(matrix.boundary_distances.len(), defect_num);
+            for i in 0..defect_num {
+                assert_eq!(matrix.distances[i][i], Some(0));
+                let boundary_distance = matrix.boundary_distances[i].unwrap();
+                for j in 0..defect_num {
+                    let distance = matrix.distances[i][j].unwrap();
+                    assert_eq!(matrix.distances[j][i], Some(distance), "symmetric");
+                    // the boundary distances also satisfy the triangle inequality
+                    assert!(boundary_distance <= distance + matrix.boundary_distances[j].unwrap());
+                    for k in 0..defect_num {
+                        assert!(distance <= matrix.distances[i][k].unwrap() + matrix.distances[k][j].unwrap());
+                    }
+                }
+            }
+            // agree with the path between sampled pairs
+            for (i, j) in [(0, 1), (0, defect_num - 1), (1, defect_num / 2)] {
+                let (a, b) = (matrix.defect_vertices[i], matrix.defect_vertices[j]);
+                let (_, weight) = complete_graph.get_path(a, b);
+                assert_eq!(matrix.distances[i][j], Some(weight));
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn analysis_defect_distance_matrix_erasures() {
+        // cargo test analysis_defect_distance_matrix_erasures -- --nocapture
+        // a chain of vertices 0 - 1 - 2 - 3 with a virtual vertex 4 attached to 3, and an isolated defect 5
+        let weighted_edges = vec![(0, 1, 2), (1, 2, 4), (2, 3, 2), (3, 4, 6)];
+        let initializer = SolverInitializer::new(6, weighted_edges, vec![4]);
+        let mut syndrome_pattern = SyndromePattern::new_vertices(vec![0, 3, 5]);
+        let matrix = defect_distance_matrix(&initializer, &syndrome_pattern);
+        assert_eq!(
+            matrix.distances,
+            vec![
+                vec![Some(0), Some(8), None],
+                vec![Some(8), Some(0), None],
+                vec![None, None, Some(0)]
+            ]
+        );
+        assert_eq!(matrix.boundary_distances, vec![Some(14), Some(6), None]);
+        assert_eq!(matrix.to_csv(), "defect,0,3,5,boundary\n0,0,8,,14\n3,8,0,,6\n5,,,0,\n");
+        let json: DistanceMatrix = serde_json::from_str(&matrix.to_json()).unwrap();
+        assert_eq!(json, matrix);
+        // erasures and dynamic weights of the syndrome are applied
+        syndrome_pattern.erasures = vec![1];
+        let matrix = defect_distance_matrix(&initializer, &syndrome_pattern);
+        assert_eq!(matrix.distances[0][1], Some(4));
+        assert_eq!(matrix.boundary_distances, vec![Some(10), Some(6), None]);
+        syndrome_pattern.erasures.clear();
+        syndrome_pattern.dynamic_weights = vec![(3, 2)];
+        let matrix = defect_distance_matrix(&initializer, &syndrome_pattern);
+        assert_eq!(matrix.boundary_distances, vec![Some(10), Some(2), None]);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn analysis_dataset_statistics_1() {
+        // cargo test analysis_dataset_statistics_1 -- --nocapture
+        let filename = std::env::temp_dir().join(format!("fusion_blossom_dataset_statistics_{}.txt", std::process::id()));
+        let filename = filename.to_str().unwrap().to_string();
+        let shots = 4000;
+        let (p, pe) = (0.02, 0.01);
+        let mut code = PhenomenologicalPlanarCode::new(5, 3, p, 500);
+        code.set_erasure_probability(pe);
+        let positions = code.get_positions();
+        let mut writer = SyndromeFileWriter::create(&filename, &code.get_initializer(), &positions, false, false).unwrap();
+        for seed in 0..shots {
+            writer.write(&code.generate_random_errors(seed), None).unwrap();
+        }
+        writer.finish().unwrap().finish().unwrap();
+        // a real vertex is a defect if an odd number of its edges have errors
+        let flip_probability = pe * 0.5 + (1. - pe) * p;
+        let defect_probabilities: Vec<f64> = (code.vertices.iter())
+            .map(|vertex| match vertex.is_virtual {
+                true => 0.,
+                false => (1. - (1. - 2. * flip_probability).powi(vertex.neighbor_edges.len() as i32)) / 2.,
+            })
+            .collect();
+        let expected_defects: f64 = defect_probabilities.iter().sum();
+        let expected_erasures = code.edges.len() as f64 * pe;
+        let mut reader = ErrorPatternReader::new(json!({ "filename": filename, "read_ahead": 100 }));
+        let statistics = dataset_statistics(&mut reader, Some(&positions));
+        println!("{}", statistics.brief());
+        assert_eq!(statistics.shots, shots as usize);
+        assert_eq!(statistics.defect_histogram.iter().sum::<usize>(), shots as usize);
+        assert!((statistics.mean_defects - expected_defects).abs() < 0.05 * expected_defects);
+        assert!((statistics.mean_erasures - expected_erasures).abs() < 0.1 * expected_erasures);
+        assert!((statistics.empty_fraction - statistics.defect_histogram[0] as f64 / shots as f64).abs() < 1e-12);
+        // one layer per measurement round, with the same expected density as the vertices in it
+        assert_eq!(statistics.layers.len(), 4);
+        for layer in statistics.layers.iter() {
+            let (vertex_num, expected_density) = (positions.iter().zip(defect_probabilities.iter()))
+                .zip(code.vertices.iter())
+                .filter(|((position, _), vertex)| position.t == layer.t && !vertex.is_virtual)
+                .fold((0, 0.), |(num, sum), ((_, probability), _)| (num + 1, sum + probability));
+            assert_eq!(layer.vertex_num, vertex_num);
+            let expected_density = expected_density / vertex_num as f64;
+            assert!((layer.density - expected_density).abs() < 0.1 * expected_density, "{layer:?}");
+        }
+        let json: DatasetStatistics = serde_json::from_str(&serde_json::to_string(&statistics).unwrap()).unwrap();
+        assert_eq!(json, statistics);
+        // without positions there are no layers, and the cursor is at the end of the file
+        let mut reader = ErrorPatternReader::new(json!({ "filename": filename, "cyclic_syndrome": true }));
+        let statistics_without_positions = dataset_statistics(&mut reader, None);
+        assert!(statistics_without_positions.layers.is_empty());
+        assert_eq!(statistics_without_positions.defect_histogram, statistics.defect_histogram);
+        assert!(reader.cyclic_syndrome);
+        assert_eq!(dataset_statistics(&mut reader, None).shots, 0);
+        std::fs::remove_file(&filename).unwrap();
+    }
+}
@@ -491,7 +491,7 @@ fn demo_aps2023_example_partition() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -547,7 +547,7 @@ fn demo_aps2023_large_demo() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
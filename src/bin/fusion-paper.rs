@@ -253,7 +253,7 @@ fn fusion_paper_example_partition() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -309,7 +309,7 @@ fn fusion_paper_large_demo() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -385,7 +385,7 @@ fn fusion_paper_example_partition_16() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -452,7 +452,7 @@ fn fusion_paper_example_partition_8() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -541,7 +541,7 @@ fn fusion_paper_example_partition_8_circuit_level() {
         ..Default::default()
     };
     let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-    code.set_defect_vertices(&defect_vertices);
+    code.set_defect_vertices(&defect_vertices).unwrap();
     primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
     let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
     let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
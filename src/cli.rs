@@ -1,8 +1,13 @@
+use super::analysis;
+use super::complete_graph::CompleteGraph;
 use super::dual_module::*;
+use super::error::FusionError;
 use super::example_codes::*;
 use super::example_partition;
 use super::mwpm_solver::*;
+use super::ordering::*;
 use super::primal_module::*;
+use super::stats::*;
 use super::util::*;
 use super::visualize::*;
 #[cfg(feature = "qecp_integrate")]
@@ -10,9 +15,14 @@ use crate::qecp;
 use clap::{Parser, Subcommand, ValueEnum};
 use pbr::ProgressBar;
 use rand::{thread_rng, Rng};
-use serde::Serialize;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeSet;
 use std::env;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 const TEST_EACH_ROUNDS: usize = 100;
 
@@ -31,12 +41,12 @@ pub struct Cli {
 
 #[derive(Parser, Clone)]
 pub struct BenchmarkParameters {
-    /// code distance
+    /// code distance, required unless `--d-list` is given
     #[clap(value_parser)]
-    d: VertexNum,
-    /// physical error rate: the probability of each edge to
+    d: Option<VertexNum>,
+    /// physical error rate: the probability of each edge to, required unless `--p-list` is given
     #[clap(value_parser)]
-    p: f64,
+    p: Option<f64>,
     /// rounds of noisy measurement, valid only when multiple rounds
     #[clap(short = 'e', long, default_value_t = 0.)]
     pe: f64,
@@ -82,12 +92,255 @@ pub struct BenchmarkParameters {
     /// use deterministic seed for debugging purpose
     #[clap(long, action)]
     use_deterministic_seed: bool,
-    /// the benchmark profile output file path
+    /// how the seed of each shot is derived, see [`Seeding`]; with `v2` the syndrome pattern of shot `i` only depends on a
+    /// master seed and `i`, which is 0 with `--use-deterministic-seed`; also passed to the workers of the parallel code
+    /// types unless `seeding` is in the code config
+    #[clap(long, value_enum, default_value_t = Seeding::V1)]
+    seeding: Seeding,
+    /// the benchmark profile output file path; the parallel solver also prints the average statistics of each fusion
     #[clap(long)]
     benchmark_profiler_output: Option<String>,
     /// skip some iterations, useful when debugging
     #[clap(long, default_value_t = 0)]
     starting_iteration: usize,
+    /// warm up the solver, and the first few rounds are decoded but excluded from all statistics, to avoid counting page
+    /// faults and lazy allocations
+    #[clap(long, default_value_t = 0)]
+    warmup: usize,
+    /// report the memory usage of the solver: an analytic estimate and the increase of the peak resident set size during
+    /// solver construction and decoding; the latter is only available on Linux
+    #[clap(long, action)]
+    report_memory: bool,
+    /// print the estimated memory of the solver for each parameter combination and exit without building the solver
+    #[clap(long, action)]
+    dry_run: bool,
+    /// sweep over these code distances, e.g. `3,5,7`; one record is reported for each combination of the lists
+    #[clap(long, value_delimiter = ',')]
+    d_list: Vec<VertexNum>,
+    /// sweep over these physical error rates, e.g. `1e-3,2e-3,5e-3`
+    #[clap(long, value_delimiter = ',')]
+    p_list: Vec<f64>,
+    /// sweep over these rounds of noisy measurement
+    #[clap(long, value_delimiter = ',')]
+    noisy_measurements_list: Vec<VertexNum>,
+    /// the format of the benchmark result
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    output_format: OutputFormat,
+    /// write the benchmark result to this file instead of stdout; csv rows and json records are appended to an existing file
+    #[clap(long)]
+    output_file: Option<String>,
+    /// count the logical errors by comparing the correction with the actual error edges; with the error pattern logger,
+    /// the error edges are recorded in the file instead
+    #[clap(long, action)]
+    compute_logical_error_rate: bool,
+    /// stop early after this many logical errors, valid only with `--compute-logical-error-rate`
+    #[clap(long)]
+    max_failures: Option<usize>,
+    /// where the syndrome patterns come from; with the Stim formats, the detection events in `--syndrome-file` are
+    /// decoded with the decoding graph of `--dem`, and the code type, distance and error rate are ignored
+    #[clap(long, value_enum, default_value_t = SyndromeFormat::CodeType)]
+    syndrome_format: SyndromeFormat,
+    /// the detection events sampled by Stim, e.g. by `stim detect --out_format b8`, valid only with the Stim formats
+    #[clap(long)]
+    syndrome_file: Option<String>,
+    /// the number of detectors of each shot in the Stim formats, checked against `--dem`; defaults to the number of
+    /// detectors of `--dem`
+    #[clap(long)]
+    num_detectors: Option<usize>,
+    /// the detector error model of the Stim formats, with the loops flattened and the errors decomposed into edges
+    #[clap(long)]
+    dem: Option<String>,
+    /// the observable flips sampled by Stim with `--obs_out` in the same format as the detection events, which implies
+    /// `--compute-logical-error-rate`: a shot fails if the correction flips different observables
+    #[clap(long)]
+    obs_file: Option<String>,
+    /// periodically save the progress to this file and resume from it if it exists, so that an interrupted benchmark
+    /// gives the same result as an uninterrupted one; the file is removed once the benchmark finishes
+    #[clap(long)]
+    checkpoint_file: Option<String>,
+    /// the number of rounds between two checkpoints; a checkpoint is also saved on SIGINT
+    #[clap(long, default_value_t = 1000)]
+    checkpoint_interval: usize,
+    /// disable the progress bar, the same as setting the environment variable `DISABLE_PROGRESS_BAR`
+    #[clap(short = 'q', long, action)]
+    quiet: bool,
+    /// called after every round, see [`BenchmarkParameters::set_progress_callback`]
+    #[clap(skip)]
+    progress_callback: Option<Arc<ProgressCallback>>,
+}
+
+#[derive(Parser, Clone)]
+pub struct GenerateSyndromesParameters {
+    /// code distance
+    #[clap(value_parser)]
+    d: VertexNum,
+    /// physical error rate: the probability of each edge to
+    #[clap(value_parser)]
+    p: f64,
+    /// erasure probability of each edge
+    #[clap(short = 'e', long, default_value_t = 0.)]
+    pe: f64,
+    /// rounds of noisy measurement, valid only when multiple rounds
+    #[clap(short = 'n', long, default_value_t = 0)]
+    noisy_measurements: VertexNum,
+    /// maximum half weight of edges
+    #[clap(long, default_value_t = 500)]
+    max_half_weight: Weight,
+    /// example code type
+    #[clap(short = 'c', long, value_enum, default_value_t = ExampleCodeType::CodeCapacityPlanarCode)]
+    code_type: ExampleCodeType,
+    /// the configuration of the code builder
+    #[clap(long, default_value_t = ("{}").to_string())]
+    code_config: String,
+    /// the number of syndrome patterns to generate
+    #[clap(short = 'r', long, default_value_t = 1000)]
+    shots: usize,
+    /// shot `i` always uses a seed derived from this seed and `i`, regardless of the number of threads
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+    /// how the workers of the parallel code types derive their seeds, see [`Seeding`]; unlike `v1`, `v2` makes the
+    /// syndrome patterns independent of `code_count`
+    #[clap(long, value_enum, default_value_t = Seeding::V1)]
+    seeding: Seeding,
+    /// the output file, compressed with gzip if it ends with `.gz`; read it with `--code-type error-pattern-reader`
+    #[clap(short = 'o', long, default_value_t = ("tmp/syndrome_patterns.txt").to_string())]
+    output: String,
+    /// split the output into this number of files named like `tmp/syndrome_patterns.000.txt`, where shot `i` goes to
+    /// shard `i % shards`; read them with `--code-type sharded-error-pattern-reader`
+    #[clap(long, default_value_t = 1)]
+    shards: usize,
+    /// the encoding of the output file
+    #[clap(long, value_enum, default_value_t = SyndromeFileFormat::Json)]
+    format: SyndromeFileFormat,
+    /// the number of threads to generate the syndrome patterns
+    #[clap(long, default_value_t = 1)]
+    threads: usize,
+    /// also record the ground-truth error edges, required by `--compute-logical-error-rate` of the reader
+    #[clap(long, action)]
+    include_error_edges: bool,
+    /// disable the progress line, which is only shown when stderr is a terminal
+    #[clap(short = 'q', long, action)]
+    quiet: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct ConvertSyndromesParameters {
+    /// the input syndrome pattern file in any supported format, decompressed with gzip if it ends with `.gz`
+    #[clap(value_parser)]
+    input: String,
+    /// the output file, compressed with gzip if it ends with `.gz`
+    #[clap(value_parser)]
+    output: String,
+    /// the encoding of the output file
+    #[clap(long, value_enum, default_value_t = SyndromeFileFormat::Json)]
+    format: SyndromeFileFormat,
+    /// write at most this number of syndrome patterns
+    #[clap(long)]
+    head: Option<usize>,
+    /// skip this number of syndrome patterns at the beginning of the input file
+    #[clap(long, default_value_t = 0)]
+    skip: usize,
+    /// drop the ground-truth error edges of every syndrome pattern
+    #[clap(long, action)]
+    strip_errors: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct InspectSyndromesParameters {
+    /// the syndrome pattern file in any supported format, decompressed with gzip if it ends with `.gz`
+    #[clap(value_parser)]
+    filename: String,
+    /// the format of the statistics; csv is not supported
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+    output_format: OutputFormat,
+}
+
+#[derive(Parser, Clone)]
+pub struct CompareParameters {
+    /// the syndrome pattern file, e.g. generated by `generate-syndromes`, or the detection events with the Stim formats
+    #[clap(value_parser)]
+    filename: String,
+    /// the format of the syndrome pattern file
+    #[clap(long, value_enum, default_value_t = SyndromeFormat::CodeType)]
+    syndrome_format: SyndromeFormat,
+    /// the number of detectors of each shot in the Stim formats, checked against `--dem`
+    #[clap(long)]
+    num_detectors: Option<usize>,
+    /// the detector error model of the Stim formats, with the loops flattened and the errors decomposed into edges
+    #[clap(long)]
+    dem: Option<String>,
+    /// maximum half weight of the edges of `--dem`
+    #[clap(long, default_value_t = 500)]
+    max_half_weight: Weight,
+    /// the two decoders to compare
+    #[clap(long, value_enum, value_delimiter = ',', num_args = 2, default_values_t = [CompareEngine::Serial, CompareEngine::Parallel])]
+    engines: Vec<CompareEngine>,
+    /// code distance, only used by the partition strategy
+    #[clap(short = 'd', long, default_value_t = 0)]
+    d: VertexNum,
+    /// rounds of noisy measurement, only used by the partition strategy
+    #[clap(short = 'n', long, default_value_t = 0)]
+    noisy_measurements: VertexNum,
+    /// partition strategy of the parallel decoder
+    #[clap(long, value_enum, default_value_t = PartitionStrategy::None)]
+    partition_strategy: PartitionStrategy,
+    /// the configuration of the partition strategy
+    #[clap(long, default_value_t = ("{}").to_string())]
+    partition_config: String,
+    /// the configuration of the parallel decoder
+    #[clap(long, default_value_t = ("{}").to_string())]
+    primal_dual_config: String,
+    /// compare only the first few syndrome patterns in the file
+    #[clap(short = 'r', long)]
+    total_rounds: Option<usize>,
+    /// every mismatch is written to a sub-folder `shot_<index>` of this folder
+    #[clap(long, default_value_t = ("tmp/compare_mismatches").to_string())]
+    dump_folder: String,
+    /// test hook: drop a matched pair from the second decoder's matching at this shot
+    #[clap(long, hide = true)]
+    corrupt_shot: Option<usize>,
+}
+
+#[derive(Parser, Clone)]
+pub struct ReplayDumpParameters {
+    /// the reproducer written by a solver with `debug_dump_on_panic`
+    #[clap(value_parser)]
+    filename: String,
+    /// logging to the default visualizer file at visualize/data/visualizer.json, with the vertices laid out on a grid
+    /// because the dump has no vertex positions
+    #[clap(short = 'v', long, action)]
+    enable_visualizer: bool,
+}
+
+#[derive(Parser, Clone)]
+pub struct ReplayVisualizationParameters {
+    /// the visualization file with an embedded initializer, e.g. written by `benchmark --enable-visualizer`
+    #[clap(value_parser)]
+    filename: String,
+}
+
+#[derive(Parser, Clone)]
+pub struct DiffInitializersParameters {
+    /// the json file of the decoding graph before the change, i.e. a serialized `SolverInitializer`
+    #[clap(value_parser)]
+    first: String,
+    /// the json file of the decoding graph after the change
+    #[clap(value_parser)]
+    second: String,
+}
+
+#[derive(Parser, Clone)]
+pub struct ThresholdParameters {
+    /// the benchmark of every combination of `--d-list` and `--p-list`, always computing the logical error rate; the
+    /// records of the points are written as by `benchmark` unless the output format is text
+    #[clap(flatten)]
+    benchmark: BenchmarkParameters,
+    /// the number of bootstrap resamples to estimate the confidence interval of the threshold
+    #[clap(long, default_value_t = 1000)]
+    bootstrap_samples: usize,
+    /// the seed of the bootstrap resampling
+    #[clap(long, default_value_t = 0)]
+    bootstrap_seed: u64,
 }
 
 #[derive(Subcommand, Clone)]
@@ -95,6 +348,25 @@ pub struct BenchmarkParameters {
 enum Commands {
     /// benchmark the speed (and also correctness if enabled)
     Benchmark(BenchmarkParameters),
+    /// estimate the threshold by fitting the logical error rates of several code distances to the finite-size scaling ansatz
+    Threshold(ThresholdParameters),
+    /// generate a syndrome pattern file in parallel
+    GenerateSyndromes(GenerateSyndromesParameters),
+    /// convert a syndrome pattern file to another format, optionally taking only a range of the syndrome patterns
+    ConvertSyndromes(ConvertSyndromesParameters),
+    /// print the statistics of a syndrome pattern file: defects and erasures per shot and the defect density per time layer
+    InspectSyndromes(InspectSyndromesParameters),
+    /// decode a syndrome pattern file with two decoders and report the shots where the matching weights differ
+    Compare(CompareParameters),
+    /// decode syndrome patterns sent by another process as newline-delimited json messages over stdin and stdout
+    Serve,
+    /// decode the syndrome pattern of a reproducer written by a panicking solver, with the same solver configuration
+    ReplayDump(ReplayDumpParameters),
+    /// decode the syndrome recorded in a visualization file again and check that the matching weight is the recorded one
+    ReplayVisualization(ReplayVisualizationParameters),
+    /// print the added, removed and re-weighted edges and the changed virtual vertices between two decoding graphs,
+    /// regardless of the order of the edges; exit with code 1 if they differ
+    DiffInitializers(DiffInitializersParameters),
     #[cfg(feature = "qecp_integrate")]
     Qecp(qecp::cli::BenchmarkParameters),
     /// built-in tests
@@ -167,14 +439,17 @@ pub enum ExampleCodeType {
     CodeCapacityPlanarCode,
     /// planar surface code with phenomenological noise model
     PhenomenologicalPlanarCode,
-    /// parallel version
+    /// parallel version, configured by e.g. `{"code_count": 10, "prefetch_depth": 2, "seeding": "v2"}`
     PhenomenologicalPlanarCodeParallel,
     /// planar surface code with circuit-level noise model
     CircuitLevelPlanarCode,
-    /// parallel version
+    /// parallel version, configured by e.g. `{"code_count": 10, "prefetch_depth": 2, "seeding": "v2"}`
     CircuitLevelPlanarCodeParallel,
     /// read from error pattern file, generated using option `--primal-dual-type error-pattern-logger`
     ErrorPatternReader,
+    /// read from several syndrome pattern files, e.g. generated with `generate-syndromes --shards`, configured by
+    /// `{"shards": ["tmp/a.*.txt"], "policy": "round-robin"}`
+    ShardedErrorPatternReader,
     /// rotated surface code with perfect stabilizer measurement
     CodeCapacityRotatedCode,
     /// rotated surface code with phenomenological noise model
@@ -211,6 +486,333 @@ pub enum PrimalDualType {
     BlossomV,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Debug)]
+pub enum CompareEngine {
+    /// serial fusion blossom
+    Serial,
+    /// parallel fusion blossom with the given partition
+    Parallel,
+    /// the reference blossom V library, requires the `blossom_v` feature
+    BlossomV,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Debug)]
+pub enum SyndromeFileFormat {
+    /// json lines, the same as written by `--primal-dual-type error-pattern-logger`
+    Json,
+    /// compact binary encoding
+    Bincode,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Debug)]
+pub enum SyndromeFormat {
+    /// generated or read by the code type, e.g. `--code-type error-pattern-reader` for a syndrome pattern file
+    CodeType,
+    /// detection events sampled by Stim in the b8 format, a bit-packed little-endian row per shot
+    StimB8,
+    /// detection events sampled by Stim in the 01 format, a line of `0` and `1` per shot
+    #[value(name = "stim-01")]
+    Stim01,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Debug)]
+pub enum OutputFormat {
+    /// human-readable brief statistics
+    Text,
+    /// a header line followed by one row per configuration
+    Csv,
+    /// an array of records, one per configuration
+    Json,
+}
+
+/// the machine-readable result of benchmarking one configuration; latencies are in seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRecord {
+    pub d: VertexNum,
+    pub p: f64,
+    pub noisy_measurements: VertexNum,
+    pub partitions: usize,
+    pub threads: usize,
+    pub shots: usize,
+    pub mean_latency: f64,
+    pub p50_latency: f64,
+    pub p90_latency: f64,
+    pub p99_latency: f64,
+    pub p999_latency: f64,
+    pub max_latency: f64,
+    /// decoded shots per second
+    pub throughput: f64,
+    /// the sum of the matching weight over all shots, to check that different solvers agree
+    pub total_weight: Weight,
+    /// the profiler report of the solver, only when `--benchmark-profiler-output` is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solver_profile: Option<serde_json::Value>,
+    /// only when `--compute-logical-error-rate` is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logical_error_rate: Option<LogicalErrorRate>,
+    /// only when `--report-memory` is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_usage: Option<MemoryUsage>,
+}
+
+/// the progress of an interrupted benchmark, see `--checkpoint-file`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkCheckpoint {
+    /// the hash of all the parameters that affect the result, to refuse resuming a different benchmark
+    pub config_hash: String,
+    /// the base seed of the syndrome patterns unless using deterministic seeds or sweeping parameters
+    pub seed: u64,
+    /// the progress of every started parameter combination, in the order of the sweep
+    pub combinations: Vec<CombinationCheckpoint>,
+}
+
+/// the progress of a single parameter combination of the benchmark
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinationCheckpoint {
+    pub d: VertexNum,
+    pub p: f64,
+    pub noisy_measurements: VertexNum,
+    /// the next round to decode; the syndrome patterns of the previous rounds are generated again without decoding when
+    /// resuming, which also restores the cursor of a syndrome pattern file reader
+    pub next_round: u64,
+    /// the number of remaining warmup rounds
+    pub warmup: usize,
+    pub finished: bool,
+    pub total_weight: Weight,
+    pub logical_failures: usize,
+    pub profiler: BenchmarkProfilerState,
+    /// the output record of a finished combination
+    pub record: Option<BenchmarkRecord>,
+}
+
+impl BenchmarkCheckpoint {
+    pub fn load(filename: &str) -> std::io::Result<Option<Self>> {
+        match std::fs::read_to_string(filename) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// write to a temporary file first, so that an interruption never leaves a corrupted checkpoint behind
+    pub fn save(&self, filename: &str) -> std::io::Result<()> {
+        let temporary_filename = format!("{filename}.tmp");
+        std::fs::write(&temporary_filename, serde_json::to_string(self)?)?;
+        std::fs::rename(temporary_filename, filename)
+    }
+}
+
+/// FNV-1a hash of the canonical json, which is stable across builds unlike [`std::collections::hash_map::DefaultHasher`]
+fn config_hash(config: &serde_json::Value) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in config.to_string().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+/// set on SIGINT once [`catch_interrupt`] is called, so that the benchmark saves a checkpoint before exiting
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(unix)]
+fn catch_interrupt() {
+    extern "C" fn handler(_signal: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    unsafe {
+        libc::signal(libc::SIGINT, handler as *const () as libc::sighandler_t);
+    }
+}
+
+#[cfg(not(unix))]
+fn catch_interrupt() {}
+
+/// draw the progress in place on stderr with the estimated remaining time, redrawn at most every 100ms; `None` when
+/// quiet or when stderr is not a terminal, e.g. redirected to a log file
+fn progress_line(quiet: bool) -> Option<ProgressCallback> {
+    use std::io::IsTerminal;
+    if quiet || !std::io::stderr().is_terminal() {
+        return None;
+    }
+    let last_draw: Mutex<Option<std::time::Instant>> = Mutex::new(None);
+    Some(Box::new(move |event: ProgressEvent| {
+        let finished = event.completed == event.total;
+        let mut last_draw = last_draw.lock().unwrap();
+        if !finished && last_draw.is_some_and(|last_draw| last_draw.elapsed().as_millis() < 100) {
+            return;
+        }
+        *last_draw = Some(std::time::Instant::now());
+        let remaining = event
+            .remaining()
+            .map_or("-".to_string(), |remaining| format!("{:.1}s", remaining.as_secs_f64()));
+        let config = if event.current_config.is_empty() {
+            String::new()
+        } else {
+            format!("{}: ", event.current_config)
+        };
+        // clear the line in case the previous one was longer
+        eprint!(
+            "\r\x1b[2K{config}{}/{} ({:.1}%), elapsed {:.1}s, ETA {remaining}",
+            event.completed,
+            event.total,
+            100. * event.completed as f64 / event.total.max(1) as f64,
+            event.elapsed.as_secs_f64()
+        );
+        if finished {
+            eprintln!();
+        }
+    }))
+}
+
+/// the memory usage of a solver in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    /// the analytic estimate of [`PrimalDualSolver::memory_estimate`], if supported by the solver
+    pub estimate: Option<usize>,
+    /// the increase of the peak resident set size during solver construction and decoding, only available on Linux
+    pub peak_resident_delta: Option<usize>,
+}
+
+impl MemoryUsage {
+    pub const CSV_HEADER: &'static str = "memory_estimate,peak_resident_delta";
+
+    pub fn brief(&self) -> String {
+        let show = |bytes: Option<usize>| bytes.map_or("unknown".to_string(), |bytes| format!("{bytes} bytes"));
+        format!(
+            "memory estimate: {}, peak resident delta: {}",
+            show(self.estimate),
+            show(self.peak_resident_delta)
+        )
+    }
+}
+
+/// the logical error rate with its 95% Wilson score interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LogicalErrorRate {
+    pub failures: usize,
+    pub shots: usize,
+    pub rate: f64,
+    pub confidence_lower: f64,
+    pub confidence_upper: f64,
+}
+
+impl LogicalErrorRate {
+    pub const CSV_HEADER: &'static str = "failures,logical_error_rate,confidence_lower,confidence_upper";
+
+    pub fn new(failures: usize, shots: usize) -> Self {
+        assert!(shots > 0, "no shots to compute the logical error rate");
+        let z = 1.96; // 95% confidence
+        let n = shots as f64;
+        let rate = failures as f64 / n;
+        let denominator = 1. + z * z / n;
+        let center = (rate + z * z / (2. * n)) / denominator;
+        let half_width = z / denominator * (rate * (1. - rate) / n + z * z / (4. * n * n)).sqrt();
+        Self {
+            failures,
+            shots,
+            rate,
+            confidence_lower: (center - half_width).max(0.),
+            confidence_upper: (center + half_width).min(1.),
+        }
+    }
+
+    pub fn brief(&self) -> String {
+        format!(
+            "logical error rate: {:.3e} ({}/{}), 95% confidence interval: [{:.3e}, {:.3e}]",
+            self.rate, self.failures, self.shots, self.confidence_lower, self.confidence_upper
+        )
+    }
+}
+
+impl BenchmarkRecord {
+    pub const CSV_HEADER: &'static str = "d,p,noisy_measurements,partitions,threads,shots,mean_latency,p50_latency,p90_latency,p99_latency,p999_latency,max_latency,throughput,total_weight";
+
+    pub fn csv_header(&self) -> String {
+        let mut header = Self::CSV_HEADER.to_string();
+        if self.logical_error_rate.is_some() {
+            header += &format!(",{}", LogicalErrorRate::CSV_HEADER);
+        }
+        if self.memory_usage.is_some() {
+            header += &format!(",{}", MemoryUsage::CSV_HEADER);
+        }
+        header
+    }
+
+    pub fn csv_row(&self) -> String {
+        let mut row = format!(
+            "{},{},{},{},{},{},{:e},{:e},{:e},{:e},{:e},{:e},{:e},{}",
+            self.d,
+            self.p,
+            self.noisy_measurements,
+            self.partitions,
+            self.threads,
+            self.shots,
+            self.mean_latency,
+            self.p50_latency,
+            self.p90_latency,
+            self.p99_latency,
+            self.p999_latency,
+            self.max_latency,
+            self.throughput,
+            self.total_weight
+        );
+        if let Some(logical_error_rate) = self.logical_error_rate.as_ref() {
+            row += &format!(
+                ",{},{:e},{:e},{:e}",
+                logical_error_rate.failures,
+                logical_error_rate.rate,
+                logical_error_rate.confidence_lower,
+                logical_error_rate.confidence_upper
+            );
+        }
+        if let Some(memory_usage) = self.memory_usage.as_ref() {
+            let show = |bytes: Option<usize>| bytes.map_or(String::new(), |bytes| bytes.to_string());
+            row += &format!(",{},{}", show(memory_usage.estimate), show(memory_usage.peak_resident_delta));
+        }
+        row
+    }
+
+    /// print the record to stdout, or append it to the file so that a sweep of configurations builds up a single table
+    /// print the records or write them to a file, appending to the existing content
+    pub fn output(records: &[Self], output_format: OutputFormat, output_file: Option<&str>) -> std::io::Result<()> {
+        let existing = match output_file {
+            Some(path) => std::fs::read_to_string(path).unwrap_or_default(),
+            None => String::new(),
+        };
+        let content = match output_format {
+            OutputFormat::Text => unreachable!("text output is the brief of the benchmark profiler"),
+            OutputFormat::Csv => {
+                let mut content = existing;
+                for record in records.iter() {
+                    if content.trim().is_empty() {
+                        content = format!("{}\n", record.csv_header());
+                    }
+                    assert!(
+                        content.lines().next() == Some(record.csv_header().as_str()),
+                        "cannot append to a csv file with a different header"
+                    );
+                    content += &format!("{}\n", record.csv_row());
+                }
+                content
+            }
+            OutputFormat::Json => {
+                let mut values: Vec<serde_json::Value> = if existing.trim().is_empty() {
+                    vec![]
+                } else {
+                    serde_json::from_str(&existing)?
+                };
+                values.extend(records.iter().map(|record| json!(record)));
+                serde_json::to_string_pretty(&values)? + "\n"
+            }
+        };
+        match output_file {
+            Some(path) => std::fs::write(path, content),
+            None => std::io::stdout().write_all(content.as_bytes()),
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Debug)]
 pub enum Verifier {
     /// disable verifier
@@ -221,108 +823,981 @@ pub enum Verifier {
     FusionSerial,
 }
 
+/// the detection events sampled by Stim and the decoding graph of its detector error model, see `--syndrome-format`
+struct StimSource {
+    format: StimFormat,
+    syndrome_file: String,
+    dem: StimDetectorErrorModel,
+    /// the number of shots in the syndrome file
+    shots: usize,
+    obs_file: Option<String>,
+}
+
+impl StimSource {
+    /// `None` unless a Stim format is given; every inconsistency between the files panics with a helpful message
+    fn new(
+        syndrome_format: SyndromeFormat,
+        syndrome_file: Option<String>,
+        num_detectors: Option<usize>,
+        dem: Option<String>,
+        obs_file: Option<String>,
+        max_half_weight: Weight,
+    ) -> Option<Self> {
+        let format = match syndrome_format {
+            SyndromeFormat::CodeType => {
+                assert!(
+                    syndrome_file.is_none() && num_detectors.is_none() && dem.is_none() && obs_file.is_none(),
+                    "`--syndrome-file`, `--num-detectors`, `--dem` and `--obs-file` require `--syndrome-format stim-b8` or `stim-01`"
+                );
+                return None;
+            }
+            SyndromeFormat::StimB8 => StimFormat::B8,
+            SyndromeFormat::Stim01 => StimFormat::Dets01,
+        };
+        let syndrome_file =
+            syndrome_file.expect("the Stim formats require the detection events, e.g. `--syndrome-file events.b8`");
+        let dem_file = dem.expect("the Stim formats require the detector error model, e.g. `--dem model.dem`");
+        let dem = StimDetectorErrorModel::load(&dem_file, max_half_weight)
+            .unwrap_or_else(|error| panic!("cannot read the detector error model {dem_file}: {error}"));
+        if let Some(num_detectors) = num_detectors {
+            assert_eq!(
+                num_detectors, dem.num_detectors,
+                "`--num-detectors {num_detectors}` does not match the {} detectors of {dem_file}; the detector error model must come from the same circuit as the detection events",
+                dem.num_detectors
+            );
+        }
+        let shots = count_stim_shots(&syndrome_file, format, dem.num_detectors).unwrap_or_else(|error| {
+            panic!(
+                "cannot read the detection events {syndrome_file} with the {} detectors of {dem_file}: {error}",
+                dem.num_detectors
+            )
+        });
+        if let Some(obs_file) = obs_file.as_ref() {
+            let observable_shots = count_stim_shots(obs_file, format, dem.num_observables).unwrap_or_else(|error| {
+                panic!(
+                    "cannot read the observable flips {obs_file} with the {} observables of {dem_file}: {error}",
+                    dem.num_observables
+                )
+            });
+            assert_eq!(
+                observable_shots, shots,
+                "{obs_file} has {observable_shots} shots but {syndrome_file} has {shots} shots"
+            );
+        }
+        Some(Self {
+            format,
+            syndrome_file,
+            dem,
+            shots,
+            obs_file,
+        })
+    }
+
+    fn event_reader(&self) -> StimEventReader {
+        StimEventReader::new(
+            &self.syndrome_file,
+            self.format,
+            self.dem.num_detectors,
+            &self.dem.initializer,
+        )
+        .unwrap_or_else(|error| panic!("cannot read {}: {error}", self.syndrome_file))
+    }
+
+    fn observable_reader(&self) -> Option<StimObservableReader> {
+        self.obs_file.as_ref().map(|obs_file| {
+            StimObservableReader::new(obs_file, self.format, self.dem.num_observables)
+                .unwrap_or_else(|error| panic!("cannot read {obs_file}: {error}"))
+        })
+    }
+}
+
+impl BenchmarkParameters {
+    /// report the progress of every combination after each round, with the combination as the current configuration
+    pub fn set_progress_callback(&mut self, callback: ProgressCallback) {
+        self.progress_callback = Some(Arc::new(callback));
+    }
+
+    /// run the benchmark of every combination of the parameter lists and return their records
+    pub fn run(self) -> Vec<BenchmarkRecord> {
+        let BenchmarkParameters {
+            d,
+            p,
+            pe,
+            noisy_measurements,
+            max_half_weight,
+            code_type,
+            enable_visualizer,
+            verifier,
+            total_rounds,
+            primal_dual_type,
+            partition_strategy,
+            pb_message,
+            primal_dual_config,
+            code_config,
+            partition_config,
+            use_deterministic_seed,
+            seeding,
+            benchmark_profiler_output,
+            print_syndrome_pattern,
+            starting_iteration,
+            output_format,
+            compute_logical_error_rate,
+            max_failures,
+            warmup,
+            report_memory,
+            dry_run,
+            d_list,
+            p_list,
+            noisy_measurements_list,
+            checkpoint_file,
+            checkpoint_interval,
+            quiet,
+            progress_callback,
+            syndrome_format,
+            syndrome_file,
+            num_detectors,
+            dem,
+            obs_file,
+            ..
+        } = self;
+        let stim = StimSource::new(syndrome_format, syndrome_file, num_detectors, dem, obs_file, max_half_weight);
+        let total_rounds = match stim.as_ref() {
+            Some(stim) => {
+                assert_eq!(
+                    partition_strategy,
+                    PartitionStrategy::None,
+                    "the Stim formats only support `--partition-strategy none`"
+                );
+                assert!(pe == 0., "the Stim formats do not support erasures");
+                assert!(
+                    !compute_logical_error_rate || stim.obs_file.is_some(),
+                    "the logical error rate of the Stim formats requires the observable flips, e.g. `--obs-file obs.b8`"
+                );
+                if total_rounds > stim.shots {
+                    eprintln!("only {} shots in {}", stim.shots, stim.syndrome_file);
+                }
+                total_rounds.min(stim.shots)
+            }
+            None => total_rounds,
+        };
+        let compute_logical_error_rate =
+            compute_logical_error_rate || stim.as_ref().is_some_and(|stim| stim.obs_file.is_some());
+        // the Stim formats compare the observables instead of the error edges
+        let uses_error_edges = compute_logical_error_rate && stim.is_none();
+        let code_config = code_type.with_seeding(serde_json::from_str(&code_config).unwrap(), seeding);
+        let primal_dual_config: serde_json::Value = serde_json::from_str(&primal_dual_config).unwrap();
+        let partition_config: serde_json::Value = serde_json::from_str(&partition_config).unwrap();
+        let build_code = |d: VertexNum, p: f64, noisy_measurements: VertexNum| -> Box<dyn ExampleCode> {
+            match stim.as_ref() {
+                Some(stim) => Box::new(stim.event_reader()),
+                None => code_type.build(d, p, noisy_measurements, max_half_weight, code_config.clone()),
+            }
+        };
+        // the error pattern logger records the error edges whenever they are generated
+        let mut solver_config = primal_dual_config.clone();
+        if primal_dual_type == PrimalDualType::ErrorPatternLogger && uses_error_edges {
+            let config = solver_config.as_object_mut().expect("config must be JSON object");
+            config.insert("error_edges".to_string(), json!(true));
+        }
+        // the watchdog of the parallel solver only guards the fallible solve
+        let has_watchdog = (primal_dual_config.pointer("/primal/hang_timeout")).is_some_and(|value| !value.is_null());
+        // check for dependency early
+        if matches!(verifier, Verifier::BlossomV) && cfg!(not(feature = "blossom_v")) {
+            panic!("need blossom V library, see README.md")
+        }
+        // whether to disable progress bar, useful when running jobs in background
+        let disable_progress_bar = quiet || env::var("DISABLE_PROGRESS_BAR").is_ok();
+        if enable_visualizer {
+            // print visualizer file path only once
+            print_visualize_link(static_visualize_data_filename());
+        }
+        // iterate over the cross product of the parameter lists, if any
+        let is_sweep = !d_list.is_empty() || !p_list.is_empty() || !noisy_measurements_list.is_empty();
+        assert!(
+            !(is_sweep && stim.is_some()),
+            "the Stim formats decode a single file, so the parameter lists cannot be swept"
+        );
+        // the distance and error rate of the Stim formats are unknown and reported as 0
+        let d_list = if d_list.is_empty() {
+            vec![d.or(stim.is_some().then_some(0)).expect("code distance is required")]
+        } else {
+            d_list
+        };
+        let p_list = if p_list.is_empty() {
+            vec![p.or(stim.is_some().then_some(0.)).expect("physical error rate is required")]
+        } else {
+            p_list
+        };
+        let noisy_measurements_list = if noisy_measurements_list.is_empty() {
+            vec![noisy_measurements]
+        } else {
+            noisy_measurements_list
+        };
+        let mut records = vec![];
+        // the solver is reused among combinations with the same decoding graph and partition
+        let mut reusable_solver: Option<(SolverInitializer, PartitionConfig, Box<dyn PrimalDualSolver>)> = None;
+        let mut combinations = vec![];
+        for &d in d_list.iter() {
+            for &p in p_list.iter() {
+                for &noisy_measurements in noisy_measurements_list.iter() {
+                    combinations.push((d, p, noisy_measurements));
+                }
+            }
+        }
+        if dry_run {
+            let solver_type = primal_dual_type
+                .solver_type()
+                .expect("the memory of this primal-dual type cannot be estimated");
+            for (d, p, noisy_measurements) in combinations {
+                let mut code = build_code(d, p, noisy_measurements);
+                let (initializer, partition_config) =
+                    partition_strategy.build(&mut *code, d, noisy_measurements, partition_config.clone());
+                let estimate = initializer.estimate_solver_memory(solver_type, partition_config.partitions.len());
+                if is_sweep {
+                    print!("d: {d}, p: {p}, noisy_measurements: {noisy_measurements}, ");
+                }
+                println!("{}", estimate.brief());
+            }
+            return records;
+        }
+        let mut checkpoint = checkpoint_file.as_ref().map(|filename| {
+            assert!(checkpoint_interval > 0, "the checkpoint interval must be positive");
+            let mut config = json!({
+                "combinations": combinations,
+                "pe": pe,
+                "max_half_weight": max_half_weight,
+                "code_type": code_type,
+                "code_config": code_config,
+                "primal_dual_type": primal_dual_type,
+                "primal_dual_config": primal_dual_config,
+                "partition_strategy": partition_strategy,
+                "partition_config": partition_config,
+                "total_rounds": total_rounds,
+                "starting_iteration": starting_iteration,
+                "warmup": warmup,
+                "use_deterministic_seed": use_deterministic_seed,
+                "seeding": seeding,
+                "compute_logical_error_rate": compute_logical_error_rate,
+                "max_failures": max_failures,
+                "output_format": output_format,
+            });
+            // only present with the Stim formats, so that the checkpoints of the other benchmarks remain valid
+            if let Some(stim) = stim.as_ref() {
+                config["stim"] = json!({
+                    "format": stim.format,
+                    "syndrome_file": stim.syndrome_file,
+                    "dem": stim.dem,
+                    "obs_file": stim.obs_file,
+                });
+            }
+            let config_hash = config_hash(&config);
+            catch_interrupt();
+            match BenchmarkCheckpoint::load(filename).unwrap() {
+                Some(checkpoint) => {
+                    assert_eq!(
+                        checkpoint.config_hash, config_hash,
+                        "the checkpoint file {filename} is written by a benchmark with different parameters"
+                    );
+                    eprintln!("resuming from checkpoint file {filename}");
+                    checkpoint
+                }
+                None => BenchmarkCheckpoint {
+                    config_hash,
+                    seed: thread_rng().gen(),
+                    combinations: vec![],
+                },
+            }
+        });
+        let checkpoint_seed = checkpoint.as_ref().map(|checkpoint| checkpoint.seed);
+        for (combination_index, (d, p, noisy_measurements)) in combinations.into_iter().enumerate() {
+            let resumed = checkpoint
+                .as_ref()
+                .and_then(|checkpoint| checkpoint.combinations.get(combination_index).cloned());
+            if let Some(resumed) = resumed.as_ref().filter(|resumed| resumed.finished) {
+                records.extend(resumed.record.clone());
+                continue;
+            }
+            let mut code = build_code(d, p, noisy_measurements);
+            let mut observable_reader = stim.as_ref().and_then(StimSource::observable_reader);
+            let mut next_observable_flips = || {
+                observable_reader.as_mut().map(|reader| match reader.next_shot() {
+                    Ok(Some(observable_flips)) => observable_flips,
+                    Ok(None) => panic!("reading more than the {} shots in {}", reader.shot_index, reader.path),
+                    Err(error) => panic!("failed to read {}: {error}", reader.path),
+                })
+            };
+            if pe != 0. {
+                code.set_erasure_probability(pe);
+            }
+            // create initializer and solver
+            let (initializer, partition_config) =
+                partition_strategy.build(&mut *code, d, noisy_measurements, partition_config.clone());
+            let partition_info = partition_config.info();
+            let threads = match primal_dual_type {
+                PrimalDualType::DualParallel | PrimalDualType::Parallel => {
+                    match primal_dual_config.get("thread_pool_size").and_then(|value| value.as_u64()) {
+                        Some(thread_pool_size) if thread_pool_size > 0 => thread_pool_size as usize,
+                        _ => rayon::current_num_threads(),
+                    }
+                }
+                _ => 1,
+            };
+            let enable_profiler = benchmark_profiler_output.is_some();
+            let resident_before = if report_memory {
+                reset_peak_resident_memory();
+                resident_memory()
+            } else {
+                None
+            };
+            let mut primal_dual_solver = match reusable_solver.take() {
+                Some((reusable_initializer, reusable_partition_config, primal_dual_solver))
+                    if reusable_initializer == initializer && reusable_partition_config == partition_config =>
+                {
+                    primal_dual_solver
+                }
+                _ => primal_dual_type.build(&initializer, &partition_info, &*code, solver_config.clone()),
+            };
+            if warmup > 0 {
+                primal_dual_solver.warm_up();
+            }
+            let mut result_verifier = verifier.build(&initializer);
+            let mut benchmark_profiler = BenchmarkProfiler::new(
+                noisy_measurements,
+                benchmark_profiler_output.clone().map(|x| (x, &partition_info)),
+            );
+            if partition_info.config.partitions.len() > 1 {
+                benchmark_profiler.fusion_depth = Some(partition_info.fusion_depth());
+            }
+            benchmark_profiler.warmup = warmup;
+            // prepare progress bar display
+            let mut pb = if !disable_progress_bar {
+                let mut pb = ProgressBar::on(std::io::stderr(), total_rounds as u64);
+                pb.message(format!("{pb_message} ").as_str());
+                Some(pb)
+            } else {
+                if !pb_message.is_empty() {
+                    print!("{pb_message} ");
+                }
+                None
+            };
+            let mut rng = thread_rng();
+            let combination_seed = shot_seed(shot_seed(d as u64, noisy_measurements as u64), p.to_bits());
+            let seeder = (seeding == Seeding::V2).then(|| {
+                ShotSeeder::new(if use_deterministic_seed {
+                    0
+                } else if is_sweep {
+                    combination_seed
+                } else {
+                    checkpoint_seed.unwrap_or_else(|| rng.gen())
+                })
+            });
+            let mut seed_of = |round: u64| {
+                if let Some(seeder) = seeder.as_ref() {
+                    seeder.seed_for(round)
+                } else if use_deterministic_seed {
+                    round
+                } else if is_sweep {
+                    // reproducible for each combination
+                    shot_seed(combination_seed, round)
+                } else if let Some(checkpoint_seed) = checkpoint_seed {
+                    // reproducible when resuming from the checkpoint
+                    shot_seed(checkpoint_seed, round)
+                } else {
+                    rng.gen()
+                }
+            };
+            let mut total_weight: Weight = 0;
+            let mut logical_failures = 0;
+            let mut parallel_statistics = vec![];
+            let mut first_round = starting_iteration as u64;
+            if let Some(resumed) = resumed {
+                benchmark_profiler.resume(resumed.profiler);
+                benchmark_profiler.warmup = resumed.warmup;
+                total_weight = resumed.total_weight;
+                logical_failures = resumed.logical_failures;
+                first_round = resumed.next_round;
+                for round in (starting_iteration as u64)..first_round {
+                    let seed = seed_of(round);
+                    if uses_error_edges {
+                        code.generate_random_errors_with_edges(seed);
+                    } else {
+                        code.generate_random_errors(seed);
+                    }
+                    next_observable_flips();
+                }
+            } else if let Some(checkpoint) = checkpoint.as_mut() {
+                checkpoint.combinations.push(CombinationCheckpoint {
+                    d,
+                    p,
+                    noisy_measurements,
+                    next_round: first_round,
+                    warmup,
+                    finished: false,
+                    total_weight,
+                    logical_failures,
+                    profiler: benchmark_profiler.state(),
+                    record: None,
+                });
+            }
+            // the combination in progress is always the last one in the checkpoint
+            let update_checkpoint = |checkpoint: &mut BenchmarkCheckpoint,
+                                     next_round: u64,
+                                     total_weight: Weight,
+                                     logical_failures: usize,
+                                     benchmark_profiler: &BenchmarkProfiler| {
+                let progress = checkpoint.combinations.last_mut().unwrap();
+                progress.next_round = next_round;
+                progress.warmup = benchmark_profiler.warmup;
+                progress.total_weight = total_weight;
+                progress.logical_failures = logical_failures;
+                progress.profiler = benchmark_profiler.state();
+            };
+            // the error pattern logger only records the syndrome (and error edges) without solving it
+            let is_solving = primal_dual_type != PrimalDualType::ErrorPatternLogger;
+            let combination_begin = std::time::Instant::now();
+            let count_logical_errors = compute_logical_error_rate && is_solving;
+            for round in first_round..(total_rounds as u64) {
+                pb.as_mut().map(|pb| pb.set(round));
+                let seed = seed_of(round);
+                let (syndrome_pattern, error_edges) = if uses_error_edges {
+                    let (syndrome_pattern, error_edges) = code.generate_random_errors_with_edges(seed);
+                    primal_dual_solver.set_error_edges(&error_edges);
+                    (syndrome_pattern, Some(error_edges))
+                } else {
+                    (code.generate_random_errors(seed), None)
+                };
+                let observable_flips = next_observable_flips();
+                if print_syndrome_pattern {
+                    println!("syndrome_pattern: {:?}", syndrome_pattern);
+                }
+                // create a new visualizer each round
+                let mut visualizer = None;
+                if enable_visualizer {
+                    let mut new_visualizer = Visualizer::new(
+                        Some(visualize_data_folder() + static_visualize_data_filename().as_str()),
+                        code.get_positions(),
+                        true,
+                    )
+                    .unwrap();
+                    let is_parallel = matches!(primal_dual_type, PrimalDualType::DualParallel | PrimalDualType::Parallel);
+                    new_visualizer
+                        .set_initializer(&initializer, is_parallel.then_some(&partition_config))
+                        .unwrap();
+                    visualizer = Some(new_visualizer);
+                }
+                let is_warmup = benchmark_profiler.warmup > 0;
+                benchmark_profiler.begin(&syndrome_pattern);
+                if has_watchdog && visualizer.is_none() {
+                    if let Err(error) = primal_dual_solver.solve_checked(&syndrome_pattern) {
+                        eprintln!("round {round}: {error}");
+                        if let FusionError::Hang(solver_hang) = &error {
+                            let filename = std::env::temp_dir().join(format!("fusion_blossom_hang_round_{round}.json"));
+                            let filename = filename.to_str().unwrap();
+                            solver_hang.write_dump(filename).unwrap();
+                            eprintln!("the diagnostic dump is written to {filename}");
+                        }
+                        std::process::exit(1);
+                    }
+                } else {
+                    primal_dual_solver.solve_visualizer(&syndrome_pattern, visualizer.as_mut());
+                }
+                benchmark_profiler.event("decoded".to_string());
+                if is_solving && !is_warmup {
+                    total_weight += primal_dual_solver.sum_dual_variables();
+                }
+                result_verifier.verify(&mut primal_dual_solver, &syndrome_pattern, visualizer.as_mut());
+                benchmark_profiler.event("verified".to_string());
+                if count_logical_errors && !is_warmup {
+                    let correction_edges = primal_dual_solver.subgraph();
+                    let is_logical_error = match (error_edges.as_ref(), observable_flips.as_ref(), stim.as_ref()) {
+                        (Some(error_edges), _, _) => code.is_logical_error(error_edges, &correction_edges),
+                        (_, Some(observable_flips), Some(stim)) => {
+                            &stim.dem.observable_flips(&correction_edges) != observable_flips
+                        }
+                        _ => false,
+                    };
+                    if is_logical_error {
+                        logical_failures += 1;
+                    }
+                }
+                primal_dual_solver.clear(); // also count the clear operation
+                benchmark_profiler.end(Some(&*primal_dual_solver));
+                if enable_profiler && !is_warmup {
+                    parallel_statistics.extend(primal_dual_solver.parallel_statistics());
+                }
+                if let Some(pb) = pb.as_mut() {
+                    if pb_message.is_empty() {
+                        pb.message(format!("{} ", benchmark_profiler.brief()).as_str());
+                    }
+                }
+                if let (Some(checkpoint), Some(filename)) = (checkpoint.as_mut(), checkpoint_file.as_ref()) {
+                    let interrupted = INTERRUPTED.load(Ordering::SeqCst);
+                    if interrupted || (round + 1 - starting_iteration as u64).is_multiple_of(checkpoint_interval as u64) {
+                        update_checkpoint(checkpoint, round + 1, total_weight, logical_failures, &benchmark_profiler);
+                        checkpoint.save(filename).unwrap();
+                    }
+                    if interrupted {
+                        eprintln!("interrupted, the progress is saved to checkpoint file {filename}");
+                        std::process::exit(130);
+                    }
+                }
+                if let Some(callback) = progress_callback.as_ref() {
+                    callback(ProgressEvent {
+                        completed: round as usize + 1,
+                        total: total_rounds,
+                        elapsed: combination_begin.elapsed(),
+                        current_config: format!("d: {d}, p: {p}, noisy_measurements: {noisy_measurements}"),
+                    });
+                }
+                if max_failures.is_some_and(|max_failures| logical_failures >= max_failures) {
+                    break;
+                }
+            }
+            let logical_error_rate =
+                count_logical_errors.then(|| LogicalErrorRate::new(logical_failures, benchmark_profiler.rounds()));
+            let memory_usage = report_memory.then(|| MemoryUsage {
+                estimate: primal_dual_solver.memory_estimate(),
+                peak_resident_delta: peak_resident_memory()
+                    .zip(resident_before)
+                    .map(|(peak, before)| peak.saturating_sub(before)),
+            });
+            let shots = benchmark_profiler.rounds();
+            records.push(BenchmarkRecord {
+                d,
+                p,
+                noisy_measurements,
+                partitions: partition_info.config.partitions.len(),
+                threads,
+                shots,
+                mean_latency: benchmark_profiler.sum_round_time / shots as f64,
+                p50_latency: benchmark_profiler.latency_histogram.percentile(0.5),
+                p90_latency: benchmark_profiler.latency_histogram.percentile(0.9),
+                p99_latency: benchmark_profiler.latency_histogram.percentile(0.99),
+                p999_latency: benchmark_profiler.latency_histogram.percentile(0.999),
+                max_latency: benchmark_profiler.latency_histogram.max,
+                throughput: shots as f64 / benchmark_profiler.sum_round_time,
+                total_weight,
+                solver_profile: enable_profiler.then(|| primal_dual_solver.generate_profiler_report()),
+                logical_error_rate,
+                memory_usage,
+            });
+            if output_format != OutputFormat::Text {
+                if let Some(pb) = pb.as_mut() {
+                    pb.finish();
+                    eprintln!();
+                }
+            } else if disable_progress_bar {
+                if is_sweep {
+                    print!("d: {d}, p: {p}, noisy_measurements: {noisy_measurements}, ");
+                }
+                // always print out brief
+                println!("{}", benchmark_profiler.brief());
+            } else {
+                if let Some(pb) = pb.as_mut() {
+                    pb.finish()
+                }
+                println!();
+            }
+            if let Some(logical_error_rate) = logical_error_rate.filter(|_| output_format == OutputFormat::Text) {
+                println!("{}", logical_error_rate.brief());
+            }
+            if let Some(memory_usage) = memory_usage.filter(|_| output_format == OutputFormat::Text) {
+                println!("{}", memory_usage.brief());
+            }
+            if output_format == OutputFormat::Text {
+                for fusion in ParallelSolverStatistics::average(&parallel_statistics) {
+                    println!(
+                        "fusion unit {}: interface nodes {:.3}, tight interface edges {:.3} over {} shots",
+                        fusion.unit_index, fusion.interface_nodes, fusion.tight_interface_edges, fusion.shots
+                    );
+                }
+            }
+            if let (Some(checkpoint), Some(filename)) = (checkpoint.as_mut(), checkpoint_file.as_ref()) {
+                update_checkpoint(
+                    checkpoint,
+                    total_rounds as u64,
+                    total_weight,
+                    logical_failures,
+                    &benchmark_profiler,
+                );
+                let progress = checkpoint.combinations.last_mut().unwrap();
+                progress.finished = true;
+                progress.record = records.last().cloned();
+                checkpoint.save(filename).unwrap();
+            }
+            reusable_solver = Some((initializer, partition_config, primal_dual_solver));
+        }
+        if let Some(filename) = checkpoint_file.as_ref() {
+            std::fs::remove_file(filename).unwrap();
+        }
+        records
+    }
+}
+
 impl Cli {
     pub fn run(self) {
         match self.command {
-            Commands::Benchmark(BenchmarkParameters {
+            Commands::Benchmark(benchmark_parameters) => {
+                let output_format = benchmark_parameters.output_format;
+                let output_file = benchmark_parameters.output_file.clone();
+                let records = benchmark_parameters.run();
+                if output_format != OutputFormat::Text {
+                    BenchmarkRecord::output(&records, output_format, output_file.as_deref()).unwrap();
+                }
+            }
+            Commands::Threshold(ThresholdParameters {
+                benchmark,
+                bootstrap_samples,
+                bootstrap_seed,
+            }) => {
+                assert!(
+                    benchmark.d_list.len() >= 2,
+                    "the threshold needs at least 2 code distances in `--d-list`"
+                );
+                assert!(!benchmark.p_list.is_empty(), "the threshold needs `--p-list`");
+                assert!(
+                    benchmark.noisy_measurements_list.len() <= 1,
+                    "the scaling ansatz assumes the same rounds of noisy measurement for all points"
+                );
+                let output_format = benchmark.output_format;
+                let output_file = benchmark.output_file.clone();
+                let records = BenchmarkParameters {
+                    compute_logical_error_rate: true,
+                    ..benchmark
+                }
+                .run();
+                if output_format != OutputFormat::Text {
+                    BenchmarkRecord::output(&records, output_format, output_file.as_deref()).unwrap();
+                }
+                let points: Vec<_> = records
+                    .iter()
+                    .map(|record| {
+                        let logical_error_rate = record
+                            .logical_error_rate
+                            .expect("the solver must decode to compute the logical error rate");
+                        ThresholdPoint {
+                            d: record.d,
+                            p: record.p,
+                            shots: logical_error_rate.shots,
+                            failures: logical_error_rate.failures,
+                        }
+                    })
+                    .collect();
+                println!("{}", estimate_threshold(&points, bootstrap_samples, bootstrap_seed).brief());
+            }
+            Commands::GenerateSyndromes(GenerateSyndromesParameters {
                 d,
                 p,
                 pe,
                 noisy_measurements,
                 max_half_weight,
                 code_type,
-                enable_visualizer,
-                verifier,
-                total_rounds,
-                primal_dual_type,
-                partition_strategy,
-                pb_message,
-                primal_dual_config,
                 code_config,
-                partition_config,
-                use_deterministic_seed,
-                benchmark_profiler_output,
-                print_syndrome_pattern,
-                starting_iteration,
-                ..
+                shots,
+                seed,
+                seeding,
+                output,
+                shards,
+                format,
+                threads,
+                include_error_edges,
+                quiet,
             }) => {
-                let code_config: serde_json::Value = serde_json::from_str(&code_config).unwrap();
-                let primal_dual_config: serde_json::Value = serde_json::from_str(&primal_dual_config).unwrap();
-                let partition_config: serde_json::Value = serde_json::from_str(&partition_config).unwrap();
-                // check for dependency early
-                if matches!(verifier, Verifier::BlossomV) && cfg!(not(feature = "blossom_v")) {
-                    panic!("need blossom V library, see README.md")
-                }
-                // whether to disable progress bar, useful when running jobs in background
-                let disable_progress_bar = env::var("DISABLE_PROGRESS_BAR").is_ok();
-                let mut code: Box<dyn ExampleCode> = code_type.build(d, p, noisy_measurements, max_half_weight, code_config);
-                if pe != 0. {
-                    code.set_erasure_probability(pe);
+                assert!(
+                    code_type != ExampleCodeType::ErrorPatternReader
+                        && code_type != ExampleCodeType::ShardedErrorPatternReader,
+                    "cannot generate syndrome patterns from a syndrome pattern file"
+                );
+                assert!(threads > 0, "at least one thread is required");
+                assert!(shards > 0, "at least one shard is required");
+                let code_config = code_type.with_seeding(serde_json::from_str(&code_config).unwrap(), seeding);
+                let build_code = || {
+                    let mut code = code_type.build(d, p, noisy_measurements, max_half_weight, code_config.clone());
+                    if pe != 0. {
+                        code.set_erasure_probability(pe);
+                    }
+                    code
+                };
+                let code = build_code();
+                let mut syndrome_file = SyndromeFile::new(code.get_initializer(), code.get_positions());
+                let mut generator = SyndromeGenerator::new(build_code, threads, include_error_edges);
+                if let Some(callback) = progress_line(quiet) {
+                    generator.set_progress_callback(callback);
                 }
-                if enable_visualizer {
-                    // print visualizer file path only once
-                    print_visualize_link(static_visualize_data_filename());
+                for (syndrome_pattern, error_edges) in generator.generate(shots, seed) {
+                    syndrome_file.push(syndrome_pattern, error_edges);
                 }
-                // create initializer and solver
-                let (initializer, partition_config) =
-                    partition_strategy.build(&mut *code, d, noisy_measurements, partition_config);
-                let partition_info = partition_config.info();
-                let mut primal_dual_solver =
-                    primal_dual_type.build(&initializer, &partition_info, &*code, primal_dual_config);
-                let mut result_verifier = verifier.build(&initializer);
-                let mut benchmark_profiler =
-                    BenchmarkProfiler::new(noisy_measurements, benchmark_profiler_output.map(|x| (x, &partition_info)));
-                // prepare progress bar display
-                let mut pb = if !disable_progress_bar {
-                    let mut pb = ProgressBar::on(std::io::stderr(), total_rounds as u64);
-                    pb.message(format!("{pb_message} ").as_str());
-                    Some(pb)
+                let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+                if shards == 1 {
+                    syndrome_file.save(&output, format == SyndromeFileFormat::Bincode).unwrap();
                 } else {
-                    if !pb_message.is_empty() {
-                        print!("{pb_message} ");
+                    // the shards are written concurrently, each taking every `shards`-th syndrome pattern
+                    thread_pool.install(|| {
+                        (0..shards).into_par_iter().for_each(|shard_index| {
+                            let mut writer = SyndromeFileWriter::create(
+                                &shard_filename(&output, shard_index),
+                                &syndrome_file.initializer,
+                                &syndrome_file.positions,
+                                format == SyndromeFileFormat::Bincode,
+                                include_error_edges,
+                            )
+                            .unwrap();
+                            for shot in (shard_index..shots).step_by(shards) {
+                                let error_edges = syndrome_file.error_edges[shot].as_deref();
+                                writer.write(&syndrome_file.syndrome_patterns[shot], error_edges).unwrap();
+                            }
+                            writer.finalize().unwrap().finish().unwrap();
+                        })
+                    });
+                }
+            }
+            Commands::ConvertSyndromes(ConvertSyndromesParameters {
+                input,
+                output,
+                format,
+                head,
+                skip,
+                strip_errors,
+            }) => {
+                let reader = SyndromeFileReader::open(&input).unwrap();
+                let (initializer, positions) = (reader.initializer.clone(), reader.positions.clone());
+                let end = head.map_or(usize::MAX, |head| skip.saturating_add(head));
+                let mut records = reader.enumerate().take(end).peekable();
+                // the error edges are kept if the (first) syndrome pattern has them
+                let keep_error_edges = !strip_errors && matches!(records.peek(), Some((_, Ok((_, Some(_))))));
+                let mut writer = SyndromeFileWriter::create(
+                    &output,
+                    &initializer,
+                    &positions,
+                    format == SyndromeFileFormat::Bincode,
+                    keep_error_edges,
+                )
+                .unwrap();
+                for (index, record) in records {
+                    let (syndrome_pattern, error_edges) = record.unwrap();
+                    if index < skip {
+                        continue;
+                    }
+                    let error_edges = error_edges.as_deref().filter(|_| keep_error_edges);
+                    writer.write(&syndrome_pattern, error_edges).unwrap();
+                }
+                writer.finalize().unwrap().finish().unwrap();
+            }
+            Commands::InspectSyndromes(InspectSyndromesParameters { filename, output_format }) => {
+                assert!(
+                    output_format != OutputFormat::Csv,
+                    "csv is not supported by inspect-syndromes"
+                );
+                let mut reader = ErrorPatternReader::new(json!({ "filename": filename }));
+                let positions = reader.get_positions();
+                let statistics = analysis::dataset_statistics(&mut reader, Some(&positions));
+                match output_format {
+                    OutputFormat::Json => println!("{}", serde_json::to_string(&statistics).unwrap()),
+                    _ => print!("{}", statistics.brief()),
+                }
+            }
+            Commands::Compare(CompareParameters {
+                filename,
+                syndrome_format,
+                num_detectors,
+                dem,
+                max_half_weight,
+                engines,
+                d,
+                noisy_measurements,
+                partition_strategy,
+                partition_config,
+                primal_dual_config,
+                total_rounds,
+                dump_folder,
+                corrupt_shot,
+            }) => {
+                let partition_config: serde_json::Value = serde_json::from_str(&partition_config).unwrap();
+                let primal_dual_config: serde_json::Value = serde_json::from_str(&primal_dual_config).unwrap();
+                // the filename is the detection events with the Stim formats
+                let stim = StimSource::new(
+                    syndrome_format,
+                    (syndrome_format != SyndromeFormat::CodeType).then(|| filename.clone()),
+                    num_detectors,
+                    dem,
+                    None,
+                    max_half_weight,
+                );
+                let (initializer, partition_config, mut next_syndrome_pattern): (
+                    _,
+                    _,
+                    Box<dyn FnMut() -> Option<SyndromePattern>>,
+                ) = match stim {
+                    Some(stim) => {
+                        assert_eq!(
+                            partition_strategy,
+                            PartitionStrategy::None,
+                            "the Stim formats only support `--partition-strategy none`"
+                        );
+                        let mut code = stim.event_reader();
+                        let (initializer, partition_config) =
+                            partition_strategy.build(&mut code, d, noisy_measurements, partition_config);
+                        let next_syndrome_pattern = move || {
+                            code.next_shot()
+                                .unwrap_or_else(|error| panic!("failed to read {}: {error}", code.path))
+                        };
+                        (initializer, partition_config, Box::new(next_syndrome_pattern))
+                    }
+                    None => {
+                        let mut code = ErrorPatternReader::new(json!({ "filename": filename }));
+                        let (initializer, partition_config) =
+                            partition_strategy.build(&mut code, d, noisy_measurements, partition_config);
+                        let next_syndrome_pattern = move || match code.next_syndrome_pattern() {
+                            Ok((syndrome_pattern, _)) => Some(syndrome_pattern),
+                            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => None,
+                            Err(error) => panic!("{error}"),
+                        };
+                        (initializer, partition_config, Box::new(next_syndrome_pattern))
                     }
-                    None
                 };
-                let mut rng = thread_rng();
-                for round in (starting_iteration as u64)..(total_rounds as u64) {
-                    pb.as_mut().map(|pb| pb.set(round));
-                    let seed = if use_deterministic_seed { round } else { rng.gen() };
-                    let syndrome_pattern = code.generate_random_errors(seed);
-                    if print_syndrome_pattern {
-                        println!("syndrome_pattern: {:?}", syndrome_pattern);
+                let partition_info = partition_config.info();
+                let mut solvers: Vec<_> = engines
+                    .iter()
+                    .map(|engine| engine.build(&initializer, &partition_info, primal_dual_config.clone()))
+                    .collect();
+                let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+                #[allow(clippy::unnecessary_cast)]
+                let is_virtual = {
+                    let mut is_virtual = vec![false; initializer.vertex_num as usize];
+                    for &virtual_vertex in initializer.virtual_vertices.iter() {
+                        is_virtual[virtual_vertex as usize] = true;
                     }
-                    // create a new visualizer each round
-                    let mut visualizer = None;
-                    if enable_visualizer {
-                        let new_visualizer = Visualizer::new(
-                            Some(visualize_data_folder() + static_visualize_data_filename().as_str()),
-                            code.get_positions(),
-                            true,
-                        )
-                        .unwrap();
-                        visualizer = Some(new_visualizer);
+                    is_virtual
+                };
+                let mut shots = 0;
+                let mut mismatches = 0;
+                let mut total_weights = vec![0; engines.len()];
+                let mut invalid_matchings = vec![0; engines.len()];
+                for shot in 0..total_rounds.unwrap_or(usize::MAX) {
+                    let Some(syndrome_pattern) = next_syndrome_pattern() else {
+                        break;
+                    };
+                    shots += 1;
+                    complete_graph.load_erasures(&syndrome_pattern.erasures);
+                    complete_graph.load_dynamic_weights(&syndrome_pattern.dynamic_weights);
+                    let mut results = vec![];
+                    for (engine_index, solver) in solvers.iter_mut().enumerate() {
+                        solver.solve(&syndrome_pattern);
+                        let mwpm_result = solver
+                            .perfect_matching()
+                            .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+                        solver.clear();
+                        let mut matching = matched_pairs(&syndrome_pattern.defect_vertices, &mwpm_result);
+                        if engine_index == 1 && corrupt_shot == Some(shot) {
+                            matching.pop();
+                        }
+                        let is_perfect = is_perfect_matching(&syndrome_pattern.defect_vertices, &is_virtual, &matching);
+                        let weight: Weight = matching.iter().map(|&(a, b)| complete_graph.get_path(a, b).1).sum();
+                        total_weights[engine_index] += weight;
+                        if !is_perfect {
+                            invalid_matchings[engine_index] += 1;
+                        }
+                        results.push(json!({
+                            "engine": engines[engine_index],
+                            "weight": weight,
+                            "is_perfect": is_perfect,
+                            "matching": matching,
+                        }));
                     }
-                    benchmark_profiler.begin(&syndrome_pattern);
-                    primal_dual_solver.solve_visualizer(&syndrome_pattern, visualizer.as_mut());
-                    benchmark_profiler.event("decoded".to_string());
-                    result_verifier.verify(&mut primal_dual_solver, &syndrome_pattern, visualizer.as_mut());
-                    benchmark_profiler.event("verified".to_string());
-                    primal_dual_solver.clear(); // also count the clear operation
-                    benchmark_profiler.end(Some(&*primal_dual_solver));
-                    if let Some(pb) = pb.as_mut() {
-                        if pb_message.is_empty() {
-                            pb.message(format!("{} ", benchmark_profiler.brief()).as_str());
+                    complete_graph.reset();
+                    if results
+                        .iter()
+                        .any(|result| result["weight"] != results[0]["weight"] || result["is_perfect"] != true)
+                    {
+                        mismatches += 1;
+                        let folder = format!("{dump_folder}/shot_{shot}");
+                        std::fs::create_dir_all(&folder).unwrap();
+                        let dump = |name: String, value: serde_json::Value| {
+                            std::fs::write(format!("{folder}/{name}.json"), serde_json::to_string(&value).unwrap()).unwrap();
+                        };
+                        dump("initializer".to_string(), json!(initializer));
+                        dump("syndrome_pattern".to_string(), json!(syndrome_pattern));
+                        for (engine_index, result) in results.into_iter().enumerate() {
+                            let engine_name = engines[engine_index].to_possible_value().unwrap().get_name().to_string();
+                            dump(format!("matching_{engine_index}_{engine_name}"), result);
                         }
+                        eprintln!("[mismatch] shot {shot}, reproducer written to {folder}");
                     }
                 }
-                if disable_progress_bar {
-                    // always print out brief
-                    println!("{}", benchmark_profiler.brief());
-                } else {
-                    if let Some(pb) = pb.as_mut() {
-                        pb.finish()
+                println!("{:<12}{:>16}{:>20}", "engine", "total weight", "invalid matchings");
+                for (engine_index, engine) in engines.iter().enumerate() {
+                    let engine_name = engine.to_possible_value().unwrap().get_name().to_string();
+                    println!(
+                        "{:<12}{:>16}{:>20}",
+                        engine_name, total_weights[engine_index], invalid_matchings[engine_index]
+                    );
+                }
+                println!("shots: {shots}, mismatches: {mismatches}");
+                if mismatches > 0 {
+                    std::process::exit(1);
+                }
+            }
+            Commands::Serve => serve(std::io::stdin().lock(), std::io::stdout().lock()).unwrap(),
+            Commands::ReplayDump(ReplayDumpParameters {
+                filename,
+                enable_visualizer,
+            }) => {
+                let dump = SolverDebugDump::load(&filename).unwrap();
+                println!(
+                    "replaying a dump of fusion blossom {} with the {} solver",
+                    dump.version, dump.solver
+                );
+                let mut solver = dump.build_solver().unwrap();
+                let mut visualizer = None;
+                if enable_visualizer {
+                    let width = (dump.initializer.vertex_num as f64).sqrt().ceil() as VertexNum;
+                    let positions = (0..dump.initializer.vertex_num)
+                        .map(|vertex_index| {
+                            VisualizePosition::new((vertex_index / width) as f64, (vertex_index % width) as f64, 0.)
+                        })
+                        .collect();
+                    let new_visualizer = Visualizer::new(
+                        Some(visualize_data_folder() + static_visualize_data_filename().as_str()),
+                        positions,
+                        true,
+                    )
+                    .unwrap();
+                    print_visualize_link(static_visualize_data_filename());
+                    visualizer = Some(new_visualizer);
+                }
+                solver.solve_visualizer(&dump.syndrome_pattern, visualizer.as_mut());
+                let subgraph = solver.subgraph_visualizer(visualizer.as_mut());
+                println!("subgraph: {subgraph:?}");
+                println!("weight: {}", solver.sum_dual_variables());
+            }
+            Commands::ReplayVisualization(ReplayVisualizationParameters { filename }) => {
+                let file = VisualizeFile::load(&filename).unwrap();
+                let initializer = file.initializer().unwrap_or_else(|| {
+                    panic!("{filename} has no initializer, record it with `Visualizer::set_initializer`")
+                });
+                let syndrome_pattern = file.recorded_syndrome_pattern().unwrap();
+                let mut solver: Box<dyn PrimalDualSolver> = match file.partition_config() {
+                    Some(partition_config) => {
+                        Box::new(SolverParallel::new(initializer, &partition_config.info(), json!({})))
                     }
-                    println!();
+                    None => Box::new(SolverSerial::new(initializer)),
+                };
+                solver.solve(&syndrome_pattern);
+                let weight = solver.sum_dual_variables();
+                println!("defects: {}, weight: {weight}", syndrome_pattern.defect_vertices.len());
+                let recorded_weight = file.recorded_sum_dual_variables().expect("no snapshot records the weight");
+                assert_eq!(weight, recorded_weight, "the weight differs from the recorded one");
+            }
+            Commands::DiffInitializers(DiffInitializersParameters { first, second }) => {
+                let load = |filename: &str| -> SolverInitializer {
+                    let content =
+                        std::fs::read_to_string(filename).unwrap_or_else(|error| panic!("cannot read {filename}: {error}"));
+                    serde_json::from_str(&content).unwrap_or_else(|error| panic!("invalid initializer {filename}: {error}"))
+                };
+                let diff = load(&first).diff(&load(&second));
+                print!("{diff}");
+                if !diff.is_empty() {
+                    std::process::exit(1);
                 }
             }
             Commands::Test { command } => {
@@ -715,6 +2190,18 @@ pub fn execute_in_cli<'a>(iter: impl Iterator<Item = &'a String> + Clone, print_
 }
 
 impl ExampleCodeType {
+    /// give the `seeding` to the workers of the parallel code types, unless it's already in the code config
+    fn with_seeding(&self, mut code_config: serde_json::Value, seeding: Seeding) -> serde_json::Value {
+        if matches!(
+            self,
+            Self::PhenomenologicalPlanarCodeParallel | Self::CircuitLevelPlanarCodeParallel
+        ) {
+            let config = code_config.as_object_mut().expect("config must be JSON object");
+            config.entry("seeding").or_insert(json!(seeding));
+        }
+        code_config
+    }
+
     fn build(
         &self,
         d: VertexNum,
@@ -738,31 +2225,62 @@ impl ExampleCodeType {
             }
             Self::PhenomenologicalPlanarCodeParallel => {
                 let mut code_count = 1;
+                let mut prefetch_depth = 0;
                 let config = code_config.as_object_mut().expect("config must be JSON object");
                 if let Some(value) = config.remove("code_count") {
                     code_count = value.as_u64().expect("code_count number") as usize;
                 }
-                Box::new(ExampleCodeParallel::new(
+                if let Some(value) = config.remove("prefetch_depth") {
+                    prefetch_depth = value.as_u64().expect("prefetch_depth number") as usize;
+                }
+                let mut code = ExampleCodeParallel::new_with_prefetch(
                     PhenomenologicalPlanarCode::new(d, noisy_measurements, p, max_half_weight),
                     code_count,
-                ))
+                    prefetch_depth,
+                );
+                if let Some(value) = config.remove("seeding") {
+                    code.seeding = serde_json::from_value(value).expect("seeding \"v1\" or \"v2\"");
+                }
+                Box::new(code)
             }
             Self::CircuitLevelPlanarCode => {
-                assert_eq!(code_config, json!({}), "config not supported");
-                Box::new(CircuitLevelPlanarCode::new(d, noisy_measurements, p, max_half_weight))
+                let mut code = CircuitLevelPlanarCode::new(d, noisy_measurements, p, max_half_weight);
+                let config = code_config.as_object_mut().expect("config must be JSON object");
+                if let Some(value) = config.remove("vertex_ordering") {
+                    let strategy: OrderingStrategy = serde_json::from_value(value).unwrap();
+                    let permutation =
+                        cache_friendly_order(&code.get_positions(), &code.get_initializer().weighted_edges, &strategy);
+                    code.reorder_vertices(&permutation.sequential_vertices).unwrap();
+                }
+                assert!(
+                    config.is_empty(),
+                    "unknown config keys: {:?}",
+                    config.keys().collect::<Vec<&String>>()
+                );
+                Box::new(code)
             }
             Self::CircuitLevelPlanarCodeParallel => {
                 let mut code_count = 1;
+                let mut prefetch_depth = 0;
                 let config = code_config.as_object_mut().expect("config must be JSON object");
                 if let Some(value) = config.remove("code_count") {
                     code_count = value.as_u64().expect("code_count number") as usize;
                 }
-                Box::new(ExampleCodeParallel::new(
+                if let Some(value) = config.remove("prefetch_depth") {
+                    prefetch_depth = value.as_u64().expect("prefetch_depth number") as usize;
+                }
+                let mut code = ExampleCodeParallel::new_with_prefetch(
                     CircuitLevelPlanarCode::new(d, noisy_measurements, p, max_half_weight),
                     code_count,
-                ))
+                    prefetch_depth,
+                );
+                if let Some(value) = config.remove("seeding") {
+                    code.seeding = serde_json::from_value(value).expect("seeding \"v1\" or \"v2\"");
+                }
+                Box::new(code)
             }
             Self::ErrorPatternReader => Box::new(ErrorPatternReader::new(code_config)),
+            Self::ShardedErrorPatternReader => Box::new(ShardedErrorPatternReader::from_config(code_config)),
             Self::CodeCapacityRotatedCode => {
                 assert_eq!(code_config, json!({}), "config not supported");
                 Box::new(CodeCapacityRotatedCode::new(d, p, max_half_weight))
@@ -807,6 +2325,7 @@ impl PartitionStrategy {
                 let mut partition_num = 10;
                 let mut enable_tree_fusion = false;
                 let mut maximum_tree_leaf_size = usize::MAX;
+                let mut fusion_tree_shape: Option<FusionTreeShape> = None;
                 if let Some(value) = config.remove("partition_num") {
                     partition_num = value.as_u64().expect("partition_num: usize") as usize;
                 }
@@ -816,23 +2335,37 @@ impl PartitionStrategy {
                 if let Some(value) = config.remove("maximum_tree_leaf_size") {
                     maximum_tree_leaf_size = value.as_u64().expect("maximum_tree_leaf_size: usize") as usize;
                 }
+                if let Some(value) = config.remove("fusion_tree_shape") {
+                    fusion_tree_shape = Some(serde_json::from_value(value).expect("fusion_tree_shape: FusionTreeShape"));
+                }
                 if !config.is_empty() {
                     panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
                 }
-                PhenomenologicalPlanarCodeTimePartition::new_tree(
-                    d,
-                    noisy_measurements,
-                    partition_num,
-                    enable_tree_fusion,
-                    maximum_tree_leaf_size,
-                )
-                .build_apply(code)
+                if let Some(fusion_tree_shape) = fusion_tree_shape {
+                    PhenomenologicalPlanarCodeTimePartition::new_shape(
+                        d,
+                        noisy_measurements,
+                        partition_num,
+                        fusion_tree_shape,
+                    )
+                    .build_apply(code)
+                } else {
+                    PhenomenologicalPlanarCodeTimePartition::new_tree(
+                        d,
+                        noisy_measurements,
+                        partition_num,
+                        enable_tree_fusion,
+                        maximum_tree_leaf_size,
+                    )
+                    .build_apply(code)
+                }
             }
             Self::PhenomenologicalRotatedCodeTimePartition => {
                 let config = partition_config.as_object_mut().expect("config must be JSON object");
                 let mut partition_num = 10;
                 let mut enable_tree_fusion = false;
                 let mut maximum_tree_leaf_size = usize::MAX;
+                let mut fusion_tree_shape: Option<FusionTreeShape> = None;
                 if let Some(value) = config.remove("partition_num") {
                     partition_num = value.as_u64().expect("partition_num: usize") as usize;
                 }
@@ -842,17 +2375,30 @@ impl PartitionStrategy {
                 if let Some(value) = config.remove("maximum_tree_leaf_size") {
                     maximum_tree_leaf_size = value.as_u64().expect("maximum_tree_leaf_size: usize") as usize;
                 }
+                if let Some(value) = config.remove("fusion_tree_shape") {
+                    fusion_tree_shape = Some(serde_json::from_value(value).expect("fusion_tree_shape: FusionTreeShape"));
+                }
                 if !config.is_empty() {
                     panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
                 }
-                PhenomenologicalRotatedCodeTimePartition::new_tree(
-                    d,
-                    noisy_measurements,
-                    partition_num,
-                    enable_tree_fusion,
-                    maximum_tree_leaf_size,
-                )
-                .build_apply(code)
+                if let Some(fusion_tree_shape) = fusion_tree_shape {
+                    PhenomenologicalRotatedCodeTimePartition::new_shape(
+                        d,
+                        noisy_measurements,
+                        partition_num,
+                        fusion_tree_shape,
+                    )
+                    .build_apply(code)
+                } else {
+                    PhenomenologicalRotatedCodeTimePartition::new_tree(
+                        d,
+                        noisy_measurements,
+                        partition_num,
+                        enable_tree_fusion,
+                        maximum_tree_leaf_size,
+                    )
+                    .build_apply(code)
+                }
             }
         };
         (code.get_initializer(), partition_config)
@@ -860,6 +2406,15 @@ impl PartitionStrategy {
 }
 
 impl PrimalDualType {
+    fn solver_type(&self) -> Option<SolverType> {
+        match self {
+            Self::Serial => Some(SolverType::Serial),
+            Self::DualParallel => Some(SolverType::DualParallel),
+            Self::Parallel => Some(SolverType::Parallel),
+            Self::ErrorPatternLogger | Self::BlossomV => None,
+        }
+    }
+
     fn build(
         &self,
         initializer: &SolverInitializer,
@@ -889,6 +2444,209 @@ impl PrimalDualType {
     }
 }
 
+impl CompareEngine {
+    fn build(
+        &self,
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        primal_dual_config: serde_json::Value,
+    ) -> Box<dyn PrimalDualSolver> {
+        match self {
+            Self::Serial => Box::new(SolverSerial::new(initializer)),
+            Self::Parallel => Box::new(SolverParallel::new(initializer, partition_info, primal_dual_config)),
+            Self::BlossomV => {
+                if cfg!(not(feature = "blossom_v")) {
+                    panic!("need blossom V library, see README.md")
+                }
+                Box::new(SolverBlossomV::new(initializer))
+            }
+        }
+    }
+}
+
+/// the matched pairs from the legacy matching result, each appears only once
+fn matched_pairs(defect_vertices: &[VertexIndex], mwpm_result: &[VertexIndex]) -> Vec<(VertexIndex, VertexIndex)> {
+    let is_defect: BTreeSet<VertexIndex> = defect_vertices.iter().cloned().collect();
+    defect_vertices
+        .iter()
+        .zip(mwpm_result.iter())
+        .filter(|(a, b)| !is_defect.contains(b) || a < b)
+        .map(|(&a, &b)| (a, b))
+        .collect()
+}
+
+/// every defect vertex is matched exactly once, either to another defect vertex or to a virtual vertex
+#[allow(clippy::unnecessary_cast)]
+fn is_perfect_matching(
+    defect_vertices: &[VertexIndex],
+    is_virtual: &[bool],
+    matching: &[(VertexIndex, VertexIndex)],
+) -> bool {
+    let mut unmatched: BTreeSet<VertexIndex> = defect_vertices.iter().cloned().collect();
+    for &(a, b) in matching.iter() {
+        for vertex in [a, b] {
+            if !unmatched.remove(&vertex) && !is_virtual[vertex as usize] {
+                return false;
+            }
+        }
+    }
+    unmatched.is_empty()
+}
+
+/// the first message of [`serve`]: the decoding graph, given either inline or as a file, and the solver options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServeInitialization {
+    /// the decoding graph
+    #[serde(default)]
+    pub initializer: Option<SolverInitializer>,
+    /// a GraphML file if it ends with `.graphml`, otherwise a syndrome pattern file whose header has the decoding graph
+    #[serde(default)]
+    pub path: Option<String>,
+    /// one of `serial`, `dual-parallel` and `parallel`, the latter two run without partition
+    #[serde(default = "ServeInitialization::default_primal_dual_type")]
+    pub primal_dual_type: String,
+    /// the configuration of the parallel solvers
+    #[serde(default = "ServeInitialization::default_primal_dual_config")]
+    pub primal_dual_config: serde_json::Value,
+}
+
+impl ServeInitialization {
+    fn default_primal_dual_type() -> String {
+        "serial".to_string()
+    }
+
+    fn default_primal_dual_config() -> serde_json::Value {
+        json!({})
+    }
+
+    fn load_initializer(&self) -> Result<SolverInitializer, String> {
+        let initializer = match (&self.initializer, &self.path) {
+            (Some(initializer), None) => initializer.clone(),
+            (None, Some(path)) if path.ends_with(".graphml") => {
+                let content = std::fs::read_to_string(path).map_err(|error| format!("cannot read {path}: {error}"))?;
+                let (initializer, _) =
+                    SolverInitializer::from_graphml(&content).map_err(|error| format!("invalid GraphML {path}: {error}"))?;
+                initializer
+            }
+            (None, Some(path)) => {
+                SyndromeFileReader::open(path)
+                    .map_err(|error| format!("cannot read {path}: {error}"))?
+                    .initializer
+            }
+            _ => return Err("exactly one of `initializer` and `path` must be given".to_string()),
+        };
+        initializer.check()?;
+        Ok(initializer)
+    }
+
+    fn build(&self, initializer: &SolverInitializer) -> Result<Box<dyn PrimalDualSolver>, String> {
+        let primal_dual_type = PrimalDualType::from_str(&self.primal_dual_type, true)?;
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        let primal_dual_config = self.primal_dual_config.clone();
+        match primal_dual_type {
+            PrimalDualType::Serial if primal_dual_config == json!({}) => Ok(Box::new(SolverSerial::new(initializer))),
+            PrimalDualType::DualParallel | PrimalDualType::Parallel => {
+                // the parallel solvers panic on an invalid configuration
+                std::panic::catch_unwind(|| -> Box<dyn PrimalDualSolver> {
+                    if primal_dual_type == PrimalDualType::Parallel {
+                        Box::new(SolverParallel::new(initializer, &partition_info, primal_dual_config))
+                    } else {
+                        Box::new(SolverDualParallel::new(initializer, &partition_info, primal_dual_config))
+                    }
+                })
+                .map_err(|_| format!("invalid primal_dual_config {}", self.primal_dual_config))
+            }
+            PrimalDualType::Serial => Err("the serial solver takes no primal_dual_config".to_string()),
+            _ => Err(format!("primal_dual_type {} is not supported", self.primal_dual_type)),
+        }
+    }
+}
+
+/// a decoding request of [`serve`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServeRequest {
+    /// any json value, copied to the response
+    pub id: serde_json::Value,
+    pub syndrome: SyndromePattern,
+}
+
+/// the weight of the subgraph under the erasures and dynamic weights of the syndrome pattern
+#[allow(clippy::unnecessary_cast)]
+fn subgraph_weight(initializer: &SolverInitializer, syndrome_pattern: &SyndromePattern, subgraph: &[EdgeIndex]) -> Weight {
+    let mut weights: Vec<Weight> = initializer.weighted_edges.iter().map(|&(_, _, weight)| weight).collect();
+    for &(edge_index, weight) in syndrome_pattern.dynamic_weights.iter() {
+        weights[edge_index as usize] = weight;
+    }
+    for &edge_index in syndrome_pattern.erasures.iter() {
+        weights[edge_index as usize] = 0;
+    }
+    subgraph.iter().map(|&edge_index| weights[edge_index as usize]).sum()
+}
+
+/// decode for another process with one json message per line. The first message is a [`ServeInitialization`], answered
+/// by `{"ready":true,"vertex_num":..,"edge_num":..}`; every following message is a [`ServeRequest`], answered by
+/// `{"id":..,"subgraph":[..],"weight":..,"time":..}` with the decoding time in seconds. A malformed message is answered by
+/// `{"id":..,"error":".."}` (the id is null if unknown) and the server keeps running, until `{"cmd":"shutdown"}` or the end
+/// of the input.
+pub fn serve(input: impl std::io::BufRead, mut output: impl Write) -> std::io::Result<()> {
+    let mut server: Option<(SolverInitializer, Box<dyn PrimalDualSolver>)> = None;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(message) => message,
+            Err(error) => {
+                writeln!(output, "{}", json!({ "id": null, "error": format!("invalid json: {error}") }))?;
+                output.flush()?;
+                continue;
+            }
+        };
+        let id = message.get("id").cloned().unwrap_or(serde_json::Value::Null);
+        let response = match (message.get("cmd"), server.as_mut()) {
+            (Some(command), _) if command == "shutdown" => break,
+            (Some(command), _) => Err(format!("unknown command {command}")),
+            (None, None) => serde_json::from_value::<ServeInitialization>(message)
+                .map_err(|error| format!("invalid initialization: {error}"))
+                .and_then(|initialization| {
+                    let initializer = initialization.load_initializer()?;
+                    let solver = initialization.build(&initializer)?;
+                    let response = json!({
+                        "ready": true,
+                        "vertex_num": initializer.vertex_num,
+                        "edge_num": initializer.weighted_edges.len(),
+                    });
+                    server = Some((initializer, solver));
+                    Ok(response)
+                }),
+            (None, Some((initializer, solver))) => serde_json::from_value::<ServeRequest>(message)
+                .map_err(|error| format!("invalid request: {error}"))
+                .and_then(|request| {
+                    initializer.check_syndrome_pattern(&request.syndrome)?;
+                    let begin = std::time::Instant::now();
+                    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                        solver.solve(&request.syndrome);
+                        solver.subgraph()
+                    }));
+                    let time = begin.elapsed().as_secs_f64();
+                    solver.clear();
+                    let subgraph = result.map_err(|_| "the solver panicked".to_string())?;
+                    Ok(json!({
+                        "id": request.id,
+                        "subgraph": subgraph,
+                        "weight": subgraph_weight(initializer, &request.syndrome, &subgraph),
+                        "time": time,
+                    }))
+                }),
+        };
+        let response = response.unwrap_or_else(|error| json!({ "id": id, "error": error }));
+        writeln!(output, "{response}")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
 impl Verifier {
     fn build(&self, initializer: &SolverInitializer) -> Box<dyn ResultVerifier> {
         match self {
@@ -1,6 +1,7 @@
 use super::dual_module::EdgeWeightModifier;
 use super::util::*;
 use crate::priority_queue::PriorityQueue;
+#[cfg(not(feature = "minimal"))]
 use crate::rayon::prelude::*;
 use std::collections::BTreeMap;
 
@@ -17,6 +18,10 @@ pub struct CompleteGraph {
     pub edge_modifier: EdgeWeightModifier,
     /// original edge weights
     pub weighted_edges: Vec<(VertexIndex, VertexIndex, Weight)>,
+    /// the number of vertices popped from the priority queue of Dijkstra's algorithm since creation
+    popped_vertices: usize,
+    /// whether Dijkstra's algorithm breaks the ties of weight by the number of edges, see [`Self::set_minimize_hops`]
+    minimize_hops: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +33,17 @@ pub struct CompleteGraphVertex {
 }
 
 impl CompleteGraph {
+    /// an analytic estimate of the memory in bytes, see [`crate::dual_module_serial::DualModuleSerial::memory_estimate`]
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += self.vertices.capacity() * size_of::<CompleteGraphVertex>();
+        for vertex in self.vertices.iter() {
+            bytes += vertex.edges.len() * size_of::<(VertexIndex, Weight)>();
+        }
+        bytes += self.weighted_edges.capacity() * size_of::<(VertexIndex, VertexIndex, Weight)>();
+        bytes
+    }
+
     /// create complete graph given skeleton graph
     #[allow(clippy::unnecessary_cast)]
     pub fn new(vertex_num: VertexNum, weighted_edges: &[(VertexIndex, VertexIndex, Weight)]) -> Self {
@@ -47,9 +63,27 @@ impl CompleteGraph {
             active_timestamp: 0,
             edge_modifier: EdgeWeightModifier::new(),
             weighted_edges: weighted_edges.to_owned(),
+            popped_vertices: 0,
+            minimize_hops: false,
         }
     }
 
+    /// the number of vertices visited by Dijkstra's algorithm since creation, which catches algorithmic regressions that
+    /// the wall clock time can hardly tell
+    pub fn popped_vertices(&self) -> usize {
+        self.popped_vertices
+    }
+
+    /// among the minimum-weight paths, find the one with the fewest edges, at the cost of visiting more vertices of the
+    /// same distance; by default the tie is broken arbitrarily
+    pub fn set_minimize_hops(&mut self, minimize_hops: bool) {
+        self.minimize_hops = minimize_hops;
+    }
+
+    pub fn minimize_hops(&self) -> bool {
+        self.minimize_hops
+    }
+
     /// reset any temporary changes like erasure edges
     #[allow(clippy::unnecessary_cast)]
     pub fn reset(&mut self) {
@@ -65,6 +99,21 @@ impl CompleteGraph {
         }
     }
 
+    /// permanently change the weights of some edges; any temporary changes must be reset beforehand
+    #[allow(clippy::unnecessary_cast)]
+    pub fn update_weights(&mut self, new_weights: &[(EdgeIndex, Weight)]) {
+        assert!(
+            !self.edge_modifier.has_modified_edges(),
+            "the temporary edge modifier must be reset before updating weights permanently"
+        );
+        for (edge_index, weight) in new_weights.iter() {
+            let (vertex_idx_1, vertex_idx_2, _) = self.weighted_edges[*edge_index as usize];
+            self.vertices[vertex_idx_1 as usize].edges.insert(vertex_idx_2, *weight);
+            self.vertices[vertex_idx_2 as usize].edges.insert(vertex_idx_1, *weight);
+            self.weighted_edges[*edge_index as usize].2 = *weight;
+        }
+    }
+
     #[allow(clippy::unnecessary_cast)]
     fn load_edge_modifier(&mut self, edge_modifier: &[(EdgeIndex, Weight)]) {
         assert!(
@@ -123,7 +172,8 @@ impl CompleteGraph {
             if pq.is_empty() {
                 break;
             }
-            let (target, PriorityElement { weight, previous }) = pq.pop().unwrap();
+            let (target, PriorityElement { weight, hops, previous }) = pq.pop().unwrap();
+            self.popped_vertices += 1;
             // eprintln!("target: {}, weight: {}, next: {}", target, weight, next);
             debug_assert!({
                 !computed_edges.contains_key(&target) // this entry shouldn't have been set
@@ -137,17 +187,19 @@ impl CompleteGraph {
                 }
             }
             // add its neighbors to priority queue
+            let edge_hops = if self.minimize_hops { hops + 1 } else { 0 };
             for (&neighbor, &neighbor_weight) in self.vertices[target as usize].edges.iter() {
                 let edge_weight = weight + neighbor_weight;
                 if let Some(PriorityElement {
                     weight: existing_weight,
+                    hops: existing_hops,
                     previous: existing_previous,
                 }) = pq.get_priority(&neighbor)
                 {
                     // update the priority if weight is smaller or weight is equal but distance is smaller
                     // this is necessary if the graph has weight-0 edges, which could lead to cycles in the graph and cause deadlock
-                    let mut update = &edge_weight < existing_weight;
-                    if &edge_weight == existing_weight {
+                    let mut update = (edge_weight, edge_hops) < (*existing_weight, *existing_hops);
+                    if (edge_weight, edge_hops) == (*existing_weight, *existing_hops) {
                         let distance = if neighbor > previous {
                             neighbor - previous
                         } else {
@@ -164,12 +216,12 @@ impl CompleteGraph {
                         }
                     }
                     if update {
-                        pq.change_priority(&neighbor, PriorityElement::new(edge_weight, target));
+                        pq.change_priority(&neighbor, PriorityElement::with_hops(edge_weight, edge_hops, target));
                     }
                 } else {
                     // insert new entry only if neighbor has not been visited
                     if self.vertices[neighbor as usize].timestamp != active_timestamp {
-                        pq.push(neighbor, PriorityElement::new(edge_weight, target));
+                        pq.push(neighbor, PriorityElement::with_hops(edge_weight, edge_hops, target));
                     }
                 }
             }
@@ -207,6 +259,7 @@ impl CompleteGraph {
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 #[derive(Clone)]
 pub struct PrebuiltCompleteGraph {
     /// number of vertices
@@ -217,6 +270,7 @@ pub struct PrebuiltCompleteGraph {
     pub virtual_boundary_weight: Vec<Option<(VertexIndex, Weight)>>,
 }
 
+#[cfg(not(feature = "minimal"))]
 impl PrebuiltCompleteGraph {
     #[allow(clippy::unnecessary_cast)]
     pub fn new_threaded(initializer: &SolverInitializer, thread_pool_size: usize) -> Self {
@@ -321,32 +375,69 @@ impl PrebuiltCompleteGraph {
 #[derive(Eq, Debug)]
 pub struct PriorityElement {
     pub weight: Weight,
+    /// the number of edges from the source, only counted with [`CompleteGraph::set_minimize_hops`] and 0 otherwise
+    pub hops: u32,
     pub previous: VertexIndex,
 }
 
 impl std::cmp::PartialEq for PriorityElement {
     #[inline]
     fn eq(&self, other: &PriorityElement) -> bool {
-        self.weight == other.weight
+        (self.weight, self.hops) == (other.weight, other.hops)
     }
 }
 
 impl std::cmp::PartialOrd for PriorityElement {
     #[inline]
     fn partial_cmp(&self, other: &PriorityElement) -> Option<std::cmp::Ordering> {
-        other.weight.partial_cmp(&self.weight) // reverse `self` and `other` to prioritize smaller weight
+        Some(self.cmp(other))
     }
 }
 
 impl std::cmp::Ord for PriorityElement {
     #[inline]
     fn cmp(&self, other: &PriorityElement) -> std::cmp::Ordering {
-        other.weight.cmp(&self.weight) // reverse `self` and `other` to prioritize smaller weight
+        (other.weight, other.hops).cmp(&(self.weight, self.hops)) // reverse `self` and `other` to prioritize smaller weight
     }
 }
 
 impl PriorityElement {
     pub fn new(weight: Weight, previous: VertexIndex) -> Self {
-        Self { weight, previous }
+        Self::with_hops(weight, 0, previous)
+    }
+
+    pub fn with_hops(weight: Weight, hops: u32, previous: VertexIndex) -> Self {
+        Self { weight, hops, previous }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_graph_popped_vertices() {
+        // cargo test complete_graph_popped_vertices -- --nocapture
+        // a chain 0 - 1 - 2 - 3 - 4 with a shortcut 0 - 4
+        let weighted_edges = vec![(0, 1, 2), (1, 2, 2), (2, 3, 2), (3, 4, 2), (0, 4, 6)];
+        let mut complete_graph = CompleteGraph::new(5, &weighted_edges);
+        assert_eq!(complete_graph.popped_vertices(), 0);
+        let edges = complete_graph.all_edges(0);
+        assert_eq!(edges[&3], (2, 6));
+        assert_eq!(edges[&4], (0, 6));
+        assert_eq!(complete_graph.popped_vertices(), 5);
+        // terminating early only visits the vertices closer than the target
+        let (path, weight) = complete_graph.get_path(0, 2);
+        assert_eq!((path, weight), (vec![(1, 2), (2, 2)], 4));
+        assert_eq!(complete_graph.popped_vertices(), 5 + 3);
+        // the erasure makes the target closer, so only vertex 1 at the same distance is visited besides the path
+        complete_graph.load_erasures(&[4]);
+        let (path, weight) = complete_graph.get_path(0, 3);
+        assert_eq!((path, weight), (vec![(4, 0), (3, 2)], 2));
+        assert_eq!(complete_graph.popped_vertices(), 5 + 3 + 4);
+        complete_graph.reset();
+        let (path, weight) = complete_graph.get_path(0, 3);
+        assert_eq!((path, weight), (vec![(1, 2), (2, 2), (3, 2)], 6));
+        assert_eq!(complete_graph.popped_vertices(), 5 + 3 + 4 + 5);
     }
 }
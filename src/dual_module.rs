@@ -453,6 +453,17 @@ impl DualNodePtr {
         pending_vec
     }
 
+    /// find all defect nodes that belong to the dual node, i.e. any defect node inside a blossom
+    pub fn get_all_defect_nodes(&self) -> Vec<DualNodePtr> {
+        let dual_node = self.read_recursive();
+        match &dual_node.class {
+            DualNodeClass::Blossom { nodes_circle, .. } => (nodes_circle.iter())
+                .flat_map(|node_weak| node_weak.upgrade_force().get_all_defect_nodes())
+                .collect(),
+            DualNodeClass::DefectVertex { .. } => vec![self.clone()],
+        }
+    }
+
     /// find a representative vertex
     pub fn get_representative_vertex(&self) -> VertexIndex {
         let dual_node = self.read_recursive();
@@ -519,6 +530,10 @@ pub trait DualModuleImpl {
     /// clear all growth and existing dual nodes, prepared for the next decoding
     fn clear(&mut self);
 
+    /// write every vertex and edge once, so that the first decoding after construction doesn't pay for cold memory;
+    /// must be called when the module is clear
+    fn warm_up(&mut self) {}
+
     /// add corresponding dual node
     fn add_dual_node(&mut self, dual_node_ptr: &DualNodePtr);
 
@@ -610,6 +625,12 @@ pub trait DualModuleImpl {
         json!({})
     }
 
+    /// the fully grown edge between two touching defect nodes, if this dual module keeps track of the edges;
+    /// only used for diagnostics, so it's allowed to be slow
+    fn find_tight_edge(&self, _touching_ptr_1: &DualNodePtr, _touching_ptr_2: &DualNodePtr) -> Option<EdgeIndex> {
+        None
+    }
+
     /*
      * the following apis are only required when this dual module can be used as a partitioned one
      */
@@ -656,6 +677,11 @@ pub trait DualModuleImpl {
     fn bias_dual_node_index(&mut self, _bias: NodeIndex) {
         panic!("the dual module implementation doesn't support this function, please use another dual module")
     }
+
+    /// count the fully grown edges incident to the vertices in the range, used to profile the interface of a fusion
+    fn count_tight_edges(&self, _vertex_range: &VertexRange) -> usize {
+        panic!("the dual module implementation doesn't support this function, please use another dual module")
+    }
 }
 
 /// this dual module is a parallel version that hosts many partitioned ones
@@ -700,6 +726,8 @@ impl FusionVisualizer for DualModuleInterfacePtr {
                         DualNodeGrowState::Stay => 0,
                     },
                     if abbrev { "p" } else { "parent_blossom" }: dual_node.parent_blossom.as_ref().map(|weak| weak.upgrade_force().read_recursive().index),
+                    // tracked by the interface, so it's available even when the dual module is split into units
+                    if abbrev { "y" } else { "dual_value" }: dual_node.get_dual_variable(&interface),
                 }));
             } else {
                 dual_nodes.push(json!(null));
@@ -716,6 +744,14 @@ impl FusionVisualizer for DualModuleInterfacePtr {
 }
 
 impl DualModuleInterface {
+    /// an analytic estimate of the memory in bytes, see [`crate::dual_module_serial::DualModuleSerial::memory_estimate`]
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += self.nodes.capacity() * size_of::<Option<DualNodePtr>>();
+        bytes += self.nodes.iter().flatten().count() * size_of::<DualNode>();
+        bytes
+    }
+
     /// return the count of all nodes including those of the children interfaces
     pub fn nodes_count(&self) -> NodeNum {
         let mut count = self.nodes_length as NodeNum;
@@ -725,6 +761,11 @@ impl DualModuleInterface {
         count
     }
 
+    /// the total length grown since the interface is loaded, i.e. the dual variable of a defect node that always grows
+    pub fn total_growth(&self) -> Weight {
+        self.dual_variable_global_progress
+    }
+
     /// get node ptr by index; if calling from the ancestor interface, node_index is absolute, otherwise it's relative
     #[allow(clippy::unnecessary_cast)]
     pub fn get_node(&self, relative_node_index: NodeIndex) -> Option<DualNodePtr> {
@@ -796,6 +837,10 @@ impl DualModuleInterfacePtr {
     }
 
     pub fn load(&self, syndrome_pattern: &SyndromePattern, dual_module_impl: &mut impl DualModuleImpl) {
+        assert!(
+            syndrome_pattern.blocked_edges.is_empty(),
+            "blocked edges must be resolved into dynamic weights first, see `SolverInitializer::resolve_blocked_edges`"
+        );
         for vertex_idx in syndrome_pattern.defect_vertices.iter() {
             self.create_defect_node(*vertex_idx, dual_module_impl);
         }
@@ -811,6 +856,18 @@ impl DualModuleInterfacePtr {
         }
     }
 
+    /// the same as [`Self::load`] but with the defect vertices and erasures given as a [`SyndromeBitmap`], whose set bits
+    /// are enumerated word by word without building the sparse lists of [`SyndromePattern`]
+    pub fn load_bitmap(&self, bitmap: &SyndromeBitmap, dual_module_impl: &mut impl DualModuleImpl) {
+        for vertex_idx in bitmap.defect_vertices() {
+            self.create_defect_node(vertex_idx, dual_module_impl);
+        }
+        let erasures: Vec<EdgeIndex> = bitmap.erasure_edges().collect();
+        if !erasures.is_empty() {
+            dual_module_impl.load_erasures(&erasures);
+        }
+    }
+
     /// a constant clear function, without dropping anything;
     /// this is for consideration of reducing the garbage collection time in the parallel solver,
     /// by distributing the clear cost into each thread but not the single main thread.
@@ -1536,6 +1593,8 @@ impl MaxUpdateLength {
 pub struct EdgeWeightModifier {
     /// edge with changed weighted caused by the erasure or X/Z correlation
     pub modified: Vec<(EdgeIndex, Weight)>,
+    /// whether each modified edge is an erasure, as opposed to a dynamic weight that may also be zero
+    pub is_erasure: Vec<bool>,
 }
 
 impl Default for EdgeWeightModifier {
@@ -1546,12 +1605,22 @@ impl Default for EdgeWeightModifier {
 
 impl EdgeWeightModifier {
     pub fn new() -> Self {
-        Self { modified: vec![] }
+        Self {
+            modified: vec![],
+            is_erasure: vec![],
+        }
     }
 
     /// record the modified edge
     pub fn push_modified_edge(&mut self, erasure_edge: EdgeIndex, original_weight: Weight) {
         self.modified.push((erasure_edge, original_weight));
+        self.is_erasure.push(false);
+    }
+
+    /// record the modified edge of an erasure
+    pub fn push_erasure_edge(&mut self, erasure_edge: EdgeIndex, original_weight: Weight) {
+        self.modified.push((erasure_edge, original_weight));
+        self.is_erasure.push(true);
     }
 
     /// if some edges are not recovered
@@ -1561,6 +1630,7 @@ impl EdgeWeightModifier {
 
     /// retrieve the last modified edge, panic if no more modified edges
     pub fn pop_modified_edge(&mut self) -> (EdgeIndex, Weight) {
+        self.is_erasure.pop();
         self.modified
             .pop()
             .expect("no more modified edges, please check `has_modified_edges` before calling this method")
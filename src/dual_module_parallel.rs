@@ -126,20 +126,44 @@ impl<SerialModule: DualModuleImpl + Send + Sync> std::fmt::Debug for DualModuleP
     }
 }
 
+impl DualModuleParallel<DualModuleSerial> {
+    /// an analytic estimate of the memory in bytes, summing up the serial modules of all units
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>() + self.units.capacity() * size_of::<DualModuleParallelUnitPtr<DualModuleSerial>>();
+        for unit_ptr in self.units.iter() {
+            let unit = unit_ptr.read_recursive();
+            bytes += size_of::<DualModuleParallelUnit<DualModuleSerial>>();
+            bytes += unit.serial_module.memory_estimate();
+        }
+        bytes
+    }
+}
+
 impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule> {
     /// recommended way to create a new instance, given a customized configuration
-    #[allow(clippy::unnecessary_cast)]
     pub fn new_config(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
         config: DualModuleParallelConfig,
     ) -> Self {
-        let partition_info = Arc::new(partition_info.clone());
         let mut thread_pool_builder = rayon::ThreadPoolBuilder::new();
         if config.thread_pool_size != 0 {
             thread_pool_builder = thread_pool_builder.num_threads(config.thread_pool_size);
         }
         let thread_pool = thread_pool_builder.build().expect("creating thread pool failed");
+        Self::new_config_with_thread_pool(initializer, partition_info, config, Arc::new(thread_pool))
+    }
+
+    /// create a new instance that runs all the parallel tasks in a caller-provided thread pool;
+    /// `thread_pool_size` in the config is ignored
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_config_with_thread_pool(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        config: DualModuleParallelConfig,
+        thread_pool: Arc<rayon::ThreadPool>,
+    ) -> Self {
+        let partition_info = Arc::new(partition_info.clone());
         let mut units = vec![];
         let unit_count = partition_info.units.len();
         let complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges); // build the graph to construct the NN data structure
@@ -395,7 +419,7 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallel<SerialModule
             units,
             config,
             partition_info,
-            thread_pool: Arc::new(thread_pool),
+            thread_pool,
             empty_sync_request: vec![],
         }
     }
@@ -480,6 +504,15 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
         })
     }
 
+    fn warm_up(&mut self) {
+        self.thread_pool.scope(|_| {
+            self.units.par_iter().for_each(|unit_ptr| {
+                lock_write!(unit, unit_ptr);
+                unit.warm_up();
+            });
+        })
+    }
+
     // although not the intended way to use it, we do support these common APIs for compatibility with normal primal modules
 
     fn add_dual_node(&mut self, dual_node_ptr: &DualNodePtr) {
@@ -572,6 +605,18 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
         })
     }
 
+    fn load_erasures(&mut self, erasures: &[EdgeIndex]) {
+        self.thread_pool.scope(|_| {
+            self.units.par_iter().for_each(|unit_ptr| {
+                lock_write!(unit, unit_ptr);
+                if !unit.is_active {
+                    return;
+                }
+                unit.load_erasures(erasures);
+            });
+        })
+    }
+
     fn prepare_nodes_shrink(&mut self, nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
         let unit_ptr = self.find_active_ancestor(&nodes_circle[0]);
         self.thread_pool.scope(|_| {
@@ -604,9 +649,17 @@ impl<SerialModule: DualModuleImpl + FusionVisualizer + Send + Sync> FusionVisual
             if !unit.is_active {
                 continue;
             } // do not visualize inactive units
-            let value_2 = unit.snapshot(abbrev);
+            let mut value_2 = unit.snapshot(abbrev);
+            value_2.as_object_mut().unwrap().remove("fusions"); // only a subset of the fusions
             snapshot_combine_values(&mut value, value_2, abbrev);
         }
+        let fusions: Vec<usize> = self
+            .units
+            .iter()
+            .filter(|unit_ptr| unit_ptr.read_recursive().is_fused())
+            .map(|unit_ptr| unit_ptr.read_recursive().unit_index)
+            .collect();
+        value.as_object_mut().unwrap().insert("fusions".to_string(), json!(fusions));
         value
     }
 }
@@ -616,23 +669,50 @@ impl<SerialModule: DualModuleImpl + FusionVisualizer + Send + Sync> FusionVisual
 {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
         let mut value = self.serial_module.snapshot(abbrev);
+        let mut fusions = vec![];
         if let Some((left_child_weak, right_child_weak)) = self.children.as_ref() {
-            snapshot_combine_values(
-                &mut value,
-                left_child_weak.upgrade_force().read_recursive().snapshot(abbrev),
-                abbrev,
-            );
-            snapshot_combine_values(
-                &mut value,
-                right_child_weak.upgrade_force().read_recursive().snapshot(abbrev),
-                abbrev,
-            );
+            for child_weak in [left_child_weak, right_child_weak] {
+                let mut child_value = child_weak.upgrade_force().read_recursive().snapshot(abbrev);
+                if let Some(child_fusions) = child_value.as_object_mut().unwrap().remove("fusions") {
+                    fusions.extend(
+                        child_fusions
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|unit_index| unit_index.as_u64().unwrap()),
+                    );
+                }
+                snapshot_combine_values(&mut value, child_value, abbrev);
+            }
+        }
+        if self.is_fused() {
+            fusions.push(self.unit_index as u64);
+        }
+        // color the vertices by their owning unit, where the vertices owned by a fusion unit are the interface
+        let leaf_num = self.partition_info.config.partitions.len();
+        if let Some(vertices) = value.get_mut("vertices").and_then(|vertices| vertices.as_array_mut()) {
+            for (vertex_index, vertex) in vertices.iter_mut().enumerate() {
+                if let Some(vertex) = vertex.as_object_mut() {
+                    let owning_unit = self.partition_info.vertex_to_owning_unit[vertex_index];
+                    vertex.insert((if abbrev { "ui" } else { "unit_index" }).to_string(), json!(owning_unit));
+                    vertex.insert(
+                        (if abbrev { "ii" } else { "is_interface" }).to_string(),
+                        json!(i32::from(owning_unit >= leaf_num)),
+                    );
+                }
+            }
         }
+        value.as_object_mut().unwrap().insert("fusions".to_string(), json!(fusions));
         value
     }
 }
 
 impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleParallelUnit<SerialModule> {
+    /// whether this unit has fused its children
+    pub fn is_fused(&self) -> bool {
+        self.children.is_some() && self.partition_unit.read_recursive().enabled
+    }
+
     /// statically fuse the children of this unit
     pub fn static_fuse(&mut self) {
         debug_assert!(!self.is_active, "cannot fuse the child an already active unit");
@@ -1119,6 +1199,10 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
         self.serial_module.clear()
     }
 
+    fn warm_up(&mut self) {
+        self.serial_module.warm_up()
+    }
+
     /// add a new dual node from dual module root
     fn add_dual_node(&mut self, dual_node_ptr: &DualNodePtr) {
         self.has_active_node = true;
@@ -1254,6 +1338,10 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
         self.serial_module.load_edge_modifier(edge_modifier)
     }
 
+    fn load_erasures(&mut self, erasures: &[EdgeIndex]) {
+        self.serial_module.load_erasures(erasures)
+    }
+
     fn prepare_nodes_shrink(&mut self, nodes_circle: &[DualNodePtr]) -> &mut Vec<SyncRequest> {
         let nodes_circle_vertices: Vec<_> = nodes_circle.iter().map(|ptr| ptr.get_representative_vertex()).collect();
         let mut sync_requests = vec![];
@@ -1319,6 +1407,27 @@ impl<SerialModule: DualModuleImpl + Send + Sync> DualModuleImpl for DualModulePa
             }
         }
     }
+
+    fn count_tight_edges(&self, vertex_range: &VertexRange) -> usize {
+        if !vertex_range
+            .iter()
+            .any(|vertex_index| self.is_vertex_in_descendant(vertex_index))
+        {
+            return 0;
+        }
+        let mut count = self.serial_module.count_tight_edges(vertex_range);
+        if let Some((left_child_weak, right_child_weak)) = self.children.as_ref() {
+            count += left_child_weak
+                .upgrade_force()
+                .read_recursive()
+                .count_tight_edges(vertex_range);
+            count += right_child_weak
+                .upgrade_force()
+                .read_recursive()
+                .count_tight_edges(vertex_range);
+        }
+        count
+    }
 }
 
 /// interface consists of several vertices; each vertex exists as a virtual vertex in several different serial dual modules.
@@ -1362,7 +1471,7 @@ pub mod tests {
     {
         println!("{defect_vertices:?}");
         if let Some(reordered_vertices) = &reordered_vertices {
-            code.reorder_vertices(reordered_vertices);
+            code.reorder_vertices(reordered_vertices).unwrap();
             defect_vertices = translated_defect_to_reordered(reordered_vertices, &defect_vertices);
         }
         let mut visualizer = match visualize_filename.as_ref() {
@@ -1391,7 +1500,7 @@ pub mod tests {
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         primal_module.write().debug_resolve_only_one = true; // to enable debug mode
                                                              // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, visualizer.as_mut());
         let perfect_matching = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -1920,4 +2029,52 @@ pub mod tests {
         });
         println!("results: {results:?}");
     }
+
+    /// the vertices in the snapshot are colored by the partition
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn dual_module_parallel_snapshot_partition_1() {
+        // cargo test dual_module_parallel_snapshot_partition_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let visualize_filename = "dual_module_parallel_snapshot_partition_1.json".to_string();
+        let visualize_path = visualize_data_folder() + visualize_filename.as_str();
+        let (d, noisy_measurements, partition_num) = (5, 11, 4);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let mut partition =
+            PhenomenologicalPlanarCodeTimePartition::new_tree(d, noisy_measurements, partition_num, true, usize::MAX);
+        let partition_config = partition.build_apply(&mut code);
+        let partition_info = partition_config.info();
+        let mut solver = SolverParallel::new(&code.get_initializer(), &partition_info, json!({}));
+        let syndrome_pattern = code.generate_random_errors(1);
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+        print_visualize_link(visualize_filename.clone());
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        drop(visualizer);
+        let file = VisualizeFile::load(&visualize_path).unwrap();
+        let snapshot = &file.snapshots.last().unwrap().1;
+        for (vertex_index, vertex) in snapshot["vertices"].as_array().unwrap().iter().enumerate() {
+            let unit_index = vertex["ui"].as_u64().unwrap() as usize;
+            let leaf_index = partition_config
+                .partitions
+                .iter()
+                .position(|range| range.contains(vertex_index as VertexIndex));
+            match leaf_index {
+                Some(leaf_index) => {
+                    assert_eq!(unit_index, leaf_index);
+                    assert_eq!(vertex["ii"], json!(0));
+                }
+                None => {
+                    assert!(unit_index >= partition_num);
+                    assert!(partition_info.units[unit_index]
+                        .owning_range
+                        .contains(vertex_index as VertexIndex));
+                    assert_eq!(vertex["ii"], json!(1));
+                }
+            }
+        }
+        let mut fusions: Vec<usize> = serde_json::from_value(snapshot["fusions"].clone()).unwrap();
+        fusions.sort_unstable();
+        assert_eq!(fusions, (partition_num..partition_info.units.len()).collect::<Vec<_>>());
+    }
 }
@@ -90,8 +90,16 @@ pub struct DualNodeInternal {
 }
 
 // when using feature `dangerous_pointer`, it doesn't provide the `upgrade()` function, so we have to fall back to the safe solution
-pub type DualNodeInternalPtr = ArcManualSafeLock<DualNodeInternal>;
-pub type DualNodeInternalWeak = WeakManualSafeLock<DualNodeInternal>;
+cfg_if::cfg_if! {
+    if #[cfg(feature="slab_nodes")] {
+        pub type DualNodeInternalPtr = SlabArc<DualNodeInternal>;
+        pub type DualNodeInternalWeak = SlabWeak<DualNodeInternal>;
+        slab_allocated!(DualNodeInternal);
+    } else {
+        pub type DualNodeInternalPtr = ArcSerialLock<DualNodeInternal>;
+        pub type DualNodeInternalWeak = WeakSerialLock<DualNodeInternal>;
+    }
+}
 
 impl std::fmt::Debug for DualNodeInternalPtr {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -128,8 +136,8 @@ pub struct Vertex {
     pub timestamp: FastClearTimestamp,
 }
 
-pub type VertexPtr = FastClearArcManualSafeLockDangerous<Vertex>;
-pub type VertexWeak = FastClearWeakManualSafeLockDangerous<Vertex>;
+pub type VertexPtr = FastClearArcSerialLock<Vertex>;
+pub type VertexWeak = FastClearWeakSerialLock<Vertex>;
 
 impl std::fmt::Debug for VertexPtr {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -177,8 +185,8 @@ pub struct Edge {
     pub dedup_timestamp: (FastClearTimestamp, FastClearTimestamp),
 }
 
-pub type EdgePtr = FastClearArcManualSafeLockDangerous<Edge>;
-pub type EdgeWeak = FastClearWeakManualSafeLockDangerous<Edge>;
+pub type EdgePtr = FastClearArcSerialLock<Edge>;
+pub type EdgeWeak = FastClearWeakSerialLock<Edge>;
 
 impl std::fmt::Debug for EdgePtr {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -320,6 +328,11 @@ impl DualModuleImpl for DualModuleSerial {
         self.active_list.clear();
     }
 
+    fn warm_up(&mut self) {
+        self.hard_clear_graph();
+        self.hard_clear_edge_dedup();
+    }
+
     /// add a new dual node from dual module root
     #[allow(clippy::unnecessary_cast)]
     fn add_dual_node(&mut self, dual_node_ptr: &DualNodePtr) {
@@ -872,21 +885,40 @@ impl DualModuleImpl for DualModuleSerial {
         }
     }
 
-    #[allow(clippy::unnecessary_cast)]
     fn load_edge_modifier(&mut self, edge_modifier: &[(EdgeIndex, Weight)]) {
-        debug_assert!(
-            !self.edge_modifier.has_modified_edges(),
-            "the current erasure modifier is not clean, probably forget to clean the state?"
-        );
+        self.modify_edges(edge_modifier, false);
+    }
+
+    fn load_erasures(&mut self, erasures: &[EdgeIndex]) {
+        let edge_modifier: Vec<_> = erasures.iter().map(|edge_index| (*edge_index, 0)).collect();
+        self.modify_edges(&edge_modifier, true);
+    }
+
+    fn find_tight_edge(&self, touching_ptr_1: &DualNodePtr, touching_ptr_2: &DualNodePtr) -> Option<EdgeIndex> {
         let active_timestamp = self.active_timestamp;
-        for (edge_index, target_weight) in edge_modifier.iter() {
-            let edge_ptr = &self.edges[*edge_index as usize];
-            edge_ptr.dynamic_clear(active_timestamp); // may visit stale edges
-            let mut edge = edge_ptr.write(active_timestamp);
-            let original_weight = edge.weight;
-            edge.weight = *target_weight;
-            self.edge_modifier.push_modified_edge(*edge_index, original_weight);
+        let grandson_origin = |grandson: &Option<DualNodeInternalWeak>| {
+            grandson
+                .as_ref()
+                .map(|weak| weak.upgrade_force().read_recursive().origin.upgrade_force())
+        };
+        for edge_ptr in self.edges.iter() {
+            // an edge not yet cleared in this shot has no growth
+            let edge = edge_ptr.read_recursive_force();
+            if edge.timestamp != active_timestamp || edge.left_growth + edge.right_growth < edge.weight {
+                continue;
+            }
+            let (Some(left), Some(right)) = (
+                grandson_origin(&edge.left_grandson_dual_node),
+                grandson_origin(&edge.right_grandson_dual_node),
+            ) else {
+                continue;
+            };
+            if (&left == touching_ptr_1 && &right == touching_ptr_2) || (&left == touching_ptr_2 && &right == touching_ptr_1)
+            {
+                return Some(edge.edge_index);
+            }
         }
+        None
     }
 
     fn prepare_all(&mut self) -> &mut Vec<SyncRequest> {
@@ -1098,6 +1130,40 @@ impl DualModuleImpl for DualModuleSerial {
         self.unit_module_info.as_mut().unwrap().owning_dual_range.bias_by(bias);
     }
 
+    /// an edge between two vertices in the range is counted once; zero-weight edges are always tight and thus ignored
+    fn count_tight_edges(&self, vertex_range: &VertexRange) -> usize {
+        let active_timestamp = self.active_timestamp;
+        let mut count = 0;
+        for vertex_index in vertex_range.iter() {
+            let Some(local_index) = self.get_vertex_index(vertex_index) else {
+                continue;
+            };
+            // the edges and the vertex indices are immutable, and an edge not yet cleared in this shot has no growth
+            let vertex_ptr = &self.vertices[local_index];
+            let vertex = vertex_ptr.read_recursive_force();
+            for edge_weak in vertex.edges.iter() {
+                let edge_ptr = edge_weak.upgrade_force();
+                let edge = edge_ptr.read_recursive_force();
+                if edge.timestamp != active_timestamp
+                    || edge.weight == 0
+                    || edge.left_growth + edge.right_growth < edge.weight
+                {
+                    continue;
+                }
+                let peer_weak = if vertex_ptr.downgrade() == edge.left {
+                    &edge.right
+                } else {
+                    &edge.left
+                };
+                let peer_index = peer_weak.upgrade_force().read_recursive_force().vertex_index;
+                if !vertex_range.contains(peer_index) || peer_index > vertex_index {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
     fn execute_sync_event(&mut self, sync_event: &SyncRequest) {
         let active_timestamp = self.active_timestamp;
         debug_assert!(self.contains_vertex(sync_event.vertex_index));
@@ -1294,6 +1360,66 @@ impl Vertex {
 }
 
 impl DualModuleSerial {
+    /// an analytic estimate of the memory in bytes, summing the capacities of the internal vectors and the objects behind pointers
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += self.vertices.capacity() * size_of::<VertexPtr>();
+        for vertex_ptr in self.vertices.iter() {
+            let vertex = vertex_ptr.read_recursive_force();
+            bytes += size_of::<Vertex>() + vertex.edges.capacity() * size_of::<EdgeWeak>();
+        }
+        bytes += self.edges.capacity() * size_of::<EdgePtr>() + self.edges.len() * size_of::<Edge>();
+        bytes += self.nodes.capacity() * size_of::<Option<DualNodeInternalPtr>>();
+        for node_ptr in self.nodes.iter().flatten() {
+            let node = node_ptr.read_recursive();
+            bytes += size_of::<DualNodeInternal>()
+                + node.boundary.capacity() * size_of::<(bool, EdgeWeak)>()
+                + node.overgrown_stack.capacity() * size_of::<(VertexWeak, Weight)>();
+        }
+        bytes += self.active_list.capacity() * size_of::<DualNodeInternalWeak>();
+        bytes += self.sync_requests.capacity() * size_of::<SyncRequest>();
+        bytes += self.updated_boundary.capacity() * size_of::<(bool, EdgeWeak)>();
+        bytes += self.propagating_vertices.capacity() * size_of::<(VertexWeak, Option<DualNodeInternalWeak>)>();
+        bytes
+    }
+
+    /// temporarily change the weights of some edges until [`DualModuleImpl::clear`], remembering whether they are erasures
+    #[allow(clippy::unnecessary_cast)]
+    fn modify_edges(&mut self, edge_modifier: &[(EdgeIndex, Weight)], is_erasure: bool) {
+        debug_assert!(
+            !self.edge_modifier.has_modified_edges(),
+            "the current erasure modifier is not clean, probably forget to clean the state?"
+        );
+        let active_timestamp = self.active_timestamp;
+        for (edge_index, target_weight) in edge_modifier.iter() {
+            let edge_ptr = &self.edges[*edge_index as usize];
+            edge_ptr.dynamic_clear(active_timestamp); // may visit stale edges
+            let mut edge = edge_ptr.write(active_timestamp);
+            let original_weight = edge.weight;
+            edge.weight = *target_weight;
+            if is_erasure {
+                self.edge_modifier.push_erasure_edge(*edge_index, original_weight);
+            } else {
+                self.edge_modifier.push_modified_edge(*edge_index, original_weight);
+            }
+        }
+    }
+
+    /// permanently change the weights of some edges; must be called on a clean module, i.e. right after [`DualModuleImpl::clear`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn update_weights(&mut self, new_weights: &[(EdgeIndex, Weight)]) {
+        debug_assert!(
+            !self.edge_modifier.has_modified_edges(),
+            "the temporary edge modifier must be recovered before updating weights permanently"
+        );
+        let active_timestamp = self.active_timestamp;
+        for (edge_index, weight) in new_weights.iter() {
+            let edge_ptr = &self.edges[*edge_index as usize];
+            edge_ptr.dynamic_clear(active_timestamp);
+            edge_ptr.write(active_timestamp).weight = *weight;
+        }
+    }
+
     /// hard clear all growth (manual call not recommended due to performance drawback)
     pub fn hard_clear_graph(&mut self) {
         for edge in self.edges.iter() {
@@ -1612,6 +1738,20 @@ impl FusionVisualizer for DualModuleSerial {
                 );
             }
         }
+        // edges with overridden weights by erasures or dynamic weights
+        for ((edge_index, original_weight), is_erasure) in
+            self.edge_modifier.iter().zip(self.edge_modifier.is_erasure.iter())
+        {
+            let edge = self.edges[*edge_index as usize].read_recursive(active_timestamp);
+            let edge_value = edges[edge.edge_index as usize].as_object_mut().unwrap();
+            edge_value.insert(
+                (if abbrev { "ow" } else { "original_weight" }).to_string(),
+                json!(original_weight),
+            );
+            if *is_erasure {
+                edge_value.insert((if abbrev { "e" } else { "is_erasure" }).to_string(), json!(1));
+            }
+        }
         let mut value = json!({
             "vertices": vertices,
             "edges": edges,
@@ -2157,7 +2297,7 @@ impl DualModuleSerial {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "minimal")))]
 mod tests {
     use super::super::example_codes::*;
     use super::super::primal_module_serial::tests::*;
@@ -2812,7 +2952,7 @@ mod tests {
         let mut dual_module = DualModuleSerial::new_empty(&initializer);
         // try to work on a simple syndrome
         code.vertices[64].is_defect = true;
-        code.set_erasures(&vec![110, 78, 57, 142, 152, 163, 164]);
+        code.set_erasures(&vec![110, 78, 57, 142, 152, 163, 164]).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
         visualizer
             .snapshot_combined(format!("syndrome"), vec![&interface_ptr, &dual_module])
@@ -2851,4 +2991,50 @@ mod tests {
                 .unwrap();
         }
     }
+
+    /// erased edges are marked in the snapshots of both the code and the dual module
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn dual_module_erasure_snapshot_1() {
+        // cargo test dual_module_erasure_snapshot_1 -- --nocapture
+        let half_weight = 500;
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, half_weight);
+        let initializer = code.get_initializer();
+        let mut dual_module = DualModuleSerial::new_empty(&initializer);
+        let erasures = vec![110, 78, 57, 142, 152, 163, 164];
+        code.vertices[64].is_defect = true;
+        code.set_erasures(&erasures).unwrap();
+        let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
+        let code_snapshot = code.snapshot(true);
+        let dual_snapshot = dual_module.snapshot(true);
+        for edge_index in 0..initializer.weighted_edges.len() {
+            let is_erasure = erasures.contains(&(edge_index as EdgeIndex));
+            let code_edge = &code_snapshot["edges"][edge_index];
+            assert_eq!(code_edge["e"], json!(i32::from(is_erasure)));
+            let dual_edge = &dual_snapshot["edges"][edge_index];
+            if is_erasure {
+                assert_eq!(dual_edge["e"], json!(1));
+                assert_eq!(dual_edge["w"], json!(0));
+                assert_eq!(dual_edge["ow"], json!(initializer.weighted_edges[edge_index].2));
+            } else {
+                assert!(dual_edge.get("e").is_none());
+                assert!(dual_edge.get("ow").is_none());
+            }
+        }
+        // the marks are removed after clearing the erasures
+        dual_module.clear();
+        interface_ptr.clear();
+        let dual_snapshot = dual_module.snapshot(true);
+        for edge in dual_snapshot["edges"].as_array().unwrap().iter() {
+            assert!(edge.get("e").is_none());
+        }
+        // a dynamic weight of zero is not an erasure
+        let syndrome_pattern = SyndromePattern::new_dynamic_weights(vec![64], vec![], vec![(110, 0)]);
+        interface_ptr.load(&syndrome_pattern, &mut dual_module);
+        let dual_snapshot = dual_module.snapshot(true);
+        let dual_edge = &dual_snapshot["edges"][110];
+        assert_eq!(dual_edge["w"], json!(0));
+        assert_eq!(dual_edge["ow"], json!(initializer.weighted_edges[110].2));
+        assert!(dual_edge.get("e").is_none());
+    }
 }
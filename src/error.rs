@@ -0,0 +1,143 @@
+//! Error
+//!
+//! A single error type returned by the fallible entry points of the library, e.g. constructing an example code, a
+//! syndrome reader, a partition or a solver, and [`crate::mwpm_solver::PrimalDualSolver::solve_checked`]. The more
+//! specific errors of each module convert into it, so that an application embedding the decoder can handle every
+//! failure with `?` instead of catching panics.
+//!
+
+use super::graph_format::GraphMLError;
+use super::mwpm_solver::{BlockedEdgesError, OddParityError};
+#[cfg(not(feature = "minimal"))]
+use super::primal_module_parallel::SolverHang;
+use super::util::*;
+use super::visualize::VisualizeError;
+
+#[derive(Debug)]
+pub enum FusionError {
+    /// the configuration is malformed, has unknown keys or inconsistent values
+    Config(String),
+    /// failed to read or write a file
+    Io(std::io::Error),
+    /// the content of a file or a string cannot be parsed
+    Parse(String),
+    /// the decoding graph or the syndrome pattern is invalid, e.g. an index out of range
+    InvalidInput(String),
+    /// the problem exceeds what the decoder is built to handle
+    Capacity(String),
+    /// the decoder fails to make progress, which indicates a bug
+    Internal(String),
+    /// the parallel solver guarded by the watchdog hangs; the error has a diagnostic dump for bug reports
+    #[cfg(not(feature = "minimal"))]
+    Hang(SolverHang),
+}
+
+impl std::fmt::Display for FusionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Config(message) => write!(f, "invalid config: {}", message),
+            Self::Io(error) => write!(f, "io error: {}", error),
+            Self::Parse(message) => write!(f, "parse error: {}", message),
+            Self::InvalidInput(message) => write!(f, "invalid input: {}", message),
+            Self::Capacity(message) => write!(f, "capacity exceeded: {}", message),
+            Self::Internal(message) => write!(f, "internal error: {}", message),
+            #[cfg(not(feature = "minimal"))]
+            Self::Hang(error) => write!(f, "solver hang: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for FusionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            #[cfg(not(feature = "minimal"))]
+            Self::Hang(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// the readers report a malformed file as [`std::io::ErrorKind::InvalidData`], which is a parse error
+impl From<std::io::Error> for FusionError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::InvalidData => Self::Parse(error.to_string()),
+            _ => Self::Io(error),
+        }
+    }
+}
+
+impl From<serde_json::Error> for FusionError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Parse(error.to_string())
+    }
+}
+
+impl From<PartitionError> for FusionError {
+    fn from(error: PartitionError) -> Self {
+        Self::Config(error.to_string())
+    }
+}
+
+impl From<CheckMatrixError> for FusionError {
+    fn from(error: CheckMatrixError) -> Self {
+        Self::InvalidInput(error.to_string())
+    }
+}
+
+impl From<GraphMLError> for FusionError {
+    fn from(error: GraphMLError) -> Self {
+        Self::Parse(error.to_string())
+    }
+}
+
+impl From<VisualizeError> for FusionError {
+    fn from(error: VisualizeError) -> Self {
+        match error {
+            VisualizeError::Io(error) => Self::Io(error),
+            error => Self::Parse(error.to_string()),
+        }
+    }
+}
+
+impl From<OddParityError> for FusionError {
+    fn from(error: OddParityError) -> Self {
+        Self::InvalidInput(error.to_string())
+    }
+}
+
+impl From<BlockedEdgesError> for FusionError {
+    fn from(error: BlockedEdgesError) -> Self {
+        Self::InvalidInput(error.to_string())
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl From<SolverHang> for FusionError {
+    fn from(error: SolverHang) -> Self {
+        Self::Hang(error)
+    }
+}
+
+#[cfg(feature = "python_binding")]
+impl From<FusionError> for pyo3::PyErr {
+    fn from(error: FusionError) -> Self {
+        use pyo3::exceptions::*;
+        match error {
+            FusionError::Config(_) | FusionError::Parse(_) | FusionError::InvalidInput(_) => {
+                PyValueError::new_err(error.to_string())
+            }
+            FusionError::Io(error) => error.into(),
+            FusionError::Capacity(_) => PyOverflowError::new_err(error.to_string()),
+            FusionError::Internal(_) => PyRuntimeError::new_err(error.to_string()),
+            #[cfg(not(feature = "minimal"))]
+            FusionError::Hang(_) => PyTimeoutError::new_err(error.to_string()),
+        }
+    }
+}
+
+/// panic with the message of the error, used by the infallible counterparts of the `try_*` functions
+pub(crate) fn or_panic<T>(result: Result<T, FusionError>) -> T {
+    result.unwrap_or_else(|error| panic!("{error}"))
+}
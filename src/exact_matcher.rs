@@ -0,0 +1,145 @@
+//! Exact Matcher
+//!
+//! A brute-force minimum-weight perfect matching solver for small syndromes, used as a reference to test the decoders.
+//! It computes the shortest paths between the defects and to the boundary with [`CompleteGraph`], and then finds the
+//! optimal matching by dynamic programming over the subsets of defects, which takes `O(2^n n)` time for `n` defects.
+//!
+
+use super::complete_graph::CompleteGraph;
+use super::util::*;
+
+/// the exponential time and memory become impractical beyond this number of defects
+pub const MAX_DEFECT_NUM: usize = 14;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExactMatching {
+    /// the total weight of the shortest paths
+    pub weight: Weight,
+    /// matched pairs of defect vertices
+    pub peer_matchings: Vec<(VertexIndex, VertexIndex)>,
+    /// defect vertices matched to the boundary. (defect vertex, nearest virtual vertex)
+    pub virtual_matchings: Vec<(VertexIndex, VertexIndex)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExactMatcher {
+    complete_graph: CompleteGraph,
+    is_virtual: Vec<bool>,
+}
+
+impl ExactMatcher {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(initializer: &SolverInitializer) -> Self {
+        let mut is_virtual = vec![false; initializer.vertex_num as usize];
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            is_virtual[virtual_vertex as usize] = true;
+        }
+        Self {
+            complete_graph: CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges),
+            is_virtual,
+        }
+    }
+
+    /// the minimum-weight perfect matching with the erasures and dynamic weights of the syndrome pattern applied,
+    /// or `None` if some defects can neither be paired nor reach the boundary
+    #[allow(clippy::unnecessary_cast)]
+    pub fn solve(&mut self, syndrome_pattern: &SyndromePattern) -> Option<ExactMatching> {
+        let defect_vertices = &syndrome_pattern.defect_vertices;
+        let defect_num = defect_vertices.len();
+        assert!(
+            defect_num <= MAX_DEFECT_NUM,
+            "too many defects for the exact matcher: {defect_num} > {MAX_DEFECT_NUM}"
+        );
+        // the complete graph holds a single edge modifier, so the erasures are loaded as zero dynamic weights
+        let edge_modifier: Vec<_> = (syndrome_pattern.erasures.iter().map(|&edge_index| (edge_index, 0)))
+            .chain(syndrome_pattern.dynamic_weights.iter().cloned())
+            .collect();
+        self.complete_graph.load_dynamic_weights(&edge_modifier);
+        let mut peer_weights = vec![vec![None; defect_num]; defect_num];
+        let mut boundaries: Vec<Option<(VertexIndex, Weight)>> = vec![None; defect_num];
+        for (i, &defect_vertex) in defect_vertices.iter().enumerate() {
+            let edges = self.complete_graph.all_edges(defect_vertex);
+            for (j, peer_vertex) in defect_vertices.iter().enumerate() {
+                peer_weights[i][j] = edges.get(peer_vertex).map(|&(_, weight)| weight);
+            }
+            for (&vertex_index, &(_, weight)) in edges.iter() {
+                if self.is_virtual[vertex_index as usize]
+                    && boundaries[i].is_none_or(|(_, boundary_weight)| weight < boundary_weight)
+                {
+                    boundaries[i] = Some((vertex_index, weight));
+                }
+            }
+        }
+        self.complete_graph.reset();
+        // `best[mask]` is the minimum weight to match the defects in `mask`, always removing its lowest defect first
+        let mut best: Vec<Option<Weight>> = vec![None; 1 << defect_num];
+        best[0] = Some(0);
+        for mask in 1..(1usize << defect_num) {
+            let i = mask.trailing_zeros() as usize;
+            let rest = mask & !(1 << i);
+            let mut candidate = boundaries[i]
+                .zip(best[rest])
+                .map(|((_, weight), rest_weight)| weight + rest_weight);
+            for j in (i + 1)..defect_num {
+                if rest & (1 << j) != 0 {
+                    if let Some(weight) = peer_weights[i][j].zip(best[rest & !(1 << j)]).map(|(a, b)| a + b) {
+                        candidate = Some(candidate.map_or(weight, |candidate| candidate.min(weight)));
+                    }
+                }
+            }
+            best[mask] = candidate;
+        }
+        // backtrack the choices
+        let mut matching = ExactMatching {
+            weight: best[(1 << defect_num) - 1]?,
+            peer_matchings: vec![],
+            virtual_matchings: vec![],
+        };
+        let mut mask = (1usize << defect_num) - 1;
+        while mask != 0 {
+            let i = mask.trailing_zeros() as usize;
+            let rest = mask & !(1 << i);
+            let target = best[mask];
+            if let Some((virtual_vertex, weight)) = boundaries[i] {
+                if best[rest].map(|rest_weight| weight + rest_weight) == target {
+                    matching.virtual_matchings.push((defect_vertices[i], virtual_vertex));
+                    mask = rest;
+                    continue;
+                }
+            }
+            let j = ((i + 1)..defect_num)
+                .find(|&j| {
+                    rest & (1 << j) != 0 && peer_weights[i][j].zip(best[rest & !(1 << j)]).map(|(a, b)| a + b) == target
+                })
+                .expect("the optimal choice must be found");
+            matching.peer_matchings.push((defect_vertices[i], defect_vertices[j]));
+            mask = rest & !(1 << j);
+        }
+        Some(matching)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example_codes::*;
+    use super::*;
+
+    #[test]
+    fn exact_matcher_repetition_code_1() {
+        // cargo test exact_matcher_repetition_code_1 -- --nocapture
+        let code = CodeCapacityRepetitionCode::new(11, 0.1, 500);
+        let mut exact_matcher = ExactMatcher::new(&code.get_initializer());
+        // two neighboring defects are paired, and the lonely one near the left boundary goes to the boundary
+        let matching = exact_matcher.solve(&SyndromePattern::new_vertices(vec![1, 5, 6])).unwrap();
+        assert_eq!(matching.weight, 3000);
+        assert_eq!(matching.peer_matchings, vec![(5, 6)]);
+        assert_eq!(matching.virtual_matchings.len(), 1);
+        assert_eq!(matching.virtual_matchings[0].0, 1);
+        // an erasure makes the path between two far-apart defects free
+        let matching = exact_matcher.solve(&SyndromePattern::new(vec![2, 5], vec![2, 3, 4])).unwrap();
+        assert_eq!(matching.weight, 0);
+        assert_eq!(matching.peer_matchings, vec![(2, 5)]);
+        // no defect costs nothing
+        assert_eq!(exact_matcher.solve(&SyndromePattern::new_vertices(vec![])).unwrap().weight, 0);
+    }
+}
@@ -8,18 +8,36 @@
 //! To maximize code efficiency, user should design how to group vertices such that memory speed is constant for arbitrary large code distance.
 //!
 
-use super::pointers::*;
+use super::complete_graph::CompleteGraph;
+use super::error::*;
+use super::ordering::VertexPermutation;
 use super::util::*;
 use super::visualize::*;
 use crate::derivative::Derivative;
+#[cfg(not(feature = "minimal"))]
+use crate::flate2::read::GzDecoder;
+#[cfg(not(feature = "minimal"))]
+use crate::flate2::write::GzEncoder;
+#[cfg(not(feature = "minimal"))]
+use crate::flate2::Compression;
 use crate::rand_xoshiro::rand_core::SeedableRng;
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
 use crate::rayon::prelude::*;
 use crate::serde_json;
 #[cfg(feature = "python_binding")]
+use pyo3::exceptions::{PyIndexError, PyValueError};
+#[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(not(feature = "minimal"))]
+use std::collections::VecDeque;
+#[cfg(not(feature = "minimal"))]
 use std::fs::File;
-use std::io::{self, BufRead};
+#[cfg(not(feature = "minimal"))]
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write};
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+use std::sync::Arc;
 
 /// Vertex corresponds to a stabilizer measurement bit
 #[derive(Derivative, Clone)]
@@ -99,6 +117,134 @@ pub fn weight_of_p(p: f64) -> f64 {
     ((1. - p) / p).ln()
 }
 
+/// check the parameters shared by the constructors of the example codes
+fn check_code_parameters(d: VertexNum, odd_d: bool, p: f64, max_half_weight: Weight) -> Result<(), FusionError> {
+    if odd_d && (d < 3 || d.is_multiple_of(2)) {
+        return Err(FusionError::InvalidInput(format!("d must be odd integer >= 3, got {d}")));
+    }
+    if d < 3 {
+        return Err(FusionError::InvalidInput(format!("d must be integer >= 3, got {d}")));
+    }
+    if !(0. ..0.5).contains(&p) {
+        return Err(FusionError::InvalidInput(format!("p must be in [0, 0.5), got {p}")));
+    }
+    if max_half_weight <= 0 {
+        return Err(FusionError::InvalidInput(format!(
+            "max_half_weight must be positive, got {max_half_weight}"
+        )));
+    }
+    Ok(())
+}
+
+/// the kind of an edge in a code of multiple measurement rounds, recorded when the edge is created instead of inferred
+/// from the positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeLayer {
+    /// between two vertices in the same round, a data error
+    Intra,
+    /// between the same stabilizer in two consecutive rounds, a measurement error
+    Inter,
+    /// between different stabilizers in two consecutive rounds, only in the circuit-level code
+    Diagonal,
+}
+
+/// reorder the vertices such that `sequential_vertices[new_index] = old_index`; the defect flags move with the vertices
+/// and the erasure flags stay with the edges whose indices don't change
+#[allow(clippy::unnecessary_cast)]
+fn reorder_code_vertices(
+    vertices: &mut Vec<CodeVertex>,
+    edges: &mut [CodeEdge],
+    sequential_vertices: &[VertexIndex],
+) -> Result<VertexPermutation, String> {
+    if vertices.len() != sequential_vertices.len() {
+        return Err(format!(
+            "{} sequential vertices given but the code has {} vertices",
+            sequential_vertices.len(),
+            vertices.len()
+        ));
+    }
+    let permutation = VertexPermutation::try_new(sequential_vertices.to_vec())?;
+    // change the vertices numbering
+    *vertices = (0..vertices.len())
+        .map(|new_index| vertices[permutation.to_old(new_index as VertexIndex) as usize].clone())
+        .collect();
+    for edge in edges.iter_mut() {
+        let (old_left, old_right) = edge.vertices;
+        edge.vertices = (permutation.to_new(old_left), permutation.to_new(old_right));
+    }
+    Ok(permutation)
+}
+
+/// the decoding graph of a code without checking the weights, see [`ExampleCode::get_initializer`]
+fn build_initializer(vertices: &[CodeVertex], edges: &[CodeEdge]) -> SolverInitializer {
+    let vertex_num = vertices.len() as VertexIndex;
+    let mut weighted_edges = Vec::with_capacity(edges.len());
+    for edge in edges.iter() {
+        weighted_edges.push((edge.vertices.0, edge.vertices.1, edge.half_weight * 2));
+    }
+    let mut virtual_vertices = Vec::new();
+    for (vertex_idx, vertex) in vertices.iter().enumerate() {
+        if vertex.is_virtual {
+            virtual_vertices.push(vertex_idx as VertexIndex);
+        }
+    }
+    SolverInitializer {
+        vertex_num,
+        weighted_edges,
+        virtual_vertices,
+        virtual_vertex_groups: None,
+    }
+}
+
+/// the two [`ExampleCode::logical_boundaries`] as virtual vertex groups, or `None` if there is no virtual vertex
+fn logical_boundary_groups(code: &(impl ExampleCode + ?Sized)) -> Option<Vec<Vec<VertexIndex>>> {
+    let (left, right) = code.logical_boundaries();
+    if left.is_empty() && right.is_empty() {
+        return None;
+    }
+    Some(vec![left, right])
+}
+
+/// hashed lookup of the edge between two vertices, built by [`ExampleCode::build_edge_index`];
+/// it's a snapshot of the graph and must be rebuilt after edges are added or removed
+#[derive(Debug, Clone)]
+pub struct EdgeLookup {
+    /// the edge index keyed by the unordered pair of its incident vertices
+    edges: HashMap<(VertexIndex, VertexIndex), EdgeIndex>,
+}
+
+impl EdgeLookup {
+    fn unique_pair(a: VertexIndex, b: VertexIndex) -> (VertexIndex, VertexIndex) {
+        if a < b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    pub fn new(edges: &[CodeEdge]) -> Self {
+        let mut lookup = HashMap::with_capacity(edges.len());
+        for (edge_index, edge) in edges.iter().enumerate() {
+            let (a, b) = edge.vertices;
+            lookup.insert(Self::unique_pair(a, b), edge_index as EdgeIndex);
+        }
+        Self { edges: lookup }
+    }
+
+    /// the edge connecting vertex `a` and `b` in either order
+    pub fn edge_between(&self, a: VertexIndex, b: VertexIndex) -> Option<EdgeIndex> {
+        self.edges.get(&Self::unique_pair(a, b)).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+}
+
 pub trait ExampleCode {
     /// get mutable references to vertices and edges
     fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>);
@@ -129,6 +275,59 @@ pub trait ExampleCode {
         }
     }
 
+    /// the edges incident to a vertex
+    #[allow(clippy::unnecessary_cast)]
+    fn edges_of_vertex(&self, vertex_index: VertexIndex) -> &[EdgeIndex] {
+        let (vertices, _edges) = self.immutable_vertices_edges();
+        &vertices[vertex_index as usize].neighbor_edges
+    }
+
+    /// find the edge connecting vertex `a` and `b` by scanning the neighbor edges of `a`;
+    /// use [`ExampleCode::build_edge_index`] for repeated lookups
+    #[allow(clippy::unnecessary_cast)]
+    fn edge_between(&self, a: VertexIndex, b: VertexIndex) -> Option<EdgeIndex> {
+        let (_vertices, edges) = self.immutable_vertices_edges();
+        self.edges_of_vertex(a).iter().copied().find(|&edge_index| {
+            let (v1, v2) = edges[edge_index as usize].vertices;
+            (v1 == a && v2 == b) || (v1 == b && v2 == a)
+        })
+    }
+
+    /// build a hashed lookup of the edge between any two vertices
+    fn build_edge_index(&self) -> EdgeLookup {
+        let (_vertices, edges) = self.immutable_vertices_edges();
+        EdgeLookup::new(edges)
+    }
+
+    /// iterate over the edges together with the positions of their two vertices
+    #[allow(clippy::unnecessary_cast)]
+    fn iter_edges_with_positions(
+        &self,
+    ) -> impl Iterator<Item = (EdgeIndex, &CodeEdge, &VisualizePosition, &VisualizePosition)>
+    where
+        Self: Sized,
+    {
+        let (vertices, edges) = self.immutable_vertices_edges();
+        edges.iter().enumerate().map(|(edge_index, edge)| {
+            let (v1, v2) = edge.vertices;
+            (
+                edge_index as EdgeIndex,
+                edge,
+                &vertices[v1 as usize].position,
+                &vertices[v2 as usize].position,
+            )
+        })
+    }
+
+    /// the Euclidean distance between the positions of the two vertices of an edge
+    #[allow(clippy::unnecessary_cast)]
+    fn edge_length(&self, edge_index: EdgeIndex) -> f64 {
+        let (vertices, edges) = self.immutable_vertices_edges();
+        let (v1, v2) = edges[edge_index as usize].vertices;
+        let (v1p, v2p) = (&vertices[v1 as usize].position, &vertices[v2 as usize].position);
+        ((v1p.i - v2p.i).powi(2) + (v1p.j - v2p.j).powi(2) + (v1p.t - v2p.t).powi(2)).sqrt()
+    }
+
     /// sanity check to avoid duplicate edges that are hard to debug
     fn sanity_check(&self) -> Result<(), String> {
         let (vertices, edges) = self.immutable_vertices_edges();
@@ -163,6 +362,12 @@ pub trait ExampleCode {
                 existing_edges.insert(*edge_idx, ());
             }
         }
+        // check the weights, which might be set directly instead of by `compute_weights`
+        let initializer = build_initializer(vertices, edges);
+        let issues = initializer.check_weights(initializer.max_safe_weight());
+        if !issues.is_empty() {
+            return Err(format!("suspicious weights of (edge index, issue): {issues:?}"));
+        }
         Ok(())
     }
 
@@ -214,31 +419,126 @@ pub trait ExampleCode {
         positions
     }
 
-    /// generate standard interface to instantiate Fusion blossom solver
+    /// the minimum and maximum coordinates `(i, j, t)` of all vertices, or the origin if there is no vertex
+    fn positions_bounding_box(&self) -> ((f64, f64, f64), (f64, f64, f64)) {
+        let (vertices, _edges) = self.immutable_vertices_edges();
+        let Some(first) = vertices.first() else {
+            return ((0., 0., 0.), (0., 0., 0.));
+        };
+        let mut min = (first.position.i, first.position.j, first.position.t);
+        let mut max = min;
+        for vertex in vertices.iter() {
+            let position = &vertex.position;
+            min = (min.0.min(position.i), min.1.min(position.j), min.2.min(position.t));
+            max = (max.0.max(position.i), max.1.max(position.j), max.2.max(position.t));
+        }
+        (min, max)
+    }
+
+    /// translate the centroid of the vertices to the origin and scale uniformly such that the largest extent among
+    /// the three axes equals `target_extent`; positions that all coincide are only translated
+    fn normalize_positions(&mut self, target_extent: f64) {
+        assert!(
+            target_extent.is_finite() && target_extent > 0.,
+            "target extent must be positive, got {target_extent}"
+        );
+        let ((min_i, min_j, min_t), (max_i, max_j, max_t)) = self.positions_bounding_box();
+        let extent = (max_i - min_i).max(max_j - min_j).max(max_t - min_t);
+        let scale = if extent > 0. { target_extent / extent } else { 1. };
+        let (vertices, _edges) = self.vertices_edges();
+        if vertices.is_empty() {
+            return;
+        }
+        let count = vertices.len() as f64;
+        let (mut ci, mut cj, mut ct) = (0., 0., 0.);
+        for vertex in vertices.iter() {
+            ci += vertex.position.i / count;
+            cj += vertex.position.j / count;
+            ct += vertex.position.t / count;
+        }
+        for vertex in vertices.iter_mut() {
+            let position = &mut vertex.position;
+            position.i = (position.i - ci) * scale;
+            position.j = (position.j - cj) * scale;
+            position.t = (position.t - ct) * scale;
+        }
+    }
+
+    /// generate standard interface to instantiate Fusion blossom solver; in debug builds, it panics if any weight is
+    /// zero or too large, see [`SolverInitializer::check_weights`]
     fn get_initializer(&self) -> SolverInitializer {
         let (vertices, edges) = self.immutable_vertices_edges();
-        let vertex_num = vertices.len() as VertexIndex;
-        let mut weighted_edges = Vec::with_capacity(edges.len());
-        for edge in edges.iter() {
-            weighted_edges.push((edge.vertices.0, edge.vertices.1, edge.half_weight * 2));
+        let mut initializer = build_initializer(vertices, edges);
+        initializer.virtual_vertex_groups = self.virtual_vertex_groups();
+        if cfg!(debug_assertions) {
+            let issues = initializer.check_weights(initializer.max_safe_weight());
+            assert!(issues.is_empty(), "suspicious weights of (edge index, issue): {issues:?}");
         }
-        let mut virtual_vertices = Vec::new();
-        for (vertex_idx, vertex) in vertices.iter().enumerate() {
+        initializer
+    }
+
+    /// generate the solver initializer with only the edges satisfying `include`, e.g. to exclude the edges of zero
+    /// probability; the returned map translates the [`crate::mwpm_solver::PrimalDualSolver::subgraph`] back to the edges
+    /// of this code, and the erasures of this code the other way before decoding
+    #[allow(clippy::unnecessary_cast)]
+    fn get_initializer_filtered(&self, include: impl Fn(&CodeEdge) -> bool) -> (SolverInitializer, EdgeIndexMap)
+    where
+        Self: Sized,
+    {
+        let (_vertices, edges) = self.immutable_vertices_edges();
+        self.get_initializer()
+            .filtered(|edge_index, _| include(&edges[edge_index as usize]))
+    }
+
+    /// check that the defect vertices are in range and not virtual
+    #[allow(clippy::unnecessary_cast)]
+    fn check_defect_vertices(&self, defect_vertices: &[VertexIndex]) -> Result<(), String> {
+        let (vertices, _edges) = self.immutable_vertices_edges();
+        for &vertex_idx in defect_vertices.iter() {
+            let Some(vertex) = vertices.get(vertex_idx as usize) else {
+                return Err(format!(
+                    "defect vertex {} out of range: valid vertices are [0, {})",
+                    vertex_idx,
+                    vertices.len()
+                ));
+            };
             if vertex.is_virtual {
-                virtual_vertices.push(vertex_idx as VertexIndex);
+                let position = &vertex.position;
+                return Err(format!(
+                    "defect vertex {} at position (i: {}, j: {}, t: {}) is virtual and cannot report a syndrome",
+                    vertex_idx, position.i, position.j, position.t
+                ));
             }
         }
-        SolverInitializer {
-            vertex_num,
-            weighted_edges,
-            virtual_vertices,
+        Ok(())
+    }
+
+    /// check that the erasure edges are in range
+    #[allow(clippy::unnecessary_cast)]
+    fn check_erasures(&self, erasures: &[EdgeIndex]) -> Result<(), String> {
+        let (_vertices, edges) = self.immutable_vertices_edges();
+        if let Some(&edge_idx) = erasures.iter().find(|&&edge_idx| edge_idx as usize >= edges.len()) {
+            return Err(format!(
+                "erasure edge {} out of range: valid edges are [0, {})",
+                edge_idx,
+                edges.len()
+            ));
         }
+        Ok(())
     }
 
     /// set defect vertices (non-trivial measurement result in case of single round of measurement,
-    /// or different result from the previous round in case of multiple rounds of measurement)
+    /// or different result from the previous round in case of multiple rounds of measurement);
+    /// the code is not modified if any of the vertices is out of range or virtual
+    fn set_defect_vertices(&mut self, defect_vertices: &[VertexIndex]) -> Result<(), String> {
+        self.check_defect_vertices(defect_vertices)?;
+        self.set_defect_vertices_unchecked(defect_vertices);
+        Ok(())
+    }
+
+    /// set defect vertices without checking the indices, panics if any vertex is out of range
     #[allow(clippy::unnecessary_cast)]
-    fn set_defect_vertices(&mut self, defect_vertices: &[VertexIndex]) {
+    fn set_defect_vertices_unchecked(&mut self, defect_vertices: &[VertexIndex]) {
         let (vertices, _edges) = self.vertices_edges();
         for vertex in vertices.iter_mut() {
             vertex.is_defect = false;
@@ -249,9 +549,16 @@ pub trait ExampleCode {
         }
     }
 
-    /// set erasure edges
+    /// set erasure edges; the code is not modified if any of the edges is out of range
+    fn set_erasures(&mut self, erasures: &[EdgeIndex]) -> Result<(), String> {
+        self.check_erasures(erasures)?;
+        self.set_erasures_unchecked(erasures);
+        Ok(())
+    }
+
+    /// set erasure edges without checking the indices, panics if any edge is out of range
     #[allow(clippy::unnecessary_cast)]
-    fn set_erasures(&mut self, erasures: &[EdgeIndex]) {
+    fn set_erasures_unchecked(&mut self, erasures: &[EdgeIndex]) {
         let (_vertices, edges) = self.vertices_edges();
         for edge in edges.iter_mut() {
             edge.is_erasure = false;
@@ -262,10 +569,42 @@ pub trait ExampleCode {
         }
     }
 
-    /// set syndrome
-    fn set_syndrome(&mut self, syndrome_pattern: &SyndromePattern) {
-        self.set_defect_vertices(&syndrome_pattern.defect_vertices);
-        self.set_erasures(&syndrome_pattern.erasures);
+    /// additionally mark as erasure every edge whose endpoint positions satisfy the predicate, e.g. a localized event;
+    /// returns the number of edges in the region
+    #[allow(clippy::unnecessary_cast)]
+    fn set_erasure_region<F: Fn(&VisualizePosition, &VisualizePosition) -> bool>(&mut self, predicate: F) -> usize
+    where
+        Self: Sized,
+    {
+        let (vertices, edges) = self.vertices_edges();
+        let mut count = 0;
+        for edge in edges.iter_mut() {
+            let (v1, v2) = edge.vertices;
+            if predicate(&vertices[v1 as usize].position, &vertices[v2 as usize].position) {
+                edge.is_erasure = true;
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// unmark all erasure edges
+    fn clear_erasures(&mut self) {
+        self.set_erasures_unchecked(&[]);
+    }
+
+    /// set syndrome; the code is not modified if the syndrome doesn't match the code
+    fn set_syndrome(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), String> {
+        self.check_defect_vertices(&syndrome_pattern.defect_vertices)?;
+        self.check_erasures(&syndrome_pattern.erasures)?;
+        self.set_syndrome_unchecked(syndrome_pattern);
+        Ok(())
+    }
+
+    /// set syndrome without checking the indices
+    fn set_syndrome_unchecked(&mut self, syndrome_pattern: &SyndromePattern) {
+        self.set_defect_vertices_unchecked(&syndrome_pattern.defect_vertices);
+        self.set_erasures_unchecked(&syndrome_pattern.erasures);
     }
 
     /// get current defect vertices
@@ -298,14 +637,21 @@ pub trait ExampleCode {
     }
 
     /// generate random errors based on the edge probabilities and a seed for pseudo number generator
-    #[allow(clippy::unnecessary_cast)]
     fn generate_random_errors(&mut self, seed: u64) -> SyndromePattern {
+        self.generate_random_errors_with_edges(seed).0
+    }
+
+    /// generate random errors like [`ExampleCode::generate_random_errors`], but also return the edges with errors as the
+    /// ground truth for [`ExampleCode::is_logical_error`]
+    #[allow(clippy::unnecessary_cast)]
+    fn generate_random_errors_with_edges(&mut self, seed: u64) -> (SyndromePattern, Vec<EdgeIndex>) {
         let mut rng = DeterministicRng::seed_from_u64(seed);
         let (vertices, edges) = self.vertices_edges();
         for vertex in vertices.iter_mut() {
             vertex.is_defect = false;
         }
-        for edge in edges.iter_mut() {
+        let mut error_edges = vec![];
+        for (edge_index, edge) in edges.iter_mut().enumerate() {
             let p = if rng.next_f64() < edge.pe {
                 edge.is_erasure = true;
                 0.5 // when erasure happens, there are 50% chance of error
@@ -314,6 +660,7 @@ pub trait ExampleCode {
                 edge.p
             };
             if rng.next_f64() < p {
+                error_edges.push(edge_index as EdgeIndex);
                 let (v1, v2) = edge.vertices;
                 let vertex_1 = &mut vertices[v1 as usize];
                 if !vertex_1.is_virtual {
@@ -325,7 +672,88 @@ pub trait ExampleCode {
                 }
             }
         }
-        self.get_syndrome()
+        (self.get_syndrome(), error_edges)
+    }
+
+    /// the virtual vertices on the two logical boundaries, split by their horizontal position relative to the average of
+    /// the real vertices; a chain of edges connecting the two boundaries is a logical operator
+    #[allow(clippy::unnecessary_cast)]
+    fn logical_boundaries(&self) -> (Vec<VertexIndex>, Vec<VertexIndex>) {
+        let (vertices, _edges) = self.immutable_vertices_edges();
+        let real_positions: Vec<f64> = vertices
+            .iter()
+            .filter(|vertex| !vertex.is_virtual)
+            .map(|vertex| vertex.position.j)
+            .collect();
+        let middle_j = real_positions.iter().sum::<f64>() / real_positions.len() as f64;
+        let (mut left, mut right) = (vec![], vec![]);
+        for (vertex_index, vertex) in vertices.iter().enumerate().filter(|(_, vertex)| vertex.is_virtual) {
+            if vertex.position.j < middle_j {
+                left.push(vertex_index as VertexIndex);
+            } else {
+                right.push(vertex_index as VertexIndex);
+            }
+        }
+        (left, right)
+    }
+
+    /// the virtual vertices grouped by boundary, recorded in [`SolverInitializer::virtual_vertex_groups`]; by default
+    /// the two [`ExampleCode::logical_boundaries`], or `None` if there is no virtual vertex
+    fn virtual_vertex_groups(&self) -> Option<Vec<Vec<VertexIndex>>> {
+        logical_boundary_groups(self)
+    }
+
+    /// whether the correction differs from the actual errors by a logical operator, i.e. the difference connects the
+    /// first of the [`ExampleCode::virtual_vertex_groups`] to the others by touching it an odd number of times
+    #[allow(clippy::unnecessary_cast)]
+    fn is_logical_error(&self, error_edges: &[EdgeIndex], correction_edges: &[EdgeIndex]) -> bool {
+        let (vertices, edges) = self.immutable_vertices_edges();
+        let mut is_left_boundary = vec![false; vertices.len()];
+        let groups = self.virtual_vertex_groups().unwrap_or_default();
+        for &vertex_index in groups.first().into_iter().flatten() {
+            is_left_boundary[vertex_index as usize] = true;
+        }
+        let mut difference = vec![false; edges.len()];
+        for &edge_index in error_edges.iter().chain(correction_edges.iter()) {
+            difference[edge_index as usize] ^= true;
+        }
+        let mut parity = false;
+        for (edge, _) in edges.iter().zip(difference.iter()).filter(|(_, &is_different)| is_different) {
+            let (left, right) = edge.vertices;
+            parity ^= is_left_boundary[left as usize] ^ is_left_boundary[right as usize];
+        }
+        parity
+    }
+
+    /// the minimum number of edges and the minimum weight of a logical operator, i.e. a path between the first two
+    /// [`SolverInitializer::virtual_vertex_groups`], computed by multi-source Dijkstra from the first group
+    #[allow(clippy::unnecessary_cast)]
+    fn min_boundary_distance(&self) -> (usize, Weight) {
+        let initializer = self.get_initializer();
+        let (left, right) = match initializer.virtual_vertex_groups.as_deref() {
+            Some([left, right, ..]) if !left.is_empty() && !right.is_empty() => (left, right),
+            _ => panic!("both logical boundaries must have virtual vertices"),
+        };
+        // a super source connected to every vertex of the left boundary with zero weight
+        let source = initializer.vertex_num;
+        let distance_to_right = |edge_weight: &dyn Fn(Weight) -> Weight| {
+            let mut weighted_edges: Vec<_> = initializer
+                .weighted_edges
+                .iter()
+                .map(|&(v1, v2, weight)| (v1, v2, edge_weight(weight)))
+                .collect();
+            weighted_edges.extend(left.iter().map(|&vertex_index| (source, vertex_index, 0)));
+            let mut complete_graph = CompleteGraph::new(initializer.vertex_num + 1, &weighted_edges);
+            let distances = complete_graph.all_edges(source);
+            right
+                .iter()
+                .filter_map(|vertex_index| distances.get(vertex_index).map(|&(_, weight)| weight))
+                .min()
+                .expect("the two logical boundaries are not connected")
+        };
+        let hops = distance_to_right(&|_| 1);
+        let weight = distance_to_right(&|weight| weight);
+        (hops as usize, weight)
     }
 
     #[allow(clippy::unnecessary_cast)]
@@ -346,6 +774,13 @@ pub trait ExampleCode {
         self.get_syndrome()
     }
 
+    /// the syndrome of exactly these error edges, without erasures, regardless of the previous defects
+    fn apply_error_edges(&mut self, error_edges: &[EdgeIndex]) -> SyndromePattern {
+        self.set_defect_vertices_unchecked(&[]);
+        self.set_erasures_unchecked(&[]);
+        self.generate_errors(error_edges)
+    }
+
     fn clear_errors(&mut self) {
         let (vertices, edges) = self.vertices_edges();
         for vertex in vertices.iter_mut() {
@@ -366,24 +801,62 @@ pub trait ExampleCode {
         vertices[vertex_idx].is_defect
     }
 
-    /// reorder the vertices such that new vertices (the indices of the old order) is sequential
-    #[allow(clippy::unnecessary_cast)]
-    fn reorder_vertices(&mut self, sequential_vertices: &Vec<VertexIndex>) {
+    /// reorder the vertices such that new vertices (the indices of the old order) is sequential; the code is not modified
+    /// if `sequential_vertices` is not a permutation of the vertices. the returned permutation translates the syndrome
+    /// patterns held elsewhere, see [`VertexPermutation::map_syndrome`]
+    fn reorder_vertices(&mut self, sequential_vertices: &[VertexIndex]) -> Result<VertexPermutation, String> {
         let (vertices, edges) = self.vertices_edges();
-        assert_eq!(vertices.len(), sequential_vertices.len(), "amount of vertices must be same");
-        let old_to_new = build_old_to_new(sequential_vertices);
-        // change the vertices numbering
-        *vertices = (0..vertices.len())
-            .map(|new_index| vertices[sequential_vertices[new_index] as usize].clone())
-            .collect();
-        for edge in edges.iter_mut() {
-            let (old_left, old_right) = edge.vertices;
-            edge.vertices = (
-                old_to_new[old_left as usize].unwrap(),
-                old_to_new[old_right as usize].unwrap(),
-            );
+        reorder_code_vertices(vertices, edges, sequential_vertices)
+    }
+
+    /// transform the stored positions once, so that every later visualizer uses the fixed layout
+    fn transform_positions(&mut self, transform: &PositionTransform) {
+        let (vertices, _edges) = self.vertices_edges();
+        for vertex in vertices.iter_mut() {
+            vertex.position = transform.apply(&vertex.position);
         }
     }
+
+    /// the parameters of the code, e.g. to be recorded by [`Visualizer::set_run_metadata`]; the built-in codes also
+    /// report their code distance `d` and, if applicable, `noisy_measurements`
+    fn run_metadata(&self) -> serde_json::Value {
+        example_code_run_metadata(self)
+    }
+}
+
+/// the generic part of [`ExampleCode::run_metadata`]: `p` and `pe` are the largest among the edges and
+/// `max_half_weight` is the largest half weight, which are the parameters of the codes with uniform probabilities
+pub fn example_code_run_metadata<T: ExampleCode + ?Sized>(code: &T) -> serde_json::Value {
+    let (vertices, edges) = code.immutable_vertices_edges();
+    let code_type = std::any::type_name::<T>().rsplit("::").next().unwrap();
+    json!({
+        "code_type": code_type,
+        "vertex_num": vertices.len(),
+        "edge_num": edges.len(),
+        "p": edges.iter().map(|edge| edge.p).fold(0., f64::max),
+        "pe": edges.iter().map(|edge| edge.pe).fold(0., f64::max),
+        "max_half_weight": edges.iter().map(|edge| edge.half_weight).max().unwrap_or(0),
+    })
+}
+
+/// [`ExampleCode::run_metadata`] of the built-in codes, which all have `d + 1` vertices for every two virtual vertices,
+/// so the code distance and the number of rounds are recovered from the numbers of vertices
+#[allow(clippy::unnecessary_cast)]
+fn built_in_code_run_metadata<T: ExampleCode>(
+    code: &T,
+    virtual_num_per_round: impl Fn(VertexNum) -> VertexNum,
+    has_noisy_measurements: bool,
+) -> serde_json::Value {
+    let (vertices, _edges) = code.immutable_vertices_edges();
+    let vertex_num = vertices.len() as VertexNum;
+    let virtual_num = vertices.iter().filter(|vertex| vertex.is_virtual).count() as VertexNum;
+    let d = 2 * vertex_num / virtual_num - 1;
+    let mut metadata = example_code_run_metadata(code);
+    metadata["d"] = json!(d);
+    if has_noisy_measurements {
+        metadata["noisy_measurements"] = json!(virtual_num / virtual_num_per_round(d) - 1);
+    }
+    metadata
 }
 
 #[cfg(feature = "python_binding")]
@@ -425,21 +898,78 @@ macro_rules! bind_trait_example_code {
             fn trait_get_positions(&self) -> Vec<VisualizePosition> {
                 self.get_positions()
             }
+            #[pyo3(name = "positions_bounding_box")]
+            fn trait_positions_bounding_box(&self) -> ((f64, f64, f64), (f64, f64, f64)) {
+                self.positions_bounding_box()
+            }
+            #[pyo3(name = "normalize_positions")]
+            fn trait_normalize_positions(&mut self, target_extent: f64) {
+                self.normalize_positions(target_extent)
+            }
+            #[pyo3(name = "logical_boundaries")]
+            fn trait_logical_boundaries(&self) -> (Vec<VertexIndex>, Vec<VertexIndex>) {
+                self.logical_boundaries()
+            }
+            #[pyo3(name = "virtual_vertex_groups")]
+            fn trait_virtual_vertex_groups(&self) -> Option<Vec<Vec<VertexIndex>>> {
+                self.virtual_vertex_groups()
+            }
+            #[pyo3(name = "min_boundary_distance")]
+            fn trait_min_boundary_distance(&self) -> (usize, Weight) {
+                self.min_boundary_distance()
+            }
+            #[pyo3(name = "edges_of_vertex")]
+            fn trait_edges_of_vertex(&self, vertex_index: VertexIndex) -> Vec<EdgeIndex> {
+                self.edges_of_vertex(vertex_index).to_vec()
+            }
+            #[pyo3(name = "edge_between")]
+            fn trait_edge_between(&self, a: VertexIndex, b: VertexIndex) -> Option<EdgeIndex> {
+                self.edge_between(a, b)
+            }
             #[pyo3(name = "get_initializer")]
             fn trait_get_initializer(&self) -> SolverInitializer {
                 self.get_initializer()
             }
             #[pyo3(name = "set_defect_vertices")]
-            fn trait_set_defect_vertices(&mut self, defect_vertices: Vec<VertexIndex>) {
+            fn trait_set_defect_vertices(&mut self, defect_vertices: Vec<VertexIndex>) -> PyResult<()> {
                 self.set_defect_vertices(&defect_vertices)
+                    .map_err(PyValueError::new_err)
             }
             #[pyo3(name = "set_erasures")]
-            fn trait_set_erasures(&mut self, erasures: Vec<EdgeIndex>) {
-                self.set_erasures(&erasures)
+            fn trait_set_erasures(&mut self, erasures: Vec<EdgeIndex>) -> PyResult<()> {
+                self.set_erasures(&erasures).map_err(PyValueError::new_err)
+            }
+            /// the predicate is called with the two endpoint positions of each edge
+            #[pyo3(name = "set_erasure_region")]
+            fn trait_set_erasure_region(&mut self, py: Python, predicate: PyObject) -> PyResult<usize> {
+                let error = std::cell::RefCell::new(None);
+                let count = self.set_erasure_region(|p1, p2| {
+                    if error.borrow().is_some() {
+                        return false;
+                    }
+                    match predicate
+                        .call1(py, (p1.clone(), p2.clone()))
+                        .and_then(|result| result.extract::<bool>(py))
+                    {
+                        Ok(value) => value,
+                        Err(err) => {
+                            *error.borrow_mut() = Some(err);
+                            false
+                        }
+                    }
+                });
+                match error.into_inner() {
+                    Some(err) => Err(err),
+                    None => Ok(count),
+                }
+            }
+            #[pyo3(name = "clear_erasures")]
+            fn trait_clear_erasures(&mut self) {
+                self.clear_erasures()
             }
             #[pyo3(name = "set_syndrome")]
-            fn trait_set_syndrome(&mut self, syndrome_pattern: &SyndromePattern) {
-                self.set_syndrome(syndrome_pattern)
+            fn trait_set_syndrome(&mut self, syndrome_pattern: &SyndromePattern) -> PyResult<()> {
+                self.set_syndrome(syndrome_pattern).map_err(PyValueError::new_err)
             }
             #[pyo3(name = "get_defect_vertices")]
             fn trait_get_defect_vertices(&self) -> Vec<VertexIndex> {
@@ -474,13 +1004,18 @@ macro_rules! bind_trait_example_code {
                 self.is_defect(vertex_idx)
             }
             #[pyo3(name = "reorder_vertices")]
-            fn trait_reorder_vertices(&mut self, sequential_vertices: Vec<VertexIndex>) {
+            fn trait_reorder_vertices(&mut self, sequential_vertices: Vec<VertexIndex>) -> PyResult<VertexPermutation> {
                 self.reorder_vertices(&sequential_vertices)
+                    .map_err(PyValueError::new_err)
             }
             #[pyo3(name = "snapshot", signature = (abbrev=true))]
             fn trait_snapshot(&mut self, abbrev: bool) -> PyObject {
                 json_to_pyobject(self.snapshot(abbrev))
             }
+            #[pyo3(name = "run_metadata")]
+            fn trait_run_metadata(&self) -> PyObject {
+                json_to_pyobject(self.run_metadata())
+            }
         }
     };
 }
@@ -504,6 +1039,7 @@ where
                 if abbrev { "w" } else { "weight" }: edge.half_weight * 2,
                 if abbrev { "l" } else { "left" }: edge.vertices.0,
                 if abbrev { "r" } else { "right" }: edge.vertices.1,
+                if abbrev { "e" } else { "is_erasure" }: i32::from(edge.is_erasure),
                 // code itself is not capable of calculating growth
             }));
         }
@@ -534,21 +1070,32 @@ impl ExampleCode for CodeCapacityRepetitionCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn run_metadata(&self) -> serde_json::Value {
+        built_in_code_run_metadata(self, |_| 2, false)
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CodeCapacityRepetitionCode}
 
+impl CodeCapacityRepetitionCode {
+    /// panics if the parameters are invalid, see [`Self::try_new`]
+    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        or_panic(Self::try_new(d, p, max_half_weight))
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeCapacityRepetitionCode {
     #[cfg_attr(feature = "python_binding", new)]
     #[cfg_attr(feature = "python_binding", pyo3(signature = (d, p, max_half_weight = 500)))]
-    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+    pub fn try_new(d: VertexNum, p: f64, max_half_weight: Weight) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p, max_half_weight)?;
         let mut code = Self::create_code(d);
         code.set_probability(p);
         code.compute_weights(max_half_weight);
-        code
+        Ok(code)
     }
 
     #[cfg_attr(feature = "python_binding", staticmethod)]
@@ -603,21 +1150,32 @@ impl ExampleCode for CodeCapacityPlanarCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn run_metadata(&self) -> serde_json::Value {
+        built_in_code_run_metadata(self, |d| 2 * d, false)
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CodeCapacityPlanarCode}
 
+impl CodeCapacityPlanarCode {
+    /// panics if the parameters are invalid, see [`Self::try_new`]
+    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        or_panic(Self::try_new(d, p, max_half_weight))
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeCapacityPlanarCode {
     #[cfg_attr(feature = "python_binding", new)]
     #[cfg_attr(feature = "python_binding", pyo3(signature = (d, p, max_half_weight = 500)))]
-    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+    pub fn try_new(d: VertexNum, p: f64, max_half_weight: Weight) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p, max_half_weight)?;
         let mut code = Self::create_code(d);
         code.set_probability(p);
         code.compute_weights(max_half_weight);
-        code
+        Ok(code)
     }
 
     #[cfg_attr(feature = "python_binding", staticmethod)]
@@ -666,12 +1224,12 @@ impl CodeCapacityPlanarCode {
     }
 }
 
-/// phenomenological noise model is multiple measurement rounds adding only measurement errors
-/// e.g. this is the decoding graph of a CSS surface code (standard one, not rotated one) with X-type stabilizers
+/// code capacity noise model on a toric code, i.e. a `d` by `d` periodic lattice where every vertex connects to its right
+/// and lower neighbors; there is no virtual vertex, so a valid syndrome always has an even number of defects
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
-pub struct PhenomenologicalPlanarCode {
+pub struct CodeCapacityToricCode {
     /// vertices in the code
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
     pub vertices: Vec<CodeVertex>,
@@ -680,80 +1238,200 @@ pub struct PhenomenologicalPlanarCode {
     pub edges: Vec<CodeEdge>,
 }
 
-impl ExampleCode for PhenomenologicalPlanarCode {
+impl ExampleCode for CodeCapacityToricCode {
     fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
         (&mut self.vertices, &mut self.edges)
     }
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    /// the difference is a logical operator if it winds around the torus, i.e. it crosses the periodic boundary of
+    /// either direction an odd number of times
+    #[allow(clippy::unnecessary_cast)]
+    fn is_logical_error(&self, error_edges: &[EdgeIndex], correction_edges: &[EdgeIndex]) -> bool {
+        let ((min_i, min_j, _), (max_i, max_j, _)) = self.positions_bounding_box();
+        let mut difference = vec![false; self.edges.len()];
+        for &edge_index in error_edges.iter().chain(correction_edges.iter()) {
+            difference[edge_index as usize] ^= true;
+        }
+        let (mut parity_i, mut parity_j) = (false, false);
+        for (edge, _) in self
+            .edges
+            .iter()
+            .zip(difference.iter())
+            .filter(|(_, &is_different)| is_different)
+        {
+            let (p1, p2) = (
+                &self.vertices[edge.vertices.0 as usize].position,
+                &self.vertices[edge.vertices.1 as usize].position,
+            );
+            // neighbors are one lattice spacing apart except for the ones across the periodic boundary
+            parity_i ^= (p1.i - p2.i).abs() > (max_i - min_i) / 2.;
+            parity_j ^= (p1.j - p2.j).abs() > (max_j - min_j) / 2.;
+        }
+        parity_i || parity_j
+    }
+    fn run_metadata(&self) -> serde_json::Value {
+        let mut metadata = example_code_run_metadata(self);
+        metadata["d"] = json!((self.vertices.len() as f64).sqrt().round() as VertexNum);
+        metadata
+    }
 }
 
 #[cfg(feature = "python_binding")]
-bind_trait_example_code! {PhenomenologicalPlanarCode}
+bind_trait_example_code! {CodeCapacityToricCode}
+
+impl CodeCapacityToricCode {
+    /// panics if the parameters are invalid, see [`Self::try_new`]
+    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        or_panic(Self::try_new(d, p, max_half_weight))
+    }
+}
 
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
-impl PhenomenologicalPlanarCode {
+impl CodeCapacityToricCode {
     #[cfg_attr(feature = "python_binding", new)]
-    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
-    pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
-        let mut code = Self::create_code(d, noisy_measurements);
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, p, max_half_weight = 500)))]
+    pub fn try_new(d: VertexNum, p: f64, max_half_weight: Weight) -> Result<Self, FusionError> {
+        check_code_parameters(d, false, p, max_half_weight)?;
+        let mut code = Self::create_code(d);
         code.set_probability(p);
         code.compute_weights(max_half_weight);
-        code
+        Ok(code)
     }
 
     #[cfg_attr(feature = "python_binding", staticmethod)]
     #[allow(clippy::unnecessary_cast)]
-    pub fn create_code(d: VertexNum, noisy_measurements: VertexNum) -> Self {
-        assert!(d >= 3 && d % 2 == 1, "d must be odd integer >= 3");
-        let row_vertex_num = (d - 1) + 2; // two virtual nodes at left and right
-        let t_vertex_num = row_vertex_num * d; // `d` rows
-        let td = noisy_measurements + 1; // a perfect measurement round is capped at the end
-        let vertex_num = t_vertex_num * td; // `td` layers
-                                            // create edges
+    pub fn create_code(d: VertexNum) -> Self {
+        assert!(d >= 3, "d must be integer >= 3");
+        let vertex_num = d * d;
+        let index = |row: VertexNum, column: VertexNum| (row % d) * d + column % d;
         let mut edges = Vec::new();
-        for t in 0..td {
-            let t_bias = t * t_vertex_num;
-            for row in 0..d {
-                let bias = t_bias + row * row_vertex_num;
-                for i in 0..d - 1 {
-                    edges.push(CodeEdge::new(bias + i, bias + i + 1));
-                }
-                edges.push(CodeEdge::new(bias, bias + d)); // left most edge
-                if row + 1 < d {
-                    for i in 0..d - 1 {
-                        edges.push(CodeEdge::new(bias + i, bias + i + row_vertex_num));
-                    }
-                }
-            }
-            // inter-layer connection
-            if t + 1 < td {
-                for row in 0..d {
-                    let bias = t_bias + row * row_vertex_num;
-                    for i in 0..d - 1 {
-                        edges.push(CodeEdge::new(bias + i, bias + i + t_vertex_num));
-                    }
-                }
+        for row in 0..d {
+            for column in 0..d {
+                edges.push(CodeEdge::new(index(row, column), index(row, column + 1)));
+                edges.push(CodeEdge::new(index(row, column), index(row + 1, column)));
             }
         }
         let mut code = Self {
             vertices: Vec::new(),
             edges,
         };
-        // create vertices
         code.fill_vertices(vertex_num);
-        for t in 0..td {
-            let t_bias = t * t_vertex_num;
-            for row in 0..d {
-                let bias = t_bias + row * row_vertex_num;
-                code.vertices[(bias + d - 1) as usize].is_virtual = true;
-                code.vertices[(bias + d) as usize].is_virtual = true;
+        for row in 0..d {
+            for column in 0..d {
+                code.vertices[index(row, column) as usize].position = VisualizePosition::new(row as f64, column as f64, 0.);
             }
         }
-        let mut positions = Vec::new();
-        for t in 0..td {
+        code
+    }
+}
+
+/// phenomenological noise model is multiple measurement rounds adding only measurement errors
+/// e.g. this is the decoding graph of a CSS surface code (standard one, not rotated one) with X-type stabilizers
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct PhenomenologicalPlanarCode {
+    /// vertices in the code
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub vertices: Vec<CodeVertex>,
+    /// nearest-neighbor edges in the decoding graph
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub edges: Vec<CodeEdge>,
+}
+
+impl ExampleCode for PhenomenologicalPlanarCode {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        (&mut self.vertices, &mut self.edges)
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        (&self.vertices, &self.edges)
+    }
+    fn run_metadata(&self) -> serde_json::Value {
+        built_in_code_run_metadata(self, |d| 2 * d, true)
+    }
+}
+
+#[cfg(feature = "python_binding")]
+bind_trait_example_code! {PhenomenologicalPlanarCode}
+
+impl PhenomenologicalPlanarCode {
+    /// panics if the parameters are invalid, see [`Self::try_new`]
+    pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        or_panic(Self::try_new(d, noisy_measurements, p, max_half_weight))
+    }
+
+    /// panics if the parameters are invalid, see [`Self::try_new_asymmetric`]
+    pub fn new_asymmetric(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p_data: f64,
+        p_measurement: f64,
+        max_half_weight: Weight,
+    ) -> Self {
+        or_panic(Self::try_new_asymmetric(
+            d,
+            noisy_measurements,
+            p_data,
+            p_measurement,
+            max_half_weight,
+        ))
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn create_code_with_layers(d: VertexNum, noisy_measurements: VertexNum) -> (Self, Vec<EdgeLayer>) {
+        assert!(d >= 3 && d % 2 == 1, "d must be odd integer >= 3");
+        let row_vertex_num = (d - 1) + 2; // two virtual nodes at left and right
+        let t_vertex_num = row_vertex_num * d; // `d` rows
+        let td = noisy_measurements + 1; // a perfect measurement round is capped at the end
+        let vertex_num = t_vertex_num * td; // `td` layers
+                                            // create edges
+        let mut edges = Vec::new();
+        let mut layers = Vec::new();
+        for t in 0..td {
+            let t_bias = t * t_vertex_num;
+            for row in 0..d {
+                let bias = t_bias + row * row_vertex_num;
+                for i in 0..d - 1 {
+                    edges.push(CodeEdge::new(bias + i, bias + i + 1));
+                }
+                edges.push(CodeEdge::new(bias, bias + d)); // left most edge
+                if row + 1 < d {
+                    for i in 0..d - 1 {
+                        edges.push(CodeEdge::new(bias + i, bias + i + row_vertex_num));
+                    }
+                }
+            }
+            layers.resize(edges.len(), EdgeLayer::Intra);
+            // inter-layer connection
+            if t + 1 < td {
+                for row in 0..d {
+                    let bias = t_bias + row * row_vertex_num;
+                    for i in 0..d - 1 {
+                        edges.push(CodeEdge::new(bias + i, bias + i + t_vertex_num));
+                    }
+                }
+            }
+            layers.resize(edges.len(), EdgeLayer::Inter);
+        }
+        let mut code = Self {
+            vertices: Vec::new(),
+            edges,
+        };
+        // create vertices
+        code.fill_vertices(vertex_num);
+        for t in 0..td {
+            let t_bias = t * t_vertex_num;
+            for row in 0..d {
+                let bias = t_bias + row * row_vertex_num;
+                code.vertices[(bias + d - 1) as usize].is_virtual = true;
+                code.vertices[(bias + d) as usize].is_virtual = true;
+            }
+        }
+        let mut positions = Vec::new();
+        for t in 0..td {
             let pos_t = t as f64;
             for row in 0..d {
                 let pos_i = row as f64;
@@ -766,7 +1444,52 @@ impl PhenomenologicalPlanarCode {
         for (i, position) in positions.into_iter().enumerate() {
             code.vertices[i].position = position;
         }
-        code
+        (code, layers)
+    }
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl PhenomenologicalPlanarCode {
+    #[cfg_attr(feature = "python_binding", new)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
+    pub fn try_new(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p: f64,
+        max_half_weight: Weight,
+    ) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p, max_half_weight)?;
+        let mut code = Self::create_code(d, noisy_measurements);
+        code.set_probability(p);
+        code.compute_weights(max_half_weight);
+        Ok(code)
+    }
+
+    /// `p_data` applies to the edges within a measurement round and `p_measurement` to the edges between two
+    /// consecutive rounds
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p_data, p_measurement, max_half_weight = 500)))]
+    pub fn try_new_asymmetric(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p_data: f64,
+        p_measurement: f64,
+        max_half_weight: Weight,
+    ) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p_data, max_half_weight)?;
+        check_code_parameters(d, true, p_measurement, max_half_weight)?;
+        let (mut code, layers) = Self::create_code_with_layers(d, noisy_measurements);
+        for (edge, layer) in code.edges.iter_mut().zip(layers) {
+            edge.p = if layer == EdgeLayer::Intra { p_data } else { p_measurement };
+        }
+        code.compute_weights(max_half_weight);
+        Ok(code)
+    }
+
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    pub fn create_code(d: VertexNum, noisy_measurements: VertexNum) -> Self {
+        Self::create_code_with_layers(d, noisy_measurements).0
     }
 }
 
@@ -791,52 +1514,39 @@ impl ExampleCode for CircuitLevelPlanarCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn run_metadata(&self) -> serde_json::Value {
+        built_in_code_run_metadata(self, |d| 2 * d, true)
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CircuitLevelPlanarCode}
 
-#[cfg_attr(feature = "python_binding", cfg_eval)]
-#[cfg_attr(feature = "python_binding", pymethods)]
 impl CircuitLevelPlanarCode {
-    /// by default diagonal edge has error rate p/3 to mimic the behavior of unequal weights
-    #[cfg_attr(feature = "python_binding", new)]
-    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
+    /// panics if the parameters are invalid, see [`Self::try_new`]
     pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
-        Self::new_diagonal(d, noisy_measurements, p, max_half_weight, Some(p / 3.))
+        or_panic(Self::try_new(d, noisy_measurements, p, max_half_weight))
     }
 
-    #[cfg_attr(feature = "python_binding", staticmethod)]
-    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500, diagonal_p = None)))]
-    #[allow(clippy::unnecessary_cast)]
-    pub fn new_diagonal(
+    /// panics if the parameters are invalid, see [`Self::try_new_asymmetric`]
+    pub fn new_asymmetric(
         d: VertexNum,
         noisy_measurements: VertexNum,
-        p: f64,
+        p_data: f64,
+        p_measurement: f64,
         max_half_weight: Weight,
-        diagonal_p: Option<f64>,
     ) -> Self {
-        let mut code = Self::create_code(d, noisy_measurements);
-        code.set_probability(p);
-        if let Some(diagonal_p) = diagonal_p {
-            let (vertices, edges) = code.vertices_edges();
-            for edge in edges.iter_mut() {
-                let (v1, v2) = edge.vertices;
-                let v1p = &vertices[v1 as usize].position;
-                let v2p = &vertices[v2 as usize].position;
-                let manhattan_distance = (v1p.i - v2p.i).abs() + (v1p.j - v2p.j).abs() + (v1p.t - v2p.t).abs();
-                if manhattan_distance > 1. {
-                    edge.p = diagonal_p;
-                }
-            }
-        }
-        code.compute_weights(max_half_weight);
-        code
+        or_panic(Self::try_new_asymmetric(
+            d,
+            noisy_measurements,
+            p_data,
+            p_measurement,
+            max_half_weight,
+        ))
     }
 
-    #[cfg_attr(feature = "python_binding", staticmethod)]
     #[allow(clippy::unnecessary_cast)]
-    pub fn create_code(d: VertexNum, noisy_measurements: VertexNum) -> Self {
+    fn create_code_with_layers(d: VertexNum, noisy_measurements: VertexNum) -> (Self, Vec<EdgeLayer>) {
         assert!(d >= 3 && d % 2 == 1, "d must be odd integer >= 3");
         let row_vertex_num = (d - 1) + 2; // two virtual nodes at left and right
         let t_vertex_num = row_vertex_num * d; // `d` rows
@@ -844,6 +1554,7 @@ impl CircuitLevelPlanarCode {
         let vertex_num = t_vertex_num * td; // `td` layers
                                             // create edges
         let mut edges = Vec::new();
+        let mut layers = Vec::new();
         for t in 0..td {
             let t_bias = t * t_vertex_num;
             for row in 0..d {
@@ -858,12 +1569,14 @@ impl CircuitLevelPlanarCode {
                     }
                 }
             }
+            layers.resize(edges.len(), EdgeLayer::Intra);
             // inter-layer connection
             if t + 1 < td {
                 for row in 0..d {
                     let bias = t_bias + row * row_vertex_num;
                     for i in 0..d - 1 {
                         edges.push(CodeEdge::new(bias + i, bias + i + t_vertex_num));
+                        layers.push(EdgeLayer::Inter);
                         let diagonal_diffs: Vec<(isize, isize)> = vec![(0, 1), (1, 0), (1, 1)];
                         for (di, dj) in diagonal_diffs {
                             let new_row = row as isize + di; // row corresponds to `i`
@@ -871,6 +1584,7 @@ impl CircuitLevelPlanarCode {
                             if new_row >= 0 && new_i >= 0 && new_row < d as isize && new_i < (d - 1) as isize {
                                 let new_bias = t_bias + (new_row as VertexNum) * row_vertex_num + t_vertex_num;
                                 edges.push(CodeEdge::new(bias + i, new_bias + new_i as VertexNum));
+                                layers.push(EdgeLayer::Diagonal);
                             }
                         }
                     }
@@ -905,8 +1619,81 @@ impl CircuitLevelPlanarCode {
         for (i, position) in positions.into_iter().enumerate() {
             code.vertices[i].position = position;
         }
+        (code, layers)
+    }
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl CircuitLevelPlanarCode {
+    /// by default diagonal edge has error rate p/3 to mimic the behavior of unequal weights
+    #[cfg_attr(feature = "python_binding", new)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
+    pub fn try_new(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p: f64,
+        max_half_weight: Weight,
+    ) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p, max_half_weight)?;
+        Ok(Self::new_diagonal(d, noisy_measurements, p, max_half_weight, Some(p / 3.)))
+    }
+
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500, diagonal_p = None)))]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_diagonal(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p: f64,
+        max_half_weight: Weight,
+        diagonal_p: Option<f64>,
+    ) -> Self {
+        let mut code = Self::create_code(d, noisy_measurements);
+        code.set_probability(p);
+        if let Some(diagonal_p) = diagonal_p {
+            let diagonal_edges: Vec<EdgeIndex> = code
+                .iter_edges_with_positions()
+                .filter(|(_, _, v1p, v2p)| (v1p.i - v2p.i).abs() + (v1p.j - v2p.j).abs() + (v1p.t - v2p.t).abs() > 1.)
+                .map(|(edge_index, ..)| edge_index)
+                .collect();
+            for edge_index in diagonal_edges {
+                code.edges[edge_index as usize].p = diagonal_p;
+            }
+        }
+        code.compute_weights(max_half_weight);
         code
     }
+
+    /// `p_data` applies to the edges within a measurement round, `p_measurement` to the edges between the same
+    /// stabilizer in two consecutive rounds, and `p_data / 3` to the diagonal edges like in [`Self::try_new`]
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p_data, p_measurement, max_half_weight = 500)))]
+    pub fn try_new_asymmetric(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p_data: f64,
+        p_measurement: f64,
+        max_half_weight: Weight,
+    ) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p_data, max_half_weight)?;
+        check_code_parameters(d, true, p_measurement, max_half_weight)?;
+        let (mut code, layers) = Self::create_code_with_layers(d, noisy_measurements);
+        for (edge, layer) in code.edges.iter_mut().zip(layers) {
+            edge.p = match layer {
+                EdgeLayer::Intra => p_data,
+                EdgeLayer::Inter => p_measurement,
+                EdgeLayer::Diagonal => p_data / 3.,
+            };
+        }
+        code.compute_weights(max_half_weight);
+        Ok(code)
+    }
+
+    #[cfg_attr(feature = "python_binding", staticmethod)]
+    pub fn create_code(d: VertexNum, noisy_measurements: VertexNum) -> Self {
+        Self::create_code_with_layers(d, noisy_measurements).0
+    }
 }
 
 /// CSS surface code (the rotated one) with X-type stabilizers
@@ -929,21 +1716,32 @@ impl ExampleCode for CodeCapacityRotatedCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn run_metadata(&self) -> serde_json::Value {
+        built_in_code_run_metadata(self, |d| d + 1, false)
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {CodeCapacityRotatedCode}
 
+impl CodeCapacityRotatedCode {
+    /// panics if the parameters are invalid, see [`Self::try_new`]
+    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        or_panic(Self::try_new(d, p, max_half_weight))
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl CodeCapacityRotatedCode {
     #[cfg_attr(feature = "python_binding", new)]
     #[cfg_attr(feature = "python_binding", pyo3(signature = (d, p, max_half_weight = 500)))]
-    pub fn new(d: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+    pub fn try_new(d: VertexNum, p: f64, max_half_weight: Weight) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p, max_half_weight)?;
         let mut code = Self::create_code(d);
         code.set_probability(p);
         code.compute_weights(max_half_weight);
-        code
+        Ok(code)
     }
 
     #[cfg_attr(feature = "python_binding", staticmethod)]
@@ -1023,21 +1821,37 @@ impl ExampleCode for PhenomenologicalRotatedCode {
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         (&self.vertices, &self.edges)
     }
+    fn run_metadata(&self) -> serde_json::Value {
+        built_in_code_run_metadata(self, |d| d + 1, true)
+    }
 }
 
 #[cfg(feature = "python_binding")]
 bind_trait_example_code! {PhenomenologicalRotatedCode}
 
+impl PhenomenologicalRotatedCode {
+    /// panics if the parameters are invalid, see [`Self::try_new`]
+    pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+        or_panic(Self::try_new(d, noisy_measurements, p, max_half_weight))
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PhenomenologicalRotatedCode {
     #[cfg_attr(feature = "python_binding", new)]
     #[cfg_attr(feature = "python_binding", pyo3(signature = (d, noisy_measurements, p, max_half_weight = 500)))]
-    pub fn new(d: VertexNum, noisy_measurements: VertexNum, p: f64, max_half_weight: Weight) -> Self {
+    pub fn try_new(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        p: f64,
+        max_half_weight: Weight,
+    ) -> Result<Self, FusionError> {
+        check_code_parameters(d, true, p, max_half_weight)?;
         let mut code = Self::create_code(d, noisy_measurements);
         code.set_probability(p);
         code.compute_weights(max_half_weight);
-        code
+        Ok(code)
     }
 
     #[cfg_attr(feature = "python_binding", staticmethod)]
@@ -1117,177 +1931,2157 @@ impl PhenomenologicalRotatedCode {
     }
 }
 
-/// read from file, including the error patterns;
-/// the point is to avoid bad cache performance, because generating random error requires iterating over a large memory space,
-/// invalidating all cache. also, this can reduce the time of decoding by prepare the data before hand and could be shared between
-/// different partition configurations
-#[cfg_attr(feature = "python_binding", cfg_eval)]
-#[cfg_attr(feature = "python_binding", pyclass)]
-pub struct ErrorPatternReader {
-    /// vertices in the code
-    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
-    pub vertices: Vec<CodeVertex>,
-    /// nearest-neighbor edges in the decoding graph
-    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
-    pub edges: Vec<CodeEdge>,
-    /// pre-generated syndrome patterns
-    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
-    pub syndrome_patterns: Vec<SyndromePattern>,
-    /// cursor of current errors
-    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
-    pub defect_index: usize,
-    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
-    pub cyclic_syndrome: bool,
+/// the first line of a syndrome pattern file, followed by one json line for each of the initializer, the positions and
+/// every syndrome pattern
+pub const SYNDROME_FILE_HEADER: &str = "Syndrome Pattern v1.0   <initializer> <positions> <syndrome_pattern>*";
+/// the same as [`SYNDROME_FILE_HEADER`] except that every syndrome pattern carries its ground-truth `error_edges`
+pub const SYNDROME_FILE_ERROR_EDGES_HEADER: &str =
+    "Syndrome Pattern v1.1   <initializer> <positions> <syndrome_pattern_with_error_edges>*";
+/// the first line of a syndrome pattern file in bincode, followed by the bincode-encoded initializer, positions and
+/// a `(syndrome_pattern, error_edges)` record for every syndrome pattern until the end of file
+pub const SYNDROME_FILE_BINCODE_HEADER: &str = "Syndrome Pattern v2.1 bincode";
+/// the same as [`SYNDROME_FILE_BINCODE_HEADER`] except that the initializer has no
+/// [`SolverInitializer::virtual_vertex_groups`], written by older versions and still readable
+pub const SYNDROME_FILE_BINCODE_V2_0_HEADER: &str = "Syndrome Pattern v2.0 bincode";
+/// the last line of a finalized syndrome pattern file in any format, followed by the number of syndrome patterns, see
+/// [`SyndromeFileWriter::finalize`]; its first 8 bytes are never the beginning of a bincode syndrome pattern, because
+/// they would be an absurdly long list of defect vertices
+pub const SYNDROME_FILE_FOOTER: &str = "Syndrome Pattern End";
+
+/// the json line of a syndrome pattern, optionally with the ground-truth error edges that cause it
+pub fn syndrome_pattern_line(syndrome_pattern: &SyndromePattern, error_edges: Option<&[EdgeIndex]>) -> String {
+    let mut value = json!(syndrome_pattern);
+    if let Some(error_edges) = error_edges {
+        value
+            .as_object_mut()
+            .unwrap()
+            .insert("error_edges".to_string(), json!(error_edges));
+    }
+    serde_json::to_string(&value).unwrap()
 }
 
-impl ExampleCode for ErrorPatternReader {
-    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
-        (&mut self.vertices, &mut self.edges)
+/// the seed of the `shot`-th syndrome pattern, independent of how the shots are distributed among threads; the same as
+/// [`ShotSeeder::seed_for`]
+pub fn shot_seed(seed: u64, shot: u64) -> u64 {
+    ShotSeeder::new(seed).seed_for(shot)
+}
+
+/// generates the syndrome patterns of many shots in parallel, where each thread takes a contiguous range of shots with
+/// its own code from `build_code`; shot `i` always uses the seed [`ShotSeeder::seed_for`]`(i)` regardless of the number
+/// of threads, which is also the case for each worker of an [`ExampleCodeParallel`] with [`Seeding::V2`]
+#[cfg(not(feature = "minimal"))]
+pub struct SyndromeGenerator<F: Fn() -> Box<dyn ExampleCode> + Sync> {
+    build_code: F,
+    threads: usize,
+    /// also record the ground-truth error edges of every syndrome pattern
+    include_error_edges: bool,
+    progress_callback: Option<ProgressCallback>,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<F: Fn() -> Box<dyn ExampleCode> + Sync> SyndromeGenerator<F> {
+    pub fn new(build_code: F, threads: usize, include_error_edges: bool) -> Self {
+        assert!(threads > 0, "at least one thread is required");
+        Self {
+            build_code,
+            threads,
+            include_error_edges,
+            progress_callback: None,
+        }
     }
-    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
-        (&self.vertices, &self.edges)
+
+    /// called from the worker threads after every generated syndrome pattern
+    pub fn set_progress_callback(&mut self, callback: ProgressCallback) {
+        self.progress_callback = Some(callback);
     }
-    fn generate_random_errors(&mut self, _seed: u64) -> SyndromePattern {
-        if self.cyclic_syndrome {
-            if self.defect_index >= self.syndrome_patterns.len() {
-                self.defect_index = 0; // cyclic
-            }
-        } else {
-            assert!(
-                self.defect_index < self.syndrome_patterns.len(),
-                "reading syndrome pattern more than in the file, consider generate the file with more data points"
-            );
-        }
-        let syndrome_pattern = self.syndrome_patterns[self.defect_index].clone();
-        self.defect_index += 1;
-        syndrome_pattern
+
+    /// the syndrome patterns of the shots `0..shots` in order
+    pub fn generate(&mut self, shots: usize, seed: u64) -> Vec<SyndromeRecord> {
+        let progress = ProgressReporter::new(self.progress_callback.take(), shots, String::new());
+        let seeder = ShotSeeder::new(seed);
+        let chunk_size = shots.div_ceil(self.threads);
+        let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(self.threads).build().unwrap();
+        let chunks: Vec<Vec<SyndromeRecord>> = thread_pool.install(|| {
+            (0..self.threads)
+                .into_par_iter()
+                .map(|thread_index| {
+                    let mut code = (self.build_code)();
+                    let shot_range = (thread_index * chunk_size).min(shots)..((thread_index + 1) * chunk_size).min(shots);
+                    shot_range
+                        .map(|shot| {
+                            let seed = seeder.seed_for(shot as u64);
+                            let record = if self.include_error_edges {
+                                let (syndrome_pattern, error_edges) = code.generate_random_errors_with_edges(seed);
+                                (syndrome_pattern, Some(error_edges))
+                            } else {
+                                (code.generate_random_errors(seed), None)
+                            };
+                            progress.complete_one();
+                            record
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+        self.progress_callback = progress.into_callback();
+        chunks.into_iter().flatten().collect()
     }
 }
 
-impl ErrorPatternReader {
-    #[allow(clippy::unnecessary_cast)]
-    pub fn new(mut config: serde_json::Value) -> Self {
-        let mut filename = "tmp/syndrome_patterns.txt".to_string();
-        let config = config.as_object_mut().expect("config must be JSON object");
-        if let Some(value) = config.remove("filename") {
-            filename = value.as_str().expect("filename string").to_string();
-        }
-        let cyclic_syndrome = if let Some(cyclic_syndrome) = config.remove("cyclic_syndrome") {
-            cyclic_syndrome.as_bool().expect("cyclic_syndrome: bool")
-        } else {
-            false
+#[cfg(not(feature = "minimal"))]
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// a syndrome pattern together with its ground-truth error edges, if recorded
+pub type SyndromeRecord = (SyndromePattern, Option<Vec<EdgeIndex>>);
+
+/// reads a syndrome pattern file one syndrome pattern at a time, so that arbitrarily large files can be processed
+/// in bounded memory; both the json lines and the bincode formats are detected from the header
+#[cfg(not(feature = "minimal"))]
+pub struct SyndromeFileReader<R: BufRead> {
+    pub initializer: SolverInitializer,
+    pub positions: Vec<VisualizePosition>,
+    /// whether the file is in bincode rather than json lines
+    pub bincode: bool,
+    /// whether the header declares that every syndrome pattern carries its ground-truth error edges; the records of a
+    /// bincode file may carry them regardless
+    pub error_edges: bool,
+    /// whether the file is gzip compressed, in which case [`SyndromeFileReader::offset`] is in the decompressed content
+    pub compressed: bool,
+    /// the number of syndrome patterns read so far
+    pub read_count: usize,
+    /// the number of syndrome patterns recorded in the footer, once it's read
+    pub footer: Option<usize>,
+    /// whether the file ends with an incomplete syndrome pattern, e.g. when the writer is interrupted; all the
+    /// syndrome patterns before it are still read
+    pub truncated: bool,
+    reader: CountingReader<R>,
+    line: String,
+    ended: bool,
+}
+
+/// counts the bytes consumed from the inner reader
+#[cfg(not(feature = "minimal"))]
+struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        self.count += length as u64;
+        Ok(length)
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.count += amount as u64;
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<R: BufRead> SyndromeFileReader<R> {
+    pub fn new(reader: R) -> io::Result<Self> {
+        trace_span!("syndrome_file_read_header");
+        let mut reader = CountingReader { inner: reader, count: 0 };
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let legacy_bincode = header.trim_end() == SYNDROME_FILE_BINCODE_V2_0_HEADER;
+        let bincode = legacy_bincode || header.trim_end() == SYNDROME_FILE_BINCODE_HEADER;
+        let error_edges = header.starts_with("Syndrome Pattern v1.1 ");
+        let (initializer, positions) = if bincode {
+            let initializer = if legacy_bincode {
+                // bincode is not self-describing, so the missing field cannot take the default value by itself
+                let (vertex_num, weighted_edges, virtual_vertices) =
+                    bincode::deserialize_from(&mut reader).map_err(invalid_data)?;
+                SolverInitializer::new(vertex_num, weighted_edges, virtual_vertices)
+            } else {
+                bincode::deserialize_from(&mut reader).map_err(invalid_data)?
+            };
+            let positions = bincode::deserialize_from(&mut reader).map_err(invalid_data)?;
+            (initializer, positions)
+        } else {
+            if !header.starts_with("Syndrome Pattern v1.0 ") && !error_edges {
+                return Err(invalid_data("incompatible file version"));
+            }
+            let mut next_line = |name: &str| {
+                let mut line = String::new();
+                if reader.read_line(&mut line)? == 0 {
+                    return Err(invalid_data(format!("{name} not present in file")));
+                }
+                Ok(line)
+            };
+            let initializer = serde_json::from_str(&next_line("initializer")?)?;
+            let positions = serde_json::from_str(&next_line("positions")?)?;
+            (initializer, positions)
+        };
+        Ok(Self {
+            initializer,
+            positions,
+            bincode,
+            error_edges,
+            compressed: false,
+            read_count: 0,
+            footer: None,
+            truncated: false,
+            reader,
+            line: String::new(),
+            ended: false,
+        })
+    }
+
+    /// the position of the next syndrome pattern in the file, see [`read_syndrome_record`]
+    pub fn offset(&self) -> u64 {
+        self.reader.count
+    }
+
+    /// the next syndrome pattern, or `None` at the footer or the end of file; an incomplete syndrome pattern at the end
+    /// of file is reported by [`SyndromeFileReader::truncated`] instead of an error
+    fn read_record(&mut self) -> io::Result<Option<SyndromeRecord>> {
+        if self.ended {
+            return Ok(None);
+        }
+        trace_span!("syndrome_file_read", index = self.read_count);
+        let entry = match read_syndrome_entry(&mut self.reader, self.bincode, &mut self.line) {
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => SyndromeFileEntry::Truncated,
+            entry => entry?,
+        };
+        match entry {
+            SyndromeFileEntry::Record(record) => {
+                self.read_count += 1;
+                return Ok(Some(record));
+            }
+            SyndromeFileEntry::Footer(count) => {
+                self.footer = Some(count);
+                if count != self.read_count {
+                    return Err(invalid_data(format!(
+                        "the footer records {count} syndrome patterns but the file has {}",
+                        self.read_count
+                    )));
+                }
+            }
+            SyndromeFileEntry::Truncated => {
+                eprintln!(
+                    "[warning] the syndrome pattern file ends with an incomplete syndrome pattern after {} complete ones, probably written by an interrupted run",
+                    self.read_count
+                );
+                self.truncated = true;
+            }
+            SyndromeFileEntry::End => {}
+        }
+        self.ended = true;
+        Ok(None)
+    }
+}
+
+/// an entry in the body of a syndrome pattern file
+#[cfg(not(feature = "minimal"))]
+enum SyndromeFileEntry {
+    Record(SyndromeRecord),
+    /// the number of syndrome patterns in the footer
+    Footer(usize),
+    /// an incomplete syndrome pattern at the end of file
+    Truncated,
+    End,
+}
+
+#[cfg(not(feature = "minimal"))]
+fn parse_footer(line: &str) -> io::Result<usize> {
+    line.strip_prefix(SYNDROME_FILE_FOOTER)
+        .and_then(|count| count.trim().parse().ok())
+        .ok_or_else(|| invalid_data(format!("invalid footer: {}", line.trim_end())))
+}
+
+#[cfg(not(feature = "minimal"))]
+fn read_syndrome_entry(reader: &mut impl BufRead, bincode: bool, line: &mut String) -> io::Result<SyndromeFileEntry> {
+    line.clear();
+    if bincode {
+        // the first 8 bytes distinguish the footer from a syndrome pattern
+        let mut head = [0; 8];
+        let mut length = 0;
+        while length < head.len() {
+            match reader.read(&mut head[length..])? {
+                0 => break,
+                read => length += read,
+            }
+        }
+        if length == 0 {
+            return Ok(SyndromeFileEntry::End);
+        }
+        if length < head.len() {
+            return Ok(SyndromeFileEntry::Truncated);
+        }
+        if head == SYNDROME_FILE_FOOTER.as_bytes()[..head.len()] {
+            reader.read_line(line)?;
+            return parse_footer(&format!("{}{line}", String::from_utf8_lossy(&head))).map(SyndromeFileEntry::Footer);
+        }
+        return match bincode::deserialize_from((&head[..]).chain(reader)) {
+            Ok(record) => Ok(SyndromeFileEntry::Record(record)),
+            Err(error) => match *error {
+                bincode::ErrorKind::Io(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+                    Ok(SyndromeFileEntry::Truncated)
+                }
+                error => Err(invalid_data(error)),
+            },
+        };
+    }
+    if reader.read_line(line)? == 0 {
+        return Ok(SyndromeFileEntry::End);
+    }
+    if line.starts_with(SYNDROME_FILE_FOOTER) {
+        return parse_footer(line).map(SyndromeFileEntry::Footer);
+    }
+    let mut value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        // every complete line ends with a newline
+        Err(_) if !line.ends_with('\n') => return Ok(SyndromeFileEntry::Truncated),
+        Err(error) => return Err(error.into()),
+    };
+    let error_edges = match value.as_object_mut().and_then(|object| object.remove("error_edges")) {
+        Some(error_edges) => Some(serde_json::from_value(error_edges)?),
+        None => None,
+    };
+    Ok(SyndromeFileEntry::Record((serde_json::from_value(value)?, error_edges)))
+}
+
+/// read a syndrome pattern from a reader placed at the [`SyndromeFileReader::offset`] of it, or `None` at the footer or
+/// the end of file
+#[cfg(not(feature = "minimal"))]
+pub fn read_syndrome_record(
+    reader: &mut impl BufRead,
+    bincode: bool,
+    line: &mut String,
+) -> io::Result<Option<SyndromeRecord>> {
+    match read_syndrome_entry(reader, bincode, line)? {
+        SyndromeFileEntry::Record(record) => Ok(Some(record)),
+        SyndromeFileEntry::Footer(_) | SyndromeFileEntry::End => Ok(None),
+        SyndromeFileEntry::Truncated => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "incomplete syndrome pattern at the end of file",
+        )),
+    }
+}
+
+/// the number of syndrome patterns in the footer of an uncompressed syndrome pattern file, read from the end of the
+/// file without scanning it; `None` if the file is not finalized
+#[cfg(not(feature = "minimal"))]
+pub fn read_syndrome_file_footer(filename: &str) -> io::Result<Option<usize>> {
+    let mut file = File::open(filename)?;
+    let length = file.seek(io::SeekFrom::End(0))?;
+    let mut tail = vec![];
+    file.seek(io::SeekFrom::Start(length.saturating_sub(64)))?;
+    file.read_to_end(&mut tail)?;
+    // the footer is not necessarily preceded by a newline in bincode
+    let footer = SYNDROME_FILE_FOOTER.as_bytes();
+    let Some(start) = tail.windows(footer.len()).rposition(|window| window == footer) else {
+        return Ok(None);
+    };
+    match std::str::from_utf8(&tail[start..]) {
+        Ok(last_line) if last_line.ends_with('\n') => parse_footer(last_line).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// the first two bytes of a gzip stream
+#[cfg(not(feature = "minimal"))]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[cfg(not(feature = "minimal"))]
+impl SyndromeFileReader<Box<dyn BufRead + Send>> {
+    /// open a syndrome pattern file, decompressing it with gzip if the path ends with `.gz` or the content starts with
+    /// the gzip magic bytes, which never start an uncompressed file
+    pub fn open(filename: &str) -> io::Result<Self> {
+        let mut file = BufReader::new(File::open(filename)?);
+        let compressed = filename.ends_with(".gz") || file.fill_buf()?.starts_with(&GZIP_MAGIC);
+        let reader: Box<dyn BufRead + Send> = if compressed {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(file)
+        };
+        let mut reader = Self::new(reader)?;
+        reader.compressed = compressed;
+        Ok(reader)
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<R: BufRead> Iterator for SyndromeFileReader<R> {
+    type Item = io::Result<SyndromeRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// an output file that is compressed with gzip if its path ends with `.gz`
+#[cfg(not(feature = "minimal"))]
+pub enum SyndromeFileOutput {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+#[cfg(not(feature = "minimal"))]
+impl SyndromeFileOutput {
+    pub fn create(filename: &str) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(filename)?);
+        Ok(if filename.ends_with(".gz") {
+            Self::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            Self::Plain(file)
+        })
+    }
+
+    /// write the gzip trailer if compressed and flush the file
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut file) => file.flush(),
+            Self::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl Write for SyndromeFileOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// writes a syndrome pattern file one syndrome pattern at a time, the counterpart of [`SyndromeFileReader`]; the writer is
+/// flushed every `flush_shots` syndrome patterns and/or every `flush_seconds` so that the file of an interrupted run
+/// (even a compressed one) can still be read up to the last flush. a complete file is closed by
+/// [`SyndromeFileWriter::finalize`]
+#[cfg(not(feature = "minimal"))]
+pub struct SyndromeFileWriter<W: Write> {
+    writer: W,
+    bincode: bool,
+    /// whether every syndrome pattern must carry its ground-truth error edges
+    error_edges: bool,
+    /// the number of syndrome patterns written
+    count: usize,
+    /// flush every this number of syndrome patterns, or never if 0; [`SyndromeFileWriter::FLUSH_INTERVAL`] by default
+    pub flush_shots: usize,
+    /// flush at the first syndrome pattern after this number of seconds since the last flush
+    pub flush_seconds: Option<f64>,
+    /// the time of the last flush, only tracked with `flush_seconds` because there is no clock in WebAssembly
+    last_flush: Option<std::time::Instant>,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl<W: Write> SyndromeFileWriter<W> {
+    pub fn new(
+        mut writer: W,
+        initializer: &SolverInitializer,
+        positions: &[VisualizePosition],
+        bincode: bool,
+        error_edges: bool,
+    ) -> io::Result<Self> {
+        trace_span!("syndrome_file_write_header", bincode);
+        if bincode {
+            writeln!(writer, "{SYNDROME_FILE_BINCODE_HEADER}")?;
+            bincode::serialize_into(&mut writer, initializer).map_err(io::Error::other)?;
+            bincode::serialize_into(&mut writer, positions).map_err(io::Error::other)?;
+        } else {
+            let header = if error_edges {
+                SYNDROME_FILE_ERROR_EDGES_HEADER
+            } else {
+                SYNDROME_FILE_HEADER
+            };
+            writeln!(writer, "{header}")?;
+            serde_json::to_writer(&mut writer, initializer)?;
+            writeln!(writer)?;
+            serde_json::to_writer(&mut writer, positions)?;
+            writeln!(writer)?;
+        }
+        Ok(Self::resume(writer, bincode, error_edges, 0))
+    }
+
+    /// continue writing syndrome patterns after the header and `count` syndrome patterns
+    fn resume(writer: W, bincode: bool, error_edges: bool, count: usize) -> Self {
+        Self {
+            writer,
+            bincode,
+            error_edges,
+            count,
+            flush_shots: Self::FLUSH_INTERVAL,
+            flush_seconds: None,
+            last_flush: None,
+        }
+    }
+
+    /// the default number of syndrome patterns between two flushes; for gzip, each flush slightly reduces the
+    /// compression ratio
+    pub const FLUSH_INTERVAL: usize = 1000;
+
+    /// the number of syndrome patterns in the file, including those before appending
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// `error_edges` must be given if and only if the writer is created with `error_edges`
+    pub fn write(&mut self, syndrome_pattern: &SyndromePattern, error_edges: Option<&[EdgeIndex]>) -> io::Result<()> {
+        trace_span!("syndrome_file_write", index = self.count);
+        if error_edges.is_some() != self.error_edges {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the file is created {} error edges but syndrome pattern {} {}",
+                    if self.error_edges { "with" } else { "without" },
+                    self.count,
+                    if error_edges.is_some() {
+                        "has them"
+                    } else {
+                        "doesn't have them"
+                    }
+                ),
+            ));
+        }
+        if self.bincode {
+            bincode::serialize_into(&mut self.writer, &(syndrome_pattern, error_edges)).map_err(io::Error::other)?;
+        } else {
+            writeln!(self.writer, "{}", syndrome_pattern_line(syndrome_pattern, error_edges))?;
+        }
+        self.count += 1;
+        let mut flush = self.flush_shots != 0 && self.count.is_multiple_of(self.flush_shots);
+        if let Some(flush_seconds) = self.flush_seconds {
+            let now = std::time::Instant::now();
+            let last_flush = *self.last_flush.get_or_insert(now);
+            if (now - last_flush).as_secs_f64() >= flush_seconds {
+                flush = true;
+            }
+        }
+        if flush {
+            self.writer.flush()?;
+            if self.flush_seconds.is_some() {
+                self.last_flush = Some(std::time::Instant::now());
+            }
+        }
+        Ok(())
+    }
+
+    /// flush and return the underlying writer, leaving the file open for appending; see [`SyndromeFileWriter::finalize`]
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// write the footer with the number of syndrome patterns, which lets a reader tell a complete file from an
+    /// interrupted one and count the syndrome patterns without scanning the file, then flush and return the writer
+    pub fn finalize(mut self) -> io::Result<W> {
+        writeln!(self.writer, "{SYNDROME_FILE_FOOTER} {}", self.count)?;
+        self.finish()
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl SyndromeFileWriter<SyndromeFileOutput> {
+    /// create a syndrome pattern file, compressed with gzip if the path ends with `.gz`
+    pub fn create(
+        filename: &str,
+        initializer: &SolverInitializer,
+        positions: &[VisualizePosition],
+        bincode: bool,
+        error_edges: bool,
+    ) -> io::Result<Self> {
+        Self::new(
+            SyndromeFileOutput::create(filename)?,
+            initializer,
+            positions,
+            bincode,
+            error_edges,
+        )
+    }
+
+    /// continue an existing syndrome pattern file, or create it if it doesn't exist; the file must have the same
+    /// initializer, positions and format. the footer and an incomplete syndrome pattern at the end of an interrupted
+    /// file are overwritten. compressed files are not supported
+    pub fn append(
+        filename: &str,
+        initializer: &SolverInitializer,
+        positions: &[VisualizePosition],
+        bincode: bool,
+        error_edges: bool,
+    ) -> io::Result<Self> {
+        if !std::path::Path::new(filename).exists() {
+            return Self::create(filename, initializer, positions, bincode, error_edges);
+        }
+        let mut reader = SyndromeFileReader::open(filename)?;
+        if reader.compressed {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("cannot append to the compressed file {filename}"),
+            ));
+        }
+        // the files of older versions have no virtual vertex groups, which are irrelevant to the decoding graph
+        let same_initializer = if reader.initializer.virtual_vertex_groups.is_none() {
+            let mut initializer = initializer.clone();
+            initializer.virtual_vertex_groups = None;
+            reader.initializer == initializer
+        } else {
+            &reader.initializer == initializer
+        };
+        if !same_initializer {
+            return Err(invalid_data(format!("{filename} has a different initializer")));
+        }
+        if reader.positions != positions {
+            return Err(invalid_data(format!("{filename} has different positions")));
+        }
+        if reader.bincode != bincode || (!bincode && reader.error_edges != error_edges) {
+            return Err(invalid_data(format!(
+                "{filename} has a different format, bincode: {}, error edges: {}",
+                reader.bincode, reader.error_edges
+            )));
+        }
+        let mut end = reader.offset();
+        while reader.read_record()?.is_some() {
+            end = reader.offset();
+        }
+        let mut file = std::fs::OpenOptions::new().write(true).open(filename)?;
+        file.set_len(end)?;
+        file.seek(io::SeekFrom::End(0))?;
+        Ok(Self::resume(
+            SyndromeFileOutput::Plain(BufWriter::new(file)),
+            bincode,
+            error_edges,
+            reader.read_count,
+        ))
+    }
+}
+
+/// the content of a syndrome pattern file, which is either in the json lines format written by
+/// [`crate::mwpm_solver::SolverErrorPatternLogger`] or in bincode; a file path ending with `.gz` is gzip compressed, and
+/// compressed files are also detected by their content when reading. use [`SyndromeFileReader`] and [`SyndromeFileWriter`] instead to avoid holding the whole file in memory
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyndromeFile {
+    pub initializer: SolverInitializer,
+    pub positions: Vec<VisualizePosition>,
+    pub syndrome_patterns: Vec<SyndromePattern>,
+    /// the ground-truth error edges of each syndrome pattern, if recorded
+    pub error_edges: Vec<Option<Vec<EdgeIndex>>>,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl SyndromeFile {
+    pub fn new(initializer: SolverInitializer, positions: Vec<VisualizePosition>) -> Self {
+        Self {
+            initializer,
+            positions,
+            syndrome_patterns: vec![],
+            error_edges: vec![],
+        }
+    }
+
+    pub fn push(&mut self, syndrome_pattern: SyndromePattern, error_edges: Option<Vec<EdgeIndex>>) {
+        self.syndrome_patterns.push(syndrome_pattern);
+        self.error_edges.push(error_edges);
+    }
+
+    /// whether every syndrome pattern has its ground-truth error edges, so that they are written to the file
+    pub fn has_error_edges(&self) -> bool {
+        !self.error_edges.is_empty() && self.error_edges.iter().all(|error_edges| error_edges.is_some())
+    }
+
+    /// the error edges are written only if every syndrome pattern has them, see [`SyndromeFile::has_error_edges`]
+    pub fn write(&self, writer: &mut impl Write, bincode: bool) -> io::Result<()> {
+        let has_error_edges = self.has_error_edges();
+        let mut writer = SyndromeFileWriter::new(writer, &self.initializer, &self.positions, bincode, has_error_edges)?;
+        for (syndrome_pattern, error_edges) in self.syndrome_patterns.iter().zip(self.error_edges.iter()) {
+            writer.write(syndrome_pattern, error_edges.as_deref().filter(|_| has_error_edges))?;
+        }
+        writer.finalize()?;
+        Ok(())
+    }
+
+    pub fn save(&self, filename: &str, bincode: bool) -> io::Result<()> {
+        let mut output = SyndromeFileOutput::create(filename)?;
+        self.write(&mut output, bincode)?;
+        output.finish()
+    }
+
+    fn collect<R: BufRead>(reader: SyndromeFileReader<R>) -> io::Result<Self> {
+        let mut file = Self::new(reader.initializer.clone(), reader.positions.clone());
+        for record in reader {
+            let (syndrome_pattern, error_edges) = record?;
+            file.push(syndrome_pattern, error_edges);
+        }
+        Ok(file)
+    }
+
+    pub fn read(reader: impl Read) -> io::Result<Self> {
+        Self::collect(SyndromeFileReader::new(BufReader::new(reader))?)
+    }
+
+    pub fn load(filename: &str) -> io::Result<Self> {
+        Self::collect(SyndromeFileReader::open(filename)?)
+    }
+}
+
+/// read from file, including the error patterns;
+/// the point is to avoid bad cache performance, because generating random error requires iterating over a large memory space,
+/// invalidating all cache. also, this can reduce the time of decoding by prepare the data before hand and could be shared between
+/// different partition configurations; see [`SyndromeFile`] for the supported file formats. not available in WebAssembly
+/// which has no file system.
+///
+/// only the header of the file is read eagerly and the syndrome patterns are streamed from the file in batches of
+/// `read_ahead`, so that files larger than the memory can be used; the `preload` option loads all of them instead
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct ErrorPatternReader {
+    /// the syndrome pattern file
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub filename: String,
+    /// vertices in the code
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub vertices: Vec<CodeVertex>,
+    /// nearest-neighbor edges in the decoding graph
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub edges: Vec<CodeEdge>,
+    /// pre-generated syndrome patterns, only if `preload`
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub syndrome_patterns: Vec<SyndromePattern>,
+    /// the ground-truth error edges of each syndrome pattern, if recorded in the file; only if `preload`
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub error_edges: Vec<Option<Vec<EdgeIndex>>>,
+    /// cursor of current errors
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub defect_index: usize,
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub cyclic_syndrome: bool,
+    /// whether all the syndrome patterns are loaded into memory
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub preload: bool,
+    /// the maximum number of syndrome patterns read from the file at once when streaming
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub read_ahead: usize,
+    /// the number of syndrome patterns in the file, known after loading or once the end of file is reached
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub syndrome_pattern_num: Option<usize>,
+    /// how many times the syndrome patterns started over from the beginning of the file with `cyclic_syndrome`; a
+    /// nonzero value means that the syndrome patterns are reused, which is fine for speed but not for accuracy
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub cycle_count: usize,
+    /// the remaining syndrome patterns of the file when streaming
+    stream: Option<SyndromeFileReader<Box<dyn BufRead + Send>>>,
+    /// the format of the file when streaming, see [`SyndromeFileReader`]
+    bincode: bool,
+    compressed: bool,
+    /// the offset of every syndrome pattern in the file when streaming, built by the first [`ErrorPatternReader::get`]
+    offsets: Option<Vec<u64>>,
+    /// see [`ErrorPatternReader::last_error_edges`]
+    last_error_edges: Option<Vec<EdgeIndex>>,
+    /// syndrome patterns read ahead from the stream
+    buffer: VecDeque<SyndromeRecord>,
+    /// translates the vertex indices in the file to the reordered ones when streaming
+    old_to_new: Option<Vec<Option<VertexIndex>>>,
+    /// the virtual vertex groups recorded in the file, see [`SolverInitializer::virtual_vertex_groups`]
+    virtual_vertex_groups: Option<Vec<Vec<VertexIndex>>>,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl std::fmt::Debug for ErrorPatternReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // the syndrome patterns are too many to print
+        f.debug_struct("ErrorPatternReader")
+            .field("filename", &self.filename)
+            .field("vertex_num", &self.vertices.len())
+            .field("edge_num", &self.edges.len())
+            .field("syndrome_pattern_num", &self.syndrome_pattern_num)
+            .field("defect_index", &self.defect_index)
+            .field("cyclic_syndrome", &self.cyclic_syndrome)
+            .field("cycle_count", &self.cycle_count)
+            .field("preload", &self.preload)
+            .field("read_ahead", &self.read_ahead)
+            .finish()
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ExampleCode for ErrorPatternReader {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        (&mut self.vertices, &mut self.edges)
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        (&self.vertices, &self.edges)
+    }
+    /// the groups recorded in the file if any, otherwise the ones computed from the positions
+    fn virtual_vertex_groups(&self) -> Option<Vec<Vec<VertexIndex>>> {
+        self.virtual_vertex_groups.clone().or_else(|| logical_boundary_groups(self))
+    }
+    fn generate_random_errors(&mut self, _seed: u64) -> SyndromePattern {
+        self.expect_next_syndrome_pattern().0
+    }
+    /// the pre-generated syndrome patterns are translated to the new vertex indices as well
+    fn reorder_vertices(&mut self, sequential_vertices: &[VertexIndex]) -> Result<VertexPermutation, String> {
+        let permutation = reorder_code_vertices(&mut self.vertices, &mut self.edges, sequential_vertices)?;
+        for vertex_index in self.virtual_vertex_groups.iter_mut().flatten().flatten() {
+            *vertex_index = permutation.to_new(*vertex_index);
+        }
+        for syndrome_pattern in self.syndrome_patterns.iter_mut() {
+            for defect_vertex in syndrome_pattern.defect_vertices.iter_mut() {
+                *defect_vertex = permutation.to_new(*defect_vertex);
+            }
+        }
+        if !self.preload {
+            self.old_to_new = Some(match self.old_to_new.take() {
+                Some(previous) => previous
+                    .iter()
+                    .map(|vertex_index| vertex_index.map(|vertex_index| permutation.to_new(vertex_index)))
+                    .collect(),
+                None => permutation.old_to_new.iter().map(|&new_index| Some(new_index)).collect(),
+            });
+        }
+        Ok(permutation)
+    }
+    fn generate_random_errors_with_edges(&mut self, _seed: u64) -> (SyndromePattern, Vec<EdgeIndex>) {
+        let (syndrome_pattern, error_edges) = self.expect_next_syndrome_pattern();
+        let error_edges = error_edges.expect(
+            "the file has no ground-truth error edges, generate it with `--primal-dual-type error-pattern-logger --compute-logical-error-rate`",
+        );
+        (syndrome_pattern, error_edges)
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ErrorPatternReader {
+    /// the default number of syndrome patterns read from the file at once when streaming
+    pub const DEFAULT_READ_AHEAD: usize = 1000;
+
+    fn expect_next_syndrome_pattern(&mut self) -> SyndromeRecord {
+        self.next_syndrome_pattern().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// the next syndrome pattern and its ground-truth error edges; reading more syndrome patterns than in the file is
+    /// an [`io::ErrorKind::UnexpectedEof`] error unless `cyclic_syndrome` is set
+    pub fn next_syndrome_pattern(&mut self) -> io::Result<SyndromeRecord> {
+        let record = self.next_record()?;
+        self.last_error_edges.clone_from(&record.1);
+        Ok(record)
+    }
+
+    /// the ground-truth error edges of the last syndrome pattern, if recorded in the file
+    pub fn last_error_edges(&self) -> Option<&[EdgeIndex]> {
+        self.last_error_edges.as_deref()
+    }
+
+    fn next_record(&mut self) -> io::Result<SyndromeRecord> {
+        if self.preload {
+            if self.defect_index >= self.syndrome_patterns.len() {
+                if !self.cyclic_syndrome || self.syndrome_patterns.is_empty() {
+                    return Err(self.exhausted_error());
+                }
+                self.start_over();
+            }
+            let syndrome_pattern = self.syndrome_patterns[self.defect_index].clone();
+            let error_edges = self.error_edges[self.defect_index].clone();
+            self.defect_index += 1;
+            return Ok((syndrome_pattern, error_edges));
+        }
+        if self.buffer.is_empty() {
+            self.read_ahead()?;
+        }
+        if self.buffer.is_empty() {
+            self.syndrome_pattern_num = Some(self.defect_index);
+            if !self.cyclic_syndrome || self.defect_index == 0 {
+                return Err(self.exhausted_error());
+            }
+            self.stream = Some(SyndromeFileReader::open(&self.filename)?);
+            self.start_over();
+            self.read_ahead()?;
+        }
+        let (syndrome_pattern, error_edges) = self.buffer.pop_front().ok_or_else(|| self.exhausted_error())?;
+        self.defect_index += 1;
+        Ok((self.reordered(syndrome_pattern), error_edges))
+    }
+
+    fn start_over(&mut self) {
+        if self.cycle_count == 0 {
+            eprintln!(
+                "[warning] all {} syndrome patterns in {} are used, starting over with `cyclic_syndrome`",
+                self.defect_index, self.filename
+            );
+        }
+        self.cycle_count += 1;
+        self.defect_index = 0;
+    }
+
+    /// translate a syndrome pattern streamed from the file to the reordered vertex indices
+    #[allow(clippy::unnecessary_cast)]
+    fn reordered(&self, mut syndrome_pattern: SyndromePattern) -> SyndromePattern {
+        if let Some(old_to_new) = self.old_to_new.as_ref() {
+            for defect_vertex in syndrome_pattern.defect_vertices.iter_mut() {
+                *defect_vertex = old_to_new[*defect_vertex as usize].expect("defect vertex must be kept after reordering");
+            }
+        }
+        syndrome_pattern
+    }
+
+    /// the number of syndrome patterns in the file; when streaming, it's read from the footer of a finalized
+    /// uncompressed file, otherwise the first call reads the whole file to index it
+    pub fn count(&mut self) -> io::Result<usize> {
+        if self.preload {
+            return Ok(self.syndrome_patterns.len());
+        }
+        if self.offsets.is_none() && !self.compressed {
+            if let Some(count) = read_syndrome_file_footer(&self.filename)? {
+                self.syndrome_pattern_num = Some(count);
+                return Ok(count);
+            }
+        }
+        Ok(self.index()?.len())
+    }
+
+    /// the offset of every syndrome pattern in the file, which takes 8 bytes of memory per syndrome pattern
+    fn index(&mut self) -> io::Result<&Vec<u64>> {
+        if self.offsets.is_none() {
+            let mut reader = SyndromeFileReader::open(&self.filename)?;
+            let mut offsets = Vec::with_capacity(self.syndrome_pattern_num.unwrap_or(0));
+            loop {
+                let offset = reader.offset();
+                if reader.read_record()?.is_none() {
+                    break;
+                }
+                offsets.push(offset);
+            }
+            self.syndrome_pattern_num = Some(offsets.len());
+            self.offsets = Some(offsets);
+        }
+        Ok(self.offsets.as_ref().unwrap())
+    }
+
+    /// the syndrome pattern at `index` without moving the cursor of [`ExampleCode::generate_random_errors`]; when
+    /// streaming, each call seeks in the file after indexing it, except that a compressed file is decompressed from the
+    /// beginning up to the syndrome pattern
+    pub fn get(&mut self, index: usize) -> io::Result<SyndromeRecord> {
+        let count = self.count()?;
+        if index >= count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("syndrome pattern index {index} out of range for {count} syndrome patterns"),
+            ));
+        }
+        if self.preload {
+            return Ok((self.syndrome_patterns[index].clone(), self.error_edges[index].clone()));
+        }
+        let offset = self.index()?[index];
+        let mut file = File::open(&self.filename)?;
+        let mut reader: Box<dyn BufRead> = if self.compressed {
+            let mut decoder = BufReader::new(GzDecoder::new(BufReader::new(file)));
+            io::copy(&mut (&mut decoder).take(offset), &mut io::sink())?;
+            Box::new(decoder)
+        } else {
+            file.seek(io::SeekFrom::Start(offset))?;
+            Box::new(BufReader::new(file))
+        };
+        let (syndrome_pattern, error_edges) = read_syndrome_record(&mut reader, self.bincode, &mut String::new())?
+            .ok_or_else(|| invalid_data(format!("{} is changed since it is indexed", self.filename)))?;
+        Ok((self.reordered(syndrome_pattern), error_edges))
+    }
+
+    fn read_ahead(&mut self) -> io::Result<()> {
+        let stream = self.stream.as_mut().expect("streaming requires an open file");
+        while self.buffer.len() < self.read_ahead {
+            match stream.next() {
+                Some(record) => self.buffer.push_back(record?),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn exhausted_error(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "reading syndrome pattern {} but {} has only {}, consider generating the file with more data points",
+                self.defect_index + 1,
+                self.filename,
+                self.syndrome_pattern_num.unwrap_or(self.defect_index)
+            ),
+        )
+    }
+
+    /// panics if the config is invalid or the file cannot be read, see [`Self::try_new`]
+    pub fn new(config: serde_json::Value) -> Self {
+        or_panic(Self::try_new(config))
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn try_new(mut config: serde_json::Value) -> Result<Self, FusionError> {
+        let mut filename = "tmp/syndrome_patterns.txt".to_string();
+        let config = config
+            .as_object_mut()
+            .ok_or_else(|| FusionError::Config("config must be JSON object".to_string()))?;
+        let invalid = |key: &str, expected: &str| FusionError::Config(format!("{key}: expecting {expected}"));
+        if let Some(value) = config.remove("filename") {
+            filename = value.as_str().ok_or_else(|| invalid("filename", "string"))?.to_string();
+        }
+        // `cycle` is a short name of `cyclic_syndrome`
+        let cyclic_syndrome = if let Some(cyclic_syndrome) = config.remove("cyclic_syndrome").or(config.remove("cycle")) {
+            cyclic_syndrome.as_bool().ok_or_else(|| invalid("cyclic_syndrome", "bool"))?
+        } else {
+            false
         }; // by default not enable cyclic syndrome, to avoid problem
+        let preload = if let Some(preload) = config.remove("preload") {
+            preload.as_bool().ok_or_else(|| invalid("preload", "bool"))?
+        } else {
+            false
+        };
+        let read_ahead = if let Some(read_ahead) = config.remove("read_ahead") {
+            read_ahead.as_u64().ok_or_else(|| invalid("read_ahead", "usize"))? as usize
+        } else {
+            Self::DEFAULT_READ_AHEAD
+        };
+        if !config.is_empty() {
+            return Err(FusionError::Config(format!(
+                "unknown config keys: {:?}",
+                config.keys().collect::<Vec<&String>>()
+            )));
+        }
+        if read_ahead == 0 {
+            return Err(FusionError::Config("read_ahead must be positive".to_string()));
+        }
+        Ok(if preload {
+            Self::load(&filename, cyclic_syndrome)?
+        } else {
+            Self::open(&filename, cyclic_syndrome, read_ahead)?
+        })
+    }
+
+    /// load all the syndrome patterns of the file into memory
+    pub fn load(filename: &str, cyclic_syndrome: bool) -> io::Result<Self> {
+        let SyndromeFile {
+            initializer,
+            positions,
+            syndrome_patterns,
+            error_edges,
+        } = SyndromeFile::load(filename)?;
+        let mut code = Self::from_header(filename, &initializer, positions, cyclic_syndrome)?;
+        code.preload = true;
+        code.syndrome_pattern_num = Some(syndrome_patterns.len());
+        code.syndrome_patterns = syndrome_patterns;
+        code.error_edges = error_edges;
+        Ok(code)
+    }
+
+    /// read only the header of the file and stream the syndrome patterns, at most `read_ahead` of them in memory
+    pub fn open(filename: &str, cyclic_syndrome: bool, read_ahead: usize) -> io::Result<Self> {
+        assert!(read_ahead > 0, "read_ahead must be positive");
+        let stream = SyndromeFileReader::open(filename)?;
+        let positions = stream.positions.clone();
+        let mut code = Self::from_header(filename, &stream.initializer, positions, cyclic_syndrome)?;
+        code.read_ahead = read_ahead;
+        code.bincode = stream.bincode;
+        code.compressed = stream.compressed;
+        code.stream = Some(stream);
+        Ok(code)
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    fn from_header(
+        filename: &str,
+        initializer: &SolverInitializer,
+        positions: Vec<VisualizePosition>,
+        cyclic_syndrome: bool,
+    ) -> io::Result<Self> {
+        if positions.len() != initializer.vertex_num as usize {
+            return Err(invalid_data(format!(
+                "{} positions for {} vertices",
+                positions.len(),
+                initializer.vertex_num
+            )));
+        }
+        let mut code = Self {
+            filename: filename.to_string(),
+            vertices: Vec::with_capacity(initializer.vertex_num as usize),
+            edges: Vec::with_capacity(initializer.weighted_edges.len()),
+            syndrome_patterns: vec![],
+            error_edges: vec![],
+            defect_index: 0,
+            cyclic_syndrome,
+            preload: false,
+            read_ahead: Self::DEFAULT_READ_AHEAD,
+            syndrome_pattern_num: None,
+            cycle_count: 0,
+            stream: None,
+            bincode: false,
+            compressed: false,
+            offsets: None,
+            buffer: VecDeque::new(),
+            old_to_new: None,
+            last_error_edges: None,
+            virtual_vertex_groups: initializer.virtual_vertex_groups.clone(),
+        };
+        load_initializer(&mut code, initializer);
+        for (vertex_index, position) in positions.into_iter().enumerate() {
+            code.vertices[vertex_index].position = position;
+        }
+        Ok(code)
+    }
+}
+
+/// build the vertices and edges of a code that is read from a file, whose weights are known but probabilities are not
+#[cfg(not(feature = "minimal"))]
+#[allow(clippy::unnecessary_cast)]
+fn load_initializer(code: &mut impl ExampleCode, initializer: &SolverInitializer) {
+    let (_, edges) = code.vertices_edges();
+    edges.clear();
+    for (left_vertex, right_vertex, weight) in initializer.weighted_edges.iter() {
+        assert!(weight % 2 == 0, "weight must be even number");
+        edges.push(CodeEdge {
+            vertices: (*left_vertex, *right_vertex),
+            p: 0.,  // doesn't matter
+            pe: 0., // doesn't matter
+            half_weight: weight / 2,
+            is_erasure: false, // doesn't matter
+        });
+    }
+    // automatically create the vertices and nearest-neighbor connection
+    code.fill_vertices(initializer.vertex_num);
+    let (vertices, _) = code.vertices_edges();
+    for &vertex_index in initializer.virtual_vertices.iter() {
+        vertices[vertex_index as usize].is_virtual = true;
+    }
+}
+
+#[cfg(feature = "python_binding")]
+bind_trait_example_code! {ErrorPatternReader}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl ErrorPatternReader {
+    /// the syndrome patterns are preloaded by default; use `preload=False` for large files
+    #[new]
+    #[pyo3(signature = (filename, cyclic_syndrome = false, preload = true, read_ahead = Self::DEFAULT_READ_AHEAD))]
+    fn py_new(filename: &str, cyclic_syndrome: bool, preload: bool, read_ahead: usize) -> PyResult<Self> {
+        if read_ahead == 0 {
+            return Err(PyValueError::new_err("read_ahead must be positive"));
+        }
+        if preload {
+            Ok(Self::load(filename, cyclic_syndrome)?)
+        } else {
+            Ok(Self::open(filename, cyclic_syndrome, read_ahead)?)
+        }
+    }
+    #[pyo3(name = "last_error_edges")]
+    fn py_last_error_edges(&self) -> Option<Vec<EdgeIndex>> {
+        self.last_error_edges.clone()
+    }
+    fn __len__(&mut self) -> PyResult<usize> {
+        Ok(self.count()?)
+    }
+    /// when streaming, the first call reads the whole file to index the syndrome patterns
+    fn __getitem__(&mut self, index: isize) -> PyResult<SyndromePattern> {
+        let len = self.count()? as isize;
+        let position = if index < 0 { index + len } else { index };
+        if position < 0 || position >= len {
+            return Err(PyIndexError::new_err(format!(
+                "syndrome pattern index {index} out of range for {len} syndrome patterns"
+            )));
+        }
+        Ok(self.get(position as usize)?.0)
+    }
+    /// stream the syndrome patterns from the file in bounded memory, independent of the cursor; note that the vertex
+    /// indices are the ones in the file even if the vertices are reordered
+    fn __iter__(&self) -> PyResult<SyndromeFileIterator> {
+        Ok(SyndromeFileIterator {
+            reader: SyndromeFileReader::open(&self.filename)?,
+        })
+    }
+}
+
+/// iterator over the syndrome patterns of a file, see [`ErrorPatternReader`]
+#[cfg(feature = "python_binding")]
+#[pyclass]
+pub struct SyndromeFileIterator {
+    reader: SyndromeFileReader<Box<dyn BufRead + Send>>,
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl SyndromeFileIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+    fn __next__(&mut self) -> PyResult<Option<SyndromePattern>> {
+        match self.reader.next() {
+            Some(record) => Ok(Some(record?.0)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// the order of reading the syndrome patterns from the shards of a [`ShardedErrorPatternReader`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ShardPolicy {
+    /// one syndrome pattern from each shard in turn, which is the original order of `generate-syndromes --shards`
+    RoundRobin,
+    /// all the syndrome patterns of a shard before the next shard
+    ShardByShard,
+}
+
+/// the file name of a shard, e.g. `data.003.syndromes` is shard 3 of `data.syndromes`; the index is inserted before
+/// the first `.` of the file name, or appended if there is none
+pub fn shard_filename(filename: &str, shard_index: usize) -> String {
+    let name_start = filename.rfind('/').map_or(0, |index| index + 1);
+    match filename[name_start..].find('.') {
+        Some(dot) => {
+            let (stem, extension) = filename.split_at(name_start + dot);
+            format!("{stem}.{shard_index:03}{extension}")
+        }
+        None => format!("{filename}.{shard_index:03}"),
+    }
+}
+
+/// whether a file name matches a pattern where `*` matches any sequence and `?` matches any single character
+#[cfg(not(feature = "minimal"))]
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => wildcard_match(&pattern[1..], name) || (!name.is_empty() && wildcard_match(pattern, &name[1..])),
+        (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(a), Some(b)) if a == b => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// the sorted file names matching a pattern with wildcards `*` and `?` in the file name (not in the folder), or the
+/// file name itself if it has no wildcard
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+pub fn expand_shard_pattern(pattern: &str) -> io::Result<Vec<String>> {
+    let name_start = pattern.rfind('/').map_or(0, |index| index + 1);
+    let (folder, name_pattern) = pattern.split_at(name_start);
+    if !name_pattern.contains(['*', '?']) {
+        return Ok(vec![pattern.to_string()]);
+    }
+    let mut filenames = vec![];
+    for entry in std::fs::read_dir(if folder.is_empty() { "." } else { folder })? {
+        let name = entry?.file_name();
+        if let Some(name) = name.to_str() {
+            if wildcard_match(name_pattern.as_bytes(), name.as_bytes()) {
+                filenames.push(format!("{folder}{name}"));
+            }
+        }
+    }
+    if filenames.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("no file matches {pattern}")));
+    }
+    filenames.sort();
+    Ok(filenames)
+}
+
+/// reads the syndrome patterns from several syndrome pattern files (shards) of the same decoding graph, e.g. generated
+/// by `generate-syndromes --shards`; every shard is streamed like [`ErrorPatternReader`]. not available in WebAssembly
+/// which has no file system
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+#[derive(Debug)]
+pub struct ShardedErrorPatternReader {
+    pub shards: Vec<ErrorPatternReader>,
+    pub policy: ShardPolicy,
+    /// the shard of the next syndrome pattern
+    pub shard_index: usize,
+    /// the number of syndrome patterns read from all the shards
+    pub consumed: usize,
+    /// whether each shard has no more syndrome patterns
+    exhausted: Vec<bool>,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ExampleCode for ShardedErrorPatternReader {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        self.shards[0].vertices_edges()
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        self.shards[0].immutable_vertices_edges()
+    }
+    fn virtual_vertex_groups(&self) -> Option<Vec<Vec<VertexIndex>>> {
+        self.shards[0].virtual_vertex_groups()
+    }
+    fn generate_random_errors(&mut self, _seed: u64) -> SyndromePattern {
+        self.next_syndrome_pattern().unwrap_or_else(|error| panic!("{error}")).0
+    }
+    /// the syndrome patterns of every shard are translated to the new vertex indices
+    fn reorder_vertices(&mut self, sequential_vertices: &[VertexIndex]) -> Result<VertexPermutation, String> {
+        // the shards share the same decoding graph, so the first one validates the permutation for all of them
+        let permutation = self.shards[0].reorder_vertices(sequential_vertices)?;
+        for shard in self.shards.iter_mut().skip(1) {
+            shard.reorder_vertices(sequential_vertices)?;
+        }
+        Ok(permutation)
+    }
+    fn generate_random_errors_with_edges(&mut self, _seed: u64) -> (SyndromePattern, Vec<EdgeIndex>) {
+        let (syndrome_pattern, error_edges) = self.next_syndrome_pattern().unwrap_or_else(|error| panic!("{error}"));
+        let error_edges =
+            error_edges.expect("the shard has no ground-truth error edges, generate it with `--include-error-edges`");
+        (syndrome_pattern, error_edges)
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ShardedErrorPatternReader {
+    /// open the shards, each of which is either a file name or a pattern with wildcards, see [`expand_shard_pattern`];
+    /// all the shards must have the same initializer and positions
+    pub fn new(shards: &[String], policy: ShardPolicy) -> io::Result<Self> {
+        let mut filenames = vec![];
+        for shard in shards.iter() {
+            filenames.extend(expand_shard_pattern(shard)?);
+        }
+        if filenames.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "no shard is given"));
+        }
+        let mut readers: Vec<ErrorPatternReader> = vec![];
+        for filename in filenames.iter() {
+            let reader = ErrorPatternReader::open(filename, false, ErrorPatternReader::DEFAULT_READ_AHEAD)?;
+            if let Some(first) = readers.first() {
+                for (name, is_same) in [
+                    ("initializer", reader.get_initializer() == first.get_initializer()),
+                    ("positions", reader.get_positions() == first.get_positions()),
+                ] {
+                    if !is_same {
+                        return Err(invalid_data(format!(
+                            "shard {filename} has different {name} from shard {}",
+                            first.filename
+                        )));
+                    }
+                }
+            }
+            readers.push(reader);
+        }
+        Ok(Self {
+            exhausted: vec![false; readers.len()],
+            shards: readers,
+            policy,
+            shard_index: 0,
+            consumed: 0,
+        })
+    }
+
+    /// the config has `shards`, a list of file names or patterns (or a single one), and an optional `policy` which is
+    /// `round-robin` by default
+    pub fn from_config(mut config: serde_json::Value) -> Self {
+        let config = config.as_object_mut().expect("config must be JSON object");
+        let shards: Vec<String> = match config.remove("shards").expect("shards required") {
+            serde_json::Value::String(shard) => vec![shard],
+            shards => serde_json::from_value(shards).expect("shards: string or list of strings"),
+        };
+        let policy = if let Some(policy) = config.remove("policy") {
+            serde_json::from_value(policy).expect("policy: round-robin or shard-by-shard")
+        } else {
+            ShardPolicy::RoundRobin
+        };
         if !config.is_empty() {
             panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
         }
-        let file = File::open(filename).unwrap();
-        let mut syndrome_patterns = vec![];
-        let mut initializer: Option<SolverInitializer> = None;
-        let mut positions: Option<Vec<VisualizePosition>> = None;
-        for (line_index, line) in io::BufReader::new(file).lines().enumerate() {
-            if let Ok(value) = line {
-                match line_index {
-                    0 => {
-                        assert!(value.starts_with("Syndrome Pattern v1.0 "), "incompatible file version");
+        Self::new(&shards, policy).unwrap()
+    }
+
+    /// the next syndrome pattern and its ground-truth error edges, skipping the shards with no more syndrome patterns;
+    /// it's an [`io::ErrorKind::UnexpectedEof`] error after all the shards are exhausted
+    pub fn next_syndrome_pattern(&mut self) -> io::Result<SyndromeRecord> {
+        for _ in 0..self.shards.len() {
+            let shard_index = self.shard_index;
+            if !self.exhausted[shard_index] {
+                match self.shards[shard_index].next_syndrome_pattern() {
+                    Ok(record) => {
+                        if self.policy == ShardPolicy::RoundRobin {
+                            self.shard_index = (shard_index + 1) % self.shards.len();
+                        }
+                        self.consumed += 1;
+                        return Ok(record);
+                    }
+                    Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => self.exhausted[shard_index] = true,
+                    Err(error) => return Err(error),
+                }
+            }
+            self.shard_index = (shard_index + 1) % self.shards.len();
+        }
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!(
+                "reading syndrome pattern {} but the {} shards have only {}, consider generating more data points",
+                self.consumed + 1,
+                self.shards.len(),
+                self.consumed
+            ),
+        ))
+    }
+}
+
+/// the file formats of detection events sampled by Stim, see <https://github.com/quantumlib/Stim/blob/main/doc/result_formats.md>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StimFormat {
+    /// each shot is `ceil(num_detectors / 8)` bytes, where detector `i` is bit `i % 8` of byte `i / 8`
+    B8,
+    /// each shot is a line of `num_detectors` ASCII `0` or `1` characters
+    Dets01,
+}
+
+/// reads the detection events sampled by Stim one shot at a time, on a decoding graph whose first `num_detectors`
+/// vertices are the detectors; like [`ErrorPatternReader`], [`ExampleCode::generate_random_errors`] returns the next
+/// shot. not available in WebAssembly which has no file system
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+pub struct StimEventReader {
+    /// the detection event file
+    pub path: String,
+    pub format: StimFormat,
+    pub num_detectors: usize,
+    /// vertices in the code
+    pub vertices: Vec<CodeVertex>,
+    /// nearest-neighbor edges in the decoding graph
+    pub edges: Vec<CodeEdge>,
+    /// the index of the next shot
+    pub shot_index: usize,
+    reader: Box<dyn BufRead + Send>,
+    buffer: Vec<u8>,
+    /// the virtual vertex groups of the given initializer, see [`SolverInitializer::virtual_vertex_groups`]
+    virtual_vertex_groups: Option<Vec<Vec<VertexIndex>>>,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl std::fmt::Debug for StimEventReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("StimEventReader")
+            .field("path", &self.path)
+            .field("format", &self.format)
+            .field("num_detectors", &self.num_detectors)
+            .field("shot_index", &self.shot_index)
+            .finish()
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ExampleCode for StimEventReader {
+    fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        (&mut self.vertices, &mut self.edges)
+    }
+    fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
+        (&self.vertices, &self.edges)
+    }
+    /// the positions are made up, so only the groups of the given initializer are meaningful
+    fn virtual_vertex_groups(&self) -> Option<Vec<Vec<VertexIndex>>> {
+        self.virtual_vertex_groups.clone()
+    }
+    fn reorder_vertices(&mut self, sequential_vertices: &[VertexIndex]) -> Result<VertexPermutation, String> {
+        let permutation = reorder_code_vertices(&mut self.vertices, &mut self.edges, sequential_vertices)?;
+        for vertex_index in self.virtual_vertex_groups.iter_mut().flatten().flatten() {
+            *vertex_index = permutation.to_new(*vertex_index);
+        }
+        Ok(permutation)
+    }
+    fn generate_random_errors(&mut self, _seed: u64) -> SyndromePattern {
+        match self.next_shot() {
+            Ok(Some(syndrome_pattern)) => syndrome_pattern,
+            Ok(None) => panic!(
+                "reading more than the {} shots in {}, consider sampling more shots",
+                self.shot_index, self.path
+            ),
+            Err(error) => panic!("failed to read {}: {error}", self.path),
+        }
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl StimEventReader {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(path: &str, format: StimFormat, num_detectors: usize, initializer: &SolverInitializer) -> io::Result<Self> {
+        if num_detectors > initializer.vertex_num as usize {
+            return Err(invalid_data(format!(
+                "{num_detectors} detectors but the decoding graph has only {} vertices",
+                initializer.vertex_num
+            )));
+        }
+        let mut code = Self {
+            path: path.to_string(),
+            format,
+            num_detectors,
+            vertices: Vec::with_capacity(initializer.vertex_num as usize),
+            edges: Vec::with_capacity(initializer.weighted_edges.len()),
+            shot_index: 0,
+            reader: Box::new(BufReader::new(File::open(path)?)),
+            buffer: vec![],
+            virtual_vertex_groups: initializer.virtual_vertex_groups.clone(),
+        };
+        load_initializer(&mut code, initializer);
+        Ok(code)
+    }
+
+    /// read the next shot, or `None` at the end of the file
+    pub fn next_shot(&mut self) -> io::Result<Option<SyndromePattern>> {
+        let dense = read_stim_shot(
+            &mut self.reader,
+            self.format,
+            self.num_detectors,
+            "detector",
+            &mut self.buffer,
+            self.shot_index,
+        )?;
+        Ok(dense.map(|dense| {
+            self.shot_index += 1;
+            SyndromePattern::from_dense(&dense)
+        }))
+    }
+}
+
+/// read the `num_bits` bits of the next shot of a file written by Stim, or `None` at the end of the file; the bits are
+/// called `bit_name` in the error messages
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+fn read_stim_shot(
+    reader: &mut impl BufRead,
+    format: StimFormat,
+    num_bits: usize,
+    bit_name: &str,
+    buffer: &mut Vec<u8>,
+    shot_index: usize,
+) -> io::Result<Option<Vec<bool>>> {
+    let mut dense = Vec::with_capacity(num_bits);
+    match format {
+        StimFormat::B8 => {
+            let num_bytes = num_bits.div_ceil(8);
+            buffer.resize(num_bytes, 0);
+            let mut length = 0;
+            while length < num_bytes {
+                match reader.read(&mut buffer[length..])? {
+                    0 => break,
+                    read_length => length += read_length,
+                }
+            }
+            if length == 0 {
+                return Ok(None);
+            }
+            if length < num_bytes {
+                return Err(invalid_data(format!(
+                    "shot {shot_index} has only {length} bytes but {num_bytes} bytes are expected for {num_bits} {bit_name}s"
+                )));
+            }
+            for bit in 0..num_bytes * 8 {
+                let is_set = buffer[bit / 8] & (1 << (bit % 8)) != 0;
+                if bit < num_bits {
+                    dense.push(is_set);
+                } else if is_set {
+                    return Err(invalid_data(format!(
+                        "shot {shot_index} has {bit_name} {bit} set but there are only {num_bits} {bit_name}s"
+                    )));
+                }
+            }
+        }
+        StimFormat::Dets01 => {
+            buffer.clear();
+            if reader.read_until(b'\n', buffer)? == 0 {
+                return Ok(None);
+            }
+            let line = buffer.strip_suffix(b"\n").unwrap_or(buffer);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.len() != num_bits {
+                return Err(invalid_data(format!(
+                    "shot {shot_index} has {} {bit_name}s but {num_bits} are expected",
+                    line.len()
+                )));
+            }
+            for &character in line.iter() {
+                match character {
+                    b'0' => dense.push(false),
+                    b'1' => dense.push(true),
+                    _ => {
+                        return Err(invalid_data(format!(
+                            "shot {shot_index} has invalid character {:?}, expecting 0 or 1",
+                            character as char
+                        )))
                     }
-                    1 => {
-                        initializer = Some(serde_json::from_str(&value).unwrap());
+                }
+            }
+        }
+    }
+    Ok(Some(dense))
+}
+
+/// the number of shots in a file written by Stim with `num_bits` bits per shot, checking that the file size is consistent
+/// with it in the b8 format, or the first line in the 01 format, which is a hint of a wrong number of bits
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+pub fn count_stim_shots(path: &str, format: StimFormat, num_bits: usize) -> io::Result<usize> {
+    match format {
+        StimFormat::B8 => {
+            let num_bytes = num_bits.div_ceil(8) as u64;
+            let length = std::fs::metadata(path)?.len();
+            if num_bytes == 0 || length % num_bytes != 0 {
+                return Err(invalid_data(format!(
+                    "{path} has {length} bytes, which is not a multiple of the {num_bytes} bytes of each shot with {num_bits} bits"
+                )));
+            }
+            Ok((length / num_bytes) as usize)
+        }
+        StimFormat::Dets01 => {
+            let mut shots = 0;
+            for line in BufReader::new(File::open(path)?).lines() {
+                let line = line?;
+                if shots == 0 && line.trim_end().len() != num_bits {
+                    return Err(invalid_data(format!(
+                        "the first shot of {path} has {} bits but {num_bits} are expected",
+                        line.trim_end().len()
+                    )));
+                }
+                shots += 1;
+            }
+            Ok(shots)
+        }
+    }
+}
+
+/// reads the logical observables sampled by Stim, e.g. with `--obs_out`, one shot at a time; compare them with
+/// [`StimDetectorErrorModel::observable_flips`] of the correction to count the logical errors
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+pub struct StimObservableReader {
+    /// the observable file
+    pub path: String,
+    pub format: StimFormat,
+    pub num_observables: usize,
+    /// the index of the next shot
+    pub shot_index: usize,
+    reader: BufReader<File>,
+    buffer: Vec<u8>,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl StimObservableReader {
+    pub fn new(path: &str, format: StimFormat, num_observables: usize) -> io::Result<Self> {
+        Ok(Self {
+            path: path.to_string(),
+            format,
+            num_observables,
+            shot_index: 0,
+            reader: BufReader::new(File::open(path)?),
+            buffer: vec![],
+        })
+    }
+
+    /// read whether each observable is flipped in the next shot, or `None` at the end of the file
+    pub fn next_shot(&mut self) -> io::Result<Option<Vec<bool>>> {
+        let flips = read_stim_shot(
+            &mut self.reader,
+            self.format,
+            self.num_observables,
+            "observable",
+            &mut self.buffer,
+            self.shot_index,
+        )?;
+        self.shot_index += flips.is_some() as usize;
+        Ok(flips)
+    }
+}
+
+/// a graph-like detector error model of Stim, i.e. every error flips at most two detectors, possibly after being
+/// decomposed by `^`, e.g. `circuit.detector_error_model(decompose_errors=True, flatten_loops=True)`; the errors
+/// flipping the same detectors are merged into a single edge, see [`Self::parse`]
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StimDetectorErrorModel {
+    pub num_detectors: usize,
+    pub num_observables: usize,
+    /// the decoding graph whose first `num_detectors` vertices are the detectors, and every edge to the boundary has
+    /// its own virtual vertex, see [`SolverInitializer::from_check_matrix`]
+    pub initializer: SolverInitializer,
+    /// the error probability of each edge
+    pub probabilities: Vec<f64>,
+    /// the observables flipped by each edge
+    pub edge_observables: Vec<Vec<usize>>,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl StimDetectorErrorModel {
+    /// parse the text of a detector error model, where the weights are scaled such that the maximum half weight is
+    /// `max_half_weight` as in [`ExampleCode::compute_weights`]; the loops (`repeat` and `shift_detectors`) must be
+    /// flattened beforehand
+    #[allow(clippy::unnecessary_cast)]
+    pub fn parse(text: &str, max_half_weight: Weight) -> Result<Self, String> {
+        let mut num_detectors = 0;
+        let mut num_observables = 0;
+        // (sorted detectors, observables, probability) of each edge in the order of first appearance
+        let mut edges: Vec<(Vec<usize>, Vec<usize>, f64)> = vec![];
+        let mut edge_indices = HashMap::<Vec<usize>, usize>::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {message}", line_index + 1);
+            // the arguments in the parentheses may contain spaces, e.g. `detector(1, 2) D0`
+            let name_end = line.find(|c: char| c == '(' || c.is_whitespace()).unwrap_or(line.len());
+            let name = &line[..name_end];
+            let (argument, rest) = match line[name_end..].strip_prefix('(') {
+                Some(after) => match after.split_once(')') {
+                    Some((argument, rest)) => (Some(argument), rest),
+                    None => return Err(error(format!("missing `)` in `{line}`"))),
+                },
+                None => (None, &line[name_end..]),
+            };
+            let parse_target = |target: &str, prefix: char| -> Result<usize, String> {
+                (target.strip_prefix(prefix).and_then(|index| index.parse().ok()))
+                    .ok_or_else(|| error(format!("invalid target `{target}`")))
+            };
+            match name {
+                "error" => {
+                    let probability: f64 = argument
+                        .and_then(|argument| argument.trim().parse().ok())
+                        .ok_or_else(|| error(format!("invalid probability in `{line}`")))?;
+                    if !(0. ..=0.5).contains(&probability) {
+                        return Err(error(format!("error probability {probability} must be in [0, 0.5]")));
                     }
-                    2 => {
-                        positions = Some(serde_json::from_str(&value).unwrap());
+                    for component in rest.split('^') {
+                        let (mut detectors, mut observables) =
+                            (std::collections::BTreeSet::new(), std::collections::BTreeSet::new());
+                        for target in component.split_whitespace() {
+                            // a target appearing twice cancels itself
+                            let (targets, index) = if target.starts_with('L') {
+                                (&mut observables, parse_target(target, 'L')?)
+                            } else {
+                                (&mut detectors, parse_target(target, 'D')?)
+                            };
+                            if !targets.remove(&index) {
+                                targets.insert(index);
+                            }
+                        }
+                        num_detectors = num_detectors.max(detectors.last().map_or(0, |&index| index + 1));
+                        num_observables = num_observables.max(observables.last().map_or(0, |&index| index + 1));
+                        let detectors: Vec<usize> = detectors.into_iter().collect();
+                        let observables: Vec<usize> = observables.into_iter().collect();
+                        match detectors.len() {
+                            0 if observables.is_empty() => continue,
+                            0 => return Err(error("an error flips observables without any detector".to_string())),
+                            1 | 2 => {}
+                            _ => {
+                                return Err(error(format!(
+                                    "an error flips {} detectors, decompose it with `decompose_errors=True`",
+                                    detectors.len()
+                                )))
+                            }
+                        }
+                        if probability == 0. {
+                            continue;
+                        }
+                        match edge_indices.get(&detectors) {
+                            Some(&edge_index) => {
+                                let (_, existing_observables, existing_probability) = &mut edges[edge_index];
+                                if *existing_observables != observables {
+                                    return Err(error(format!(
+                                        "the errors flipping detectors {detectors:?} flip different observables"
+                                    )));
+                                }
+                                // either of the two independent errors happens
+                                *existing_probability =
+                                    *existing_probability * (1. - probability) + probability * (1. - *existing_probability);
+                            }
+                            None => {
+                                edge_indices.insert(detectors.clone(), edges.len());
+                                edges.push((detectors, observables, probability));
+                            }
+                        }
                     }
-                    _ => {
-                        let syndrome_pattern: SyndromePattern = serde_json::from_str(&value).unwrap();
-                        syndrome_patterns.push(syndrome_pattern);
+                }
+                "detector" => {
+                    for target in rest.split_whitespace() {
+                        num_detectors = num_detectors.max(parse_target(target, 'D')? + 1);
+                    }
+                }
+                "logical_observable" => {
+                    for target in rest.split_whitespace() {
+                        num_observables = num_observables.max(parse_target(target, 'L')? + 1);
+                    }
+                }
+                "repeat" | "shift_detectors" | "}" => {
+                    return Err(error(format!(
+                        "`{name}` is not supported, flatten the loops with `flatten_loops=True`"
+                    )))
+                }
+                _ => return Err(error(format!("unknown instruction `{name}`"))),
+            }
+        }
+        let probabilities: Vec<f64> = edges.iter().map(|(_, _, probability)| *probability).collect();
+        let float_weights: Vec<f64> = probabilities.iter().map(|&p| weight_of_p(p)).collect();
+        let max_weight = float_weights.iter().cloned().fold(0., f64::max);
+        let scale = if max_weight > 0. {
+            max_half_weight as f64 / max_weight
+        } else {
+            0.
+        };
+        let weights: Vec<Weight> = float_weights
+            .iter()
+            .map(|&weight| 2 * ((weight * scale).round() as Weight).max(1))
+            .collect();
+        let mut column_pointers = vec![0];
+        let mut row_indices = vec![];
+        for (detectors, _, _) in edges.iter() {
+            row_indices.extend(detectors.iter().map(|&detector| detector as VertexIndex));
+            column_pointers.push(row_indices.len());
+        }
+        let initializer =
+            SolverInitializer::from_check_matrix(num_detectors as VertexNum, &column_pointers, &row_indices, &weights)
+                .map_err(|error| error.to_string())?;
+        Ok(Self {
+            num_detectors,
+            num_observables,
+            initializer,
+            probabilities,
+            edge_observables: edges.into_iter().map(|(_, observables, _)| observables).collect(),
+        })
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &str, max_half_weight: Weight) -> io::Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?, max_half_weight)
+            .map_err(|error| invalid_data(format!("{path}: {error}")))
+    }
+
+    /// whether each observable is flipped by the correction, i.e. the edges of [`crate::mwpm_solver::PrimalDualSolver::subgraph`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn observable_flips(&self, subgraph: &[EdgeIndex]) -> Vec<bool> {
+        let mut flips = vec![false; self.num_observables];
+        for &edge_index in subgraph.iter() {
+            for &observable in self.edge_observables[edge_index as usize].iter() {
+                flips[observable] ^= true;
+            }
+        }
+        flips
+    }
+}
+
+/// the read-only part of a code needed to sample errors, shared by all the workers of [`ExampleCodeParallel`]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+#[derive(Debug)]
+struct ErrorModel {
+    /// the two vertices, the error probability and the erasure probability of every edge
+    edges: Vec<(VertexIndex, VertexIndex, f64, f64)>,
+    is_virtual: Vec<bool>,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ErrorModel {
+    fn new(vertices: &[CodeVertex], edges: &[CodeEdge]) -> Self {
+        Self {
+            edges: edges
+                .iter()
+                .map(|edge| (edge.vertices.0, edge.vertices.1, edge.p, edge.pe))
+                .collect(),
+            is_virtual: vertices.iter().map(|vertex| vertex.is_virtual).collect(),
+        }
+    }
+
+    fn memory_estimate(&self) -> usize {
+        size_of::<Self>()
+            + self.edges.capacity() * size_of::<(VertexIndex, VertexIndex, f64, f64)>()
+            + self.is_virtual.capacity() * size_of::<bool>()
+    }
+}
+
+/// the mutable state of a worker of [`ExampleCodeParallel`]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+#[derive(Debug, Clone, Default)]
+struct ErrorSampler {
+    is_defect: BitVec,
+    is_erasure: BitVec,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl ErrorSampler {
+    /// the same random numbers and the same syndrome pattern as [`ExampleCode::generate_random_errors`] given the seed
+    #[allow(clippy::unnecessary_cast)]
+    fn generate_random_errors(&mut self, model: &ErrorModel, seed: u64) -> SyndromePattern {
+        if self.is_defect.len() != model.is_virtual.len() {
+            self.is_defect = BitVec::new(model.is_virtual.len());
+        }
+        if self.is_erasure.len() != model.edges.len() {
+            self.is_erasure = BitVec::new(model.edges.len());
+        }
+        self.is_defect.clear();
+        self.is_erasure.clear();
+        let mut rng = DeterministicRng::seed_from_u64(seed);
+        for (edge_index, &(vertex_1, vertex_2, p, pe)) in model.edges.iter().enumerate() {
+            let p = if rng.next_f64() < pe {
+                self.is_erasure.set(edge_index);
+                0.5 // when erasure happens, there are 50% chance of error
+            } else {
+                p
+            };
+            if rng.next_f64() < p {
+                for vertex_index in [vertex_1 as usize, vertex_2 as usize] {
+                    if !model.is_virtual[vertex_index] {
+                        self.is_defect.flip(vertex_index);
                     }
                 }
             }
         }
-        let initializer = initializer.expect("initializer not present in file");
-        let positions = positions.expect("positions not present in file");
-        assert_eq!(positions.len(), initializer.vertex_num as usize);
-        let mut code = Self {
-            vertices: Vec::with_capacity(initializer.vertex_num as usize),
-            edges: Vec::with_capacity(initializer.weighted_edges.len()),
-            syndrome_patterns,
-            defect_index: 0,
-            cyclic_syndrome,
-        };
-        for (left_vertex, right_vertex, weight) in initializer.weighted_edges.iter() {
-            assert!(weight % 2 == 0, "weight must be even number");
-            code.edges.push(CodeEdge {
-                vertices: (*left_vertex, *right_vertex),
-                p: 0.,  // doesn't matter
-                pe: 0., // doesn't matter
-                half_weight: weight / 2,
-                is_erasure: false, // doesn't matter
-            });
-        }
-        // automatically create the vertices and nearest-neighbor connection
-        code.fill_vertices(initializer.vertex_num);
-        // set virtual vertices and positions
-        for (vertex_index, position) in positions.into_iter().enumerate() {
-            code.vertices[vertex_index].position = position;
-        }
-        for vertex_index in initializer.virtual_vertices {
-            code.vertices[vertex_index as usize].is_virtual = true;
-        }
-        code
+        SyndromePattern::new(
+            self.is_defect.ones().map(|index| index as VertexIndex).collect(),
+            self.is_erasure.ones().map(|index| index as EdgeIndex).collect(),
+        )
+    }
+}
+
+/// the seeds of the workers in a batch whose first syndrome pattern uses `seed`, see [`ExampleCodeParallel`]
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+fn worker_seeds(seed: u64, code_count: usize, seeding: Seeding) -> Vec<u64> {
+    match seeding {
+        Seeding::V1 => (0..code_count)
+            .map(|code_index| seed + (code_index * 1_000_000_000) as u64)
+            .collect(),
+        Seeding::V2 => std::iter::successors(Some(seed), |&seed| Some(ShotSeeder::next_seed(seed)))
+            .take(code_count)
+            .collect(),
+    }
+}
+
+/// the seed of the first syndrome pattern in the batch after the one starting with `seed`
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+fn next_batch_seed(seed: u64, code_count: usize, seeding: Seeding) -> u64 {
+    match seeding {
+        Seeding::V1 => seed + code_count as u64,
+        Seeding::V2 => (0..code_count).fold(seed, |seed, _| ShotSeeder::next_seed(seed)),
     }
 }
 
-/// generate error patterns in parallel by hold multiple instances of the same code type
-pub struct ExampleCodeParallel<CodeType: ExampleCode + Sync + Send + Clone> {
+/// generate a batch of syndrome patterns, one by each worker
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+fn generate_batch(
+    samplers: &mut [ErrorSampler],
+    model: &ErrorModel,
+    seeds: &[u64],
+    thread_pool: Option<&rayon::ThreadPool>,
+) -> Vec<SyndromePattern> {
+    let mut generate = || -> Vec<SyndromePattern> {
+        samplers
+            .par_iter_mut()
+            .zip(seeds.par_iter())
+            .map(|(sampler, &seed)| sampler.generate_random_errors(model, seed))
+            .collect()
+    };
+    match thread_pool {
+        Some(thread_pool) => thread_pool.install(generate),
+        None => generate(),
+    }
+}
+
+/// generates the batches of [`ExampleCodeParallel`] in a background thread, ahead of the consumer
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+struct Prefetcher {
+    receiver: std::sync::mpsc::Receiver<Vec<SyndromePattern>>,
+    /// returns the samplers when the receiver is dropped
+    handle: std::thread::JoinHandle<Vec<ErrorSampler>>,
+    /// the seed of the next batch in the channel
+    next_seed: u64,
+}
+
+/// generate error patterns in parallel with multiple workers; the workers share a read-only copy of the edge
+/// probabilities and each only keeps the defect vertices and the erasures it samples, so the memory barely grows with
+/// `code_count`. with [`Seeding::V1`], worker `i` samples the errors of seed `seed + i * 1_000_000_000` with the default
+/// [`ExampleCode::generate_random_errors`] of the code, where `seed` is given to the first syndrome pattern of the batch
+/// and the seeds of the others are ignored. with [`Seeding::V2`], worker `i` runs `i` shots ahead, i.e. it applies
+/// [`ShotSeeder::next_seed`] `i` times, and a batch is regenerated whenever the given seed is not the predicted one, so
+/// every syndrome pattern is that of the code with the given seed regardless of `code_count`. not available in
+/// WebAssembly which has no threads.
+///
+/// with a positive `prefetch_depth`, up to this number of batches are generated in a background thread while the
+/// consumer decodes the current one. the background thread expects the seeds of consecutive shots (e.g.
+/// `--use-deterministic-seed`, or any [`ShotSeeder`] with [`Seeding::V2`]); at the first unexpected seed the prefetched
+/// batches are discarded and the batches are generated on demand from then on, so the syndrome patterns never depend on
+/// timing
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+pub struct ExampleCodeParallel<CodeType: ExampleCode + Sync + Send> {
     /// used to provide graph
     pub example: CodeType,
-    /// list of codes
-    pub codes: Vec<ArcRwLock<CodeType>>,
-    /// syndrome patterns generated by individual code
+    /// the number of workers, each generating a syndrome pattern per round
+    pub code_count: usize,
+    /// syndrome patterns generated by individual workers
     pub syndrome_patterns: Vec<SyndromePattern>,
     /// currently using code
     pub code_index: usize,
+    /// run the generators in this thread pool if provided, otherwise in the global rayon thread pool
+    pub thread_pool: Option<Arc<rayon::ThreadPool>>,
+    /// built from `example` when generating errors and dropped whenever `example` is mutably borrowed, so that
+    /// changes like [`ExampleCode::set_erasure_probability`] are respected
+    error_model: Option<Arc<ErrorModel>>,
+    /// empty while they are moved to the prefetcher
+    samplers: Vec<ErrorSampler>,
+    /// the number of batches generated ahead in the background, or 0 to generate them on demand
+    pub prefetch_depth: usize,
+    prefetcher: Option<Prefetcher>,
+    /// whether the seeds are those of consecutive shots so far, see [`ExampleCodeParallel`]
+    predictable_seeds: bool,
+    /// how the seeds of the workers are derived, see [`ExampleCodeParallel`]
+    pub seeding: Seeding,
+    /// the seeds of the current batch
+    batch_seeds: Vec<u64>,
 }
 
-impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCodeParallel<CodeType> {
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl<CodeType: ExampleCode + Sync + Send> ExampleCodeParallel<CodeType> {
     pub fn new(example: CodeType, code_count: usize) -> Self {
-        let mut codes = vec![];
-        for _ in 0..code_count {
-            codes.push(ArcRwLock::<CodeType>::new_value(example.clone()));
-        }
         Self {
             example,
-            codes,
+            code_count,
             syndrome_patterns: vec![],
             code_index: 0,
+            thread_pool: None,
+            error_model: None,
+            samplers: vec![ErrorSampler::default(); code_count],
+            prefetch_depth: 0,
+            prefetcher: None,
+            predictable_seeds: true,
+            seeding: Seeding::default(),
+            batch_seeds: vec![],
+        }
+    }
+
+    pub fn new_with_prefetch(example: CodeType, code_count: usize, prefetch_depth: usize) -> Self {
+        let mut code = Self::new(example, code_count);
+        code.prefetch_depth = prefetch_depth;
+        code
+    }
+
+    pub fn new_with_thread_pool(example: CodeType, code_count: usize, thread_pool: Arc<rayon::ThreadPool>) -> Self {
+        let mut code = Self::new(example, code_count);
+        code.thread_pool = Some(thread_pool);
+        code
+    }
+
+    fn error_model(&mut self) -> Arc<ErrorModel> {
+        let (vertices, edges) = self.example.immutable_vertices_edges();
+        self.error_model
+            .get_or_insert_with(|| Arc::new(ErrorModel::new(vertices, edges)))
+            .clone()
+    }
+
+    fn generate_all_random_errors(&mut self, seed: u64) {
+        self.batch_seeds = worker_seeds(seed, self.code_count, self.seeding);
+        if self.prefetch_depth > 0 && self.predictable_seeds {
+            if self
+                .prefetcher
+                .as_ref()
+                .is_some_and(|prefetcher| prefetcher.next_seed != seed)
+            {
+                self.predictable_seeds = false;
+                self.stop_prefetching();
+            } else {
+                if self.prefetcher.is_none() {
+                    self.start_prefetching(seed);
+                }
+                let prefetcher = self.prefetcher.as_mut().unwrap();
+                self.syndrome_patterns = prefetcher.receiver.recv().expect("the prefetching thread panicked");
+                prefetcher.next_seed = next_batch_seed(seed, self.code_count, self.seeding);
+                return;
+            }
+        }
+        let model = self.error_model();
+        let thread_pool = self.thread_pool.as_deref();
+        self.syndrome_patterns = generate_batch(&mut self.samplers, &model, &self.batch_seeds, thread_pool);
+    }
+
+    /// generate the batches starting from `seed` in the background
+    fn start_prefetching(&mut self, seed: u64) {
+        let model = self.error_model();
+        let mut samplers = std::mem::take(&mut self.samplers);
+        let thread_pool = self.thread_pool.clone();
+        let (code_count, seeding) = (self.code_count, self.seeding);
+        let (sender, receiver) = std::sync::mpsc::sync_channel(self.prefetch_depth);
+        let handle = std::thread::spawn(move || {
+            let mut batch_seed = seed;
+            loop {
+                let seeds = worker_seeds(batch_seed, code_count, seeding);
+                let batch = generate_batch(&mut samplers, &model, &seeds, thread_pool.as_deref());
+                if sender.send(batch).is_err() {
+                    break; // the consumer stops prefetching
+                }
+                batch_seed = next_batch_seed(batch_seed, code_count, seeding);
+            }
+            samplers
+        });
+        self.prefetcher = Some(Prefetcher {
+            receiver,
+            handle,
+            next_seed: seed,
+        });
+    }
+
+    /// discard the prefetched batches and take back the samplers
+    fn stop_prefetching(&mut self) {
+        if let Some(Prefetcher { receiver, handle, .. }) = self.prefetcher.take() {
+            drop(receiver);
+            self.samplers = handle.join().expect("the prefetching thread panicked");
+        }
+    }
+
+    /// an analytic estimate of the memory in bytes, including the example code
+    pub fn memory_estimate(&self) -> usize {
+        let (vertices, edges) = self.example.immutable_vertices_edges();
+        let mut bytes = size_of::<Self>() + edges.capacity() * size_of::<CodeEdge>();
+        bytes += vertices.capacity() * size_of::<CodeVertex>();
+        for vertex in vertices.iter() {
+            bytes += vertex.neighbor_edges.capacity() * size_of::<EdgeIndex>();
         }
+        if let Some(model) = self.error_model.as_ref() {
+            bytes += model.memory_estimate();
+        }
+        for sampler in self.samplers.iter() {
+            bytes += size_of::<ErrorSampler>() + sampler.is_defect.memory_estimate() + sampler.is_erasure.memory_estimate();
+        }
+        for syndrome_pattern in self.syndrome_patterns.iter() {
+            bytes += size_of::<SyndromePattern>()
+                + syndrome_pattern.defect_vertices.capacity() * size_of::<VertexIndex>()
+                + syndrome_pattern.erasures.capacity() * size_of::<EdgeIndex>();
+        }
+        bytes
     }
 }
 
-impl<CodeType: ExampleCode + Sync + Send + Clone> ExampleCode for ExampleCodeParallel<CodeType> {
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl<CodeType: ExampleCode + Sync + Send> ExampleCode for ExampleCodeParallel<CodeType> {
     fn vertices_edges(&mut self) -> (&mut Vec<CodeVertex>, &mut Vec<CodeEdge>) {
+        self.stop_prefetching();
+        self.error_model = None;
         self.example.vertices_edges()
     }
     fn immutable_vertices_edges(&self) -> (&Vec<CodeVertex>, &Vec<CodeEdge>) {
         self.example.immutable_vertices_edges()
     }
     fn generate_random_errors(&mut self, seed: u64) -> SyndromePattern {
-        if self.code_index == 0 {
+        let unexpected_seed =
+            self.seeding == Seeding::V2 && self.code_index != 0 && self.batch_seeds[self.code_index] != seed;
+        if self.code_index == 0 || unexpected_seed {
             // run generator in parallel
-            (0..self.codes.len())
-                .into_par_iter()
-                .map(|code_index| {
-                    self.codes[code_index]
-                        .write()
-                        .generate_random_errors(seed + (code_index * 1_000_000_000) as u64)
-                })
-                .collect_into_vec(&mut self.syndrome_patterns);
+            self.code_index = 0;
+            self.generate_all_random_errors(seed);
         }
         let syndrome_pattern = self.syndrome_patterns[self.code_index].clone();
-        self.code_index = (self.code_index + 1) % self.codes.len();
+        self.code_index = (self.code_index + 1) % self.code_count;
         syndrome_pattern
     }
 }
 
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
+impl<CodeType: ExampleCode + Sync + Send> Drop for ExampleCodeParallel<CodeType> {
+    fn drop(&mut self) {
+        self.stop_prefetching();
+    }
+}
+
 #[cfg(feature = "python_binding")]
 #[pyfunction]
 pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
@@ -1296,14 +4090,17 @@ pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(weight_of_p, m)?)?;
     m.add_class::<CodeCapacityRepetitionCode>()?;
     m.add_class::<CodeCapacityPlanarCode>()?;
+    m.add_class::<CodeCapacityToricCode>()?;
     m.add_class::<PhenomenologicalPlanarCode>()?;
     m.add_class::<CircuitLevelPlanarCode>()?;
     m.add_class::<CodeCapacityRotatedCode>()?;
     m.add_class::<PhenomenologicalRotatedCode>()?;
+    m.add_class::<ErrorPatternReader>()?;
+    m.add_class::<SyndromeFileIterator>()?;
     Ok(())
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "minimal")))]
 mod tests {
     use super::*;
 
@@ -1315,10 +4112,13 @@ mod tests {
             true,
         )
         .unwrap();
-        visualizer.snapshot(format!("code"), code).unwrap();
+        visualizer.set_run_metadata(code.run_metadata()).unwrap();
+        visualizer.snapshot(format!("code"), code, None).unwrap();
         for round in 0..3 {
             code.generate_random_errors(round);
-            visualizer.snapshot(format!("syndrome {}", round + 1), code).unwrap();
+            visualizer
+                .snapshot(format!("syndrome {}", round + 1), code, Some(json!({ "seed": round })))
+                .unwrap();
         }
     }
 
@@ -1327,6 +4127,7 @@ mod tests {
         // cargo test example_code_capacity_repetition_code -- --nocapture
         let mut code = CodeCapacityRepetitionCode::new(7, 0.2, 500);
         code.sanity_check().unwrap();
+        assert_eq!(code.min_boundary_distance().0, 7);
         visualize_code(&mut code, format!("example_code_capacity_repetition_code.json"));
     }
 
@@ -1335,14 +4136,34 @@ mod tests {
         // cargo test example_code_capacity_planar_code -- --nocapture
         let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
         code.sanity_check().unwrap();
+        assert_eq!(code.min_boundary_distance().0, 7);
         visualize_code(&mut code, format!("example_code_capacity_planar_code.json"));
     }
 
+    #[test]
+    fn example_code_capacity_toric_code() {
+        // cargo test example_code_capacity_toric_code -- --nocapture
+        let mut code = CodeCapacityToricCode::new(5, 0.1, 500);
+        code.sanity_check().unwrap();
+        assert!(code.get_initializer().virtual_vertices.is_empty());
+        assert_eq!(code.run_metadata()["d"], json!(5));
+        // a single error is not a logical error but a row of errors winding around the torus is
+        assert!(!code.is_logical_error(&[0], &[]));
+        let row_errors: Vec<EdgeIndex> = (0..5).map(|column| 2 * column).collect();
+        assert!(code.apply_error_edges(&row_errors).defect_vertices.is_empty());
+        assert!(code.is_logical_error(&row_errors, &[]));
+        let column_errors: Vec<EdgeIndex> = (0..5).map(|row| 10 * row + 1).collect();
+        assert!(code.apply_error_edges(&column_errors).defect_vertices.is_empty());
+        assert!(code.is_logical_error(&[], &column_errors));
+        visualize_code(&mut code, "example_code_capacity_toric_code.json".to_string());
+    }
+
     #[test]
     fn example_phenomenological_planar_code() {
         // cargo test example_phenomenological_planar_code -- --nocapture
         let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.01, 500);
         code.sanity_check().unwrap();
+        assert_eq!(code.min_boundary_distance().0, 7);
         visualize_code(&mut code, format!("example_phenomenological_planar_code.json"));
     }
 
@@ -1359,14 +4180,135 @@ mod tests {
         // cargo test example_circuit_level_planar_code -- --nocapture
         let mut code = CircuitLevelPlanarCode::new(7, 7, 0.01, 500);
         code.sanity_check().unwrap();
+        assert_eq!(code.min_boundary_distance().0, 7);
         visualize_code(&mut code, format!("example_circuit_level_planar_code.json"));
     }
 
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_asymmetric_probabilities() {
+        // cargo test example_code_asymmetric_probabilities -- --nocapture
+        let (p_data, p_measurement) = (0.01, 0.002);
+        // the half weights of the intra-layer, inter-layer and diagonal edges, classified by the positions here
+        fn half_weights_by_layer(code: &impl ExampleCode) -> [Vec<Weight>; 3] {
+            let (vertices, edges) = code.immutable_vertices_edges();
+            let mut half_weights: [Vec<Weight>; 3] = Default::default();
+            for edge in edges.iter() {
+                let (p1, p2) = (
+                    &vertices[edge.vertices.0 as usize].position,
+                    &vertices[edge.vertices.1 as usize].position,
+                );
+                let layer = match (p1.t == p2.t, p1.i == p2.i && p1.j == p2.j) {
+                    (true, _) => 0,
+                    (false, true) => 1,
+                    (false, false) => 2,
+                };
+                half_weights[layer].push(edge.half_weight);
+            }
+            half_weights
+        }
+        let assert_ratio = |half_weight: Weight, max_half_weight: Weight, p: f64, max_p: f64| {
+            let ratio = half_weight as f64 / max_half_weight as f64;
+            assert!((ratio - weight_of_p(p) / weight_of_p(max_p)).abs() < 1. / max_half_weight as f64);
+        };
+        let code = PhenomenologicalPlanarCode::new_asymmetric(5, 4, p_data, p_measurement, 500);
+        code.sanity_check().unwrap();
+        let [intra, inter, diagonal] = half_weights_by_layer(&code);
+        assert_eq!(
+            (intra.len(), inter.len(), diagonal.len()),
+            (5 * (5 * 5 + 4 * 4), 4 * 5 * 4, 0)
+        );
+        assert!(inter.iter().all(|&half_weight| half_weight == 500));
+        assert!(intra.iter().all(|&half_weight| half_weight == intra[0]));
+        assert_ratio(intra[0], 500, p_data, p_measurement);
+        let code = CircuitLevelPlanarCode::new_asymmetric(5, 4, p_data, p_measurement, 500);
+        code.sanity_check().unwrap();
+        let [intra, inter, diagonal] = half_weights_by_layer(&code);
+        assert!(!diagonal.is_empty());
+        assert!(inter.iter().all(|&half_weight| half_weight == 500));
+        assert_ratio(intra[0], 500, p_data, p_measurement);
+        assert_ratio(diagonal[0], 500, p_data / 3., p_measurement);
+        // the same rates give the symmetric codes
+        let half_weights = |edges: &[CodeEdge]| edges.iter().map(|edge| edge.half_weight).collect::<Vec<_>>();
+        assert_eq!(
+            half_weights(&PhenomenologicalPlanarCode::new_asymmetric(5, 4, 0.03, 0.03, 500).edges),
+            half_weights(&PhenomenologicalPlanarCode::new(5, 4, 0.03, 500).edges)
+        );
+        assert_eq!(
+            half_weights(&CircuitLevelPlanarCode::new_asymmetric(5, 4, 0.03, 0.03, 500).edges),
+            half_weights(&CircuitLevelPlanarCode::new(5, 4, 0.03, 500).edges)
+        );
+        assert!(PhenomenologicalPlanarCode::try_new_asymmetric(5, 4, p_data, 0.5, 500).is_err());
+        assert!(CircuitLevelPlanarCode::try_new_asymmetric(5, 4, 0.5, p_measurement, 500).is_err());
+    }
+
+    #[test]
+    fn example_code_asymmetric_initializer_diff() {
+        // cargo test example_code_asymmetric_initializer_diff -- --nocapture
+        PhenomenologicalPlanarCode::new_asymmetric(5, 4, 0.03, 0.03, 500)
+            .get_initializer()
+            .assert_equivalent(&PhenomenologicalPlanarCode::new(5, 4, 0.03, 500).get_initializer());
+        CircuitLevelPlanarCode::new_asymmetric(5, 4, 0.03, 0.03, 500)
+            .get_initializer()
+            .assert_equivalent(&CircuitLevelPlanarCode::new(5, 4, 0.03, 500).get_initializer());
+        // different rates only change the weights, but not the graph
+        let diff = CircuitLevelPlanarCode::new(5, 4, 0.03, 500)
+            .get_initializer()
+            .diff(&CircuitLevelPlanarCode::new_asymmetric(5, 4, 0.03, 0.01, 500).get_initializer());
+        assert!(!diff.changed_weights.is_empty());
+        assert_eq!(diff.vertex_num, None);
+        assert!(diff.added_edges.is_empty() && diff.removed_edges.is_empty());
+        assert!(diff.added_virtual_vertices.is_empty() && diff.removed_virtual_vertices.is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_edges_with_positions() {
+        // cargo test example_code_edges_with_positions -- --nocapture
+        let (_, layers) = CircuitLevelPlanarCode::create_code_with_layers(5, 4);
+        let diagonal_p = 0.01;
+        let code = CircuitLevelPlanarCode::new_diagonal(5, 4, 0.03, 500, Some(diagonal_p));
+        assert_eq!(code.iter_edges_with_positions().count(), layers.len());
+        for ((edge_index, edge, v1p, v2p), layer) in code.iter_edges_with_positions().zip(layers) {
+            let (v1, v2) = edge.vertices;
+            assert_eq!(v1p, &code.vertices[v1 as usize].position);
+            assert_eq!(v2p, &code.vertices[v2 as usize].position);
+            // the diagonal edges found by the positions are exactly those created as diagonal
+            assert_eq!(edge.p == diagonal_p, layer == EdgeLayer::Diagonal, "edge {edge_index}");
+            let length = code.edge_length(edge_index);
+            if layer == EdgeLayer::Diagonal {
+                assert!(length == 2f64.sqrt() || length == 3f64.sqrt(), "edge {edge_index}: {length}");
+            } else {
+                assert_eq!(length, 1., "edge {edge_index}");
+            }
+        }
+    }
+
+    #[test]
+    fn example_code_weight_sanity_check() {
+        // cargo test example_code_weight_sanity_check -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        code.sanity_check().unwrap();
+        // the weights are twice the half weights and thus never odd
+        code.vertices_edges().1[3].half_weight = 0;
+        let error = code.sanity_check().unwrap_err();
+        assert!(error.contains("(3, NonPositive)"), "{error}");
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        code.vertices_edges().1[4].half_weight = Weight::MAX / 4;
+        let error = code.sanity_check().unwrap_err();
+        assert!(error.contains("(4, TooLarge)"), "{error}");
+        if cfg!(debug_assertions) {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| code.get_initializer()));
+            assert!(result.is_err());
+        }
+    }
+
     #[test]
     fn example_code_capacity_rotated_code() {
         // cargo test example_code_capacity_rotated_code -- --nocapture
         let mut code = CodeCapacityRotatedCode::new(5, 0.1, 500);
         code.sanity_check().unwrap();
+        assert_eq!(code.min_boundary_distance().0, 5);
         visualize_code(&mut code, format!("example_code_capacity_rotated_code.json"));
     }
 
@@ -1375,6 +4317,953 @@ mod tests {
         // cargo test example_code_phenomenological_rotated_code -- --nocapture
         let mut code = PhenomenologicalRotatedCode::new(5, 5, 0.01, 500);
         code.sanity_check().unwrap();
+        assert_eq!(code.min_boundary_distance().0, 5);
         visualize_code(&mut code, format!("example_code_phenomenological_rotated_code.json"));
     }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_edge_lookup() {
+        // cargo test example_code_edge_lookup -- --nocapture
+        let code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let (vertices, edges) = code.immutable_vertices_edges();
+        let edge_index = code.build_edge_index();
+        assert_eq!(edge_index.len(), edges.len());
+        let vertex_num = vertices.len() as VertexIndex;
+        for a in 0..vertex_num {
+            for b in 0..vertex_num {
+                let expected = edges
+                    .iter()
+                    .position(|edge| edge.vertices == (a, b) || edge.vertices == (b, a))
+                    .map(|edge_index| edge_index as EdgeIndex);
+                assert_eq!(code.edge_between(a, b), expected, "vertices {a} and {b}");
+                assert_eq!(edge_index.edge_between(a, b), expected, "vertices {a} and {b}");
+            }
+            for &edge in code.edges_of_vertex(a) {
+                let (v1, v2) = edges[edge as usize].vertices;
+                assert!(v1 == a || v2 == a);
+            }
+        }
+        // far away vertices are not connected
+        assert_eq!(code.edge_between(0, vertex_num - 1), None);
+        assert_eq!(edge_index.edge_between(0, vertex_num - 1), None);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_set_syndrome_validation() {
+        // cargo test example_code_set_syndrome_validation -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let vertex_num = code.vertex_num();
+        let edge_num = code.immutable_vertices_edges().1.len() as EdgeIndex;
+        let virtual_vertex = code.get_initializer().virtual_vertices[0];
+        code.set_defect_vertices(&[0, 1]).unwrap();
+        code.set_erasures(&[2]).unwrap();
+        // out of range defect vertex
+        let error = code.set_defect_vertices(&[3, vertex_num]).unwrap_err();
+        assert!(error.contains(&format!("{}", vertex_num)), "{error}");
+        assert!(error.contains(&format!("[0, {})", vertex_num)), "{error}");
+        // virtual vertex cannot be a defect
+        let error = code.set_defect_vertices(&[virtual_vertex]).unwrap_err();
+        let position = code.get_positions()[virtual_vertex as usize].clone();
+        assert!(error.contains(&format!("defect vertex {} ", virtual_vertex)), "{error}");
+        assert!(
+            error.contains(&format!("(i: {}, j: {}, t: {})", position.i, position.j, position.t)),
+            "{error}"
+        );
+        assert!(error.contains("virtual"), "{error}");
+        // out of range erasure
+        let error = code.set_erasures(&[edge_num + 3]).unwrap_err();
+        assert!(error.contains(&format!("{}", edge_num + 3)), "{error}");
+        assert!(error.contains(&format!("[0, {})", edge_num)), "{error}");
+        // an invalid syndrome leaves the code untouched, even if the defect vertices are valid
+        let error = code.set_syndrome(&SyndromePattern::new(vec![3], vec![edge_num])).unwrap_err();
+        assert!(error.contains("erasure edge"), "{error}");
+        assert_eq!(code.get_defect_vertices(), vec![0, 1]);
+        assert_eq!(code.get_erasures(), vec![2]);
+        code.set_syndrome(&SyndromePattern::new(vec![3], vec![4])).unwrap();
+        assert_eq!(code.get_defect_vertices(), vec![3]);
+        assert_eq!(code.get_erasures(), vec![4]);
+        // the unchecked variants skip validation
+        code.set_syndrome_unchecked(&SyndromePattern::new(vec![virtual_vertex], vec![]));
+        assert_eq!(code.get_defect_vertices(), vec![virtual_vertex]);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_erasure_region() {
+        // cargo test example_code_erasure_region -- --nocapture
+        use crate::mwpm_solver::{PrimalDualSolver, SolverSerial};
+        let mut code = CodeCapacityPlanarCode::new(9, 0.1, 500);
+        let ((min_i, min_j, _), (max_i, max_j, _)) = code.positions_bounding_box();
+        let center = VisualizePosition::new((min_i + max_i) / 2., (min_j + max_j) / 2., 0.);
+        let radius = 1.5;
+        let in_ball = |position: &VisualizePosition| {
+            ((position.i - center.i).powi(2) + (position.j - center.j).powi(2) + (position.t - center.t).powi(2)).sqrt()
+                <= radius
+        };
+        let count = code.set_erasure_region(|p1, p2| in_ball(p1) && in_ball(p2));
+        // brute-force check on the positions of the endpoints
+        let positions = code.get_positions();
+        let expected: Vec<EdgeIndex> = (0..code.edges.len() as EdgeIndex)
+            .filter(|&edge_index| {
+                let (v1, v2) = code.edges[edge_index as usize].vertices;
+                in_ball(&positions[v1 as usize]) && in_ball(&positions[v2 as usize])
+            })
+            .collect();
+        assert!(count > 0);
+        assert_eq!(count, expected.len());
+        assert_eq!(code.get_erasures(), expected);
+        // the region is added on top of existing erasures
+        code.clear_erasures();
+        code.set_erasures(&[0]).unwrap();
+        assert_eq!(code.set_erasure_region(|p1, p2| in_ball(p1) && in_ball(p2)), count);
+        assert_eq!(code.get_erasures().len(), count + 1);
+        // an error inside the region is corrected by the erased edges only
+        let defect_vertices = code.apply_error_edges(&expected[..1]).defect_vertices;
+        code.set_erasure_region(|p1, p2| in_ball(p1) && in_ball(p2));
+        code.set_defect_vertices(&defect_vertices).unwrap();
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        solver.solve(&code.get_syndrome());
+        let subgraph = solver.subgraph();
+        assert!(!subgraph.is_empty());
+        assert!(subgraph.iter().all(|edge_index| expected.contains(edge_index)));
+        code.clear_erasures();
+        assert!(code.get_erasures().is_empty());
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_min_boundary_distance() {
+        // cargo test example_code_min_boundary_distance -- --nocapture
+        for d in [3, 5, 7, 9, 11] {
+            let code = CodeCapacityPlanarCode::new(d, 0.1, 500);
+            let (left, right) = code.logical_boundaries();
+            assert_eq!((left.len(), right.len()), (d as usize, d as usize));
+            let initializer = code.get_initializer();
+            assert_eq!(initializer.virtual_vertex_groups, Some(vec![left.clone(), right.clone()]));
+            for (group_index, group) in [&left, &right].into_iter().enumerate() {
+                assert!(group.iter().all(|&vertex| initializer.group_of(vertex) == Some(group_index)));
+            }
+            // all edges have the same weight
+            assert_eq!(code.min_boundary_distance(), (d as usize, d as Weight * 1000));
+            // a cheaper edge on one of the shortest paths reduces the weight but not the number of edges
+            let mut code = code;
+            code.edges[0].half_weight = 100;
+            assert_eq!(code.min_boundary_distance(), (d as usize, (d as Weight - 1) * 1000 + 200));
+        }
+        assert_eq!(CodeCapacityRepetitionCode::new(9, 0.1, 500).min_boundary_distance().0, 9);
+        assert_eq!(PhenomenologicalPlanarCode::new(9, 3, 0.01, 500).min_boundary_distance().0, 9);
+        assert_eq!(CodeCapacityRotatedCode::new(9, 0.1, 500).min_boundary_distance().0, 9);
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_initializer_filtered() {
+        // cargo test example_code_initializer_filtered -- --nocapture
+        use crate::mwpm_solver::{PrimalDualSolver, SolverSerial};
+        let mut code = CircuitLevelPlanarCode::new(5, 5, 0.01, 500);
+        let positions = code.get_positions();
+        let is_diagonal = |edge: &CodeEdge| {
+            let (p1, p2) = (&positions[edge.vertices.0 as usize], &positions[edge.vertices.1 as usize]);
+            (p1.i - p2.i).abs() + (p1.j - p2.j).abs() + (p1.t - p2.t).abs() > 1.
+        };
+        let diagonal_num = code.edges.iter().filter(|edge| is_diagonal(edge)).count();
+        assert!(diagonal_num > 0);
+        let (initializer, edge_map) = code.get_initializer_filtered(|edge| !is_diagonal(edge));
+        assert_eq!(initializer.weighted_edges.len(), code.edges.len() - diagonal_num);
+        assert_eq!(initializer.weighted_edges.len(), edge_map.new_edge_num());
+        assert_eq!(edge_map.original_edge_num(), code.edges.len());
+        assert_eq!(initializer.vertex_num, code.vertex_num());
+        assert_eq!(initializer.virtual_vertices, code.get_initializer().virtual_vertices);
+        for (new_index, &(v1, v2, weight)) in initializer.weighted_edges.iter().enumerate() {
+            let edge_index = edge_map.original_edge(new_index as EdgeIndex);
+            assert_eq!(edge_map.map_edge(edge_index), Some(new_index as EdgeIndex));
+            let edge = &code.edges[edge_index as usize];
+            assert_eq!((v1, v2, weight), (edge.vertices.0, edge.vertices.1, edge.half_weight * 2));
+        }
+        // including every edge is the same as the default initializer
+        let (full_initializer, full_map) = code.get_initializer_filtered(|_| true);
+        assert_eq!(full_initializer, code.get_initializer());
+        assert_eq!(full_map, EdgeIndexMap::identity(code.edges.len()));
+        // decode on the filtered graph and translate the correction back to the original edges
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..20 {
+            let (syndrome_pattern, _) = code.generate_random_errors_with_edges(seed);
+            solver.solve(&syndrome_pattern);
+            let correction = edge_map.original_edges(&solver.subgraph());
+            assert!(correction
+                .iter()
+                .all(|&edge_index| !is_diagonal(&code.edges[edge_index as usize])));
+            assert_eq!(
+                code.apply_error_edges(&correction).defect_vertices,
+                syndrome_pattern.defect_vertices
+            );
+            solver.clear();
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_code_edge_index_map() {
+        // cargo test example_code_edge_index_map -- --nocapture
+        use crate::mwpm_solver::{PrimalDualSolver, SolverSerial};
+        use crate::simulator::InitializerSampler;
+        let mut code = CircuitLevelPlanarCode::new(5, 5, 0.01, 500);
+        let positions = code.get_positions();
+        let is_diagonal = |edge: &CodeEdge| {
+            let (p1, p2) = (&positions[edge.vertices.0 as usize], &positions[edge.vertices.1 as usize]);
+            (p1.i - p2.i).abs() + (p1.j - p2.j).abs() + (p1.t - p2.t).abs() > 1.
+        };
+        let is_timelike =
+            |vertex_1: VertexIndex, vertex_2: VertexIndex| positions[vertex_1 as usize].t != positions[vertex_2 as usize].t;
+        // first remove the diagonal edges and then the time-like ones, composing the maps
+        let (filtered, diagonal_map) = code.get_initializer_filtered(|edge| !is_diagonal(edge));
+        let (initializer, timelike_map) = filtered.filtered(|_, &(vertex_1, vertex_2, _)| !is_timelike(vertex_1, vertex_2));
+        let edge_map = diagonal_map.then(&timelike_map);
+        assert_eq!(edge_map.original_edge_num(), code.edges.len());
+        assert_eq!(edge_map.new_edge_num(), initializer.weighted_edges.len());
+        assert_eq!(edge_map.then(&EdgeIndexMap::identity(edge_map.new_edge_num())), edge_map);
+        let kept_num = (code.edges.iter())
+            .filter(|edge| !is_diagonal(edge) && !is_timelike(edge.vertices.0, edge.vertices.1))
+            .count();
+        assert_eq!(kept_num, initializer.weighted_edges.len());
+        // erasures sampled on the original edges land on the edges with the same end points
+        let edge_probabilities: Vec<f64> = code.edges.iter().map(|edge| edge.p).collect();
+        let sampler =
+            InitializerSampler::new(&code.get_initializer(), &edge_probabilities, &vec![0.05; code.edges.len()]).unwrap();
+        for seed in 0..10 {
+            let (syndrome_pattern, _) = sampler.sample(seed);
+            let erasures = &syndrome_pattern.erasures;
+            assert!(!erasures.is_empty());
+            let mapped = edge_map.map_erasures(&syndrome_pattern);
+            assert_eq!(mapped.defect_vertices, syndrome_pattern.defect_vertices);
+            let expected: Vec<_> = (erasures.iter())
+                .map(|&edge_index| &code.edges[edge_index as usize])
+                .filter(|edge| !is_diagonal(edge) && !is_timelike(edge.vertices.0, edge.vertices.1))
+                .map(|edge| {
+                    (
+                        positions[edge.vertices.0 as usize].clone(),
+                        positions[edge.vertices.1 as usize].clone(),
+                    )
+                })
+                .collect();
+            let actual: Vec<_> = (mapped.erasures.iter())
+                .map(|&edge_index| initializer.weighted_edges[edge_index as usize])
+                .map(|(vertex_1, vertex_2, _)| (positions[vertex_1 as usize].clone(), positions[vertex_2 as usize].clone()))
+                .collect();
+            assert_eq!(actual, expected);
+            // the mapped syndrome is decoded on the transformed graph and the correction is mapped back
+            let mut solver = SolverSerial::new(&initializer);
+            solver.solve(&mapped);
+            let correction = edge_map.original_edges(&solver.subgraph());
+            assert_eq!(
+                code.apply_error_edges(&correction).defect_vertices,
+                syndrome_pattern.defect_vertices
+            );
+        }
+    }
+
+    #[test]
+    fn example_code_reorder_vertices_permutation() {
+        // cargo test example_code_reorder_vertices_permutation -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(5, 3, 0.05, 500);
+        let syndrome_pattern = code.generate_random_errors(1);
+        code.set_erasures(&[3, 7]).unwrap();
+        let initializer = code.get_initializer();
+        let vertex_num = code.vertex_num();
+        // a repeated index is rejected without modifying the code
+        let mut duplicated: Vec<VertexIndex> = (0..vertex_num).collect();
+        duplicated[5] = 2;
+        let error = code.reorder_vertices(&duplicated).unwrap_err();
+        assert!(error.contains("duplicate vertex 2"), "{error}");
+        let error = code.reorder_vertices(&(1..=vertex_num).collect::<Vec<_>>()).unwrap_err();
+        assert!(error.contains(&format!("vertex {vertex_num}")), "{error}");
+        let error = code.reorder_vertices(&(0..vertex_num - 1).collect::<Vec<_>>()).unwrap_err();
+        assert!(error.contains(&format!("the code has {vertex_num} vertices")), "{error}");
+        assert_eq!(code.get_initializer(), initializer);
+        assert_eq!(code.get_defect_vertices(), syndrome_pattern.defect_vertices);
+        // the defect and erasure flags follow the reordering, and so does the stored syndrome
+        let sequential_vertices: Vec<VertexIndex> = (0..vertex_num).map(|index| (index * 7) % vertex_num).collect();
+        let permutation = code.reorder_vertices(&sequential_vertices).unwrap();
+        assert_eq!(permutation.sequential_vertices, sequential_vertices);
+        let mapped = permutation.map_syndrome(&syndrome_pattern);
+        assert_eq!(code.get_defect_vertices(), mapped.defect_vertices);
+        assert_eq!(code.get_erasures(), vec![3, 7]);
+        // reordering by the inverse permutation restores the original numbering
+        let inverse = code.reorder_vertices(&permutation.old_to_new).unwrap();
+        assert_eq!(inverse.map_syndrome(&mapped), syndrome_pattern);
+        assert_eq!(code.get_initializer(), initializer);
+        assert_eq!(code.get_defect_vertices(), syndrome_pattern.defect_vertices);
+    }
+
+    #[test]
+    fn example_code_run_metadata() {
+        // cargo test example_code_run_metadata -- --nocapture
+        let codes: Vec<(Box<dyn ExampleCode>, VertexNum, Option<VertexNum>)> = vec![
+            (Box::new(CodeCapacityRepetitionCode::new(7, 0.2, 500)), 7, None),
+            (Box::new(CodeCapacityPlanarCode::new(7, 0.1, 500)), 7, None),
+            (Box::new(PhenomenologicalPlanarCode::new(5, 4, 0.01, 500)), 5, Some(4)),
+            (Box::new(CircuitLevelPlanarCode::new(3, 6, 0.01, 500)), 3, Some(6)),
+            (Box::new(CodeCapacityRotatedCode::new(9, 0.1, 500)), 9, None),
+            (Box::new(PhenomenologicalRotatedCode::new(5, 2, 0.01, 500)), 5, Some(2)),
+        ];
+        for (code, d, noisy_measurements) in codes.iter() {
+            let run_metadata = code.run_metadata();
+            println!("{run_metadata}");
+            assert_eq!(run_metadata["d"], json!(d));
+            assert_eq!(
+                run_metadata.get("noisy_measurements"),
+                noisy_measurements.map(|n| json!(n)).as_ref()
+            );
+            assert_eq!(run_metadata["max_half_weight"], json!(500));
+            assert_eq!(run_metadata["edge_num"], json!(code.get_initializer().weighted_edges.len()));
+        }
+        assert_eq!(codes[0].0.run_metadata()["code_type"], json!("CodeCapacityRepetitionCode"));
+    }
+
+    /// a repetition code of 10 detectors in a chain, with a virtual vertex on each side
+    fn stim_repetition_initializer() -> SolverInitializer {
+        let mut weighted_edges = vec![(10, 0, 100), (9, 11, 100)];
+        weighted_edges.extend((0..9).map(|i| (i, i + 1, 100)));
+        SolverInitializer::new(12, weighted_edges, vec![10, 11])
+    }
+
+    fn stim_fixture(filename: &str) -> String {
+        format!("{}/tests/stim/{filename}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn example_stim_event_reader() {
+        // cargo test example_stim_event_reader -- --nocapture
+        let initializer = stim_repetition_initializer();
+        for (filename, format) in [("repetition_10.b8", StimFormat::B8), ("repetition_10.01", StimFormat::Dets01)] {
+            let mut code = StimEventReader::new(&stim_fixture(filename), format, 10, &initializer).unwrap();
+            assert_eq!(code.get_initializer(), initializer);
+            assert_eq!(code.generate_random_errors(0).defect_vertices, Vec::<VertexIndex>::new());
+            assert_eq!(code.generate_random_errors(0).defect_vertices, vec![0, 9]);
+            assert_eq!(code.next_shot().unwrap().unwrap().defect_vertices, vec![3, 4, 8]);
+            assert!(code.next_shot().unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn example_code_normalize_positions() {
+        // cargo test example_code_normalize_positions -- --nocapture
+        let initializer = stim_repetition_initializer();
+        let mut code = StimEventReader::new(&stim_fixture("repetition_10.b8"), StimFormat::B8, 10, &initializer).unwrap();
+        // the imported graph has no positions: all of them coincide and must not be scaled to NaN
+        code.normalize_positions(10.);
+        assert_eq!(code.positions_bounding_box(), ((0., 0., 0.), (0., 0., 0.)));
+        // detector coordinates far from the origin with an aspect ratio of 4:1
+        for (vertex_index, vertex) in code.vertices.iter_mut().enumerate() {
+            vertex.position = VisualizePosition::new(
+                1000. + 400. * (vertex_index % 2) as f64,
+                3000. + 200. * vertex_index as f64,
+                5000.,
+            );
+        }
+        let vertex_num = code.vertices.len() as f64;
+        code.normalize_positions(10.);
+        let ((min_i, min_j, min_t), (max_i, max_j, max_t)) = code.positions_bounding_box();
+        assert!((max_j - min_j - 10.).abs() < 1e-9);
+        assert!((max_i - min_i - 10. * 400. / (200. * (vertex_num - 1.))).abs() < 1e-9);
+        assert_eq!((min_t, max_t), (0., 0.));
+        let positions = code.get_positions();
+        let centroid_j: f64 = positions.iter().map(|position| position.j).sum::<f64>() / vertex_num;
+        assert!(centroid_j.abs() < 1e-9);
+        // normalizing again is idempotent
+        code.normalize_positions(10.);
+        for (position, normalized) in positions.iter().zip(code.get_positions().iter()) {
+            assert!((position.i - normalized.i).abs() < 1e-9 && (position.j - normalized.j).abs() < 1e-9);
+        }
+        // the visualizer auto-center then centers the bounding box without scaling
+        let centered = center_positions(positions.clone());
+        for (position, centered) in positions.iter().zip(centered.iter()) {
+            assert!((position.i - (min_i + max_i) / 2. - centered.i).abs() < 1e-9);
+            assert!((position.j - (min_j + max_j) / 2. - centered.j).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn example_stim_event_reader_invalid() {
+        // cargo test example_stim_event_reader_invalid -- --nocapture
+        let initializer = stim_repetition_initializer();
+        let mut code = StimEventReader::new(&stim_fixture("wrong_length.01"), StimFormat::Dets01, 10, &initializer).unwrap();
+        assert!(code.next_shot().unwrap().is_some());
+        let error = code.next_shot().unwrap_err();
+        assert_eq!(error.to_string(), "shot 1 has 9 detectors but 10 are expected");
+        let mut code = StimEventReader::new(&stim_fixture("truncated.b8"), StimFormat::B8, 10, &initializer).unwrap();
+        assert!(code.next_shot().unwrap().is_some());
+        let error = code.next_shot().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "shot 1 has only 1 bytes but 2 bytes are expected for 10 detectors"
+        );
+        // the padding bits of the b8 format must be zero, otherwise the number of detectors is probably wrong
+        let mut code = StimEventReader::new(&stim_fixture("repetition_10.b8"), StimFormat::B8, 9, &initializer).unwrap();
+        assert!(code.next_shot().unwrap().is_some());
+        let error = code.next_shot().unwrap_err();
+        assert_eq!(error.to_string(), "shot 1 has detector 9 set but there are only 9 detectors");
+        assert!(StimEventReader::new(&stim_fixture("repetition_10.01"), StimFormat::Dets01, 13, &initializer).is_err());
+    }
+
+    #[test]
+    fn example_stim_detector_error_model() {
+        // cargo test example_stim_detector_error_model -- --nocapture
+        let dem = StimDetectorErrorModel::load(&stim_fixture("repetition_10.dem"), 50).unwrap();
+        assert_eq!((dem.num_detectors, dem.num_observables), (10, 1));
+        let mut weighted_edges = vec![(0, 10, 100)];
+        weighted_edges.extend((0..9).map(|i| (i, i + 1, 100)));
+        weighted_edges.push((9, 11, 100));
+        assert_eq!(dem.initializer.vertex_num, 12);
+        assert_eq!(dem.initializer.weighted_edges, weighted_edges);
+        assert_eq!(dem.initializer.virtual_vertices, vec![10, 11]);
+        assert_eq!(dem.observable_flips(&[0, 1]), vec![true]);
+        assert_eq!(dem.observable_flips(&[0, 10]), vec![true]);
+        assert_eq!(dem.observable_flips(&[2, 10]), vec![false]);
+        // the components of a decomposed error are merged into the edges flipping the same detectors
+        let dem = StimDetectorErrorModel::parse(
+            "error(0.1) D0 D1 ^ D1 L0 # a hook error\nerror(0.2) D1 D0\nerror(0) D2\nerror(0.1) L0 D1 L0 L0",
+            500,
+        )
+        .unwrap();
+        assert_eq!((dem.num_detectors, dem.num_observables), (3, 1));
+        assert_eq!(dem.edge_observables, vec![vec![], vec![0]]);
+        assert!((dem.probabilities[0] - (0.1 * 0.8 + 0.2 * 0.9)).abs() < 1e-12);
+        assert!((dem.probabilities[1] - (0.1 * 0.9 + 0.1 * 0.9)).abs() < 1e-12);
+        assert_eq!(dem.initializer.vertex_num, 4);
+        assert_eq!(dem.initializer.weighted_edges[1].2, 1000);
+        for (text, message) in [
+            (
+                "error(0.1) D0 D1 D2",
+                "line 1: an error flips 3 detectors, decompose it with `decompose_errors=True`",
+            ),
+            (
+                "error(0.1) D0\nrepeat 10 {",
+                "line 2: `repeat` is not supported, flatten the loops with `flatten_loops=True`",
+            ),
+            ("error(0.1) L0", "line 1: an error flips observables without any detector"),
+            (
+                "error(0.1) D0\nerror(0.1) D0 L0",
+                "line 2: the errors flipping detectors [0] flip different observables",
+            ),
+            ("error(0.6) D0", "line 1: error probability 0.6 must be in [0, 0.5]"),
+            ("error D0", "line 1: invalid probability in `error D0`"),
+            ("error(0.1) X0", "line 1: invalid target `X0`"),
+            ("tick", "line 1: unknown instruction `tick`"),
+        ] {
+            assert_eq!(StimDetectorErrorModel::parse(text, 500).unwrap_err(), message);
+        }
+    }
+
+    #[test]
+    fn example_stim_observable_reader() {
+        // cargo test example_stim_observable_reader -- --nocapture
+        let mut reader = StimObservableReader::new(&stim_fixture("repetition_10_obs.b8"), StimFormat::B8, 1).unwrap();
+        for expected in [false, true, true] {
+            assert_eq!(reader.next_shot().unwrap(), Some(vec![expected]));
+        }
+        assert!(reader.next_shot().unwrap().is_none());
+        assert_eq!(
+            count_stim_shots(&stim_fixture("repetition_10_obs.b8"), StimFormat::B8, 1).unwrap(),
+            3
+        );
+        assert_eq!(
+            count_stim_shots(&stim_fixture("repetition_10.b8"), StimFormat::B8, 10).unwrap(),
+            3
+        );
+        assert_eq!(
+            count_stim_shots(&stim_fixture("repetition_10.01"), StimFormat::Dets01, 10).unwrap(),
+            3
+        );
+        let error = count_stim_shots(&stim_fixture("repetition_10.b8"), StimFormat::B8, 33).unwrap_err();
+        assert!(error
+            .to_string()
+            .ends_with("has 6 bytes, which is not a multiple of the 5 bytes of each shot with 33 bits"));
+        assert!(count_stim_shots(&stim_fixture("repetition_10.01"), StimFormat::Dets01, 9).is_err());
+    }
+
+    #[test]
+    fn example_error_pattern_reader_stream() {
+        // cargo test example_error_pattern_reader_stream -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let filename = format!("{}/example_error_pattern_reader_stream.syndromes", visualize_data_folder());
+        let mut file = SyndromeFile::new(code.get_initializer(), code.get_positions());
+        for seed in 0..25 {
+            file.push(code.generate_random_errors(seed), None);
+        }
+        file.save(&filename, false).unwrap();
+        let sequential_vertices: Vec<VertexIndex> = (0..code.vertex_num()).rev().collect();
+        let mut preloaded =
+            ErrorPatternReader::new(json!({ "filename": filename, "preload": true, "cyclic_syndrome": true }));
+        let mut streamed =
+            ErrorPatternReader::new(json!({ "filename": filename, "read_ahead": 7, "cyclic_syndrome": true }));
+        assert_eq!(preloaded.get_initializer(), streamed.get_initializer());
+        // reorder twice to check that the translations are composed
+        for code in [&mut preloaded, &mut streamed] {
+            code.reorder_vertices(&sequential_vertices).unwrap();
+            code.reorder_vertices(&sequential_vertices).unwrap();
+        }
+        assert_eq!(streamed.syndrome_pattern_num, None);
+        for index in 0..60 {
+            let syndrome_pattern = streamed.generate_random_errors(0);
+            assert_eq!(syndrome_pattern, preloaded.generate_random_errors(0));
+            assert_eq!(syndrome_pattern, file.syndrome_patterns[index % 25]);
+        }
+        assert_eq!(streamed.syndrome_pattern_num, Some(25));
+        // without `cyclic_syndrome`, reading more than in the file is an error
+        for preload in [true, false] {
+            let mut code = ErrorPatternReader::new(json!({ "filename": filename, "preload": preload }));
+            for _ in 0..25 {
+                code.next_syndrome_pattern().unwrap();
+            }
+            let error = code.next_syndrome_pattern().unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+            assert!(error.to_string().contains("reading syndrome pattern 26 but"), "{error}");
+            assert!(error.to_string().contains("has only 25"), "{error}");
+        }
+    }
+
+    #[test]
+    fn example_syndrome_file_gzip() {
+        // cargo test example_syndrome_file_gzip -- --nocapture
+        use crate::mwpm_solver::{PrimalDualSolver, SolverErrorPatternLogger};
+        let mut code = PhenomenologicalPlanarCode::new(5, 4, 0.02, 500);
+        let folder = visualize_data_folder();
+        let mut reference = SyndromeFile::new(code.get_initializer(), code.get_positions());
+        for seed in 0..1000 {
+            let (syndrome_pattern, error_edges) = code.generate_random_errors_with_edges(seed);
+            reference.push(syndrome_pattern, Some(error_edges));
+        }
+        let reference_filename = format!("{folder}/example_syndrome_file_gzip_reference.syndromes");
+        reference.save(&reference_filename, false).unwrap();
+        let reference_size = std::fs::metadata(&reference_filename).unwrap().len();
+        let check = |filename: &str| {
+            for preload in [true, false] {
+                let mut reader = ErrorPatternReader::new(json!({ "filename": filename, "preload": preload }));
+                assert_eq!(reader.get_initializer(), reference.initializer);
+                for (syndrome_pattern, error_edges) in reference.syndrome_patterns.iter().zip(reference.error_edges.iter()) {
+                    assert_eq!(
+                        &reader.next_syndrome_pattern().unwrap(),
+                        &(syndrome_pattern.clone(), error_edges.clone())
+                    );
+                }
+                assert!(reader.next_syndrome_pattern().is_err());
+            }
+        };
+        for bincode in [false, true] {
+            let filename = format!("{folder}/example_syndrome_file_gzip_{bincode}.syndromes.gz");
+            reference.save(&filename, bincode).unwrap();
+            assert!(std::fs::metadata(&filename).unwrap().len() < reference_size / 3);
+            check(&filename);
+            // compressed files are detected by the magic bytes as well
+            let filename = format!("{folder}/example_syndrome_file_gzip_{bincode}_no_extension.syndromes");
+            let mut output = GzEncoder::new(File::create(&filename).unwrap(), Compression::default());
+            reference.write(&mut output, bincode).unwrap();
+            output.finish().unwrap();
+            check(&filename);
+            // the logger writes compressed files as well
+            let filename = format!("{folder}/example_syndrome_file_gzip_{bincode}_logger.syndromes.gz");
+            let mut logger = SolverErrorPatternLogger::new(
+                &reference.initializer,
+                &reference.positions,
+                json!({ "filename": filename, "bincode": bincode, "error_edges": true }),
+            );
+            for (syndrome_pattern, error_edges) in reference.syndrome_patterns.iter().zip(reference.error_edges.iter()) {
+                logger.set_error_edges(error_edges.as_ref().unwrap());
+                logger.solve(syndrome_pattern);
+            }
+            drop(logger);
+            check(&filename);
+        }
+    }
+
+    #[test]
+    fn example_syndrome_file_legacy_bincode() {
+        // cargo test example_syndrome_file_legacy_bincode -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.05, 500);
+        let initializer = code.get_initializer();
+        assert!(initializer.virtual_vertex_groups.is_some());
+        let positions = code.get_positions();
+        // the format before the virtual vertex groups are added to the initializer
+        let folder = visualize_data_folder();
+        let filename = format!("{folder}/example_syndrome_file_legacy_bincode.syndromes");
+        let mut output = BufWriter::new(File::create(&filename).unwrap());
+        writeln!(output, "{SYNDROME_FILE_BINCODE_V2_0_HEADER}").unwrap();
+        let legacy_initializer = (
+            initializer.vertex_num,
+            &initializer.weighted_edges,
+            &initializer.virtual_vertices,
+        );
+        bincode::serialize_into(&mut output, &legacy_initializer).unwrap();
+        bincode::serialize_into(&mut output, &positions).unwrap();
+        let syndrome_patterns: Vec<_> = (0..10).map(|seed| code.generate_random_errors(seed)).collect();
+        for syndrome_pattern in syndrome_patterns[..5].iter() {
+            bincode::serialize_into(&mut output, &(syndrome_pattern, None::<Vec<EdgeIndex>>)).unwrap();
+        }
+        drop(output);
+        // appending keeps the legacy header, even though the given initializer has the groups
+        let mut writer = SyndromeFileWriter::append(&filename, &initializer, &positions, true, false).unwrap();
+        for syndrome_pattern in syndrome_patterns[5..].iter() {
+            writer.write(syndrome_pattern, None).unwrap();
+        }
+        writer.finalize().unwrap();
+        let syndrome_file = SyndromeFile::load(&filename).unwrap();
+        assert_eq!(syndrome_file.initializer.virtual_vertex_groups, None);
+        assert_eq!(
+            syndrome_file.initializer,
+            SolverInitializer::new(
+                initializer.vertex_num,
+                initializer.weighted_edges.clone(),
+                initializer.virtual_vertices.clone()
+            )
+        );
+        assert_eq!(syndrome_file.syndrome_patterns, syndrome_patterns);
+        // the reader falls back to the groups computed from the positions
+        let reader = ErrorPatternReader::new(json!({ "filename": filename }));
+        assert_eq!(reader.get_initializer(), initializer);
+        // the groups are kept in the current format
+        let filename = format!("{folder}/example_syndrome_file_legacy_bincode_v2_1.syndromes");
+        let mut syndrome_file = syndrome_file;
+        syndrome_file.initializer.virtual_vertex_groups = Some(vec![initializer.virtual_vertices.clone()]);
+        syndrome_file.save(&filename, true).unwrap();
+        let reloaded = SyndromeFile::load(&filename).unwrap();
+        assert_eq!(reloaded.initializer, syndrome_file.initializer);
+        assert_eq!(reloaded.syndrome_patterns, syndrome_file.syndrome_patterns);
+        let reader = ErrorPatternReader::new(json!({ "filename": filename }));
+        let groups = reader.virtual_vertex_groups();
+        assert_eq!(groups, syndrome_file.initializer.virtual_vertex_groups);
+    }
+
+    #[test]
+    fn example_syndrome_file_crash_recovery() {
+        // cargo test example_syndrome_file_crash_recovery -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(7, 0.05, 500);
+        let (initializer, positions) = (code.get_initializer(), code.get_positions());
+        let records: Vec<_> = (0..1000)
+            .map(|seed| {
+                let (syndrome_pattern, error_edges) = code.generate_random_errors_with_edges(seed);
+                (syndrome_pattern, Some(error_edges))
+            })
+            .collect();
+        let read_all = |filename: &str| {
+            let mut reader = SyndromeFileReader::open(filename).unwrap();
+            let records: Vec<_> = (&mut reader).map(|record| record.unwrap()).collect();
+            (records, reader.truncated, reader.footer)
+        };
+        for bincode in [false, true] {
+            let filename = format!(
+                "{}/example_syndrome_file_crash_recovery_{bincode}.syndromes",
+                visualize_data_folder()
+            );
+            let _ = std::fs::remove_file(&filename);
+            let mut writer = SyndromeFileWriter::append(&filename, &initializer, &positions, bincode, true).unwrap();
+            writer.flush_shots = 100;
+            for (syndrome_pattern, error_edges) in records[..650].iter() {
+                writer.write(syndrome_pattern, error_edges.as_deref()).unwrap();
+            }
+            // crash: the buffered syndrome patterns since the last flush are lost without running any destructor
+            std::mem::forget(writer);
+            let (recovered, _, footer) = read_all(&filename);
+            assert!(recovered.len() >= 600, "every flushed syndrome pattern is recovered");
+            assert_eq!(recovered[..], records[..recovered.len()]);
+            assert_eq!(footer, None);
+            // a write torn in the middle of a syndrome pattern is reported as a truncated tail
+            let file = std::fs::OpenOptions::new().write(true).open(&filename).unwrap();
+            let length = file.metadata().unwrap().len();
+            file.set_len(length - 3).unwrap();
+            let (recovered, truncated, _) = read_all(&filename);
+            assert!(truncated);
+            assert_eq!(recovered[..], records[..recovered.len()]);
+            // appending resumes after the last complete syndrome pattern, and the footer counts all of them
+            let mut writer = SyndromeFileWriter::append(&filename, &initializer, &positions, bincode, true).unwrap();
+            assert_eq!(writer.count(), recovered.len());
+            for (syndrome_pattern, error_edges) in records[recovered.len()..].iter() {
+                writer.write(syndrome_pattern, error_edges.as_deref()).unwrap();
+            }
+            writer.finalize().unwrap().finish().unwrap();
+            assert_eq!(read_all(&filename), (records.clone(), false, Some(1000)));
+            assert_eq!(read_syndrome_file_footer(&filename).unwrap(), Some(1000));
+            let mut reader = ErrorPatternReader::new(json!({ "filename": filename }));
+            assert_eq!(reader.count().unwrap(), 1000);
+            assert_eq!(reader.get(999).unwrap(), records[999]);
+            // the header must match before appending
+            let other = CodeCapacityPlanarCode::new(5, 0.05, 500);
+            let error =
+                SyndromeFileWriter::append(&filename, &other.get_initializer(), &other.get_positions(), bincode, true)
+                    .err()
+                    .unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert!(SyndromeFileWriter::append(&filename, &initializer, &positions, !bincode, true).is_err());
+        }
+    }
+
+    #[test]
+    fn example_code_parallel_shared_graph() {
+        // cargo test example_code_parallel_shared_graph -- --nocapture
+        let mut example = CircuitLevelPlanarCode::new(7, 6, 0.005, 500);
+        example.set_erasure_probability(0.001);
+        let mut reference = example.clone();
+        let code_count = 16;
+        let mut code = ExampleCodeParallel::new(example.clone(), code_count);
+        // the same syndrome patterns as generating them one by one with the seed offsets of the workers
+        for seed in [0, 7] {
+            for code_index in 0..code_count {
+                assert_eq!(
+                    code.generate_random_errors(seed),
+                    reference.generate_random_errors(seed + (code_index * 1_000_000_000) as u64)
+                );
+            }
+        }
+        // the workers only add their bitsets and syndrome patterns, far less than a copy of the code
+        let (vertices, edges) = example.immutable_vertices_edges();
+        let code_size = vertices.len() * size_of::<CodeVertex>() + edges.len() * size_of::<CodeEdge>();
+        let mut single = ExampleCodeParallel::new(example.clone(), 1);
+        single.generate_random_errors(0);
+        let per_worker = (code.memory_estimate() - single.memory_estimate()) / (code_count - 1);
+        println!("code: {code_size} bytes, per worker: {per_worker} bytes");
+        assert!(per_worker * 20 < code_size, "{per_worker} bytes per worker");
+        // changing the example code through the wrapper is respected
+        code.set_erasure_probability(0.);
+        reference.set_erasure_probability(0.);
+        assert_eq!(code.generate_random_errors(3), reference.generate_random_errors(3));
+    }
+
+    #[test]
+    fn example_code_parallel_prefetch() {
+        // cargo test example_code_parallel_prefetch -- --nocapture
+        let example = PhenomenologicalPlanarCode::new(5, 4, 0.02, 500);
+        let code_count = 8;
+        // the batch of shot `round` starts with seed `round`, like in the original implementation without prefetching
+        let mut reference = example.clone();
+        let expected: Vec<_> = (0..1000)
+            .map(|round| {
+                let batch_seed = round - round % code_count;
+                reference.generate_random_errors((batch_seed + (round % code_count) * 1_000_000_000) as u64)
+            })
+            .collect();
+        for prefetch_depth in [0, 1, 3] {
+            let mut code = ExampleCodeParallel::new_with_prefetch(example.clone(), code_count, prefetch_depth);
+            for (round, syndrome_pattern) in expected.iter().enumerate() {
+                assert_eq!(&code.generate_random_errors(round as u64), syndrome_pattern);
+            }
+            assert_eq!(code.prefetcher.is_some(), prefetch_depth > 0);
+            // unexpected seeds stop prefetching without changing the syndrome patterns
+            for (round, seed) in [(1000, 12345), (1008, 3)] {
+                assert_eq!(code.generate_random_errors(seed), reference.generate_random_errors(seed));
+                for code_index in 1..code_count {
+                    assert_eq!(
+                        code.generate_random_errors(round + code_index as u64),
+                        reference.generate_random_errors(seed + (code_index * 1_000_000_000) as u64)
+                    );
+                }
+            }
+            assert!(code.prefetcher.is_none());
+        }
+    }
+
+    #[test]
+    fn example_code_parallel_seeding_v2() {
+        // cargo test example_code_parallel_seeding_v2 -- --nocapture
+        let example = PhenomenologicalPlanarCode::new(5, 4, 0.02, 500);
+        let mut reference = example.clone();
+        let seeder = ShotSeeder::new(42);
+        let expected: Vec<_> = (0..50)
+            .map(|shot| reference.generate_random_errors(seeder.seed_for(shot)))
+            .collect();
+        // every syndrome pattern is that of the given seed, regardless of the number of workers and prefetching
+        for code_count in [1, 3, 8] {
+            for prefetch_depth in [0, 2] {
+                let mut code = ExampleCodeParallel::new_with_prefetch(example.clone(), code_count, prefetch_depth);
+                code.seeding = Seeding::V2;
+                for (shot, syndrome_pattern) in expected.iter().enumerate() {
+                    assert_eq!(&code.generate_random_errors(seeder.seed_for(shot as u64)), syndrome_pattern);
+                }
+                // including an unexpected seed in the middle of a batch
+                assert_eq!(code.generate_random_errors(12345), reference.generate_random_errors(12345));
+                assert_eq!(code.generate_random_errors(seeder.seed_for(0)), expected[0]);
+            }
+        }
+        // and regardless of the number of threads of the generator and the batch size of the workers
+        let build_code = |code_count: usize| {
+            move || -> Box<dyn ExampleCode> {
+                let mut code = ExampleCodeParallel::new(PhenomenologicalPlanarCode::new(5, 4, 0.02, 500), code_count);
+                code.seeding = Seeding::V2;
+                Box::new(code)
+            }
+        };
+        let expected =
+            SyndromeGenerator::new(|| -> Box<dyn ExampleCode> { Box::new(example.clone()) }, 1, false).generate(30, 7);
+        for (code_count, threads) in [(1, 1), (4, 1), (3, 4)] {
+            assert_eq!(
+                SyndromeGenerator::new(build_code(code_count), threads, false).generate(30, 7),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn example_error_pattern_reader_random_access() {
+        // cargo test example_error_pattern_reader_random_access -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let mut file = SyndromeFile::new(code.get_initializer(), code.get_positions());
+        for seed in 0..25 {
+            file.push(code.generate_random_errors(seed), None);
+        }
+        for (bincode, extension) in [(false, "syndromes"), (true, "syndromes.gz")] {
+            let filename = format!(
+                "{}/example_error_pattern_reader_random_access.{extension}",
+                visualize_data_folder()
+            );
+            file.save(&filename, bincode).unwrap();
+            for preload in [true, false] {
+                let mut reader = ErrorPatternReader::new(json!({ "filename": filename, "preload": preload, "cycle": true }));
+                assert_eq!(reader.generate_random_errors(0), file.syndrome_patterns[0]);
+                for index in [24, 0, 13, 1] {
+                    assert_eq!(reader.get(index).unwrap().0, file.syndrome_patterns[index]);
+                }
+                assert_eq!(reader.count().unwrap(), 25);
+                let error = reader.get(25).unwrap_err();
+                assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+                assert_eq!(
+                    error.to_string(),
+                    "syndrome pattern index 25 out of range for 25 syndrome patterns"
+                );
+                // `get` doesn't move the cursor, and the syndrome patterns wrap around in the same order
+                for shot in 1..60 {
+                    assert_eq!(reader.generate_random_errors(0), file.syndrome_patterns[shot % 25]);
+                    assert_eq!(reader.cycle_count, shot / 25);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn example_syndrome_file_error_edges() {
+        // cargo test example_syndrome_file_error_edges -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(5, 4, 0.03, 500);
+        let initializer = code.get_initializer();
+        let mut file = SyndromeFile::new(initializer.clone(), code.get_positions());
+        for seed in 0..200 {
+            let (syndrome_pattern, error_edges) = code.generate_random_errors_with_edges(seed);
+            file.push(syndrome_pattern, Some(error_edges));
+        }
+        assert!(file.has_error_edges());
+        let is_virtual = |vertex_index: VertexIndex| initializer.virtual_vertices.contains(&vertex_index);
+        for bincode in [false, true] {
+            let filename = format!(
+                "{}/example_syndrome_file_error_edges_{bincode}.syndromes",
+                visualize_data_folder()
+            );
+            file.save(&filename, bincode).unwrap();
+            if !bincode {
+                let content = std::fs::read_to_string(&filename).unwrap();
+                assert_eq!(content.lines().next().unwrap(), SYNDROME_FILE_ERROR_EDGES_HEADER);
+            }
+            let mut reader = ErrorPatternReader::new(json!({ "filename": filename }));
+            assert_eq!(reader.last_error_edges(), None);
+            for index in 0..200 {
+                let (syndrome_pattern, error_edges) = reader.generate_random_errors_with_edges(0);
+                assert_eq!(reader.last_error_edges(), Some(error_edges.as_slice()));
+                assert_eq!(Some(&error_edges), file.error_edges[index].as_ref());
+                // the endpoints of the error edges flip exactly the defect vertices
+                let mut defect_vertices = std::collections::BTreeSet::new();
+                #[allow(clippy::unnecessary_cast)]
+                for &edge_index in error_edges.iter() {
+                    let (vertex_1, vertex_2, _) = initializer.weighted_edges[edge_index as usize];
+                    for vertex_index in [vertex_1, vertex_2] {
+                        if !is_virtual(vertex_index) && !defect_vertices.remove(&vertex_index) {
+                            defect_vertices.insert(vertex_index);
+                        }
+                    }
+                }
+                let expected: std::collections::BTreeSet<VertexIndex> =
+                    syndrome_pattern.defect_vertices.iter().cloned().collect();
+                assert_eq!(defect_vertices, expected, "shot {index}");
+            }
+        }
+        // files without error edges still load, and the writer checks the consistency
+        let filename = format!("{}/example_syndrome_file_error_edges_none.syndromes", visualize_data_folder());
+        let mut writer = SyndromeFileWriter::create(&filename, &file.initializer, &file.positions, false, false).unwrap();
+        writer.write(&file.syndrome_patterns[0], None).unwrap();
+        let error = writer
+            .write(&file.syndrome_patterns[1], file.error_edges[1].as_deref())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        writer.finish().unwrap().finish().unwrap();
+        let content = std::fs::read_to_string(&filename).unwrap();
+        assert_eq!(content.lines().next().unwrap(), SYNDROME_FILE_HEADER);
+        let mut reader = ErrorPatternReader::new(json!({ "filename": filename }));
+        assert_eq!(reader.generate_random_errors(0), file.syndrome_patterns[0]);
+        assert_eq!(reader.last_error_edges(), None);
+    }
+
+    #[test]
+    fn example_syndrome_generator_progress() {
+        // cargo test example_syndrome_generator_progress -- --nocapture
+        let build_code = || -> Box<dyn ExampleCode> { Box::new(PhenomenologicalPlanarCode::new(5, 5, 0.02, 500)) };
+        let shots = 25;
+        let mut serial_generator = SyndromeGenerator::new(build_code, 1, true);
+        let expected = serial_generator.generate(shots, 7);
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+        let recorded_events = events.clone();
+        let mut generator = SyndromeGenerator::new(build_code, 4, true);
+        generator.set_progress_callback(Box::new(move |event: ProgressEvent| {
+            recorded_events.lock().unwrap().push(event)
+        }));
+        // the result doesn't depend on the number of threads
+        assert_eq!(generator.generate(shots, 7), expected);
+        let events = std::mem::take(&mut *events.lock().unwrap());
+        assert_eq!(events.len(), shots, "one event per shot");
+        for (index, event) in events.iter().enumerate() {
+            assert_eq!((event.completed, event.total), (index + 1, shots));
+        }
+    }
+
+    #[test]
+    fn example_code_try_new_errors() {
+        // cargo test example_code_try_new_errors -- --nocapture
+        let invalid_input = |result: Result<(), FusionError>| matches!(result, Err(FusionError::InvalidInput(_)));
+        assert!(invalid_input(CodeCapacityPlanarCode::try_new(4, 0.1, 500).map(|_| ())));
+        assert!(invalid_input(CodeCapacityRepetitionCode::try_new(1, 0.1, 500).map(|_| ())));
+        assert!(invalid_input(PhenomenologicalPlanarCode::try_new(5, 5, 0.6, 500).map(|_| ())));
+        assert!(invalid_input(CircuitLevelPlanarCode::try_new(5, 5, 0.5, 500).map(|_| ())));
+        assert!(invalid_input(CodeCapacityRotatedCode::try_new(5, 0.1, 0).map(|_| ())));
+        // p = 0 is allowed and gives uniform weights, e.g. for erasure-only decoding graphs
+        assert!(CodeCapacityPlanarCode::try_new(5, 0., 500).is_ok());
+        // the toric code also accepts an even distance
+        assert!(CodeCapacityToricCode::try_new(4, 0.1, 500).is_ok());
+        assert_eq!(
+            CodeCapacityPlanarCode::try_new(5, 0.1, 500).unwrap().get_initializer(),
+            CodeCapacityPlanarCode::new(5, 0.1, 500).get_initializer()
+        );
+    }
+
+    #[test]
+    fn example_error_pattern_reader_try_new_errors() {
+        // cargo test example_error_pattern_reader_try_new_errors -- --nocapture
+        let config_error =
+            |config: serde_json::Value| matches!(ErrorPatternReader::try_new(config), Err(FusionError::Config(_)));
+        assert!(config_error(json!([])));
+        assert!(config_error(json!({ "preload": "yes" })));
+        assert!(config_error(json!({ "filename": "a.syndromes", "unknown": 1 })));
+        assert!(config_error(json!({ "filename": "a.syndromes", "read_ahead": 0 })));
+        let filename = format!(
+            "{}/example_error_pattern_reader_try_new_errors.missing",
+            visualize_data_folder()
+        );
+        let _ = std::fs::remove_file(&filename);
+        assert!(matches!(
+            ErrorPatternReader::try_new(json!({ "filename": filename })),
+            Err(FusionError::Io(_))
+        ));
+        std::fs::write(&filename, "not a syndrome file\n").unwrap();
+        for preload in [true, false] {
+            assert!(matches!(
+                ErrorPatternReader::try_new(json!({ "filename": filename, "preload": preload })),
+                Err(FusionError::Parse(_))
+            ));
+        }
+    }
 }
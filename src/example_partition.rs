@@ -14,7 +14,7 @@ pub trait ExamplePartition {
     fn build_apply(&mut self, code: &mut dyn ExampleCode) -> PartitionConfig {
         // first apply reorder
         if let Some(reordered_vertices) = self.build_reordered_vertices(code) {
-            code.reorder_vertices(&reordered_vertices);
+            code.reorder_vertices(&reordered_vertices).unwrap();
         }
         self.build_partition(code)
     }
@@ -257,6 +257,8 @@ pub struct PhenomenologicalPlanarCodeTimePartition {
     /// maximum amount of tree leaf; if the total partition is greater than this, it will be cut into multiple regions and each region is a separate tree;
     /// those trees are then fused sequentially
     maximum_tree_leaf_size: usize,
+    /// if set, the fusions follow this shape and `enable_tree_fusion` and `maximum_tree_leaf_size` are ignored
+    fusion_tree_shape: Option<FusionTreeShape>,
 }
 
 impl PhenomenologicalPlanarCodeTimePartition {
@@ -273,11 +275,22 @@ impl PhenomenologicalPlanarCodeTimePartition {
             partition_num,
             enable_tree_fusion,
             maximum_tree_leaf_size,
+            fusion_tree_shape: None,
         }
     }
     pub fn new(d: VertexNum, noisy_measurements: VertexNum, partition_num: usize) -> Self {
         Self::new_tree(d, noisy_measurements, partition_num, false, usize::MAX)
     }
+    pub fn new_shape(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        partition_num: usize,
+        fusion_tree_shape: FusionTreeShape,
+    ) -> Self {
+        let mut partition = Self::new(d, noisy_measurements, partition_num);
+        partition.fusion_tree_shape = Some(fusion_tree_shape);
+        partition
+    }
 }
 
 impl ExamplePartition for PhenomenologicalPlanarCodeTimePartition {
@@ -308,7 +321,9 @@ impl ExamplePartition for PhenomenologicalPlanarCodeTimePartition {
             }
         }
         config.fusions.clear();
-        if !self.enable_tree_fusion || self.maximum_tree_leaf_size == 1 {
+        if let Some(fusion_tree_shape) = &self.fusion_tree_shape {
+            config.fusions = fusion_tree_shape.build_fusions(partition_num);
+        } else if !self.enable_tree_fusion || self.maximum_tree_leaf_size == 1 {
             for unit_index in partition_num..(2 * partition_num - 1) {
                 if unit_index == partition_num {
                     config.fusions.push((0, 1));
@@ -407,6 +422,9 @@ pub struct PhenomenologicalRotatedCodeTimePartition {
     /// those trees are then fused sequentially
     #[clap(short = 'l', long, default_value_t = usize::MAX)]
     pub maximum_tree_leaf_size: usize,
+    /// if set, the fusions follow this shape and `enable_tree_fusion` and `maximum_tree_leaf_size` are ignored
+    #[clap(skip)]
+    pub fusion_tree_shape: Option<FusionTreeShape>,
 }
 
 impl PhenomenologicalRotatedCodeTimePartition {
@@ -423,11 +441,22 @@ impl PhenomenologicalRotatedCodeTimePartition {
             partition_num,
             enable_tree_fusion,
             maximum_tree_leaf_size,
+            fusion_tree_shape: None,
         }
     }
     pub fn new(d: VertexNum, noisy_measurements: VertexNum, partition_num: usize) -> Self {
         Self::new_tree(d, noisy_measurements, partition_num, false, usize::MAX)
     }
+    pub fn new_shape(
+        d: VertexNum,
+        noisy_measurements: VertexNum,
+        partition_num: usize,
+        fusion_tree_shape: FusionTreeShape,
+    ) -> Self {
+        let mut partition = Self::new(d, noisy_measurements, partition_num);
+        partition.fusion_tree_shape = Some(fusion_tree_shape);
+        partition
+    }
 }
 
 impl ExamplePartition for PhenomenologicalRotatedCodeTimePartition {
@@ -459,7 +488,9 @@ impl ExamplePartition for PhenomenologicalRotatedCodeTimePartition {
             }
         }
         config.fusions.clear();
-        if !self.enable_tree_fusion || self.maximum_tree_leaf_size == 1 {
+        if let Some(fusion_tree_shape) = &self.fusion_tree_shape {
+            config.fusions = fusion_tree_shape.build_fusions(partition_num);
+        } else if !self.enable_tree_fusion || self.maximum_tree_leaf_size == 1 {
             for unit_index in partition_num..(2 * partition_num - 1) {
                 if unit_index == partition_num {
                     config.fusions.push((0, 1));
@@ -584,7 +615,7 @@ pub mod tests {
         let mut primal_config = PrimalModuleParallelConfig::default();
         primal_config.debug_sequential = true;
         let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, visualizer.as_mut());
         let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
         let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -854,4 +885,48 @@ pub mod tests {
             PhenomenologicalPlanarCodeTimePartition::new_tree(7, noisy_measurements, 8, true, 3),
         );
     }
+
+    /// different fusion tree shapes must give the same matching weight
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn example_partition_fusion_tree_shape_1() {
+        // cargo test example_partition_fusion_tree_shape_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements, partition_num) = (5, 15, 8);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let shapes = [
+            FusionTreeShape::BalancedBinary,
+            FusionTreeShape::LinearChain,
+            FusionTreeShape::Custom(vec![(6, 7), (5, 8), (4, 9), (0, 1), (2, 3), (11, 12), (13, 10)]),
+        ];
+        let mut solvers = vec![];
+        for shape in shapes.iter() {
+            let mut partition =
+                PhenomenologicalPlanarCodeTimePartition::new_shape(d, noisy_measurements, partition_num, shape.clone());
+            let partition_info = partition.build_apply(&mut code).info();
+            println!("{shape:?}: fusion depth {}", partition_info.fusion_depth());
+            let initializer = code.get_initializer();
+            solvers.push(SolverParallel::new(&initializer, &partition_info, json!({})));
+        }
+        let initializer = code.get_initializer();
+        let mut serial_solver = SolverSerial::new(&initializer);
+        let subgraph_weight = |subgraph: &Vec<EdgeIndex>| -> Weight {
+            subgraph
+                .iter()
+                .map(|edge_index| initializer.weighted_edges[*edge_index as usize].2)
+                .sum()
+        };
+        for seed in 0..30 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            serial_solver.solve(&syndrome_pattern);
+            let expected_weight = subgraph_weight(&serial_solver.subgraph());
+            for solver in solvers.iter_mut() {
+                solver.solve(&syndrome_pattern);
+                assert_eq!(solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+                assert_eq!(subgraph_weight(&solver.subgraph()), expected_weight);
+                solver.clear();
+            }
+            serial_solver.clear();
+        }
+    }
 }
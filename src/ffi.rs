@@ -0,0 +1,349 @@
+//! C Foreign Function Interface
+//!
+//! A flat C API over [`SolverSerial`] for embedding the decoder in C/C++ control stacks, enabled by the `ffi` feature,
+//! which also generates the header `include/fusion_blossom.h` with cbindgen.
+//! The types in the signatures do not depend on the `u32_index` or `i32_weight` features, so that the ABI is stable:
+//! indices are always `size_t` and weights are always `int64_t`.
+//!
+//! Every function that can fail either returns an [`FbErrorCode`], or returns `NULL` (or `-1`) for the functions that
+//! return a pointer (or a length); in both cases [`fb_last_error_code`] and [`fb_last_error_message`] describe the
+//! failure. Panics never cross the FFI boundary: they are caught and reported as [`FbErrorCode::Panic`].
+//!
+
+use super::mwpm_solver::*;
+use super::util::*;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FbErrorCode {
+    Ok = 0,
+    /// a required pointer is `NULL`
+    NullPointer = 1,
+    /// an index is out of range or a weight is invalid, see [`fb_last_error_message`]
+    InvalidArgument = 2,
+    /// the solver panicked; the solver is cleared and can be used for the next syndrome
+    Panic = 3,
+}
+
+/// an edge of the decoding graph, the same as an element of [`SolverInitializer::weighted_edges`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FbWeightedEdge {
+    pub vertex_1: usize,
+    pub vertex_2: usize,
+    /// must be a non-negative even number
+    pub weight: i64,
+}
+
+/// opaque handle of a [`SolverInitializer`]
+pub struct FbInitializer(SolverInitializer);
+
+/// opaque handle of a [`SolverSerial`]
+pub struct FbSolver {
+    solver: SolverSerial,
+    vertex_num: usize,
+    edge_num: usize,
+}
+
+type FfiResult<T> = Result<T, (FbErrorCode, String)>;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(FbErrorCode, CString)>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(code: FbErrorCode, message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some((code, message)));
+}
+
+/// run `f` without letting the panic unwind into C, reporting any failure to the last error and returning `on_error(code)`
+fn ffi_call<T>(on_error: impl FnOnce(FbErrorCode) -> T, f: impl FnOnce() -> FfiResult<T>) -> T {
+    let result =
+        catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err((FbErrorCode::Panic, panic_message(payload))));
+    result.unwrap_or_else(|(code, message)| {
+        set_last_error(code, message);
+        on_error(code)
+    })
+}
+
+fn invalid(message: String) -> (FbErrorCode, String) {
+    (FbErrorCode::InvalidArgument, message)
+}
+
+/// `NULL` is only allowed for empty arrays
+unsafe fn slice_from<'a, T>(ptr: *const T, len: usize, name: &str) -> FfiResult<&'a [T]> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err((FbErrorCode::NullPointer, format!("`{name}` is NULL but its length is {len}")));
+    }
+    Ok(std::slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn handle_from<'a, T>(ptr: *mut T, name: &str) -> FfiResult<&'a mut T> {
+    ptr.as_mut()
+        .ok_or_else(|| (FbErrorCode::NullPointer, format!("`{name}` is NULL")))
+}
+
+fn to_index(index: usize, bound: usize, name: &str) -> FfiResult<VertexIndex> {
+    if index >= bound {
+        return Err(invalid(format!("{name} {index} out of range for {bound}")));
+    }
+    VertexIndex::try_from(index).map_err(|_| invalid(format!("{name} {index} overflows the index type")))
+}
+
+/// the code of the last failed call on this thread, or [`FbErrorCode::Ok`] if no call has failed
+#[no_mangle]
+pub extern "C" fn fb_last_error_code() -> FbErrorCode {
+    LAST_ERROR.with(|last_error| last_error.borrow().as_ref().map_or(FbErrorCode::Ok, |(code, _)| *code))
+}
+
+/// the message of the last failed call on this thread, or `NULL` if no call has failed;
+/// the string is owned by the library and stays valid until the next failed call on this thread
+#[no_mangle]
+pub extern "C" fn fb_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |(_, message)| message.as_ptr())
+    })
+}
+
+/// create a decoding graph with `vertex_num` vertices, returning `NULL` on error; free it with [`fb_initializer_free`]
+///
+/// # Safety
+/// `edges_ptr` and `virtual_ptr` must point to arrays of at least `edges_len` and `virtual_len` elements
+#[no_mangle]
+pub unsafe extern "C" fn fb_initializer_new(
+    vertex_num: usize,
+    edges_ptr: *const FbWeightedEdge,
+    edges_len: usize,
+    virtual_ptr: *const usize,
+    virtual_len: usize,
+) -> *mut FbInitializer {
+    ffi_call(
+        |_| std::ptr::null_mut(),
+        || {
+            let edges = slice_from(edges_ptr, edges_len, "edges_ptr")?;
+            let virtual_vertices = slice_from(virtual_ptr, virtual_len, "virtual_ptr")?;
+            let mut weighted_edges = Vec::with_capacity(edges.len());
+            for (edge_index, edge) in edges.iter().enumerate() {
+                let vertex_1 = to_index(edge.vertex_1, vertex_num, "vertex")?;
+                let vertex_2 = to_index(edge.vertex_2, vertex_num, "vertex")?;
+                if vertex_1 == vertex_2 {
+                    return Err(invalid(format!("edge {edge_index} is a self loop on vertex {vertex_1}")));
+                }
+                let weight = Weight::try_from(edge.weight)
+                    .ok()
+                    .filter(|weight| *weight >= 0 && *weight % 2 == 0)
+                    .ok_or_else(|| {
+                        invalid(format!(
+                            "weight {} of edge {edge_index} must be a non-negative even number",
+                            edge.weight
+                        ))
+                    })?;
+                weighted_edges.push((vertex_1, vertex_2, weight));
+            }
+            let virtual_vertices = (virtual_vertices.iter())
+                .map(|&vertex_index| to_index(vertex_index, vertex_num, "virtual vertex"))
+                .collect::<FfiResult<Vec<_>>>()?;
+            let vertex_num = VertexNum::try_from(vertex_num)
+                .map_err(|_| invalid(format!("vertex number {vertex_num} overflows the index type")))?;
+            let initializer = SolverInitializer::new(vertex_num, weighted_edges, virtual_vertices);
+            Ok(Box::into_raw(Box::new(FbInitializer(initializer))))
+        },
+    )
+}
+
+/// # Safety
+/// `initializer` must be `NULL` or returned by [`fb_initializer_new`], and is invalid afterwards
+#[no_mangle]
+pub unsafe extern "C" fn fb_initializer_free(initializer: *mut FbInitializer) {
+    if !initializer.is_null() {
+        drop(Box::from_raw(initializer));
+    }
+}
+
+/// create a solver, returning `NULL` on error; the initializer can be freed right after; free the solver with
+/// [`fb_solver_free`]
+///
+/// # Safety
+/// `initializer` must be returned by [`fb_initializer_new`]
+#[no_mangle]
+#[allow(clippy::unnecessary_cast)]
+pub unsafe extern "C" fn fb_solver_new(initializer: *const FbInitializer) -> *mut FbSolver {
+    ffi_call(
+        |_| std::ptr::null_mut(),
+        || {
+            let FbInitializer(initializer) = handle_from(initializer as *mut FbInitializer, "initializer")?;
+            let solver = FbSolver {
+                solver: SolverSerial::new(initializer),
+                vertex_num: initializer.vertex_num as usize,
+                edge_num: initializer.weighted_edges.len(),
+            };
+            Ok(Box::into_raw(Box::new(solver)))
+        },
+    )
+}
+
+/// # Safety
+/// `solver` must be `NULL` or returned by [`fb_solver_new`], and is invalid afterwards
+#[no_mangle]
+pub unsafe extern "C" fn fb_solver_free(solver: *mut FbSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// solve the defect vertices with the given edges erased (set to zero weight) for this syndrome only;
+/// the solver must be cleared with [`fb_solver_clear`] before solving the next syndrome
+///
+/// # Safety
+/// `solver` must be returned by [`fb_solver_new`]; `defects_ptr` and `erasures_ptr` must point to arrays of at least
+/// `defects_len` and `erasures_len` elements
+#[no_mangle]
+#[allow(clippy::unnecessary_cast)]
+pub unsafe extern "C" fn fb_solver_solve(
+    solver: *mut FbSolver,
+    defects_ptr: *const usize,
+    defects_len: usize,
+    erasures_ptr: *const usize,
+    erasures_len: usize,
+) -> FbErrorCode {
+    ffi_call(
+        |code| code,
+        || {
+            let solver = handle_from(solver, "solver")?;
+            let defect_vertices = (slice_from(defects_ptr, defects_len, "defects_ptr")?.iter())
+                .map(|&vertex_index| to_index(vertex_index, solver.vertex_num, "defect vertex"))
+                .collect::<FfiResult<Vec<_>>>()?;
+            let erasures = (slice_from(erasures_ptr, erasures_len, "erasures_ptr")?.iter())
+                .map(|&edge_index| to_index(edge_index, solver.edge_num, "erased edge").map(|index| index as EdgeIndex))
+                .collect::<FfiResult<Vec<_>>>()?;
+            let syndrome_pattern = SyndromePattern::new(defect_vertices, erasures);
+            let FbSolver { solver, .. } = solver;
+            if let Err(payload) = catch_unwind(AssertUnwindSafe(|| solver.solve(&syndrome_pattern))) {
+                // leave the solver usable for the next syndrome
+                let _ = catch_unwind(AssertUnwindSafe(|| solver.clear()));
+                return Err((FbErrorCode::Panic, panic_message(payload)));
+            }
+            Ok(FbErrorCode::Ok)
+        },
+    )
+}
+
+/// write the edge indices of the minimum-weight parity subgraph of the last solved syndrome to `out_ptr` and return
+/// the number of edges, or `-1` on error; if the number of edges is larger than `out_cap`, only the first `out_cap`
+/// edges are written and the caller should retry with a larger buffer
+///
+/// # Safety
+/// `solver` must be returned by [`fb_solver_new`]; `out_ptr` must point to an array of at least `out_cap` elements
+#[no_mangle]
+#[allow(clippy::unnecessary_cast)]
+pub unsafe extern "C" fn fb_solver_subgraph(solver: *mut FbSolver, out_ptr: *mut usize, out_cap: usize) -> isize {
+    ffi_call(
+        |_| -1,
+        || {
+            let FbSolver { solver, .. } = handle_from(solver, "solver")?;
+            if out_ptr.is_null() && out_cap > 0 {
+                return Err((
+                    FbErrorCode::NullPointer,
+                    format!("`out_ptr` is NULL but its capacity is {out_cap}"),
+                ));
+            }
+            let subgraph = solver.subgraph();
+            for (i, &edge_index) in subgraph.iter().take(out_cap).enumerate() {
+                *out_ptr.add(i) = edge_index as usize;
+            }
+            Ok(subgraph.len() as isize)
+        },
+    )
+}
+
+/// clear the solver for the next syndrome
+///
+/// # Safety
+/// `solver` must be returned by [`fb_solver_new`]
+#[no_mangle]
+pub unsafe extern "C" fn fb_solver_clear(solver: *mut FbSolver) -> FbErrorCode {
+    ffi_call(
+        |code| code,
+        || {
+            let FbSolver { solver, .. } = handle_from(solver, "solver")?;
+            solver.clear();
+            Ok(FbErrorCode::Ok)
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn repetition_code() -> *mut FbInitializer {
+        // 0 - 1 - 2 - 3 - 4, where 0 and 4 are virtual
+        let edges: Vec<_> = (0..4)
+            .map(|i| FbWeightedEdge {
+                vertex_1: i,
+                vertex_2: i + 1,
+                weight: 100,
+            })
+            .collect();
+        let virtual_vertices = [0, 4];
+        unsafe { fb_initializer_new(5, edges.as_ptr(), edges.len(), virtual_vertices.as_ptr(), 2) }
+    }
+
+    #[test]
+    fn ffi_solve_1() {
+        // cargo test ffi_solve_1 -- --nocapture
+        let initializer = repetition_code();
+        assert!(!initializer.is_null());
+        unsafe {
+            let solver = fb_solver_new(initializer);
+            fb_initializer_free(initializer);
+            let defects = [1, 2];
+            assert_eq!(
+                fb_solver_solve(solver, defects.as_ptr(), 2, std::ptr::null(), 0),
+                FbErrorCode::Ok
+            );
+            let mut subgraph = [0; 1];
+            assert_eq!(fb_solver_subgraph(solver, subgraph.as_mut_ptr(), 1), 1);
+            assert_eq!(subgraph, [1]);
+            assert_eq!(fb_solver_clear(solver), FbErrorCode::Ok);
+            // an out-of-range defect is reported instead of panicking
+            let defects = [5];
+            assert_eq!(
+                fb_solver_solve(solver, defects.as_ptr(), 1, std::ptr::null(), 0),
+                FbErrorCode::InvalidArgument
+            );
+            assert_eq!(fb_last_error_code(), FbErrorCode::InvalidArgument);
+            let message = CStr::from_ptr(fb_last_error_message()).to_str().unwrap();
+            assert_eq!(message, "defect vertex 5 out of range for 5");
+            assert_eq!(
+                fb_solver_solve(std::ptr::null_mut(), defects.as_ptr(), 1, std::ptr::null(), 0),
+                FbErrorCode::NullPointer
+            );
+            fb_solver_free(solver);
+        }
+    }
+
+    #[test]
+    fn ffi_invalid_initializer_1() {
+        // cargo test ffi_invalid_initializer_1 -- --nocapture
+        let edges = [FbWeightedEdge {
+            vertex_1: 0,
+            vertex_2: 1,
+            weight: 99,
+        }];
+        let initializer = unsafe { fb_initializer_new(2, edges.as_ptr(), 1, std::ptr::null(), 0) };
+        assert!(initializer.is_null());
+        assert_eq!(fb_last_error_code(), FbErrorCode::InvalidArgument);
+    }
+}
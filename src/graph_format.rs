@@ -0,0 +1,425 @@
+//! Graph Formats
+//!
+//! Export a decoding graph to DOT (Graphviz) and GraphML (Gephi, yEd, NetworkX) so that small graphs can be inspected
+//! without the web visualizer, and import a GraphML file back into a [`SolverInitializer`] for hand-edited graphs.
+//!
+
+use super::util::*;
+use super::visualize::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphExportOptions {
+    /// the name of the graph
+    pub name: String,
+    /// multiply the positions by this factor, e.g. Graphviz uses points as the unit of `pos`
+    pub position_scale: f64,
+}
+
+impl Default for GraphExportOptions {
+    fn default() -> Self {
+        Self {
+            name: "decoding_graph".to_string(),
+            position_scale: 1.,
+        }
+    }
+}
+
+/// errors when importing a GraphML file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphMLError {
+    /// the file is not well-formed
+    Syntax(String),
+    /// an edge refers to a node that is not defined
+    UnknownNode(String),
+    /// an edge has no integer weight
+    InvalidWeight { source: String, target: String },
+}
+
+impl std::fmt::Display for GraphMLError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Syntax(message) => write!(f, "invalid GraphML: {}", message),
+            Self::UnknownNode(node) => write!(f, "edge refers to unknown node `{}`", node),
+            Self::InvalidWeight { source, target } => {
+                write!(f, "edge ({}, {}) has no integer weight", source, target)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GraphMLError {}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// a start, end or empty element tag of an XML document, with the text that follows it
+#[derive(Debug)]
+struct XmlTag {
+    name: String,
+    attributes: HashMap<String, String>,
+    is_end: bool,
+    is_empty: bool,
+    text: String,
+}
+
+/// a minimal XML tokenizer that is just enough for GraphML: declarations, comments and CDATA are skipped
+fn xml_tags(content: &str) -> Result<Vec<XmlTag>, GraphMLError> {
+    let mut tags = vec![];
+    let mut rest = content;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let (skip_end, is_skipped) = if rest.starts_with("<!--") {
+            ("-->", true)
+        } else if rest.starts_with("<![CDATA[") {
+            ("]]>", true)
+        } else if rest.starts_with("<?") {
+            ("?>", true)
+        } else if rest.starts_with("<!") {
+            (">", true)
+        } else {
+            (">", false)
+        };
+        let end = rest
+            .find(skip_end)
+            .ok_or_else(|| GraphMLError::Syntax("unterminated tag".to_string()))?;
+        let tag = &rest[1..end];
+        rest = &rest[end + skip_end.len()..];
+        if is_skipped {
+            continue;
+        }
+        let is_end = tag.starts_with('/');
+        let is_empty = tag.ends_with('/');
+        let tag = tag.trim_start_matches('/').trim_end_matches('/').trim();
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let mut attributes = HashMap::new();
+        let mut attribute_text = tag[name_end..].trim_start();
+        while !attribute_text.is_empty() {
+            let equal = attribute_text
+                .find('=')
+                .ok_or_else(|| GraphMLError::Syntax(format!("invalid attributes in <{}>", tag)))?;
+            let key = attribute_text[..equal].trim().to_string();
+            let value_text = attribute_text[equal + 1..].trim_start();
+            let quote = value_text
+                .chars()
+                .next()
+                .filter(|quote| *quote == '"' || *quote == '\'')
+                .ok_or_else(|| GraphMLError::Syntax(format!("unquoted attribute in <{}>", tag)))?;
+            let value_end = value_text[1..]
+                .find(quote)
+                .ok_or_else(|| GraphMLError::Syntax(format!("unterminated attribute in <{}>", tag)))?;
+            attributes.insert(key, xml_unescape(&value_text[1..1 + value_end]));
+            attribute_text = value_text[value_end + 2..].trim_start();
+        }
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        tags.push(XmlTag {
+            name: tag[..name_end].to_string(),
+            attributes,
+            is_end,
+            is_empty,
+            text: xml_unescape(rest[..text_end].trim()),
+        });
+    }
+    Ok(tags)
+}
+
+impl SolverInitializer {
+    /// export to the DOT language of Graphviz, where virtual vertices are dashed boxes and edges are labeled by weights;
+    /// the optional positions are projected to the i-j plane as fixed `pos` attributes (use `neato -n` to respect them)
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_dot(&self, positions: Option<&[VisualizePosition]>, options: &GraphExportOptions) -> String {
+        let mut is_virtual = vec![false; self.vertex_num as usize];
+        for &virtual_vertex in self.virtual_vertices.iter() {
+            is_virtual[virtual_vertex as usize] = true;
+        }
+        let mut dot = String::new();
+        writeln!(dot, "graph \"{}\" {{", options.name.replace('"', "\\\"")).unwrap();
+        writeln!(dot, "    node [shape=circle];").unwrap();
+        for vertex_index in 0..self.vertex_num as usize {
+            let mut attributes = vec![format!("label=\"{}\"", vertex_index)];
+            if is_virtual[vertex_index] {
+                attributes.push("shape=box".to_string());
+                attributes.push("style=dashed".to_string());
+                attributes.push("virtual=true".to_string());
+            }
+            if let Some(positions) = positions {
+                let position = &positions[vertex_index];
+                attributes.push(format!(
+                    "pos=\"{},{}!\"",
+                    position.j * options.position_scale,
+                    -position.i * options.position_scale
+                ));
+            }
+            writeln!(dot, "    {} [{}];", vertex_index, attributes.join(", ")).unwrap();
+        }
+        for &(left, right, weight) in self.weighted_edges.iter() {
+            writeln!(dot, "    {} -- {} [label=\"{}\", weight={}];", left, right, weight, weight).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// export to GraphML with a boolean `virtual` attribute on nodes, an integer `weight` attribute on edges and
+    /// the optional `i`, `j`, `t` positions on nodes
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_graphml(&self, positions: Option<&[VisualizePosition]>, options: &GraphExportOptions) -> String {
+        let mut is_virtual = vec![false; self.vertex_num as usize];
+        for &virtual_vertex in self.virtual_vertices.iter() {
+            is_virtual[virtual_vertex as usize] = true;
+        }
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"virtual\" for=\"node\" attr.name=\"virtual\" attr.type=\"boolean\"/>\n");
+        if positions.is_some() {
+            for axis in ["i", "j", "t"] {
+                writeln!(
+                    xml,
+                    "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"double\"/>",
+                    axis, axis
+                )
+                .unwrap();
+            }
+        }
+        xml.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"long\"/>\n");
+        writeln!(
+            xml,
+            "  <graph id=\"{}\" edgedefault=\"undirected\">",
+            xml_escape(&options.name)
+        )
+        .unwrap();
+        for vertex_index in 0..self.vertex_num as usize {
+            write!(
+                xml,
+                "    <node id=\"n{}\"><data key=\"virtual\">{}</data>",
+                vertex_index, is_virtual[vertex_index]
+            )
+            .unwrap();
+            if let Some(positions) = positions {
+                let position = &positions[vertex_index];
+                let scale = options.position_scale;
+                for (axis, value) in [("i", position.i), ("j", position.j), ("t", position.t)] {
+                    write!(xml, "<data key=\"{}\">{}</data>", axis, value * scale).unwrap();
+                }
+            }
+            xml.push_str("</node>\n");
+        }
+        for &(left, right, weight) in self.weighted_edges.iter() {
+            writeln!(
+                xml,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"weight\">{}</data></edge>",
+                left, right, weight
+            )
+            .unwrap();
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// import a GraphML file, e.g. one generated by [`SolverInitializer::to_graphml`] and then edited by hand or by
+    /// another tool; nodes keep their indices if all node ids are `n<index>` (or `<index>`), otherwise they are numbered
+    /// in the order of appearance. Positions are returned only if every node has the `i`, `j` and `t` attributes.
+    #[allow(clippy::type_complexity)]
+    pub fn from_graphml(content: &str) -> Result<(Self, Option<Vec<VisualizePosition>>), GraphMLError> {
+        let tags = xml_tags(content)?;
+        // map the key ids to attribute names, since other tools use ids like `d0`
+        let mut key_names = HashMap::new();
+        for tag in tags.iter().filter(|tag| tag.name == "key" && !tag.is_end) {
+            if let Some(id) = tag.attributes.get("id") {
+                let name = tag.attributes.get("attr.name").unwrap_or(id);
+                key_names.insert(id.clone(), name.clone());
+            }
+        }
+        let mut nodes: Vec<(String, HashMap<String, String>)> = vec![];
+        let mut edges: Vec<(String, String, HashMap<String, String>)> = vec![];
+        // whether the `<data>` tags currently belong to the last node (`Some(true)`) or the last edge (`Some(false)`)
+        let mut data_of_node: Option<bool> = None;
+        for tag in tags.iter() {
+            match (tag.name.as_str(), tag.is_end) {
+                ("node", false) => {
+                    let id = tag
+                        .attributes
+                        .get("id")
+                        .ok_or_else(|| GraphMLError::Syntax("node without id".to_string()))?;
+                    nodes.push((id.clone(), HashMap::new()));
+                    data_of_node = (!tag.is_empty).then_some(true);
+                }
+                ("edge", false) => {
+                    let endpoint = |name: &str| {
+                        tag.attributes
+                            .get(name)
+                            .cloned()
+                            .ok_or_else(|| GraphMLError::Syntax(format!("edge without {}", name)))
+                    };
+                    edges.push((endpoint("source")?, endpoint("target")?, HashMap::new()));
+                    data_of_node = (!tag.is_empty).then_some(false);
+                }
+                ("node", true) | ("edge", true) => {
+                    data_of_node = None;
+                }
+                ("data", false) => {
+                    if let Some(is_node) = data_of_node {
+                        let key = tag
+                            .attributes
+                            .get("key")
+                            .ok_or_else(|| GraphMLError::Syntax("data without key".to_string()))?;
+                        let name = key_names.get(key).unwrap_or(key);
+                        let data = if is_node {
+                            &mut nodes.last_mut().unwrap().1
+                        } else {
+                            &mut edges.last_mut().unwrap().2
+                        };
+                        data.insert(name.clone(), tag.text.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        // keep the original indices if possible
+        let parsed_indices: Option<Vec<usize>> = nodes
+            .iter()
+            .map(|(id, _)| id.strip_prefix('n').unwrap_or(id).parse::<usize>().ok())
+            .collect();
+        let node_indices = match parsed_indices {
+            Some(indices)
+                if {
+                    let mut sorted = indices.clone();
+                    sorted.sort_unstable();
+                    sorted.iter().enumerate().all(|(index, &parsed)| index == parsed)
+                } =>
+            {
+                indices
+            }
+            _ => (0..nodes.len()).collect(),
+        };
+        let mut index_of = HashMap::new();
+        for ((id, _), &index) in nodes.iter().zip(node_indices.iter()) {
+            index_of.insert(id.clone(), index as VertexIndex);
+        }
+        let mut virtual_vertices = vec![];
+        let mut positions = vec![None; nodes.len()];
+        for ((_, data), &index) in nodes.iter().zip(node_indices.iter()) {
+            if matches!(data.get("virtual").map(|value| value.as_str()), Some("true" | "1")) {
+                virtual_vertices.push(index as VertexIndex);
+            }
+            let coordinate = |axis: &str| data.get(axis).and_then(|value| value.parse::<f64>().ok());
+            if let (Some(i), Some(j), Some(t)) = (coordinate("i"), coordinate("j"), coordinate("t")) {
+                positions[index] = Some(VisualizePosition::new(i, j, t));
+            }
+        }
+        virtual_vertices.sort_unstable();
+        let mut weighted_edges = Vec::with_capacity(edges.len());
+        for (source, target, data) in edges.iter() {
+            let vertex = |id: &String| index_of.get(id).cloned().ok_or_else(|| GraphMLError::UnknownNode(id.clone()));
+            let weight = data
+                .get("weight")
+                .and_then(|weight| weight.parse::<Weight>().ok())
+                .ok_or_else(|| GraphMLError::InvalidWeight {
+                    source: source.clone(),
+                    target: target.clone(),
+                })?;
+            weighted_edges.push((vertex(source)?, vertex(target)?, weight));
+        }
+        let initializer = SolverInitializer::new(nodes.len() as VertexNum, weighted_edges, virtual_vertices);
+        Ok((initializer, positions.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example_codes::*;
+    use super::*;
+
+    fn assert_same_initializer(a: &SolverInitializer, b: &SolverInitializer) {
+        assert_eq!(a.vertex_num, b.vertex_num);
+        assert_eq!(a.weighted_edges, b.weighted_edges);
+        let mut virtual_vertices = a.virtual_vertices.clone();
+        virtual_vertices.sort_unstable();
+        assert_eq!(virtual_vertices, b.virtual_vertices);
+    }
+
+    #[test]
+    fn graph_format_graphml_round_trip_1() {
+        // cargo test graph_format_graphml_round_trip_1 -- --nocapture
+        let code = CodeCapacityRepetitionCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let positions = code.get_positions();
+        let options = GraphExportOptions::default();
+        let graphml = initializer.to_graphml(Some(&positions), &options);
+        let (imported, imported_positions) = SolverInitializer::from_graphml(&graphml).unwrap();
+        assert_same_initializer(&initializer, &imported);
+        assert_eq!(imported_positions, Some(positions));
+        // without positions
+        let graphml = initializer.to_graphml(None, &options);
+        let (imported, imported_positions) = SolverInitializer::from_graphml(&graphml).unwrap();
+        assert_same_initializer(&initializer, &imported);
+        assert_eq!(imported_positions, None);
+    }
+
+    /// files saved by other tools use their own key ids and node names
+    #[test]
+    fn graph_format_graphml_hand_edited_1() {
+        // cargo test graph_format_graphml_hand_edited_1 -- --nocapture
+        let graphml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+              <key id="d0" for="node" attr.name="virtual" attr.type="boolean"/>
+              <key id="d1" for="edge" attr.name="weight" attr.type="long"/>
+              <graph edgedefault="undirected">
+                <!-- a boundary on the left -->
+                <node id="left"><data key="d0">true</data></node>
+                <node id="a"/>
+                <node id="b"></node>
+                <edge source="left" target="a"><data key="d1">100</data></edge>
+                <edge source="a" target="b"><data key="d1">200</data></edge>
+              </graph>
+            </graphml>"#;
+        let (initializer, positions) = SolverInitializer::from_graphml(graphml).unwrap();
+        assert_eq!(initializer.vertex_num, 3);
+        assert_eq!(initializer.weighted_edges, vec![(0, 1, 100), (1, 2, 200)]);
+        assert_eq!(initializer.virtual_vertices, vec![0]);
+        assert_eq!(positions, None);
+        let missing_node = graphml.replace(r#"target="b""#, r#"target="c""#);
+        assert_eq!(
+            SolverInitializer::from_graphml(&missing_node).unwrap_err(),
+            GraphMLError::UnknownNode("c".to_string())
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn graph_format_dot_1() {
+        // cargo test graph_format_dot_1 -- --nocapture
+        let code = CodeCapacityRepetitionCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        let positions = code.get_positions();
+        let dot = initializer.to_dot(Some(&positions), &GraphExportOptions::default());
+        println!("{}", dot);
+        assert!(dot.starts_with("graph \"decoding_graph\" {"));
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            assert!(dot.contains(&format!(
+                "    {} [label=\"{}\", shape=box, style=dashed",
+                virtual_vertex, virtual_vertex
+            )));
+        }
+        let dashed_num = dot.matches("style=dashed").count();
+        assert_eq!(dashed_num, initializer.virtual_vertices.len());
+        let (left, right, weight) = initializer.weighted_edges[0];
+        assert!(dot.contains(&format!("    {} -- {} [label=\"{}\"", left, right, weight)));
+        assert_eq!(dot.matches("pos=").count(), initializer.vertex_num as usize);
+    }
+}
@@ -2,6 +2,17 @@
 #![cfg_attr(feature = "unsafe_pointer", allow(unused_mut))]
 #![cfg_attr(feature = "python_binding", feature(cfg_eval))]
 
+#[cfg(not(any(feature = "full", feature = "minimal")))]
+compile_error!("either the default feature `full` or the feature `minimal` is required");
+#[cfg(all(feature = "full", feature = "minimal"))]
+compile_error!("the feature `minimal` requires `--no-default-features`");
+#[cfg(all(feature = "python_binding", feature = "minimal"))]
+compile_error!("the Python binding is not available with the feature `minimal`");
+
+#[cfg(not(feature = "minimal"))]
+extern crate base64;
+#[cfg(not(feature = "minimal"))]
+extern crate bincode;
 extern crate cfg_if;
 extern crate libc;
 extern crate parking_lot;
@@ -10,37 +21,76 @@ extern crate rand_xoshiro;
 extern crate serde;
 #[macro_use]
 extern crate serde_json;
+#[cfg(not(feature = "minimal"))]
 extern crate chrono;
+#[cfg(not(feature = "minimal"))]
 extern crate clap;
+#[cfg(not(feature = "minimal"))]
 extern crate core_affinity;
 extern crate derivative;
+#[cfg(not(feature = "minimal"))]
+extern crate flate2;
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
 extern crate pbr;
-#[cfg(test)]
+#[cfg(all(test, not(feature = "minimal")))]
 extern crate petgraph;
 #[cfg(feature = "python_binding")]
 extern crate pyo3;
 #[cfg(feature = "qecp_integrate")]
 extern crate qecp;
 extern crate rand;
+#[cfg(not(feature = "minimal"))]
 extern crate rayon;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(not(feature = "minimal"))]
 extern crate urlencoding;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
 extern crate weak_table;
 
+/// enter a `tracing` span until the end of the current scope, e.g. `trace_span!("fuse", unit_index = 3)`; it expands to
+/// nothing without the `tracing` feature, so the arguments are not even evaluated
+macro_rules! trace_span {
+    ($($span:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _trace_span = tracing::info_span!($($span)*).entered();
+    };
+}
+
+#[cfg(not(feature = "minimal"))]
+pub mod analysis;
 pub mod blossom_v;
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "minimal")))]
 pub mod cli;
 pub mod complete_graph;
 pub mod dual_module;
+#[cfg(not(feature = "minimal"))]
 pub mod dual_module_parallel;
 pub mod dual_module_serial;
+pub mod error;
+pub mod exact_matcher;
 pub mod example_codes;
+#[cfg(not(feature = "minimal"))]
 pub mod example_partition;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod graph_format;
 pub mod mwpm_solver;
+pub mod ordering;
+pub mod partition;
 pub mod pointers;
 pub mod primal_module;
+#[cfg(not(feature = "minimal"))]
 pub mod primal_module_parallel;
 pub mod primal_module_serial;
+pub mod simulator;
+pub mod stats;
 pub mod util;
+pub mod verification;
 pub mod visualize;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
 
@@ -55,6 +105,10 @@ fn fusion_blossom(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     example_codes::register(py, m)?;
     visualize::register(py, m)?;
     primal_module::register(py, m)?;
+    ordering::register(py, m)?;
+    partition::register(py, m)?;
+    simulator::register(py, m)?;
+    analysis::register(py, m)?;
     let helper_code = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/helper.py"));
     let helper_module = PyModule::from_code(py, helper_code, "helper", "helper")?;
     helper_module.add("visualizer_website", generate_visualizer_website(py))?;
@@ -5,24 +5,35 @@
 //!
 
 use super::dual_module::{DualModuleImpl, DualModuleInterfacePtr};
+#[cfg(not(feature = "minimal"))]
 use super::dual_module_parallel::*;
 use super::dual_module_serial::DualModuleSerial;
+use super::error::*;
+#[cfg(not(feature = "minimal"))]
+use super::example_codes::{SyndromeFileOutput, SyndromeFileWriter};
 use super::pointers::*;
-use super::primal_module::{PerfectMatching, PrimalModuleImpl, SubGraphBuilder, VisualizeSubgraph};
+use super::primal_module::{BoundarySelection, PerfectMatching, PrimalModuleImpl, SubGraphBuilder, VisualizeSubgraph};
+#[cfg(not(feature = "minimal"))]
 use super::primal_module_parallel::*;
-use super::primal_module_serial::PrimalModuleSerialPtr;
+use super::primal_module_serial::{PrimalModuleSerialPtr, SolverEvent, SolverEventLog};
 use super::util::*;
 use super::visualize::*;
+#[cfg(not(feature = "minimal"))]
 use crate::blossom_v;
 use crate::complete_graph::*;
 use crate::derivative::Derivative;
 use crate::dual_module::*;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
-use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "python_binding")]
+use pyo3::types::{IntoPyDict, PyByteArray};
+use serde::{Deserialize, Serialize};
+#[cfg(not(feature = "minimal"))]
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufWriter;
+use std::sync::Arc;
 
 /// a serial solver
 #[derive(Derivative)]
@@ -140,6 +151,13 @@ pub trait PrimalDualSolver {
     fn solve(&mut self, syndrome_pattern: &SyndromePattern) {
         self.solve_visualizer(syndrome_pattern, None)
     }
+    /// solve a syndrome given as a [`SyndromeBitmap`], which by default is converted to a [`SyndromePattern`]
+    fn solve_bitmap_visualizer(&mut self, bitmap: &SyndromeBitmap, visualizer: Option<&mut Visualizer>) {
+        self.solve_visualizer(&bitmap.to_syndrome_pattern(), visualizer)
+    }
+    fn solve_bitmap(&mut self, bitmap: &SyndromeBitmap) {
+        self.solve_bitmap_visualizer(bitmap, None)
+    }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching;
     fn perfect_matching(&mut self) -> PerfectMatching {
         self.perfect_matching_visualizer(None)
@@ -150,7 +168,44 @@ pub trait PrimalDualSolver {
     }
     fn sum_dual_variables(&self) -> Weight;
     fn generate_profiler_report(&self) -> serde_json::Value;
+    /// the ground-truth error edges of the next syndrome pattern, only used by [`SolverErrorPatternLogger`] to record them
+    fn set_error_edges(&mut self, _error_edges: &[EdgeIndex]) {}
+    /// an analytic estimate of the memory in bytes, or `None` if not supported by this solver
+    fn memory_estimate(&self) -> Option<usize> {
+        None
+    }
+    /// the number of edges in the decoding graph, or `None` if unknown to this solver
+    fn edge_num(&self) -> Option<usize> {
+        None
+    }
+    /// the statistics of the fusions in the last solved syndrome, or `None` if the solver doesn't fuse partitions
+    #[cfg(not(feature = "minimal"))]
+    fn parallel_statistics(&self) -> Option<ParallelSolverStatistics> {
+        None
+    }
+    /// write every internal buffer once and decode a small synthetic syndrome, so that the first real decoding after
+    /// construction runs at steady-state latency; the solver is clear afterwards. By default it does nothing
+    fn warm_up(&mut self) {}
+    /// like [`Self::solve`], but returns an error instead of panicking if the syndrome pattern is invalid for this
+    /// solver; by default only the edge indices are checked
     #[allow(clippy::unnecessary_cast)]
+    fn solve_checked(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), FusionError> {
+        if let Some(edge_num) = self.edge_num() {
+            let edge_indices = (syndrome_pattern.erasures.iter())
+                .chain(syndrome_pattern.dynamic_weights.iter().map(|(edge_index, _)| edge_index))
+                .chain(syndrome_pattern.blocked_edges.iter());
+            for &edge_index in edge_indices {
+                if edge_index as usize >= edge_num {
+                    return Err(FusionError::InvalidInput(format!(
+                        "edge index {edge_index} out of range for {edge_num} edges"
+                    )));
+                }
+            }
+        }
+        self.solve(syndrome_pattern);
+        Ok(())
+    }
+    /// decode the bit-packed detection events written by `stim` and write the bit-packed predictions of the observables
     fn stim_integration_predict_bit_packed_data(
         &mut self,
         in_file: String,
@@ -160,42 +215,499 @@ pub trait PrimalDualSolver {
         num_dets: usize,
         num_obs: usize,
     ) {
-        let mut in_reader = std::io::BufReader::new(File::open(&in_file).expect("in_file not found"));
-        let mut out_writer = std::io::BufWriter::new(File::create(&out_file).expect("out_file not found"));
-        let num_det_bytes = (num_dets + 7) / 8; // ceil
-        let mut dets_bit_packed = vec![0; num_det_bytes];
-        assert!(num_obs <= 64, "too many observables");
-        let prediction_bytes = (num_obs + 7) / 8; // ceil
-        for _ in 0..num_shots {
-            in_reader.read_exact(&mut dets_bit_packed).expect("read success");
-            let mut defect_vertices = vec![];
-            for (i, &byte) in dets_bit_packed.iter().enumerate() {
-                if byte == 0 {
-                    continue;
-                }
-                for j in 0..8 {
-                    if byte & (1 << j) != 0 {
-                        // little endian
-                        defect_vertices.push((i * 8 + j) as VertexIndex);
-                    }
+        let progress = ProgressReporter::new(None, num_shots, String::new());
+        stim_predict_bit_packed_data(self, &in_file, &out_file, edge_masks, num_shots, num_dets, num_obs, &progress)
+    }
+}
+
+/// check the decoding graph before building a solver: an invalid index would otherwise panic deep inside the solver, and
+/// the dual variables are bounded by the sum of all weights which must fit in [`Weight`]
+fn check_initializer(initializer: &SolverInitializer) -> Result<(), FusionError> {
+    initializer.check().map_err(FusionError::InvalidInput)?;
+    (initializer.weighted_edges.iter())
+        .try_fold(0 as Weight, |sum, &(_, _, weight)| sum.checked_add(weight))
+        .ok_or_else(|| FusionError::Capacity("the sum of all edge weights overflows".to_string()))?;
+    Ok(())
+}
+
+/// the check of [`PrimalDualSolver::solve_checked`] for the solvers that keep the decoding graph
+fn check_syndrome_pattern(
+    initializer: &SolverInitializer,
+    syndrome_pattern: &SyndromePattern,
+    supports_blocked_edges: bool,
+) -> Result<(), FusionError> {
+    initializer
+        .check_syndrome_pattern(syndrome_pattern)
+        .map_err(FusionError::InvalidInput)?;
+    if !supports_blocked_edges && !syndrome_pattern.blocked_edges.is_empty() {
+        return Err(FusionError::InvalidInput(
+            "blocked edges are only supported by the serial solver".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// a small syndrome used by [`PrimalDualSolver::warm_up`]: the two endpoints of a regular edge, or otherwise a single
+/// vertex next to a virtual vertex
+fn warm_up_syndrome(initializer: &SolverInitializer) -> Option<SyndromePattern> {
+    let is_virtual: BTreeSet<VertexIndex> = initializer.virtual_vertices.iter().cloned().collect();
+    let regular_edge = (initializer.weighted_edges.iter())
+        .find(|(left, right, _)| !is_virtual.contains(left) && !is_virtual.contains(right));
+    if let Some(&(left, right, _)) = regular_edge {
+        return Some(SyndromePattern::new_vertices(vec![left, right]));
+    }
+    (initializer.weighted_edges.iter())
+        .find_map(
+            |&(left, right, _)| match (is_virtual.contains(&left), is_virtual.contains(&right)) {
+                (false, true) => Some(left),
+                (true, false) => Some(right),
+                _ => None,
+            },
+        )
+        .map(|vertex_index| SyndromePattern::new_vertices(vec![vertex_index]))
+}
+
+/// the solvers whose memory can be estimated before building them, see [`SolverInitializer::estimate_solver_memory`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverType {
+    /// [`SolverSerial`]
+    Serial,
+    /// [`SolverDualParallel`]
+    #[cfg(not(feature = "minimal"))]
+    DualParallel,
+    /// [`SolverParallel`]
+    #[cfg(not(feature = "minimal"))]
+    Parallel,
+}
+
+/// the estimated memory of a solver in bytes, see [`SolverInitializer::estimate_solver_memory`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryEstimate {
+    /// the vertices of the dual module and of the complete graph that builds the subgraph
+    pub vertices: usize,
+    /// the edges of the dual module, the complete graph, the subgraph builder and the copy of the initializer
+    pub edges: usize,
+    /// the dual and primal nodes when every regular vertex is a defect, which bounds the pools of any syndrome except for
+    /// the blossoms; the pools grow to this size only after decoding such a syndrome
+    pub dual_node_pools: usize,
+    /// the units of the parallel modules and the vertices mirrored across the interfaces between partitions
+    pub partition_overhead: usize,
+}
+
+impl MemoryEstimate {
+    pub fn total(&self) -> usize {
+        self.vertices + self.edges + self.dual_node_pools + self.partition_overhead
+    }
+
+    pub fn brief(&self) -> String {
+        let megabytes = |bytes: usize| bytes as f64 / (1024. * 1024.);
+        format!(
+            "estimated memory: {:.1} MB (vertices: {:.1} MB, edges: {:.1} MB, dual node pools: {:.1} MB, partition overhead: {:.1} MB)",
+            megabytes(self.total()),
+            megabytes(self.vertices),
+            megabytes(self.edges),
+            megabytes(self.dual_node_pools),
+            megabytes(self.partition_overhead)
+        )
+    }
+}
+
+/// the bytes of a heap allocation, including the 8-byte header and the 16-byte alignment of the system allocator
+fn allocated_bytes(bytes: usize) -> usize {
+    if bytes == 0 {
+        0
+    } else {
+        (bytes + 8).max(32).next_multiple_of(16)
+    }
+}
+
+/// the capacity of a vector after pushing `len` elements one by one
+fn pushed_capacity(len: usize) -> usize {
+    if len == 0 {
+        0
+    } else {
+        len.next_power_of_two().max(4)
+    }
+}
+
+fn vec_bytes<T>(capacity: usize) -> usize {
+    allocated_bytes(capacity * size_of::<T>())
+}
+
+/// the shared allocation behind the pointers of vertices, edges and nodes
+fn locked_arc_bytes<T>() -> usize {
+    allocated_bytes(2 * size_of::<usize>() + size_of::<crate::parking_lot::RwLock<T>>())
+}
+
+/// a B-tree node holds up to 11 entries and is about 3/4 full after random insertions
+fn btree_map_bytes<K, V>(len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let node_bytes = allocated_bytes(11 * (size_of::<K>() + size_of::<V>()) + 2 * size_of::<usize>());
+    len.div_ceil(8) * node_bytes
+}
+
+/// a hash map keeps at most 7/8 of its power-of-two buckets occupied, with one control byte per bucket
+fn hash_map_bytes<K, V>(len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let buckets = (len * 8).div_ceil(7).next_power_of_two();
+    allocated_bytes(buckets * (size_of::<(K, V)>() + 1) + 16)
+}
+
+impl SolverInitializer {
+    /// estimate the memory of a solver on this decoding graph without building it, from the sizes of the internal
+    /// structures; `partitions` is the number of partitions of the parallel solvers and is ignored by the serial solver.
+    /// The interfaces of a partition are unknown at this point and assumed to be cuts of about `vertex_num^(2/3)`
+    /// vertices, as in a 3D decoding graph partitioned along the time axis
+    #[allow(clippy::unnecessary_cast)]
+    #[cfg_attr(feature = "minimal", allow(unused_variables))]
+    pub fn estimate_solver_memory(&self, solver_type: SolverType, partitions: usize) -> MemoryEstimate {
+        use super::complete_graph::CompleteGraphVertex;
+        use super::dual_module::DualNode;
+        use super::dual_module_serial::*;
+        use super::primal_module_serial::*;
+        let vertex_num = self.vertex_num as usize;
+        let edge_num = self.weighted_edges.len();
+        let mut degrees = vec![0; vertex_num];
+        for &(i, j, _) in self.weighted_edges.iter() {
+            degrees[i as usize] += 1;
+            degrees[j as usize] += 1;
+        }
+        let mut is_virtual = vec![false; vertex_num];
+        for &vertex_index in self.virtual_vertices.iter() {
+            is_virtual[vertex_index as usize] = true;
+        }
+        // the vertex of the dual module with its list of edges, and the vertex of the complete graph with its neighbors
+        let vertex_bytes = |degree: usize| {
+            locked_arc_bytes::<Vertex>()
+                + vec_bytes::<EdgeWeak>(pushed_capacity(degree))
+                + btree_map_bytes::<VertexIndex, Weight>(degree)
+        };
+        let vertices = vertex_num * size_of::<VertexPtr>()
+            + vec_bytes::<CompleteGraphVertex>(vertex_num)
+            + degrees.iter().map(|&degree| vertex_bytes(degree)).sum::<usize>();
+        let edges = vec_bytes::<EdgePtr>(pushed_capacity(edge_num))
+            + edge_num * locked_arc_bytes::<Edge>()
+            + 2 * vec_bytes::<(VertexIndex, VertexIndex, Weight)>(edge_num)
+            + vec_bytes::<VertexIndex>(self.virtual_vertices.len())
+            + hash_map_bytes::<(VertexIndex, VertexIndex), EdgeIndex>(edge_num);
+        // each defect creates a dual node in the interface, an internal node in the dual module whose boundary starts
+        // with the edges of the vertex, and a primal node
+        let defect_num = vertex_num - self.virtual_vertices.len();
+        let node_pointers = pushed_capacity(defect_num);
+        let dual_node_pools = vec_bytes::<Option<DualNodePtr>>(node_pointers)
+            + vec_bytes::<Option<DualNodeInternalPtr>>(node_pointers)
+            + vec_bytes::<DualNodeInternalWeak>(node_pointers)
+            + vec_bytes::<Option<PrimalNodeInternalPtr>>(node_pointers)
+            + (0..vertex_num)
+                .filter(|&vertex_index| !is_virtual[vertex_index])
+                .map(|vertex_index| {
+                    locked_arc_bytes::<DualNode>()
+                        + locked_arc_bytes::<DualNodeInternal>()
+                        + vec_bytes::<(bool, EdgeWeak)>(pushed_capacity(degrees[vertex_index]))
+                        + locked_arc_bytes::<PrimalNodeInternal>()
+                })
+                .sum::<usize>();
+        let partition_overhead = match solver_type {
+            SolverType::Serial => 0,
+            #[cfg(not(feature = "minimal"))]
+            SolverType::DualParallel | SolverType::Parallel => {
+                let unit_num = 2 * partitions.max(1) - 1;
+                let mut unit_bytes = allocated_bytes(size_of::<DualModuleParallelUnit<DualModuleSerial>>());
+                if solver_type == SolverType::Parallel {
+                    unit_bytes += allocated_bytes(size_of::<PrimalModuleParallelUnit>())
+                        + locked_arc_bytes::<PrimalModuleSerial>()
+                        + locked_arc_bytes::<DualModuleInterface>();
                 }
+                // every interface is mirrored in the units on both sides of it
+                let average_degree = (2 * edge_num).div_ceil(vertex_num.max(1));
+                let interface_vertices = (vertex_num as f64).powf(2. / 3.).ceil() as usize;
+                let mirrored_vertices = 2 * (partitions.max(1) - 1) * interface_vertices;
+                let mirrored_bytes = locked_arc_bytes::<Vertex>()
+                    + vec_bytes::<EdgeWeak>(pushed_capacity(average_degree))
+                    + size_of::<VertexPtr>();
+                unit_num * unit_bytes
+                    + mirrored_vertices * mirrored_bytes
+                    + hash_map_bytes::<VertexIndex, VertexIndex>(mirrored_vertices)
             }
-            let syndrome_pattern = SyndromePattern::new_vertices(defect_vertices);
-            self.solve(&syndrome_pattern);
-            let subgraph = self.subgraph();
-            let mut prediction = 0;
-            for edge_index in subgraph {
-                prediction ^= edge_masks[edge_index as usize];
+        };
+        MemoryEstimate {
+            vertices,
+            edges,
+            dual_node_pools,
+            partition_overhead,
+        }
+    }
+}
+
+/// see [`PrimalDualSolver::stim_integration_predict_bit_packed_data`], reporting the progress after every shot
+#[allow(clippy::unnecessary_cast, clippy::too_many_arguments)]
+pub fn stim_predict_bit_packed_data<S: PrimalDualSolver + ?Sized>(
+    solver: &mut S,
+    in_file: &str,
+    out_file: &str,
+    edge_masks: &[usize],
+    num_shots: usize,
+    num_dets: usize,
+    num_obs: usize,
+    progress: &ProgressReporter,
+) {
+    let mut in_reader = std::io::BufReader::new(File::open(in_file).expect("in_file not found"));
+    let mut out_writer = std::io::BufWriter::new(File::create(out_file).expect("out_file not found"));
+    let num_det_bytes = (num_dets + 7) / 8; // ceil
+    let mut dets_bit_packed = vec![0; num_det_bytes];
+    assert!(num_obs <= 64, "too many observables");
+    let prediction_bytes = (num_obs + 7) / 8; // ceil
+    for _ in 0..num_shots {
+        in_reader.read_exact(&mut dets_bit_packed).expect("read success");
+        let mut defect_vertices = vec![];
+        for (i, &byte) in dets_bit_packed.iter().enumerate() {
+            if byte == 0 {
+                continue;
             }
-            for j in 0..prediction_bytes {
-                let byte = ((prediction >> (j * 8)) & 0x0FF) as u8;
-                out_writer.write_all(&[byte]).unwrap();
+            for j in 0..8 {
+                if byte & (1 << j) != 0 {
+                    // little endian
+                    defect_vertices.push((i * 8 + j) as VertexIndex);
+                }
             }
-            self.clear();
         }
+        let syndrome_pattern = SyndromePattern::new_vertices(defect_vertices);
+        solver.solve(&syndrome_pattern);
+        let subgraph = solver.subgraph();
+        let mut prediction = 0;
+        for edge_index in subgraph {
+            prediction ^= edge_masks[edge_index as usize];
+        }
+        for j in 0..prediction_bytes {
+            let byte = ((prediction >> (j * 8)) & 0x0FF) as u8;
+            out_writer.write_all(&[byte]).unwrap();
+        }
+        solver.clear();
+        progress.complete_one();
     }
 }
 
+/// decode many syndrome patterns with one solver, reporting the progress to an optional callback after every shot
+pub struct SolverBatch {
+    pub solver: Box<dyn PrimalDualSolver>,
+    progress_callback: Option<ProgressCallback>,
+}
+
+impl SolverBatch {
+    pub fn new(solver: Box<dyn PrimalDualSolver>) -> Self {
+        Self {
+            solver,
+            progress_callback: None,
+        }
+    }
+
+    pub fn set_progress_callback(&mut self, callback: ProgressCallback) {
+        self.progress_callback = Some(callback);
+    }
+
+    /// the subgraph and the sum of dual variables of each syndrome pattern
+    pub fn solve(&mut self, syndrome_patterns: &[SyndromePattern]) -> Vec<(Vec<EdgeIndex>, Weight)> {
+        let progress = ProgressReporter::new(self.progress_callback.take(), syndrome_patterns.len(), String::new());
+        let results = syndrome_patterns
+            .iter()
+            .map(|syndrome_pattern| {
+                self.solver.solve(syndrome_pattern);
+                let result = (self.solver.subgraph(), self.solver.sum_dual_variables());
+                self.solver.clear();
+                progress.complete_one();
+                result
+            })
+            .collect();
+        self.progress_callback = progress.into_callback();
+        results
+    }
+
+    /// see [`PrimalDualSolver::stim_integration_predict_bit_packed_data`]
+    pub fn stim_integration_predict_bit_packed_data(
+        &mut self,
+        in_file: &str,
+        out_file: &str,
+        edge_masks: &[usize],
+        num_shots: usize,
+        num_dets: usize,
+        num_obs: usize,
+    ) {
+        let progress = ProgressReporter::new(self.progress_callback.take(), num_shots, String::new());
+        stim_predict_bit_packed_data(
+            self.solver.as_mut(),
+            in_file,
+            out_file,
+            edge_masks,
+            num_shots,
+            num_dets,
+            num_obs,
+            &progress,
+        );
+        self.progress_callback = progress.into_callback();
+    }
+}
+
+/// annotate the following snapshots of the visualizer with the number of defects, see [`Visualizer::set_frame_metadata`]
+fn annotate_visualizer<'a>(
+    mut visualizer: Option<&'a mut Visualizer>,
+    syndrome_pattern: &SyndromePattern,
+) -> Option<&'a mut Visualizer> {
+    if let Some(visualizer) = visualizer.as_mut() {
+        visualizer.set_frame_metadata(Some(json!({ "defect_num": syndrome_pattern.defect_vertices.len() })));
+    }
+    visualizer
+}
+
+/// the reproducer written by a solver whose `solve` panics, see [`SolverSerial::debug_dump_on_panic`]; replay it with
+/// the `replay-dump` subcommand of the command line interface
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolverDebugDump {
+    /// the version of fusion blossom that panicked
+    pub version: String,
+    /// `serial`, `dual-parallel` or `parallel`
+    pub solver: String,
+    pub initializer: SolverInitializer,
+    /// the syndrome pattern being solved, including the erasures and dynamic weights
+    pub syndrome_pattern: SyndromePattern,
+    /// the partition of the parallel solvers
+    pub partition_config: Option<PartitionConfig>,
+    /// the configuration of the parallel solvers
+    pub primal_dual_config: serde_json::Value,
+}
+
+impl SolverDebugDump {
+    pub fn load(filename: &str) -> std::io::Result<Self> {
+        let content = std::fs::read_to_string(filename)?;
+        serde_json::from_str(&content).map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+
+    /// a new solver of the same type and configuration as the one that panicked
+    #[cfg_attr(feature = "minimal", allow(unused_variables))]
+    pub fn build_solver(&self) -> Result<Box<dyn PrimalDualSolver>, String> {
+        let partition_info = || {
+            self.partition_config
+                .as_ref()
+                .map(|partition_config| partition_config.info())
+                .ok_or(format!("the {} solver requires a partition config", self.solver))
+        };
+        match self.solver.as_str() {
+            "serial" => Ok(Box::new(SolverSerial::new(&self.initializer))),
+            #[cfg(not(feature = "minimal"))]
+            "dual-parallel" => Ok(Box::new(SolverDualParallel::new(
+                &self.initializer,
+                &partition_info()?,
+                self.primal_dual_config.clone(),
+            ))),
+            #[cfg(not(feature = "minimal"))]
+            "parallel" => Ok(Box::new(SolverParallel::new(
+                &self.initializer,
+                &partition_info()?,
+                self.primal_dual_config.clone(),
+            ))),
+            solver => Err(format!("unknown solver {solver}")),
+        }
+    }
+}
+
+/// where and how a solver writes [`SolverDebugDump`] when it panics
+#[derive(Debug)]
+struct DebugDumpSetting {
+    dir: std::path::PathBuf,
+    solver: &'static str,
+    partition_config: Option<PartitionConfig>,
+    primal_dual_config: serde_json::Value,
+}
+
+/// distinguishes the dumps written in the same millisecond
+static DEBUG_DUMP_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+impl DebugDumpSetting {
+    fn write(
+        &self,
+        initializer: &SolverInitializer,
+        syndrome_pattern: &SyndromePattern,
+    ) -> std::io::Result<std::path::PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let counter = DEBUG_DUMP_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let path = self
+            .dir
+            .join(format!("dump_{timestamp}_{}_{counter}.json", std::process::id()));
+        let dump = SolverDebugDump {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            solver: self.solver.to_string(),
+            initializer: initializer.clone(),
+            syndrome_pattern: syndrome_pattern.clone(),
+            partition_config: self.partition_config.clone(),
+            primal_dual_config: self.primal_dual_config.clone(),
+        };
+        std::fs::write(&path, serde_json::to_string(&dump)?)?;
+        Ok(path)
+    }
+}
+
+/// writes the dump if dropped while unwinding from a panic in `solve`
+struct DebugDumpGuard<'a> {
+    setting: Arc<DebugDumpSetting>,
+    initializer: Arc<SolverInitializer>,
+    syndrome_pattern: &'a SyndromePattern,
+}
+
+impl<'a> DebugDumpGuard<'a> {
+    fn new(
+        setting: &Option<Arc<DebugDumpSetting>>,
+        initializer: &Arc<SolverInitializer>,
+        syndrome_pattern: &'a SyndromePattern,
+    ) -> Option<Self> {
+        setting.as_ref().map(|setting| Self {
+            setting: Arc::clone(setting),
+            initializer: Arc::clone(initializer),
+            syndrome_pattern,
+        })
+    }
+}
+
+impl Drop for DebugDumpGuard<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            match self.setting.write(&self.initializer, self.syndrome_pattern) {
+                Ok(path) => eprintln!(
+                    "[fusion blossom] the solver panicked, reproducer written to {}",
+                    path.display()
+                ),
+                Err(error) => eprintln!("[fusion blossom] the solver panicked, failed to write the reproducer: {error}"),
+            }
+        }
+    }
+}
+
+/// the erasures and dynamic weights must refer to existing edges, otherwise the solver panics deep inside
+#[cfg(feature = "python_binding")]
+#[allow(clippy::unnecessary_cast)]
+pub fn py_check_edge_indices(syndrome_pattern: &SyndromePattern, edge_num: Option<usize>) -> PyResult<()> {
+    let Some(edge_num) = edge_num else {
+        return Ok(());
+    };
+    let edge_indices = (syndrome_pattern.erasures.iter())
+        .chain(syndrome_pattern.dynamic_weights.iter().map(|(edge_index, _)| edge_index))
+        .chain(syndrome_pattern.blocked_edges.iter());
+    for &edge_index in edge_indices {
+        if edge_index as usize >= edge_num {
+            return Err(pyo3::exceptions::PyIndexError::new_err(format!(
+                "edge index {edge_index} out of range for {edge_num} edges"
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(feature = "python_binding")]
 macro_rules! bind_trait_primal_dual_solver {
     ($struct_name:ident) => {
@@ -205,13 +717,29 @@ macro_rules! bind_trait_primal_dual_solver {
             fn trait_clear(&mut self) {
                 self.clear()
             }
+            #[pyo3(name = "warm_up")]
+            fn trait_warm_up(&mut self) {
+                self.warm_up()
+            }
+            /// the syndrome pattern can also be the defect vertices as a list or a 1-D integer array, or a dense boolean
+            /// array of all the measurement results, see [`py_syndrome_pattern`]
             #[pyo3(name = "solve_visualizer")]
-            fn trait_solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
-                self.solve_visualizer(syndrome_pattern, visualizer)
+            fn trait_solve_visualizer(
+                &mut self,
+                syndrome_pattern: &PyAny,
+                visualizer: Option<&mut Visualizer>,
+            ) -> PyResult<()> {
+                let syndrome_pattern = py_syndrome_pattern(syndrome_pattern)?;
+                py_check_edge_indices(&syndrome_pattern, self.edge_num())?;
+                self.solve_visualizer(&syndrome_pattern, visualizer);
+                Ok(())
             }
             #[pyo3(name = "solve")] // in Python, `solve` and `solve_visualizer` is the same because it can take optional parameter
-            fn trait_solve(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
-                self.solve_visualizer(syndrome_pattern, visualizer)
+            fn trait_solve(&mut self, syndrome_pattern: &PyAny, visualizer: Option<&mut Visualizer>) -> PyResult<()> {
+                let syndrome_pattern = py_syndrome_pattern(syndrome_pattern)?;
+                py_check_edge_indices(&syndrome_pattern, self.edge_num())?;
+                self.solve_visualizer(&syndrome_pattern, visualizer);
+                Ok(())
             }
             #[pyo3(name = "perfect_matching_visualizer")]
             fn trait_perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
@@ -237,22 +765,310 @@ macro_rules! bind_trait_primal_dual_solver {
             fn trait_generate_profiler_report(&self) -> PyObject {
                 json_to_pyobject(self.generate_profiler_report())
             }
+            /// `progress_callback` is called with a dict of [`ProgressEvent`] after every shot; the GIL is released
+            /// while decoding and only taken to call it
             #[pyo3(name = "stim_integration_predict_bit_packed_data")]
+            #[pyo3(signature = (in_file, out_file, edge_masks, num_shots, num_dets, num_obs, progress_callback=None))]
+            #[allow(clippy::too_many_arguments)]
             fn trait_stim_integration_predict_bit_packed_data(
                 &mut self,
+                py: Python<'_>,
                 in_file: String,
                 out_file: String,
                 edge_masks: Vec<usize>,
                 num_shots: usize,
                 num_dets: usize,
                 num_obs: usize,
+                progress_callback: Option<PyObject>,
             ) {
-                self.stim_integration_predict_bit_packed_data(in_file, out_file, &edge_masks, num_shots, num_dets, num_obs)
+                let progress = ProgressReporter::new(progress_callback.map(py_progress_callback), num_shots, String::new());
+                py.allow_threads(|| {
+                    stim_predict_bit_packed_data(
+                        self,
+                        &in_file,
+                        &out_file,
+                        &edge_masks,
+                        num_shots,
+                        num_dets,
+                        num_obs,
+                        &progress,
+                    )
+                })
+            }
+        }
+    };
+}
+
+/// a drop-in replacement of `pymatching.Matching.decode` for the solvers that build the subgraph
+#[cfg(feature = "python_binding")]
+macro_rules! bind_trait_pymatching_decode {
+    ($struct_name:ident) => {
+        #[pymethods]
+        impl $struct_name {
+            /// decode the detection events, a dense 0/1 array over all vertices given in the same way as [`py_dense`],
+            /// and return the correction as a dense 0/1 numpy array of `uint8` over the edges in the order of
+            /// `SolverInitializer.weighted_edges`; with `return_weight=True`, return `(correction, weight)` where the
+            /// weight is the sum of the original weights of the correction edges.
+            /// the solver is cleared before decoding, so the result is still available from e.g. `perfect_matching()`
+            #[pyo3(signature = (detection_events, return_weight=false))]
+            #[allow(clippy::unnecessary_cast)]
+            fn decode(&mut self, py: Python<'_>, detection_events: &PyAny, return_weight: bool) -> PyResult<PyObject> {
+                let detection_events = py_dense(detection_events, "detection_events")?;
+                let vertex_num = self.subgraph_builder.vertex_num as usize;
+                if detection_events.len() > vertex_num {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "`detection_events` has {} elements but there are only {vertex_num} vertices",
+                        detection_events.len()
+                    )));
+                }
+                self.clear();
+                self.solve_visualizer(&SyndromePattern::from_dense(&detection_events), None);
+                let subgraph = self.subgraph_visualizer(None);
+                let mut correction = vec![0u8; self.subgraph_builder.complete_graph.weighted_edges.len()];
+                for &edge_index in subgraph.iter() {
+                    correction[edge_index as usize] = 1;
+                }
+                let numpy = py.import("numpy")?;
+                let correction = numpy.call_method(
+                    "frombuffer",
+                    (PyByteArray::new(py, &correction),),
+                    Some([("dtype", numpy.getattr("uint8")?)].into_py_dict(py)),
+                )?;
+                if return_weight {
+                    Ok((correction, self.subgraph_builder.total_weight()).into_py(py))
+                } else {
+                    Ok(correction.into_py(py))
+                }
             }
         }
     };
 }
 
+/// what to do when a connected component of the decoding graph without any virtual vertex has an odd number of defects,
+/// which cannot be perfectly matched; this is unphysical but may happen due to data corruption or windowing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OddParityPolicy {
+    /// fail with an [`OddParityError`]
+    #[default]
+    Error,
+    /// flip the defect of this vertex, which must be in the same component, so that the leftover defect pairs with it
+    MatchToSpecifiedVertex(VertexIndex),
+    /// drop the defect whose distance to the nearest other defect in the component is the largest
+    DropHeaviestDefect,
+}
+
+/// the repair of a component with an odd number of defects, see [`OddParityPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OddParityAction {
+    /// the defect of this vertex is flipped
+    MatchedToVertex(VertexIndex),
+    /// the defect of this vertex is removed from the syndrome
+    DroppedDefect(VertexIndex),
+}
+
+/// a component without virtual vertices has an odd number of defects and the [`OddParityPolicy`] cannot repair it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OddParityError {
+    /// the defects of the component, with [`OddParityPolicy::Error`]
+    OddDefects { defect_vertices: Vec<VertexIndex> },
+    /// the vertex of [`OddParityPolicy::MatchToSpecifiedVertex`] is not in the component of the defects
+    VertexNotInComponent {
+        vertex_index: VertexIndex,
+        defect_vertices: Vec<VertexIndex>,
+    },
+}
+
+impl std::fmt::Display for OddParityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::OddDefects { defect_vertices } => write!(
+                f,
+                "odd number of defects {:?} in a component without virtual vertices, the syndrome is probably corrupted; \
+                consider an `OddParityPolicy` to repair it",
+                defect_vertices
+            ),
+            Self::VertexNotInComponent {
+                vertex_index,
+                defect_vertices,
+            } => write!(
+                f,
+                "cannot match the odd number of defects {:?} to vertex {} which is not in their component",
+                defect_vertices, vertex_index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OddParityError {}
+
+/// no valid correction exists without the blocked edges of the syndrome, see [`SyndromePattern::blocked_edges`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedEdgesError {
+    /// the blocked edges that the minimum-weight correction has to use
+    pub used_blocked_edges: Vec<EdgeIndex>,
+}
+
+impl std::fmt::Display for BlockedEdgesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "no valid correction exists without the blocked edges, the minimum-weight correction uses {:?}",
+            self.used_blocked_edges
+        )
+    }
+}
+
+impl std::error::Error for BlockedEdgesError {}
+
+/// how the last syndrome is solved under [`SolverSerial::set_max_total_growth`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct GrowthCapStatistics {
+    /// the growth stops at the cap and some defects are matched greedily, so the correction may not have the minimum weight
+    pub suboptimal: bool,
+    /// the total growth when the solver stops, see [`crate::dual_module::DualModuleInterface::total_growth`]
+    pub total_growth: Weight,
+    /// the number of defects matched greedily
+    pub greedy_defects: usize,
+    /// an upper bound of the weight of the correction minus the minimum weight, i.e. its weight minus the sum of the dual
+    /// variables which never exceeds the minimum weight; 0 if not suboptimal
+    pub weight_gap_bound: Weight,
+}
+
+/// the connected components of the decoding graph that have no virtual vertex, where the parity of the defects matters
+#[derive(Debug, Clone)]
+struct BoundaryFreeComponents {
+    /// the component of each vertex, or `None` if its component has a virtual vertex
+    component_of: Vec<Option<usize>>,
+    component_num: usize,
+}
+
+impl BoundaryFreeComponents {
+    /// `None` if every component has a virtual vertex, which is the case for most codes
+    #[allow(clippy::unnecessary_cast)]
+    fn new(initializer: &SolverInitializer) -> Option<Self> {
+        let vertex_num = initializer.vertex_num as usize;
+        let mut neighbors = vec![vec![]; vertex_num];
+        for &(v1, v2, _) in initializer.weighted_edges.iter() {
+            neighbors[v1 as usize].push(v2 as usize);
+            neighbors[v2 as usize].push(v1 as usize);
+        }
+        let mut is_virtual = vec![false; vertex_num];
+        for &vertex_index in initializer.virtual_vertices.iter() {
+            is_virtual[vertex_index as usize] = true;
+        }
+        let mut component_of = vec![None; vertex_num];
+        let mut visited = vec![false; vertex_num];
+        let mut component_num = 0;
+        for root in 0..vertex_num {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut members = vec![root];
+            let mut has_virtual = false;
+            let mut next = 0;
+            while next < members.len() {
+                let vertex_index = members[next];
+                next += 1;
+                has_virtual |= is_virtual[vertex_index];
+                for &neighbor in neighbors[vertex_index].iter() {
+                    if !visited[neighbor] {
+                        visited[neighbor] = true;
+                        members.push(neighbor);
+                    }
+                }
+            }
+            if !has_virtual {
+                for &vertex_index in members.iter() {
+                    component_of[vertex_index] = Some(component_num);
+                }
+                component_num += 1;
+            }
+        }
+        (component_num > 0).then_some(Self {
+            component_of,
+            component_num,
+        })
+    }
+
+    /// repair the defects of every component with an odd number of them, in the order of the components
+    #[allow(clippy::unnecessary_cast)]
+    fn repair(
+        &self,
+        defect_vertices: &mut Vec<VertexIndex>,
+        policy: OddParityPolicy,
+        complete_graph: &mut CompleteGraph,
+    ) -> Result<Vec<OddParityAction>, OddParityError> {
+        let mut component_defects = vec![vec![]; self.component_num];
+        for &defect_vertex in defect_vertices.iter() {
+            if let Some(component) = self.component_of[defect_vertex as usize] {
+                component_defects[component].push(defect_vertex);
+            }
+        }
+        let mut actions = vec![];
+        for (component, defects) in component_defects.into_iter().enumerate() {
+            if defects.len() % 2 == 0 {
+                continue;
+            }
+            let action = match policy {
+                OddParityPolicy::Error => {
+                    return Err(OddParityError::OddDefects {
+                        defect_vertices: defects,
+                    })
+                }
+                OddParityPolicy::MatchToSpecifiedVertex(vertex_index) => {
+                    if self.component_of.get(vertex_index as usize).copied().flatten() != Some(component) {
+                        return Err(OddParityError::VertexNotInComponent {
+                            vertex_index,
+                            defect_vertices: defects,
+                        });
+                    }
+                    OddParityAction::MatchedToVertex(vertex_index)
+                }
+                OddParityPolicy::DropHeaviestDefect => {
+                    // a single defect has no neighbor and is dropped right away
+                    let mut heaviest = (defects[0], Weight::MAX);
+                    if defects.len() > 1 {
+                        heaviest.1 = Weight::MIN;
+                        for &defect_vertex in defects.iter() {
+                            let distances = complete_graph.all_edges(defect_vertex);
+                            let nearest = defects
+                                .iter()
+                                .filter_map(|peer| distances.get(peer).map(|&(_, weight)| weight))
+                                .min()
+                                .unwrap_or(Weight::MAX);
+                            if nearest > heaviest.1 {
+                                heaviest = (defect_vertex, nearest);
+                            }
+                        }
+                    }
+                    OddParityAction::DroppedDefect(heaviest.0)
+                }
+            };
+            // both actions flip the defect of a vertex
+            let flipped_vertex = match action {
+                OddParityAction::MatchedToVertex(vertex_index) | OddParityAction::DroppedDefect(vertex_index) => {
+                    vertex_index
+                }
+            };
+            match defect_vertices
+                .iter()
+                .position(|&defect_vertex| defect_vertex == flipped_vertex)
+            {
+                Some(index) => {
+                    defect_vertices.remove(index);
+                }
+                None => {
+                    defect_vertices.push(flipped_vertex);
+                    defect_vertices.sort_unstable();
+                }
+            }
+            actions.push(action);
+        }
+        Ok(actions)
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverSerial {
@@ -260,6 +1076,25 @@ pub struct SolverSerial {
     primal_module: PrimalModuleSerialPtr,
     interface_ptr: DualModuleInterfacePtr,
     subgraph_builder: SubGraphBuilder,
+    initializer: Arc<SolverInitializer>,
+    debug_dump: Option<Arc<DebugDumpSetting>>,
+    boundary_selection: BoundarySelection,
+    /// only if some component of the decoding graph has no virtual vertex
+    boundary_free_components: Option<BoundaryFreeComponents>,
+    odd_parity_policy: OddParityPolicy,
+    /// the repairs of the last solved syndrome
+    odd_parity_actions: Vec<OddParityAction>,
+    /// the number of syndromes that are repaired since the solver is created
+    odd_parity_shots: usize,
+    /// the sorted blocked edges of the last solved syndrome
+    blocked_edges: Vec<EdgeIndex>,
+    /// see [`SolverSerial::set_max_total_growth`]
+    max_total_growth: Option<Weight>,
+    growth_cap_statistics: GrowthCapStatistics,
+    /// the matching of the last solved syndrome if its growth stops at the cap
+    greedy_matching: Option<PerfectMatching>,
+    /// see [`SolverSerial::enable_event_log`]
+    event_log: Option<SolverEventLog>,
 }
 
 bind_trait_fusion_visualizer!(SolverSerial);
@@ -274,18 +1109,345 @@ impl FusionVisualizer for SolverSerial {
 
 #[cfg(feature = "python_binding")]
 bind_trait_primal_dual_solver! {SolverSerial}
+#[cfg(feature = "python_binding")]
+bind_trait_pymatching_decode! {SolverSerial}
 
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl SolverSerial {
     #[cfg_attr(feature = "python_binding", new)]
-    pub fn new(initializer: &SolverInitializer) -> Self {
-        Self {
+    pub fn try_new(initializer: &SolverInitializer) -> Result<Self, FusionError> {
+        check_initializer(initializer)?;
+        trace_span!("solver_new", solver = "serial", vertex_num = initializer.vertex_num);
+        Ok(Self {
             dual_module: DualModuleSerial::new_empty(initializer),
             primal_module: PrimalModuleSerialPtr::new_empty(initializer),
             interface_ptr: DualModuleInterfacePtr::new_empty(),
             subgraph_builder: SubGraphBuilder::new(initializer),
+            initializer: Arc::new(initializer.clone()),
+            debug_dump: None,
+            boundary_selection: BoundarySelection::default(),
+            boundary_free_components: BoundaryFreeComponents::new(initializer),
+            odd_parity_policy: OddParityPolicy::default(),
+            odd_parity_actions: vec![],
+            odd_parity_shots: 0,
+            blocked_edges: vec![],
+            max_total_growth: None,
+            growth_cap_statistics: GrowthCapStatistics::default(),
+            greedy_matching: None,
+            event_log: None,
+        })
+    }
+}
+
+impl SolverSerial {
+    /// panics if the decoding graph is invalid, see [`Self::try_new`]
+    pub fn new(initializer: &SolverInitializer) -> Self {
+        or_panic(Self::try_new(initializer))
+    }
+    /// see [`OddParityPolicy`]; it only matters if some component of the decoding graph has no virtual vertex
+    pub fn set_odd_parity_policy(&mut self, policy: OddParityPolicy) {
+        self.odd_parity_policy = policy;
+    }
+
+    /// how the odd parity of the last solved syndrome is repaired; a non-empty list flags the shot as unreliable
+    pub fn odd_parity_actions(&self) -> &[OddParityAction] {
+        &self.odd_parity_actions
+    }
+
+    /// stop growing once the total growth reaches `max_total_growth` and match the remaining defects greedily to their
+    /// nearest available peer or virtual vertex, which bounds the latency of pathological syndromes at the cost of a
+    /// possibly suboptimal correction, see [`Self::growth_cap_statistics`]; `None` (the default) has no limit
+    pub fn set_max_total_growth(&mut self, max_total_growth: Option<Weight>) {
+        self.max_total_growth = max_total_growth;
+    }
+
+    /// whether the growth of the last solved syndrome stops at the cap of [`Self::set_max_total_growth`]
+    pub fn growth_cap_statistics(&self) -> GrowthCapStatistics {
+        self.growth_cap_statistics
+    }
+
+    /// which virtual vertex a defect matched to the boundary goes to, in both [`PrimalDualSolver::perfect_matching`] and
+    /// [`PrimalDualSolver::subgraph`]; it never changes the total weight, see [`BoundarySelection`]
+    pub fn set_boundary_selection(&mut self, selection: BoundarySelection) {
+        self.boundary_selection = selection;
+    }
+
+    /// record the conflicts resolved by the primal module while solving, see [`SolverEvent`]; the log accumulates
+    /// until [`PrimalDualSolver::clear`] and keeps at most [`SolverEventLog::DEFAULT_CAPACITY`] events
+    pub fn enable_event_log(&mut self) {
+        self.set_event_log_capacity(SolverEventLog::DEFAULT_CAPACITY);
+    }
+
+    /// enable the event log with at most `capacity` events, see [`Self::event_log_truncated`]
+    pub fn set_event_log_capacity(&mut self, capacity: usize) {
+        match self.event_log.as_mut() {
+            Some(event_log) => event_log.capacity = capacity,
+            None => self.event_log = Some(SolverEventLog::new(capacity)),
+        }
+    }
+
+    /// the recorded events since the last clear, empty if the event log is not enabled
+    pub fn event_log(&self) -> &[SolverEvent] {
+        self.event_log.as_ref().map_or(&[], |event_log| &event_log.events)
+    }
+
+    /// whether some events are dropped because the event log is full
+    pub fn event_log_truncated(&self) -> bool {
+        self.event_log.as_ref().is_some_and(|event_log| event_log.truncated)
+    }
+
+    /// solve the syndrome, unless a component without virtual vertices has an odd number of defects that the
+    /// [`OddParityPolicy`] cannot repair, in which case the solver is left cleared
+    pub fn try_solve(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), OddParityError> {
+        self.try_solve_visualizer(syndrome_pattern, None)
+    }
+
+    pub fn try_solve_visualizer(
+        &mut self,
+        syndrome_pattern: &SyndromePattern,
+        visualizer: Option<&mut Visualizer>,
+    ) -> Result<(), OddParityError> {
+        let _debug_dump_guard = DebugDumpGuard::new(&self.debug_dump, &self.initializer, syndrome_pattern);
+        self.blocked_edges.clone_from(&syndrome_pattern.blocked_edges);
+        self.blocked_edges.sort_unstable();
+        let resolved_syndrome_pattern =
+            (!syndrome_pattern.blocked_edges.is_empty()).then(|| self.initializer.resolve_blocked_edges(syndrome_pattern));
+        let syndrome_pattern = resolved_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        if !syndrome_pattern.erasures.is_empty() {
+            assert!(
+                syndrome_pattern.dynamic_weights.is_empty(),
+                "erasures and dynamic_weights cannot be provided at the same time"
+            );
+            self.subgraph_builder.load_erasures(&syndrome_pattern.erasures);
+        }
+        if !syndrome_pattern.dynamic_weights.is_empty() {
+            self.subgraph_builder.load_dynamic_weights(&syndrome_pattern.dynamic_weights);
+        }
+        self.odd_parity_actions.clear();
+        self.growth_cap_statistics = GrowthCapStatistics::default();
+        self.greedy_matching = None;
+        let mut repaired_syndrome_pattern = None;
+        if let Some(components) = self.boundary_free_components.as_ref() {
+            let mut defect_vertices = syndrome_pattern.defect_vertices.clone();
+            let actions = components
+                .repair(
+                    &mut defect_vertices,
+                    self.odd_parity_policy,
+                    &mut self.subgraph_builder.complete_graph,
+                )
+                .inspect_err(|_| self.subgraph_builder.clear())?;
+            if !actions.is_empty() {
+                self.odd_parity_actions = actions;
+                self.odd_parity_shots += 1;
+                let mut syndrome_pattern = syndrome_pattern.clone();
+                syndrome_pattern.defect_vertices = defect_vertices;
+                repaired_syndrome_pattern = Some(syndrome_pattern);
+            }
+        }
+        let syndrome_pattern = repaired_syndrome_pattern.as_ref().unwrap_or(syndrome_pattern);
+        let visualizer = annotate_visualizer(visualizer, syndrome_pattern);
+        // the primal module only holds the event log while solving
+        self.primal_module.write().event_log = self.event_log.take();
+        match self.max_total_growth {
+            Some(max_total_growth) => self.solve_growth_capped(syndrome_pattern, max_total_growth, visualizer),
+            None => {
+                self.primal_module
+                    .solve_visualizer(&self.interface_ptr, syndrome_pattern, &mut self.dual_module, visualizer)
+            }
+        }
+        self.event_log = self.primal_module.write().event_log.take();
+        Ok(())
+    }
+
+    /// only the final state is snapshot in the visualizer, not every growth and conflict
+    fn solve_growth_capped(
+        &mut self,
+        syndrome_pattern: &SyndromePattern,
+        max_total_growth: Weight,
+        visualizer: Option<&mut Visualizer>,
+    ) {
+        self.interface_ptr.load(syndrome_pattern, &mut self.dual_module);
+        self.primal_module.load(&self.interface_ptr);
+        let stopped = self.primal_module.solve_interface_loaded_growth_capped(
+            &self.interface_ptr,
+            &mut self.dual_module,
+            max_total_growth,
+        );
+        if let Some(visualizer) = visualizer {
+            visualizer
+                .snapshot_combined_category(
+                    if stopped { "growth capped" } else { "solved" }.to_string(),
+                    SnapshotCategory::Final,
+                    vec![&self.interface_ptr, &self.dual_module, &self.primal_module],
+                )
+                .unwrap();
+        }
+        self.growth_cap_statistics.total_growth = self.interface_ptr.read_recursive().total_growth();
+        if !stopped {
+            return;
+        }
+        let (intermediate_matching, unmatched_nodes) = self.primal_module.partial_matching();
+        let mut perfect_matching = intermediate_matching.get_perfect_matching();
+        let defect_nodes: Vec<DualNodePtr> = (unmatched_nodes.iter())
+            .flat_map(|node_ptr| node_ptr.get_all_defect_nodes())
+            .collect();
+        self.match_greedily(&defect_nodes, &mut perfect_matching);
+        self.subgraph_builder.load_perfect_matching(&perfect_matching);
+        self.growth_cap_statistics.suboptimal = true;
+        self.growth_cap_statistics.greedy_defects = defect_nodes.len();
+        self.growth_cap_statistics.weight_gap_bound =
+            self.subgraph_builder.total_weight() - self.interface_ptr.sum_dual_variables();
+        self.greedy_matching = Some(perfect_matching);
+    }
+
+    /// match the defects in the order of increasing distance, each to a peer or to its nearest virtual vertex
+    fn match_greedily(&mut self, defect_nodes: &[DualNodePtr], perfect_matching: &mut PerfectMatching) {
+        let is_virtual: BTreeSet<VertexIndex> = self.initializer.virtual_vertices.iter().cloned().collect();
+        let defect_vertices: Vec<VertexIndex> = defect_nodes
+            .iter()
+            .map(|node_ptr| node_ptr.get_representative_vertex())
+            .collect();
+        // (distance, defect, peer defect or `None` for the nearest virtual vertex)
+        let mut candidates: Vec<(Weight, usize, Option<usize>)> = vec![];
+        let mut nearest_virtual = Vec::with_capacity(defect_vertices.len());
+        for (index, &defect_vertex) in defect_vertices.iter().enumerate() {
+            let edges = self.subgraph_builder.complete_graph.all_edges(defect_vertex);
+            for (peer_index, peer_vertex) in defect_vertices.iter().enumerate().skip(index + 1) {
+                if let Some(&(_, weight)) = edges.get(peer_vertex) {
+                    candidates.push((weight, index, Some(peer_index)));
+                }
+            }
+            let boundary = (edges.iter())
+                .filter(|(vertex, _)| is_virtual.contains(vertex))
+                .map(|(&vertex, &(_, weight))| (weight, vertex))
+                .min();
+            if let Some((weight, _)) = boundary {
+                candidates.push((weight, index, None));
+            }
+            nearest_virtual.push(boundary.map(|(_, virtual_vertex)| virtual_vertex));
+        }
+        candidates.sort_unstable();
+        let mut is_matched = vec![false; defect_vertices.len()];
+        for (_, index, peer_index) in candidates {
+            if is_matched[index] {
+                continue;
+            }
+            match peer_index {
+                Some(peer_index) if !is_matched[peer_index] => {
+                    is_matched[peer_index] = true;
+                    perfect_matching
+                        .peer_matchings
+                        .push((defect_nodes[index].clone(), defect_nodes[peer_index].clone()));
+                }
+                Some(_) => continue,
+                None => {
+                    let virtual_vertex = nearest_virtual[index].unwrap();
+                    perfect_matching
+                        .virtual_matchings
+                        .push((defect_nodes[index].clone(), virtual_vertex));
+                }
+            }
+            is_matched[index] = true;
+        }
+        let unmatched_vertices: Vec<VertexIndex> = (defect_vertices.iter().zip(is_matched))
+            .filter(|(_, is_matched)| !is_matched)
+            .map(|(&defect_vertex, _)| defect_vertex)
+            .collect();
+        assert!(
+            unmatched_vertices.is_empty(),
+            "cannot match the defect vertices {unmatched_vertices:?} greedily"
+        );
+    }
+
+    /// the correction of the last solved syndrome, unless it has to use some of its [`SyndromePattern::blocked_edges`]
+    /// because no valid correction exists without them
+    pub fn try_subgraph(&mut self) -> Result<Vec<EdgeIndex>, BlockedEdgesError> {
+        self.try_subgraph_visualizer(None)
+    }
+
+    pub fn try_subgraph_visualizer(
+        &mut self,
+        visualizer: Option<&mut Visualizer>,
+    ) -> Result<Vec<EdgeIndex>, BlockedEdgesError> {
+        let perfect_matching = self.perfect_matching();
+        self.subgraph_builder.load_perfect_matching(&perfect_matching);
+        let subgraph = self.subgraph_builder.get_subgraph();
+        if let Some(visualizer) = visualizer {
+            visualizer
+                .snapshot_combined_category(
+                    "perfect matching and subgraph".to_string(),
+                    SnapshotCategory::Final,
+                    vec![
+                        &self.interface_ptr,
+                        &self.dual_module,
+                        &perfect_matching,
+                        &VisualizeSubgraph::new(&subgraph),
+                    ],
+                )
+                .unwrap();
+        }
+        let used_blocked_edges: Vec<EdgeIndex> = (subgraph.iter())
+            .filter(|edge_index| self.blocked_edges.binary_search(edge_index).is_ok())
+            .cloned()
+            .collect();
+        if !used_blocked_edges.is_empty() {
+            return Err(BlockedEdgesError { used_blocked_edges });
+        }
+        Ok(subgraph)
+    }
+
+    /// permanently change the weights of some edges, unlike [`SyndromePattern::dynamic_weights`] which only lasts for a
+    /// single decoding; this also clears the solver
+    #[allow(clippy::unnecessary_cast)]
+    pub fn update_weights(&mut self, new_weights: &[(EdgeIndex, Weight)]) {
+        self.clear();
+        self.dual_module.update_weights(new_weights);
+        self.subgraph_builder.update_weights(new_weights);
+        let initializer = Arc::make_mut(&mut self.initializer);
+        for &(edge_index, weight) in new_weights.iter() {
+            initializer.weighted_edges[edge_index as usize].2 = weight;
+        }
+    }
+
+    /// when `solve` panics, write the initializer, the syndrome pattern and the configuration as a [`SolverDebugDump`]
+    /// in a timestamped json file in `dir` before unwinding further; replay it with `fusion_blossom replay-dump <file>`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn debug_dump_on_panic(&mut self, dir: impl Into<std::path::PathBuf>) {
+        self.debug_dump = Some(Arc::new(DebugDumpSetting {
+            dir: dir.into(),
+            solver: "serial",
+            partition_config: None,
+            primal_dual_config: json!({}),
+        }));
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl SolverSerial {
+    #[pyo3(name = "update_weights")]
+    #[allow(clippy::unnecessary_cast)]
+    fn py_update_weights(&mut self, edges: Vec<EdgeIndex>, weights: Vec<Weight>) -> PyResult<()> {
+        if edges.len() != weights.len() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "{} edges but {} weights are given",
+                edges.len(),
+                weights.len()
+            )));
+        }
+        let new_weights: Vec<_> = edges.into_iter().zip(weights).collect();
+        let syndrome_pattern = SyndromePattern::new_dynamic_weights(vec![], vec![], new_weights.clone());
+        py_check_edge_indices(&syndrome_pattern, self.edge_num())?;
+        for &(edge_index, weight) in new_weights.iter() {
+            if weight < 0 || weight % 2 != 0 {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "weight {weight} of edge {edge_index} must be a non-negative even number"
+                )));
+            }
         }
+        self.update_weights(&new_weights);
+        Ok(())
     }
 }
 
@@ -295,65 +1457,120 @@ impl PrimalDualSolver for SolverSerial {
         self.dual_module.clear();
         self.interface_ptr.clear();
         self.subgraph_builder.clear();
+        self.blocked_edges.clear();
+        self.greedy_matching = None;
+        if let Some(event_log) = self.event_log.as_mut() {
+            event_log.clear();
+        }
+    }
+    fn warm_up(&mut self) {
+        self.clear();
+        self.dual_module.warm_up();
+        if let Some(syndrome_pattern) = warm_up_syndrome(&self.initializer) {
+            self.solve(&syndrome_pattern);
+            self.subgraph();
+        }
+        self.clear();
+    }
+    fn solve_checked(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), FusionError> {
+        check_syndrome_pattern(&self.initializer, syndrome_pattern, true)?;
+        Ok(self.try_solve(syndrome_pattern)?)
     }
+    /// panics if the odd parity of the defects cannot be repaired, see [`SolverSerial::try_solve`]
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
-        if !syndrome_pattern.erasures.is_empty() {
-            assert!(
-                syndrome_pattern.dynamic_weights.is_empty(),
-                "erasures and dynamic_weights cannot be provided at the same time"
-            );
-            self.subgraph_builder.load_erasures(&syndrome_pattern.erasures);
+        if let Err(error) = self.try_solve_visualizer(syndrome_pattern, visualizer) {
+            panic!("{error}");
         }
-        if !syndrome_pattern.dynamic_weights.is_empty() {
-            self.subgraph_builder.load_dynamic_weights(&syndrome_pattern.dynamic_weights);
+    }
+    /// without a visualizer, debug dump, parity check, growth cap or event log the defects are loaded directly from the set bits of the bitmap,
+    /// otherwise it's converted
+    fn solve_bitmap_visualizer(&mut self, bitmap: &SyndromeBitmap, visualizer: Option<&mut Visualizer>) {
+        if visualizer.is_some()
+            || self.debug_dump.is_some()
+            || self.boundary_free_components.is_some()
+            || self.max_total_growth.is_some()
+            || self.event_log.is_some()
+        {
+            return self.solve_visualizer(&bitmap.to_syndrome_pattern(), visualizer);
+        }
+        let erasures: Vec<EdgeIndex> = bitmap.erasure_edges().collect();
+        if !erasures.is_empty() {
+            self.subgraph_builder.load_erasures(&erasures);
         }
+        self.interface_ptr.load_bitmap(bitmap, &mut self.dual_module);
+        self.primal_module.load(&self.interface_ptr);
         self.primal_module
-            .solve_visualizer(&self.interface_ptr, syndrome_pattern, &mut self.dual_module, visualizer);
+            .solve_step_callback_interface_loaded(&self.interface_ptr, &mut self.dual_module, |_, _, _, _| {});
     }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
-        let perfect_matching = self
-            .primal_module
-            .perfect_matching(&self.interface_ptr, &mut self.dual_module);
+        let mut perfect_matching = match self.greedy_matching.as_ref() {
+            Some(perfect_matching) => perfect_matching.clone(),
+            None => self
+                .primal_module
+                .perfect_matching(&self.interface_ptr, &mut self.dual_module),
+        };
+        perfect_matching.select_boundary(
+            &self.boundary_selection,
+            &self.initializer,
+            &mut self.subgraph_builder.complete_graph,
+        );
         if let Some(visualizer) = visualizer {
             visualizer
-                .snapshot_combined(
+                .snapshot_combined_category(
                     "perfect matching".to_string(),
+                    SnapshotCategory::Final,
                     vec![&self.interface_ptr, &self.dual_module, &perfect_matching],
                 )
                 .unwrap();
         }
         perfect_matching
     }
+    /// panics if no valid correction exists without the blocked edges, see [`SolverSerial::try_subgraph`]
     fn subgraph_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> Vec<EdgeIndex> {
-        let perfect_matching = self.perfect_matching();
-        self.subgraph_builder.load_perfect_matching(&perfect_matching);
-        let subgraph = self.subgraph_builder.get_subgraph();
-        if let Some(visualizer) = visualizer {
-            visualizer
-                .snapshot_combined(
-                    "perfect matching and subgraph".to_string(),
-                    vec![
-                        &self.interface_ptr,
-                        &self.dual_module,
-                        &perfect_matching,
-                        &VisualizeSubgraph::new(&subgraph),
-                    ],
-                )
-                .unwrap();
+        match self.try_subgraph_visualizer(visualizer) {
+            Ok(subgraph) => subgraph,
+            Err(error) => panic!("{error}"),
         }
-        subgraph
     }
     fn sum_dual_variables(&self) -> Weight {
         self.interface_ptr.read_recursive().sum_dual_variables
     }
     fn generate_profiler_report(&self) -> serde_json::Value {
-        json!({
+        let mut report = json!({
             "dual": self.dual_module.generate_profiler_report(),
             "primal": self.primal_module.generate_profiler_report(),
-        })
+        });
+        if let Some(max_total_growth) = self.max_total_growth {
+            report["growth_cap"] = json!({
+                "max_total_growth": max_total_growth,
+                "statistics": self.growth_cap_statistics,
+            });
+        }
+        if self.boundary_free_components.is_some() {
+            report["odd_parity"] = json!({
+                "policy": self.odd_parity_policy,
+                "actions": self.odd_parity_actions,
+                "shots": self.odd_parity_shots,
+            });
+        }
+        report
+    }
+    fn memory_estimate(&self) -> Option<usize> {
+        Some(
+            size_of::<Self>()
+                + self.dual_module.memory_estimate()
+                + self.primal_module.read_recursive().memory_estimate()
+                + self.interface_ptr.read_recursive().memory_estimate()
+                + self.subgraph_builder.memory_estimate()
+                + self.initializer.memory_estimate(),
+        )
+    }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.subgraph_builder.complete_graph.weighted_edges.len())
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverDualParallel {
@@ -361,9 +1578,14 @@ pub struct SolverDualParallel {
     primal_module: PrimalModuleSerialPtr,
     interface_ptr: DualModuleInterfacePtr,
     subgraph_builder: SubGraphBuilder,
+    initializer: Arc<SolverInitializer>,
+    debug_dump: Option<Arc<DebugDumpSetting>>,
+    boundary_selection: BoundarySelection,
 }
 
+#[cfg(not(feature = "minimal"))]
 bind_trait_fusion_visualizer!(SolverDualParallel);
+#[cfg(not(feature = "minimal"))]
 impl FusionVisualizer for SolverDualParallel {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
         let mut value = self.primal_module.snapshot(abbrev);
@@ -373,39 +1595,88 @@ impl FusionVisualizer for SolverDualParallel {
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg(feature = "python_binding")]
 bind_trait_primal_dual_solver! {SolverDualParallel}
+#[cfg(not(feature = "minimal"))]
+#[cfg(feature = "python_binding")]
+bind_trait_pymatching_decode! {SolverDualParallel}
 
+#[cfg(not(feature = "minimal"))]
 #[cfg(feature = "python_binding")]
 #[pymethods]
 impl SolverDualParallel {
+    /// the vertices of the initializer and the syndromes must be in the ordering that the partition assumes, see
+    /// [`crate::partition::PartitionPlan`]; `thread_pool_size` is a shortcut for the same key in the config
     #[new]
+    #[pyo3(signature = (initializer, partition_info, primal_dual_config=None, thread_pool_size=None))]
     pub fn new_python(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
-        primal_dual_config: PyObject,
-    ) -> Self {
-        let primal_dual_config = pyobject_to_json(primal_dual_config);
-        Self::new(initializer, partition_info, primal_dual_config)
+        primal_dual_config: Option<PyObject>,
+        thread_pool_size: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut primal_dual_config = primal_dual_config.map(pyobject_to_json).unwrap_or(json!({}));
+        if let Some(thread_pool_size) = thread_pool_size {
+            primal_dual_config
+                .as_object_mut()
+                .ok_or_else(|| pyo3::exceptions::PyTypeError::new_err("`primal_dual_config` must be a dict"))?
+                .insert("thread_pool_size".to_string(), json!(thread_pool_size));
+        }
+        Ok(Self::try_new(initializer, partition_info, primal_dual_config)?)
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 impl SolverDualParallel {
+    /// panics if the decoding graph, the partition or the config is invalid, see [`Self::try_new`]
     pub fn new(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
         primal_dual_config: serde_json::Value,
     ) -> Self {
-        let config: DualModuleParallelConfig = serde_json::from_value(primal_dual_config).unwrap();
-        Self {
+        or_panic(Self::try_new(initializer, partition_info, primal_dual_config))
+    }
+
+    pub fn try_new(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        primal_dual_config: serde_json::Value,
+    ) -> Result<Self, FusionError> {
+        check_initializer(initializer)?;
+        partition_info.check_edges(&initializer.weighted_edges)?;
+        trace_span!("solver_new", solver = "dual-parallel", vertex_num = initializer.vertex_num);
+        let config: DualModuleParallelConfig =
+            serde_json::from_value(primal_dual_config).map_err(|error| FusionError::Config(error.to_string()))?;
+        Ok(Self {
             dual_module: DualModuleParallel::new_config(initializer, partition_info, config),
             primal_module: PrimalModuleSerialPtr::new_empty(initializer),
             interface_ptr: DualModuleInterfacePtr::new_empty(),
             subgraph_builder: SubGraphBuilder::new(initializer),
-        }
+            initializer: Arc::new(initializer.clone()),
+            debug_dump: None,
+            boundary_selection: BoundarySelection::default(),
+        })
+    }
+
+    /// the same as [`SolverSerial::set_boundary_selection`]
+    pub fn set_boundary_selection(&mut self, selection: BoundarySelection) {
+        self.boundary_selection = selection;
+    }
+
+    /// the same as [`SolverSerial::debug_dump_on_panic`]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn debug_dump_on_panic(&mut self, dir: impl Into<std::path::PathBuf>) {
+        self.debug_dump = Some(Arc::new(DebugDumpSetting {
+            dir: dir.into(),
+            solver: "dual-parallel",
+            partition_config: Some(self.dual_module.partition_info.config.clone()),
+            primal_dual_config: json!({ "dual": self.dual_module.config }),
+        }));
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 impl PrimalDualSolver for SolverDualParallel {
     fn clear(&mut self) {
         self.dual_module.clear();
@@ -413,7 +1684,22 @@ impl PrimalDualSolver for SolverDualParallel {
         self.interface_ptr.clear();
         self.subgraph_builder.clear();
     }
+    fn warm_up(&mut self) {
+        self.clear();
+        self.dual_module.warm_up();
+        if let Some(syndrome_pattern) = warm_up_syndrome(&self.initializer) {
+            self.solve(&syndrome_pattern);
+            self.subgraph();
+        }
+        self.clear();
+    }
+    fn solve_checked(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), FusionError> {
+        check_syndrome_pattern(&self.initializer, syndrome_pattern, false)?;
+        self.solve(syndrome_pattern);
+        Ok(())
+    }
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
+        let _debug_dump_guard = DebugDumpGuard::new(&self.debug_dump, &self.initializer, syndrome_pattern);
         if !syndrome_pattern.erasures.is_empty() {
             assert!(
                 syndrome_pattern.dynamic_weights.is_empty(),
@@ -425,17 +1711,24 @@ impl PrimalDualSolver for SolverDualParallel {
             self.subgraph_builder.load_dynamic_weights(&syndrome_pattern.dynamic_weights);
         }
         self.dual_module.static_fuse_all();
+        let visualizer = annotate_visualizer(visualizer, syndrome_pattern);
         self.primal_module
             .solve_visualizer(&self.interface_ptr, syndrome_pattern, &mut self.dual_module, visualizer);
     }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
-        let perfect_matching = self
+        let mut perfect_matching = self
             .primal_module
             .perfect_matching(&self.interface_ptr, &mut self.dual_module);
+        perfect_matching.select_boundary(
+            &self.boundary_selection,
+            &self.initializer,
+            &mut self.subgraph_builder.complete_graph,
+        );
         if let Some(visualizer) = visualizer {
             visualizer
-                .snapshot_combined(
+                .snapshot_combined_category(
                     "perfect matching".to_string(),
+                    SnapshotCategory::Final,
                     vec![&self.interface_ptr, &self.dual_module, &perfect_matching],
                 )
                 .unwrap();
@@ -448,8 +1741,9 @@ impl PrimalDualSolver for SolverDualParallel {
         let subgraph = self.subgraph_builder.get_subgraph();
         if let Some(visualizer) = visualizer {
             visualizer
-                .snapshot_combined(
+                .snapshot_combined_category(
                     "perfect matching and subgraph".to_string(),
+                    SnapshotCategory::Final,
                     vec![
                         &self.interface_ptr,
                         &self.dual_module,
@@ -470,17 +1764,40 @@ impl PrimalDualSolver for SolverDualParallel {
             "primal": self.primal_module.generate_profiler_report(),
         })
     }
+    fn memory_estimate(&self) -> Option<usize> {
+        Some(
+            size_of::<Self>()
+                + self.dual_module.memory_estimate()
+                + self.primal_module.read_recursive().memory_estimate()
+                + self.interface_ptr.read_recursive().memory_estimate()
+                + self.subgraph_builder.memory_estimate()
+                + self.initializer.memory_estimate(),
+        )
+    }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.subgraph_builder.complete_graph.weighted_edges.len())
+    }
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverParallel {
-    dual_module: DualModuleParallel<DualModuleSerial>,
-    primal_module: PrimalModuleParallel,
+    /// shared with the tasks of streaming decoding, otherwise exclusively owned
+    dual_module: Arc<DualModuleParallel<DualModuleSerial>>,
+    /// shared with the tasks of streaming decoding, otherwise exclusively owned
+    pub(crate) primal_module: Arc<PrimalModuleParallel>,
     subgraph_builder: SubGraphBuilder,
+    /// the ongoing (or last finished) streaming decoding
+    streaming: Option<StreamingDecodePtr>,
+    initializer: Arc<SolverInitializer>,
+    debug_dump: Option<Arc<DebugDumpSetting>>,
+    boundary_selection: BoundarySelection,
 }
 
+#[cfg(not(feature = "minimal"))]
 bind_trait_fusion_visualizer!(SolverParallel);
+#[cfg(not(feature = "minimal"))]
 impl FusionVisualizer for SolverParallel {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
         let mut value = self.primal_module.snapshot(abbrev);
@@ -489,76 +1806,347 @@ impl FusionVisualizer for SolverParallel {
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg(feature = "python_binding")]
 bind_trait_primal_dual_solver! {SolverParallel}
+#[cfg(not(feature = "minimal"))]
+#[cfg(feature = "python_binding")]
+bind_trait_pymatching_decode! {SolverParallel}
 
+#[cfg(not(feature = "minimal"))]
 #[cfg(feature = "python_binding")]
 #[pymethods]
 impl SolverParallel {
+    /// the vertices of the initializer and the syndromes must be in the ordering that the partition assumes, see
+    /// [`crate::partition::PartitionPlan`]; `thread_pool_size` is a shortcut for the same key in the config
     #[new]
+    #[pyo3(signature = (initializer, partition_info, primal_dual_config=None, thread_pool_size=None))]
     pub fn new_python(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
-        primal_dual_config: PyObject,
-    ) -> Self {
-        let primal_dual_config = pyobject_to_json(primal_dual_config);
-        Self::new(initializer, partition_info, primal_dual_config)
+        primal_dual_config: Option<PyObject>,
+        thread_pool_size: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut primal_dual_config = primal_dual_config.map(pyobject_to_json).unwrap_or(json!({}));
+        if let Some(thread_pool_size) = thread_pool_size {
+            primal_dual_config
+                .as_object_mut()
+                .ok_or_else(|| pyo3::exceptions::PyTypeError::new_err("`primal_dual_config` must be a dict"))?
+                .insert("thread_pool_size".to_string(), json!(thread_pool_size));
+        }
+        Ok(Self::try_new(initializer, partition_info, primal_dual_config)?)
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 impl SolverParallel {
+    /// the primal and dual modules each create their own thread pool, unless `thread_pool_size` is given in the config,
+    /// in which case a single thread pool of that size is shared by both modules
     pub fn new(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
-        mut primal_dual_config: serde_json::Value,
+        primal_dual_config: serde_json::Value,
+    ) -> Self {
+        Self::new_config_thread_pool(initializer, partition_info, primal_dual_config, None)
+    }
+
+    pub fn try_new(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        primal_dual_config: serde_json::Value,
+    ) -> Result<Self, FusionError> {
+        Self::try_new_config_thread_pool(initializer, partition_info, primal_dual_config, None)
+    }
+
+    /// run all the parallel tasks inside a caller-provided thread pool, to avoid oversubscription when the application
+    /// already manages its own rayon thread pool
+    pub fn new_with_thread_pool(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        thread_pool: Arc<rayon::ThreadPool>,
+    ) -> Self {
+        Self::new_config_thread_pool(initializer, partition_info, json!({}), Some(thread_pool))
+    }
+
+    /// panics if the decoding graph, the partition or the config is invalid, see [`Self::try_new_config_thread_pool`]
+    pub fn new_config_thread_pool(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        primal_dual_config: serde_json::Value,
+        thread_pool: Option<Arc<rayon::ThreadPool>>,
     ) -> Self {
-        let primal_dual_config = primal_dual_config.as_object_mut().expect("config must be JSON object");
+        or_panic(Self::try_new_config_thread_pool(
+            initializer,
+            partition_info,
+            primal_dual_config,
+            thread_pool,
+        ))
+    }
+
+    pub fn try_new_config_thread_pool(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        mut primal_dual_config: serde_json::Value,
+        mut thread_pool: Option<Arc<rayon::ThreadPool>>,
+    ) -> Result<Self, FusionError> {
+        check_initializer(initializer)?;
+        partition_info.check_edges(&initializer.weighted_edges)?;
+        trace_span!("solver_new", solver = "parallel", vertex_num = initializer.vertex_num);
+        let primal_dual_config = primal_dual_config
+            .as_object_mut()
+            .ok_or_else(|| FusionError::Config("config must be JSON object".to_string()))?;
+        let config_error = |error: serde_json::Error| FusionError::Config(error.to_string());
         let mut dual_config = DualModuleParallelConfig::default();
         let mut primal_config = PrimalModuleParallelConfig::default();
+        let mut thread_pool_size: Option<usize> = None;
         if let Some(value) = primal_dual_config.remove("dual") {
-            dual_config = serde_json::from_value(value).unwrap();
+            dual_config = serde_json::from_value(value).map_err(config_error)?;
         }
         if let Some(value) = primal_dual_config.remove("primal") {
-            primal_config = serde_json::from_value(value).unwrap();
+            primal_config = serde_json::from_value(value).map_err(config_error)?;
+        }
+        if let Some(value) = primal_dual_config.remove("thread_pool_size") {
+            thread_pool_size = serde_json::from_value(value).map_err(config_error)?;
         }
         if !primal_dual_config.is_empty() {
-            panic!(
+            return Err(FusionError::Config(format!(
                 "unknown primal_dual_config keys: {:?}",
                 primal_dual_config.keys().collect::<Vec<&String>>()
-            );
+            )));
         }
-        Self {
-            dual_module: DualModuleParallel::new_config(initializer, partition_info, dual_config),
-            primal_module: PrimalModuleParallel::new_config(initializer, partition_info, primal_config),
-            subgraph_builder: SubGraphBuilder::new(initializer),
+        if let Some(thread_pool_size) = thread_pool_size {
+            if thread_pool.is_some() {
+                return Err(FusionError::Config(
+                    "thread_pool_size cannot be set when a thread pool is provided".to_string(),
+                ));
+            }
+            thread_pool = Some(Arc::new(build_thread_pool(
+                thread_pool_size,
+                primal_config.pin_threads_to_cores,
+            )));
+        }
+        Ok(if let Some(thread_pool) = thread_pool {
+            Self {
+                dual_module: Arc::new(DualModuleParallel::new_config_with_thread_pool(
+                    initializer,
+                    partition_info,
+                    dual_config,
+                    Arc::clone(&thread_pool),
+                )),
+                primal_module: Arc::new(PrimalModuleParallel::new_config_with_thread_pool(
+                    initializer,
+                    partition_info,
+                    primal_config,
+                    thread_pool,
+                )),
+                subgraph_builder: SubGraphBuilder::new(initializer),
+                streaming: None,
+                initializer: Arc::new(initializer.clone()),
+                debug_dump: None,
+                boundary_selection: BoundarySelection::default(),
+            }
+        } else {
+            Self {
+                dual_module: Arc::new(DualModuleParallel::new_config(initializer, partition_info, dual_config)),
+                primal_module: Arc::new(PrimalModuleParallel::new_config(initializer, partition_info, primal_config)),
+                subgraph_builder: SubGraphBuilder::new(initializer),
+                streaming: None,
+                initializer: Arc::new(initializer.clone()),
+                debug_dump: None,
+                boundary_selection: BoundarySelection::default(),
+            }
+        })
+    }
+
+    fn modules_mut(&mut self) -> (&mut PrimalModuleParallel, &mut DualModuleParallel<DualModuleSerial>) {
+        assert!(!self.is_streaming(), "call `finish` before using the solver");
+        (
+            Arc::get_mut(&mut self.primal_module).expect("primal module is still used by streaming tasks"),
+            Arc::get_mut(&mut self.dual_module).expect("dual module is still used by streaming tasks"),
+        )
+    }
+
+    fn is_streaming(&self) -> bool {
+        match &self.streaming {
+            Some(streaming) => !streaming.0.lock().unwrap().is_finished(),
+            None => false,
+        }
+    }
+
+    /// start streaming decoding on a cleared solver: instead of providing the whole syndrome to `solve`,
+    /// push the syndrome of each unit with [`Self::push_layer`] as soon as each measurement round completes, mark the unit
+    /// with [`Self::complete_unit`] after its last round and then call [`Self::finish`]
+    pub fn begin_streaming(&mut self) {
+        assert!(!self.is_streaming(), "streaming decoding already started");
+        self.streaming = Some(self.primal_module.streaming_begin());
+    }
+
+    /// append the defect vertices of a measurement round owned by a unit; a unit can be pushed any number of times until
+    /// it's complete, and each defect vertex can only be pushed once
+    pub fn push_layer(&mut self, unit_index: usize, partial_syndrome: SyndromePattern) {
+        assert!(
+            partial_syndrome.erasures.is_empty() && partial_syndrome.dynamic_weights.is_empty(),
+            "streaming decoding doesn't support erasures or dynamic weights yet"
+        );
+        let streaming = self.streaming.as_ref().expect("call `begin_streaming` first");
+        self.primal_module.streaming_push(streaming, unit_index, partial_syndrome);
+    }
+
+    /// mark that all the rounds of a unit are pushed; the unit starts solving as soon as its children are solved
+    pub fn complete_unit(&mut self, unit_index: usize) {
+        let streaming = self.streaming.as_ref().expect("call `begin_streaming` first");
+        self.primal_module
+            .streaming_complete(&self.dual_module, streaming, unit_index);
+    }
+
+    /// the units that are not complete are completed with the rounds pushed so far, so the units that are never pushed have no
+    /// defect vertices; block until the whole syndrome is solved
+    pub fn finish(&mut self) -> PerfectMatching {
+        let streaming = self.streaming.as_ref().expect("call `begin_streaming` first");
+        self.primal_module.streaming_finish(&self.dual_module, streaming);
+        self.perfect_matching()
+    }
+
+    /// solve the syndrome guarded by the watchdog, which requires `hang_timeout` in the primal config; if the solver hangs,
+    /// the error contains a diagnostic dump, and the modules still held by the hung tasks are abandoned and rebuilt from the
+    /// initializer, so that the solver can be cleared and used again
+    pub fn try_solve(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), FusionError> {
+        let Some(hang_timeout) = self.primal_module.config.hang_timeout else {
+            return Err(FusionError::Config(
+                "`hang_timeout` must be set in the primal config to use the watchdog".to_string(),
+            ));
+        };
+        if self.is_streaming() {
+            return Err(FusionError::InvalidInput(
+                "call `finish` before solving another syndrome".to_string(),
+            ));
+        }
+        if !syndrome_pattern.erasures.is_empty() || !syndrome_pattern.dynamic_weights.is_empty() {
+            return Err(FusionError::InvalidInput(
+                "the watchdog doesn't support erasures or dynamic weights yet".to_string(),
+            ));
+        }
+        let result = self
+            .primal_module
+            .parallel_solve_watchdog(&self.dual_module, syndrome_pattern, hang_timeout);
+        if result.is_err() {
+            self.rebuild_modules();
         }
+        Ok(result?)
+    }
+
+    /// replace the modules held by hung tasks with new ones of the same configuration; the hung tasks keep running on the
+    /// threads of the old thread pool, so the new modules get a new thread pool of the same size
+    fn rebuild_modules(&mut self) {
+        let thread_pool = Arc::new(build_thread_pool(
+            self.primal_module.thread_pool.current_num_threads(),
+            self.primal_module.config.pin_threads_to_cores,
+        ));
+        let partition_info = Arc::clone(&self.primal_module.partition_info);
+        self.dual_module = Arc::new(DualModuleParallel::new_config_with_thread_pool(
+            &self.initializer,
+            &partition_info,
+            self.dual_module.config.clone(),
+            Arc::clone(&thread_pool),
+        ));
+        self.primal_module = Arc::new(PrimalModuleParallel::new_config_with_thread_pool(
+            &self.initializer,
+            &partition_info,
+            self.primal_module.config.clone(),
+            thread_pool,
+        ));
+        self.subgraph_builder.clear();
+        self.streaming = None;
+    }
+
+    /// time from the last pushed layer to the result of the last streaming decoding, in seconds
+    pub fn streaming_latency(&self) -> Option<f64> {
+        self.streaming
+            .as_ref()
+            .and_then(|streaming| streaming.0.lock().unwrap().last_push_to_result())
+    }
+
+    /// the same as [`SolverSerial::set_boundary_selection`]
+    pub fn set_boundary_selection(&mut self, selection: BoundarySelection) {
+        self.boundary_selection = selection;
+    }
+
+    /// the same as [`SolverSerial::debug_dump_on_panic`]; the `thread_pool_size` of the configuration is not recorded
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn debug_dump_on_panic(&mut self, dir: impl Into<std::path::PathBuf>) {
+        self.debug_dump = Some(Arc::new(DebugDumpSetting {
+            dir: dir.into(),
+            solver: "parallel",
+            partition_config: Some(self.dual_module.partition_info.config.clone()),
+            primal_dual_config: json!({ "dual": self.dual_module.config, "primal": self.primal_module.config }),
+        }));
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 impl PrimalDualSolver for SolverParallel {
     fn clear(&mut self) {
-        self.dual_module.clear();
-        self.primal_module.clear();
+        let (primal_module, dual_module) = self.modules_mut();
+        dual_module.clear();
+        primal_module.clear();
         self.subgraph_builder.clear();
+        self.streaming = None;
+    }
+    fn warm_up(&mut self) {
+        self.clear();
+        self.modules_mut().1.warm_up();
+        if let Some(syndrome_pattern) = warm_up_syndrome(&self.initializer) {
+            self.solve(&syndrome_pattern);
+            self.subgraph();
+        }
+        self.clear();
+    }
+    /// a hang of the solver guarded by the watchdog is reported as [`FusionError::Hang`], see
+    /// [`SolverParallel::try_solve`]
+    fn solve_checked(&mut self, syndrome_pattern: &SyndromePattern) -> Result<(), FusionError> {
+        check_syndrome_pattern(&self.initializer, syndrome_pattern, false)?;
+        if !syndrome_pattern.erasures.is_empty() {
+            return Err(FusionError::InvalidInput(
+                "erasures are not supported by the parallel solver, consider dynamic weights".to_string(),
+            ));
+        }
+        if self.is_streaming() {
+            return Err(FusionError::InvalidInput(
+                "call `finish` before solving another syndrome".to_string(),
+            ));
+        }
+        if self.primal_module.config.hang_timeout.is_some() {
+            self.try_solve(syndrome_pattern)?;
+        } else {
+            self.solve(syndrome_pattern);
+        }
+        Ok(())
     }
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, visualizer: Option<&mut Visualizer>) {
+        let _debug_dump_guard = DebugDumpGuard::new(&self.debug_dump, &self.initializer, syndrome_pattern);
         if !syndrome_pattern.erasures.is_empty() {
             self.subgraph_builder.load_erasures(&syndrome_pattern.erasures);
         }
-        self.primal_module
-            .parallel_solve_visualizer(syndrome_pattern, &mut self.dual_module, visualizer);
+        let visualizer = annotate_visualizer(visualizer, syndrome_pattern);
+        let (primal_module, dual_module) = self.modules_mut();
+        primal_module.parallel_solve_visualizer(syndrome_pattern, dual_module, visualizer);
     }
     fn perfect_matching_visualizer(&mut self, visualizer: Option<&mut Visualizer>) -> PerfectMatching {
         let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
-        let perfect_matching = self
-            .primal_module
-            .perfect_matching(&useless_interface_ptr, &mut self.dual_module);
+        let (primal_module, dual_module) = self.modules_mut();
+        let mut perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, dual_module);
+        perfect_matching.select_boundary(
+            &self.boundary_selection,
+            &self.initializer,
+            &mut self.subgraph_builder.complete_graph,
+        );
         if let Some(visualizer) = visualizer {
             let last_interface_ptr = &self.primal_module.units.last().unwrap().read_recursive().interface_ptr;
             visualizer
-                .snapshot_combined(
+                .snapshot_combined_category(
                     "perfect matching".to_string(),
-                    vec![last_interface_ptr, &self.dual_module, &perfect_matching],
+                    SnapshotCategory::Final,
+                    vec![last_interface_ptr, &*self.dual_module, &perfect_matching],
                 )
                 .unwrap();
         }
@@ -571,11 +2159,12 @@ impl PrimalDualSolver for SolverParallel {
         if let Some(visualizer) = visualizer {
             let last_interface_ptr = &self.primal_module.units.last().unwrap().read_recursive().interface_ptr;
             visualizer
-                .snapshot_combined(
+                .snapshot_combined_category(
                     "perfect matching and subgraph".to_string(),
+                    SnapshotCategory::Final,
                     vec![
                         last_interface_ptr,
-                        &self.dual_module,
+                        &*self.dual_module,
                         &perfect_matching,
                         &VisualizeSubgraph::new(&subgraph),
                     ],
@@ -590,55 +2179,186 @@ impl PrimalDualSolver for SolverParallel {
         sum_dual_variables
     }
     fn generate_profiler_report(&self) -> serde_json::Value {
-        json!({
+        let mut report = json!({
             "dual": self.dual_module.generate_profiler_report(),
             "primal": self.primal_module.generate_profiler_report(),
+        });
+        if let Some(streaming_latency) = self.streaming_latency() {
+            report["last_push_to_result"] = json!(streaming_latency);
+        }
+        report
+    }
+    fn memory_estimate(&self) -> Option<usize> {
+        Some(
+            size_of::<Self>()
+                + self.dual_module.memory_estimate()
+                + self.primal_module.memory_estimate()
+                + self.subgraph_builder.memory_estimate()
+                + self.initializer.memory_estimate(),
+        )
+    }
+    fn edge_num(&self) -> Option<usize> {
+        Some(self.subgraph_builder.complete_graph.weighted_edges.len())
+    }
+    fn parallel_statistics(&self) -> Option<ParallelSolverStatistics> {
+        Some(ParallelSolverStatistics {
+            fusions: self.primal_module.fusion_statistics(),
         })
     }
 }
 
+/// the statistics of a single shot decoded by [`SolverParallel`]
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParallelSolverStatistics {
+    /// one entry per fusion unit, see [`FusionStatistics`]
+    pub fusions: Vec<FusionStatistics>,
+}
+
+/// the statistics of a fusion unit averaged over the shots, see [`ParallelSolverStatistics::average`]
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AverageFusionStatistics {
+    pub unit_index: usize,
+    /// the number of shots in which this unit fused
+    pub shots: usize,
+    pub interface_nodes: f64,
+    pub tight_interface_edges: f64,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl ParallelSolverStatistics {
+    /// average the counts of each fusion unit over the shots, ordered by the unit index
+    pub fn average(statistics: &[Self]) -> Vec<AverageFusionStatistics> {
+        let mut sums: BTreeMap<usize, (usize, usize, usize)> = BTreeMap::new();
+        for fusion in statistics.iter().flat_map(|shot| shot.fusions.iter()) {
+            let (shots, interface_nodes, tight_interface_edges) = sums.entry(fusion.unit_index).or_default();
+            *shots += 1;
+            *interface_nodes += fusion.interface_nodes;
+            *tight_interface_edges += fusion.tight_interface_edges;
+        }
+        sums.into_iter()
+            .map(
+                |(unit_index, (shots, interface_nodes, tight_interface_edges))| AverageFusionStatistics {
+                    unit_index,
+                    shots,
+                    interface_nodes: interface_nodes as f64 / shots as f64,
+                    tight_interface_edges: tight_interface_edges as f64 / shots as f64,
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct SolverErrorPatternLogger {
-    file: BufWriter<File>,
+    /// `None` after [`SolverErrorPatternLogger::finalize`]
+    writer: Option<SyndromeFileWriter<SyndromeFileOutput>>,
+    /// recorded together with the next syndrome pattern, see [`crate::example_codes::ErrorPatternReader`]
+    error_edges: Option<Vec<EdgeIndex>>,
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg(feature = "python_binding")]
 bind_trait_primal_dual_solver! {SolverErrorPatternLogger}
 
+#[cfg(not(feature = "minimal"))]
 impl SolverErrorPatternLogger {
-    pub fn new(initializer: &SolverInitializer, positions: &Vec<VisualizePosition>, mut config: serde_json::Value) -> Self {
+    /// the file is compressed with gzip if the `filename` ends with `.gz`, and written in bincode if `bincode` is true;
+    /// with `error_edges`, every syndrome pattern must be preceded by [`PrimalDualSolver::set_error_edges`].
+    /// the file is flushed every `flush_shots` syndrome patterns (1000 by default, 0 for never) and/or every
+    /// `flush_seconds`, and with `append` the syndrome patterns are added to an existing file of the same decoding graph
+    pub fn new(initializer: &SolverInitializer, positions: &[VisualizePosition], config: serde_json::Value) -> Self {
+        or_panic(Self::try_new(initializer, positions, config))
+    }
+
+    pub fn try_new(
+        initializer: &SolverInitializer,
+        positions: &[VisualizePosition],
+        mut config: serde_json::Value,
+    ) -> Result<Self, FusionError> {
         let mut filename = "tmp/syndrome_patterns.txt".to_string();
-        let config = config.as_object_mut().expect("config must be JSON object");
+        let config = config
+            .as_object_mut()
+            .ok_or_else(|| FusionError::Config("config must be JSON object".to_string()))?;
+        let invalid = |key: &str, expected: &str| FusionError::Config(format!("{key}: expecting {expected}"));
         if let Some(value) = config.remove("filename") {
-            filename = value.as_str().expect("filename string").to_string();
+            filename = value.as_str().ok_or_else(|| invalid("filename", "string"))?.to_string();
         }
+        let bincode = if let Some(value) = config.remove("bincode") {
+            value.as_bool().ok_or_else(|| invalid("bincode", "bool"))?
+        } else {
+            false
+        };
+        let error_edges = if let Some(value) = config.remove("error_edges") {
+            value.as_bool().ok_or_else(|| invalid("error_edges", "bool"))?
+        } else {
+            false
+        };
+        let append = if let Some(value) = config.remove("append") {
+            value.as_bool().ok_or_else(|| invalid("append", "bool"))?
+        } else {
+            false
+        };
+        let flush_shots = match config.remove("flush_shots") {
+            Some(value) => Some(value.as_u64().ok_or_else(|| invalid("flush_shots", "usize"))? as usize),
+            None => None,
+        };
+        let flush_seconds = match config.remove("flush_seconds") {
+            Some(value) => Some(value.as_f64().ok_or_else(|| invalid("flush_seconds", "number"))?),
+            None => None,
+        };
         if !config.is_empty() {
-            panic!("unknown config keys: {:?}", config.keys().collect::<Vec<&String>>());
+            return Err(FusionError::Config(format!(
+                "unknown config keys: {:?}",
+                config.keys().collect::<Vec<&String>>()
+            )));
+        }
+        let mut writer = if append {
+            SyndromeFileWriter::append(&filename, initializer, positions, bincode, error_edges)
+        } else {
+            SyndromeFileWriter::create(&filename, initializer, positions, bincode, error_edges)
+        }
+        .map_err(|error| std::io::Error::new(error.kind(), format!("cannot write {filename}: {error}")))?;
+        if let Some(flush_shots) = flush_shots {
+            writer.flush_shots = flush_shots;
+        }
+        writer.flush_seconds = flush_seconds;
+        Ok(Self {
+            writer: Some(writer),
+            error_edges: None,
+        })
+    }
+
+    /// write the footer and close the file, which is otherwise done when the logger is dropped
+    pub fn finalize(&mut self) -> std::io::Result<()> {
+        match self.writer.take() {
+            Some(writer) => writer.finalize()?.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+impl Drop for SolverErrorPatternLogger {
+    fn drop(&mut self) {
+        if let Err(error) = self.finalize() {
+            eprintln!("[warning] failed to finalize the syndrome pattern file: {error}");
         }
-        let file = File::create(filename).unwrap();
-        let mut file = BufWriter::new(file);
-        file.write_all(b"Syndrome Pattern v1.0   <initializer> <positions> <syndrome_pattern>*\n")
-            .unwrap();
-        serde_json::to_writer(&mut file, &initializer).unwrap(); // large object write to file directly
-        file.write_all(b"\n").unwrap();
-        serde_json::to_writer(&mut file, &positions).unwrap();
-        file.write_all(b"\n").unwrap();
-        Self { file }
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 impl PrimalDualSolver for SolverErrorPatternLogger {
     fn clear(&mut self) {}
     fn solve_visualizer(&mut self, syndrome_pattern: &SyndromePattern, _visualizer: Option<&mut Visualizer>) {
-        self.file
-            .write_all(
-                serde_json::to_string(&serde_json::json!(syndrome_pattern))
-                    .unwrap()
-                    .as_bytes(),
-            )
+        self.writer
+            .as_mut()
+            .expect("the logger is finalized")
+            .write(syndrome_pattern, self.error_edges.take().as_deref())
             .unwrap();
-        self.file.write_all(b"\n").unwrap();
     }
     fn perfect_matching_visualizer(&mut self, _visualizer: Option<&mut Visualizer>) -> PerfectMatching {
         panic!("error pattern logger do not actually solve the problem, please use Verifier::None by `--verifier none`")
@@ -652,9 +2372,13 @@ impl PrimalDualSolver for SolverErrorPatternLogger {
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({})
     }
+    fn set_error_edges(&mut self, error_edges: &[EdgeIndex]) {
+        self.error_edges = Some(error_edges.to_vec());
+    }
 }
 
 /// an exact solver calling blossom V library for benchmarking comparison
+#[cfg(not(feature = "minimal"))]
 #[derive(Clone)]
 pub struct SolverBlossomV {
     initializer: SolverInitializer,
@@ -663,6 +2387,7 @@ pub struct SolverBlossomV {
     matched_pairs: Vec<(VertexIndex, VertexIndex)>,
 }
 
+#[cfg(not(feature = "minimal"))]
 impl SolverBlossomV {
     pub fn new(initializer: &SolverInitializer) -> Self {
         Self {
@@ -674,6 +2399,7 @@ impl SolverBlossomV {
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 impl PrimalDualSolver for SolverBlossomV {
     fn clear(&mut self) {
         self.matched_pairs.clear();
@@ -817,3 +2543,695 @@ pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<SolverErrorPatternLogger>()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example_codes::*;
+
+    #[test]
+    fn solver_memory_estimate_1() {
+        // cargo test solver_memory_estimate_1 -- --nocapture
+        let mut bytes_per_vertex = vec![];
+        for d in [5, 7, 9] {
+            let mut code = PhenomenologicalPlanarCode::new(d, d, 0.01, 500);
+            let initializer = code.get_initializer();
+            let mut solver = SolverSerial::new(&initializer);
+            for seed in 0..10 {
+                solver.solve(&code.generate_random_errors(seed));
+                solver.clear();
+            }
+            let estimate = solver.memory_estimate().unwrap();
+            println!("d = {d}: {estimate} bytes for {} vertices", initializer.vertex_num);
+            bytes_per_vertex.push(estimate as f64 / initializer.vertex_num as f64);
+        }
+        // the estimate grows roughly linearly with the number of vertices
+        for ratio in bytes_per_vertex.iter() {
+            assert!(
+                *ratio / bytes_per_vertex[0] > 0.8 && *ratio / bytes_per_vertex[0] < 1.25,
+                "{bytes_per_vertex:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn solver_syndrome_bitmap_1() {
+        // cargo test solver_syndrome_bitmap_1 -- --nocapture
+        let mut code = CircuitLevelPlanarCode::new(7, 7, 0.05, 500);
+        code.set_erasure_probability(0.02);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut bitmap_solver = SolverSerial::new(&initializer);
+        for seed in 0..50 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let bitmap = SyndromeBitmap::from_syndrome_pattern(
+                &syndrome_pattern,
+                initializer.vertex_num,
+                initializer.weighted_edges.len(),
+            );
+            assert_eq!(bitmap.to_syndrome_pattern(), syndrome_pattern);
+            solver.solve(&syndrome_pattern);
+            bitmap_solver.solve_bitmap(&bitmap);
+            assert_eq!(solver.sum_dual_variables(), bitmap_solver.sum_dual_variables());
+            assert_eq!(solver.subgraph(), bitmap_solver.subgraph());
+            solver.clear();
+            bitmap_solver.clear();
+        }
+    }
+
+    #[test]
+    fn solver_debug_dump_on_panic_1() {
+        // cargo test solver_debug_dump_on_panic_1 -- --nocapture
+        let dir = std::env::temp_dir().join(format!("fusion_blossom_debug_dump_{}", std::process::id()));
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut syndrome_pattern = code.generate_random_errors(1);
+        syndrome_pattern.erasures = vec![0, 3];
+        let mut solver = SolverSerial::new(&initializer);
+        solver.debug_dump_on_panic(&dir);
+        // no dump without a panic
+        solver.solve(&syndrome_pattern);
+        solver.clear();
+        assert!(!dir.exists());
+        // the unchecked `solve` panics deep inside on a defect vertex out of range
+        let mut invalid_syndrome_pattern = syndrome_pattern.clone();
+        invalid_syndrome_pattern.defect_vertices.push(initializer.vertex_num);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| solver.solve(&invalid_syndrome_pattern)));
+        assert!(result.is_err());
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        let dump = SolverDebugDump::load(files[0].to_str().unwrap()).unwrap();
+        assert_eq!(dump.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(dump.solver, "serial");
+        assert_eq!(dump.initializer, initializer);
+        assert_eq!(dump.syndrome_pattern, invalid_syndrome_pattern);
+        assert!(dump.partition_config.is_none());
+        // the replayed solver reproduces the panic
+        let mut replayed = dump.build_solver().unwrap();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| replayed.solve(&dump.syndrome_pattern)));
+        assert!(result.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn solver_odd_parity_policy_1() {
+        // cargo test solver_odd_parity_policy_1 -- --nocapture
+        let mut code = CodeCapacityToricCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut correction_defects = |solver: &mut SolverSerial| {
+            let subgraph = solver.subgraph();
+            solver.clear();
+            code.apply_error_edges(&subgraph).defect_vertices
+        };
+        // a valid syndrome is not touched
+        solver.solve(&SyndromePattern::new_vertices(vec![0, 1]));
+        assert!(solver.odd_parity_actions().is_empty());
+        assert_eq!(correction_defects(&mut solver), vec![0, 1]);
+        // by default an odd number of defects is an error
+        let odd_syndrome = SyndromePattern::new_vertices(vec![0, 1, 12]);
+        assert_eq!(
+            solver.try_solve(&odd_syndrome),
+            Err(OddParityError::OddDefects {
+                defect_vertices: vec![0, 1, 12]
+            })
+        );
+        assert!(solver
+            .try_solve(&odd_syndrome)
+            .unwrap_err()
+            .to_string()
+            .contains("[0, 1, 12]"));
+        // pair the leftover defect with a specified vertex
+        solver.set_odd_parity_policy(OddParityPolicy::MatchToSpecifiedVertex(24));
+        solver.try_solve(&odd_syndrome).unwrap();
+        assert_eq!(solver.odd_parity_actions(), &[OddParityAction::MatchedToVertex(24)]);
+        assert_eq!(correction_defects(&mut solver), vec![0, 1, 12, 24]);
+        // the specified vertex may be a defect itself
+        solver.try_solve(&SyndromePattern::new_vertices(vec![6, 12, 24])).unwrap();
+        assert_eq!(correction_defects(&mut solver), vec![6, 12]);
+        solver.set_odd_parity_policy(OddParityPolicy::MatchToSpecifiedVertex(25));
+        assert_eq!(
+            solver.try_solve(&odd_syndrome),
+            Err(OddParityError::VertexNotInComponent {
+                vertex_index: 25,
+                defect_vertices: vec![0, 1, 12]
+            })
+        );
+        // drop the defect that is the farthest from the others and flag the shot
+        solver.set_odd_parity_policy(OddParityPolicy::DropHeaviestDefect);
+        solver.solve(&odd_syndrome);
+        assert_eq!(solver.odd_parity_actions(), &[OddParityAction::DroppedDefect(12)]);
+        let report = solver.generate_profiler_report();
+        assert_eq!(report["odd_parity"]["actions"], json!([{ "DroppedDefect": 12 }]));
+        assert_eq!(report["odd_parity"]["shots"], json!(3));
+        assert_eq!(correction_defects(&mut solver), vec![0, 1]);
+        solver.solve(&SyndromePattern::new_vertices(vec![7]));
+        assert_eq!(solver.odd_parity_actions(), &[OddParityAction::DroppedDefect(7)]);
+        assert!(correction_defects(&mut solver).is_empty());
+        // the parity is not checked on decoding graphs with virtual vertices
+        let solver = SolverSerial::new(&CodeCapacityPlanarCode::new(5, 0.1, 500).get_initializer());
+        assert!(solver.generate_profiler_report().get("odd_parity").is_none());
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_max_total_growth_1() {
+        // cargo test solver_max_total_growth_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut optimal_solver = SolverSerial::new(&initializer);
+        let mut solver = SolverSerial::new(&initializer);
+        let mut suboptimal_shots = 0;
+        for max_total_growth in [0, 100, 400, 1000] {
+            solver.set_max_total_growth(Some(max_total_growth));
+            for seed in 0..20 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                optimal_solver.solve(&syndrome_pattern);
+                let optimal_weight = optimal_solver.sum_dual_variables();
+                optimal_solver.clear();
+                solver.solve(&syndrome_pattern);
+                let statistics = solver.growth_cap_statistics();
+                assert!(statistics.total_growth <= max_total_growth);
+                let subgraph = solver.subgraph();
+                solver.clear();
+                // every defect is still resolved by the correction
+                let mut defect_vertices = code.apply_error_edges(&subgraph).defect_vertices;
+                defect_vertices.sort_unstable();
+                let mut expected_defect_vertices = syndrome_pattern.defect_vertices.clone();
+                expected_defect_vertices.sort_unstable();
+                assert_eq!(defect_vertices, expected_defect_vertices);
+                let weight: Weight = subgraph
+                    .iter()
+                    .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                    .sum();
+                assert!(weight - optimal_weight <= statistics.weight_gap_bound);
+                if statistics.suboptimal {
+                    suboptimal_shots += 1;
+                    assert!(statistics.greedy_defects > 0);
+                } else {
+                    assert_eq!((weight, statistics.weight_gap_bound), (optimal_weight, 0));
+                }
+                if max_total_growth == 0 && !syndrome_pattern.defect_vertices.is_empty() {
+                    assert!(statistics.suboptimal);
+                    assert_eq!(statistics.greedy_defects, syndrome_pattern.defect_vertices.len());
+                }
+            }
+        }
+        assert!(suboptimal_shots > 20);
+        let report = solver.generate_profiler_report();
+        assert_eq!(report["growth_cap"]["max_total_growth"], json!(1000));
+        // unlimited by default
+        solver.set_max_total_growth(None);
+        solver.solve(&code.generate_random_errors(0));
+        assert!(!solver.growth_cap_statistics().suboptimal);
+        assert!(solver.generate_profiler_report().get("growth_cap").is_none());
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn solver_boundary_selection_1() {
+        // cargo test solver_boundary_selection_1 -- --nocapture
+        // the defect vertex 2 has three equally good virtual vertices: 0 and 4 two edges away and 5 a single edge away
+        let weighted_edges = vec![(0, 1, 2), (1, 2, 2), (2, 3, 2), (3, 4, 2), (2, 5, 4)];
+        let initializer = SolverInitializer::new(6, weighted_edges, vec![0, 4, 5]);
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![2]);
+        let mut positions: Vec<_> = (0..6).map(|i| VisualizePosition::new(i as f64, 0., 0.)).collect();
+        positions[0] = VisualizePosition::new(-1., 0., 0.);
+        positions[5] = VisualizePosition::new(2., 5., 0.);
+        let cases = [
+            (BoundarySelection::SmallestIndex, 0, vec![0, 1]),
+            (BoundarySelection::NearestByWeight, 5, vec![4]),
+            (BoundarySelection::NearestByPosition(positions), 4, vec![2, 3]),
+        ];
+        let mut solver = SolverSerial::new(&initializer);
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        for (selection, virtual_vertex, subgraph) in cases {
+            solver.set_boundary_selection(selection.clone());
+            parallel_solver.set_boundary_selection(selection);
+            // the selection is stable across repeated decoding
+            for _ in 0..3 {
+                solver.solve(&syndrome_pattern);
+                let perfect_matching = solver.perfect_matching();
+                assert_eq!(perfect_matching.virtual_matchings[0].1, virtual_vertex);
+                assert_eq!(solver.subgraph(), subgraph);
+                assert_eq!(solver.sum_dual_variables(), 4);
+                solver.clear();
+                parallel_solver.solve(&syndrome_pattern);
+                assert_eq!(parallel_solver.perfect_matching().virtual_matchings[0].1, virtual_vertex);
+                assert_eq!(parallel_solver.subgraph(), subgraph);
+                parallel_solver.clear();
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_minimize_support_1() {
+        // cargo test solver_minimize_support_1 -- --nocapture
+        // defects 0 and 3 are connected by a wide corridor 0 - 1 - 2 - 4 - 3 and a narrow one 0 - 5 - 3 of the same weight,
+        // and the defect 6 has two equally good virtual vertices: 8 two edges away and 9 a single edge away
+        let weighted_edges = vec![
+            (0, 1, 100),
+            (1, 2, 100),
+            (2, 4, 100),
+            (4, 3, 100),
+            (0, 5, 200),
+            (5, 3, 200),
+            (6, 7, 100),
+            (7, 8, 100),
+            (6, 9, 200),
+        ];
+        let initializer = SolverInitializer::new(10, weighted_edges, vec![8, 9]);
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![0, 3, 6]);
+        let subgraph_weight = |initializer: &SolverInitializer, subgraph: &[EdgeIndex]| -> Weight {
+            (subgraph.iter())
+                .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                .sum()
+        };
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&syndrome_pattern);
+        let subgraph = solver.subgraph();
+        let mut perfect_matching = solver.perfect_matching();
+        let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+        let minimized = perfect_matching.minimize_support(&initializer, &mut complete_graph);
+        assert_eq!(minimized, vec![4, 5, 8]);
+        assert_eq!(perfect_matching.virtual_matchings[0].1, 9);
+        let weight = subgraph_weight(&initializer, &minimized);
+        assert_eq!(weight, subgraph_weight(&initializer, &subgraph));
+        assert_eq!(weight, solver.sum_dual_variables());
+        // the solver happens to take the wide corridor
+        assert!(minimized.len() < subgraph.len());
+        // the tie-break is restored afterwards
+        assert!(!complete_graph.minimize_hops());
+        // random syndromes of a code keep the weight without adding edges
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+        for seed in 0..50 {
+            solver.solve(&code.generate_random_errors(seed));
+            let subgraph = solver.subgraph();
+            let minimized = solver.perfect_matching().minimize_support(&initializer, &mut complete_graph);
+            assert_eq!(
+                subgraph_weight(&initializer, &minimized),
+                subgraph_weight(&initializer, &subgraph)
+            );
+            assert!(minimized.len() <= subgraph.len());
+            solver.clear();
+        }
+    }
+
+    #[test]
+    fn solver_event_log_1() {
+        // cargo test solver_event_log_1 -- --nocapture
+        // defects 0, 1, 2 form a triangle that becomes a blossom, and defect 3 is matched to the virtual vertex 4 before
+        // the blossom reaches it through the heavy edge 3
+        let weighted_edges = vec![(0, 1, 100), (1, 2, 100), (0, 2, 100), (2, 3, 300), (3, 4, 100)];
+        let initializer = SolverInitializer::new(5, weighted_edges, vec![4]);
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![0, 1, 2, 3]);
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&syndrome_pattern);
+        assert!(solver.event_log().is_empty(), "the event log is opt-in");
+        solver.clear();
+        solver.enable_event_log();
+        solver.solve(&syndrome_pattern);
+        assert_eq!(solver.sum_dual_variables(), 400);
+        let events = vec![
+            SolverEvent::Conflict {
+                node_a: 1,
+                node_b: 2,
+                edge: Some(1),
+                growth: 50,
+            },
+            SolverEvent::Conflict {
+                node_a: 0,
+                node_b: 1,
+                edge: Some(0),
+                growth: 50,
+            },
+            SolverEvent::Conflict {
+                node_a: 0,
+                node_b: 2,
+                edge: Some(2),
+                growth: 50,
+            },
+            SolverEvent::BlossomCreated {
+                blossom: 4,
+                nodes: vec![0, 1, 2],
+                growth: 50,
+            },
+            SolverEvent::TouchingVirtual {
+                node: 3,
+                vertex: 4,
+                growth: 100,
+            },
+            SolverEvent::Conflict {
+                node_a: 3,
+                node_b: 4,
+                edge: Some(3),
+                growth: 200,
+            },
+        ];
+        assert_eq!(solver.event_log(), events);
+        solver.clear();
+        assert!(solver.event_log().is_empty());
+        solver.solve(&syndrome_pattern);
+        assert_eq!(solver.event_log(), events);
+        assert!(!solver.event_log_truncated());
+        solver.clear();
+        solver.set_event_log_capacity(2);
+        solver.solve(&syndrome_pattern);
+        assert_eq!(solver.event_log(), &events[..2]);
+        assert!(solver.event_log_truncated());
+    }
+
+    #[test]
+    fn solver_blocked_edges_1() {
+        // cargo test solver_blocked_edges_1 -- --nocapture
+        // two routes between vertices 0 and 2: 0-1-2 with weight 4 and 0-3-4-2 with weight 6
+        let weighted_edges = vec![(0, 1, 2), (1, 2, 2), (0, 3, 2), (3, 4, 2), (4, 2, 2)];
+        let initializer = SolverInitializer::new(5, weighted_edges, vec![]);
+        let mut solver = SolverSerial::new(&initializer);
+        let mut syndrome_pattern = SyndromePattern::new_vertices(vec![0, 2]);
+        solver.solve(&syndrome_pattern);
+        assert_eq!((solver.subgraph(), solver.sum_dual_variables()), (vec![0, 1], 4));
+        solver.clear();
+        // blocking the shortest route makes the decoder take the other one
+        syndrome_pattern.blocked_edges = vec![0];
+        solver.solve(&syndrome_pattern);
+        assert_eq!(
+            (solver.try_subgraph().unwrap(), solver.sum_dual_variables()),
+            (vec![2, 3, 4], 6)
+        );
+        solver.clear();
+        // the blocked edges are combined with erasures
+        syndrome_pattern.erasures = vec![3];
+        solver.solve(&syndrome_pattern);
+        assert_eq!((solver.subgraph(), solver.sum_dual_variables()), (vec![2, 3, 4], 4));
+        solver.clear();
+        // no valid correction exists when both routes are blocked, even if a blocked edge is also erased
+        syndrome_pattern.blocked_edges = vec![3, 0];
+        solver.solve(&syndrome_pattern);
+        let error = solver.try_subgraph().unwrap_err();
+        assert_eq!(error.used_blocked_edges, vec![0]);
+        assert!(error.to_string().contains("[0]"));
+        solver.clear();
+        // the blocked edges only last for a single decoding
+        solver.solve(&SyndromePattern::new_vertices(vec![0, 2]));
+        assert_eq!(solver.try_subgraph(), Ok(vec![0, 1]));
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn solver_try_new_errors() {
+        // cargo test solver_try_new_errors -- --nocapture
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 2), (1, 2, 2), (2, 3, 2)], vec![3]);
+        let invalid_input = |result: Result<(), FusionError>| matches!(result, Err(FusionError::InvalidInput(_)));
+        let mut invalid = initializer.clone();
+        invalid.weighted_edges.push((2, 4, 2));
+        assert!(invalid_input(SolverSerial::try_new(&invalid).map(|_| ())));
+        invalid.weighted_edges[3] = (1, 3, 3);
+        assert!(invalid_input(SolverSerial::try_new(&invalid).map(|_| ())), "odd weight");
+        invalid.weighted_edges[3] = (1, 3, Weight::MAX - 1);
+        assert!(matches!(SolverSerial::try_new(&invalid), Err(FusionError::Capacity(_))));
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        let config_error = |result: Result<(), FusionError>| matches!(result, Err(FusionError::Config(_)));
+        assert!(config_error(
+            SolverParallel::try_new(&initializer, &partition_info, json!({ "unknown": 1 })).map(|_| ())
+        ));
+        assert!(config_error(
+            SolverParallel::try_new(
+                &initializer,
+                &partition_info,
+                json!({ "primal": { "thread_pool_size": "1" } })
+            )
+            .map(|_| ())
+        ));
+        assert!(config_error(
+            SolverDualParallel::try_new(&initializer, &partition_info, json!({ "unknown": 1 })).map(|_| ())
+        ));
+        // the vertices are not in the ordering of the partition
+        let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+        partition_config.partitions = vec![VertexRange::new(0, 1), VertexRange::new(2, 4)];
+        partition_config.fusions = vec![(0, 1)];
+        let mut crossing = initializer.clone();
+        crossing.weighted_edges.push((0, 3, 2));
+        assert!(config_error(
+            SolverParallel::try_new(&crossing, &partition_config.info(), json!({})).map(|_| ())
+        ));
+        // invalid syndrome patterns are reported by `solve_checked` of every solver
+        let mut solvers: Vec<Box<dyn PrimalDualSolver>> = vec![
+            Box::new(SolverSerial::try_new(&initializer).unwrap()),
+            Box::new(SolverDualParallel::try_new(&initializer, &partition_info, json!({})).unwrap()),
+            Box::new(SolverParallel::try_new(&initializer, &partition_info, json!({})).unwrap()),
+        ];
+        for solver in solvers.iter_mut() {
+            let mut syndrome_pattern = SyndromePattern::new_vertices(vec![0]);
+            syndrome_pattern.erasures = vec![3];
+            assert!(invalid_input(solver.solve_checked(&syndrome_pattern)));
+            solver.clear();
+            syndrome_pattern.erasures.clear();
+            syndrome_pattern.dynamic_weights = vec![(0, 0)];
+            solver.solve_checked(&syndrome_pattern).unwrap();
+            solver.clear();
+        }
+        for solver in solvers.iter_mut() {
+            assert!(invalid_input(solver.solve_checked(&SyndromePattern::new_vertices(vec![4]))));
+            assert!(invalid_input(solver.solve_checked(&SyndromePattern::new_vertices(vec![3]))));
+            solver.clear();
+        }
+        // only the serial solver supports blocked edges
+        let blocked = SyndromePattern {
+            blocked_edges: vec![0],
+            ..SyndromePattern::new_vertices(vec![1])
+        };
+        solvers[0].solve_checked(&blocked).unwrap();
+        assert!(invalid_input(solvers[1].solve_checked(&blocked)));
+        assert!(invalid_input(solvers[2].solve_checked(&blocked)));
+        let erased = SyndromePattern::new(vec![1], vec![0]);
+        assert!(invalid_input(solvers[2].solve_checked(&erased)));
+        // the odd parity of a component without virtual vertex cannot be repaired by default
+        let isolated = SolverInitializer::new(2, vec![(0, 1, 2)], vec![]);
+        let mut solver = SolverSerial::try_new(&isolated).unwrap();
+        assert!(invalid_input(solver.solve_checked(&SyndromePattern::new_vertices(vec![0]))));
+        // the error pattern logger
+        assert!(config_error(
+            SolverErrorPatternLogger::try_new(&initializer, &[], json!({ "bincode": 1 })).map(|_| ())
+        ));
+        let filename = format!("{}/solver_try_new_errors/missing/folder.syndromes", visualize_data_folder());
+        assert!(matches!(
+            SolverErrorPatternLogger::try_new(&initializer, &[], json!({ "filename": filename })),
+            Err(FusionError::Io(_))
+        ));
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn solver_warm_up_1() {
+        // cargo test solver_warm_up_1 -- --nocapture
+        let mut code = CircuitLevelPlanarCode::new(11, 11, 0.005, 500);
+        let initializer = code.get_initializer();
+        let partition_info = PartitionConfig::new(initializer.vertex_num).info();
+        let syndrome_pattern = code.generate_random_errors(0);
+        type Builder = fn(&SolverInitializer, &PartitionInfo) -> Box<dyn PrimalDualSolver>;
+        let builders: [(&str, Builder); 3] = [
+            ("serial", |initializer, _| Box::new(SolverSerial::new(initializer))),
+            ("dual parallel", |initializer, partition_info| {
+                Box::new(SolverDualParallel::new(initializer, partition_info, json!({})))
+            }),
+            ("parallel", |initializer, partition_info| {
+                Box::new(SolverParallel::new(initializer, partition_info, json!({})))
+            }),
+        ];
+        for (name, builder) in builders {
+            // the first shot of a new solver, optionally warmed up, and a later shot of the same solver
+            let shots = |warm_up: bool| {
+                let mut solver = builder(&initializer, &partition_info);
+                if warm_up {
+                    solver.warm_up();
+                    assert_eq!(solver.sum_dual_variables(), 0, "the solver is clear after warming up");
+                    assert!(solver.subgraph().is_empty(), "the solver is clear after warming up");
+                }
+                let mut elapsed = [0.; 2];
+                let mut results = vec![];
+                for elapsed in elapsed.iter_mut() {
+                    solver.clear();
+                    let begin = std::time::Instant::now();
+                    solver.solve(&syndrome_pattern);
+                    *elapsed = begin.elapsed().as_secs_f64();
+                    results.push((solver.subgraph(), solver.sum_dual_variables()));
+                }
+                assert_eq!(results[0], results[1]);
+                (elapsed, results.pop().unwrap())
+            };
+            // the fastest of a few repetitions to tolerate noise
+            let (mut cold, mut warm, mut steady) = (f64::MAX, f64::MAX, f64::MAX);
+            for _ in 0..5 {
+                let ([cold_time, steady_time], cold_result) = shots(false);
+                let ([warm_time, _], warm_result) = shots(true);
+                assert_eq!(warm_result, cold_result);
+                cold = cold.min(cold_time);
+                warm = warm.min(warm_time);
+                steady = steady.min(steady_time);
+            }
+            println!("{name}: first shot {cold:.3e}s cold, {warm:.3e}s after warming up, {steady:.3e}s in steady state");
+            // a generous threshold, since the gap between cold and steady state depends on the machine
+            assert!(
+                warm < 2. * steady,
+                "first shot after warming up should be close to the steady state"
+            );
+        }
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn solver_parallel_statistics_1() {
+        // cargo test solver_parallel_statistics_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(11, 0.1, 500);
+        let initializer = code.get_initializer();
+        // 4 partitions of 2 rows each, separated by the interfaces of row 2 (unit 4), row 8 (unit 5) and row 5 (unit 6)
+        let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+        partition_config.partitions = vec![
+            VertexRange::new(0, 24),
+            VertexRange::new(36, 60),
+            VertexRange::new(72, 96),
+            VertexRange::new(108, 132),
+        ];
+        partition_config.fusions = vec![(0, 1), (2, 3), (4, 5)];
+        let partition_info = partition_config.info();
+        let mut solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        // pairs of defects in row 1 and row 3, each of them matched to the interface of row 2 before the fusion
+        code.set_defect_vertices(&[15, 19, 39, 43]).unwrap();
+        let syndrome_pattern = code.get_syndrome();
+        let mut statistics = vec![];
+        for _ in 0..3 {
+            solver.solve(&syndrome_pattern);
+            assert_eq!(solver.sum_dual_variables(), 4 * 500 * 2);
+            solver.clear();
+            statistics.push(solver.parallel_statistics().unwrap());
+        }
+        println!("{:?}", statistics[0]);
+        let fusions = &statistics[0].fusions;
+        assert_eq!(
+            fusions.iter().map(|fusion| fusion.unit_index).collect::<Vec<_>>(),
+            vec![4, 5, 6]
+        );
+        assert_eq!(fusions[0].interface_nodes, 4);
+        assert!(fusions[0].tight_interface_edges >= 4);
+        for fusion in fusions[1..].iter() {
+            assert_eq!(fusion.interface_nodes, 0, "{fusion:?}");
+            assert_eq!(fusion.tight_interface_edges, 0, "{fusion:?}");
+        }
+        let average = ParallelSolverStatistics::average(&statistics);
+        assert_eq!(average.len(), 3);
+        for (average, fusion) in average.iter().zip(fusions.iter()) {
+            assert_eq!((average.unit_index, average.shots), (fusion.unit_index, 3));
+            assert_eq!(average.interface_nodes, fusion.interface_nodes as f64);
+            assert_eq!(average.tight_interface_edges, fusion.tight_interface_edges as f64);
+        }
+        assert!(SolverSerial::new(&initializer).parallel_statistics().is_none());
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn solver_batch_progress_1() {
+        // cargo test solver_batch_progress_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let syndrome_patterns: Vec<_> = (0..8).map(|seed| code.generate_random_errors(seed)).collect();
+        let events = Arc::new(std::sync::Mutex::new(vec![]));
+        let mut batch = SolverBatch::new(Box::new(SolverSerial::new(&initializer)));
+        let recorded_events = events.clone();
+        batch.set_progress_callback(Box::new(move |event| recorded_events.lock().unwrap().push(event)));
+        let results = batch.solve(&syndrome_patterns);
+        let mut solver = SolverSerial::new(&initializer);
+        for (syndrome_pattern, (subgraph, weight)) in syndrome_patterns.iter().zip(results.iter()) {
+            solver.solve(syndrome_pattern);
+            assert_eq!((&solver.subgraph(), solver.sum_dual_variables()), (subgraph, *weight));
+            solver.clear();
+        }
+        let take_events = || std::mem::take(&mut *events.lock().unwrap());
+        let check_events = |events: Vec<ProgressEvent>, total: usize| {
+            assert_eq!(events.len(), total, "one event per shot");
+            for (index, event) in events.iter().enumerate() {
+                assert_eq!((event.completed, event.total), (index + 1, total));
+            }
+        };
+        check_events(take_events(), 8);
+        // the callback is kept for the next batch
+        batch.solve(&syndrome_patterns[..3]);
+        check_events(take_events(), 3);
+        // the bit-packed detection events of stim, one byte per 8 vertices
+        let dir = std::env::temp_dir();
+        let in_file = dir.join(format!("fusion_blossom_batch_{}.b8", std::process::id()));
+        let out_file = dir.join(format!("fusion_blossom_batch_{}.out", std::process::id()));
+        let num_dets = initializer.vertex_num as usize;
+        let mut packed = vec![];
+        for syndrome_pattern in syndrome_patterns.iter() {
+            let mut bytes = vec![0u8; num_dets.div_ceil(8)];
+            for &vertex_index in syndrome_pattern.defect_vertices.iter() {
+                bytes[vertex_index as usize / 8] |= 1 << (vertex_index as usize % 8);
+            }
+            packed.extend(bytes);
+        }
+        std::fs::write(&in_file, packed).unwrap();
+        let edge_masks = vec![1; initializer.weighted_edges.len()];
+        batch.stim_integration_predict_bit_packed_data(
+            in_file.to_str().unwrap(),
+            out_file.to_str().unwrap(),
+            &edge_masks,
+            syndrome_patterns.len(),
+            num_dets,
+            1,
+        );
+        check_events(take_events(), 8);
+        // every prediction is the parity of the number of edges in the subgraph
+        let predictions = std::fs::read(&out_file).unwrap();
+        let expected: Vec<u8> = results.iter().map(|(subgraph, _)| (subgraph.len() % 2) as u8).collect();
+        assert_eq!(predictions, expected);
+        std::fs::remove_file(&in_file).unwrap();
+        std::fs::remove_file(&out_file).unwrap();
+    }
+
+    /// a hang is returned by the fallible solve as a structured error with the diagnostic dump
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn solver_parallel_watchdog_1() {
+        // cargo test solver_parallel_watchdog_1 -- --nocapture
+        use crate::example_partition::*;
+        let (d, noisy_measurements) = (5, 7);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, 4);
+        let partition_info = partition.build_apply(&mut code).info();
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(0);
+        let config = json!({ "primal": { "hang_timeout": 0.2 } });
+        let mut solver = SolverParallel::new(&initializer, &partition_info, config.clone());
+        solver.solve_checked(&syndrome_pattern).unwrap();
+        let mut solver = SolverParallel::new(&initializer, &partition_info, config);
+        solver.primal_module.units[1].write().test_stall = Some(std::time::Duration::from_secs(2));
+        match solver.solve_checked(&syndrome_pattern) {
+            Err(FusionError::Hang(solver_hang)) => {
+                assert_eq!(solver_hang.unit_index, 1);
+                assert_eq!(solver_hang.dump["units"][1]["phase"], "running");
+            }
+            result => panic!("expecting a hang, got {result:?}"),
+        }
+        // the hung modules are replaced, so the solver is usable again
+        solver.clear();
+        solver.solve_checked(&syndrome_pattern).unwrap();
+        let mut reference_solver = SolverSerial::new(&initializer);
+        reference_solver.solve(&syndrome_pattern);
+        assert_eq!(solver.sum_dual_variables(), reference_solver.sum_dual_variables());
+        // the misuses of the watchdog are errors
+        let mut erasure_syndrome = syndrome_pattern.clone();
+        erasure_syndrome.erasures.push(0);
+        assert!(matches!(
+            solver.try_solve(&erasure_syndrome),
+            Err(FusionError::InvalidInput(_))
+        ));
+        let mut solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        assert!(matches!(solver.try_solve(&syndrome_pattern), Err(FusionError::Config(_))));
+    }
+}
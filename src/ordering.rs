@@ -0,0 +1,322 @@
+//! Vertex Ordering
+//!
+//! The solver accesses vertices by index, so vertices close to each other in the decoding graph should also be close in
+//! memory for better cache performance. This module generates such an ordering from the positions of the vertices,
+//! which can then be applied by [`crate::example_codes::ExampleCode::reorder_vertices`] or [`SolverInitializer::permuted`],
+//! and a [`VertexPermutation`] to map externally generated syndromes into the new numbering.
+//!
+
+use super::util::*;
+use super::visualize::*;
+#[cfg(feature = "python_binding")]
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderingStrategy {
+    /// sort by time, then by row (i), then by column (j)
+    TimeMajorRowMajor,
+    /// sort along a 3D Hilbert curve over (t, i, j), so that neighbors in all directions stay close
+    HilbertCurve,
+    /// breadth-first search on the decoding graph starting from a vertex; disconnected vertices are visited afterwards
+    BfsFromVertex(VertexIndex),
+}
+
+/// the mapping between the old and new vertex indices, where `sequential_vertices[new_index] = old_index`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct VertexPermutation {
+    /// the old indices in the new order, used by [`crate::example_codes::ExampleCode::reorder_vertices`]
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub sequential_vertices: Vec<VertexIndex>,
+    /// `old_to_new[old_index] = new_index`
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub old_to_new: Vec<VertexIndex>,
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl VertexPermutation {
+    #[new]
+    fn py_new(sequential_vertices: Vec<VertexIndex>) -> Self {
+        Self::new(sequential_vertices)
+    }
+    #[pyo3(name = "to_new")]
+    fn py_to_new(&self, old_index: VertexIndex) -> VertexIndex {
+        self.to_new(old_index)
+    }
+    #[pyo3(name = "to_old")]
+    fn py_to_old(&self, new_index: VertexIndex) -> VertexIndex {
+        self.to_old(new_index)
+    }
+    /// accepts the same syndrome formats as the solvers, see [`py_syndrome_pattern`]
+    #[pyo3(name = "map_syndrome")]
+    fn py_map_syndrome(&self, syndrome_pattern: &PyAny) -> PyResult<SyndromePattern> {
+        Ok(self.map_syndrome(&py_syndrome_pattern(syndrome_pattern)?))
+    }
+    /// the initializer in the new numbering, see [`SolverInitializer::permuted`]
+    fn permuted_initializer(&self, initializer: &SolverInitializer) -> SolverInitializer {
+        initializer.permuted(&self.sequential_vertices)
+    }
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+impl VertexPermutation {
+    /// panics if `sequential_vertices` is not a permutation, see [`VertexPermutation::try_new`]
+    pub fn new(sequential_vertices: Vec<VertexIndex>) -> Self {
+        Self::try_new(sequential_vertices).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// fails if `sequential_vertices` is not a permutation of `0..sequential_vertices.len()`, naming the first vertex
+    /// that is out of range or duplicated
+    #[allow(clippy::unnecessary_cast)]
+    pub fn try_new(sequential_vertices: Vec<VertexIndex>) -> Result<Self, String> {
+        let vertex_num = sequential_vertices.len();
+        let mut old_to_new: Vec<Option<VertexIndex>> = vec![None; vertex_num];
+        for (new_index, &old_index) in sequential_vertices.iter().enumerate() {
+            let Some(entry) = old_to_new.get_mut(old_index as usize) else {
+                return Err(format!(
+                    "vertex {} at position {} is out of range: valid vertices are [0, {})",
+                    old_index, new_index, vertex_num
+                ));
+            };
+            if let Some(previous_index) = entry {
+                return Err(format!(
+                    "duplicate vertex {} at position {} and {} is not a permutation",
+                    old_index, previous_index, new_index
+                ));
+            }
+            *entry = Some(new_index as VertexIndex);
+        }
+        // all `vertex_num` entries are set by the `vertex_num` distinct indices in range
+        let old_to_new = old_to_new.into_iter().map(Option::unwrap).collect();
+        Ok(Self {
+            sequential_vertices,
+            old_to_new,
+        })
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_new(&self, old_index: VertexIndex) -> VertexIndex {
+        self.old_to_new[old_index as usize]
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_old(&self, new_index: VertexIndex) -> VertexIndex {
+        self.sequential_vertices[new_index as usize]
+    }
+
+    /// map a syndrome of the old numbering into the new numbering; edge indices are not changed by reordering vertices;
+    /// the defect vertices are sorted because the parallel solver requires ordered defect vertices
+    pub fn map_syndrome(&self, syndrome_pattern: &SyndromePattern) -> SyndromePattern {
+        let mut syndrome_pattern = syndrome_pattern.clone();
+        for defect_vertex in syndrome_pattern.defect_vertices.iter_mut() {
+            *defect_vertex = self.to_new(*defect_vertex);
+        }
+        syndrome_pattern.defect_vertices.sort_unstable();
+        syndrome_pattern
+    }
+}
+
+/// generate a cache-friendly order of the vertices; `weighted_edges` is only used by [`OrderingStrategy::BfsFromVertex`]
+#[allow(clippy::unnecessary_cast)]
+pub fn cache_friendly_order(
+    positions: &[VisualizePosition],
+    weighted_edges: &[(VertexIndex, VertexIndex, Weight)],
+    strategy: &OrderingStrategy,
+) -> VertexPermutation {
+    let vertex_num = positions.len();
+    let mut sequential_vertices: Vec<VertexIndex> = (0..vertex_num as VertexIndex).collect();
+    match strategy {
+        OrderingStrategy::TimeMajorRowMajor => {
+            sequential_vertices.sort_by(|&a, &b| {
+                let (a, b) = (&positions[a as usize], &positions[b as usize]);
+                (a.t, a.i, a.j).partial_cmp(&(b.t, b.i, b.j)).unwrap()
+            });
+        }
+        OrderingStrategy::HilbertCurve => {
+            // positions are usually on a grid, so the rank of each coordinate is used as the integer coordinate
+            let ranks: Vec<Vec<u64>> = [
+                positions.iter().map(|position| position.t).collect::<Vec<_>>(),
+                positions.iter().map(|position| position.i).collect(),
+                positions.iter().map(|position| position.j).collect(),
+            ]
+            .iter()
+            .map(|values| coordinate_ranks(values))
+            .collect();
+            let max_rank = ranks.iter().flatten().cloned().max().unwrap_or(0);
+            let bits = std::cmp::max(1, 64 - max_rank.leading_zeros());
+            assert!(bits <= 21, "too many distinct coordinates for a 64-bit Hilbert index");
+            let hilbert_indices: Vec<u64> = (0..vertex_num)
+                .map(|vertex_index| {
+                    hilbert_index_3d([ranks[0][vertex_index], ranks[1][vertex_index], ranks[2][vertex_index]], bits)
+                })
+                .collect();
+            sequential_vertices.sort_by_key(|&vertex_index| hilbert_indices[vertex_index as usize]);
+        }
+        OrderingStrategy::BfsFromVertex(start_vertex) => {
+            assert!((*start_vertex as usize) < vertex_num, "invalid start vertex {}", start_vertex);
+            let mut neighbors: Vec<Vec<VertexIndex>> = vec![vec![]; vertex_num];
+            for &(vertex_1, vertex_2, _) in weighted_edges.iter() {
+                neighbors[vertex_1 as usize].push(vertex_2);
+                neighbors[vertex_2 as usize].push(vertex_1);
+            }
+            for vertex_neighbors in neighbors.iter_mut() {
+                vertex_neighbors.sort_unstable();
+            }
+            let mut visited = vec![false; vertex_num];
+            sequential_vertices.clear();
+            let roots = std::iter::once(*start_vertex).chain(0..vertex_num as VertexIndex);
+            for root in roots {
+                if visited[root as usize] {
+                    continue;
+                }
+                visited[root as usize] = true;
+                let mut queue = VecDeque::from([root]);
+                while let Some(vertex_index) = queue.pop_front() {
+                    sequential_vertices.push(vertex_index);
+                    for &neighbor in neighbors[vertex_index as usize].iter() {
+                        if !visited[neighbor as usize] {
+                            visited[neighbor as usize] = true;
+                            queue.push_back(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    VertexPermutation::new(sequential_vertices)
+}
+
+/// the rank of each value among all distinct values
+fn coordinate_ranks(values: &[f64]) -> Vec<u64> {
+    let mut distinct = values.to_vec();
+    distinct.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    distinct.dedup();
+    values
+        .iter()
+        .map(|value| distinct.partition_point(|x| x < value) as u64)
+        .collect()
+}
+
+/// the index along a 3D Hilbert curve of `bits` bits per axis, using Skilling's algorithm
+fn hilbert_index_3d(mut x: [u64; 3], bits: u32) -> u64 {
+    let m = 1u64 << (bits - 1);
+    // inverse undo excess work
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..3 {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+    // gray encode
+    for i in 1..3 {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    q = m;
+    while q > 1 {
+        if x[2] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for value in x.iter_mut() {
+        *value ^= t;
+    }
+    // interleave the transposed bits
+    let mut index = 0;
+    for bit in (0..bits).rev() {
+        for value in x.iter() {
+            index = (index << 1) | ((value >> bit) & 1);
+        }
+    }
+    index
+}
+
+#[cfg(feature = "python_binding")]
+#[pyfunction]
+pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<VertexPermutation>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::super::example_codes::*;
+    use super::super::mwpm_solver::*;
+    use super::*;
+
+    /// every strategy must give a bijection, and decoding with the permuted initializer gives the same weight
+    #[test]
+    fn ordering_cache_friendly_order_1() {
+        // cargo test ordering_cache_friendly_order_1 -- --nocapture
+        let mut code = CircuitLevelPlanarCode::new(5, 5, 0.005, 500);
+        let positions = code.get_positions();
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let strategies = [
+            OrderingStrategy::TimeMajorRowMajor,
+            OrderingStrategy::HilbertCurve,
+            OrderingStrategy::BfsFromVertex(initializer.vertex_num as VertexIndex / 2),
+        ];
+        for strategy in strategies.iter() {
+            let permutation = cache_friendly_order(&positions, &initializer.weighted_edges, strategy);
+            let mut sorted_vertices = permutation.sequential_vertices.clone();
+            sorted_vertices.sort_unstable();
+            assert_eq!(
+                sorted_vertices,
+                (0..initializer.vertex_num as VertexIndex).collect::<Vec<_>>()
+            );
+            for vertex_index in 0..initializer.vertex_num as VertexIndex {
+                assert_eq!(permutation.to_old(permutation.to_new(vertex_index)), vertex_index);
+            }
+            let permuted_initializer = initializer.permuted(&permutation.sequential_vertices);
+            let mut permuted_solver = SolverSerial::new(&permuted_initializer);
+            for seed in 0..20 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                solver.solve(&syndrome_pattern);
+                permuted_solver.solve(&permutation.map_syndrome(&syndrome_pattern));
+                assert_eq!(solver.sum_dual_variables(), permuted_solver.sum_dual_variables());
+                solver.clear();
+                permuted_solver.clear();
+            }
+        }
+    }
+
+    /// the Hilbert curve visits every cell of a cube exactly once, moving to a neighboring cell at each step
+    #[test]
+    fn ordering_hilbert_index_1() {
+        // cargo test ordering_hilbert_index_1 -- --nocapture
+        let bits = 3;
+        let side = 1u64 << bits;
+        let mut cells: Vec<(u64, [u64; 3])> = vec![];
+        for a in 0..side {
+            for b in 0..side {
+                for c in 0..side {
+                    cells.push((hilbert_index_3d([a, b, c], bits), [a, b, c]));
+                }
+            }
+        }
+        cells.sort_unstable();
+        for (expected_index, (index, _)) in cells.iter().enumerate() {
+            assert_eq!(*index, expected_index as u64);
+        }
+        for pair in cells.windows(2) {
+            let distance: u64 = (0..3).map(|i| pair[0].1[i].abs_diff(pair[1].1[i])).sum();
+            assert_eq!(distance, 1);
+        }
+    }
+}
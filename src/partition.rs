@@ -0,0 +1,397 @@
+//! Partition Planning
+//!
+//! A partition requires the vertices of each unit to be contiguous and the interfaces to sit between them.
+//! Given which unit each vertex belongs to, this module generates the vertex ordering together with a [`PartitionConfig`]
+//! that agrees with it, so that the user doesn't need to write them separately.
+//!
+
+use super::example_codes::*;
+use super::ordering::*;
+use super::util::*;
+use super::visualize::*;
+#[cfg(feature = "python_binding")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python_binding")]
+use pyo3::prelude::*;
+
+/// the vertex ordering and the partition config that agree with each other
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct PartitionPlan {
+    /// maps externally generated syndromes into the new numbering
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub permutation: VertexPermutation,
+    /// the partition config in the new numbering, with interfaces inferred from the edges
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub partition_config: PartitionConfig,
+}
+
+impl PartitionPlan {
+    /// the old indices in the new order, see [`ExampleCode::reorder_vertices`]
+    pub fn sequential_vertices(&self) -> &Vec<VertexIndex> {
+        &self.permutation.sequential_vertices
+    }
+
+    /// reorder the vertices of the code and return the partition config
+    pub fn apply(&self, code: &mut dyn ExampleCode) -> PartitionConfig {
+        code.reorder_vertices(self.sequential_vertices()).unwrap();
+        self.partition_config.clone()
+    }
+}
+
+/// plan a partition given the leaf unit of each vertex (`units[vertex_index]`), where unit `i` and `i + 1` should be neighbors;
+/// vertices are sorted by unit and then in time-major order, so that the first layer of each unit becomes the interface
+/// between it and the previous unit
+#[allow(clippy::unnecessary_cast)]
+pub fn plan(
+    positions: &[VisualizePosition],
+    vertex_num: VertexNum,
+    weighted_edges: &[(VertexIndex, VertexIndex, Weight)],
+    units: &[usize],
+    shape: &FusionTreeShape,
+) -> Result<PartitionPlan, PartitionError> {
+    assert_eq!(
+        positions.len(),
+        vertex_num as usize,
+        "amount of positions must be same as vertices"
+    );
+    assert_eq!(units.len(), vertex_num as usize, "amount of units must be same as vertices");
+    let unit_num = units.iter().max().expect("at least one vertex required") + 1;
+    let time_major_order = cache_friendly_order(positions, weighted_edges, &OrderingStrategy::TimeMajorRowMajor);
+    let mut sequential_vertices = time_major_order.sequential_vertices;
+    sequential_vertices.sort_by_key(|&vertex_index| units[vertex_index as usize]); // stable sort keeps the time-major order
+    let permutation = VertexPermutation::new(sequential_vertices);
+    let mut partition_config = PartitionConfig::new(vertex_num);
+    partition_config.partitions.clear();
+    let mut unit_lengths = vec![0; unit_num];
+    for &unit_index in units.iter() {
+        unit_lengths[unit_index] += 1;
+    }
+    let mut start = 0;
+    for (unit_index, &length) in unit_lengths.iter().enumerate() {
+        if length == 0 {
+            return Err(PartitionError::EmptyPartition {
+                partition_index: unit_index,
+            });
+        }
+        partition_config
+            .partitions
+            .push(VertexRange::new(start as VertexIndex, (start + length) as VertexIndex));
+        start += length;
+    }
+    partition_config.fusions = shape.build_fusions(unit_num);
+    let permuted_edges: Vec<_> = weighted_edges
+        .iter()
+        .map(|&(vertex_1, vertex_2, weight)| (permutation.to_new(vertex_1), permutation.to_new(vertex_2), weight))
+        .collect();
+    partition_config.infer_interfaces(&permuted_edges)?;
+    Ok(PartitionPlan {
+        permutation,
+        partition_config,
+    })
+}
+
+/// plan `partition_num` partitions along the time axis, where the measurement rounds are distributed evenly among them
+pub fn plan_time_axis(
+    positions: &[VisualizePosition],
+    vertex_num: VertexNum,
+    weighted_edges: &[(VertexIndex, VertexIndex, Weight)],
+    partition_num: usize,
+    shape: &FusionTreeShape,
+) -> Result<PartitionPlan, PartitionError> {
+    assert!(partition_num >= 1, "at least one partition must exist");
+    let mut times: Vec<f64> = positions.iter().map(|position| position.t).collect();
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    times.dedup();
+    let units: Vec<usize> = positions
+        .iter()
+        .map(|position| times.partition_point(|&t| t < position.t) * partition_num / times.len())
+        .collect();
+    check_no_empty_unit(&units, partition_num)?;
+    plan(positions, vertex_num, weighted_edges, &units, shape)
+}
+
+/// plan `partition_num` partitions of balanced sizes by recursively bisecting the vertices along the axis of the largest
+/// extent, preferring the time axis; the cut is moved to the nearest change of coordinate so that a layer of vertices
+/// is never split. this only succeeds when the cuts are parallel, e.g. a code with many more rounds than its distance
+pub fn plan_bisection(
+    positions: &[VisualizePosition],
+    vertex_num: VertexNum,
+    weighted_edges: &[(VertexIndex, VertexIndex, Weight)],
+    partition_num: usize,
+    shape: &FusionTreeShape,
+) -> Result<PartitionPlan, PartitionError> {
+    assert!(partition_num >= 1, "at least one partition must exist");
+    let mut units = vec![0; positions.len()];
+    bisect(positions, (0..positions.len()).collect(), 0, partition_num, &mut units);
+    check_no_empty_unit(&units, partition_num)?;
+    plan(positions, vertex_num, weighted_edges, &units, shape)
+}
+
+/// a planner may leave a unit without any vertex when there are too many partitions
+fn check_no_empty_unit(units: &[usize], partition_num: usize) -> Result<(), PartitionError> {
+    let mut is_used = vec![false; partition_num];
+    for &unit_index in units.iter() {
+        is_used[unit_index] = true;
+    }
+    match is_used.iter().position(|&used| !used) {
+        Some(partition_index) => Err(PartitionError::EmptyPartition { partition_index }),
+        None => Ok(()),
+    }
+}
+
+fn bisect(
+    positions: &[VisualizePosition],
+    mut vertices: Vec<usize>,
+    first_unit: usize,
+    unit_num: usize,
+    units: &mut [usize],
+) {
+    if unit_num == 1 || vertices.len() <= 1 {
+        for &vertex_index in vertices.iter() {
+            units[vertex_index] = first_unit;
+        }
+        return;
+    }
+    let coordinates: [fn(&VisualizePosition) -> f64; 3] =
+        [|position| position.t, |position| position.i, |position| position.j];
+    let extent = |coordinate: &fn(&VisualizePosition) -> f64| {
+        let values = vertices.iter().map(|&vertex_index| coordinate(&positions[vertex_index]));
+        values.clone().fold(f64::NEG_INFINITY, f64::max) - values.fold(f64::INFINITY, f64::min)
+    };
+    let mut coordinate = coordinates[0];
+    for candidate in coordinates[1..].iter() {
+        if extent(candidate) > extent(&coordinate) {
+            coordinate = *candidate;
+        }
+    }
+    vertices.sort_by(|&a, &b| coordinate(&positions[a]).partial_cmp(&coordinate(&positions[b])).unwrap());
+    let left_unit_num = unit_num / 2;
+    let target = vertices.len() * left_unit_num / unit_num;
+    let is_cut = |index: usize| coordinate(&positions[vertices[index - 1]]) != coordinate(&positions[vertices[index]]);
+    let cut = (1..vertices.len())
+        .filter(|&index| is_cut(index))
+        .min_by_key(|&index| index.abs_diff(target))
+        .unwrap_or(target);
+    let right_vertices = vertices.split_off(cut);
+    bisect(positions, vertices, first_unit, left_unit_num, units);
+    bisect(
+        positions,
+        right_vertices,
+        first_unit + left_unit_num,
+        unit_num - left_unit_num,
+        units,
+    );
+}
+
+/// the fusion tree shape given by its name, `"balanced_binary"` or `"linear_chain"`, or by the list of fusions
+#[cfg(feature = "python_binding")]
+fn py_fusion_tree_shape(fusion_tree: Option<&PyAny>, partition_num: usize) -> PyResult<FusionTreeShape> {
+    let Some(fusion_tree) = fusion_tree else {
+        return Ok(FusionTreeShape::BalancedBinary);
+    };
+    if let Ok(name) = fusion_tree.extract::<&str>() {
+        return match name {
+            "balanced_binary" => Ok(FusionTreeShape::BalancedBinary),
+            "linear_chain" => Ok(FusionTreeShape::LinearChain),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown fusion tree `{name}`, expecting \"balanced_binary\", \"linear_chain\" or a list of fusions"
+            ))),
+        };
+    }
+    let fusions: Vec<(usize, usize)> = fusion_tree.extract()?;
+    if fusions.len() + 1 != partition_num {
+        return Err(PyValueError::new_err(format!(
+            "a fusion tree of {} partitions must have {} fusions",
+            partition_num,
+            partition_num - 1
+        )));
+    }
+    Ok(FusionTreeShape::Custom(fusions))
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl PartitionPlan {
+    /// plan along the time axis, see [`plan_time_axis`]
+    #[staticmethod]
+    #[pyo3(signature = (positions, initializer, partition_num, fusion_tree=None))]
+    fn from_positions_time_axis(
+        positions: Vec<VisualizePosition>,
+        initializer: &SolverInitializer,
+        partition_num: usize,
+        fusion_tree: Option<&PyAny>,
+    ) -> PyResult<Self> {
+        if partition_num == 0 {
+            return Err(PyValueError::new_err("at least one partition must exist"));
+        }
+        let shape = py_fusion_tree_shape(fusion_tree, partition_num)?;
+        py_check_positions(&positions, initializer)?;
+        plan_time_axis(
+            &positions,
+            initializer.vertex_num,
+            &initializer.weighted_edges,
+            partition_num,
+            &shape,
+        )
+        .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+    /// plan by recursive bisection, see [`plan_bisection`]
+    #[staticmethod]
+    #[pyo3(signature = (positions, initializer, partition_num, fusion_tree=None))]
+    fn bisection(
+        positions: Vec<VisualizePosition>,
+        initializer: &SolverInitializer,
+        partition_num: usize,
+        fusion_tree: Option<&PyAny>,
+    ) -> PyResult<Self> {
+        if partition_num == 0 {
+            return Err(PyValueError::new_err("at least one partition must exist"));
+        }
+        let shape = py_fusion_tree_shape(fusion_tree, partition_num)?;
+        py_check_positions(&positions, initializer)?;
+        plan_bisection(
+            &positions,
+            initializer.vertex_num,
+            &initializer.weighted_edges,
+            partition_num,
+            &shape,
+        )
+        .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+    /// the initializer in the new numbering, which the parallel solver must be constructed with
+    fn initializer(&self, initializer: &SolverInitializer) -> SolverInitializer {
+        initializer.permuted(self.sequential_vertices())
+    }
+    fn partition_info(&self) -> PartitionInfo {
+        self.partition_config.info()
+    }
+    #[getter]
+    #[pyo3(name = "sequential_vertices")]
+    fn py_sequential_vertices(&self) -> Vec<VertexIndex> {
+        self.sequential_vertices().clone()
+    }
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[allow(clippy::unnecessary_cast)]
+fn py_check_positions(positions: &[VisualizePosition], initializer: &SolverInitializer) -> PyResult<()> {
+    if positions.len() != initializer.vertex_num as usize {
+        return Err(PyValueError::new_err(format!(
+            "{} positions for {} vertices",
+            positions.len(),
+            initializer.vertex_num
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "python_binding")]
+#[pyfunction]
+pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PartitionPlan>()?;
+    Ok(())
+}
+
+#[cfg(all(test, not(feature = "minimal")))]
+pub mod tests {
+    use super::super::mwpm_solver::*;
+    use super::*;
+
+    /// from a code to an 8-way parallel solve using only the plan
+    #[test]
+    fn partition_plan_1() {
+        // cargo test partition_plan_1 -- --nocapture
+        let (d, noisy_measurements, partition_num) = (5, 15, 8);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let mut original_code = code.clone();
+        let positions = code.get_positions();
+        let initializer = code.get_initializer();
+        // group the rounds evenly into the units
+        let mut times: Vec<f64> = positions.iter().map(|position| position.t).collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        times.dedup();
+        let units: Vec<usize> = positions
+            .iter()
+            .map(|position| times.iter().position(|&t| t == position.t).unwrap() * partition_num / times.len())
+            .collect();
+        let plan = plan(
+            &positions,
+            initializer.vertex_num,
+            &initializer.weighted_edges,
+            &units,
+            &FusionTreeShape::BalancedBinary,
+        )
+        .unwrap();
+        let partition_info = plan.apply(&mut code).info();
+        assert_eq!(partition_info.config.partitions.len(), partition_num);
+        let mut parallel_solver = SolverParallel::new(&code.get_initializer(), &partition_info, json!({}));
+        let mut serial_solver = SolverSerial::new(&initializer);
+        for seed in 0..50 {
+            let syndrome_pattern = original_code.generate_random_errors(seed);
+            serial_solver.solve(&syndrome_pattern);
+            parallel_solver.solve(&plan.permutation.map_syndrome(&syndrome_pattern));
+            assert_eq!(parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+            serial_solver.clear();
+            parallel_solver.clear();
+        }
+    }
+
+    /// the time-axis and bisection planners agree on a code with many rounds
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn partition_plan_2() {
+        // cargo test partition_plan_2 -- --nocapture
+        let (d, noisy_measurements, partition_num) = (5, 19, 4);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let positions = code.get_positions();
+        let initializer = code.get_initializer();
+        let shape = FusionTreeShape::BalancedBinary;
+        let (vertex_num, weighted_edges) = (initializer.vertex_num, &initializer.weighted_edges);
+        let plans = [
+            plan_time_axis(&positions, vertex_num, weighted_edges, partition_num, &shape).unwrap(),
+            plan_bisection(&positions, vertex_num, weighted_edges, partition_num, &shape).unwrap(),
+        ];
+        assert_eq!(plans[0].partition_config.partitions, plans[1].partition_config.partitions);
+        let mut serial_solver = SolverSerial::new(&initializer);
+        for plan in plans.iter() {
+            let partition_info = plan.partition_config.info();
+            assert_eq!(partition_info.config.partitions.len(), partition_num);
+            let permuted_initializer = initializer.permuted(plan.sequential_vertices());
+            assert_eq!(partition_info.check_edges(&permuted_initializer.weighted_edges), Ok(()));
+            let mut crossing_edges = permuted_initializer.weighted_edges.clone();
+            crossing_edges.push((0, vertex_num - 1, 2)); // between the first and the last partition
+            assert_eq!(
+                partition_info.check_edges(&crossing_edges),
+                Err(PartitionError::CrossingEdge {
+                    edge: (0, vertex_num - 1),
+                    units: (0, partition_num - 1)
+                })
+            );
+            let mut parallel_solver = SolverParallel::new(&permuted_initializer, &partition_info, json!({}));
+            for seed in 0..20 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                serial_solver.solve(&syndrome_pattern);
+                parallel_solver.solve(&plan.permutation.map_syndrome(&syndrome_pattern));
+                assert_eq!(parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+                serial_solver.clear();
+                parallel_solver.clear();
+            }
+        }
+        // more partitions than rounds
+        assert!(matches!(
+            plan_time_axis(
+                &positions,
+                vertex_num,
+                weighted_edges,
+                noisy_measurements as usize + 2,
+                &shape
+            ),
+            Err(PartitionError::EmptyPartition { .. })
+        ));
+    }
+}
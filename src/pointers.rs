@@ -5,6 +5,17 @@
 //! The solution is to write everything in safe Rust, and debug them.
 //! After this, one can enable the feature `unsafe_pointer` to remove the unnecessary locks, thus improving the performance.
 //!
+//! The feature `unsafe_fast` is a narrower alternative: only the internal nodes, vertices and edges of the serial modules
+//! ([`ArcSerialLock`] and [`FastClearArcSerialLock`]) are replaced by a [`FastCell`], an `UnsafeCell` with the same
+//! `read_recursive` and `write` interface, while the pointers shared between the units of the parallel modules, e.g. the
+//! dual nodes, the interface and the units themselves, keep their locks. This is sound because
+//! - within a thread, the code is deadlock-free with `RwLock`, so a write never overlaps another borrow of the same
+//!   object, which is exactly the aliasing rule of `&mut`; debug builds check it by counting the borrows and panic
+//!   where the lock would have deadlocked;
+//! - across threads, a serial module is only reached through its parallel unit, which is locked before use; the dual
+//!   units never share vertices or edges (mirrored vertices are copies synchronized by messages), and the nodes of the
+//!   primal units are only reached from their parent after fusion, when the threads of the children have finished.
+//!
 
 use super::util::*;
 use crate::parking_lot::lock_api::{RwLockReadGuard, RwLockWriteGuard};
@@ -741,6 +752,352 @@ cfg_if::cfg_if! {
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(feature="slab_nodes")] {
+
+        use std::cell::UnsafeCell;
+        use std::mem::MaybeUninit;
+        use std::sync::atomic::{fence, AtomicPtr, AtomicU32, AtomicUsize, Ordering};
+
+        /// the number of slots allocated at once by a [`SlabArena`]
+        pub const SLAB_CHUNK_SIZE: usize = 1024;
+        /// the maximum number of chunks of a [`SlabArena`], whose table is allocated upfront so that a slot is located
+        /// from its index without a lock
+        pub const SLAB_MAX_CHUNKS: usize = 1 << 14;
+        /// the number of free slots moved at once between the cache of a thread and the [`SlabArena`]
+        pub const SLAB_CACHE_BATCH: usize = 64;
+
+        /// a slot of a [`SlabArena`] whose object is alive while `strong` is positive; `generation` increases every time
+        /// the object is dropped, so that a [`SlabWeak`] never upgrades to a later object in the same slot
+        pub struct SlabSlot<T> {
+            strong: AtomicUsize,
+            generation: AtomicU32,
+            value: UnsafeCell<MaybeUninit<RwLock<T>>>,
+        }
+
+        /// the allocator of the objects of a type, shared by all threads: the slots are allocated in contiguous chunks
+        /// owned by the arena and freed when it's dropped; a freed slot goes back to the arena (through a small cache
+        /// of the thread that frees it, returned when the thread exits), so that the memory is bounded by the peak
+        /// number of objects alive at the same time, however many threads or thread pools have allocated them
+        pub struct SlabArena<T> {
+            chunks: Box<[AtomicPtr<SlabSlot<T>>]>,
+            state: parking_lot::Mutex<SlabArenaState>,
+        }
+
+        struct SlabArenaState {
+            chunk_num: usize,
+            free: Vec<u32>,
+        }
+
+        impl<T> SlabArena<T> {
+            #[allow(clippy::new_without_default)]
+            pub fn new() -> Self {
+                Self {
+                    chunks: (0..SLAB_MAX_CHUNKS).map(|_| AtomicPtr::new(std::ptr::null_mut())).collect(),
+                    state: parking_lot::Mutex::new(SlabArenaState { chunk_num: 0, free: vec![] }),
+                }
+            }
+
+            /// the number of allocated chunks, each of [`SLAB_CHUNK_SIZE`] slots
+            pub fn chunk_num(&self) -> usize {
+                self.state.lock().chunk_num
+            }
+
+            #[inline(always)]
+            fn slot(&self, index: u32) -> &SlabSlot<T> {
+                let index = index as usize;
+                let chunk = self.chunks[index / SLAB_CHUNK_SIZE].load(Ordering::Acquire);
+                // an index is only handed out after its chunk is allocated, and the chunk lives as long as the arena
+                debug_assert!(!chunk.is_null(), "slab index out of the allocated chunks");
+                unsafe { &*chunk.add(index % SLAB_CHUNK_SIZE) }
+            }
+
+            /// move at most [`SLAB_CACHE_BATCH`] free slots into `free`, allocating a new chunk if none is left
+            fn acquire(&self, free: &mut Vec<u32>) {
+                let mut state = self.state.lock();
+                if state.free.is_empty() {
+                    assert!(state.chunk_num < SLAB_MAX_CHUNKS, "too many objects in the slab arena");
+                    let chunk: Box<[SlabSlot<T>]> = (0..SLAB_CHUNK_SIZE)
+                        .map(|_| SlabSlot {
+                            strong: AtomicUsize::new(0),
+                            generation: AtomicU32::new(0),
+                            value: UnsafeCell::new(MaybeUninit::uninit()),
+                        })
+                        .collect();
+                    let chunk_index = state.chunk_num;
+                    self.chunks[chunk_index].store(Box::into_raw(chunk) as *mut SlabSlot<T>, Ordering::Release);
+                    state.chunk_num += 1;
+                    let begin = chunk_index * SLAB_CHUNK_SIZE;
+                    state.free.extend((begin..begin + SLAB_CHUNK_SIZE).rev().map(|index| index as u32));
+                }
+                let remaining = state.free.len().saturating_sub(SLAB_CACHE_BATCH);
+                free.extend(state.free.drain(remaining..));
+            }
+
+            fn release(&self, free: impl IntoIterator<Item = u32>) {
+                self.state.lock().free.extend(free);
+            }
+        }
+
+        impl<T> Drop for SlabArena<T> {
+            fn drop(&mut self) {
+                let chunk_num = self.state.get_mut().chunk_num;
+                for chunk in self.chunks[..chunk_num].iter_mut() {
+                    let chunk = unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(*chunk.get_mut(), SLAB_CHUNK_SIZE)) };
+                    for slot in chunk.iter() {
+                        if slot.strong.load(Ordering::Acquire) > 0 {
+                            unsafe { (*slot.value.get()).assume_init_drop(); }
+                        }
+                    }
+                }
+            }
+        }
+
+        /// the free slots cached by a thread to avoid locking the [`SlabArena`] for every object, given back when the
+        /// thread exits
+        pub struct SlabCache<T: SlabAllocated> {
+            free: Vec<u32>,
+            _phantom: std::marker::PhantomData<T>,
+        }
+
+        impl<T: SlabAllocated> SlabCache<T> {
+            #[allow(clippy::new_without_default)]
+            pub fn new() -> Self {
+                Self { free: vec![], _phantom: std::marker::PhantomData }
+            }
+        }
+
+        impl<T: SlabAllocated> Drop for SlabCache<T> {
+            fn drop(&mut self) {
+                T::arena().release(self.free.drain(..));
+            }
+        }
+
+        /// a type whose objects are allocated in a [`SlabArena`], see [`slab_allocated`]
+        pub trait SlabAllocated: Sized + 'static {
+            /// the arena of this type
+            fn arena() -> &'static SlabArena<Self>;
+            /// run `f` with the cache of the current thread, or return `None` if the thread is being destroyed
+            fn with_cache<R>(f: impl FnOnce(&mut SlabCache<Self>) -> R) -> Option<R>;
+        }
+
+        /// implement [`SlabAllocated`] for a type with a global arena and a thread-local cache
+        #[macro_export]
+        macro_rules! slab_allocated {
+            ($type:ty) => {
+                impl $crate::pointers::SlabAllocated for $type {
+                    fn arena() -> &'static $crate::pointers::SlabArena<Self> {
+                        static ARENA: std::sync::OnceLock<$crate::pointers::SlabArena<$type>> = std::sync::OnceLock::new();
+                        ARENA.get_or_init($crate::pointers::SlabArena::new)
+                    }
+                    fn with_cache<R>(f: impl FnOnce(&mut $crate::pointers::SlabCache<Self>) -> R) -> Option<R> {
+                        thread_local! {
+                            static CACHE: std::cell::RefCell<$crate::pointers::SlabCache<$type>> =
+                                std::cell::RefCell::new($crate::pointers::SlabCache::new());
+                        }
+                        CACHE.try_with(|cache| f(&mut cache.borrow_mut())).ok()
+                    }
+                }
+            };
+        }
+        #[allow(unused_imports)] pub use slab_allocated;
+
+        /// the same as [`ArcRwLock`] (or [`ArcUnsafe`] with `unsafe_pointer`) but the object lives in a slot of a
+        /// [`SlabArena`], which improves the locality and avoids a heap allocation for every object; the handle is the
+        /// index of the slot together with the generation of the object
+        pub struct SlabArc<T: SlabAllocated> {
+            index: u32,
+            generation: u32,
+            _phantom: std::marker::PhantomData<T>,
+        }
+
+        /// the same as [`WeakRwLock`] but for [`SlabArc`]: it fails to upgrade once the generation of the slot changes
+        pub struct SlabWeak<T: SlabAllocated> {
+            index: u32,
+            generation: u32,
+            _phantom: std::marker::PhantomData<T>,
+        }
+
+        // the object is only accessed through the lock (or as carefully as `ArcUnsafe` with `unsafe_pointer`)
+        unsafe impl<T: SlabAllocated + Send + Sync> Send for SlabArc<T> { }
+        unsafe impl<T: SlabAllocated + Send + Sync> Sync for SlabArc<T> { }
+        unsafe impl<T: SlabAllocated + Send + Sync> Send for SlabWeak<T> { }
+        unsafe impl<T: SlabAllocated + Send + Sync> Sync for SlabWeak<T> { }
+
+        /// drop a strong reference, and the object as well if it's the last one
+        fn slab_release<T: SlabAllocated>(index: u32) {
+            let arena = T::arena();
+            let slot = arena.slot(index);
+            if slot.strong.fetch_sub(1, Ordering::Release) != 1 {
+                return;
+            }
+            fence(Ordering::Acquire);
+            slot.generation.fetch_add(1, Ordering::AcqRel);
+            unsafe { (*slot.value.get()).assume_init_drop(); }
+            let cached = T::with_cache(|cache| {
+                cache.free.push(index);
+                if cache.free.len() >= 2 * SLAB_CACHE_BATCH {
+                    let remaining = cache.free.len() - SLAB_CACHE_BATCH;
+                    arena.release(cache.free.drain(remaining..));
+                }
+            });
+            if cached.is_none() {
+                arena.release([index]);
+            }
+        }
+
+        fn slab_allocate<T: SlabAllocated>() -> u32 {
+            let arena = T::arena();
+            T::with_cache(|cache| {
+                if cache.free.is_empty() {
+                    arena.acquire(&mut cache.free);
+                }
+                cache.free.pop().unwrap()
+            }).unwrap_or_else(|| {
+                let mut free = vec![];
+                arena.acquire(&mut free);
+                let index = free.pop().unwrap();
+                arena.release(free);
+                index
+            })
+        }
+
+        impl<T: SlabAllocated> SlabArc<T> {
+            pub fn new_value(obj: T) -> Self {
+                let index = slab_allocate::<T>();
+                let slot = T::arena().slot(index);
+                unsafe { (*slot.value.get()).write(RwLock::new(obj)); }
+                let generation = slot.generation.load(Ordering::Acquire);
+                slot.strong.store(1, Ordering::Release);
+                Self { index, generation, _phantom: std::marker::PhantomData }
+            }
+
+            #[inline(always)]
+            fn lock(&self) -> &RwLock<T> {
+                unsafe { (*T::arena().slot(self.index).value.get()).assume_init_ref() }
+            }
+
+            pub fn downgrade(&self) -> SlabWeak<T> {
+                SlabWeak { index: self.index, generation: self.generation, _phantom: std::marker::PhantomData }
+            }
+
+            #[inline(always)]
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                self.index == other.index
+            }
+        }
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature="unsafe_pointer")] {
+                impl<T: SlabAllocated> SlabArc<T> {
+                    #[inline(always)]
+                    pub fn read_recursive(&self) -> &T {
+                        unsafe { &*self.lock().data_ptr() }
+                    }
+                    #[inline(always)]
+                    #[allow(clippy::mut_from_ref)]
+                    pub fn write(&self) -> &mut T {
+                        unsafe { &mut *self.lock().data_ptr() }
+                    }
+                }
+                impl<T: SlabAllocated> std::ops::Deref for SlabArc<T> {
+                    type Target = T;
+                    fn deref(&self) -> &Self::Target {
+                        self.read_recursive()
+                    }
+                }
+            } else {
+                impl<T: SlabAllocated> SlabArc<T> {
+                    #[inline(always)]
+                    pub fn read_recursive(&self) -> RwLockReadGuard<'_, RawRwLock, T> {
+                        self.lock().read_recursive()
+                    }
+                    #[inline(always)]
+                    pub fn write(&self) -> RwLockWriteGuard<'_, RawRwLock, T> {
+                        self.lock().write()
+                    }
+                }
+                impl<T: SlabAllocated> std::ops::Deref for SlabArc<T> {
+                    type Target = RwLock<T>;
+                    fn deref(&self) -> &Self::Target {
+                        self.lock()
+                    }
+                }
+            }
+        }
+
+        impl<T: SlabAllocated> SlabWeak<T> {
+            pub fn upgrade_force(&self) -> SlabArc<T> {
+                self.upgrade().unwrap()
+            }
+            pub fn upgrade(&self) -> Option<SlabArc<T>> {
+                let slot = T::arena().slot(self.index);
+                let mut strong = slot.strong.load(Ordering::Relaxed);
+                loop {
+                    if strong == 0 {
+                        return None;
+                    }
+                    match slot.strong.compare_exchange_weak(strong, strong + 1, Ordering::Acquire, Ordering::Relaxed) {
+                        Ok(_) => break,
+                        Err(current) => strong = current,
+                    }
+                }
+                // the slot may hold a later object
+                if slot.generation.load(Ordering::Acquire) != self.generation {
+                    slab_release::<T>(self.index);
+                    return None;
+                }
+                Some(SlabArc { index: self.index, generation: self.generation, _phantom: std::marker::PhantomData })
+            }
+        }
+
+        impl<T: SlabAllocated> Clone for SlabArc<T> {
+            fn clone(&self) -> Self {
+                T::arena().slot(self.index).strong.fetch_add(1, Ordering::Relaxed);
+                Self { index: self.index, generation: self.generation, _phantom: std::marker::PhantomData }
+            }
+        }
+
+        impl<T: SlabAllocated> Drop for SlabArc<T> {
+            fn drop(&mut self) {
+                slab_release::<T>(self.index);
+            }
+        }
+
+        impl<T: SlabAllocated> PartialEq for SlabArc<T> {
+            fn eq(&self, other: &Self) -> bool { self.ptr_eq(other) }
+        }
+
+        impl<T: SlabAllocated> Eq for SlabArc<T> { }
+
+        impl<T: SlabAllocated> Clone for SlabWeak<T> {
+            fn clone(&self) -> Self {
+                Self { index: self.index, generation: self.generation, _phantom: std::marker::PhantomData }
+            }
+        }
+
+        impl<T: SlabAllocated> PartialEq for SlabWeak<T> {
+            fn eq(&self, other: &Self) -> bool { self.index == other.index && self.generation == other.generation }
+        }
+
+        impl<T: SlabAllocated> Eq for SlabWeak<T> { }
+
+        impl<T: SlabAllocated> weak_table::traits::WeakElement for SlabWeak<T> {
+            type Strong = SlabArc<T>;
+            fn new(view: &Self::Strong) -> Self {
+                view.downgrade()
+            }
+            fn view(&self) -> Option<Self::Strong> {
+                self.upgrade()
+            }
+            fn clone(view: &Self::Strong) -> Self::Strong {
+                view.clone()
+            }
+        }
+
+    }
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature="unsafe_pointer")] {
         pub type FastClearArcManualSafeLock<T> = FastClearArcUnsafe<T>;
@@ -778,6 +1135,308 @@ cfg_if::cfg_if! {
     }
 }
 
+/*
+ * lock-free pointers for the internals of the serial modules, see the module docs for the safety argument
+ */
+
+cfg_if::cfg_if! {
+    if #[cfg(feature="unsafe_fast")] {
+
+        use std::cell::UnsafeCell;
+        #[cfg(debug_assertions)]
+        use std::sync::atomic::{AtomicIsize, Ordering};
+
+        #[cfg(feature="unsafe_pointer")]
+        compile_error!("features `unsafe_fast` and `unsafe_pointer` are mutually exclusive");
+
+        /// an [`UnsafeCell`] with the interface of a [`RwLock`]; in debug builds it counts the borrows like a
+        /// `RefCell` and panics on a conflicting one instead of blocking, in release builds it has no overhead
+        pub struct FastCell<T> {
+            value: UnsafeCell<T>,
+            /// the number of readers, or -1 when being written
+            #[cfg(debug_assertions)]
+            borrow: AtomicIsize,
+        }
+
+        unsafe impl<T: Send> Send for FastCell<T> { }
+        unsafe impl<T: Send + Sync> Sync for FastCell<T> { }
+
+        impl<T> FastCell<T> {
+            pub fn new(value: T) -> Self {
+                Self {
+                    value: UnsafeCell::new(value),
+                    #[cfg(debug_assertions)]
+                    borrow: AtomicIsize::new(0),
+                }
+            }
+
+            #[inline(always)]
+            pub fn read_recursive(&self) -> FastReadGuard<'_, T> {
+                #[cfg(debug_assertions)]
+                {
+                    let mut borrow = self.borrow.load(Ordering::Relaxed);
+                    loop {
+                        assert!(borrow >= 0, "bug detected: reading a fast pointer while it's being written");
+                        match self.borrow.compare_exchange_weak(borrow, borrow + 1, Ordering::Acquire, Ordering::Relaxed) {
+                            Ok(_) => break,
+                            Err(current) => borrow = current,
+                        }
+                    }
+                }
+                FastReadGuard { cell: self }
+            }
+
+            #[inline(always)]
+            pub fn write(&self) -> FastWriteGuard<'_, T> {
+                #[cfg(debug_assertions)]
+                if let Err(borrow) = self.borrow.compare_exchange(0, -1, Ordering::Acquire, Ordering::Relaxed) {
+                    panic!("bug detected: writing a fast pointer while it's borrowed ({borrow})");
+                }
+                FastWriteGuard { cell: self }
+            }
+        }
+
+        pub struct FastReadGuard<'a, T> {
+            cell: &'a FastCell<T>,
+        }
+
+        impl<T> std::ops::Deref for FastReadGuard<'_, T> {
+            type Target = T;
+            #[inline(always)]
+            fn deref(&self) -> &T {
+                unsafe { &*self.cell.value.get() }
+            }
+        }
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for FastReadGuard<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                (**self).fmt(f)
+            }
+        }
+
+        impl<T> Drop for FastReadGuard<'_, T> {
+            #[inline(always)]
+            fn drop(&mut self) {
+                #[cfg(debug_assertions)]
+                self.cell.borrow.fetch_sub(1, Ordering::Release);
+            }
+        }
+
+        pub struct FastWriteGuard<'a, T> {
+            cell: &'a FastCell<T>,
+        }
+
+        impl<T> std::ops::Deref for FastWriteGuard<'_, T> {
+            type Target = T;
+            #[inline(always)]
+            fn deref(&self) -> &T {
+                unsafe { &*self.cell.value.get() }
+            }
+        }
+
+        impl<T> std::ops::DerefMut for FastWriteGuard<'_, T> {
+            #[inline(always)]
+            fn deref_mut(&mut self) -> &mut T {
+                unsafe { &mut *self.cell.value.get() }
+            }
+        }
+
+        impl<T: std::fmt::Debug> std::fmt::Debug for FastWriteGuard<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                (**self).fmt(f)
+            }
+        }
+
+        impl<T> Drop for FastWriteGuard<'_, T> {
+            #[inline(always)]
+            fn drop(&mut self) {
+                #[cfg(debug_assertions)]
+                self.cell.borrow.store(0, Ordering::Release);
+            }
+        }
+
+        pub struct ArcFast<T> {
+            ptr: Arc<FastCell<T>>,
+        }
+
+        pub struct WeakFast<T> {
+            ptr: Weak<FastCell<T>>,
+        }
+
+        impl<T> ArcFast<T> {
+            pub fn new_value(obj: T) -> Self {
+                Self { ptr: Arc::new(FastCell::new(obj)) }
+            }
+            #[inline(always)]
+            pub fn read_recursive(&self) -> FastReadGuard<'_, T> {
+                self.ptr.read_recursive()
+            }
+            #[inline(always)]
+            pub fn write(&self) -> FastWriteGuard<'_, T> {
+                self.ptr.write()
+            }
+            pub fn downgrade(&self) -> WeakFast<T> {
+                WeakFast::<T> { ptr: Arc::downgrade(&self.ptr) }
+            }
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                Arc::ptr_eq(&self.ptr, &other.ptr)
+            }
+        }
+
+        impl<T> WeakFast<T> {
+            pub fn upgrade_force(&self) -> ArcFast<T> {
+                ArcFast::<T> { ptr: self.ptr.upgrade().unwrap() }
+            }
+            pub fn upgrade(&self) -> Option<ArcFast<T>> {
+                self.ptr.upgrade().map(|x| ArcFast::<T> { ptr: x })
+            }
+        }
+
+        impl<T> Clone for ArcFast<T> {
+            fn clone(&self) -> Self {
+                Self { ptr: self.ptr.clone() }
+            }
+        }
+
+        impl<T> PartialEq for ArcFast<T> {
+            fn eq(&self, other: &Self) -> bool { self.ptr_eq(other) }
+        }
+
+        impl<T> Eq for ArcFast<T> { }
+
+        impl<T> Clone for WeakFast<T> {
+            fn clone(&self) -> Self {
+                Self { ptr: self.ptr.clone() }
+            }
+        }
+
+        impl<T> PartialEq for WeakFast<T> {
+            fn eq(&self, other: &Self) -> bool { self.ptr.ptr_eq(&other.ptr) }
+        }
+
+        impl<T> Eq for WeakFast<T> { }
+
+        impl<T> weak_table::traits::WeakElement for WeakFast<T> {
+            type Strong = ArcFast<T>;
+            fn new(view: &Self::Strong) -> Self {
+                view.downgrade()
+            }
+            fn view(&self) -> Option<Self::Strong> {
+                self.upgrade()
+            }
+            fn clone(view: &Self::Strong) -> Self::Strong {
+                view.clone()
+            }
+        }
+
+        pub struct FastClearArcFast<T: FastClear> {
+            ptr: Arc<FastCell<T>>,
+        }
+
+        pub struct FastClearWeakFast<T: FastClear> {
+            ptr: Weak<FastCell<T>>,
+        }
+
+        impl<T: FastClear> FastClearArcFast<T> {
+            pub fn new_value(obj: T) -> Self {
+                Self { ptr: Arc::new(FastCell::new(obj)) }
+            }
+            #[inline(always)]
+            pub fn read_recursive(&self, active_timestamp: FastClearTimestamp) -> FastReadGuard<'_, T> {
+                let ret = self.ptr.read_recursive();
+                ret.debug_assert_dynamic_cleared(active_timestamp);  // only assert during debug modes
+                ret
+            }
+            /// without sanity check: this data might be outdated, so only use when you're read those immutable fields
+            #[inline(always)]
+            pub fn read_recursive_force(&self) -> FastReadGuard<'_, T> {
+                self.ptr.read_recursive()
+            }
+            #[inline(always)]
+            pub fn write(&self, active_timestamp: FastClearTimestamp) -> FastWriteGuard<'_, T> {
+                let ret = self.ptr.write();
+                ret.debug_assert_dynamic_cleared(active_timestamp);  // only assert during debug modes
+                ret
+            }
+            /// without sanity check: useful only in implementing hard_clear
+            #[inline(always)]
+            pub fn write_force(&self) -> FastWriteGuard<'_, T> {
+                self.ptr.write()
+            }
+            /// dynamically clear it if not already cleared; it's safe to call many times
+            #[inline(always)]
+            pub fn dynamic_clear(&self, active_timestamp: FastClearTimestamp) {
+                self.write_force().dynamic_clear(active_timestamp);
+            }
+            pub fn downgrade(&self) -> FastClearWeakFast<T> {
+                FastClearWeakFast::<T> { ptr: Arc::downgrade(&self.ptr) }
+            }
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                Arc::ptr_eq(&self.ptr, &other.ptr)
+            }
+        }
+
+        impl<T: FastClear> FastClearWeakFast<T> {
+            pub fn upgrade_force(&self) -> FastClearArcFast<T> {
+                FastClearArcFast::<T> { ptr: self.ptr.upgrade().unwrap() }
+            }
+            pub fn upgrade(&self) -> Option<FastClearArcFast<T>> {
+                self.ptr.upgrade().map(|x| FastClearArcFast::<T> { ptr: x })
+            }
+        }
+
+        impl<T: FastClear> Clone for FastClearArcFast<T> {
+            fn clone(&self) -> Self {
+                Self { ptr: self.ptr.clone() }
+            }
+        }
+
+        impl<T: FastClear> PartialEq for FastClearArcFast<T> {
+            fn eq(&self, other: &Self) -> bool { self.ptr_eq(other) }
+        }
+
+        impl<T: FastClear> Eq for FastClearArcFast<T> { }
+
+        impl<T: FastClear> Clone for FastClearWeakFast<T> {
+            fn clone(&self) -> Self {
+                Self { ptr: self.ptr.clone() }
+            }
+        }
+
+        impl<T: FastClear> PartialEq for FastClearWeakFast<T> {
+            fn eq(&self, other: &Self) -> bool { self.ptr.ptr_eq(&other.ptr) }
+        }
+
+        impl<T: FastClear> Eq for FastClearWeakFast<T> { }
+
+        impl<T: FastClear> weak_table::traits::WeakElement for FastClearWeakFast<T> {
+            type Strong = FastClearArcFast<T>;
+            fn new(view: &Self::Strong) -> Self {
+                view.downgrade()
+            }
+            fn view(&self) -> Option<Self::Strong> {
+                self.upgrade()
+            }
+            fn clone(view: &Self::Strong) -> Self::Strong {
+                view.clone()
+            }
+        }
+
+        pub type ArcSerialLock<T> = ArcFast<T>;
+        pub type WeakSerialLock<T> = WeakFast<T>;
+        pub type FastClearArcSerialLock<T> = FastClearArcFast<T>;
+        pub type FastClearWeakSerialLock<T> = FastClearWeakFast<T>;
+
+    } else {
+
+        pub type ArcSerialLock<T> = ArcManualSafeLock<T>;
+        pub type WeakSerialLock<T> = WeakManualSafeLock<T>;
+        pub type FastClearArcSerialLock<T> = FastClearArcManualSafeLockDangerous<T>;
+        pub type FastClearWeakSerialLock<T> = FastClearWeakManualSafeLockDangerous<T>;
+
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -838,4 +1497,69 @@ mod tests {
 
         }
     }
+
+    cfg_if::cfg_if! {
+        if #[cfg(feature="slab_nodes")] {
+
+            struct SlabTester {
+                idx: usize,
+            }
+
+            slab_allocated!(SlabTester);
+
+            /// a stale handle is detected, and the slots freed by exited threads are reused without growing the arena
+            #[test]
+            fn pointers_test_slab() {  // cargo test pointers_test_slab --features slab_nodes -- --nocapture
+                let ptr = SlabArc::new_value(SlabTester { idx: 0 });
+                let weak = ptr.downgrade();
+                ptr.write().idx = 1;
+                assert_eq!(weak.upgrade_force().read_recursive().idx, 1);
+                drop(ptr);
+                let reused = SlabArc::new_value(SlabTester { idx: 2 });
+                assert_eq!(reused.index, weak.index, "the freed slot is reused first");
+                assert!(weak.upgrade().is_none());
+                assert_eq!(reused.downgrade().upgrade_force().read_recursive().idx, 2);
+                let allocate_and_drop = || {
+                    let ptrs: Vec<_> = (0..3 * SLAB_CHUNK_SIZE).map(|idx| SlabArc::new_value(SlabTester { idx })).collect();
+                    assert!(ptrs.iter().enumerate().all(|(idx, ptr)| ptr.read_recursive().idx == idx));
+                };
+                std::thread::spawn(allocate_and_drop).join().unwrap();
+                let chunk_num = SlabTester::arena().chunk_num();
+                for _ in 0..10 {
+                    std::thread::spawn(allocate_and_drop).join().unwrap();
+                }
+                assert_eq!(SlabTester::arena().chunk_num(), chunk_num);
+            }
+
+        }
+    }
+
+    cfg_if::cfg_if! {
+        if #[cfg(feature="unsafe_fast")] {
+
+            type TesterFastPtr = ArcFast<Tester>;
+
+            #[test]
+            fn pointers_test_4() {  // cargo test pointers_test_4 --features unsafe_fast -- --nocapture
+                let ptr = TesterFastPtr::new_value(Tester { idx: 0 });
+                let weak = ptr.downgrade();
+                ptr.write().idx = 1;
+                assert_eq!(weak.upgrade_force().read_recursive().idx, 1);
+                weak.upgrade_force().write().idx = 2;
+                let (read_1, read_2) = (ptr.read_recursive(), ptr.read_recursive());
+                assert_eq!(read_1.idx + read_2.idx, 4);
+            }
+
+            /// a write that would deadlock with a lock is caught in debug builds
+            #[test]
+            #[cfg(debug_assertions)]
+            #[should_panic(expected = "writing a fast pointer while it's borrowed")]
+            fn pointers_test_5() {  // cargo test pointers_test_5 --features unsafe_fast -- --nocapture
+                let ptr = TesterFastPtr::new_value(Tester { idx: 0 });
+                let _read = ptr.read_recursive();
+                ptr.write().idx = 1;
+            }
+
+        }
+    }
 }
@@ -6,12 +6,14 @@
 #![cfg_attr(feature = "unsafe_pointer", allow(dropping_references))]
 use super::complete_graph::*;
 use super::dual_module::*;
+use super::mwpm_solver::{PrimalDualSolver, SolverSerial};
 use super::pointers::*;
 use super::util::*;
 use super::visualize::*;
 use crate::derivative::Derivative;
 #[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 #[derive(Derivative)]
@@ -25,7 +27,7 @@ pub struct IntermediateMatching {
     pub virtual_matchings: Vec<((DualNodePtr, DualNodeWeak), VertexIndex)>,
 }
 
-#[derive(Derivative)]
+#[derive(Derivative, Clone)]
 #[derivative(Debug)]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
@@ -36,6 +38,22 @@ pub struct PerfectMatching {
     pub virtual_matchings: Vec<(DualNodePtr, VertexIndex)>,
 }
 
+/// which virtual vertex a defect matched to the boundary goes to, among those at the same minimum distance from it; the
+/// total weight of the matching and of the subgraph is the same whatever the choice
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum BoundarySelection {
+    /// the virtual vertex found by the solver, which depends on the order of growth and thus on the partition
+    #[default]
+    AsMatched,
+    /// the smallest vertex index
+    SmallestIndex,
+    /// the fewest edges on the minimum-weight path that the subgraph takes, then the smallest vertex index
+    NearestByWeight,
+    /// the smallest euclidean distance between the positions of the defect and the virtual vertex, then the smallest
+    /// vertex index; the positions of all vertices are given
+    NearestByPosition(Vec<VisualizePosition>),
+}
+
 /// common trait that must be implemented for each implementation of primal module
 pub trait PrimalModuleImpl {
     /// create a primal module given the dual module
@@ -146,13 +164,18 @@ pub trait PrimalModuleImpl {
                     }
                     if let Some(length) = group_max_update_length.get_none_zero_growth() {
                         visualizer
-                            .snapshot_combined(format!("grow {length}"), vec![interface, dual_module, primal_module])
+                            .snapshot_combined_category(
+                                format!("grow {length}"),
+                                SnapshotCategory::Growth,
+                                vec![interface, dual_module, primal_module],
+                            )
                             .unwrap();
                     } else {
-                        let first_conflict = format!("{:?}", group_max_update_length.peek().unwrap());
+                        let first_conflict = group_max_update_length.peek().unwrap();
                         visualizer
-                            .snapshot_combined(
-                                format!("resolve {first_conflict}"),
+                            .snapshot_combined_category(
+                                format!("resolve {first_conflict:?}"),
+                                SnapshotCategory::of_conflict(first_conflict),
                                 vec![interface, dual_module, primal_module],
                             )
                             .unwrap();
@@ -160,7 +183,11 @@ pub trait PrimalModuleImpl {
                 },
             );
             visualizer
-                .snapshot_combined("solved".to_string(), vec![interface, dual_module, self])
+                .snapshot_combined_category(
+                    "solved".to_string(),
+                    SnapshotCategory::Final,
+                    vec![interface, dual_module, self],
+                )
                 .unwrap();
         } else {
             self.solve(interface, syndrome_pattern, dual_module);
@@ -176,8 +203,11 @@ pub trait PrimalModuleImpl {
     ) where
         F: FnMut(&DualModuleInterfacePtr, &mut D, &mut Self, &GroupMaxUpdateLength),
     {
-        interface.load(syndrome_pattern, dual_module);
-        self.load(interface);
+        {
+            trace_span!("load_syndrome", defect_num = syndrome_pattern.defect_vertices.len());
+            interface.load(syndrome_pattern, dual_module);
+            self.load(interface);
+        }
         self.solve_step_callback_interface_loaded(interface, dual_module, callback);
     }
 
@@ -189,8 +219,10 @@ pub trait PrimalModuleImpl {
     ) where
         F: FnMut(&DualModuleInterfacePtr, &mut D, &mut Self, &GroupMaxUpdateLength),
     {
+        let mut trace_rounds = TraceRounds::default();
         let mut group_max_update_length = dual_module.compute_maximum_update_length();
         while !group_max_update_length.is_empty() {
+            trace_rounds.next_iteration();
             callback(interface, dual_module, self, &group_max_update_length);
             if let Some(length) = group_max_update_length.get_none_zero_growth() {
                 interface.grow(length, dual_module);
@@ -201,12 +233,65 @@ pub trait PrimalModuleImpl {
         }
     }
 
+    /// the same as [`Self::solve_step_callback_interface_loaded`] without callback, but stops growing once the
+    /// [`DualModuleInterface::total_growth`] reaches `max_total_growth`; returns whether it stops early, in which case some
+    /// nodes are left unmatched or in alternating trees
+    fn solve_interface_loaded_growth_capped<D: DualModuleImpl>(
+        &mut self,
+        interface: &DualModuleInterfacePtr,
+        dual_module: &mut D,
+        max_total_growth: Weight,
+    ) -> bool {
+        let mut trace_rounds = TraceRounds::default();
+        let mut group_max_update_length = dual_module.compute_maximum_update_length();
+        while !group_max_update_length.is_empty() {
+            trace_rounds.next_iteration();
+            if let Some(length) = group_max_update_length.get_none_zero_growth() {
+                let remaining_growth = max_total_growth - interface.read_recursive().total_growth();
+                if remaining_growth <= 0 {
+                    return true;
+                }
+                interface.grow(std::cmp::min(length, remaining_growth), dual_module);
+            } else {
+                self.resolve(group_max_update_length, interface, dual_module);
+            }
+            group_max_update_length = dual_module.compute_maximum_update_length();
+        }
+        false
+    }
+
     /// performance profiler report
     fn generate_profiler_report(&self) -> serde_json::Value {
         json!({})
     }
 }
 
+/// the number of grow or resolve iterations in a single `grow_resolve` span of the `tracing` feature
+pub const TRACE_ITERATIONS_PER_SPAN: usize = 100;
+
+/// groups the iterations of the grow and resolve loop into `tracing` spans, which is a no-op without the feature
+#[derive(Default)]
+struct TraceRounds {
+    #[cfg(feature = "tracing")]
+    iteration: usize,
+    #[cfg(feature = "tracing")]
+    span: Option<tracing::span::EnteredSpan>,
+}
+
+impl TraceRounds {
+    #[inline]
+    fn next_iteration(&mut self) {
+        #[cfg(feature = "tracing")]
+        {
+            if self.iteration.is_multiple_of(TRACE_ITERATIONS_PER_SPAN) {
+                self.span = None; // exit the previous span first, so that the spans are siblings
+                self.span = Some(tracing::info_span!("grow_resolve", first_iteration = self.iteration).entered());
+            }
+            self.iteration += 1;
+        }
+    }
+}
+
 impl Default for IntermediateMatching {
     fn default() -> Self {
         Self::new()
@@ -415,6 +500,119 @@ impl PerfectMatching {
         mwpm_result
     }
 
+    /// rewrite the matching into a canonical form, so that the serial and parallel solvers (or any other MWPM decoder) give
+    /// bit-identical results; the canonical form is the unique minimum-weight perfect matching under a deterministic tie-breaker
+    /// on the defect pairs, with each boundary matching going to the smallest-index virtual vertex of minimum distance, and the
+    /// pairs are sorted by vertex index. erasures and dynamic weights are not considered, i.e., the weights of `initializer` are
+    /// used. this is an opt-in post-processing that runs Dijkstra's algorithm from every defect vertex and then solves the MWPM
+    /// problem again on the complete graph of the defect vertices, which is much slower than the decoding itself
+    #[allow(clippy::unnecessary_cast)]
+    pub fn canonicalize(&mut self, initializer: &SolverInitializer) {
+        let mut defect_nodes: Vec<(VertexIndex, DualNodePtr)> = vec![];
+        for (node_ptr_1, node_ptr_2) in self.peer_matchings.iter() {
+            defect_nodes.push((node_ptr_1.get_representative_vertex(), node_ptr_1.clone()));
+            defect_nodes.push((node_ptr_2.get_representative_vertex(), node_ptr_2.clone()));
+        }
+        for (node_ptr, _) in self.virtual_matchings.iter() {
+            defect_nodes.push((node_ptr.get_representative_vertex(), node_ptr.clone()));
+        }
+        defect_nodes.sort_by_key(|(defect_vertex, _)| *defect_vertex);
+        let defect_num = defect_nodes.len();
+        if defect_num == 0 {
+            return;
+        }
+        let mut is_virtual = vec![false; initializer.vertex_num as usize];
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            is_virtual[virtual_vertex as usize] = true;
+        }
+        let mut complete_graph = CompleteGraph::new(initializer.vertex_num, &initializer.weighted_edges);
+        // vertices `[0, defect_num)` are the defects and `defect_num + i` is the boundary of the i-th defect, where each edge
+        // also remembers the pair of original vertices for the tie-breaker
+        let mut defect_edges: Vec<(VertexIndex, VertexIndex, Weight, VertexIndex, VertexIndex)> = vec![];
+        let mut boundary_vertices = vec![];
+        for (index, &(defect_vertex, _)) in defect_nodes.iter().enumerate() {
+            let edges = complete_graph.all_edges(defect_vertex);
+            for (peer_index, &(peer_vertex, _)) in defect_nodes.iter().enumerate().skip(index + 1) {
+                if let Some(&(_, weight)) = edges.get(&peer_vertex) {
+                    defect_edges.push((
+                        index as VertexIndex,
+                        peer_index as VertexIndex,
+                        weight,
+                        defect_vertex,
+                        peer_vertex,
+                    ));
+                }
+            }
+            let boundary = edges
+                .iter()
+                .filter(|(vertex, _)| is_virtual[**vertex as usize])
+                .map(|(&vertex, &(_, weight))| (weight, vertex))
+                .min();
+            if let Some((weight, virtual_vertex)) = boundary {
+                defect_edges.push((
+                    index as VertexIndex,
+                    (defect_num + index) as VertexIndex,
+                    weight,
+                    defect_vertex,
+                    virtual_vertex,
+                ));
+            }
+            boundary_vertices.push(boundary.map(|(_, virtual_vertex)| virtual_vertex));
+        }
+        // the tie-breaker takes as many bits as the weights leave, and is bounded so that it never changes the order of
+        // matchings with different weights: the sum of all the perturbed weights `2 * (weight * range * (defect_num + 1)
+        // + tie_breaker)` must not overflow
+        let max_weight = defect_edges.iter().map(|edge| edge.2).max().unwrap_or(0).max(1);
+        let max_range = Weight::MAX / 2 / (max_weight + 1) / (defect_num as Weight + 1) / (defect_edges.len() as Weight + 1);
+        let tie_breaker_range: Weight = 1 << max_range.max(1).ilog2();
+        let weight_scale = tie_breaker_range * (defect_num as Weight + 1);
+        let weighted_edges: Vec<_> = defect_edges
+            .into_iter()
+            .map(|(index_1, index_2, weight, vertex_1, vertex_2)| {
+                let perturbed_weight = weight
+                    .checked_mul(weight_scale)
+                    .and_then(|weight| weight.checked_add(canonical_tie_breaker(vertex_1, vertex_2, tie_breaker_range)))
+                    .and_then(|weight| weight.checked_mul(2))
+                    .expect("weight overflow in canonicalization, consider a larger `Weight` type");
+                (index_1, index_2, perturbed_weight)
+            })
+            .collect();
+        let defect_initializer = SolverInitializer::new(
+            (2 * defect_num) as VertexNum,
+            weighted_edges,
+            (defect_num..2 * defect_num).map(|index| index as VertexIndex).collect(),
+        );
+        let mut solver = SolverSerial::new(&defect_initializer);
+        solver.solve(&SyndromePattern::new_vertices(
+            (0..defect_num).map(|index| index as VertexIndex).collect(),
+        ));
+        let canonical_matching = solver.perfect_matching();
+        let mut peer_matchings: Vec<(usize, usize)> = canonical_matching
+            .peer_matchings
+            .iter()
+            .map(|(node_ptr_1, node_ptr_2)| {
+                let index_1 = node_ptr_1.get_representative_vertex() as usize;
+                let index_2 = node_ptr_2.get_representative_vertex() as usize;
+                (std::cmp::min(index_1, index_2), std::cmp::max(index_1, index_2))
+            })
+            .collect();
+        peer_matchings.sort_unstable();
+        let mut virtual_matchings: Vec<usize> = canonical_matching
+            .virtual_matchings
+            .iter()
+            .map(|(node_ptr, _)| node_ptr.get_representative_vertex() as usize)
+            .collect();
+        virtual_matchings.sort_unstable();
+        self.peer_matchings = peer_matchings
+            .into_iter()
+            .map(|(index_1, index_2)| (defect_nodes[index_1].1.clone(), defect_nodes[index_2].1.clone()))
+            .collect();
+        self.virtual_matchings = virtual_matchings
+            .into_iter()
+            .map(|index| (defect_nodes[index].1.clone(), boundary_vertices[index].unwrap()))
+            .collect();
+    }
+
     #[cfg(feature = "python_binding")]
     fn __repr__(&self) -> String {
         format!("{:?}", self)
@@ -436,6 +634,135 @@ impl PerfectMatching {
     }
 }
 
+impl PerfectMatching {
+    /// choose the virtual vertex of every boundary matching by `selection`, which runs Dijkstra's algorithm from each of
+    /// these defects unless it's [`BoundarySelection::AsMatched`]; the distances are those of `complete_graph`, so that the
+    /// erasures and dynamic weights loaded in it are respected
+    #[allow(clippy::unnecessary_cast)]
+    pub fn select_boundary(
+        &mut self,
+        selection: &BoundarySelection,
+        initializer: &SolverInitializer,
+        complete_graph: &mut CompleteGraph,
+    ) {
+        if *selection == BoundarySelection::AsMatched || self.virtual_matchings.is_empty() {
+            return;
+        }
+        let mut is_virtual = vec![false; initializer.vertex_num as usize];
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            is_virtual[virtual_vertex as usize] = true;
+        }
+        for (node_ptr, virtual_vertex) in self.virtual_matchings.iter_mut() {
+            let defect_vertex = node_ptr.get_representative_vertex();
+            let edges = complete_graph.all_edges(defect_vertex);
+            let distance = edges[&*virtual_vertex].1;
+            let candidates = (edges.iter())
+                .filter(|(vertex, (_, weight))| is_virtual[**vertex as usize] && *weight == distance)
+                .map(|(&vertex, _)| vertex);
+            let path_length = |mut vertex: VertexIndex| {
+                let mut length = 0;
+                while vertex != defect_vertex {
+                    vertex = edges[&vertex].0;
+                    length += 1;
+                }
+                length
+            };
+            let selected = match selection {
+                BoundarySelection::AsMatched => unreachable!(),
+                BoundarySelection::SmallestIndex => candidates.min(),
+                BoundarySelection::NearestByWeight => candidates.min_by_key(|&vertex| (path_length(vertex), vertex)),
+                BoundarySelection::NearestByPosition(positions) => {
+                    let defect_position = &positions[defect_vertex as usize];
+                    let squared_distance = |vertex: VertexIndex| {
+                        let position = &positions[vertex as usize];
+                        (position.i - defect_position.i).powi(2)
+                            + (position.j - defect_position.j).powi(2)
+                            + (position.t - defect_position.t).powi(2)
+                    };
+                    candidates.min_by(|&vertex_1, &vertex_2| {
+                        (squared_distance(vertex_1).total_cmp(&squared_distance(vertex_2))).then(vertex_1.cmp(&vertex_2))
+                    })
+                }
+            };
+            *virtual_vertex = selected.expect("the matched virtual vertex is always a candidate");
+        }
+    }
+
+    /// build the subgraph from the minimum-weight paths that have the fewest edges, choosing the virtual vertex of every
+    /// boundary matching likewise; the total weight is the same as the subgraph of [`SubGraphBuilder`], and with positive
+    /// weights the paths are disjoint, so the subgraph has no more edges. the distances are those of `complete_graph`, so
+    /// that the erasures and dynamic weights loaded in it are respected
+    #[allow(clippy::unnecessary_cast)]
+    pub fn minimize_support(
+        &mut self,
+        initializer: &SolverInitializer,
+        complete_graph: &mut CompleteGraph,
+    ) -> Vec<EdgeIndex> {
+        let minimize_hops = complete_graph.minimize_hops();
+        complete_graph.set_minimize_hops(true);
+        // the shortest path tree now has the fewest edges, which the nearest virtual vertex by weight is chosen from
+        self.select_boundary(&BoundarySelection::NearestByWeight, initializer, complete_graph);
+        let mut vertex_pair_edges = HashMap::with_capacity(initializer.weighted_edges.len());
+        for (edge_index, &(vertex_1, vertex_2, _)) in initializer.weighted_edges.iter().enumerate() {
+            let id = (std::cmp::min(vertex_1, vertex_2), std::cmp::max(vertex_1, vertex_2));
+            vertex_pair_edges.insert(id, edge_index as EdgeIndex);
+        }
+        let pairs = (self.peer_matchings.iter())
+            .map(|(ptr_1, ptr_2)| (ptr_1.get_representative_vertex(), ptr_2.get_representative_vertex()))
+            .chain(
+                (self.virtual_matchings.iter())
+                    .map(|(ptr, virtual_vertex)| (ptr.get_representative_vertex(), *virtual_vertex)),
+            );
+        let mut subgraph = BTreeSet::new();
+        for (vertex_1, vertex_2) in pairs {
+            let (path, _) = complete_graph.get_path(vertex_1, vertex_2);
+            let mut a = vertex_1;
+            for &(b, _) in path.iter() {
+                let edge_index = vertex_pair_edges[&(std::cmp::min(a, b), std::cmp::max(a, b))];
+                if !subgraph.remove(&edge_index) {
+                    subgraph.insert(edge_index);
+                }
+                a = b;
+            }
+        }
+        complete_graph.set_minimize_hops(minimize_hops);
+        subgraph.into_iter().collect()
+    }
+
+    /// the final matched pairs of defect vertices, each with either a peer defect vertex or a virtual vertex
+    pub fn snapshot_matchings(&self, abbrev: bool) -> serde_json::Value {
+        let mut matchings = vec![];
+        for (ptr_1, ptr_2) in self.peer_matchings.iter() {
+            matchings.push(json!({
+                if abbrev { "s" } else { "defect_vertex" }: ptr_1.get_representative_vertex(),
+                if abbrev { "p" } else { "peer" }: ptr_2.get_representative_vertex(),
+            }));
+        }
+        for (ptr, virtual_vertex) in self.virtual_matchings.iter() {
+            matchings.push(json!({
+                if abbrev { "s" } else { "defect_vertex" }: ptr.get_representative_vertex(),
+                if abbrev { "v" } else { "virtual_vertex" }: virtual_vertex,
+            }));
+        }
+        json!(matchings)
+    }
+}
+
+/// a deterministic pseudo-random value in `[0, range)` of a vertex pair, used to break ties between equal-weight matchings;
+/// it depends on all the bits of both vertex indices, and `range` must be a power of two
+fn canonical_tie_breaker(vertex_1: VertexIndex, vertex_2: VertexIndex, range: Weight) -> Weight {
+    let (vertex_1, vertex_2) = (std::cmp::min(vertex_1, vertex_2), std::cmp::max(vertex_1, vertex_2));
+    // splitmix64 finalizer
+    let mix = |mut value: u64| {
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+        value ^ (value >> 31)
+    };
+    let value = mix(mix(vertex_1 as u64) ^ vertex_2 as u64);
+    // the high bits are the best mixed
+    value.checked_shr(u64::BITS - range.ilog2()).unwrap_or(0) as Weight
+}
+
 impl FusionVisualizer for PerfectMatching {
     #[allow(clippy::unnecessary_cast)]
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
@@ -481,6 +808,7 @@ impl FusionVisualizer for PerfectMatching {
         };
         json!({
             "primal_nodes": primal_nodes,
+            "matchings": self.snapshot_matchings(abbrev),
         })
     }
 }
@@ -499,6 +827,15 @@ pub struct SubGraphBuilder {
 }
 
 impl SubGraphBuilder {
+    /// an analytic estimate of the memory in bytes, see [`crate::dual_module_serial::DualModuleSerial::memory_estimate`]
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>() - size_of::<CompleteGraph>();
+        bytes += self.vertex_pair_edges.capacity() * size_of::<((VertexIndex, VertexIndex), EdgeIndex)>();
+        bytes += self.complete_graph.memory_estimate();
+        bytes += self.subgraph.len() * size_of::<EdgeIndex>();
+        bytes
+    }
+
     pub fn new(initializer: &SolverInitializer) -> Self {
         let mut vertex_pair_edges = HashMap::with_capacity(initializer.weighted_edges.len());
         for (edge_index, (i, j, _)) in initializer.weighted_edges.iter().enumerate() {
@@ -520,13 +857,33 @@ impl SubGraphBuilder {
 
     /// temporarily set some edges to 0 weight, and when it resets, those edges will be reverted back to the original weight
     pub fn load_erasures(&mut self, erasures: &[EdgeIndex]) {
+        self.debug_assert_edges_in_range(erasures.iter());
         self.complete_graph.load_erasures(erasures);
     }
 
     pub fn load_dynamic_weights(&mut self, dynamic_weights: &[(EdgeIndex, Weight)]) {
+        self.debug_assert_edges_in_range(dynamic_weights.iter().map(|(edge_index, _)| edge_index));
         self.complete_graph.load_dynamic_weights(dynamic_weights);
     }
 
+    /// the edge indices of a syndrome pattern refer to the edges of this decoding graph; when the decoding graph is
+    /// transformed, they must be translated with [`EdgeIndexMap::map_erasures`] first
+    #[allow(clippy::unnecessary_cast)]
+    fn debug_assert_edges_in_range<'a>(&self, edge_indices: impl Iterator<Item = &'a EdgeIndex>) {
+        let edge_num = self.complete_graph.weighted_edges.len();
+        for &edge_index in edge_indices {
+            debug_assert!(
+                (edge_index as usize) < edge_num,
+                "edge index {edge_index} out of range for {edge_num} edges, is it translated to the transformed decoding graph?"
+            );
+        }
+    }
+
+    /// permanently change the weights of some edges, see [`CompleteGraph::update_weights`]
+    pub fn update_weights(&mut self, new_weights: &[(EdgeIndex, Weight)]) {
+        self.complete_graph.update_weights(new_weights);
+    }
+
     /// load perfect matching to the subgraph builder
     pub fn load_perfect_matching(&mut self, perfect_matching: &PerfectMatching) {
         self.subgraph.clear();
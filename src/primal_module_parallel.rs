@@ -14,6 +14,7 @@ use super::visualize::*;
 use crate::rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::ops::DerefMut;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::time::{Duration, Instant};
 
@@ -28,6 +29,10 @@ pub struct PrimalModuleParallel {
     pub thread_pool: Arc<rayon::ThreadPool>,
     /// the time of calling [`PrimalModuleParallel::parallel_solve_step_callback`] method
     pub last_solve_start_time: ArcRwLock<Instant>,
+    /// the summaries of the nodes matched to the interface of each unit, recorded by its descendants when they are solved
+    /// and consumed when it fuses them; each arena is allocated with its capacity bound, i.e. the number of edges incident
+    /// to the owning vertices of the unit, and is only cleared afterwards
+    pub interface_summaries: Vec<parking_lot::Mutex<Vec<BoundaryNodeSummary>>>,
 }
 
 pub struct PrimalModuleParallelUnit {
@@ -47,8 +52,16 @@ pub struct PrimalModuleParallelUnit {
     pub parent: Option<PrimalModuleParallelUnitWeak>,
     /// record the time of events
     pub event_time: Option<PrimalModuleParallelUnitEventTime>,
+    /// the state of the interface when this unit fuses its children, `None` for a base partition
+    pub fusion_statistics: Option<FusionStatistics>,
     /// streaming decode mocker, if exists, base partition will wait until specified time and then start decoding
     pub streaming_decode_mocker: Option<StreamingDecodeMocker>,
+    /// artificially stall the unit before solving, to test the watchdog
+    #[cfg(test)]
+    pub test_stall: Option<Duration>,
+    /// artificially panic when solving the unit, to test that the panic reaches the caller
+    #[cfg(test)]
+    pub test_panic: bool,
 }
 
 pub type PrimalModuleParallelUnitPtr = ArcManualSafeLock<PrimalModuleParallelUnit>;
@@ -72,10 +85,16 @@ impl std::fmt::Debug for PrimalModuleParallelUnitWeak {
 pub struct PrimalModuleParallelUnitEventTime {
     /// unit starts executing
     pub start: f64,
+    /// a fusion unit has fused its children and broken the matchings to its interface, before solving the fused graph;
+    /// the same as `start` for a base partition
+    pub fused: f64,
     /// unit ends executing
     pub end: f64,
     /// thread index
     pub thread_index: usize,
+    /// the thread that executes the unit, useful to check which thread pool the task runs in
+    #[serde(skip)]
+    pub thread_id: std::thread::ThreadId,
 }
 
 impl Default for PrimalModuleParallelUnitEventTime {
@@ -88,12 +107,26 @@ impl PrimalModuleParallelUnitEventTime {
     pub fn new() -> Self {
         Self {
             start: 0.,
+            fused: 0.,
             end: 0.,
             thread_index: rayon::current_thread_index().unwrap_or(0),
+            thread_id: std::thread::current().id(),
         }
     }
 }
 
+/// the state of the interface when a fusion unit fuses its two children, for profiling purposes; a syndrome concentrated
+/// around an interface shows up as elevated counts of that fusion
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FusionStatistics {
+    /// the fusion unit, whose owning vertices are the interface between its children
+    pub unit_index: usize,
+    /// the dual nodes temporarily matched to the interface, whose matchings are broken by the fusion
+    pub interface_nodes: usize,
+    /// the fully grown edges incident to the interface vertices
+    pub tight_interface_edges: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct PrimalModuleParallelConfig {
@@ -106,6 +139,9 @@ pub struct PrimalModuleParallelConfig {
     /// schedule base partition tasks in the front
     #[serde(default = "primal_module_parallel_default_configs::prioritize_base_partition")]
     pub prioritize_base_partition: bool,
+    /// the units are scheduled as a dependency DAG: a fusion unit becomes runnable once both children are solved, and idle
+    /// threads steal runnable units; fusion `i` additionally waits until base partition `i + interleaving_base_fusion` is
+    /// started, so that by default all the base partitions are started before any fusion
     #[serde(default = "primal_module_parallel_default_configs::interleaving_base_fusion")]
     pub interleaving_base_fusion: usize,
     /// pin threads to cores sequentially
@@ -113,9 +149,35 @@ pub struct PrimalModuleParallelConfig {
     pub pin_threads_to_cores: bool,
     /// streaming decode mocker
     pub streaming_decode_mock_measure_interval: Option<f64>,
-    /// streaming decoder using spin lock instead of threads.sleep to avoid context switch
+    /// streaming decoder using spin lock instead of threads.sleep to avoid context switch when a base partition waits for
+    /// its mocked measurement time, which is the only wait of the DAG scheduler
     #[serde(default = "primal_module_parallel_default_configs::streaming_decode_use_spin_lock")]
     pub streaming_decode_use_spin_lock: bool,
+    /// watchdog: if any unit runs longer than this, or no progress is made in this time, the solve is aborted with a
+    /// [`SolverHang`] error containing a diagnostic dump instead of blocking forever; only the fallible
+    /// [`crate::mwpm_solver::PrimalDualSolver::solve_checked`] and [`crate::mwpm_solver::SolverParallel::try_solve`] are
+    /// guarded, so that a hang is an error the caller can handle. it's given in seconds in the JSON config
+    #[serde(default, with = "duration_seconds")]
+    pub hang_timeout: Option<Duration>,
+}
+
+/// (de)serialize an optional duration as a number of seconds, e.g. `{"hang_timeout": 0.5}`
+mod duration_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Option::<f64>::deserialize(deserializer)?
+            .map(|seconds| Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom))
+            .transpose()
+    }
 }
 
 impl Default for PrimalModuleParallelConfig {
@@ -151,29 +213,62 @@ pub struct StreamingDecodeMocker {
     pub bias: Duration,
 }
 
+/// build a thread pool with the given number of threads (0 means the number of CPU cores), optionally pinning threads to cores sequentially
+pub fn build_thread_pool(thread_pool_size: usize, pin_threads_to_cores: bool) -> rayon::ThreadPool {
+    let mut thread_pool_builder = rayon::ThreadPoolBuilder::new();
+    if thread_pool_size != 0 {
+        thread_pool_builder = thread_pool_builder.num_threads(thread_pool_size);
+    }
+    if pin_threads_to_cores {
+        let core_ids = core_affinity::get_core_ids().unwrap();
+        // println!("core_ids: {core_ids:?}");
+        thread_pool_builder = thread_pool_builder.start_handler(move |thread_index| {
+            // https://stackoverflow.com/questions/7274585/linux-find-out-hyper-threaded-core-id
+            if thread_index < core_ids.len() {
+                crate::core_affinity::set_for_current(core_ids[thread_index]);
+            } // otherwise let OS decide which core to execute
+        });
+    }
+    thread_pool_builder.build().expect("creating thread pool failed")
+}
+
 impl PrimalModuleParallel {
+    /// an analytic estimate of the memory in bytes, summing up the serial modules and interfaces of all units
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>() + self.units.capacity() * size_of::<PrimalModuleParallelUnitPtr>();
+        for unit_ptr in self.units.iter() {
+            let unit = unit_ptr.read_recursive();
+            bytes += size_of::<PrimalModuleParallelUnit>();
+            bytes += unit.serial_module.read_recursive().memory_estimate();
+            bytes += unit.interface_ptr.read_recursive().memory_estimate();
+        }
+        for summaries in self.interface_summaries.iter() {
+            bytes += size_of::<parking_lot::Mutex<Vec<BoundaryNodeSummary>>>();
+            bytes += summaries.lock().capacity() * size_of::<BoundaryNodeSummary>();
+        }
+        bytes
+    }
+
     /// recommended way to create a new instance, given a customized configuration
     pub fn new_config(
         initializer: &SolverInitializer,
         partition_info: &PartitionInfo,
         config: PrimalModuleParallelConfig,
+    ) -> Self {
+        let thread_pool = Arc::new(build_thread_pool(config.thread_pool_size, config.pin_threads_to_cores));
+        Self::new_config_with_thread_pool(initializer, partition_info, config, thread_pool)
+    }
+
+    /// create a new instance that runs all the parallel tasks in a caller-provided thread pool, e.g. an application-wide pool
+    /// shared with other modules to avoid oversubscription; `thread_pool_size` and `pin_threads_to_cores` in the config are ignored
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_config_with_thread_pool(
+        initializer: &SolverInitializer,
+        partition_info: &PartitionInfo,
+        config: PrimalModuleParallelConfig,
+        thread_pool: Arc<rayon::ThreadPool>,
     ) -> Self {
         let partition_info = Arc::new(partition_info.clone());
-        let mut thread_pool_builder = rayon::ThreadPoolBuilder::new();
-        if config.thread_pool_size != 0 {
-            thread_pool_builder = thread_pool_builder.num_threads(config.thread_pool_size);
-        }
-        if config.pin_threads_to_cores {
-            let core_ids = core_affinity::get_core_ids().unwrap();
-            // println!("core_ids: {core_ids:?}");
-            thread_pool_builder = thread_pool_builder.start_handler(move |thread_index| {
-                // https://stackoverflow.com/questions/7274585/linux-find-out-hyper-threaded-core-id
-                if thread_index < core_ids.len() {
-                    crate::core_affinity::set_for_current(core_ids[thread_index]);
-                } // otherwise let OS decide which core to execute
-            });
-        }
-        let thread_pool = thread_pool_builder.build().expect("creating thread pool failed");
         let mut units = vec![];
         let unit_count = partition_info.units.len();
         thread_pool.scope(|_| {
@@ -207,12 +302,28 @@ impl PrimalModuleParallel {
                 }
             }
         }
+        // a node can only be matched to an interface vertex through one of its edges, so each arena is bounded by the edges
+        // incident to the owning vertices of the unit; a base partition owns no interface
+        let mut summary_capacities = vec![0; unit_count];
+        for (vertex_1, vertex_2, _) in initializer.weighted_edges.iter() {
+            for vertex_index in [*vertex_1, *vertex_2] {
+                let owning_unit = partition_info.vertex_to_owning_unit[vertex_index as usize];
+                if owning_unit >= partition_info.config.partitions.len() {
+                    summary_capacities[owning_unit] += 1;
+                }
+            }
+        }
+        let interface_summaries = summary_capacities
+            .into_iter()
+            .map(|capacity| parking_lot::Mutex::new(Vec::with_capacity(capacity)))
+            .collect();
         Self {
             units,
             config,
             partition_info,
-            thread_pool: Arc::new(thread_pool),
+            thread_pool,
             last_solve_start_time: ArcRwLock::new_value(Instant::now()),
+            interface_summaries,
         }
     }
 }
@@ -237,6 +348,9 @@ impl PrimalModuleImpl for PrimalModuleParallel {
                 unit.is_active = is_active;
             });
         });
+        for summaries in self.interface_summaries.iter() {
+            summaries.lock().clear();
+        }
     }
 
     fn load_defect_dual_node(&mut self, _dual_node_ptr: &DualNodePtr) {
@@ -270,13 +384,36 @@ impl PrimalModuleImpl for PrimalModuleParallel {
 
     fn generate_profiler_report(&self) -> serde_json::Value {
         let event_time_vec: Vec<_> = self.units.iter().map(|ptr| ptr.read_recursive().event_time.clone()).collect();
+        // busy time of all threads over the total thread time from the start of solving to the last unit solved
+        let busy_time: f64 = event_time_vec
+            .iter()
+            .flatten()
+            .map(|event_time| event_time.end - event_time.start)
+            .sum();
+        let total_time = event_time_vec
+            .iter()
+            .flatten()
+            .map(|event_time| event_time.end)
+            .fold(0., f64::max)
+            * self.thread_pool.current_num_threads() as f64;
+        let scheduler_efficiency = if total_time > 0. { busy_time / total_time } else { 0. };
         json!({
             "event_time_vec": event_time_vec,
+            "scheduler_efficiency": scheduler_efficiency,
+            "fusions": self.fusion_statistics(),
         })
     }
 }
 
 impl PrimalModuleParallel {
+    /// the statistics of every fusion of the last solved syndrome, in the order of the fusion units
+    pub fn fusion_statistics(&self) -> Vec<FusionStatistics> {
+        self.units
+            .iter()
+            .filter_map(|unit_ptr| unit_ptr.read_recursive().fusion_statistics.clone())
+            .collect()
+    }
+
     pub fn parallel_solve<DualSerialModule: DualModuleImpl + Send + Sync>(
         &mut self,
         syndrome_pattern: &SyndromePattern,
@@ -292,38 +429,55 @@ impl PrimalModuleParallel {
         visualizer: Option<&mut Visualizer>,
     ) {
         if let Some(visualizer) = visualizer {
+            // a bundle visualizer keeps the snapshots of each leaf unit in a separate file
+            let leaf_unit_num = self.partition_info.config.partitions.len();
             self.parallel_solve_step_callback(
                 syndrome_pattern,
                 parallel_dual_module,
                 |interface_ptr, dual_module, primal_module, group_max_update_length| {
+                    let visualizer = if dual_module.unit_index < leaf_unit_num {
+                        visualizer.unit_visualizer(dual_module.unit_index).unwrap()
+                    } else {
+                        &mut *visualizer
+                    };
                     if let Some(group_max_update_length) = group_max_update_length {
                         if cfg!(debug_assertions) {
                             println!("group_max_update_length: {:?}", group_max_update_length);
                         }
                         if let Some(length) = group_max_update_length.get_none_zero_growth() {
                             visualizer
-                                .snapshot_combined(format!("grow {length}"), vec![interface_ptr, dual_module, primal_module])
+                                .snapshot_combined_category(
+                                    format!("grow {length}"),
+                                    SnapshotCategory::Growth,
+                                    vec![interface_ptr, dual_module, primal_module],
+                                )
                                 .unwrap();
                         } else {
-                            let first_conflict = format!("{:?}", group_max_update_length.peek().unwrap());
+                            let first_conflict = group_max_update_length.peek().unwrap();
                             visualizer
-                                .snapshot_combined(
-                                    format!("resolve {first_conflict}"),
+                                .snapshot_combined_category(
+                                    format!("resolve {first_conflict:?}"),
+                                    SnapshotCategory::of_conflict(first_conflict),
                                     vec![interface_ptr, dual_module, primal_module],
                                 )
                                 .unwrap();
                         };
                     } else {
                         visualizer
-                            .snapshot_combined("unit solved".to_string(), vec![interface_ptr, dual_module, primal_module])
+                            .snapshot_combined_category(
+                                "unit solved".to_string(),
+                                SnapshotCategory::Fusion,
+                                vec![interface_ptr, dual_module, primal_module],
+                            )
                             .unwrap();
                     }
                 },
             );
             let last_unit = self.units.last().unwrap().read_recursive();
             visualizer
-                .snapshot_combined(
+                .snapshot_combined_category(
                     "solved".to_string(),
+                    SnapshotCategory::Final,
                     vec![&last_unit.interface_ptr, parallel_dual_module, self],
                 )
                 .unwrap();
@@ -349,100 +503,34 @@ impl PrimalModuleParallel {
         *self.last_solve_start_time.write() = Instant::now();
         if self.config.prioritize_base_partition {
             if self.config.debug_sequential {
-                for unit_index in 0..self.partition_info.units.len() {
-                    let unit_ptr = self.units[unit_index].clone();
-                    unit_ptr.children_ready_solve::<DualSerialModule, F>(
-                        self,
-                        PartitionedSyndromePattern::new(syndrome_pattern),
-                        parallel_dual_module,
-                        &mut Some(&mut callback),
-                    );
-                }
+                thread_pool.install(|| {
+                    for unit_index in 0..self.partition_info.units.len() {
+                        let unit_ptr = self.units[unit_index].clone();
+                        unit_ptr.children_ready_solve::<DualSerialModule, F>(
+                            self,
+                            PartitionedSyndromePattern::new(syndrome_pattern),
+                            parallel_dual_module,
+                            &mut Some(&mut callback),
+                        );
+                    }
+                })
             } else {
-                use std::sync::atomic::{AtomicUsize, Ordering};
-                let ready_vec: Vec<_> = {
-                    (0..self.partition_info.units.len())
-                        .map(|_| Arc::new((Mutex::new(false), Condvar::new(), Arc::new(AtomicUsize::new(0)))))
-                        .collect()
+                let partition_num = self.partition_info.config.partitions.len();
+                let scheduler = DagScheduler {
+                    primal_module: self,
+                    syndrome_pattern,
+                    parallel_dual_module,
+                    pending_dependencies: (0..self.partition_info.units.len())
+                        .map(|unit_index| {
+                            // a fusion unit waits for its two children and the start of a base partition
+                            AtomicUsize::new(if unit_index < partition_num { 0 } else { 3 })
+                        })
+                        .collect(),
                 };
                 thread_pool.scope_fifo(|s| {
-                    let issue_unit = |unit_index: usize| {
-                        let ready_vec = &ready_vec;
-                        let units = &self.units;
-                        let partition_info = &self.partition_info;
-                        let parallel_unit = &self;
-                        let parallel_dual_module = &parallel_dual_module;
-                        let streaming_decode_use_spin_lock = self.config.streaming_decode_use_spin_lock;
-                        s.spawn_fifo(move |_| {
-                            let ready_pair = ready_vec[unit_index].clone();
-                            let (ready, condvar, spin_ready) = &*ready_pair;
-                            if streaming_decode_use_spin_lock {
-                                let unit_ptr = units[unit_index].clone();
-                                if unit_index >= partition_info.config.partitions.len() {
-                                    // wait for children to complete
-                                    let fusion_index = unit_index - partition_info.config.partitions.len();
-                                    let (left_unit_index, right_unit_index) = partition_info.config.fusions[fusion_index];
-                                    for child_unit_index in [left_unit_index, right_unit_index] {
-                                        let child_ready_pair = ready_vec[child_unit_index].clone();
-                                        let (_, _, child_spin_ready) = &*child_ready_pair;
-                                        while child_spin_ready.load(Ordering::SeqCst) != 1 {
-                                            // hopefully this asserts false at the beginning
-                                            std::hint::spin_loop();
-                                            // println!("spin_loop");
-                                        }
-                                    }
-                                }
-                                unit_ptr.children_ready_solve::<DualSerialModule, F>(
-                                    parallel_unit,
-                                    PartitionedSyndromePattern::new(syndrome_pattern),
-                                    parallel_dual_module,
-                                    &mut None,
-                                );
-                                spin_ready.store(1, Ordering::SeqCst);
-                            } else {
-                                let mut is_ready = ready.lock().unwrap();
-                                let unit_ptr = units[unit_index].clone();
-                                if unit_index >= partition_info.config.partitions.len() {
-                                    // wait for children to complete
-                                    let fusion_index = unit_index - partition_info.config.partitions.len();
-                                    let (left_unit_index, right_unit_index) = partition_info.config.fusions[fusion_index];
-                                    for child_unit_index in [left_unit_index, right_unit_index] {
-                                        let child_ready_pair = ready_vec[child_unit_index].clone();
-                                        let (child_ready, child_condvar, _) = &*child_ready_pair;
-                                        let mut child_is_ready = child_ready.lock().unwrap();
-                                        while !*child_is_ready {
-                                            // hopefully this asserts false at the beginning
-                                            child_is_ready = child_condvar.wait(child_is_ready).unwrap();
-                                        }
-                                    }
-                                }
-                                unit_ptr.children_ready_solve::<DualSerialModule, F>(
-                                    parallel_unit,
-                                    PartitionedSyndromePattern::new(syndrome_pattern),
-                                    parallel_dual_module,
-                                    &mut None,
-                                );
-                                *is_ready = true;
-                                condvar.notify_one();
-                            }
-                        })
-                    };
-                    if self.config.interleaving_base_fusion >= self.partition_info.config.fusions.len() {
-                        for unit_index in 0..self.partition_info.units.len() {
-                            issue_unit(unit_index);
-                        }
-                    } else {
-                        for unit_index in 0..self.partition_info.config.partitions.len() {
-                            if unit_index >= self.config.interleaving_base_fusion {
-                                let fusion_index = self.partition_info.config.partitions.len()
-                                    + (unit_index - self.config.interleaving_base_fusion);
-                                issue_unit(fusion_index);
-                            }
-                            issue_unit(unit_index);
-                        }
-                        for bias_index in 1..self.config.interleaving_base_fusion {
-                            issue_unit(self.partition_info.units.len() - self.config.interleaving_base_fusion + bias_index);
-                        }
+                    for unit_index in 0..partition_num {
+                        let scheduler = &scheduler;
+                        s.spawn_fifo(move |s| scheduler.execute(s, unit_index));
                     }
                 });
             }
@@ -460,6 +548,477 @@ impl PrimalModuleParallel {
     }
 }
 
+/// dependency-DAG executor of the units: leaves are spawned to the thread pool, and the thread that resolves the last
+/// dependency of a fusion unit continues to solve that fusion unit (or spawns it, if it's about to solve a base partition),
+/// so no thread is ever blocked waiting for other units
+struct DagScheduler<'a, DualSerialModule: DualModuleImpl + Send + Sync> {
+    primal_module: &'a PrimalModuleParallel,
+    syndrome_pattern: &'a SyndromePattern,
+    parallel_dual_module: &'a DualModuleParallel<DualSerialModule>,
+    /// the number of children of each unit that are not yet solved, plus one if its base partition gate is not yet started
+    pending_dependencies: Vec<AtomicUsize>,
+}
+
+impl<'a, DualSerialModule: DualModuleImpl + Send + Sync> DagScheduler<'a, DualSerialModule> {
+    /// the fusion indices that wait for the base partition `unit_index` to start, see
+    /// [`PrimalModuleParallelConfig::interleaving_base_fusion`]
+    fn gated_fusions(&self, unit_index: usize) -> std::ops::Range<usize> {
+        let partition_num = self.primal_module.partition_info.config.partitions.len();
+        let fusion_num = self.primal_module.partition_info.config.fusions.len();
+        let interleaving = self.primal_module.config.interleaving_base_fusion;
+        if unit_index >= partition_num {
+            0..0
+        } else if unit_index + 1 == partition_num {
+            // the fusions that would be issued after all the base partitions
+            unit_index.saturating_sub(interleaving).min(fusion_num)..fusion_num
+        } else if unit_index >= interleaving && unit_index - interleaving < fusion_num {
+            unit_index - interleaving..unit_index - interleaving + 1
+        } else {
+            0..0
+        }
+    }
+
+    /// returns true if this was the last dependency of the unit
+    fn resolve_dependency(&self, unit_index: usize) -> bool {
+        self.pending_dependencies[unit_index].fetch_sub(1, Ordering::AcqRel) == 1
+    }
+
+    fn execute<'s>(&'s self, scope: &rayon::ScopeFifo<'s>, mut unit_index: usize) {
+        let partition_num = self.primal_module.partition_info.config.partitions.len();
+        for fusion_index in self.gated_fusions(unit_index) {
+            let fusion_unit_index = partition_num + fusion_index;
+            if self.resolve_dependency(fusion_unit_index) {
+                // both children are already solved
+                scope.spawn_fifo(move |s| self.execute(s, fusion_unit_index));
+            }
+        }
+        loop {
+            let unit_ptr = self.primal_module.units[unit_index].clone();
+            unit_ptr.children_ready_solve::<DualSerialModule, StreamingDecodeCallback<DualSerialModule>>(
+                self.primal_module,
+                PartitionedSyndromePattern::new(self.syndrome_pattern),
+                self.parallel_dual_module,
+                &mut None,
+            );
+            match self.primal_module.partition_info.units[unit_index].parent {
+                // the sibling or the base partition gate resolves later and will continue with the parent
+                Some(parent_index) if self.resolve_dependency(parent_index) => {
+                    unit_index = parent_index;
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+/// the error returned by the watchdog when a parallel solve hangs
+#[derive(Debug, Clone)]
+pub struct SolverHang {
+    /// the unit that exceeds the timeout
+    pub unit_index: usize,
+    /// how long the unit has been running (or the solver has made no progress), in seconds
+    pub elapsed: f64,
+    /// per-unit state: phase, number of defects loaded, start and end time, and outstanding fusion dependencies
+    pub dump: serde_json::Value,
+}
+
+impl SolverHang {
+    /// write the diagnostic dump to a file, e.g. for attaching to a bug report
+    pub fn write_dump(&self, filename: &str) -> std::io::Result<()> {
+        let file = std::fs::File::create(filename)?;
+        serde_json::to_writer_pretty(
+            file,
+            &json!({
+                "unit_index": self.unit_index,
+                "elapsed": self.elapsed,
+                "dump": self.dump,
+            }),
+        )?;
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for SolverHang {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "parallel solver hangs at unit {} for {}s, diagnostic dump: {}",
+            self.unit_index, self.elapsed, self.dump
+        )
+    }
+}
+
+impl std::error::Error for SolverHang {}
+
+/// the state of streaming decoding, where the syndrome of each unit is pushed layer by layer as soon as it's measured;
+/// a unit is issued to the thread pool once it's complete and its children (if any) are solved
+#[derive(Debug, Clone)]
+pub struct StreamingDecodeState {
+    /// the defect vertices pushed to each unit so far
+    pub unit_syndromes: Vec<SyndromePattern>,
+    /// whether all the layers of the unit are pushed, see [`PrimalModuleParallel::streaming_complete`]
+    pub is_complete: Vec<bool>,
+    /// whether the unit has been issued to the thread pool
+    pub is_issued: Vec<bool>,
+    /// whether the unit has been solved
+    pub is_solved: Vec<bool>,
+    /// the time when each unit starts and ends solving, used by the watchdog
+    pub unit_start_time: Vec<Option<Instant>>,
+    pub unit_end_time: Vec<Option<Instant>>,
+    /// the time of the last event (push, start or end of a unit), used by the watchdog to detect a stalled schedule
+    pub last_event_time: Instant,
+    /// the number of issued tasks that are not yet returned
+    pub running_tasks: usize,
+    /// the time of the last [`PrimalModuleParallel::streaming_push`] or [`PrimalModuleParallel::streaming_complete`]
+    pub last_push_time: Option<Instant>,
+    /// the time when the root unit is solved
+    pub solved_time: Option<Instant>,
+    /// the unit whose task panicked and the panic message, which is raised again by [`PrimalModuleParallel::streaming_finish`]
+    pub panic: Option<(usize, String)>,
+}
+
+pub type StreamingDecodePtr = Arc<(Mutex<StreamingDecodeState>, Condvar)>;
+
+impl StreamingDecodeState {
+    /// whether the root unit is solved, or a task panicked so that it will never be, and no task is running
+    pub fn is_finished(&self) -> bool {
+        (*self.is_solved.last().unwrap() || self.panic.is_some()) && self.running_tasks == 0
+    }
+
+    /// time from the last pushed syndrome to the final result, in seconds
+    pub fn last_push_to_result(&self) -> Option<f64> {
+        match (self.last_push_time, self.solved_time) {
+            (Some(last_push_time), Some(solved_time)) => {
+                Some(solved_time.saturating_duration_since(last_push_time).as_secs_f64())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// callbacks are not supported in streaming decoding or DAG scheduling, because units are solved in arbitrary threads
+type StreamingDecodeCallback<DualSerialModule> = fn(
+    &DualModuleInterfacePtr,
+    &DualModuleParallelUnit<DualSerialModule>,
+    &PrimalModuleSerialPtr,
+    Option<&GroupMaxUpdateLength>,
+);
+
+impl PrimalModuleParallel {
+    /// start streaming decoding; the modules must be cleared before calling this function
+    pub fn streaming_begin(&self) -> StreamingDecodePtr {
+        *self.last_solve_start_time.write() = Instant::now();
+        let unit_count = self.partition_info.units.len();
+        Arc::new((
+            Mutex::new(StreamingDecodeState {
+                unit_syndromes: vec![SyndromePattern::new_empty(); unit_count],
+                is_complete: vec![false; unit_count],
+                is_issued: vec![false; unit_count],
+                is_solved: vec![false; unit_count],
+                unit_start_time: vec![None; unit_count],
+                unit_end_time: vec![None; unit_count],
+                last_event_time: Instant::now(),
+                running_tasks: 0,
+                last_push_time: None,
+                solved_time: None,
+                panic: None,
+            }),
+            Condvar::new(),
+        ))
+    }
+
+    /// append a layer of the syndrome of a unit, which must only contain defect vertices in the owning range of this unit;
+    /// a unit can be pushed any number of times until it's complete, see [`Self::streaming_complete`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn streaming_push(&self, streaming: &StreamingDecodePtr, unit_index: usize, mut syndrome_pattern: SyndromePattern) {
+        let owning_range = &self.partition_info.units[unit_index].owning_range;
+        for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+            assert!(
+                owning_range.contains(defect_vertex),
+                "defect vertex {} is not owned by unit {} with range {:?}",
+                defect_vertex,
+                unit_index,
+                owning_range
+            );
+        }
+        syndrome_pattern.defect_vertices.sort_unstable();
+        let (lock, _) = &**streaming;
+        let mut state = lock.lock().unwrap();
+        let defect_vertices = &state.unit_syndromes[unit_index].defect_vertices;
+        let duplicate = (syndrome_pattern.defect_vertices.windows(2).find(|pair| pair[0] == pair[1]))
+            .map(|pair| pair[0])
+            .or_else(|| {
+                (syndrome_pattern.defect_vertices.iter().copied())
+                    .find(|defect_vertex| defect_vertices.binary_search(defect_vertex).is_ok())
+            });
+        let is_complete = state.is_complete[unit_index];
+        if is_complete || duplicate.is_some() {
+            drop(state); // do not poison the lock
+            assert!(!is_complete, "unit {} is already complete", unit_index);
+            panic!("defect vertex {} of unit {} is pushed twice", duplicate.unwrap(), unit_index);
+        }
+        // partitioning requires ordered defect vertices
+        let defect_vertices = &mut state.unit_syndromes[unit_index].defect_vertices;
+        defect_vertices.extend(syndrome_pattern.defect_vertices);
+        defect_vertices.sort_unstable();
+        state.last_push_time = Some(Instant::now());
+        state.last_event_time = Instant::now();
+    }
+
+    /// mark that all the layers of a unit are pushed; it returns immediately and the unit is solved in the thread pool once
+    /// its children are solved
+    pub fn streaming_complete<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        streaming: &StreamingDecodePtr,
+        unit_index: usize,
+    ) {
+        let (lock, _) = &**streaming;
+        let mut state = lock.lock().unwrap();
+        if state.is_complete[unit_index] {
+            drop(state); // do not poison the lock
+            panic!("unit {} is already complete", unit_index);
+        }
+        state.last_push_time = Some(Instant::now());
+        state.last_event_time = Instant::now();
+        self.streaming_mark_complete(parallel_dual_module, streaming, &mut state, unit_index);
+    }
+
+    /// mark all the units as complete, so that the units that are never pushed have no defect vertices, and block until the
+    /// root unit is solved
+    pub fn streaming_finish<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        streaming: &StreamingDecodePtr,
+    ) {
+        let (lock, condvar) = &**streaming;
+        let mut state = lock.lock().unwrap();
+        self.streaming_issue_remaining(parallel_dual_module, streaming, &mut state);
+        while !state.is_finished() {
+            state = condvar.wait(state).unwrap();
+        }
+        if let Some((unit_index, message)) = state.panic.clone() {
+            drop(state); // do not poison the lock
+            panic!("streaming decoding of unit {unit_index} panicked: {message}");
+        }
+    }
+
+    /// the same as [`Self::streaming_finish`], but returns a [`SolverHang`] error if any unit runs longer than `hang_timeout`
+    /// or no progress is made within `hang_timeout`; the hung tasks keep running in the background and still hold the modules
+    pub fn streaming_finish_watchdog<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        streaming: &StreamingDecodePtr,
+        hang_timeout: Duration,
+    ) -> Result<(), SolverHang> {
+        let (lock, condvar) = &**streaming;
+        let mut state = lock.lock().unwrap();
+        self.streaming_issue_remaining(parallel_dual_module, streaming, &mut state);
+        while !state.is_finished() {
+            // the earliest running unit, or the first unsolved unit if nothing is running
+            let (unit_index, since) = (0..self.partition_info.units.len())
+                .filter(|&unit_index| state.unit_start_time[unit_index].is_some() && !state.is_solved[unit_index])
+                .map(|unit_index| (unit_index, state.unit_start_time[unit_index].unwrap()))
+                .min_by_key(|(_, start_time)| *start_time)
+                .unwrap_or_else(|| {
+                    let unit_index = state.is_solved.iter().position(|is_solved| !is_solved).unwrap_or(0);
+                    (unit_index, state.last_event_time)
+                });
+            let elapsed = since.elapsed();
+            if elapsed >= hang_timeout {
+                return Err(SolverHang {
+                    unit_index,
+                    elapsed: elapsed.as_secs_f64(),
+                    dump: self.streaming_diagnostic_dump(&state),
+                });
+            }
+            state = condvar.wait_timeout(state, hang_timeout - elapsed).unwrap().0;
+        }
+        if let Some((unit_index, message)) = state.panic.clone() {
+            drop(state); // do not poison the lock
+            panic!("streaming decoding of unit {unit_index} panicked: {message}");
+        }
+        Ok(())
+    }
+
+    /// solve the whole syndrome by pushing every unit to the streaming decoder, guarded by the watchdog
+    pub fn parallel_solve_watchdog<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        syndrome_pattern: &SyndromePattern,
+        hang_timeout: Duration,
+    ) -> Result<(), SolverHang> {
+        let streaming = self.streaming_begin();
+        for (unit_index, partial_syndrome) in self
+            .partition_info
+            .partition_syndrome_unordered(syndrome_pattern)
+            .into_iter()
+            .enumerate()
+        {
+            self.streaming_push(&streaming, unit_index, partial_syndrome);
+            self.streaming_complete(parallel_dual_module, &streaming, unit_index);
+        }
+        self.streaming_finish_watchdog(parallel_dual_module, &streaming, hang_timeout)
+    }
+
+    /// the state of each unit, for debugging a hung solver
+    pub fn streaming_diagnostic_dump(&self, state: &StreamingDecodeState) -> serde_json::Value {
+        let start_time = *self.last_solve_start_time.read_recursive();
+        let relative_time =
+            |time: &Option<Instant>| time.map(|time| time.saturating_duration_since(start_time).as_secs_f64());
+        let units: Vec<_> = (0..self.partition_info.units.len())
+            .map(|unit_index| {
+                let outstanding_children: Vec<usize> = match self.partition_info.units[unit_index].children {
+                    Some((left_child_index, right_child_index)) => [left_child_index, right_child_index]
+                        .into_iter()
+                        .filter(|&child_index| !state.is_solved[child_index])
+                        .collect(),
+                    None => vec![],
+                };
+                let phase = if state.is_solved[unit_index] {
+                    "solved"
+                } else if state.unit_start_time[unit_index].is_some() {
+                    "running"
+                } else if state.is_issued[unit_index] {
+                    "queued"
+                } else if !state.is_complete[unit_index] {
+                    "waiting for syndrome"
+                } else {
+                    "waiting for children"
+                };
+                json!({
+                    "unit_index": unit_index,
+                    "phase": phase,
+                    "defect_num": state.unit_syndromes[unit_index].defect_vertices.len(),
+                    "start": relative_time(&state.unit_start_time[unit_index]),
+                    "end": relative_time(&state.unit_end_time[unit_index]),
+                    "outstanding_children": outstanding_children,
+                    "parent": self.partition_info.units[unit_index].parent,
+                })
+            })
+            .collect();
+        json!({
+            "elapsed": start_time.elapsed().as_secs_f64(),
+            "last_event": relative_time(&Some(state.last_event_time)),
+            "running_tasks": state.running_tasks,
+            "units": units,
+        })
+    }
+
+    fn streaming_issue_remaining<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        streaming: &StreamingDecodePtr,
+        state: &mut StreamingDecodeState,
+    ) {
+        for unit_index in 0..self.partition_info.units.len() {
+            if !state.is_complete[unit_index] {
+                self.streaming_mark_complete(parallel_dual_module, streaming, state, unit_index);
+            }
+        }
+    }
+
+    fn streaming_mark_complete<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        streaming: &StreamingDecodePtr,
+        state: &mut StreamingDecodeState,
+        unit_index: usize,
+    ) {
+        state.is_complete[unit_index] = true;
+        self.streaming_try_issue(parallel_dual_module, streaming, state, unit_index);
+    }
+
+    fn streaming_try_issue<DualSerialModule: DualModuleImpl + Send + Sync + 'static>(
+        self: &Arc<Self>,
+        parallel_dual_module: &Arc<DualModuleParallel<DualSerialModule>>,
+        streaming: &StreamingDecodePtr,
+        state: &mut StreamingDecodeState,
+        unit_index: usize,
+    ) {
+        if state.is_issued[unit_index] || !state.is_complete[unit_index] {
+            return;
+        }
+        let syndrome_pattern = state.unit_syndromes[unit_index].clone();
+        if let Some((left_child_index, right_child_index)) = self.partition_info.units[unit_index].children {
+            if !state.is_solved[left_child_index] || !state.is_solved[right_child_index] {
+                return;
+            }
+        }
+        state.is_issued[unit_index] = true;
+        state.running_tasks += 1;
+        let primal_module = Arc::clone(self);
+        let dual_module = Arc::clone(parallel_dual_module);
+        let streaming = Arc::clone(streaming);
+        self.thread_pool.spawn(move || {
+            {
+                let mut state = streaming.0.lock().unwrap();
+                state.unit_start_time[unit_index] = Some(Instant::now());
+                state.last_event_time = Instant::now();
+            }
+            let unit_ptr = primal_module.units[unit_index].clone();
+            // a panic is recorded and raised by `streaming_finish`, which would otherwise wait forever
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                unit_ptr.children_ready_solve::<DualSerialModule, StreamingDecodeCallback<DualSerialModule>>(
+                    &primal_module,
+                    PartitionedSyndromePattern::new(&syndrome_pattern),
+                    &dual_module,
+                    &mut None,
+                );
+            }));
+            let (lock, condvar) = &*streaming;
+            if let Err(payload) = result {
+                let mut state = lock.lock().unwrap();
+                state.panic.get_or_insert((unit_index, panic_message(payload)));
+                state.last_event_time = Instant::now();
+            } else {
+                let mut state = lock.lock().unwrap();
+                state.is_solved[unit_index] = true;
+                state.unit_end_time[unit_index] = Some(Instant::now());
+                state.last_event_time = Instant::now();
+                match primal_module.partition_info.units[unit_index].parent {
+                    Some(parent_index) => {
+                        primal_module.streaming_try_issue(&dual_module, &streaming, &mut state, parent_index)
+                    }
+                    None => state.solved_time = Some(Instant::now()),
+                }
+            }
+            // release the modules before reporting, so that the caller can exclusively own them after `streaming_finish`
+            drop(unit_ptr);
+            drop(primal_module);
+            drop(dual_module);
+            let mut state = lock.lock().unwrap();
+            state.running_tasks -= 1;
+            condvar.notify_all();
+        });
+    }
+}
+
+impl PrimalModuleParallel {
+    /// move the summaries recorded by a solved unit to the arenas of the interfaces owning the mirrored vertices, together
+    /// with the side of the interface that the unit descends from
+    #[allow(clippy::unnecessary_cast)]
+    fn move_boundary_summaries(&self, unit_index: usize, serial_module: &PrimalModuleSerialPtr) {
+        let mut module = serial_module.write();
+        for mut summary in module.possible_break.drain(..) {
+            let owning_unit = self.partition_info.vertex_to_owning_unit[summary.virtual_vertex as usize];
+            let mut child = unit_index;
+            while let Some(parent) = self.partition_info.units[child]
+                .parent
+                .filter(|parent| *parent != owning_unit)
+            {
+                child = parent;
+            }
+            let (_, right_child) = self.partition_info.units[owning_unit]
+                .children
+                .expect("a mirrored vertex must be owned by an ancestor");
+            debug_assert_eq!(self.partition_info.units[child].parent, Some(owning_unit));
+            summary.parity = child == right_child;
+            self.interface_summaries[owning_unit].lock().push(summary);
+        }
+    }
+}
+
 impl FusionVisualizer for PrimalModuleParallel {
     fn snapshot(&self, abbrev: bool) -> serde_json::Value {
         // do the sanity check first before taking snapshot
@@ -490,6 +1049,7 @@ impl PrimalModuleParallelUnitPtr {
         let is_active = partition_unit_info.children.is_none();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         interface_ptr.write().unit_index = unit_index;
+        serial_module.write().unit_index = unit_index;
         Self::new_value(PrimalModuleParallelUnit {
             unit_index,
             interface_ptr,
@@ -499,7 +1059,12 @@ impl PrimalModuleParallelUnitPtr {
             children: None, // to be filled later
             parent: None,   // to be filled later
             event_time: None,
+            fusion_statistics: None,
             streaming_decode_mocker: None,
+            #[cfg(test)]
+            test_stall: None,
+            #[cfg(test)]
+            test_panic: false,
         })
     }
 
@@ -520,6 +1085,13 @@ impl PrimalModuleParallelUnitPtr {
         ),
     {
         let mut primal_unit = self.write();
+        trace_span!("solve_unit", unit_index = primal_unit.unit_index);
+        #[cfg(test)]
+        if let Some(stall) = primal_unit.test_stall {
+            std::thread::sleep(stall);
+        }
+        #[cfg(test)]
+        assert!(!primal_unit.test_panic, "test panic of unit {}", primal_unit.unit_index);
         if let Some(mocker) = &primal_unit.streaming_decode_mocker {
             if primal_module_parallel.config.streaming_decode_use_spin_lock {
                 while primal_module_parallel.last_solve_start_time.read_recursive().elapsed() < mocker.bias {
@@ -539,6 +1111,7 @@ impl PrimalModuleParallelUnitPtr {
             .read_recursive()
             .elapsed()
             .as_secs_f64();
+        event_time.fused = event_time.start;
         let dual_module_ptr = parallel_dual_module.get_unit(primal_unit.unit_index);
         let mut dual_unit = dual_module_ptr.write();
         let partition_unit_info = &primal_unit.partition_info.units[primal_unit.unit_index];
@@ -559,7 +1132,19 @@ impl PrimalModuleParallelUnitPtr {
                 // do callback before actually breaking the matched pairs, for ease of visualization
                 callback(&primal_unit.interface_ptr, &dual_unit, &primal_unit.serial_module, None);
             }
-            primal_unit.break_matching_with_mirror(dual_unit.deref_mut());
+            let interface_range = primal_unit.partition_info.units[primal_unit.unit_index].owning_range;
+            let tight_interface_edges = dual_unit.count_tight_edges(&interface_range);
+            let interface_nodes = primal_unit.break_matching_with_mirror(primal_module_parallel, dual_unit.deref_mut());
+            event_time.fused = primal_module_parallel
+                .last_solve_start_time
+                .read_recursive()
+                .elapsed()
+                .as_secs_f64();
+            primal_unit.fusion_statistics = Some(FusionStatistics {
+                unit_index: primal_unit.unit_index,
+                interface_nodes,
+                tight_interface_edges,
+            });
             for defect_index in owned_defect_range.whole_defect_range.iter() {
                 let defect_vertex = partitioned_syndrome_pattern.syndrome_pattern.defect_vertices[defect_index as usize];
                 primal_unit
@@ -595,6 +1180,7 @@ impl PrimalModuleParallelUnitPtr {
                 callback(&primal_unit.interface_ptr, &dual_unit, &primal_unit.serial_module, None);
             }
         }
+        primal_module_parallel.move_boundary_summaries(primal_unit.unit_index, &primal_unit.serial_module);
         primal_unit.is_active = true;
         event_time.end = primal_module_parallel
             .last_solve_start_time
@@ -691,37 +1277,50 @@ impl PrimalModuleParallelUnit {
         );
         let left_child = left_child_ptr.read_recursive();
         let right_child = right_child_ptr.read_recursive();
+        trace_span!(
+            "fuse",
+            unit_index = self.unit_index,
+            left_unit_index = left_child.unit_index,
+            right_unit_index = right_child.unit_index
+        );
         dual_unit.fuse(&self.interface_ptr, (&left_child.interface_ptr, &right_child.interface_ptr));
         self.serial_module.fuse(&left_child.serial_module, &right_child.serial_module);
     }
 
-    /// break the matched pairs of interface vertices
-    #[allow(clippy::unnecessary_cast)]
-    pub fn break_matching_with_mirror(&mut self, dual_module: &mut impl DualModuleImpl) {
-        // use `possible_break` to efficiently break those
-        let mut possible_break = vec![];
-        let module = self.serial_module.read_recursive();
-        for node_index in module.possible_break.iter() {
-            let primal_node_ptr = module.get_node(*node_index);
-            if let Some(primal_node_ptr) = primal_node_ptr {
-                let mut primal_node = primal_node_ptr.write();
-                if let Some((MatchTarget::VirtualVertex(vertex_index), _)) = &primal_node.temporary_match {
-                    if self.partition_info.vertex_to_owning_unit[*vertex_index as usize] == self.unit_index {
-                        primal_node.temporary_match = None;
-                        self.interface_ptr.set_grow_state(
-                            &primal_node.origin.upgrade_force(),
-                            DualNodeGrowState::Grow,
-                            dual_module,
-                        );
-                    } else {
-                        // still possible break
-                        possible_break.push(*node_index);
-                    }
-                }
+    /// break the matched pairs of interface vertices, returning the number of broken matchings
+    pub fn break_matching_with_mirror(
+        &mut self,
+        primal_module_parallel: &PrimalModuleParallel,
+        dual_module: &mut impl DualModuleImpl,
+    ) -> usize {
+        // only the summaries in the arena of this interface are visited, the left child first to be deterministic
+        let mut summaries = primal_module_parallel.interface_summaries[self.unit_index].lock();
+        summaries.sort_by_key(|summary| (summary.parity, summary.unit_index));
+        let mut broken = 0;
+        for summary in summaries.drain(..) {
+            debug_assert_ne!(summary.unit_index, self.unit_index, "a unit never records its own interface");
+            let serial_module = primal_module_parallel.units[summary.unit_index]
+                .read_recursive()
+                .serial_module
+                .clone();
+            let Some(primal_node_ptr) = serial_module.read_recursive().get_node(summary.node_index) else {
+                continue;
+            };
+            let mut primal_node = primal_node_ptr.write();
+            if primal_node.temporary_match.as_ref().map(|(target, _)| target)
+                != Some(&MatchTarget::VirtualVertex(summary.virtual_vertex))
+            {
+                continue; // the node has been matched to something else since
+            }
+            if primal_node.dual_variable(&self.interface_ptr) != summary.dual_variable {
+                continue; // out-of-date summary of a node matched again to the same vertex
             }
+            primal_node.temporary_match = None;
+            broken += 1;
+            self.interface_ptr
+                .set_grow_state(&primal_node.origin.upgrade_force(), DualNodeGrowState::Grow, dual_module);
         }
-        drop(module);
-        self.serial_module.write().possible_break = possible_break;
+        broken
     }
 }
 
@@ -780,7 +1379,7 @@ pub mod tests {
     {
         println!("{defect_vertices:?}");
         if let Some(reordered_vertices) = &reordered_vertices {
-            code.reorder_vertices(reordered_vertices);
+            code.reorder_vertices(reordered_vertices).unwrap();
             defect_vertices = translated_defect_to_reordered(reordered_vertices, &defect_vertices);
         }
         let mut visualizer = match visualize_filename.as_ref() {
@@ -805,7 +1404,7 @@ pub mod tests {
         let mut primal_config = PrimalModuleParallelConfig::default();
         primal_config.debug_sequential = true;
         let mut primal_module = PrimalModuleParallel::new_config(&initializer, &partition_info, primal_config);
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         primal_module.parallel_solve_visualizer(&code.get_syndrome(), &mut dual_module, visualizer.as_mut());
         let useless_interface_ptr = DualModuleInterfacePtr::new_empty(); // don't actually use it
         let perfect_matching = primal_module.perfect_matching(&useless_interface_ptr, &mut dual_module);
@@ -1115,4 +1714,392 @@ pub mod tests {
         let defect_vertices = vec![88, 89, 102, 103, 105, 106, 118, 120, 122, 134, 138]; // indices are before the reorder
         primal_module_parallel_debug_planar_code_common(15, visualize_filename, defect_vertices, 10);
     }
+
+    /// all the tasks should run inside a caller-provided thread pool, regardless of the scheduling strategy
+    #[test]
+    fn primal_module_parallel_injected_thread_pool_1() {
+        // cargo test primal_module_parallel_injected_thread_pool_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        use std::collections::HashSet;
+        let pool_thread_ids = Arc::new(Mutex::new(HashSet::new()));
+        let thread_pool = {
+            let pool_thread_ids = Arc::clone(&pool_thread_ids);
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .start_handler(move |_| {
+                    pool_thread_ids.lock().unwrap().insert(std::thread::current().id());
+                })
+                .build()
+                .unwrap()
+        };
+        let thread_pool = Arc::new(thread_pool);
+        let (d, noisy_measurements) = (5, 15);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new_tree(d, noisy_measurements, 8, true, usize::MAX);
+        let partition_info = partition.build_apply(&mut code).info();
+        assert_eq!(partition_info.config.partitions.len(), 8);
+        let initializer = code.get_initializer();
+        let mut serial_solver = SolverSerial::new(&initializer);
+        for prioritize_base_partition in [true, false] {
+            let mut dual_module = DualModuleParallel::<DualModuleSerial>::new_config_with_thread_pool(
+                &initializer,
+                &partition_info,
+                DualModuleParallelConfig::default(),
+                Arc::clone(&thread_pool),
+            );
+            let primal_config = PrimalModuleParallelConfig {
+                prioritize_base_partition,
+                ..Default::default()
+            };
+            let mut primal_module = PrimalModuleParallel::new_config_with_thread_pool(
+                &initializer,
+                &partition_info,
+                primal_config,
+                Arc::clone(&thread_pool),
+            );
+            for seed in 0..20 {
+                let syndrome_pattern = code.generate_random_errors(seed);
+                primal_module.parallel_solve(&syndrome_pattern, &mut dual_module);
+                let pool_thread_ids = pool_thread_ids.lock().unwrap();
+                assert!(pool_thread_ids.len() <= 2);
+                for unit_ptr in primal_module.units.iter() {
+                    let event_time = unit_ptr.read_recursive().event_time.clone().unwrap();
+                    assert!(
+                        pool_thread_ids.contains(&event_time.thread_id),
+                        "unit executed outside the injected thread pool"
+                    );
+                }
+                drop(pool_thread_ids);
+                let last_interface_ptr = primal_module.units.last().unwrap().read_recursive().interface_ptr.clone();
+                serial_solver.solve(&syndrome_pattern);
+                assert_eq!(last_interface_ptr.sum_dual_variables(), serial_solver.sum_dual_variables());
+                serial_solver.clear();
+                primal_module.clear();
+                dual_module.clear();
+            }
+        }
+    }
+
+    /// streaming decoding must give the same matching as batch decoding
+    #[test]
+    fn primal_module_parallel_streaming_1() {
+        // cargo test primal_module_parallel_streaming_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (5, 31);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new_tree(d, noisy_measurements, 8, true, usize::MAX);
+        let partition_info = partition.build_apply(&mut code).info();
+        let initializer = code.get_initializer();
+        let mut batch_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        let mut streaming_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        // push units in the order of time
+        let mut unit_order: Vec<usize> = (0..partition_info.units.len()).collect();
+        unit_order.sort_by_key(|&unit_index| partition_info.units[unit_index].owning_range.start());
+        for seed in 0..30 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            batch_solver.solve(&syndrome_pattern);
+            let batch_matching = batch_solver
+                .perfect_matching()
+                .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            let partitioned_syndrome = partition_info.partition_syndrome_unordered(&syndrome_pattern);
+            streaming_solver.begin_streaming();
+            for &unit_index in unit_order.iter() {
+                if partitioned_syndrome[unit_index].defect_vertices.is_empty() && seed % 2 == 0 {
+                    continue; // units without defects can also be left to `finish`
+                }
+                streaming_solver.push_layer(unit_index, partitioned_syndrome[unit_index].clone());
+                streaming_solver.complete_unit(unit_index);
+            }
+            let streaming_matching = streaming_solver
+                .finish()
+                .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            assert_eq!(streaming_solver.sum_dual_variables(), batch_solver.sum_dual_variables());
+            assert_eq!(streaming_matching, batch_matching);
+            assert!(streaming_solver.streaming_latency().is_some());
+            batch_solver.clear();
+            streaming_solver.clear();
+        }
+    }
+
+    /// pushing one measurement round at a time and completing each unit after its last round gives the same matching
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn primal_module_parallel_streaming_rounds_1() {
+        // cargo test primal_module_parallel_streaming_rounds_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (5, 31);
+        let round_vertex_num = d * (d + 1);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new_tree(d, noisy_measurements, 8, true, usize::MAX);
+        let partition_info = partition.build_apply(&mut code).info();
+        let initializer = code.get_initializer();
+        let mut batch_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        let mut streaming_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        let unit_num = partition_info.units.len();
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            batch_solver.solve(&syndrome_pattern);
+            let batch_matching = batch_solver
+                .perfect_matching()
+                .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            streaming_solver.begin_streaming();
+            let mut is_complete = vec![false; unit_num];
+            for round in 0..=noisy_measurements as usize {
+                let mut round_defect_vertices = vec![vec![]; unit_num];
+                for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+                    if defect_vertex as usize / round_vertex_num == round {
+                        round_defect_vertices[partition_info.vertex_to_owning_unit[defect_vertex as usize]]
+                            .push(defect_vertex);
+                    }
+                }
+                for (unit_index, defect_vertices) in round_defect_vertices.into_iter().enumerate() {
+                    if !defect_vertices.is_empty() {
+                        streaming_solver.push_layer(unit_index, SyndromePattern::new_vertices(defect_vertices));
+                    }
+                }
+                for unit_index in 0..unit_num {
+                    let owning_range = &partition_info.units[unit_index].owning_range;
+                    if !is_complete[unit_index] && owning_range.end() as usize <= (round + 1) * round_vertex_num {
+                        streaming_solver.complete_unit(unit_index);
+                        is_complete[unit_index] = true;
+                    }
+                }
+            }
+            assert!(is_complete.iter().all(|&is_complete| is_complete));
+            let streaming_matching = streaming_solver
+                .finish()
+                .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            assert_eq!(streaming_solver.sum_dual_variables(), batch_solver.sum_dual_variables());
+            assert_eq!(streaming_matching, batch_matching);
+            batch_solver.clear();
+            streaming_solver.clear();
+        }
+    }
+
+    /// the matching must not depend on the order in which the DAG scheduler executes the units
+    #[test]
+    fn primal_module_parallel_dag_scheduler_determinism_1() {
+        // cargo test primal_module_parallel_dag_scheduler_determinism_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (3, 15);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new_tree(d, noisy_measurements, 8, true, usize::MAX);
+        let partition_info = partition.build_apply(&mut code).info();
+        let initializer = code.get_initializer();
+        let syndrome_patterns: Vec<_> = (0..200).map(|seed| code.generate_random_errors(seed)).collect();
+        let mut expected_matchings: Option<Vec<String>> = None;
+        for thread_pool_size in [1, 2, 8] {
+            let mut solver =
+                SolverParallel::new(&initializer, &partition_info, json!({ "thread_pool_size": thread_pool_size }));
+            for _ in 0..10 {
+                let matchings: Vec<String> = syndrome_patterns
+                    .iter()
+                    .map(|syndrome_pattern| {
+                        solver.solve(syndrome_pattern);
+                        let mwpm_result = solver
+                            .perfect_matching()
+                            .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+                        solver.clear();
+                        serde_json::to_string(&mwpm_result).unwrap()
+                    })
+                    .collect();
+                match &expected_matchings {
+                    Some(expected_matchings) => assert_eq!(&matchings, expected_matchings),
+                    None => expected_matchings = Some(matchings),
+                }
+            }
+        }
+    }
+
+    /// the interleaving of base partitions and fusions and the spin lock of the mocked measurement only change the order of
+    /// the units, not the matching
+    #[test]
+    fn primal_module_parallel_dag_scheduler_interleaving_1() {
+        // cargo test primal_module_parallel_dag_scheduler_interleaving_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (3, 15);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new_tree(d, noisy_measurements, 8, true, usize::MAX);
+        let partition_info = partition.build_apply(&mut code).info();
+        let initializer = code.get_initializer();
+        let syndrome_patterns: Vec<_> = (0..20).map(|seed| code.generate_random_errors(seed)).collect();
+        let mut serial_solver = SolverSerial::new(&initializer);
+        let expected_weights: Vec<Weight> = syndrome_patterns
+            .iter()
+            .map(|syndrome_pattern| {
+                serial_solver.solve(syndrome_pattern);
+                let weight = serial_solver.sum_dual_variables();
+                serial_solver.clear();
+                weight
+            })
+            .collect();
+        for interleaving_base_fusion in [0, 1, 3, 7, usize::MAX] {
+            for streaming_decode_use_spin_lock in [false, true] {
+                let mut solver = SolverParallel::new(
+                    &initializer,
+                    &partition_info,
+                    json!({ "thread_pool_size": 2, "primal": {
+                        "interleaving_base_fusion": interleaving_base_fusion,
+                        "streaming_decode_mock_measure_interval": 1e-5,
+                        "streaming_decode_use_spin_lock": streaming_decode_use_spin_lock,
+                    } }),
+                );
+                for (syndrome_pattern, &expected_weight) in syndrome_patterns.iter().zip(expected_weights.iter()) {
+                    solver.solve(syndrome_pattern);
+                    assert_eq!(solver.sum_dual_variables(), expected_weight);
+                    solver.clear();
+                }
+            }
+        }
+    }
+
+    /// canonicalized serial and parallel matchings must be bit-identical
+    #[test]
+    fn primal_module_parallel_canonicalize_1() {
+        // cargo test primal_module_parallel_canonicalize_1 -- --nocapture
+        use super::super::example_partition::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (3, 7);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.02, 500);
+        let initializer = code.get_initializer();
+        let mut parallel_solvers: Vec<_> = [2, 4, 8]
+            .into_iter()
+            .map(|partition_num| {
+                let mut partition = PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, partition_num);
+                let partition_info = partition.build_apply(&mut code).info();
+                SolverParallel::new(&initializer, &partition_info, json!({}))
+            })
+            .collect();
+        let mut serial_solver = SolverSerial::new(&initializer);
+        for seed in 0..1000 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            serial_solver.solve(&syndrome_pattern);
+            let mut serial_matching = serial_solver.perfect_matching();
+            serial_matching.canonicalize(&initializer);
+            let expected = serial_matching.legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            serial_solver.clear();
+            for parallel_solver in parallel_solvers.iter_mut() {
+                parallel_solver.solve(&syndrome_pattern);
+                let mut parallel_matching = parallel_solver.perfect_matching();
+                parallel_matching.canonicalize(&initializer);
+                assert_eq!(
+                    parallel_matching.legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone()),
+                    expected
+                );
+                parallel_solver.clear();
+            }
+        }
+    }
+
+    /// the watchdog reports a stalled unit instead of blocking forever
+    #[test]
+    fn primal_module_parallel_watchdog_1() {
+        // cargo test primal_module_parallel_watchdog_1 -- --nocapture
+        use super::super::example_partition::*;
+        let (d, noisy_measurements) = (5, 7);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, 4);
+        let partition_info = Arc::new(partition.build_apply(&mut code).info());
+        let initializer = code.get_initializer();
+        let build_modules = || {
+            let dual_module = DualModuleParallel::<DualModuleSerial>::new_config(
+                &initializer,
+                &partition_info,
+                DualModuleParallelConfig::default(),
+            );
+            let primal_module =
+                PrimalModuleParallel::new_config(&initializer, &partition_info, PrimalModuleParallelConfig::default());
+            (primal_module, dual_module)
+        };
+        let hang_timeout = Duration::from_millis(200);
+        let syndrome_pattern = code.generate_random_errors(0);
+        // a normal solve finishes in time
+        let (primal_module, dual_module) = build_modules();
+        let (primal_module, dual_module) = (Arc::new(primal_module), Arc::new(dual_module));
+        primal_module
+            .parallel_solve_watchdog(&dual_module, &syndrome_pattern, hang_timeout)
+            .unwrap();
+        // stall the second partition
+        let (primal_module, dual_module) = build_modules();
+        primal_module.units[1].write().test_stall = Some(Duration::from_secs(2));
+        let (primal_module, dual_module) = (Arc::new(primal_module), Arc::new(dual_module));
+        let solver_hang = primal_module
+            .parallel_solve_watchdog(&dual_module, &syndrome_pattern, hang_timeout)
+            .unwrap_err();
+        println!("{solver_hang}");
+        assert_eq!(solver_hang.unit_index, 1);
+        assert!(solver_hang.elapsed >= 0.2);
+        let units = solver_hang.dump["units"].as_array().unwrap();
+        assert_eq!(units.len(), 7);
+        assert_eq!(units[1]["phase"], "running");
+        let partitioned_syndrome = partition_info.partition_syndrome_unordered(&syndrome_pattern);
+        assert_eq!(units[1]["defect_num"], partitioned_syndrome[1].defect_vertices.len());
+        assert_eq!(units[4]["phase"], "waiting for children"); // fusing 0 and 1
+        assert_eq!(units[4]["outstanding_children"], json!([1]));
+        assert_eq!(units[6]["phase"], "waiting for children");
+        // the dump can be attached to a bug report
+        let filename = std::env::temp_dir().join("fusion_blossom_watchdog_1.json");
+        let filename = filename.to_str().unwrap();
+        solver_hang.write_dump(filename).unwrap();
+        let written: serde_json::Value = serde_json::from_reader(std::fs::File::open(filename).unwrap()).unwrap();
+        assert_eq!(written["unit_index"], 1);
+        assert_eq!(written["dump"], solver_hang.dump);
+    }
+
+    /// a panic in a streaming task must be raised by `streaming_finish` instead of blocking it forever
+    #[test]
+    fn primal_module_parallel_streaming_panic_1() {
+        // cargo test primal_module_parallel_streaming_panic_1 -- --nocapture
+        use super::super::example_partition::*;
+        let (d, noisy_measurements) = (5, 10);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.03, 500);
+        let mut partition = PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, 4);
+        let partition_info = Arc::new(partition.build_apply(&mut code).info());
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(0);
+        for watchdog in [false, true] {
+            let dual_module = DualModuleParallel::<DualModuleSerial>::new_config(
+                &initializer,
+                &partition_info,
+                DualModuleParallelConfig::default(),
+            );
+            let primal_module =
+                PrimalModuleParallel::new_config(&initializer, &partition_info, PrimalModuleParallelConfig::default());
+            primal_module.units[1].write().test_panic = true;
+            let (primal_module, dual_module) = (Arc::new(primal_module), Arc::new(dual_module));
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                if watchdog {
+                    primal_module
+                        .parallel_solve_watchdog(&dual_module, &syndrome_pattern, Duration::from_secs(60))
+                        .unwrap();
+                } else {
+                    let streaming = primal_module.streaming_begin();
+                    primal_module.streaming_finish(&dual_module, &streaming);
+                }
+            }));
+            let message = panic_message(result.unwrap_err());
+            assert!(message.contains("unit 1"), "{message}");
+            assert!(message.contains("test panic of unit 1"), "{message}");
+        }
+        // the solver is usable again after clearing
+        use super::super::mwpm_solver::*;
+        let mut solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        solver.primal_module.units[1].write().test_panic = true;
+        solver.begin_streaming();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            solver.finish();
+        }));
+        assert!(panic_message(result.unwrap_err()).contains("test panic of unit 1"));
+        solver.primal_module.units[1].write().test_panic = false;
+        solver.clear();
+        solver.solve(&syndrome_pattern);
+        let mut serial_solver = SolverSerial::new(&initializer);
+        serial_solver.solve(&syndrome_pattern);
+        assert_eq!(solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+    }
 }
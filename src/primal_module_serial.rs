@@ -11,6 +11,7 @@ use super::primal_module::*;
 use super::util::*;
 use super::visualize::*;
 use crate::derivative::Derivative;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
 #[derive(Derivative)]
@@ -24,8 +25,10 @@ pub struct PrimalModuleSerial {
     pub nodes_length: usize,
     /// allow pointer reuse will reduce the time of reallocation, but it's unsafe if not owning it
     pub is_fusion: bool,
-    /// the indices of primal nodes that is possibly matched to the mirrored vertex, and need to break when mirrored vertices are no longer mirrored
-    pub possible_break: Vec<NodeIndex>,
+    /// the primal nodes matched to mirrored vertices while solving this unit, which need to break when the mirrored vertices
+    /// are no longer mirrored; the parallel primal module moves them to the arena of the interface owning the vertex once the
+    /// unit is solved, see [`crate::primal_module_parallel::PrimalModuleParallel::interface_summaries`]
+    pub possible_break: Vec<BoundaryNodeSummary>,
     /// debug mode: only resolve one conflict each time
     pub debug_resolve_only_one: bool,
     /// the parent of this serial module, when fused
@@ -35,6 +38,88 @@ pub struct PrimalModuleSerial {
     /// the two children of this serial module, when fused; following the length of this child,
     /// given that fused children serial modules will not have new nodes anymore
     pub children: Option<((PrimalModuleSerialWeak, NodeNum), (PrimalModuleSerialWeak, NodeNum))>,
+    /// record the resolved conflicts if set, see [`SolverEventLog`]
+    pub event_log: Option<SolverEventLog>,
+}
+
+/// a compact summary of a primal node matched to a mirrored virtual vertex, which is all that the interface owning the
+/// vertex needs from the other units when it fuses them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundaryNodeSummary {
+    /// the unit whose serial module matched the node
+    pub unit_index: usize,
+    /// the index of the primal node in the serial module of `unit_index`, which stays valid after that module is fused
+    pub node_index: NodeIndex,
+    /// the mirrored virtual vertex it's matched to; the unit owning this vertex is the one to break the matching
+    pub virtual_vertex: VertexIndex,
+    /// the dual variable of the node when matched, which doesn't change as long as the node stays matched; a summary with
+    /// a different dual variable is out of date because the node has been matched again since
+    pub dual_variable: Weight,
+    /// the parity of the child of the interface that `unit_index` descends from, `false` for the left child and `true` for
+    /// the right one; set when the summary is moved to the arena of the interface
+    pub parity: bool,
+}
+
+/// a conflict that is actually resolved by the primal module, stamped with the total growth (see
+/// [`DualModuleInterface::total_growth`]) when it happens; out-of-date conflicts are not recorded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverEvent {
+    /// two nodes touch each other; `edge` is the fully grown edge between them if the dual module keeps track of it
+    Conflict {
+        node_a: NodeIndex,
+        node_b: NodeIndex,
+        edge: Option<EdgeIndex>,
+        growth: Weight,
+    },
+    /// a node touches a virtual vertex
+    TouchingVirtual {
+        node: NodeIndex,
+        vertex: VertexIndex,
+        growth: Weight,
+    },
+    /// an odd circle of nodes becomes a new blossom node
+    BlossomCreated {
+        blossom: NodeIndex,
+        nodes: Vec<NodeIndex>,
+        growth: Weight,
+    },
+    /// a blossom node with zero dual variable is expanded into its circle of nodes
+    BlossomExpanded { node: NodeIndex, growth: Weight },
+}
+
+/// the events of the primal module in the order they happen, with bounded memory: events beyond the capacity are dropped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolverEventLog {
+    pub events: Vec<SolverEvent>,
+    /// the maximum number of events
+    pub capacity: usize,
+    /// whether some events are dropped because of the capacity
+    pub truncated: bool,
+}
+
+impl SolverEventLog {
+    pub const DEFAULT_CAPACITY: usize = 1 << 20;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: vec![],
+            capacity,
+            truncated: false,
+        }
+    }
+
+    pub fn push(&mut self, event: SolverEvent) {
+        if self.events.len() < self.capacity {
+            self.events.push(event);
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.truncated = false;
+    }
 }
 
 pub type PrimalModuleSerialPtr = ArcManualSafeLock<PrimalModuleSerial>;
@@ -89,8 +174,16 @@ pub struct PrimalNodeInternal {
     pub belonging: PrimalModuleSerialWeak,
 }
 
-pub type PrimalNodeInternalPtr = ArcManualSafeLock<PrimalNodeInternal>;
-pub type PrimalNodeInternalWeak = WeakManualSafeLock<PrimalNodeInternal>;
+cfg_if::cfg_if! {
+    if #[cfg(feature="slab_nodes")] {
+        pub type PrimalNodeInternalPtr = SlabArc<PrimalNodeInternal>;
+        pub type PrimalNodeInternalWeak = SlabWeak<PrimalNodeInternal>;
+        slab_allocated!(PrimalNodeInternal);
+    } else {
+        pub type PrimalNodeInternalPtr = ArcSerialLock<PrimalNodeInternal>;
+        pub type PrimalNodeInternalWeak = WeakSerialLock<PrimalNodeInternal>;
+    }
+}
 
 impl std::fmt::Debug for PrimalNodeInternalPtr {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -107,6 +200,13 @@ impl std::fmt::Debug for PrimalNodeInternalWeak {
 }
 
 impl PrimalNodeInternal {
+    /// the current dual variable of the node
+    pub fn dual_variable(&self, interface_ptr: &DualModuleInterfacePtr) -> Weight {
+        let origin_ptr = self.origin.upgrade_force();
+        let node = origin_ptr.read_recursive();
+        node.get_dual_variable(&interface_ptr.read_recursive())
+    }
+
     /// check if in the cache, this node is a free node
     pub fn is_free(&self) -> bool {
         debug_assert!(
@@ -170,6 +270,7 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
             parent: None,
             index_bias: 0,
             children: None,
+            event_log: None,
         })
     }
 
@@ -181,6 +282,9 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
         module.parent = None;
         module.index_bias = 0;
         module.children = None;
+        if let Some(event_log) = module.event_log.as_mut() {
+            event_log.clear();
+        }
     }
 
     fn load_defect_dual_node(&mut self, dual_node_ptr: &DualNodePtr) {
@@ -271,6 +375,12 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                         );
                         continue; // this is no longer a conflict
                     }
+                    self.record_event(interface_ptr, |growth| SolverEvent::Conflict {
+                        node_a: node_ptr_1.read_recursive().index,
+                        node_b: node_ptr_2.read_recursive().index,
+                        edge: dual_module.find_tight_edge(&touching_ptr_1, &touching_ptr_2),
+                        growth,
+                    });
                     // this is the most probable case, so put it in the front
                     let (free_1, free_2) = (primal_node_internal_1.is_free(), primal_node_internal_2.is_free());
                     if free_1 && free_2 {
@@ -636,8 +746,15 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                                 }
                                 touching_children
                             };
+                            let nodes_circle_indices: Vec<NodeIndex> =
+                                nodes_circle.iter().map(|ptr| ptr.read_recursive().index).collect();
                             let blossom_node_ptr =
                                 interface_ptr.create_blossom(nodes_circle, touching_children, dual_module);
+                            self.record_event(interface_ptr, |growth| SolverEvent::BlossomCreated {
+                                blossom: blossom_node_ptr.read_recursive().index,
+                                nodes: nodes_circle_indices,
+                                growth,
+                            });
                             let primal_node_internal_blossom_ptr = {
                                 // create the corresponding primal node
                                 let belonging = self.downgrade();
@@ -799,13 +916,25 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                         );
                         continue; // this is no longer a conflict
                     }
+                    self.record_event(interface_ptr, |growth| SolverEvent::TouchingVirtual {
+                        node: node_ptr.read_recursive().index,
+                        vertex: virtual_vertex_index,
+                        growth,
+                    });
                     // this is the most probable case, so put it in the front
                     if primal_node_internal.is_free() {
                         primal_node_internal.temporary_match =
                             Some((MatchTarget::VirtualVertex(virtual_vertex_index), touching_ptr.downgrade()));
                         if is_mirror {
                             lock_write!(module, self);
-                            module.possible_break.push(primal_node_internal.index);
+                            let unit_index = module.unit_index;
+                            module.possible_break.push(BoundaryNodeSummary {
+                                unit_index,
+                                node_index: primal_node_internal.index,
+                                virtual_vertex: virtual_vertex_index,
+                                dual_variable: primal_node_internal.dual_variable(interface_ptr),
+                                parity: false,
+                            });
                         }
                         interface_ptr.set_grow_state(
                             &primal_node_internal.origin.upgrade_force(),
@@ -818,7 +947,14 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                     if primal_node_internal.tree_node.is_some() {
                         if is_mirror {
                             lock_write!(module, self);
-                            module.possible_break.push(primal_node_internal.index);
+                            let unit_index = module.unit_index;
+                            module.possible_break.push(BoundaryNodeSummary {
+                                unit_index,
+                                node_index: primal_node_internal.index,
+                                virtual_vertex: virtual_vertex_index,
+                                dual_variable: primal_node_internal.dual_variable(interface_ptr),
+                                parity: false,
+                            });
                         }
                         drop(primal_node_internal);
                         self.augment_tree_given_virtual_vertex(
@@ -921,6 +1057,10 @@ impl PrimalModuleImpl for PrimalModuleSerialPtr {
                             child_touching_child_ptr,
                         )
                     };
+                    self.record_event(interface_ptr, |growth| SolverEvent::BlossomExpanded {
+                        node: node_ptr.read_recursive().index,
+                        growth,
+                    });
                     interface_ptr.expand_blossom(node_ptr, dual_module);
                     // now we need to re-connect all the expanded nodes, by analyzing the relationship of nodes_circle, parent_touching_ptr and child_touching_ptr
                     let parent_touching_index = nodes_circle
@@ -1237,6 +1377,15 @@ impl FusionVisualizer for PrimalModuleSerialPtr {
 }
 
 impl PrimalModuleSerial {
+    /// an analytic estimate of the memory in bytes, see [`crate::dual_module_serial::DualModuleSerial::memory_estimate`]
+    pub fn memory_estimate(&self) -> usize {
+        let mut bytes = size_of::<Self>();
+        bytes += self.nodes.capacity() * size_of::<Option<PrimalNodeInternalPtr>>();
+        bytes += self.nodes.iter().flatten().count() * size_of::<PrimalNodeInternal>();
+        bytes += self.possible_break.capacity() * size_of::<BoundaryNodeSummary>();
+        bytes
+    }
+
     /// return the count of all nodes including those of the children interfaces
     pub fn nodes_count(&self) -> NodeNum {
         let mut count = self.nodes_length as NodeNum;
@@ -1292,6 +1441,15 @@ impl PrimalModuleSerial {
 }
 
 impl PrimalModuleSerialPtr {
+    /// the event is only constructed when the event log is enabled, given the current total growth
+    fn record_event(&self, interface_ptr: &DualModuleInterfacePtr, event: impl FnOnce(Weight) -> SolverEvent) {
+        if self.read_recursive().event_log.is_none() {
+            return;
+        }
+        let event = event(interface_ptr.read_recursive().total_growth());
+        self.write().event_log.as_mut().unwrap().push(event);
+    }
+
     pub fn get_primal_node_internal_ptr_option(&self, dual_node_ptr: &DualNodePtr) -> Option<PrimalNodeInternalPtr> {
         let module = self.read_recursive();
         let dual_node = dual_node_ptr.read_recursive();
@@ -1578,6 +1736,43 @@ impl PrimalModuleSerialPtr {
         debug_assert_eq!(flattened_nodes.len() - flattened_nodes_length, module.nodes_count() as usize);
     }
 
+    /// the matching of the outer nodes that are matched outside of any alternating tree, and the other outer nodes; unlike
+    /// [`PrimalModuleImpl::intermediate_matching`] it accepts a primal module whose growth stops early, see
+    /// [`PrimalModuleImpl::solve_interface_loaded_growth_capped`]
+    pub fn partial_matching(&self) -> (IntermediateMatching, Vec<DualNodePtr>) {
+        let mut intermediate_matching = IntermediateMatching::new();
+        let mut unmatched_nodes = vec![];
+        let mut flattened_nodes = vec![];
+        self.flatten_nodes(&mut flattened_nodes);
+        for primal_node_internal_ptr in flattened_nodes.iter().flatten() {
+            let primal_node_internal = primal_node_internal_ptr.read_recursive();
+            let origin_ptr = primal_node_internal.origin.upgrade_force();
+            if origin_ptr.read_recursive().parent_blossom.is_some() {
+                continue; // handled by the outer blossom
+            }
+            match (&primal_node_internal.tree_node, &primal_node_internal.temporary_match) {
+                (None, Some((MatchTarget::Peer(peer_internal_weak), match_touching_weak))) => {
+                    let peer_internal_ptr = peer_internal_weak.upgrade_force();
+                    let peer_internal = peer_internal_ptr.read_recursive();
+                    if primal_node_internal.index < peer_internal.index {
+                        let peer_touching_weak = peer_internal.temporary_match.as_ref().unwrap().1.clone();
+                        intermediate_matching.peer_matchings.push((
+                            (origin_ptr, match_touching_weak.clone()),
+                            (peer_internal.origin.upgrade_force(), peer_touching_weak),
+                        ));
+                    }
+                }
+                (None, Some((MatchTarget::VirtualVertex(virtual_vertex), match_touching_weak))) => {
+                    intermediate_matching
+                        .virtual_matchings
+                        .push(((origin_ptr, match_touching_weak.clone()), *virtual_vertex));
+                }
+                _ => unmatched_nodes.push(origin_ptr),
+            }
+        }
+        (intermediate_matching, unmatched_nodes)
+    }
+
     /// fuse two modules by copying the nodes in `other` into myself
     #[allow(clippy::unnecessary_cast)]
     pub fn slow_fuse(&self, left: &Self, right: &Self) {
@@ -1600,10 +1795,8 @@ impl PrimalModuleSerialPtr {
                 }
                 module.nodes[(bias + other_node_index) as usize] = node_ptr.clone();
             }
-            // copy `possible_break`
-            for node_index in other_module.possible_break.iter() {
-                module.possible_break.push(*node_index + bias);
-            }
+            // copy `possible_break`, whose node indices are relative to the module that recorded them
+            module.possible_break.extend_from_slice(&other_module.possible_break);
         }
     }
 
@@ -1628,12 +1821,8 @@ impl PrimalModuleSerialPtr {
             (left_weak, left_module.nodes_count()),
             (right_weak, right_module.nodes_count()),
         ));
-        // the possible_break should be a small subset, thus copying them is fine
-        for other_module in [left_module, right_module] {
-            for node_index in other_module.possible_break.iter() {
-                module.possible_break.push(*node_index + other_module.index_bias);
-            }
-        }
+        // the summaries of the children are already moved to the arenas of the interfaces when they are solved
+        debug_assert!(left_module.possible_break.is_empty() && right_module.possible_break.is_empty());
     }
 
     /// do a sanity check of it's tree structure and internal state
@@ -1823,7 +2012,7 @@ impl PrimalModuleSerialPtr {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "minimal")))]
 pub mod tests {
     use super::super::dual_module_serial::*;
     use super::super::example_codes::*;
@@ -1859,7 +2048,7 @@ pub mod tests {
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         primal_module.write().debug_resolve_only_one = true; // to enable debug mode
                                                              // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, visualizer.as_mut());
         let perfect_matching = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -2047,7 +2236,7 @@ pub mod tests {
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         primal_module.write().debug_resolve_only_one = true; // to enable debug mode
                                                              // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
         let fusion_mwpm = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -2112,7 +2301,7 @@ pub mod tests {
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         primal_module.write().debug_resolve_only_one = true; // to enable debug mode
                                                              // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
         let fusion_mwpm = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -2177,7 +2366,7 @@ pub mod tests {
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         primal_module.write().debug_resolve_only_one = true; // to enable debug mode
                                                              // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
         let fusion_mwpm = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -2239,7 +2428,7 @@ pub mod tests {
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         primal_module.write().debug_resolve_only_one = true; // to enable debug mode
                                                              // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
         let fusion_mwpm = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -2300,7 +2489,7 @@ pub mod tests {
         // create primal module
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
         let fusion_mwpm = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -2379,7 +2568,7 @@ pub mod tests {
         // create primal module
         let mut primal_module = PrimalModuleSerialPtr::new_empty(&initializer);
         // try to work on a simple syndrome
-        code.set_defect_vertices(&defect_vertices);
+        code.set_defect_vertices(&defect_vertices).unwrap();
         let interface_ptr = DualModuleInterfacePtr::new_empty();
         primal_module.solve_visualizer(&interface_ptr, &code.get_syndrome(), &mut dual_module, Some(&mut visualizer));
         let fusion_mwpm = primal_module.perfect_matching(&interface_ptr, &mut dual_module);
@@ -0,0 +1,171 @@
+//! Simulator
+//!
+//! Random errors and erasures sampled on a [`SolverInitializer`] alone, for decoding graphs that are not built as an
+//! [`crate::example_codes::ExampleCode`], e.g. the ones converted from a detector error model or a check matrix.
+//! The samples are exactly the same as [`crate::example_codes::ExampleCode::generate_random_errors`] given the same
+//! probabilities and seed.
+//!
+
+use super::util::*;
+use crate::rand_xoshiro::rand_core::SeedableRng;
+#[cfg(feature = "python_binding")]
+use pyo3::prelude::*;
+
+/// samples the errors of each edge independently given the error and erasure probabilities of the edges
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass)]
+pub struct InitializerSampler {
+    /// the two vertices incident to each edge
+    edges: Vec<(VertexIndex, VertexIndex)>,
+    /// virtual vertices never become defects
+    is_virtual: Vec<bool>,
+    /// the probability of an error on each edge when it's not erased
+    edge_probabilities: Vec<f64>,
+    /// the probability that each edge is erased, in which case the error probability is 50%
+    edge_erasure_probabilities: Vec<f64>,
+}
+
+impl InitializerSampler {
+    /// fails if the number of probabilities doesn't match the number of edges or a probability is not in `[0, 1]`
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(
+        initializer: &SolverInitializer,
+        edge_probabilities: &[f64],
+        edge_erasure_probabilities: &[f64],
+    ) -> Result<Self, String> {
+        let edge_num = initializer.weighted_edges.len();
+        for (name, probabilities) in [
+            ("edge_probabilities", edge_probabilities),
+            ("edge_erasure_probabilities", edge_erasure_probabilities),
+        ] {
+            if probabilities.len() != edge_num {
+                return Err(format!(
+                    "{} has {} elements but the initializer has {} edges",
+                    name,
+                    probabilities.len(),
+                    edge_num
+                ));
+            }
+            if let Some((edge_index, p)) = probabilities.iter().enumerate().find(|(_, p)| !(0. ..=1.).contains(*p)) {
+                return Err(format!("{name}[{edge_index}] = {p} is not a probability"));
+            }
+        }
+        let mut is_virtual = vec![false; initializer.vertex_num as usize];
+        for &vertex_index in initializer.virtual_vertices.iter() {
+            is_virtual[vertex_index as usize] = true;
+        }
+        Ok(Self {
+            edges: initializer.weighted_edges.iter().map(|&(v1, v2, _)| (v1, v2)).collect(),
+            is_virtual,
+            edge_probabilities: edge_probabilities.to_vec(),
+            edge_erasure_probabilities: edge_erasure_probabilities.to_vec(),
+        })
+    }
+
+    /// the syndrome pattern with the erasures, and the edges with errors as the ground truth
+    #[allow(clippy::unnecessary_cast)]
+    pub fn sample(&self, seed: u64) -> (SyndromePattern, Vec<EdgeIndex>) {
+        let mut rng = DeterministicRng::seed_from_u64(seed);
+        let mut is_defect = vec![false; self.is_virtual.len()];
+        let mut erasures = vec![];
+        let mut error_edges = vec![];
+        for (edge_index, &(v1, v2)) in self.edges.iter().enumerate() {
+            // the random numbers are drawn in the same order as `ExampleCode::generate_random_errors`
+            let p = if rng.next_f64() < self.edge_erasure_probabilities[edge_index] {
+                erasures.push(edge_index as EdgeIndex);
+                0.5 // when erasure happens, there are 50% chance of error
+            } else {
+                self.edge_probabilities[edge_index]
+            };
+            if rng.next_f64() < p {
+                error_edges.push(edge_index as EdgeIndex);
+                for vertex_index in [v1, v2] {
+                    if !self.is_virtual[vertex_index as usize] {
+                        is_defect[vertex_index as usize] ^= true;
+                    }
+                }
+            }
+        }
+        let defect_vertices = (0..is_defect.len() as VertexIndex)
+            .filter(|&vertex_index| is_defect[vertex_index as usize])
+            .collect();
+        (SyndromePattern::new(defect_vertices, erasures), error_edges)
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[pymethods]
+impl InitializerSampler {
+    #[new]
+    fn py_new(
+        initializer: &SolverInitializer,
+        edge_probabilities: Vec<f64>,
+        edge_erasure_probabilities: Vec<f64>,
+    ) -> PyResult<Self> {
+        Self::new(initializer, &edge_probabilities, &edge_erasure_probabilities)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+    #[pyo3(name = "sample")]
+    fn py_sample(&self, seed: u64) -> (SyndromePattern, Vec<EdgeIndex>) {
+        self.sample(seed)
+    }
+    fn __repr__(&self) -> String {
+        format!("InitializerSampler {{ edge_num: {} }}", self.edges.len())
+    }
+}
+
+#[cfg(feature = "python_binding")]
+#[pyfunction]
+pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<InitializerSampler>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::example_codes::*;
+
+    #[test]
+    fn simulator_initializer_sampler_1() {
+        // cargo test simulator_initializer_sampler_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        code.set_erasure_probability(0.05);
+        // non-uniform probabilities, including zero and certain errors
+        for (edge_index, edge) in code.edges.iter_mut().enumerate() {
+            match edge_index % 5 {
+                0 => edge.p = 0.,
+                1 => edge.pe = 1.,
+                2 => edge.p = 0.3,
+                _ => {}
+            }
+        }
+        let edge_probabilities: Vec<f64> = code.edges.iter().map(|edge| edge.p).collect();
+        let edge_erasure_probabilities: Vec<f64> = code.edges.iter().map(|edge| edge.pe).collect();
+        let sampler =
+            InitializerSampler::new(&code.get_initializer(), &edge_probabilities, &edge_erasure_probabilities).unwrap();
+        for seed in 0..100 {
+            assert_eq!(sampler.sample(seed), code.generate_random_errors_with_edges(seed));
+        }
+    }
+
+    #[test]
+    fn simulator_initializer_sampler_invalid() {
+        // cargo test simulator_initializer_sampler_invalid -- --nocapture
+        let initializer = CodeCapacityPlanarCode::new(5, 0.1, 500).get_initializer();
+        let edge_num = initializer.weighted_edges.len();
+        let error = InitializerSampler::new(&initializer, &vec![0.1; edge_num - 1], &vec![0.; edge_num]).unwrap_err();
+        assert!(
+            error.contains(&format!("edge_probabilities has {} elements", edge_num - 1)),
+            "{error}"
+        );
+        assert!(error.contains(&format!("{edge_num} edges")), "{error}");
+        let error = InitializerSampler::new(&initializer, &vec![0.1; edge_num], &vec![0.; edge_num + 1]).unwrap_err();
+        assert!(error.contains("edge_erasure_probabilities"), "{error}");
+        let mut edge_probabilities = vec![0.1; edge_num];
+        edge_probabilities[3] = 1.5;
+        let error = InitializerSampler::new(&initializer, &edge_probabilities, &vec![0.; edge_num]).unwrap_err();
+        assert!(error.contains("edge_probabilities[3] = 1.5"), "{error}");
+    }
+}
@@ -0,0 +1,400 @@
+//! Statistics
+//!
+//! Threshold estimation from the logical error rates of several code distances `d` and physical error rates `p`. Near
+//! the threshold `p_th`, the logical error rate follows the finite-size scaling ansatz `P_L = A + B x + C x^2` of the
+//! rescaled variable `x = (p - p_th) d^(1/nu)`. The ansatz is fitted by weighted least squares: the coefficients are
+//! linear given `p_th` and `nu`, which are searched by the Nelder-Mead method. The confidence interval of `p_th` is the
+//! percentile interval of a parametric bootstrap that resamples the logical errors of every point.
+//!
+
+use super::util::*;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// the parameters of the ansatz: `p_th`, `nu`, `A`, `B` and `C`
+pub const SCALING_PARAMETER_NUM: usize = 5;
+
+/// the logical errors observed at a code distance and a physical error rate
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdPoint {
+    pub d: VertexNum,
+    pub p: f64,
+    pub shots: usize,
+    pub failures: usize,
+}
+
+impl ThresholdPoint {
+    pub fn rate(&self) -> f64 {
+        self.failures as f64 / self.shots as f64
+    }
+
+    /// the binomial variance of the rate, with a pseudo-count of each outcome so that a point without any logical
+    /// error still has a finite weight
+    fn variance(&self) -> f64 {
+        let rate = (self.failures as f64 + 1.) / (self.shots as f64 + 2.);
+        rate * (1. - rate) / self.shots as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScalingFit {
+    /// the threshold `p_th`
+    pub threshold: f64,
+    /// the critical exponent `nu`
+    pub nu: f64,
+    /// `[A, B, C]`
+    pub coefficients: [f64; 3],
+    /// the sum of squared residuals weighted by the inverse binomial variance of each point
+    pub chi_squared: f64,
+    /// the number of points minus [`SCALING_PARAMETER_NUM`]
+    pub degrees_of_freedom: usize,
+}
+
+impl ScalingFit {
+    pub fn rescaled(&self, d: VertexNum, p: f64) -> f64 {
+        (p - self.threshold) * (d as f64).powf(1. / self.nu)
+    }
+
+    /// the logical error rate predicted by the ansatz
+    pub fn predict(&self, d: VertexNum, p: f64) -> f64 {
+        let x = self.rescaled(d, p);
+        let [a, b, c] = self.coefficients;
+        a + b * x + c * x * x
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdEstimate {
+    pub fit: ScalingFit,
+    /// the 95% percentile interval of the bootstrapped thresholds
+    pub confidence_lower: f64,
+    pub confidence_upper: f64,
+    pub bootstrap_samples: usize,
+}
+
+impl ThresholdEstimate {
+    pub fn brief(&self) -> String {
+        let [a, b, c] = self.fit.coefficients;
+        format!(
+            "threshold: {:.4e}, 95% confidence interval: [{:.4e}, {:.4e}], nu: {:.4}, A: {:.4e}, B: {:.4e}, C: {:.4e}, \
+            chi^2/dof: {:.4} ({} bootstrap samples)",
+            self.fit.threshold,
+            self.confidence_lower,
+            self.confidence_upper,
+            self.fit.nu,
+            a,
+            b,
+            c,
+            self.fit.chi_squared / self.fit.degrees_of_freedom.max(1) as f64,
+            self.bootstrap_samples
+        )
+    }
+}
+
+/// the weighted least squares of `y = A + B x + C x^2`, returning the coefficients and the weighted sum of squared
+/// residuals, or `None` if the rescaled variables don't take at least 3 distinct values
+fn fit_quadratic(points: &[(f64, f64, f64)]) -> Option<([f64; 3], f64)> {
+    // normalize the rescaled variable to keep the normal equations well conditioned
+    let scale = points.iter().fold(0., |scale: f64, &(x, _, _)| scale.max(x.abs()));
+    let scale = if scale > 0. { scale } else { 1. };
+    let mut matrix = [[0.; 4]; 3];
+    for &(x, y, weight) in points.iter() {
+        let powers = [1., x / scale, (x / scale) * (x / scale)];
+        for (row, row_power) in matrix.iter_mut().zip(powers) {
+            // the first 3 columns, followed by the right-hand side
+            for (entry, column_power) in row.iter_mut().zip(powers) {
+                *entry += weight * row_power * column_power;
+            }
+            row[3] += weight * row_power * y;
+        }
+    }
+    // Gaussian elimination with partial pivoting
+    let tolerance = 1e-12 * (0..3).map(|row| matrix[row][row]).fold(0., f64::max);
+    for pivot in 0..3 {
+        let best = (pivot..3)
+            .max_by(|&a, &b| matrix[a][pivot].abs().total_cmp(&matrix[b][pivot].abs()))
+            .unwrap();
+        if matrix[best][pivot].abs() <= tolerance {
+            return None;
+        }
+        matrix.swap(pivot, best);
+        let pivot_row = matrix[pivot];
+        for (row_index, row) in matrix.iter_mut().enumerate() {
+            if row_index != pivot {
+                let factor = row[pivot] / pivot_row[pivot];
+                for (entry, pivot_entry) in row.iter_mut().zip(pivot_row).skip(pivot) {
+                    *entry -= factor * pivot_entry;
+                }
+            }
+        }
+    }
+    let coefficients = [
+        matrix[0][3] / matrix[0][0],
+        matrix[1][3] / matrix[1][1] / scale,
+        matrix[2][3] / matrix[2][2] / (scale * scale),
+    ];
+    let [a, b, c] = coefficients;
+    let chi_squared = points
+        .iter()
+        .map(|&(x, y, weight)| weight * (y - a - b * x - c * x * x).powi(2))
+        .sum();
+    Some((coefficients, chi_squared))
+}
+
+/// the best linear coefficients given the threshold and `mu = 1/nu`
+fn fit_coefficients(points: &[ThresholdPoint], threshold: f64, mu: f64) -> Option<([f64; 3], f64)> {
+    let rescaled: Vec<_> = points
+        .iter()
+        .map(|point| {
+            let x = (point.p - threshold) * (point.d as f64).powf(mu);
+            (x, point.rate(), 1. / point.variance())
+        })
+        .collect();
+    fit_quadratic(&rescaled)
+}
+
+/// minimize a function of 2 variables with the Nelder-Mead method, starting from a simplex with the given steps
+fn nelder_mead(function: impl Fn([f64; 2]) -> f64, start: [f64; 2], steps: [f64; 2], max_iterations: usize) -> [f64; 2] {
+    let mut simplex: Vec<([f64; 2], f64)> = [start, [start[0] + steps[0], start[1]], [start[0], start[1] + steps[1]]]
+        .into_iter()
+        .map(|vertex| (vertex, function(vertex)))
+        .collect();
+    let along = |from: [f64; 2], to: [f64; 2], ratio: f64| {
+        [from[0] + ratio * (to[0] - from[0]), from[1] + ratio * (to[1] - from[1])]
+    };
+    for _ in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0], simplex[2]);
+        if (worst.1 - best.1).abs() <= 1e-10 * (1. + best.1.abs())
+            && (0..2).all(|i| (worst.0[i] - best.0[i]).abs() <= 1e-10 * (1. + best.0[i].abs()))
+        {
+            break;
+        }
+        let centroid = along(simplex[0].0, simplex[1].0, 0.5);
+        let reflected = along(centroid, worst.0, -1.);
+        let reflected_value = function(reflected);
+        if reflected_value < best.1 {
+            let expanded = along(centroid, worst.0, -2.);
+            let expanded_value = function(expanded);
+            simplex[2] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[1].1 {
+            simplex[2] = (reflected, reflected_value);
+        } else {
+            let contracted = if reflected_value < worst.1 {
+                along(centroid, reflected, 0.5)
+            } else {
+                along(centroid, worst.0, 0.5)
+            };
+            let contracted_value = function(contracted);
+            if contracted_value < worst.1.min(reflected_value) {
+                simplex[2] = (contracted, contracted_value);
+            } else {
+                // shrink towards the best vertex
+                for vertex in simplex.iter_mut().skip(1) {
+                    vertex.0 = along(best.0, vertex.0, 0.5);
+                    vertex.1 = function(vertex.0);
+                }
+            }
+        }
+    }
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    simplex[0].0
+}
+
+/// fit the finite-size scaling ansatz; the points need at least 2 code distances and more points than the
+/// [`SCALING_PARAMETER_NUM`] parameters
+pub fn fit_scaling_ansatz(points: &[ThresholdPoint]) -> ScalingFit {
+    assert!(
+        points.len() > SCALING_PARAMETER_NUM,
+        "the ansatz needs more than {SCALING_PARAMETER_NUM} points, got {}",
+        points.len()
+    );
+    assert!(
+        points.iter().any(|point| point.d != points[0].d),
+        "the ansatz needs at least 2 code distances"
+    );
+    assert!(points.iter().all(|point| point.shots > 0), "every point must have shots");
+    // searching `ln(mu)` keeps `nu` positive
+    let chi_squared = |[threshold, log_mu]: [f64; 2]| {
+        fit_coefficients(points, threshold, log_mu.exp()).map_or(f64::INFINITY, |(_, chi_squared)| chi_squared)
+    };
+    let p_min = points.iter().map(|point| point.p).fold(f64::INFINITY, f64::min);
+    let p_max = points.iter().map(|point| point.p).fold(f64::NEG_INFINITY, f64::max);
+    // start from the best point of a coarse grid to avoid the local minima far from the crossing
+    let mut start = [p_min, 0.];
+    let mut start_value = f64::INFINITY;
+    for p_index in 0..=40 {
+        for mu_index in -4..=4 {
+            let candidate = [p_min + (p_max - p_min) * p_index as f64 / 40., mu_index as f64 * 0.35];
+            let value = chi_squared(candidate);
+            if value < start_value {
+                (start, start_value) = (candidate, value);
+            }
+        }
+    }
+    let steps = [(p_max - p_min).max(p_max * 1e-3) / 20., 0.2];
+    // restarting helps when the simplex collapses before reaching the minimum
+    let mut best = start;
+    for _ in 0..3 {
+        best = nelder_mead(chi_squared, best, steps, 2000);
+    }
+    let [threshold, log_mu] = best;
+    let (coefficients, chi_squared) = fit_coefficients(points, threshold, log_mu.exp()).expect("the fit must converge");
+    ScalingFit {
+        threshold,
+        nu: (-log_mu).exp(),
+        coefficients,
+        chi_squared,
+        degrees_of_freedom: points.len() - SCALING_PARAMETER_NUM,
+    }
+}
+
+/// a binomial sample, by inversion when the mean is small and otherwise by the normal approximation
+#[allow(clippy::unnecessary_cast)]
+pub fn sample_binomial(rng: &mut impl Rng, n: usize, p: f64) -> usize {
+    if p > 0.5 {
+        return n - sample_binomial(rng, n, 1. - p);
+    }
+    let mean = n as f64 * p;
+    if n <= 64 {
+        (0..n).filter(|_| rng.gen::<f64>() < p).count()
+    } else if mean < 30. {
+        let mut probability = (n as f64 * (-p).ln_1p()).exp();
+        let mut cumulative = probability;
+        let uniform: f64 = rng.gen();
+        let mut k = 0;
+        while uniform > cumulative && k < n {
+            probability *= p / (1. - p) * (n - k) as f64 / (k + 1) as f64;
+            k += 1;
+            cumulative += probability;
+        }
+        k
+    } else {
+        // Box-Muller transform
+        let normal = (-2. * (1. - rng.gen::<f64>()).ln()).sqrt() * (2. * std::f64::consts::PI * rng.gen::<f64>()).cos();
+        (mean + normal * (mean * (1. - p)).sqrt()).round().clamp(0., n as f64) as usize
+    }
+}
+
+/// fit the ansatz and bootstrap the 95% confidence interval of the threshold by resampling the logical errors of every
+/// point from the binomial distribution of its observed rate
+pub fn estimate_threshold(points: &[ThresholdPoint], bootstrap_samples: usize, seed: u64) -> ThresholdEstimate {
+    let fit = fit_scaling_ansatz(points);
+    let mut rng = DeterministicRng::seed_from_u64(seed);
+    let mut thresholds: Vec<f64> = (0..bootstrap_samples)
+        .map(|_| {
+            let resampled: Vec<_> = points
+                .iter()
+                .map(|point| ThresholdPoint {
+                    failures: sample_binomial(&mut rng, point.shots, point.rate()),
+                    ..*point
+                })
+                .collect();
+            fit_scaling_ansatz(&resampled).threshold
+        })
+        .collect();
+    thresholds.sort_by(f64::total_cmp);
+    let percentile = |fraction: f64| {
+        if thresholds.is_empty() {
+            return f64::NAN;
+        }
+        let index = (fraction * (thresholds.len() - 1) as f64).round() as usize;
+        thresholds[index]
+    };
+    ThresholdEstimate {
+        fit,
+        confidence_lower: percentile(0.025),
+        confidence_upper: percentile(0.975),
+        bootstrap_samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: f64 = 0.1;
+    const NU: f64 = 1.2;
+    const COEFFICIENTS: [f64; 3] = [0.2, 0.6, 0.4];
+
+    /// points of a known ansatz around the threshold, with the logical errors sampled from the ansatz unless `shots` is
+    /// so large that the rates are exact
+    fn synthetic_points(shots: usize, rng: &mut DeterministicRng, exact: bool) -> Vec<ThresholdPoint> {
+        let truth = ScalingFit {
+            threshold: THRESHOLD,
+            nu: NU,
+            coefficients: COEFFICIENTS,
+            chi_squared: 0.,
+            degrees_of_freedom: 0,
+        };
+        let mut points = vec![];
+        for d in [5, 9, 13] {
+            for p_index in 0..7 {
+                let p = 0.085 + 0.005 * p_index as f64;
+                let rate = truth.predict(d, p);
+                assert!(rate > 0. && rate < 1.);
+                let failures = if exact {
+                    (rate * shots as f64).round() as usize
+                } else {
+                    sample_binomial(rng, shots, rate)
+                };
+                points.push(ThresholdPoint { d, p, shots, failures });
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn stats_fit_exact_data() {
+        // cargo test stats_fit_exact_data -- --nocapture
+        let mut rng = DeterministicRng::seed_from_u64(0);
+        let points = synthetic_points(1_000_000_000_000, &mut rng, true);
+        let fit = fit_scaling_ansatz(&points);
+        println!("{fit:?}");
+        assert!((fit.threshold - THRESHOLD).abs() < 1e-6, "{fit:?}");
+        assert!((fit.nu - NU).abs() < 1e-3, "{fit:?}");
+        for (coefficient, expected) in fit.coefficients.iter().zip(COEFFICIENTS) {
+            assert!((coefficient - expected).abs() < 1e-3, "{fit:?}");
+        }
+    }
+
+    #[test]
+    fn stats_threshold_confidence_interval() {
+        // cargo test stats_threshold_confidence_interval -- --nocapture
+        for seed in 0..3 {
+            let mut rng = DeterministicRng::seed_from_u64(seed);
+            let points = synthetic_points(100_000, &mut rng, false);
+            let estimate = estimate_threshold(&points, 200, seed);
+            println!("{}", estimate.brief());
+            assert!(
+                estimate.confidence_lower <= THRESHOLD && THRESHOLD <= estimate.confidence_upper,
+                "{estimate:?}"
+            );
+            assert!(estimate.confidence_upper - estimate.confidence_lower < 0.005, "{estimate:?}");
+            // the fit is consistent with the binomial noise
+            let reduced_chi_squared = estimate.fit.chi_squared / estimate.fit.degrees_of_freedom as f64;
+            assert!(reduced_chi_squared < 3., "{estimate:?}");
+        }
+    }
+
+    #[test]
+    fn stats_sample_binomial() {
+        // cargo test stats_sample_binomial -- --nocapture
+        let mut rng = DeterministicRng::seed_from_u64(0);
+        // direct sampling, inversion, normal approximation and the symmetric case
+        for (n, p) in [(50, 0.3), (100_000, 1e-4), (100_000, 0.2), (1000, 0.9)] {
+            let samples = 20_000;
+            let values: Vec<f64> = (0..samples).map(|_| sample_binomial(&mut rng, n, p) as f64).collect();
+            let mean = values.iter().sum::<f64>() / samples as f64;
+            let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (samples - 1) as f64;
+            let expected_variance = n as f64 * p * (1. - p);
+            println!("n: {n}, p: {p}, mean: {mean}, variance: {variance}");
+            assert!((mean - n as f64 * p).abs() < 5. * (expected_variance / samples as f64).sqrt());
+            assert!((variance / expected_variance - 1.).abs() < 0.05);
+        }
+    }
+}
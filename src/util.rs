@@ -1,15 +1,65 @@
+use super::error::*;
+#[cfg(not(target_arch = "wasm32"))]
 use super::mwpm_solver::PrimalDualSolver;
 use super::pointers::*;
 use super::rand_xoshiro;
+use super::visualize::VisualizePosition;
 use crate::rand_xoshiro::rand_core::RngCore;
 #[cfg(feature = "python_binding")]
+use pyo3::basic::CompareOp;
+#[cfg(feature = "python_binding")]
+use pyo3::exceptions::{PyTypeError, PyValueError};
+#[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+#[cfg(feature = "python_binding")]
+use pyo3::types::{IntoPyDict, PyBytes, PyTuple};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
+#[cfg(not(target_arch = "wasm32"))]
 use std::io::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 
+/// pickle (through bincode), copy and compare a Python class that is serializable; `$new_args` are the arguments of its
+/// constructor to create a placeholder object which is then overwritten by the unpickled state
+#[cfg(feature = "python_binding")]
+macro_rules! bind_trait_python_serde {
+    ($struct_name:ident, $new_args:expr) => {
+        #[pymethods]
+        impl $struct_name {
+            fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+                let state = bincode::serialize(self).map_err(|error| PyValueError::new_err(error.to_string()))?;
+                Ok(PyBytes::new(py, &state))
+            }
+            fn __setstate__(&mut self, state: &PyBytes) -> PyResult<()> {
+                *self = bincode::deserialize(state.as_bytes()).map_err(|error| PyValueError::new_err(error.to_string()))?;
+                Ok(())
+            }
+            fn __getnewargs__(&self, py: Python<'_>) -> Py<PyTuple> {
+                $new_args.into_py(py)
+            }
+            fn __copy__(&self) -> Self {
+                self.clone()
+            }
+            fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+                self.clone()
+            }
+            fn __richcmp__(&self, other: &PyAny, op: CompareOp, py: Python<'_>) -> PyObject {
+                let Ok(other) = other.extract::<PyRef<Self>>() else {
+                    return py.NotImplemented();
+                };
+                match op {
+                    CompareOp::Eq => (*self == *other).into_py(py),
+                    CompareOp::Ne => (*self != *other).into_py(py),
+                    _ => py.NotImplemented(),
+                }
+            }
+        }
+    };
+}
+
 cfg_if::cfg_if! {
     if #[cfg(feature="i32_weight")] {
         /// use i32 to store weight to be compatible with blossom V library (c_int)
@@ -41,8 +91,8 @@ cfg_if::cfg_if! {
 }
 
 #[cfg_attr(feature = "python_binding", cfg_eval)]
-#[cfg_attr(feature = "python_binding", pyclass)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python_binding", pyclass(module = "fusion_blossom"))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SolverInitializer {
     /// the number of vertices
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
@@ -53,11 +103,16 @@ pub struct SolverInitializer {
     /// the virtual vertices
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
     pub virtual_vertices: Vec<VertexIndex>,
+    /// optionally, the virtual vertices grouped by the boundary they belong to, e.g. `[left, right]` of a planar code,
+    /// such that a chain between two groups is a logical operator; absent in the files written by older versions
+    #[serde(default)]
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    pub virtual_vertex_groups: Option<Vec<Vec<VertexIndex>>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
-#[cfg_attr(feature = "python_binding", pyclass)]
+#[cfg_attr(feature = "python_binding", pyclass(module = "fusion_blossom"))]
 pub struct SyndromePattern {
     /// the vertices corresponding to defect measurements
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
@@ -72,6 +127,11 @@ pub struct SyndromePattern {
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
     #[serde(default = "default_dynamic_weights")]
     pub dynamic_weights: Vec<(EdgeIndex, Weight)>,
+    /// the trusted edges that must not be part of the correction of this syndrome, see
+    /// [`SolverInitializer::resolve_blocked_edges`]
+    #[cfg_attr(feature = "python_binding", pyo3(get, set))]
+    #[serde(default = "default_blocked_edges")]
+    pub blocked_edges: Vec<EdgeIndex>,
 }
 
 pub fn default_dynamic_weights() -> Vec<(EdgeIndex, Weight)> {
@@ -82,12 +142,17 @@ pub fn default_erasures() -> Vec<EdgeIndex> {
     vec![]
 }
 
+pub fn default_blocked_edges() -> Vec<EdgeIndex> {
+    vec![]
+}
+
 impl SyndromePattern {
     pub fn new(defect_vertices: Vec<VertexIndex>, erasures: Vec<EdgeIndex>) -> Self {
         Self {
             defect_vertices,
             erasures,
             dynamic_weights: vec![],
+            blocked_edges: vec![],
         }
     }
     pub fn new_dynamic_weights(
@@ -99,33 +164,66 @@ impl SyndromePattern {
             defect_vertices,
             erasures,
             dynamic_weights,
+            blocked_edges: vec![],
         }
     }
+    /// the defect vertices are the indices of `true` in the dense measurement results
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_dense(dense: &[bool]) -> Self {
+        let defect_vertices = (0..dense.len())
+            .filter(|&vertex_index| dense[vertex_index])
+            .map(|vertex_index| vertex_index as VertexIndex)
+            .collect();
+        Self::new_vertices(defect_vertices)
+    }
 }
 
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl SyndromePattern {
-    #[cfg_attr(feature = "python_binding", new)]
-    #[cfg_attr(feature = "python_binding", pyo3(signature = (defect_vertices=vec![], erasures=vec![], dynamic_weights=vec![], syndrome_vertices=None)))]
+    /// `defect_vertices`, `erasures` and `syndrome_vertices` are either lists or 1-D numpy arrays, see [`py_indices`];
+    /// an integer array is not read in place but copied once into native 64-bit indices
+    #[cfg(feature = "python_binding")]
+    #[new]
+    #[pyo3(signature = (defect_vertices=None, erasures=None, dynamic_weights=vec![], syndrome_vertices=None, blocked_edges=None))]
     pub fn py_new(
-        mut defect_vertices: Vec<VertexIndex>,
-        erasures: Vec<EdgeIndex>,
+        defect_vertices: Option<&PyAny>,
+        erasures: Option<&PyAny>,
         dynamic_weights: Vec<(EdgeIndex, Weight)>,
-        syndrome_vertices: Option<Vec<VertexIndex>>,
-    ) -> Self {
+        syndrome_vertices: Option<&PyAny>,
+        blocked_edges: Option<&PyAny>,
+    ) -> PyResult<Self> {
+        let mut defect_vertices = match defect_vertices {
+            Some(defect_vertices) => py_indices(defect_vertices, "defect_vertices")?,
+            None => vec![],
+        };
         if let Some(syndrome_vertices) = syndrome_vertices {
             assert!(
                 defect_vertices.is_empty(),
                 "do not pass both `syndrome_vertices` and `defect_vertices` since they're aliasing"
             );
-            defect_vertices = syndrome_vertices;
+            defect_vertices = py_indices(syndrome_vertices, "syndrome_vertices")?;
         }
+        let erasures = match erasures {
+            Some(erasures) => py_indices(erasures, "erasures")?,
+            None => vec![],
+        };
         assert!(
             erasures.is_empty() || dynamic_weights.is_empty(),
             "erasures and dynamic_weights cannot be provided at the same time"
         );
-        Self::new_dynamic_weights(defect_vertices, erasures, dynamic_weights)
+        let mut syndrome_pattern = Self::new_dynamic_weights(defect_vertices, erasures, dynamic_weights);
+        if let Some(blocked_edges) = blocked_edges {
+            syndrome_pattern.blocked_edges = py_indices(blocked_edges, "blocked_edges")?;
+        }
+        Ok(syndrome_pattern)
+    }
+    /// build from all the measurement results, given in the same way as [`py_dense`]
+    #[cfg(feature = "python_binding")]
+    #[staticmethod]
+    #[pyo3(name = "from_dense")]
+    pub fn py_from_dense(dense: &PyAny) -> PyResult<Self> {
+        Ok(Self::from_dense(&py_dense(dense, "dense")?))
     }
     #[cfg_attr(feature = "python_binding", staticmethod)]
     pub fn new_vertices(defect_vertices: Vec<VertexIndex>) -> Self {
@@ -141,6 +239,140 @@ impl SyndromePattern {
     }
 }
 
+#[cfg(feature = "python_binding")]
+bind_trait_python_serde!(SyndromePattern, ());
+
+/// a fixed-length bit vector stored in 64-bit words, so that the set bits can be enumerated word by word
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "bit {index} out of range {}", self.len);
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn set(&mut self, index: usize) {
+        assert!(index < self.len, "bit {index} out of range {}", self.len);
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn flip(&mut self, index: usize) {
+        assert!(index < self.len, "bit {index} out of range {}", self.len);
+        self.words[index / 64] ^= 1 << (index % 64);
+    }
+
+    /// unset all the bits without reallocation
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// the indices of the bits that are set, in increasing order; empty words are skipped and each set bit is found
+    /// with a single `trailing_zeros`
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .filter(|(_, &word)| word != 0)
+            .flat_map(|(word_index, &word)| {
+                let mut word = word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(word_index * 64 + bit)
+                })
+            })
+    }
+
+    pub fn memory_estimate(&self) -> usize {
+        self.words.capacity() * size_of::<u64>()
+    }
+}
+
+/// a dense representation of [`SyndromePattern`] with one bit per vertex and per edge, which is cheaper than the
+/// sorted index lists when a large fraction of the vertices are defects or of the edges are erased;
+/// dynamic weights are not supported
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyndromeBitmap {
+    /// one bit per vertex, set if the vertex is a defect
+    pub defects: BitVec,
+    /// one bit per edge, set if the edge is erased
+    pub erasures: BitVec,
+}
+
+impl SyndromeBitmap {
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(vertex_num: VertexNum, edge_num: usize) -> Self {
+        Self {
+            defects: BitVec::new(vertex_num as usize),
+            erasures: BitVec::new(edge_num),
+        }
+    }
+
+    /// panics if the syndrome pattern has dynamic weights or refers to a vertex or edge out of range
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_syndrome_pattern(syndrome_pattern: &SyndromePattern, vertex_num: VertexNum, edge_num: usize) -> Self {
+        assert!(
+            syndrome_pattern.dynamic_weights.is_empty(),
+            "dynamic weights cannot be represented as a bitmap"
+        );
+        let mut bitmap = Self::new(vertex_num, edge_num);
+        for &vertex_index in syndrome_pattern.defect_vertices.iter() {
+            bitmap.defects.set(vertex_index as usize);
+        }
+        for &edge_index in syndrome_pattern.erasures.iter() {
+            bitmap.erasures.set(edge_index as usize);
+        }
+        bitmap
+    }
+
+    pub fn defect_vertices(&self) -> impl Iterator<Item = VertexIndex> + '_ {
+        self.defects.ones().map(|vertex_index| vertex_index as VertexIndex)
+    }
+
+    pub fn erasure_edges(&self) -> impl Iterator<Item = EdgeIndex> + '_ {
+        self.erasures.ones().map(|edge_index| edge_index as EdgeIndex)
+    }
+
+    /// the fraction of the vertices that are defects
+    pub fn defect_density(&self) -> f64 {
+        if self.defects.is_empty() {
+            return 0.;
+        }
+        self.defects.count_ones() as f64 / self.defects.len() as f64
+    }
+
+    /// the sparse representation with sorted defect vertices and erasures
+    pub fn to_syndrome_pattern(&self) -> SyndromePattern {
+        SyndromePattern::new(self.defect_vertices().collect(), self.erasure_edges().collect())
+    }
+}
+
 /// an efficient representation of partitioned vertices and erasures when they're ordered
 #[derive(Debug, Clone, Serialize)]
 pub struct PartitionedSyndromePattern<'a> {
@@ -282,10 +514,10 @@ impl std::fmt::Debug for PartitionUnitWeak {
 }
 
 /// user input partition configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
-#[cfg_attr(feature = "python_binding", pyclass)]
+#[cfg_attr(feature = "python_binding", pyclass(module = "fusion_blossom"))]
 pub struct PartitionConfig {
     /// the number of vertices
     #[cfg_attr(feature = "python_binding", pyo3(get, set))]
@@ -298,6 +530,64 @@ pub struct PartitionConfig {
     pub fusions: Vec<(usize, usize)>,
 }
 
+/// the shape of the fusion tree over a sequence of partitions, where partition `i` and `i + 1` are neighbors;
+/// a linear chain minimizes the peak memory while a balanced binary tree minimizes the depth (and thus the latency)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FusionTreeShape {
+    /// fuse neighboring units level by level
+    BalancedBinary,
+    /// fuse the partitions one by one, starting from the first partition
+    LinearChain,
+    /// user-specified fusions, in the same format as [`PartitionConfig::fusions`]
+    Custom(Vec<(usize, usize)>),
+}
+
+impl FusionTreeShape {
+    /// build the fusions of `partition_num` sequential partitions
+    pub fn build_fusions(&self, partition_num: usize) -> Vec<(usize, usize)> {
+        assert!(partition_num >= 1, "at least one partition must exist");
+        let mut fusions = vec![];
+        match self {
+            Self::BalancedBinary => {
+                let mut pending_units: Vec<usize> = (0..partition_num).collect();
+                while pending_units.len() > 1 {
+                    let mut next_pending_units = vec![];
+                    for pair in pending_units.chunks(2) {
+                        if pair.len() == 2 {
+                            fusions.push((pair[0], pair[1]));
+                            next_pending_units.push(partition_num + fusions.len() - 1);
+                        } else {
+                            next_pending_units.push(pair[0]); // the odd one is fused in the next level
+                        }
+                    }
+                    pending_units = next_pending_units;
+                }
+            }
+            Self::LinearChain => {
+                for partition_index in 1..partition_num {
+                    let last_unit = if partition_index == 1 {
+                        0
+                    } else {
+                        partition_num + partition_index - 2
+                    };
+                    fusions.push((last_unit, partition_index));
+                }
+            }
+            Self::Custom(custom_fusions) => {
+                assert_eq!(
+                    custom_fusions.len() + 1,
+                    partition_num,
+                    "a fusion tree of {} partitions must have {} fusions",
+                    partition_num,
+                    partition_num - 1
+                );
+                fusions = custom_fusions.clone();
+            }
+        }
+        fusions
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl PartitionConfig {
@@ -315,18 +605,23 @@ impl PartitionConfig {
         format!("{:?}", self)
     }
 
+    /// check the partitions and the fusion tree, returning [`FusionError::Config`] if they're invalid
+    #[cfg_attr(feature = "python_binding", pyo3(name = "info"))]
     #[allow(clippy::unnecessary_cast)]
-    pub fn info(&self) -> PartitionInfo {
-        assert!(!self.partitions.is_empty(), "at least one partition must exist");
+    pub fn try_info(&self) -> Result<PartitionInfo, FusionError> {
+        let config_error = |message: String| Err(FusionError::Config(message));
+        if self.partitions.is_empty() {
+            return config_error("at least one partition must exist".to_string());
+        }
         let mut whole_ranges = vec![];
         let mut owning_ranges = vec![];
         for &partition in self.partitions.iter() {
-            partition.sanity_check();
-            assert!(
-                partition.end() <= self.vertex_num as VertexIndex,
-                "invalid vertex index {} in partitions",
-                partition.end()
-            );
+            if partition.start() > partition.end() {
+                return config_error(format!("invalid vertex range {:?}", partition));
+            }
+            if partition.end() > self.vertex_num as VertexIndex {
+                return config_error(format!("invalid vertex index {} in partitions", partition.end()));
+            }
             whole_ranges.push(partition);
             owning_ranges.push(partition);
         }
@@ -334,20 +629,17 @@ impl PartitionConfig {
         let mut parents: Vec<Option<usize>> = (0..unit_count).map(|_| None).collect();
         for (fusion_index, (left_index, right_index)) in self.fusions.iter().enumerate() {
             let unit_index = fusion_index + self.partitions.len();
-            assert!(
-                *left_index < unit_index,
-                "dependency wrong, {} depending on {}",
-                unit_index,
-                left_index
-            );
-            assert!(
-                *right_index < unit_index,
-                "dependency wrong, {} depending on {}",
-                unit_index,
-                right_index
-            );
-            assert!(parents[*left_index].is_none(), "cannot fuse {} twice", left_index);
-            assert!(parents[*right_index].is_none(), "cannot fuse {} twice", right_index);
+            for child_index in [*left_index, *right_index] {
+                if child_index >= unit_index {
+                    return config_error(format!("dependency wrong, {} depending on {}", unit_index, child_index));
+                }
+                if parents[child_index].is_some() {
+                    return config_error(format!("cannot fuse {} twice", child_index));
+                }
+            }
+            if whole_ranges[*left_index].end() > whole_ranges[*right_index].start() {
+                return config_error(format!("unit {} cannot fuse a lower unit {}", left_index, right_index));
+            }
             parents[*left_index] = Some(unit_index);
             parents[*right_index] = Some(unit_index);
             // fusing range
@@ -357,20 +649,20 @@ impl PartitionConfig {
         }
         // check that all nodes except for the last one has been merged
         for (unit_index, parent) in parents.iter().enumerate().take(unit_count - 1) {
-            assert!(parent.is_some(), "found unit {} without being fused", unit_index);
+            if parent.is_none() {
+                return config_error(format!("found unit {} without being fused", unit_index));
+            }
         }
         // check that the final node has the full range
         let last_unit_index = self.partitions.len() + self.fusions.len() - 1;
-        assert!(
-            whole_ranges[last_unit_index].start() == 0,
-            "final range not covering all vertices {:?}",
-            whole_ranges[last_unit_index]
-        );
-        assert!(
-            whole_ranges[last_unit_index].end() == self.vertex_num as VertexIndex,
-            "final range not covering all vertices {:?}",
-            whole_ranges[last_unit_index]
-        );
+        if whole_ranges[last_unit_index].start() != 0
+            || whole_ranges[last_unit_index].end() != self.vertex_num as VertexIndex
+        {
+            return config_error(format!(
+                "final range not covering all vertices {:?}",
+                whole_ranges[last_unit_index]
+            ));
+        }
         // construct partition info
         let mut partition_unit_info: Vec<_> = (0..self.partitions.len() + self.fusions.len())
             .map(|i| PartitionUnitInfo {
@@ -406,10 +698,146 @@ impl PartitionConfig {
                 vertex_to_owning_unit[vertex_index as usize] = unit_index;
             }
         }
-        PartitionInfo {
+        Ok(PartitionInfo {
             config: self.clone(),
             units: partition_unit_info,
             vertex_to_owning_unit,
+        })
+    }
+}
+
+#[cfg(feature = "python_binding")]
+bind_trait_python_serde!(PartitionConfig, (0 as VertexNum,));
+
+/// errors when inferring the interfaces of a partition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartitionError {
+    /// an edge connects two units that do not share an interface in the fusion tree
+    NonAdjacentUnits {
+        edge: (VertexIndex, VertexIndex),
+        units: (usize, usize),
+    },
+    /// all vertices of a partition are moved to the interfaces
+    EmptyPartition { partition_index: usize },
+    /// an edge connects two units where neither is the ancestor of the other, see [`PartitionInfo::check_edges`]
+    CrossingEdge {
+        edge: (VertexIndex, VertexIndex),
+        units: (usize, usize),
+    },
+    /// an edge has a vertex that is out of range or not owned by any unit
+    UnownedVertex {
+        edge: (VertexIndex, VertexIndex),
+        vertex_index: VertexIndex,
+    },
+    /// the partitions or the fusion tree are invalid, see [`PartitionConfig::try_info`]
+    InvalidConfig(String),
+}
+
+impl std::fmt::Display for PartitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::NonAdjacentUnits { edge, units } => write!(
+                f,
+                "edge {:?} crosses units {} and {} which are not adjacent in the fusion tree, \
+                consider a fusion tree that directly fuses the subtrees of these two units",
+                edge, units.0, units.1
+            ),
+            Self::EmptyPartition { partition_index } => write!(
+                f,
+                "partition {} becomes empty after moving the interface vertices out, consider a larger partition",
+                partition_index
+            ),
+            Self::CrossingEdge { edge, units } => write!(
+                f,
+                "edge {:?} connects units {} and {} where neither is the ancestor of the other, \
+                the vertices are probably not in the ordering of the partition, consider planning the partition \
+                together with the vertex ordering",
+                edge, units.0, units.1
+            ),
+            Self::UnownedVertex { edge, vertex_index } => write!(
+                f,
+                "vertex {} of edge {:?} is out of range or not owned by any unit of the partition",
+                vertex_index, edge
+            ),
+            Self::InvalidConfig(message) => write!(f, "invalid partition config: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PartitionError {}
+
+impl PartitionConfig {
+    /// panics if the partitions or the fusion tree are invalid, see [`Self::try_info`]
+    pub fn info(&self) -> PartitionInfo {
+        or_panic(self.try_info())
+    }
+
+    /// given partitions that may touch each other, shrink the partitions so that every edge crossing two units has an endpoint
+    /// in the interface of their common ancestor; the endpoint in the higher partition is moved to the interface, so that
+    /// the interface is always the beginning of the higher partition; `self` is left unchanged on error
+    #[allow(clippy::unnecessary_cast)]
+    pub fn infer_interfaces(&mut self, weighted_edges: &[(VertexIndex, VertexIndex, Weight)]) -> Result<(), PartitionError> {
+        // shrink a copy so that `self` is untouched on error
+        let mut inferred = self.clone();
+        // moving vertices to an interface may create new crossing edges, repeat until no partition shrinks
+        loop {
+            let partition_info = inferred.try_info().map_err(|error| match error {
+                FusionError::Config(message) => PartitionError::InvalidConfig(message),
+                error => PartitionError::InvalidConfig(error.to_string()),
+            })?;
+            let mut partition_starts: Vec<VertexIndex> =
+                inferred.partitions.iter().map(|partition| partition.start()).collect();
+            for &(vertex_1, vertex_2, _) in weighted_edges.iter() {
+                let (unit_1, unit_2) = partition_info.edge_owning_units(vertex_1, vertex_2)?;
+                if unit_1 == unit_2
+                    || partition_info.units[unit_1].descendants.contains(&unit_2)
+                    || partition_info.units[unit_2].descendants.contains(&unit_1)
+                {
+                    continue;
+                }
+                let ((left_vertex, left_unit), (right_vertex, right_unit)) =
+                    if partition_info.units[unit_1].whole_range.start() < partition_info.units[unit_2].whole_range.start() {
+                        ((vertex_1, unit_1), (vertex_2, unit_2))
+                    } else {
+                        ((vertex_2, unit_2), (vertex_1, unit_1))
+                    };
+                let non_adjacent_error = PartitionError::NonAdjacentUnits {
+                    edge: (left_vertex, right_vertex),
+                    units: (left_unit, right_unit),
+                };
+                // only neighboring leaves can be separated by the interface of their lowest common ancestor
+                let is_leaf = |unit_index: usize| partition_info.units[unit_index].children.is_none();
+                if !is_leaf(left_unit) || !is_leaf(right_unit) {
+                    return Err(non_adjacent_error);
+                }
+                let mut common_ancestor = left_unit;
+                while !partition_info.units[common_ancestor].descendants.contains(&right_unit) {
+                    common_ancestor = partition_info.units[common_ancestor].parent.unwrap();
+                }
+                let (left_child, right_child) = partition_info.units[common_ancestor].children.unwrap();
+                if partition_info.units[left_child].whole_range.end() != partition_info.units[left_unit].whole_range.end()
+                    || partition_info.units[right_child].whole_range.start()
+                        != partition_info.units[right_unit].whole_range.start()
+                {
+                    return Err(non_adjacent_error);
+                }
+                partition_starts[right_unit] = std::cmp::max(partition_starts[right_unit], right_vertex + 1);
+            }
+            let mut shrunk = false;
+            for (partition_index, partition) in inferred.partitions.iter_mut().enumerate() {
+                if partition_starts[partition_index] == partition.start() {
+                    continue;
+                }
+                if partition_starts[partition_index] >= partition.end() {
+                    return Err(PartitionError::EmptyPartition { partition_index });
+                }
+                *partition = VertexRange::new(partition_starts[partition_index], partition.end());
+                shrunk = true;
+            }
+            if !shrunk {
+                *self = inferred;
+                return Ok(());
+            }
         }
     }
 }
@@ -445,12 +873,66 @@ impl PartitionInfo {
         partitioned_syndrome
     }
 
+    /// the number of fusions from a unit to the root of the fusion tree
+    pub fn unit_depth(&self, mut unit_index: usize) -> usize {
+        let mut depth = 0;
+        while let Some(parent) = self.units[unit_index].parent {
+            unit_index = parent;
+            depth += 1;
+        }
+        depth
+    }
+
+    /// the maximum number of sequential fusions from any partition to the root, which determines the latency of fusion
+    pub fn fusion_depth(&self) -> usize {
+        (0..self.config.partitions.len())
+            .map(|unit_index| self.unit_depth(unit_index))
+            .max()
+            .unwrap()
+    }
+
     #[cfg(feature = "python_binding")]
     fn __repr__(&self) -> String {
         format!("{:?}", self)
     }
 }
 
+impl PartitionInfo {
+    /// the units owning the two vertices of an edge
+    #[allow(clippy::unnecessary_cast)]
+    fn edge_owning_units(&self, vertex_1: VertexIndex, vertex_2: VertexIndex) -> Result<(usize, usize), PartitionError> {
+        let owning_unit = |vertex_index: VertexIndex| {
+            (self.vertex_to_owning_unit.get(vertex_index as usize))
+                .filter(|&&unit_index| unit_index < self.units.len())
+                .copied()
+                .ok_or(PartitionError::UnownedVertex {
+                    edge: (vertex_1, vertex_2),
+                    vertex_index,
+                })
+        };
+        Ok((owning_unit(vertex_1)?, owning_unit(vertex_2)?))
+    }
+
+    /// check that every edge is either inside a unit or between a unit and its ancestor, as required by the parallel
+    /// solver; this fails when the vertices of the decoding graph are not in the ordering that the partition assumes
+    #[allow(clippy::unnecessary_cast)]
+    pub fn check_edges(&self, weighted_edges: &[(VertexIndex, VertexIndex, Weight)]) -> Result<(), PartitionError> {
+        for &(vertex_1, vertex_2, _) in weighted_edges.iter() {
+            let (unit_1, unit_2) = self.edge_owning_units(vertex_1, vertex_2)?;
+            if unit_1 != unit_2
+                && !self.units[unit_1].descendants.contains(&unit_2)
+                && !self.units[unit_2].descendants.contains(&unit_1)
+            {
+                return Err(PartitionError::CrossingEdge {
+                    edge: (vertex_1, vertex_2),
+                    units: (unit_1, unit_2),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
 impl<'a> PartitionedSyndromePattern<'a> {
     /// partition the syndrome pattern into 2 partitioned syndrome pattern and my whole range
     #[allow(clippy::unnecessary_cast)]
@@ -605,7 +1087,81 @@ impl SolverInitializer {
             vertex_num,
             weighted_edges,
             virtual_vertices,
+            virtual_vertex_groups: None,
+        }
+    }
+    /// build from a scipy sparse check matrix (CSC, CSR or COO) without densifying it, see [`Self::from_check_matrix`];
+    /// the weights are either given directly or computed from `error_probabilities` with
+    /// [`crate::example_codes::weight_of_p`], then scaled such that the maximum half weight is `max_half_weight`
+    #[cfg(feature = "python_binding")]
+    #[staticmethod]
+    #[allow(clippy::unnecessary_cast)]
+    #[pyo3(name = "from_check_matrix", signature = (check_matrix, weights=None, error_probabilities=None, max_half_weight=500))]
+    pub fn py_from_check_matrix(
+        check_matrix: &PyAny,
+        weights: Option<Vec<f64>>,
+        error_probabilities: Option<Vec<f64>>,
+        max_half_weight: Weight,
+    ) -> PyResult<Self> {
+        if !check_matrix.hasattr("tocsc")? {
+            return Err(PyTypeError::new_err(
+                "`check_matrix` must be a scipy sparse matrix in CSC, CSR or COO format",
+            ));
+        }
+        // converting between sparse formats never densifies; copy so that the caller's matrix is left untouched
+        let kwargs = [("copy", true)].into_py_dict(check_matrix.py());
+        let csc = check_matrix.call_method("tocsc", (), Some(kwargs))?;
+        csc.call_method0("eliminate_zeros")?;
+        let (row_num, column_num): (usize, usize) = csc.getattr("shape")?.extract()?;
+        let column_pointers: Vec<usize> = py_indices(csc.getattr("indptr")?, "indptr")?
+            .into_iter()
+            .map(|pointer| pointer as usize)
+            .collect();
+        let row_indices = py_indices(csc.getattr("indices")?, "indices")?;
+        let float_weights = match (weights, error_probabilities) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "`weights` and `error_probabilities` cannot be given at the same time",
+                ))
+            }
+            (Some(weights), None) => weights,
+            (None, Some(error_probabilities)) => {
+                if let Some(p) = error_probabilities.iter().find(|&&p| !(p > 0. && p <= 0.5)) {
+                    return Err(PyValueError::new_err(format!("error probability {p} must be in (0, 0.5]")));
+                }
+                error_probabilities
+                    .iter()
+                    .map(|&p| crate::example_codes::weight_of_p(p))
+                    .collect()
+            }
+            (None, None) => vec![1.; column_num],
+        };
+        if let Some(weight) = float_weights.iter().find(|&&weight| !(weight >= 0. && weight.is_finite())) {
+            return Err(PyValueError::new_err(format!(
+                "weight {weight} must be non-negative and finite"
+            )));
+        }
+        let max_weight = float_weights.iter().cloned().fold(0., f64::max);
+        let scale = if max_weight > 0. {
+            max_half_weight as f64 / max_weight
+        } else {
+            0.
+        };
+        // scale the same way as [`crate::example_codes::ExampleCode::compute_weights`], where every weight is even
+        let weights: Vec<Weight> = float_weights
+            .iter()
+            .map(|&weight| 2 * ((weight * scale).round() as Weight).max(1))
+            .collect();
+        Self::from_check_matrix(row_num as VertexNum, &column_pointers, &row_indices, &weights)
+            .map_err(|error| PyValueError::new_err(error.to_string()))
+    }
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "split_virtual_vertices")]
+    fn py_split_virtual_vertices(&mut self, max_degree: usize) -> PyResult<VirtualSplitMap> {
+        if max_degree == 0 {
+            return Err(PyValueError::new_err("`max_degree` must be positive"));
         }
+        Ok(self.split_virtual_vertices(max_degree))
     }
     #[cfg(feature = "python_binding")]
     fn __repr__(&self) -> String {
@@ -613,47 +1169,946 @@ impl SolverInitializer {
     }
 }
 
-/// timestamp type determines how many fast clear before a hard clear is required, see [`FastClear`]
-pub type FastClearTimestamp = usize;
+#[cfg(feature = "python_binding")]
+bind_trait_python_serde!(
+    SolverInitializer,
+    (
+        0 as VertexNum,
+        Vec::<(VertexIndex, VertexIndex, Weight)>::new(),
+        Vec::<VertexIndex>::new()
+    )
+);
 
-#[allow(dead_code)]
-/// use Xoshiro256StarStar for deterministic random number generator
-pub type DeterministicRng = rand_xoshiro::Xoshiro256StarStar;
+impl SolverInitializer {
+    /// an analytic estimate of the memory in bytes
+    pub fn memory_estimate(&self) -> usize {
+        self.weighted_edges.capacity() * size_of::<(VertexIndex, VertexIndex, Weight)>()
+            + self.virtual_vertices.capacity() * size_of::<VertexIndex>()
+    }
 
-pub trait F64Rng {
-    fn next_f64(&mut self) -> f64;
-}
+    /// renumber the vertices such that `sequential_vertices[new_index] = old_index`, see [`crate::ordering`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn permuted(&self, sequential_vertices: &[VertexIndex]) -> SolverInitializer {
+        assert_eq!(
+            self.vertex_num as usize,
+            sequential_vertices.len(),
+            "amount of vertices must be same"
+        );
+        let old_to_new = build_old_to_new(&sequential_vertices.to_vec());
+        let new_index = |old_index: VertexIndex| old_to_new[old_index as usize].unwrap();
+        SolverInitializer {
+            vertex_num: self.vertex_num,
+            weighted_edges: self
+                .weighted_edges
+                .iter()
+                .map(|&(vertex_1, vertex_2, weight)| (new_index(vertex_1), new_index(vertex_2), weight))
+                .collect(),
+            virtual_vertices: self.virtual_vertices.iter().map(|&vertex| new_index(vertex)).collect(),
+            virtual_vertex_groups: self.virtual_vertex_groups.as_ref().map(|groups| {
+                groups
+                    .iter()
+                    .map(|group| group.iter().map(|&vertex| new_index(vertex)).collect())
+                    .collect()
+            }),
+        }
+    }
 
-impl F64Rng for DeterministicRng {
-    fn next_f64(&mut self) -> f64 {
-        f64::from_bits(0x3FF << 52 | self.next_u64() >> 12) - 1.
+    /// the index of the group in [`Self::virtual_vertex_groups`] that the vertex belongs to, if any
+    pub fn group_of(&self, vertex: VertexIndex) -> Option<usize> {
+        self.virtual_vertex_groups
+            .as_ref()?
+            .iter()
+            .position(|group| group.contains(&vertex))
+    }
+
+    /// replace every virtual vertex with more than `max_degree` incident edges by several copies, each taking at most
+    /// `max_degree` of its edges in the order of edge indices; the copies are appended after the existing vertices and
+    /// the edge indices are unchanged, so subgraphs need no translation while matched virtual vertices can be translated
+    /// back with the returned map; apply it before building a partition, since the copies are not in any vertex range
+    #[allow(clippy::unnecessary_cast)]
+    pub fn split_virtual_vertices(&mut self, max_degree: usize) -> VirtualSplitMap {
+        assert!(max_degree > 0, "a virtual vertex must keep at least one edge");
+        let mut split_map = VirtualSplitMap {
+            original_vertex_num: self.vertex_num,
+            copy_of: vec![],
+        };
+        let mut is_virtual = vec![false; self.vertex_num as usize];
+        for &virtual_vertex in self.virtual_vertices.iter() {
+            is_virtual[virtual_vertex as usize] = true;
+        }
+        // (the vertex that currently takes the edges, the number of edges it has taken)
+        let mut current: Vec<(VertexIndex, usize)> = (0..self.vertex_num).map(|vertex_index| (vertex_index, 0)).collect();
+        for edge in self.weighted_edges.iter_mut() {
+            for vertex in [&mut edge.0, &mut edge.1] {
+                if !is_virtual[*vertex as usize] {
+                    continue;
+                }
+                let (taking_vertex, taken) = &mut current[*vertex as usize];
+                if *taken == max_degree {
+                    *taking_vertex = (split_map.original_vertex_num as usize + split_map.copy_of.len()) as VertexIndex;
+                    *taken = 0;
+                    split_map.copy_of.push(*vertex);
+                }
+                *taken += 1;
+                *vertex = *taking_vertex;
+            }
+        }
+        self.vertex_num += split_map.copy_of.len() as VertexNum;
+        self.virtual_vertices.extend(
+            (0..split_map.copy_of.len()).map(|index| (split_map.original_vertex_num as usize + index) as VertexIndex),
+        );
+        // every copy belongs to the same group as its original vertex
+        if let Some(groups) = self.virtual_vertex_groups.as_mut() {
+            for (index, &original) in split_map.copy_of.iter().enumerate() {
+                let copy = (split_map.original_vertex_num as usize + index) as VertexIndex;
+                if let Some(group) = groups.iter_mut().find(|group| group.contains(&original)) {
+                    group.push(copy);
+                }
+            }
+        }
+        split_map
+    }
+
+    /// keep only the edges satisfying `include`, which renumbers the edges; the syndrome patterns over the original edges
+    /// must be translated with the returned map, see [`EdgeIndexMap::map_erasures`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn filtered(
+        &self,
+        include: impl Fn(EdgeIndex, &(VertexIndex, VertexIndex, Weight)) -> bool,
+    ) -> (SolverInitializer, EdgeIndexMap) {
+        let new_to_original: Vec<EdgeIndex> = (self.weighted_edges.iter().enumerate())
+            .filter(|(edge_index, edge)| include(*edge_index as EdgeIndex, edge))
+            .map(|(edge_index, _)| edge_index as EdgeIndex)
+            .collect();
+        let mut initializer = SolverInitializer::new(
+            self.vertex_num,
+            new_to_original
+                .iter()
+                .map(|&edge_index| self.weighted_edges[edge_index as usize])
+                .collect(),
+            self.virtual_vertices.clone(),
+        );
+        initializer.virtual_vertex_groups = self.virtual_vertex_groups.clone();
+        (initializer, EdgeIndexMap::new(self.weighted_edges.len(), new_to_original))
+    }
+
+    /// turn the blocked edges of the syndrome pattern into dynamic weights that no valid correction would use: the
+    /// minimum-weight correction avoiding the blocked edges is a subset of the other edges, so giving each blocked edge
+    /// more than their total weight makes any correction through a blocked edge heavier; a returned correction that
+    /// still contains a blocked edge thus means that no valid correction exists without them
+    #[allow(clippy::unnecessary_cast)]
+    pub fn resolve_blocked_edges(&self, syndrome_pattern: &SyndromePattern) -> SyndromePattern {
+        let mut weights: Vec<Weight> = self.weighted_edges.iter().map(|&(_, _, weight)| weight).collect();
+        let mut is_blocked = vec![false; weights.len()];
+        for &edge_index in syndrome_pattern.blocked_edges.iter() {
+            is_blocked[edge_index as usize] = true;
+        }
+        let mut dynamic_weights = vec![];
+        let modified_weights = (syndrome_pattern.erasures.iter().map(|&edge_index| (edge_index, 0)))
+            .chain(syndrome_pattern.dynamic_weights.iter().cloned());
+        for (edge_index, weight) in modified_weights {
+            weights[edge_index as usize] = weight;
+            if !is_blocked[edge_index as usize] {
+                dynamic_weights.push((edge_index, weight));
+            }
+        }
+        let unblocked_weight: Weight = (weights.iter().zip(is_blocked.iter()))
+            .filter(|(_, &is_blocked)| !is_blocked)
+            .map(|(&weight, _)| weight.max(0))
+            .fold(0, |sum, weight| sum.saturating_add(weight));
+        let blocked_weight = unblocked_weight.saturating_add(2) / 2 * 2;
+        dynamic_weights.extend((syndrome_pattern.blocked_edges.iter()).map(|&edge_index| (edge_index, blocked_weight)));
+        SyndromePattern::new_dynamic_weights(syndrome_pattern.defect_vertices.clone(), vec![], dynamic_weights)
+    }
+
+    /// how `other` differs from `self`, matching the edges by their unordered pair of vertices regardless of the edge
+    /// indices; parallel edges between the same pair are matched in the order of increasing weight
+    pub fn diff(&self, other: &Self) -> InitializerDiff {
+        let edge_map = |initializer: &Self| {
+            let mut edges = BTreeMap::<(VertexIndex, VertexIndex), Vec<Weight>>::new();
+            for &(vertex_1, vertex_2, weight) in initializer.weighted_edges.iter() {
+                let key = (vertex_1.min(vertex_2), vertex_1.max(vertex_2));
+                edges.entry(key).or_default().push(weight);
+            }
+            for weights in edges.values_mut() {
+                weights.sort_unstable();
+            }
+            edges
+        };
+        let (edges, other_edges) = (edge_map(self), edge_map(other));
+        let mut diff = InitializerDiff {
+            vertex_num: (self.vertex_num != other.vertex_num).then_some((self.vertex_num, other.vertex_num)),
+            ..Default::default()
+        };
+        let empty = vec![];
+        let pairs: BTreeSet<_> = edges.keys().chain(other_edges.keys()).cloned().collect();
+        for (vertex_1, vertex_2) in pairs {
+            let weights = edges.get(&(vertex_1, vertex_2)).unwrap_or(&empty);
+            let other_weights = other_edges.get(&(vertex_1, vertex_2)).unwrap_or(&empty);
+            for (&weight, &other_weight) in weights.iter().zip(other_weights.iter()) {
+                if weight != other_weight {
+                    diff.changed_weights.push((vertex_1, vertex_2, weight, other_weight));
+                }
+            }
+            for &weight in weights.iter().skip(other_weights.len()) {
+                diff.removed_edges.push((vertex_1, vertex_2, weight));
+            }
+            for &other_weight in other_weights.iter().skip(weights.len()) {
+                diff.added_edges.push((vertex_1, vertex_2, other_weight));
+            }
+        }
+        let virtual_vertices: BTreeSet<VertexIndex> = self.virtual_vertices.iter().cloned().collect();
+        let other_virtual_vertices: BTreeSet<VertexIndex> = other.virtual_vertices.iter().cloned().collect();
+        diff.added_virtual_vertices = other_virtual_vertices.difference(&virtual_vertices).cloned().collect();
+        diff.removed_virtual_vertices = virtual_vertices.difference(&other_virtual_vertices).cloned().collect();
+        diff
+    }
+
+    /// panics with the human-readable [`InitializerDiff`] if the two decoding graphs differ other than the order of the
+    /// edges and of the virtual vertices
+    pub fn assert_equivalent(&self, other: &Self) {
+        let diff = self.diff(other);
+        assert!(diff.is_empty(), "the decoding graphs differ:\n{diff}");
     }
 }
 
-/// record the decoding time of multiple syndrome patterns
-pub struct BenchmarkProfiler {
-    /// each record corresponds to a different syndrome pattern
-    pub records: Vec<BenchmarkProfilerEntry>,
-    /// summation of all decoding time
-    pub sum_round_time: f64,
-    /// syndrome count
-    pub sum_syndrome: usize,
-    /// noisy measurement round
-    pub noisy_measurements: VertexNum,
-    /// the file to output the profiler results
-    pub benchmark_profiler_output: Option<File>,
+/// the difference between two decoding graphs, see [`SolverInitializer::diff`]; edges are given with the smaller vertex
+/// index first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InitializerDiff {
+    /// the number of vertices before and after, if changed
+    pub vertex_num: Option<(VertexNum, VertexNum)>,
+    pub added_edges: Vec<(VertexIndex, VertexIndex, Weight)>,
+    pub removed_edges: Vec<(VertexIndex, VertexIndex, Weight)>,
+    /// (vertex 1, vertex 2, weight before, weight after)
+    pub changed_weights: Vec<(VertexIndex, VertexIndex, Weight, Weight)>,
+    pub added_virtual_vertices: Vec<VertexIndex>,
+    pub removed_virtual_vertices: Vec<VertexIndex>,
 }
 
-impl BenchmarkProfiler {
-    pub fn new(noisy_measurements: VertexNum, detail_log_file: Option<(String, &PartitionInfo)>) -> Self {
-        let benchmark_profiler_output = detail_log_file.map(|(filename, partition_info)| {
-            let mut file = File::create(filename).unwrap();
+impl InitializerDiff {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// one line per difference, or `no difference`
+impl std::fmt::Display for InitializerDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no difference");
+        }
+        if let Some((vertex_num, other_vertex_num)) = self.vertex_num {
+            writeln!(f, "vertex_num: {vertex_num} -> {other_vertex_num}")?;
+        }
+        for (vertex_1, vertex_2, weight) in self.added_edges.iter() {
+            writeln!(f, "+ edge ({vertex_1}, {vertex_2}) weight {weight}")?;
+        }
+        for (vertex_1, vertex_2, weight) in self.removed_edges.iter() {
+            writeln!(f, "- edge ({vertex_1}, {vertex_2}) weight {weight}")?;
+        }
+        for (vertex_1, vertex_2, weight, other_weight) in self.changed_weights.iter() {
+            writeln!(f, "~ edge ({vertex_1}, {vertex_2}) weight {weight} -> {other_weight}")?;
+        }
+        for vertex_index in self.added_virtual_vertices.iter() {
+            writeln!(f, "+ virtual vertex {vertex_index}")?;
+        }
+        for vertex_index in self.removed_virtual_vertices.iter() {
+            writeln!(f, "- virtual vertex {vertex_index}")?;
+        }
+        Ok(())
+    }
+}
+
+/// the map from the vertices after [`SolverInitializer::split_virtual_vertices`] to the original ones
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pyclass(module = "fusion_blossom"))]
+pub struct VirtualSplitMap {
+    /// the number of vertices before splitting; vertices from this index on are copies
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub original_vertex_num: VertexNum,
+    /// the original virtual vertex of each copy: vertex `original_vertex_num + i` is a copy of `copy_of[i]`
+    #[cfg_attr(feature = "python_binding", pyo3(get))]
+    pub copy_of: Vec<VertexIndex>,
+}
+
+#[cfg_attr(feature = "python_binding", cfg_eval)]
+#[cfg_attr(feature = "python_binding", pymethods)]
+impl VirtualSplitMap {
+    /// the vertex in the original numbering
+    #[allow(clippy::unnecessary_cast)]
+    pub fn original_vertex(&self, vertex_index: VertexIndex) -> VertexIndex {
+        if vertex_index < self.original_vertex_num as VertexIndex {
+            vertex_index
+        } else {
+            self.copy_of[(vertex_index - self.original_vertex_num as VertexIndex) as usize]
+        }
+    }
+    /// translate the vertices, e.g. the virtual vertices of a perfect matching, to the original numbering
+    pub fn original_vertices(&self, vertices: Vec<VertexIndex>) -> Vec<VertexIndex> {
+        vertices
+            .into_iter()
+            .map(|vertex_index| self.original_vertex(vertex_index))
+            .collect()
+    }
+    #[cfg(feature = "python_binding")]
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// the map of edge indices between a decoding graph and the one produced by a transformation that renumbers the edges,
+/// e.g. [`SolverInitializer::filtered`]; an edge index is only meaningful together with the edge list it refers to, so
+/// anything produced over the original edges, like the erasures of a simulator, must be mapped before decoding, and the
+/// subgraph must be mapped back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeIndexMap {
+    /// the index of each original edge in the transformed graph, or `None` if it's removed
+    original_to_new: Vec<Option<EdgeIndex>>,
+    /// the original index of each edge in the transformed graph
+    new_to_original: Vec<EdgeIndex>,
+}
+
+impl EdgeIndexMap {
+    /// `new_to_original[new_index] = original_index`, where each original edge appears at most once
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new(original_edge_num: usize, new_to_original: Vec<EdgeIndex>) -> Self {
+        let mut original_to_new = vec![None; original_edge_num];
+        for (new_index, &original_index) in new_to_original.iter().enumerate() {
+            assert!(
+                (original_index as usize) < original_edge_num,
+                "original edge {original_index} out of range for {original_edge_num} edges"
+            );
+            assert!(
+                original_to_new[original_index as usize].is_none(),
+                "original edge {original_index} is mapped twice"
+            );
+            original_to_new[original_index as usize] = Some(new_index as EdgeIndex);
+        }
+        Self {
+            original_to_new,
+            new_to_original,
+        }
+    }
+
+    /// the map of a transformation that keeps the edge numbering
+    #[allow(clippy::unnecessary_cast)]
+    pub fn identity(edge_num: usize) -> Self {
+        Self::new(edge_num, (0..edge_num as EdgeIndex).collect())
+    }
+
+    pub fn original_edge_num(&self) -> usize {
+        self.original_to_new.len()
+    }
+
+    pub fn new_edge_num(&self) -> usize {
+        self.new_to_original.len()
+    }
+
+    /// the index of an original edge in the transformed graph, or `None` if it's removed
+    #[allow(clippy::unnecessary_cast)]
+    pub fn map_edge(&self, original_index: EdgeIndex) -> Option<EdgeIndex> {
+        self.original_to_new[original_index as usize]
+    }
+
+    /// the original index of an edge in the transformed graph
+    #[allow(clippy::unnecessary_cast)]
+    pub fn original_edge(&self, new_index: EdgeIndex) -> EdgeIndex {
+        self.new_to_original[new_index as usize]
+    }
+
+    /// translate a subgraph of the transformed graph back to the original edges
+    pub fn original_edges(&self, new_indices: &[EdgeIndex]) -> Vec<EdgeIndex> {
+        new_indices.iter().map(|&new_index| self.original_edge(new_index)).collect()
+    }
+
+    /// translate the erasures, dynamic weights and blocked edges of a syndrome pattern over the original edges; those on
+    /// the removed edges are dropped since the transformed graph cannot use these edges anyway
+    pub fn map_erasures(&self, syndrome_pattern: &SyndromePattern) -> SyndromePattern {
+        let mut mapped = syndrome_pattern.clone();
+        mapped.erasures = (syndrome_pattern.erasures.iter())
+            .filter_map(|&edge_index| self.map_edge(edge_index))
+            .collect();
+        mapped.dynamic_weights = (syndrome_pattern.dynamic_weights.iter())
+            .filter_map(|&(edge_index, weight)| self.map_edge(edge_index).map(|new_index| (new_index, weight)))
+            .collect();
+        mapped.blocked_edges = (syndrome_pattern.blocked_edges.iter())
+            .filter_map(|&edge_index| self.map_edge(edge_index))
+            .collect();
+        mapped
+    }
+
+    /// the map of applying the transformation of `self` and then the one of `next`
+    pub fn then(&self, next: &EdgeIndexMap) -> EdgeIndexMap {
+        assert_eq!(
+            self.new_edge_num(),
+            next.original_edge_num(),
+            "the second transformation must apply to the result of the first one"
+        );
+        Self::new(
+            self.original_edge_num(),
+            (next.new_to_original.iter())
+                .map(|&middle_index| self.original_edge(middle_index))
+                .collect(),
+        )
+    }
+}
+
+/// slices a space-time syndrome pattern into windows of consecutive time layers, for sliding-window decoding; an edge
+/// belongs to a window only if both its vertices do, so the erasures, dynamic weights and blocked edges on the edges
+/// cut by a window boundary are dropped by every window, see [`Self::is_edge_inside`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyndromeWindower {
+    /// the time layer of each vertex
+    pub vertex_layers: Vec<usize>,
+    /// the vertices of each edge
+    pub edges: Vec<(VertexIndex, VertexIndex)>,
+    /// the number of time layers
+    pub layer_num: usize,
+}
+
+impl SyndromeWindower {
+    /// a vertex at time `t` belongs to layer `round((t - t_min) / layer_height)`, where `t_min` is the earliest time
+    pub fn new(initializer: &SolverInitializer, positions: &[VisualizePosition], layer_height: f64) -> Self {
+        or_panic(Self::try_new(initializer, positions, layer_height))
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn try_new(
+        initializer: &SolverInitializer,
+        positions: &[VisualizePosition],
+        layer_height: f64,
+    ) -> Result<Self, FusionError> {
+        if positions.len() != initializer.vertex_num as usize {
+            return Err(FusionError::InvalidInput(format!(
+                "{} positions for {} vertices",
+                positions.len(),
+                initializer.vertex_num
+            )));
+        }
+        if !(layer_height > 0. && layer_height.is_finite()) {
+            return Err(FusionError::InvalidInput(format!("invalid layer height {layer_height}")));
+        }
+        let t_min = positions.iter().map(|position| position.t).fold(f64::INFINITY, f64::min);
+        let vertex_layers = (positions.iter())
+            .map(|position| ((position.t - t_min) / layer_height).round() as usize)
+            .collect();
+        Ok(Self::from_vertex_layers(initializer, vertex_layers))
+    }
+
+    /// the vertices are ordered by time, each layer consisting of `layer_vertex_num` vertices
+    #[allow(clippy::unnecessary_cast)]
+    pub fn new_layer_vertex_num(initializer: &SolverInitializer, layer_vertex_num: VertexNum) -> Self {
+        assert!(layer_vertex_num > 0, "layer must have at least one vertex");
+        let vertex_layers = (0..initializer.vertex_num)
+            .map(|vertex_index| (vertex_index / layer_vertex_num) as usize)
+            .collect();
+        Self::from_vertex_layers(initializer, vertex_layers)
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_vertex_layers(initializer: &SolverInitializer, vertex_layers: Vec<usize>) -> Self {
+        assert_eq!(vertex_layers.len(), initializer.vertex_num as usize, "one layer per vertex");
+        let layer_num = vertex_layers.iter().max().map_or(0, |layer| layer + 1);
+        Self {
+            vertex_layers,
+            edges: (initializer.weighted_edges.iter()).map(|&(i, j, _)| (i, j)).collect(),
+            layer_num,
+        }
+    }
+
+    #[allow(clippy::unnecessary_cast)]
+    pub fn vertex_layer(&self, vertex_index: VertexIndex) -> usize {
+        self.vertex_layers[vertex_index as usize]
+    }
+
+    /// whether both vertices of the edge are in the layer range
+    #[allow(clippy::unnecessary_cast)]
+    pub fn is_edge_inside(&self, edge_index: EdgeIndex, layer_range: &std::ops::Range<usize>) -> bool {
+        let (i, j) = self.edges[edge_index as usize];
+        layer_range.contains(&self.vertex_layer(i)) && layer_range.contains(&self.vertex_layer(j))
+    }
+
+    /// the defects in the layer range, and the erasures, dynamic weights and blocked edges entirely inside the range
+    pub fn slice(&self, syndrome_pattern: &SyndromePattern, layer_range: std::ops::Range<usize>) -> SyndromePattern {
+        let inside = |edge_index: &EdgeIndex| self.is_edge_inside(*edge_index, &layer_range);
+        SyndromePattern {
+            defect_vertices: (syndrome_pattern.defect_vertices.iter())
+                .filter(|&&vertex_index| layer_range.contains(&self.vertex_layer(vertex_index)))
+                .cloned()
+                .collect(),
+            erasures: syndrome_pattern
+                .erasures
+                .iter()
+                .filter(|edge_index| inside(edge_index))
+                .cloned()
+                .collect(),
+            dynamic_weights: (syndrome_pattern.dynamic_weights.iter())
+                .filter(|(edge_index, _)| inside(edge_index))
+                .cloned()
+                .collect(),
+            blocked_edges: (syndrome_pattern.blocked_edges.iter())
+                .filter(|edge_index| inside(edge_index))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// the defects in the layer range with an edge to a vertex outside the range, whose matching may be an artifact of
+    /// the cut; the order follows the syndrome pattern
+    pub fn boundary_defects(
+        &self,
+        syndrome_pattern: &SyndromePattern,
+        layer_range: std::ops::Range<usize>,
+    ) -> Vec<VertexIndex> {
+        let mut is_boundary = BTreeSet::new();
+        for &(i, j) in self.edges.iter() {
+            let (i_inside, j_inside) = (
+                layer_range.contains(&self.vertex_layer(i)),
+                layer_range.contains(&self.vertex_layer(j)),
+            );
+            if i_inside != j_inside {
+                is_boundary.insert(if i_inside { i } else { j });
+            }
+        }
+        (syndrome_pattern.defect_vertices.iter())
+            .filter(|vertex_index| is_boundary.contains(vertex_index))
+            .cloned()
+            .collect()
+    }
+}
+
+/// errors when building a decoding graph from a check matrix, see [`SolverInitializer::from_check_matrix`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckMatrixError {
+    /// the number of weights differs from the number of columns
+    WeightNum { column_num: usize, weight_num: usize },
+    /// a column flips no detector
+    EmptyColumn { column: usize },
+    /// a column flips more than two detectors, which is a hyperedge that matching cannot decode
+    HyperEdge { column: usize, nonzero_num: usize },
+    /// a column refers to a row beyond the number of detectors
+    RowOutOfRange { column: usize, row: VertexIndex },
+    /// two columns flip the same pair of detectors
+    ParallelColumns { columns: (usize, usize) },
+}
+
+impl std::fmt::Display for CheckMatrixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::WeightNum { column_num, weight_num } => {
+                write!(
+                    f,
+                    "expecting {} weights, one for each column, but got {}",
+                    column_num, weight_num
+                )
+            }
+            Self::EmptyColumn { column } => write!(f, "column {} has no nonzero entry", column),
+            Self::HyperEdge { column, nonzero_num } => write!(
+                f,
+                "column {} has {} nonzero entries but matching only supports columns with 1 or 2 nonzero entries",
+                column, nonzero_num
+            ),
+            Self::RowOutOfRange { column, row } => write!(f, "column {} refers to row {} out of range", column, row),
+            Self::ParallelColumns { columns } => write!(
+                f,
+                "columns {} and {} flip the same detectors, consider merging them into a single column",
+                columns.0, columns.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CheckMatrixError {}
+
+impl SolverInitializer {
+    /// build the decoding graph of a check matrix in the compressed sparse column format: column `j` is an error that
+    /// flips the detectors `row_indices[column_pointers[j]..column_pointers[j + 1]]` with an (even) weight `weights[j]`;
+    /// edge `j` is always column `j`, and a column with a single nonzero entry connects to its own virtual vertex numbered
+    /// after all the detectors
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_check_matrix(
+        detector_num: VertexNum,
+        column_pointers: &[usize],
+        row_indices: &[VertexIndex],
+        weights: &[Weight],
+    ) -> Result<Self, CheckMatrixError> {
+        let column_num = column_pointers.len().saturating_sub(1);
+        if weights.len() != column_num {
+            return Err(CheckMatrixError::WeightNum {
+                column_num,
+                weight_num: weights.len(),
+            });
+        }
+        let mut weighted_edges = Vec::with_capacity(column_num);
+        let mut virtual_vertices = vec![];
+        let mut detector_pairs = BTreeMap::new();
+        for column in 0..column_num {
+            let rows = &row_indices[column_pointers[column]..column_pointers[column + 1]];
+            if let Some(&row) = rows.iter().find(|&&row| row as usize >= detector_num as usize) {
+                return Err(CheckMatrixError::RowOutOfRange { column, row });
+            }
+            let (vertex_1, vertex_2) = match *rows {
+                [] => return Err(CheckMatrixError::EmptyColumn { column }),
+                [detector] => {
+                    let virtual_vertex = (detector_num as usize + virtual_vertices.len()) as VertexIndex;
+                    virtual_vertices.push(virtual_vertex);
+                    (detector, virtual_vertex)
+                }
+                [detector_1, detector_2] => {
+                    let pair = (detector_1.min(detector_2), detector_1.max(detector_2));
+                    if let Some(previous) = detector_pairs.insert(pair, column) {
+                        return Err(CheckMatrixError::ParallelColumns {
+                            columns: (previous, column),
+                        });
+                    }
+                    (detector_1, detector_2)
+                }
+                _ => {
+                    return Err(CheckMatrixError::HyperEdge {
+                        column,
+                        nonzero_num: rows.len(),
+                    })
+                }
+            };
+            weighted_edges.push((vertex_1, vertex_2, weights[column]));
+        }
+        let vertex_num = (detector_num as usize + virtual_vertices.len()) as VertexNum;
+        Ok(Self::new(vertex_num, weighted_edges, virtual_vertices))
+    }
+
+    /// check that the decoding graph is valid, for inputs that must not panic the solver (e.g. from another process)
+    #[allow(clippy::unnecessary_cast)]
+    pub fn check(&self) -> Result<(), String> {
+        let vertex_num = self.vertex_num as usize;
+        for &virtual_vertex in self.virtual_vertices.iter() {
+            if virtual_vertex as usize >= vertex_num {
+                return Err(format!("virtual vertex {virtual_vertex} out of range for {vertex_num}"));
+            }
+        }
+        for &(vertex_1, vertex_2, weight) in self.weighted_edges.iter() {
+            if vertex_1 as usize >= vertex_num || vertex_2 as usize >= vertex_num || vertex_1 == vertex_2 {
+                return Err(format!("invalid edge ({vertex_1}, {vertex_2}) for {vertex_num} vertices"));
+            }
+            check_even_weight(weight)?;
+        }
+        Ok(())
+    }
+
+    /// the largest edge weight such that the total weight of any path over the vertices cannot overflow [`Weight`]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn max_safe_weight(&self) -> Weight {
+        ((Weight::MAX as usize) / (self.vertex_num as usize).max(1)) as Weight
+    }
+
+    /// the edges with suspicious weights, ordered by edge index, an edge may have several issues: a zero (or negative)
+    /// weight, which is only meaningful for an erasure that is given in the syndrome pattern instead, an odd weight that
+    /// the solver cannot handle, or a weight above `max_reasonable`, e.g. [`Self::max_safe_weight`]
+    pub fn check_weights(&self, max_reasonable: Weight) -> Vec<(EdgeIndex, WeightIssue)> {
+        let mut issues = vec![];
+        for (edge_index, &(_, _, weight)) in self.weighted_edges.iter().enumerate() {
+            let edge_index = edge_index as EdgeIndex;
+            if weight <= 0 {
+                issues.push((edge_index, WeightIssue::NonPositive));
+            }
+            if weight % 2 != 0 {
+                issues.push((edge_index, WeightIssue::Odd));
+            }
+            if weight > max_reasonable {
+                issues.push((edge_index, WeightIssue::TooLarge));
+            }
+        }
+        issues
+    }
+
+    /// check that the syndrome pattern is valid on this (already checked) decoding graph
+    #[allow(clippy::unnecessary_cast)]
+    pub fn check_syndrome_pattern(&self, syndrome_pattern: &SyndromePattern) -> Result<(), String> {
+        let vertex_num = self.vertex_num as usize;
+        let edge_num = self.weighted_edges.len();
+        let mut is_virtual_or_defect = vec![false; vertex_num];
+        for &virtual_vertex in self.virtual_vertices.iter() {
+            is_virtual_or_defect[virtual_vertex as usize] = true;
+        }
+        for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+            if defect_vertex as usize >= vertex_num {
+                return Err(format!("defect vertex {defect_vertex} out of range for {vertex_num}"));
+            }
+            if is_virtual_or_defect[defect_vertex as usize] {
+                return Err(format!("defect vertex {defect_vertex} is virtual or duplicated"));
+            }
+            is_virtual_or_defect[defect_vertex as usize] = true;
+        }
+        if !syndrome_pattern.erasures.is_empty() && !syndrome_pattern.dynamic_weights.is_empty() {
+            return Err("erasures and dynamic_weights cannot be provided at the same time".to_string());
+        }
+        let edge_indices = (syndrome_pattern.erasures.iter())
+            .chain(syndrome_pattern.dynamic_weights.iter().map(|(edge_index, _)| edge_index))
+            .chain(syndrome_pattern.blocked_edges.iter());
+        for &edge_index in edge_indices {
+            if edge_index as usize >= edge_num {
+                return Err(format!("edge index {edge_index} out of range for {edge_num} edges"));
+            }
+        }
+        for &(_, weight) in syndrome_pattern.dynamic_weights.iter() {
+            check_even_weight(weight)?;
+        }
+        Ok(())
+    }
+}
+
+/// a suspicious edge weight, see [`SolverInitializer::check_weights`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeightIssue {
+    NonPositive,
+    Odd,
+    TooLarge,
+}
+
+fn check_even_weight(weight: Weight) -> Result<(), String> {
+    if weight < 0 || weight % 2 != 0 {
+        return Err(format!("weight {weight} must be a non-negative even number"));
+    }
+    Ok(())
+}
+
+/// timestamp type determines how many fast clear before a hard clear is required, see [`FastClear`]
+pub type FastClearTimestamp = usize;
+
+#[allow(dead_code)]
+/// use Xoshiro256StarStar for deterministic random number generator
+pub type DeterministicRng = rand_xoshiro::Xoshiro256StarStar;
+
+pub trait F64Rng {
+    fn next_f64(&mut self) -> f64;
+}
+
+impl F64Rng for DeterministicRng {
+    fn next_f64(&mut self) -> f64 {
+        f64::from_bits(0x3FF << 52 | self.next_u64() >> 12) - 1.
+    }
+}
+
+/// how the seed of each shot is derived from a master seed; the default is kept for one more release so that the syndrome
+/// patterns of existing benchmarks can be reproduced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(not(feature = "minimal"), derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Seeding {
+    /// consecutive shots use consecutive seeds, and worker `i` of [`crate::example_codes::ExampleCodeParallel`] offsets
+    /// the seed by `i * 1_000_000_000`, so its syndrome patterns depend on the number of workers
+    #[default]
+    V1,
+    /// shot `i` uses [`ShotSeeder::seed_for`]`(i)` wherever it is generated, so its syndrome pattern only depends on the
+    /// master seed and `i`
+    V2,
+}
+
+const SPLITMIX64_GAMMA: u64 = 0x9E3779B97F4A7C15;
+const SPLITMIX64_MULTIPLIERS: [u64; 2] = [0xBF58476D1CE4E5B9, 0x94D049BB133111EB];
+
+/// the multiplicative inverse of an odd number modulo 2^64, by Newton's iteration
+const fn inverse_odd(value: u64) -> u64 {
+    let mut inverse = value; // correct to 3 bits, doubled by each iteration
+    let mut iteration = 0;
+    while iteration < 5 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(value.wrapping_mul(inverse)));
+        iteration += 1;
+    }
+    inverse
+}
+
+/// derives the seed of every shot from a master seed with splitmix64, so that nearby shots get uncorrelated seeds and
+/// the seed of a shot doesn't depend on how the shots are distributed among threads or workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShotSeeder {
+    pub master_seed: u64,
+}
+
+impl ShotSeeder {
+    pub fn new(master_seed: u64) -> Self {
+        Self { master_seed }
+    }
+
+    pub fn seed_for(&self, shot_index: u64) -> u64 {
+        Self::mix(
+            self.master_seed
+                .wrapping_add(shot_index.wrapping_add(1).wrapping_mul(SPLITMIX64_GAMMA)),
+        )
+    }
+
+    /// the seed of shot `i + 1` given the seed of shot `i`, whatever the master seed; this is possible because the
+    /// finalizer of splitmix64 is a bijection, and lets a generator run ahead of the shots it is asked for
+    pub fn next_seed(seed: u64) -> u64 {
+        Self::mix(Self::unmix(seed).wrapping_add(SPLITMIX64_GAMMA))
+    }
+
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(SPLITMIX64_MULTIPLIERS[0]);
+        z = (z ^ (z >> 27)).wrapping_mul(SPLITMIX64_MULTIPLIERS[1]);
+        z ^ (z >> 31)
+    }
+
+    fn unmix(mut z: u64) -> u64 {
+        z ^= (z >> 31) ^ (z >> 62);
+        z = z.wrapping_mul(inverse_odd(SPLITMIX64_MULTIPLIERS[1]));
+        z ^= (z >> 27) ^ (z >> 54);
+        z = z.wrapping_mul(inverse_odd(SPLITMIX64_MULTIPLIERS[0]));
+        z ^ (z >> 30) ^ (z >> 60)
+    }
+}
+
+/// read a memory field like `VmRSS` from `/proc/self/status` in bytes; `None` on platforms other than Linux
+fn proc_status_memory(key: &str) -> Option<usize> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status
+        .lines()
+        .find(|line| line.starts_with(key) && line[key.len()..].starts_with(':'))?;
+    let kilobytes: usize = line[key.len() + 1..].trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// the current resident set size of this process in bytes, only available on Linux
+pub fn resident_memory() -> Option<usize> {
+    proc_status_memory("VmRSS")
+}
+
+/// the peak resident set size of this process in bytes, only available on Linux
+pub fn peak_resident_memory() -> Option<usize> {
+    proc_status_memory("VmHWM")
+}
+
+/// the message of a panic caught by [`std::panic::catch_unwind`]
+pub fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// reset the peak resident set size to the current one (Linux 4.0+), so that [`peak_resident_memory`] only reflects later allocations
+pub fn reset_peak_resident_memory() -> bool {
+    std::fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// a log-bucketed latency histogram in the style of HdrHistogram: every bucket spans at most 1/64 of its value,
+/// so the memory is bounded regardless of the number of records while percentiles stay within ~1.6% relative error
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// the number of records in each bucket, see [`Self::bucket_of`]
+    pub buckets: Vec<u64>,
+    /// the number of records
+    pub count: u64,
+    /// the exact maximum latency in seconds
+    pub max: f64,
+}
+
+impl LatencyHistogram {
+    const SUB_BUCKET_BITS: u32 = 6;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn bucket_of(nanoseconds: u64) -> usize {
+        let sub_bucket_count = 1u64 << (Self::SUB_BUCKET_BITS + 1);
+        if nanoseconds < sub_bucket_count {
+            return nanoseconds as usize;
+        }
+        let shift = 63 - nanoseconds.leading_zeros() - Self::SUB_BUCKET_BITS;
+        ((shift as u64) << Self::SUB_BUCKET_BITS) as usize + (nanoseconds >> shift) as usize
+    }
+
+    /// the middle of the range of values that fall into this bucket
+    fn value_of(bucket: usize) -> u64 {
+        let sub_bucket_count = 1usize << (Self::SUB_BUCKET_BITS + 1);
+        if bucket < sub_bucket_count {
+            return bucket as u64;
+        }
+        let shift = (bucket >> Self::SUB_BUCKET_BITS) - 1;
+        let sub_bucket = (bucket & ((1 << Self::SUB_BUCKET_BITS) - 1)) + (1 << Self::SUB_BUCKET_BITS);
+        ((sub_bucket as u64) << shift) + ((1u64 << shift) >> 1)
+    }
+
+    /// record a latency in seconds
+    pub fn record(&mut self, latency: f64) {
+        let bucket = Self::bucket_of((latency * 1e9).round() as u64);
+        if bucket >= self.buckets.len() {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.max = self.max.max(latency);
+    }
+
+    /// the latency in seconds at the given percentile in [0, 1] using the nearest-rank method, e.g. 1 gives the maximum
+    pub fn percentile(&self, percentile: f64) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+        let rank = ((percentile * self.count as f64).ceil() as u64).clamp(1, self.count);
+        if rank == self.count {
+            return self.max;
+        }
+        let mut accumulated = 0;
+        for (bucket, &count) in self.buckets.iter().enumerate() {
+            accumulated += count;
+            if accumulated >= rank {
+                return (Self::value_of(bucket) as f64 / 1e9).min(self.max);
+            }
+        }
+        unreachable!("the counts of buckets must sum up to the total count")
+    }
+}
+
+/// record the decoding time of multiple syndrome patterns; not available in WebAssembly which has no wall clock
+#[cfg(not(target_arch = "wasm32"))]
+pub struct BenchmarkProfiler {
+    /// each record corresponds to a different syndrome pattern
+    pub records: Vec<BenchmarkProfilerEntry>,
+    /// summation of all decoding time
+    pub sum_round_time: f64,
+    /// syndrome count
+    pub sum_syndrome: usize,
+    /// noisy measurement round
+    pub noisy_measurements: VertexNum,
+    /// the file to output the profiler results
+    pub benchmark_profiler_output: Option<File>,
+    /// the depth of the fusion tree, reported in the brief statistics if set
+    pub fusion_depth: Option<usize>,
+    /// the number of remaining warmup rounds, which are excluded from all statistics
+    pub warmup: usize,
+    /// the decoding time of every (non-warmup) record
+    pub latency_histogram: LatencyHistogram,
+    /// the number of rounds recorded before resuming from [`BenchmarkProfilerState`], whose entries are not kept
+    pub resumed_rounds: usize,
+}
+
+/// the accumulated statistics of a [`BenchmarkProfiler`], to resume an interrupted benchmark
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BenchmarkProfilerState {
+    pub rounds: usize,
+    pub sum_round_time: f64,
+    pub sum_syndrome: usize,
+    pub latency_histogram: LatencyHistogram,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BenchmarkProfiler {
+    pub fn new(noisy_measurements: VertexNum, detail_log_file: Option<(String, &PartitionInfo)>) -> Self {
+        let benchmark_profiler_output = detail_log_file.map(|(filename, partition_info)| {
+            let mut file = File::create(filename).unwrap();
             file.write_all(serde_json::to_string(&partition_info.config).unwrap().as_bytes())
                 .unwrap();
             file.write_all(b"\n").unwrap();
             file.write_all(
                 serde_json::to_string(&json!({
                     "noisy_measurements": noisy_measurements,
+                    "fusion_depth": partition_info.fusion_depth(),
                 }))
                 .unwrap()
                 .as_bytes(),
@@ -668,8 +2123,32 @@ impl BenchmarkProfiler {
             sum_syndrome: 0,
             noisy_measurements,
             benchmark_profiler_output,
+            fusion_depth: None,
+            warmup: 0,
+            latency_histogram: LatencyHistogram::new(),
+            resumed_rounds: 0,
+        }
+    }
+    /// the number of (non-warmup) rounds, including those before resuming
+    pub fn rounds(&self) -> usize {
+        self.resumed_rounds + self.records.len()
+    }
+    pub fn state(&self) -> BenchmarkProfilerState {
+        BenchmarkProfilerState {
+            rounds: self.rounds(),
+            sum_round_time: self.sum_round_time,
+            sum_syndrome: self.sum_syndrome,
+            latency_histogram: self.latency_histogram.clone(),
         }
     }
+    /// continue the statistics of an interrupted benchmark; must be called before any record
+    pub fn resume(&mut self, state: BenchmarkProfilerState) {
+        assert!(self.records.is_empty(), "cannot resume a benchmark profiler with records");
+        self.resumed_rounds = state.rounds;
+        self.sum_round_time = state.sum_round_time;
+        self.sum_syndrome = state.sum_syndrome;
+        self.latency_histogram = state.latency_histogram;
+    }
     /// record the beginning of a decoding procedure
     pub fn begin(&mut self, syndrome_pattern: &SyndromePattern) {
         // sanity check last entry, if exists, is complete
@@ -697,6 +2176,12 @@ impl BenchmarkProfiler {
             .last_mut()
             .expect("last entry not exists, call `begin` before `end`");
         last_entry.record_end();
+        if self.warmup > 0 {
+            self.warmup -= 1;
+            self.records.pop();
+            return;
+        }
+        self.latency_histogram.record(last_entry.round_time.unwrap());
         self.sum_round_time += last_entry.round_time.unwrap();
         self.sum_syndrome += last_entry.syndrome_pattern.defect_vertices.len();
         if let Some(file) = self.benchmark_profiler_output.as_mut() {
@@ -722,13 +2207,18 @@ impl BenchmarkProfiler {
     }
     /// print out a brief one-line statistics
     pub fn brief(&self) -> String {
-        let total = self.sum_round_time / (self.records.len() as f64);
+        let total = self.sum_round_time / (self.rounds() as f64);
         let per_round = total / (1. + self.noisy_measurements as f64);
         let per_defect = self.sum_round_time / (self.sum_syndrome as f64);
-        format!("total: {total:.3e}, round: {per_round:.3e}, defect: {per_defect:.3e},")
+        let mut brief = format!("total: {total:.3e}, round: {per_round:.3e}, defect: {per_defect:.3e},");
+        if let Some(fusion_depth) = self.fusion_depth {
+            brief += format!(" fusion depth: {fusion_depth},").as_str();
+        }
+        brief
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 pub struct BenchmarkProfilerEntry {
     /// the syndrome pattern of this decoding problem
     pub syndrome_pattern: SyndromePattern,
@@ -740,6 +2230,7 @@ pub struct BenchmarkProfilerEntry {
     pub round_time: Option<f64>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl BenchmarkProfilerEntry {
     pub fn new(syndrome_pattern: &SyndromePattern) -> Self {
         Self {
@@ -774,6 +2265,91 @@ impl BenchmarkProfilerEntry {
     }
 }
 
+/// the progress of a long batch operation, reported after every completed item
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    pub completed: usize,
+    pub total: usize,
+    /// the time since the operation began
+    pub elapsed: std::time::Duration,
+    /// a short description of the configuration in progress, e.g. the parameters of a benchmark combination
+    pub current_config: String,
+}
+
+impl ProgressEvent {
+    /// the remaining time extrapolated from the average time per completed item
+    pub fn remaining(&self) -> Option<std::time::Duration> {
+        (self.completed > 0).then(|| {
+            self.elapsed
+                .mul_f64((self.total - self.completed) as f64 / self.completed as f64)
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "completed": self.completed,
+            "total": self.total,
+            "elapsed": self.elapsed.as_secs_f64(),
+            "current_config": self.current_config,
+        })
+    }
+}
+
+pub type ProgressCallback = Box<dyn Fn(ProgressEvent) + Send + Sync>;
+
+/// counts the completed items of a batch operation, possibly from multiple threads, and reports each completion to the
+/// callback; the callback is called by one thread at a time with increasing `completed`
+pub struct ProgressReporter {
+    callback: parking_lot::Mutex<Option<ProgressCallback>>,
+    completed: std::sync::atomic::AtomicUsize,
+    total: usize,
+    begin: std::time::Instant,
+    current_config: String,
+}
+
+impl ProgressReporter {
+    pub fn new(callback: Option<ProgressCallback>, total: usize, current_config: String) -> Self {
+        Self {
+            callback: parking_lot::Mutex::new(callback),
+            completed: std::sync::atomic::AtomicUsize::new(0),
+            total,
+            begin: std::time::Instant::now(),
+            current_config,
+        }
+    }
+
+    pub fn complete_one(&self) {
+        let callback = self.callback.lock();
+        let completed = self.completed.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        if let Some(callback) = callback.as_ref() {
+            callback(ProgressEvent {
+                completed,
+                total: self.total,
+                elapsed: self.begin.elapsed(),
+                current_config: self.current_config.clone(),
+            });
+        }
+    }
+
+    /// give back the callback to reuse it in the next operation
+    pub fn into_callback(self) -> Option<ProgressCallback> {
+        self.callback.into_inner()
+    }
+}
+
+/// call a Python callable with the dict of every [`ProgressEvent`]; the callback takes the GIL, so the batch operation
+/// should release it in the meantime. An exception raised by the callable is printed without stopping the operation
+#[cfg(feature = "python_binding")]
+pub fn py_progress_callback(callable: PyObject) -> ProgressCallback {
+    Box::new(move |event: ProgressEvent| {
+        Python::with_gil(|py| {
+            if let Err(error) = callable.call1(py, (json_to_pyobject_locked(event.to_json(), py),)) {
+                error.print(py);
+            }
+        })
+    })
+}
+
 /**
  * If you want to modify a field of a Rust struct, it will return a copy of it to avoid memory unsafety.
  * Thus, typical way of modifying a python field doesn't work, e.g. `obj.a.b.c = 1` won't actually modify `obj`.
@@ -859,6 +2435,112 @@ pub fn json_to_pyobject(value: serde_json::Value) -> PyObject {
     Python::with_gil(|py| json_to_pyobject_locked(value, py))
 }
 
+/// whether the value looks like a numpy array; checked by attributes to avoid depending on numpy
+#[cfg(feature = "python_binding")]
+fn is_py_array(value: &PyAny) -> bool {
+    value.hasattr("ndim").unwrap_or(false) && value.hasattr("dtype").unwrap_or(false)
+}
+
+/// extract vertex or edge indices from a list or a 1-D numpy array; an integer array of any dtype is converted with a
+/// single bulk copy through `tobytes` instead of extracting one Python object per element, which is one more copy than
+/// reading the array in place but avoids depending on numpy, and a boolean array is a dense mask whose `true` positions
+/// are the indices, as in [`SyndromePattern::from_dense`]
+#[cfg(feature = "python_binding")]
+pub fn py_indices(value: &PyAny, name: &str) -> PyResult<Vec<VertexIndex>> {
+    if !is_py_array(value) {
+        return value.extract().map_err(|error| {
+            PyTypeError::new_err(format!(
+                "`{name}` must be a list of non-negative integers or a 1-D integer or boolean array: {error}"
+            ))
+        });
+    }
+    let ndim: usize = value.getattr("ndim")?.extract()?;
+    if ndim != 1 {
+        return Err(PyValueError::new_err(format!(
+            "`{name}` must be a 1-D array, got a {ndim}-D array"
+        )));
+    }
+    let dtype = value.getattr("dtype")?;
+    let kind: String = dtype.getattr("kind")?.extract()?;
+    match kind.as_str() {
+        "b" => Ok(SyndromePattern::from_dense(&py_dense(value, name)?).defect_vertices),
+        "i" | "u" => {
+            if kind == "i" && value.rich_compare(0, CompareOp::Lt)?.call_method0("any")?.is_true()? {
+                return Err(PyValueError::new_err(format!("`{name}` must not contain negative indices")));
+            }
+            // `astype` doesn't copy an array that is already native `u64`, so only `tobytes` copies
+            let kwargs = [("copy", false)].into_py_dict(value.py());
+            let native = value.call_method("astype", ("=u8",), Some(kwargs))?;
+            let native: &PyBytes = native.call_method0("tobytes")?.downcast()?;
+            native
+                .as_bytes()
+                .chunks_exact(8)
+                .map(|bytes| {
+                    let index = u64::from_ne_bytes(bytes.try_into().unwrap());
+                    VertexIndex::try_from(index)
+                        .map_err(|_| PyValueError::new_err(format!("`{name}` contains an index {index} that is too large")))
+                })
+                .collect()
+        }
+        _ => Err(PyTypeError::new_err(format!(
+            "`{name}` must be an integer or boolean array, got dtype {dtype}"
+        ))),
+    }
+}
+
+/// extract dense measurement results from a list of booleans or 0/1 integers, or a 1-D boolean or 0/1 integer numpy
+/// array which is converted with a single bulk copy
+#[cfg(feature = "python_binding")]
+pub fn py_dense(value: &PyAny, name: &str) -> PyResult<Vec<bool>> {
+    let invalid_value = || PyValueError::new_err(format!("`{name}` must only contain 0 and 1"));
+    if !is_py_array(value) {
+        let dense: Vec<u8> = value.extract().map_err(|error| {
+            PyTypeError::new_err(format!(
+                "`{name}` must be a list of booleans or 0/1 integers, or a 1-D boolean or integer array: {error}"
+            ))
+        })?;
+        if dense.iter().any(|&bit| bit > 1) {
+            return Err(invalid_value());
+        }
+        return Ok(dense.into_iter().map(|bit| bit == 1).collect());
+    }
+    let ndim: usize = value.getattr("ndim")?.extract()?;
+    if ndim != 1 {
+        return Err(PyValueError::new_err(format!(
+            "`{name}` must be a 1-D array, got a {ndim}-D array"
+        )));
+    }
+    let dtype = value.getattr("dtype")?;
+    let kind: String = dtype.getattr("kind")?.extract()?;
+    match kind.as_str() {
+        "b" => {}
+        "i" | "u" => {
+            let not_bit = value
+                .rich_compare(0, CompareOp::Ne)?
+                .call_method1("__and__", (value.rich_compare(1, CompareOp::Ne)?,))?;
+            if not_bit.call_method0("any")?.is_true()? {
+                return Err(invalid_value());
+            }
+        }
+        _ => {
+            return Err(PyTypeError::new_err(format!(
+                "`{name}` must be a boolean or integer array, got dtype {dtype}"
+            )))
+        }
+    }
+    let dense: &PyBytes = value.call_method1("astype", ("u1",))?.call_method0("tobytes")?.downcast()?;
+    Ok(dense.as_bytes().iter().map(|&bit| bit != 0).collect())
+}
+
+/// a syndrome pattern, or its defect vertices given in the same way as [`py_indices`]
+#[cfg(feature = "python_binding")]
+pub fn py_syndrome_pattern(value: &PyAny) -> PyResult<SyndromePattern> {
+    if let Ok(syndrome_pattern) = value.extract::<SyndromePattern>() {
+        return Ok(syndrome_pattern);
+    }
+    Ok(SyndromePattern::new_vertices(py_indices(value, "syndrome_pattern")?))
+}
+
 #[cfg(feature = "python_binding")]
 pub fn pyobject_to_json_locked<'py>(value: PyObject, py: Python<'py>) -> serde_json::Value {
     let value: &PyAny = value.as_ref(py);
@@ -909,6 +2591,7 @@ pub(crate) fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PartitionInfo>()?;
     m.add_class::<PartitionConfig>()?;
     m.add_class::<SyndromePattern>()?;
+    m.add_class::<VirtualSplitMap>()?;
     use crate::pyo3::PyTypeInfo;
     // m.add_class::<IndexRange>()?;
     m.add("VertexRange", VertexRange::type_object(py))?;
@@ -922,6 +2605,34 @@ pub(crate) fn register(py: Python<'_>, m: &PyModule) -> PyResult<()> {
 pub mod tests {
     use super::*;
 
+    #[test]
+    fn util_latency_histogram_1() {
+        // cargo test util_latency_histogram_1 -- --nocapture
+        let mut benchmark_profiler = BenchmarkProfiler::new(0, None);
+        benchmark_profiler.warmup = 50;
+        let syndrome_pattern = SyndromePattern::new_vertices(vec![]);
+        for _ in 0..200 {
+            benchmark_profiler.begin(&syndrome_pattern);
+            benchmark_profiler.end(None);
+        }
+        assert_eq!(benchmark_profiler.latency_histogram.count, 150);
+        assert_eq!(benchmark_profiler.records.len(), 150);
+        // percentiles are within the relative precision of the buckets
+        let mut histogram = LatencyHistogram::new();
+        for i in 1..=100000 {
+            histogram.record(i as f64 / 1e6);
+        }
+        for (percentile, expected) in [(0.5, 0.05), (0.9, 0.09), (0.99, 0.099), (0.999, 0.0999), (1., 0.1)] {
+            let value = histogram.percentile(percentile);
+            assert!(
+                (value - expected).abs() / expected < 0.02,
+                "{percentile}: {value} vs {expected}"
+            );
+        }
+        assert_eq!(histogram.max, 0.1);
+        assert!(LatencyHistogram::new().percentile(0.5).is_nan());
+    }
+
     /// test syndrome partition utilities
     #[test]
     fn util_partitioned_syndrome_pattern_1() {
@@ -953,4 +2664,446 @@ pub mod tests {
             assert_eq!(owned_partitioned.whole_defect_range, expected_defect_range);
         }
     }
+
+    /// test the fusion tree shapes of sequential partitions
+    #[test]
+    fn util_fusion_tree_shape_1() {
+        // cargo test util_fusion_tree_shape_1 -- --nocapture
+        let build_info = |partition_num: usize, shape: &FusionTreeShape| {
+            let mut partition_config = PartitionConfig::new(10 * partition_num as VertexNum);
+            partition_config.partitions = (0..partition_num as VertexIndex)
+                .map(|i| VertexRange::new(if i == 0 { 0 } else { 10 * i + 1 }, 10 * (i + 1)))
+                .collect();
+            partition_config.fusions = shape.build_fusions(partition_num);
+            partition_config.info()
+        };
+        assert_eq!(FusionTreeShape::LinearChain.build_fusions(4), vec![(0, 1), (4, 2), (5, 3)]);
+        assert_eq!(
+            FusionTreeShape::BalancedBinary.build_fusions(5),
+            vec![(0, 1), (2, 3), (5, 6), (7, 4)]
+        );
+        for partition_num in [1, 2, 3, 7, 16] {
+            let chain_info = build_info(partition_num, &FusionTreeShape::LinearChain);
+            assert_eq!(chain_info.fusion_depth(), partition_num - 1);
+            let binary_info = build_info(partition_num, &FusionTreeShape::BalancedBinary);
+            let expected_depth = (partition_num as f64).log2().ceil() as usize;
+            assert_eq!(binary_info.fusion_depth(), expected_depth);
+        }
+        let custom = FusionTreeShape::Custom(vec![(2, 3), (1, 4), (0, 5)]); // fuse from the last partition
+        assert_eq!(build_info(4, &custom).fusion_depth(), 3);
+    }
+
+    /// infer the interfaces of a phenomenological code partitioned by hand into touching time ranges
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn util_infer_interfaces_1() {
+        // cargo test util_infer_interfaces_1 -- --nocapture
+        use super::super::example_codes::*;
+        use super::super::mwpm_solver::*;
+        let (d, noisy_measurements) = (5, 7);
+        let round_vertex_num = d * (d + 1);
+        let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+        let initializer = code.get_initializer();
+        let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+        partition_config.partitions = (0..4)
+            .map(|i| VertexRange::new(2 * i * round_vertex_num, 2 * (i + 1) * round_vertex_num))
+            .collect();
+        partition_config.fusions = FusionTreeShape::BalancedBinary.build_fusions(4);
+        partition_config.infer_interfaces(&initializer.weighted_edges).unwrap();
+        // the interface is the first round of the higher partition, except for the last 2 virtual vertices
+        // which have no edge to the previous round
+        let mut expected_config = PartitionConfig::new(initializer.vertex_num);
+        expected_config.partitions = vec![
+            VertexRange::new(0, 2 * round_vertex_num),
+            VertexRange::new(3 * round_vertex_num - 2, 4 * round_vertex_num),
+            VertexRange::new(5 * round_vertex_num - 2, 6 * round_vertex_num),
+            VertexRange::new(7 * round_vertex_num - 2, 8 * round_vertex_num),
+        ];
+        expected_config.fusions = vec![(0, 1), (2, 3), (4, 5)];
+        assert_eq!(partition_config.partitions, expected_config.partitions);
+        assert_eq!(partition_config.fusions, expected_config.fusions);
+        // the inferred partition decodes the same as the serial solver
+        let partition_info = partition_config.info();
+        let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, json!({}));
+        let mut serial_solver = SolverSerial::new(&initializer);
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            parallel_solver.solve(&syndrome_pattern);
+            serial_solver.solve(&syndrome_pattern);
+            assert_eq!(parallel_solver.sum_dual_variables(), serial_solver.sum_dual_variables());
+            parallel_solver.clear();
+            serial_solver.clear();
+        }
+        // an edge between the first and the third partition cannot be resolved by a balanced binary tree
+        let mut weighted_edges = initializer.weighted_edges.clone();
+        weighted_edges.push((1, 4 * round_vertex_num + 1, 2));
+        let mut partition_config = PartitionConfig::new(initializer.vertex_num);
+        partition_config.partitions = (0..4)
+            .map(|i| VertexRange::new(2 * i * round_vertex_num, 2 * (i + 1) * round_vertex_num))
+            .collect();
+        partition_config.fusions = FusionTreeShape::BalancedBinary.build_fusions(4);
+        assert_eq!(
+            partition_config.infer_interfaces(&weighted_edges),
+            Err(PartitionError::NonAdjacentUnits {
+                edge: (1, 4 * round_vertex_num + 1),
+                units: (0, 2)
+            })
+        );
+        // the second partition shrinks before the third one turns out to be empty, which must not be kept
+        let mut partition_config = PartitionConfig::new(5);
+        partition_config.partitions = vec![VertexRange::new(0, 2), VertexRange::new(2, 4), VertexRange::new(4, 5)];
+        partition_config.fusions = vec![(0, 1), (3, 2)];
+        let original = partition_config.clone();
+        let weighted_edges: Vec<_> = (0..4).map(|i| (i, i + 1, 2)).collect();
+        assert_eq!(
+            partition_config.infer_interfaces(&weighted_edges),
+            Err(PartitionError::EmptyPartition { partition_index: 2 })
+        );
+        assert_eq!(partition_config, original);
+        // an edge referring to a vertex beyond the partitioned range is reported instead of panicking
+        let weighted_edges = vec![(0, 1, 2), (3, 5, 2)];
+        assert_eq!(
+            partition_config.infer_interfaces(&weighted_edges),
+            Err(PartitionError::UnownedVertex {
+                edge: (3, 5),
+                vertex_index: 5
+            })
+        );
+        assert_eq!(partition_config, original);
+        // an invalid fusion tree is reported instead of panicking
+        let mut partition_config = PartitionConfig::new(4);
+        partition_config.partitions = vec![VertexRange::new(0, 2), VertexRange::new(2, 4)];
+        partition_config.fusions = vec![(0, 2)];
+        let original = partition_config.clone();
+        let weighted_edges: Vec<_> = (0..3).map(|i| (i, i + 1, 2)).collect();
+        assert_eq!(
+            partition_config.infer_interfaces(&weighted_edges),
+            Err(PartitionError::InvalidConfig(
+                "dependency wrong, 2 depending on 2".to_string()
+            ))
+        );
+        assert_eq!(partition_config, original);
+    }
+
+    #[test]
+    fn util_from_check_matrix_1() {
+        // cargo test util_from_check_matrix_1 -- --nocapture
+        use super::super::mwpm_solver::*;
+        // repetition code with 5 data qubits: column `j` flips checks `j - 1` and `j`
+        let column_pointers = [0, 1, 3, 5, 7, 8];
+        let row_indices = [0, 0, 1, 1, 2, 2, 3, 3];
+        let initializer = SolverInitializer::from_check_matrix(4, &column_pointers, &row_indices, &[2; 5]).unwrap();
+        assert_eq!(initializer.vertex_num, 6);
+        assert_eq!(initializer.virtual_vertices, vec![4, 5]);
+        assert_eq!(
+            initializer.weighted_edges,
+            vec![(0, 4, 2), (0, 1, 2), (1, 2, 2), (2, 3, 2), (3, 5, 2)]
+        );
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&SyndromePattern::new_vertices(vec![1, 2]));
+        assert_eq!(solver.subgraph(), vec![2]);
+        // invalid check matrices
+        assert_eq!(
+            SolverInitializer::from_check_matrix(4, &column_pointers, &row_indices, &[2; 4]),
+            Err(CheckMatrixError::WeightNum {
+                column_num: 5,
+                weight_num: 4
+            })
+        );
+        assert_eq!(
+            SolverInitializer::from_check_matrix(4, &[0, 1, 4], &[0, 0, 1, 2], &[2; 2]),
+            Err(CheckMatrixError::HyperEdge {
+                column: 1,
+                nonzero_num: 3
+            })
+        );
+        assert_eq!(
+            SolverInitializer::from_check_matrix(4, &[0, 2, 2], &[0, 1], &[2; 2]),
+            Err(CheckMatrixError::EmptyColumn { column: 1 })
+        );
+        assert_eq!(
+            SolverInitializer::from_check_matrix(4, &[0, 2, 4], &[0, 4, 1, 2], &[2; 2]),
+            Err(CheckMatrixError::RowOutOfRange { column: 0, row: 4 })
+        );
+        assert_eq!(
+            SolverInitializer::from_check_matrix(4, &[0, 2, 4], &[0, 1, 1, 0], &[2; 2]),
+            Err(CheckMatrixError::ParallelColumns { columns: (0, 1) })
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn util_split_virtual_vertices_1() {
+        // cargo test util_split_virtual_vertices_1 -- --nocapture
+        use crate::example_codes::{ExampleCode, PhenomenologicalPlanarCode};
+        use crate::mwpm_solver::{PrimalDualSolver, SolverSerial};
+        let mut code = PhenomenologicalPlanarCode::new(5, 4, 0.05, 500);
+        let initializer = code.get_initializer();
+        // route every boundary edge to a single virtual vertex, which then has a very high degree
+        let mut merged = initializer.clone();
+        let sink = merged.virtual_vertices[0];
+        for edge in merged.weighted_edges.iter_mut() {
+            for vertex in [&mut edge.0, &mut edge.1] {
+                if initializer.virtual_vertices.contains(vertex) {
+                    *vertex = sink;
+                }
+            }
+        }
+        let boundary_edge_num = merged.weighted_edges.iter().filter(|edge| edge.1 == sink).count();
+        let mut split = merged.clone();
+        let split_map = split.split_virtual_vertices(3);
+        assert_eq!(split_map.original_vertex_num, initializer.vertex_num);
+        assert_eq!(split_map.copy_of, vec![sink; boundary_edge_num.div_ceil(3) - 1]);
+        assert_eq!(
+            split.vertex_num as usize,
+            initializer.vertex_num as usize + split_map.copy_of.len()
+        );
+        split.check().unwrap();
+        let mut degrees = vec![0; split.vertex_num as usize];
+        for &(vertex_1, vertex_2, _) in split.weighted_edges.iter() {
+            degrees[vertex_1 as usize] += 1;
+            degrees[vertex_2 as usize] += 1;
+        }
+        assert!(split.virtual_vertices.iter().all(|&vertex| degrees[vertex as usize] <= 3));
+        for (edge_index, &(vertex_1, vertex_2, weight)) in split.weighted_edges.iter().enumerate() {
+            let original = merged.weighted_edges[edge_index];
+            assert_eq!(
+                (
+                    split_map.original_vertex(vertex_1),
+                    split_map.original_vertex(vertex_2),
+                    weight
+                ),
+                original
+            );
+        }
+        // splitting again is a no-op
+        assert!(split.clone().split_virtual_vertices(3).copy_of.is_empty());
+        // the matching weight is unchanged, and the subgraph needs no translation
+        let subgraph_weight = |initializer: &SolverInitializer, subgraph: &[EdgeIndex]| -> Weight {
+            subgraph
+                .iter()
+                .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                .sum()
+        };
+        let mut solvers: Vec<_> = [&initializer, &merged, &split]
+            .iter()
+            .map(|initializer| SolverSerial::new(initializer))
+            .collect();
+        for seed in 0..20 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            let weights: Vec<Weight> = solvers
+                .iter_mut()
+                .zip([&initializer, &merged, &split])
+                .map(|(solver, initializer)| {
+                    solver.solve(&syndrome_pattern);
+                    let weight = subgraph_weight(initializer, &solver.subgraph());
+                    assert_eq!(weight, solver.sum_dual_variables());
+                    solver.clear();
+                    weight
+                })
+                .collect();
+            assert_eq!(weights, vec![weights[0]; 3]);
+        }
+    }
+
+    #[cfg(not(feature = "minimal"))]
+    #[test]
+    fn util_virtual_vertex_groups() {
+        // cargo test util_virtual_vertex_groups -- --nocapture
+        // the json written by older versions has no groups
+        let old_json = r#"{"vertex_num":4,"weighted_edges":[[0,1,100],[1,2,100],[2,3,100]],"virtual_vertices":[0,3]}"#;
+        let initializer: SolverInitializer = serde_json::from_str(old_json).unwrap();
+        assert_eq!(
+            initializer,
+            SolverInitializer::new(4, vec![(0, 1, 100), (1, 2, 100), (2, 3, 100)], vec![0, 3])
+        );
+        assert_eq!(initializer.group_of(0), None);
+        let new_json = r#"{"vertex_num":4,"weighted_edges":[[0,1,100],[1,2,100],[2,3,100]],"virtual_vertices":[0,3],"virtual_vertex_groups":[[0],[3]]}"#;
+        let initializer: SolverInitializer = serde_json::from_str(new_json).unwrap();
+        assert_eq!(initializer.virtual_vertex_groups, Some(vec![vec![0], vec![3]]));
+        assert_eq!(serde_json::to_string(&initializer).unwrap(), new_json);
+        let decoded: SolverInitializer = bincode::deserialize(&bincode::serialize(&initializer).unwrap()).unwrap();
+        assert_eq!(decoded, initializer);
+        assert_eq!(
+            (0..4).map(|vertex| initializer.group_of(vertex)).collect::<Vec<_>>(),
+            vec![Some(0), None, None, Some(1)]
+        );
+        // the groups follow the vertices when permuted, filtered or split
+        let permuted = initializer.permuted(&[3, 2, 1, 0]);
+        assert_eq!(permuted.virtual_vertex_groups, Some(vec![vec![3], vec![0]]));
+        let (filtered, _) = initializer.filtered(|edge_index, _| edge_index != 1);
+        assert_eq!(filtered.virtual_vertex_groups, initializer.virtual_vertex_groups);
+        let mut merged = SolverInitializer::new(3, vec![(0, 1, 100), (0, 2, 100), (0, 2, 100)], vec![0]);
+        merged.virtual_vertex_groups = Some(vec![vec![0]]);
+        let split_map = merged.split_virtual_vertices(1);
+        assert_eq!(split_map.copy_of, vec![0, 0]);
+        assert_eq!(merged.virtual_vertex_groups, Some(vec![vec![0, 3, 4]]));
+        assert_eq!(merged.group_of(4), Some(0));
+    }
+
+    #[test]
+    fn util_partition_config_try_info() {
+        // cargo test util_partition_config_try_info -- --nocapture
+        let mut partition_config = PartitionConfig::new(10);
+        partition_config.partitions = vec![VertexRange::new(0, 4), VertexRange::new(5, 10)];
+        partition_config.fusions = vec![(0, 1)];
+        assert_eq!(partition_config.try_info().unwrap().units.len(), 3);
+        let config_error =
+            |partition_config: &PartitionConfig| matches!(partition_config.try_info(), Err(FusionError::Config(_)));
+        let mut invalid = partition_config.clone();
+        invalid.partitions.clear();
+        assert!(config_error(&invalid));
+        let mut invalid = partition_config.clone();
+        invalid.partitions[1] = VertexRange::new(5, 11);
+        assert!(config_error(&invalid));
+        let mut invalid = partition_config.clone();
+        invalid.fusions = vec![];
+        assert!(config_error(&invalid), "a unit is not fused");
+        let mut invalid = partition_config.clone();
+        invalid.fusions = vec![(1, 0)];
+        assert!(config_error(&invalid), "the lower unit must be the left one");
+        let mut invalid = partition_config.clone();
+        invalid.fusions = vec![(0, 1), (0, 2)];
+        assert!(config_error(&invalid), "fused twice");
+        let mut invalid = partition_config;
+        invalid.fusions = vec![(0, 2)];
+        assert!(config_error(&invalid), "depending on itself");
+    }
+
+    #[test]
+    fn util_syndrome_windower_1() {
+        // cargo test util_syndrome_windower_1 -- --nocapture
+        use crate::example_codes::{ExampleCode, PhenomenologicalPlanarCode};
+        let d = 5;
+        let mut code = PhenomenologicalPlanarCode::new(d, 8, 0.05, 500);
+        code.set_erasure_probability(0.05);
+        let initializer = code.get_initializer();
+        let windower = SyndromeWindower::new(&initializer, &code.get_positions(), 1.);
+        assert_eq!(windower.layer_num, 9);
+        assert_eq!(windower, SyndromeWindower::new_layer_vertex_num(&initializer, d * (d + 1)));
+        let windows: Vec<std::ops::Range<usize>> = (0..9).step_by(2).map(|start| start..(start + 2).min(9)).collect();
+        for seed in 0..10 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            assert!(!syndrome_pattern.erasures.is_empty());
+            let slices: Vec<SyndromePattern> = (windows.iter())
+                .map(|window| windower.slice(&syndrome_pattern, window.clone()))
+                .collect();
+            // the defects are ordered by time, so the windows concatenate to the original
+            let defect_vertices: Vec<VertexIndex> = (slices.iter())
+                .flat_map(|slice| slice.defect_vertices.iter().cloned())
+                .collect();
+            assert_eq!(defect_vertices, syndrome_pattern.defect_vertices);
+            // every erasure is either in exactly one window or on an edge cut by a window boundary
+            let is_cut = |edge_index: &EdgeIndex| windows.iter().all(|window| !windower.is_edge_inside(*edge_index, window));
+            let mut erasures: Vec<EdgeIndex> = (slices.iter())
+                .flat_map(|slice| slice.erasures.iter().cloned())
+                .chain(
+                    syndrome_pattern
+                        .erasures
+                        .iter()
+                        .filter(|edge_index| is_cut(edge_index))
+                        .cloned(),
+                )
+                .collect();
+            erasures.sort();
+            let mut expected = syndrome_pattern.erasures.clone();
+            expected.sort();
+            assert_eq!(erasures, expected);
+            // the boundary defects are on the first or last layer of the window, next to a vertex outside
+            for window in windows.iter() {
+                for vertex_index in windower.boundary_defects(&syndrome_pattern, window.clone()) {
+                    let layer = windower.vertex_layer(vertex_index);
+                    assert!(layer == window.start || layer + 1 == window.end);
+                    assert!(syndrome_pattern.defect_vertices.contains(&vertex_index));
+                }
+            }
+        }
+        // the whole range reproduces the syndrome and has no boundary
+        let syndrome_pattern = code.generate_random_errors(0);
+        assert_eq!(windower.slice(&syndrome_pattern, 0..9), syndrome_pattern);
+        assert!(windower.boundary_defects(&syndrome_pattern, 0..9).is_empty());
+        assert!(matches!(
+            SyndromeWindower::try_new(&initializer, &code.get_positions(), 0.),
+            Err(FusionError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            SyndromeWindower::try_new(&initializer, &[], 1.),
+            Err(FusionError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn util_shot_seeder_1() {
+        // cargo test util_shot_seeder_1 -- --nocapture
+        // the reference values of splitmix64 seeded with 1234567
+        let seeder = ShotSeeder::new(1234567);
+        assert_eq!(seeder.seed_for(0), 6457827717110365317);
+        assert_eq!(seeder.seed_for(1), 3203168211198807973);
+        for master_seed in [0, 1, 1234567, u64::MAX] {
+            let seeder = ShotSeeder::new(master_seed);
+            for shot_index in [0, 1, 2, 1000, u64::MAX - 1] {
+                assert_eq!(
+                    ShotSeeder::next_seed(seeder.seed_for(shot_index)),
+                    seeder.seed_for(shot_index + 1)
+                );
+            }
+        }
+        assert_eq!(serde_json::to_string(&Seeding::V2).unwrap(), "\"v2\"");
+        assert_eq!(Seeding::default(), Seeding::V1);
+    }
+    #[test]
+    fn util_initializer_diff_1() {
+        // cargo test util_initializer_diff_1 -- --nocapture
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 2), (1, 2, 4), (2, 3, 6), (2, 3, 8)], vec![0, 3]);
+        // reordering the edges, their vertices and the virtual vertices makes no difference
+        let reordered = SolverInitializer::new(4, vec![(3, 2, 8), (1, 0, 2), (2, 3, 6), (2, 1, 4)], vec![3, 0]);
+        assert!(initializer.diff(&reordered).is_empty());
+        initializer.assert_equivalent(&reordered);
+        assert_eq!(format!("{}", initializer.diff(&reordered)), "no difference\n");
+        let modified = SolverInitializer::new(5, vec![(1, 0, 2), (2, 3, 10), (2, 3, 6), (3, 4, 2)], vec![0, 4]);
+        let diff = initializer.diff(&modified);
+        assert_eq!(
+            diff,
+            InitializerDiff {
+                vertex_num: Some((4, 5)),
+                added_edges: vec![(3, 4, 2)],
+                removed_edges: vec![(1, 2, 4)],
+                changed_weights: vec![(2, 3, 8, 10)],
+                added_virtual_vertices: vec![4],
+                removed_virtual_vertices: vec![3],
+            }
+        );
+        assert_eq!(
+            format!("{diff}"),
+            "vertex_num: 4 -> 5\n+ edge (3, 4) weight 2\n- edge (1, 2) weight 4\n~ edge (2, 3) weight 8 -> 10\n\
+            + virtual vertex 4\n- virtual vertex 3\n"
+        );
+        let result = std::panic::catch_unwind(|| initializer.assert_equivalent(&modified));
+        assert!(result.is_err());
+    }
+    #[test]
+    fn util_check_weights_1() {
+        // cargo test util_check_weights_1 -- --nocapture
+        let initializer = SolverInitializer::new(4, vec![(0, 1, 2), (1, 2, 0), (2, 3, 3), (0, 3, 1000), (1, 3, -2)], vec![]);
+        assert_eq!(
+            initializer.check_weights(100),
+            vec![
+                (1, WeightIssue::NonPositive),
+                (2, WeightIssue::Odd),
+                (3, WeightIssue::TooLarge),
+                (4, WeightIssue::NonPositive),
+            ]
+        );
+        // an edge can have several issues, and a weight equal to the threshold is fine
+        let initializer = SolverInitializer::new(2, vec![(0, 1, 101)], vec![1]);
+        assert_eq!(
+            initializer.check_weights(100),
+            vec![(0, WeightIssue::Odd), (0, WeightIssue::TooLarge)]
+        );
+        assert_eq!(initializer.check_weights(101), vec![(0, WeightIssue::Odd)]);
+        assert_eq!(initializer.max_safe_weight(), Weight::MAX / 2);
+        assert!(SolverInitializer::new(2, vec![(0, 1, Weight::MAX / 2 - 1)], vec![1])
+            .check_weights(initializer.max_safe_weight())
+            .is_empty());
+    }
 }
@@ -0,0 +1,112 @@
+//! Verification
+//!
+//! Exhaustive checks of the decoder on small codes: every error pattern up to a given number of error edges is decoded
+//! by [`SolverSerial`] and compared with the brute-force [`ExactMatcher`]. The logical errors of each weight are the
+//! exact coefficients of the code-capacity failure polynomial `sum_k c_k p^k (1-p)^(n-k)`, up to the weight bound.
+//!
+
+use super::exact_matcher::*;
+use super::example_codes::*;
+use super::mwpm_solver::*;
+use super::util::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExhaustiveReport {
+    /// the number of edges `n` in the failure polynomial
+    pub edge_num: usize,
+    /// `patterns[k]` is the number of error patterns with `k` error edges, i.e. `n choose k`
+    pub patterns: Vec<usize>,
+    /// `logical_errors[k]` is the coefficient `c_k`, the number of error patterns with `k` error edges that are
+    /// corrected to a logical error
+    pub logical_errors: Vec<usize>,
+    /// the error edges where the decoder doesn't find the minimum-weight matching, which must be empty
+    pub suboptimal: Vec<Vec<EdgeIndex>>,
+}
+
+impl ExhaustiveReport {
+    /// the logical error rate at physical error rate `p`, exact up to the terms of `p^(max_error_weight + 1)`
+    pub fn failure_probability(&self, p: f64) -> f64 {
+        self.logical_errors
+            .iter()
+            .enumerate()
+            .map(|(k, &count)| count as f64 * p.powi(k as i32) * (1. - p).powi((self.edge_num - k) as i32))
+            .sum()
+    }
+}
+
+/// decode every error pattern of at most `max_error_weight` edges; the number of patterns grows like
+/// `n^max_error_weight`, so this is only feasible for small codes such as d=3 and d=5 code capacity codes
+#[allow(clippy::unnecessary_cast)]
+pub fn exhaustive_check(code: &mut impl ExampleCode, max_error_weight: usize) -> ExhaustiveReport {
+    let initializer = code.get_initializer();
+    let edge_num = initializer.weighted_edges.len();
+    let mut solver = SolverSerial::new(&initializer);
+    let mut exact_matcher = ExactMatcher::new(&initializer);
+    let mut report = ExhaustiveReport {
+        edge_num,
+        patterns: vec![0; max_error_weight + 1],
+        logical_errors: vec![0; max_error_weight + 1],
+        suboptimal: vec![],
+    };
+    for error_weight in 0..=max_error_weight.min(edge_num) {
+        // the combination `error_edges[0] < error_edges[1] < ...`, advanced in lexicographic order
+        let mut error_edges: Vec<EdgeIndex> = (0..error_weight).map(|index| index as EdgeIndex).collect();
+        loop {
+            let syndrome_pattern = code.apply_error_edges(&error_edges);
+            solver.solve(&syndrome_pattern);
+            let correction_edges = solver.subgraph();
+            let exact_matching = exact_matcher
+                .solve(&syndrome_pattern)
+                .expect("the error edges form a perfect matching");
+            if solver.sum_dual_variables() != exact_matching.weight {
+                report.suboptimal.push(error_edges.clone());
+            }
+            solver.clear();
+            report.patterns[error_weight] += 1;
+            if code.is_logical_error(&error_edges, &correction_edges) {
+                report.logical_errors[error_weight] += 1;
+            }
+            // the rightmost edge that can still move right
+            let Some(index) = (0..error_weight)
+                .rev()
+                .find(|&index| error_edges[index] as usize != edge_num - error_weight + index)
+            else {
+                break;
+            };
+            error_edges[index] += 1;
+            for next in index + 1..error_weight {
+                error_edges[next] = error_edges[next - 1] + 1;
+            }
+        }
+    }
+    code.apply_error_edges(&[]);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore = "slow, enumerates about 10^5 error patterns"]
+    fn verification_exhaustive_planar_code_1() {
+        // cargo test --release verification_exhaustive_planar_code_1 -- --ignored --nocapture
+        let mut code = CodeCapacityPlanarCode::new(3, 0.1, 500);
+        let report = exhaustive_check(&mut code, 4);
+        assert!(report.suboptimal.is_empty(), "suboptimal: {:?}", report.suboptimal);
+        assert_eq!(report.patterns, vec![1, 13, 78, 286, 715]);
+        // every single error is corrected
+        assert_eq!(report.logical_errors[0..2], [0, 0]);
+        // 9 of the weight-2 failures are two errors on one of the 3 horizontal logical operators of weight 3, which any
+        // minimum-weight decoder completes to a logical error; the other 12 are ties between two corrections of weight 2
+        // that differ by a logical operator of weight 4, so they depend on how the decoder breaks ties
+        assert_eq!(report.logical_errors[2], 21);
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let report = exhaustive_check(&mut code, 4);
+        assert!(report.suboptimal.is_empty(), "suboptimal: {:?}", report.suboptimal);
+        // every pattern of at most 2 errors is corrected
+        assert_eq!(report.logical_errors[0..3], [0, 0, 0]);
+        println!("{}", serde_json::to_string(&report).unwrap());
+    }
+}
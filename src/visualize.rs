@@ -3,16 +3,34 @@
 //! This module helps visualize the progress of a fusion blossom algorithm
 //!
 
+#[cfg(not(feature = "minimal"))]
+use crate::base64::Engine;
+#[cfg(not(feature = "minimal"))]
 use crate::chrono::Local;
+use crate::dual_module::MaxUpdateLength;
+#[cfg(not(feature = "minimal"))]
+use crate::flate2::read::GzDecoder;
+#[cfg(not(feature = "minimal"))]
+use crate::flate2::write::GzEncoder;
+#[cfg(not(feature = "minimal"))]
+use crate::flate2::Compression;
+use crate::primal_module::PerfectMatching;
 use crate::serde::{Deserialize, Serialize};
 use crate::serde_json;
+#[cfg(not(feature = "minimal"))]
 use crate::urlencoding;
-#[cfg(feature = "python_binding")]
 use crate::util::*;
 #[cfg(feature = "python_binding")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python_binding")]
 use pyo3::prelude::*;
+use std::collections::BTreeMap;
 use std::fs::File;
+#[cfg(not(feature = "minimal"))]
+use std::io::Read;
 use std::io::{Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 
 pub trait FusionVisualizer {
     /// take a snapshot, set `abbrev` to true to save space
@@ -35,7 +53,7 @@ macro_rules! bind_trait_fusion_visualizer {
 #[allow(unused_imports)]
 pub use bind_trait_fusion_visualizer;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct VisualizePosition {
@@ -50,6 +68,136 @@ pub struct VisualizePosition {
     pub t: f64,
 }
 
+/// the axis to drop when projecting the positions into 2D
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectionAxis {
+    /// vertical axis
+    I,
+    /// horizontal axis
+    J,
+    /// time axis, giving a top-down view of a space-time decoding graph
+    T,
+}
+
+impl VisualizePosition {
+    /// drop one axis so that the vertices differing only in that axis overlap
+    pub fn project_2d(positions: &[VisualizePosition], axis: ProjectionAxis) -> Vec<VisualizePosition> {
+        positions
+            .iter()
+            .map(|position| {
+                let mut position = position.clone();
+                *position.coordinate_mut(axis) = 0.;
+                position
+            })
+            .collect()
+    }
+
+    pub fn coordinate_mut(&mut self, axis: ProjectionAxis) -> &mut f64 {
+        match axis {
+            ProjectionAxis::I => &mut self.i,
+            ProjectionAxis::J => &mut self.j,
+            ProjectionAxis::T => &mut self.t,
+        }
+    }
+}
+
+/// a single step of [`PositionTransform`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PositionTransformStep {
+    Translate {
+        i: f64,
+        j: f64,
+        t: f64,
+    },
+    Scale {
+        i: f64,
+        j: f64,
+        t: f64,
+    },
+    /// rotate in the i-j plane about the t-axis, in degrees
+    RotateT {
+        degrees: f64,
+    },
+    SwapAxes {
+        a: ProjectionAxis,
+        b: ProjectionAxis,
+    },
+}
+
+/// a sequence of coordinate transforms applied in order, built like
+/// `PositionTransform::new().rotate_t(90.).scale(2., 2., 1.)`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PositionTransform {
+    pub steps: Vec<PositionTransformStep>,
+}
+
+impl PositionTransform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn translate(mut self, i: f64, j: f64, t: f64) -> Self {
+        self.steps.push(PositionTransformStep::Translate { i, j, t });
+        self
+    }
+
+    pub fn scale(mut self, i: f64, j: f64, t: f64) -> Self {
+        self.steps.push(PositionTransformStep::Scale { i, j, t });
+        self
+    }
+
+    /// counter-clockwise rotation from +i towards +j, in degrees
+    pub fn rotate_t(mut self, degrees: f64) -> Self {
+        self.steps.push(PositionTransformStep::RotateT { degrees });
+        self
+    }
+
+    pub fn swap_axes(mut self, a: ProjectionAxis, b: ProjectionAxis) -> Self {
+        self.steps.push(PositionTransformStep::SwapAxes { a, b });
+        self
+    }
+
+    pub fn apply(&self, position: &VisualizePosition) -> VisualizePosition {
+        let mut position = position.clone();
+        for step in self.steps.iter() {
+            match *step {
+                PositionTransformStep::Translate { i, j, t } => {
+                    position.i += i;
+                    position.j += j;
+                    position.t += t;
+                }
+                PositionTransformStep::Scale { i, j, t } => {
+                    position.i *= i;
+                    position.j *= j;
+                    position.t *= t;
+                }
+                PositionTransformStep::RotateT { degrees } => {
+                    // use exact values for right angles so that grid positions stay on the grid
+                    let (sin, cos) = if degrees % 90. == 0. {
+                        [(0., 1.), (1., 0.), (0., -1.), (-1., 0.)][(degrees / 90.).rem_euclid(4.) as usize]
+                    } else {
+                        degrees.to_radians().sin_cos()
+                    };
+                    let (i, j) = (position.i, position.j);
+                    position.i = i * cos - j * sin;
+                    position.j = i * sin + j * cos;
+                }
+                PositionTransformStep::SwapAxes { a, b } => {
+                    let value_a = *position.coordinate_mut(a);
+                    let value_b = *position.coordinate_mut(b);
+                    *position.coordinate_mut(a) = value_b;
+                    *position.coordinate_mut(b) = value_a;
+                }
+            }
+        }
+        position
+    }
+
+    pub fn apply_all(&self, positions: &[VisualizePosition]) -> Vec<VisualizePosition> {
+        positions.iter().map(|position| self.apply(position)).collect()
+    }
+}
+
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
 impl VisualizePosition {
@@ -64,14 +212,167 @@ impl VisualizePosition {
     }
 }
 
+/// the kind of event that a snapshot records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapshotCategory {
+    /// dual nodes grow by a non-zero length
+    Growth,
+    /// conflicts are resolved by the primal module
+    Conflict,
+    /// a blossom needs to expand
+    Blossom,
+    /// a unit finishes solving, so that its parent can fuse it
+    Fusion,
+    /// the solved state, the perfect matching or the subgraph; never decimated
+    Final,
+    /// not tagged by the caller
+    Other,
+}
+
+impl SnapshotCategory {
+    /// the category of a snapshot taken when resolving conflicts
+    pub fn of_conflict(max_update_length: &MaxUpdateLength) -> Self {
+        match max_update_length {
+            MaxUpdateLength::BlossomNeedExpand(_) => Self::Blossom,
+            _ => Self::Conflict,
+        }
+    }
+}
+
+/// the categories of snapshots to keep
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFilter {
+    pub categories: Vec<SnapshotCategory>,
+}
+
+impl SnapshotFilter {
+    pub fn all() -> Self {
+        Self::only(&[
+            SnapshotCategory::Growth,
+            SnapshotCategory::Conflict,
+            SnapshotCategory::Blossom,
+            SnapshotCategory::Fusion,
+            SnapshotCategory::Final,
+            SnapshotCategory::Other,
+        ])
+    }
+
+    pub fn only(categories: &[SnapshotCategory]) -> Self {
+        Self {
+            categories: categories.to_vec(),
+        }
+    }
+
+    pub fn contains(&self, category: SnapshotCategory) -> bool {
+        self.categories.contains(&category)
+    }
+}
+
+impl Default for SnapshotFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// options to reduce the number of snapshots of a long decoding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VisualizerOptions {
+    /// keep only one of every n snapshots that pass the filter; [`SnapshotCategory::Final`] snapshots are always kept
+    #[serde(default = "visualizer_default_configs::every_nth_snapshot")]
+    pub every_nth_snapshot: usize,
+    /// keep at most this many snapshots of each decoding, i.e. between [`SnapshotCategory::Final`] snapshots which are
+    /// always kept; they are evenly spread over the decoding and include its last snapshot, so every candidate snapshot
+    /// is computed and buffered until the next final snapshot or [`Visualizer::close`]
+    #[serde(default = "visualizer_default_configs::max_snapshots")]
+    pub max_snapshots: Option<usize>,
+    /// the categories of snapshots to keep
+    #[serde(default = "visualizer_default_configs::include")]
+    pub include: SnapshotFilter,
+    /// save each kept snapshot as a delta against the previously kept one, see [`snapshot_delta`]
+    #[serde(default = "visualizer_default_configs::delta")]
+    pub delta: bool,
+}
+
+impl Default for VisualizerOptions {
+    fn default() -> Self {
+        serde_json::from_value(json!({})).unwrap()
+    }
+}
+
+pub mod visualizer_default_configs {
+    use super::*;
+    pub fn every_nth_snapshot() -> usize {
+        1
+    }
+    pub fn max_snapshots() -> Option<usize> {
+        None
+    }
+    pub fn include() -> SnapshotFilter {
+        SnapshotFilter::all()
+    }
+    pub fn delta() -> bool {
+        false
+    }
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pyclass)]
 pub struct Visualizer {
+    /// the path of the file, used to export the recorded snapshots
+    filepath: Option<String>,
+    /// whether to write through a gzip encoder
+    compress: bool,
     /// save to file if applicable
     file: Option<File>,
+    /// save to a gzip-compressed file if applicable; a compressed file cannot be rewritten in place, so the ending `]}`
+    /// is only appended when the visualizer is dropped, see [`VisualizeFile::load`] for reading an unfinished file
+    #[cfg(not(feature = "minimal"))]
+    gz_file: Option<GzEncoder<File>>,
+    /// stream the snapshots to a live client instead of saving them, see [`Visualizer::new_streaming`]
+    stream: Option<VisualizeStream>,
     /// if waiting for the first snapshot
     empty_snapshot: bool,
+    /// decimation and filtering of snapshots
+    options: VisualizerOptions,
+    /// the projection applied to the positions, recorded in every snapshot
+    projection: Option<serde_json::Value>,
+    /// the positions in the header, kept to rewrite the header when the run metadata is set
+    positions: Vec<VisualizePosition>,
+    /// the file-level metadata of the run, see [`Visualizer::set_run_metadata`]
+    run_metadata: Option<serde_json::Value>,
+    /// the decoding graph and the partition in the header, see [`Visualizer::set_initializer`]
+    initializer: Option<SolverInitializer>,
+    partition_config: Option<PartitionConfig>,
+    /// the metadata attached to every following snapshot, see [`Visualizer::set_frame_metadata`]
+    frame_metadata: Option<serde_json::Value>,
+    /// the time that `elapsed_us` of each snapshot is measured from, see [`Visualizer::begin`]
+    begin_time: Option<std::time::Instant>,
+    /// the number of snapshots requested so far, including the dropped ones; each kept snapshot records its index in
+    /// `snapshot_index` so that the numbering stays monotonically increasing
+    requested_snapshots: usize,
+    /// the number of requested non-final snapshots that pass the filter, used for decimation
+    candidate_snapshots: usize,
+    /// the number of non-final snapshots of the current decoding that pass the decimation, see
+    /// [`VisualizerOptions::max_snapshots`]
+    limited_snapshots: usize,
+    /// the position of the snapshot being taken among the limited ones, if it's buffered instead of saved
+    limited_position: Option<usize>,
+    /// only the limited snapshots at a multiple of the stride are kept, which doubles whenever the buffer overflows
+    limited_stride: usize,
+    /// the buffered limited snapshots with their positions, the last one being the latest snapshot of the decoding
+    limited_buffer: Vec<(usize, String, serde_json::Value)>,
+    /// the number of snapshots saved so far, i.e. the index of the next one in the file
+    saved_snapshots: usize,
+    /// the index and the full value of the last saved snapshot, which the next delta snapshot is computed against
+    previous_snapshot: Option<(usize, serde_json::Value)>,
+    /// whether [`Visualizer::close`] is called, after which no snapshot is accepted
+    closed: bool,
+    /// the directory if the visualizer writes a bundle of files, see [`Visualizer::unit_visualizer`]
+    bundle_dir: Option<String>,
+    /// the visualizers of the leaf units in a bundle, each created at its first snapshot
+    unit_visualizers: BTreeMap<usize, Visualizer>,
     /// names of the snapshots
     #[cfg_attr(feature = "python_binding", pyo3(get))]
     pub snapshots: Vec<String>,
@@ -297,6 +598,7 @@ pub fn snapshot_combine_values(value: &mut serde_json::Value, mut value_2: serde
                 let key_grow_state = if abbrev { "g" } else { "grow_state" };
                 let key_unit_growth = if abbrev { "u" } else { "unit_growth" };
                 let key_parent_blossom = if abbrev { "p" } else { "parent_blossom" };
+                let key_dual_value = if abbrev { "y" } else { "dual_value" };
                 let known_keys = [
                     key_boundary,
                     key_dual_variable,
@@ -305,6 +607,7 @@ pub fn snapshot_combine_values(value: &mut serde_json::Value, mut value_2: serde
                     key_grow_state,
                     key_unit_growth,
                     key_parent_blossom,
+                    key_dual_value,
                 ];
                 for key in known_keys {
                     snapshot_combine_object_known_key(dual_node, dual_node_2, key);
@@ -318,6 +621,84 @@ pub fn snapshot_combine_values(value: &mut serde_json::Value, mut value_2: serde
     snapshot_copy_remaining_fields(value, value_2);
 }
 
+/// the difference of a snapshot against a previous one in the same file at `base_index`: array fields (e.g. vertices and
+/// edges) only keep the changed entries under `"delta": {key: {"len": .., "changed": {index: entry}}}`, other fields are
+/// kept only if changed, and the removed fields are listed in `"removed"`
+pub fn snapshot_delta(base: &serde_json::Value, value: &serde_json::Value, base_index: usize) -> serde_json::Value {
+    let base = base.as_object().expect("snapshot must be an object");
+    let value = value.as_object().expect("snapshot must be an object");
+    let mut delta = serde_json::Map::new();
+    let mut array_deltas = serde_json::Map::new();
+    for (key, entry) in value.iter() {
+        match (base.get(key), entry) {
+            (Some(base_entry), _) if base_entry == entry => {}
+            (Some(serde_json::Value::Array(base_array)), serde_json::Value::Array(array)) => {
+                let mut changed = serde_json::Map::new();
+                for (index, element) in array.iter().enumerate() {
+                    if base_array.get(index) != Some(element) {
+                        changed.insert(index.to_string(), element.clone());
+                    }
+                }
+                array_deltas.insert(key.clone(), json!({ "len": array.len(), "changed": changed }));
+            }
+            _ => {
+                delta.insert(key.clone(), entry.clone());
+            }
+        }
+    }
+    let removed: Vec<&String> = base.keys().filter(|key| !value.contains_key(*key)).collect();
+    delta.insert("base".to_string(), json!(base_index));
+    delta.insert("delta".to_string(), serde_json::Value::Object(array_deltas));
+    delta.insert("removed".to_string(), json!(removed));
+    serde_json::Value::Object(delta)
+}
+
+/// reconstruct the full snapshot from a delta generated by [`snapshot_delta`]
+pub fn snapshot_apply_delta(
+    base: &serde_json::Value,
+    delta: &serde_json::Value,
+) -> Result<serde_json::Value, VisualizeError> {
+    let invalid = |message: &str| VisualizeError::Format(format!("invalid delta snapshot: {message}"));
+    let mut value = base.as_object().ok_or_else(|| invalid("base must be an object"))?.clone();
+    let delta = delta.as_object().ok_or_else(|| invalid("snapshot must be an object"))?;
+    for (key, entry) in delta.iter() {
+        match key.as_str() {
+            "base" => {}
+            "delta" => {
+                for (key, array_delta) in entry.as_object().ok_or_else(|| invalid("delta must be an object"))?.iter() {
+                    let array = value
+                        .get_mut(key)
+                        .and_then(|array| array.as_array_mut())
+                        .ok_or_else(|| invalid(&format!("delta of a missing array `{key}`")))?;
+                    let length = array_delta.get("len").and_then(|length| length.as_u64());
+                    let changed = array_delta.get("changed").and_then(|changed| changed.as_object());
+                    let (Some(length), Some(changed)) = (length, changed) else {
+                        return Err(invalid(&format!("delta of array `{key}` must have `len` and `changed`")));
+                    };
+                    array.resize(length as usize, serde_json::Value::Null);
+                    for (index, element) in changed.iter() {
+                        let entry = index
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| array.get_mut(index))
+                            .ok_or_else(|| invalid(&format!("index `{index}` out of array `{key}` of length {length}")))?;
+                        *entry = element.clone();
+                    }
+                }
+            }
+            "removed" => {
+                for key in entry.as_array().ok_or_else(|| invalid("removed must be an array"))?.iter() {
+                    value.remove(key.as_str().ok_or_else(|| invalid("removed keys must be strings"))?);
+                }
+            }
+            _ => {
+                value.insert(key.clone(), entry.clone());
+            }
+        }
+    }
+    Ok(serde_json::Value::Object(value))
+}
+
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn center_positions(mut positions: Vec<VisualizePosition>) -> Vec<VisualizePosition> {
     if !positions.is_empty() {
@@ -359,153 +740,1369 @@ pub fn center_positions(mut positions: Vec<VisualizePosition>) -> Vec<VisualizeP
 
 #[cfg_attr(feature = "python_binding", cfg_eval)]
 #[cfg_attr(feature = "python_binding", pymethods)]
+impl Visualizer {
+    /// create a new visualizer with target filename and node layout, optionally decimating the snapshots as in
+    /// [`VisualizerOptions`] where `include` is a list of [`SnapshotCategory`] names
+    #[cfg(feature = "python_binding")]
+    #[new]
+    #[pyo3(signature = (filepath, positions=vec![], center=true, every_nth_snapshot=1, max_snapshots=None, include=None, delta=false))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        filepath: Option<String>,
+        positions: Vec<VisualizePosition>,
+        center: bool,
+        every_nth_snapshot: usize,
+        max_snapshots: Option<usize>,
+        include: Option<Vec<String>>,
+        delta: bool,
+    ) -> PyResult<Self> {
+        if every_nth_snapshot == 0 {
+            return Err(PyValueError::new_err("`every_nth_snapshot` must be positive"));
+        }
+        let include = match include {
+            Some(categories) => serde_json::from_value(json!({ "categories": categories }))
+                .map_err(|error| PyValueError::new_err(format!("invalid snapshot category in `include`: {error}")))?,
+            None => SnapshotFilter::all(),
+        };
+        let options = VisualizerOptions {
+            every_nth_snapshot,
+            max_snapshots,
+            include,
+            delta,
+        };
+        Ok(Self::new_with_options(filepath, positions, center, options)?)
+    }
+
+    #[cfg(feature = "python_binding")]
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[cfg(feature = "python_binding")]
+    fn __exit__(&mut self, _exc_type: &PyAny, _exc_value: &PyAny, _traceback: &PyAny) -> std::io::Result<bool> {
+        self.close()?;
+        Ok(false) // do not suppress the exception
+    }
+
+    /// start the clock of the `elapsed_us` field recorded in every snapshot, e.g. right before solving; otherwise the
+    /// clock starts at the first snapshot. the field is not recorded in WebAssembly which has no wall clock
+    pub fn begin(&mut self) {
+        self.begin_time = Some(std::time::Instant::now());
+        for unit_visualizer in self.unit_visualizers.values_mut() {
+            unit_visualizer.begin_time = self.begin_time;
+        }
+    }
+
+    /// finish the file and disconnect the live client, after which no snapshot is accepted; the recorded file can
+    /// still be exported
+    pub fn close(&mut self) -> std::io::Result<()> {
+        // the file is finished even if the buffered snapshots cannot be saved
+        let flushed = if self.closed { Ok(()) } else { self.flush_limited_snapshots() };
+        self.closed = true;
+        self.file = None;
+        #[cfg(not(feature = "minimal"))]
+        if let Some(mut encoder) = self.gz_file.take() {
+            encoder.write_all(b"]}")?;
+            encoder.try_finish()?;
+        }
+        if let Some(mut stream) = self.stream.take() {
+            stream.close();
+        }
+        for unit_visualizer in self.unit_visualizers.values_mut() {
+            unit_visualizer.close()?;
+        }
+        flushed
+    }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "standalone_html")]
+    fn standalone_html_py(&self, output_path: &str) -> std::io::Result<()> {
+        self.export_standalone_html(output_path)
+    }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "snapshot_combined")]
+    pub fn snapshot_combined_py(&mut self, name: String, object_pys: Vec<&PyAny>) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let mut values = Vec::<serde_json::Value>::with_capacity(object_pys.len());
+        for object_py in object_pys.into_iter() {
+            values.push(pyobject_to_json(object_py.call_method0("snapshot")?.extract::<PyObject>()?));
+        }
+        self.snapshot_combined_value(name, values)
+    }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "snapshot", signature = (name, object_py, metadata=None))]
+    pub fn snapshot_py(&mut self, name: String, object_py: &PyAny, metadata: Option<PyObject>) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let Some(snapshot_index) = self.accept_snapshot(SnapshotCategory::Other) else {
+            return Ok(());
+        };
+        let value = pyobject_to_json(object_py.call_method0("snapshot")?.extract::<PyObject>()?);
+        self.save_snapshot(name, value, snapshot_index, metadata.map(pyobject_to_json))
+    }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "set_run_metadata")]
+    fn set_run_metadata_py(&mut self, run_metadata: PyObject) -> std::io::Result<()> {
+        self.set_run_metadata(pyobject_to_json(run_metadata))
+    }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "snapshot_combined_value")]
+    pub fn snapshot_combined_value_py(&mut self, name: String, value_pys: Vec<PyObject>) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let values: Vec<_> = value_pys.into_iter().map(|value_py| pyobject_to_json(value_py)).collect();
+        self.snapshot_combined_value(name, values)
+    }
+
+    #[cfg(feature = "python_binding")]
+    #[pyo3(name = "snapshot_value")]
+    pub fn snapshot_value_py(&mut self, name: String, value_py: PyObject) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let value = pyobject_to_json(value_py);
+        self.snapshot_value(name, value)
+    }
+}
+
 impl Visualizer {
     /// create a new visualizer with target filename and node layout
-    #[cfg_attr(feature = "python_binding", new)]
-    #[cfg_attr(feature = "python_binding", pyo3(signature = (filepath, positions=vec![], center=true)))]
-    pub fn new(mut filepath: Option<String>, mut positions: Vec<VisualizePosition>, center: bool) -> std::io::Result<Self> {
+    pub fn new(filepath: Option<String>, positions: Vec<VisualizePosition>, center: bool) -> std::io::Result<Self> {
+        let compress = filepath
+            .as_ref()
+            .map(|filepath| filepath.ends_with(".json.gz"))
+            .unwrap_or(false);
+        Self::new_with_compression(filepath, positions, center, compress)
+    }
+
+    /// create a new visualizer, optionally writing through a gzip encoder; if `filepath` is a directory or ends with `/`,
+    /// the visualizer writes a bundle of files into it, see [`Visualizer::unit_visualizer`]
+    pub fn new_with_compression(
+        mut filepath: Option<String>,
+        mut positions: Vec<VisualizePosition>,
+        center: bool,
+        compress: bool,
+    ) -> std::io::Result<Self> {
         if cfg!(feature = "disable_visualizer") {
             filepath = None; // do not open file
         }
+        if cfg!(target_arch = "wasm32") && filepath.is_some() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "visualization files are not available in WebAssembly, use a visualizer without file instead",
+            ));
+        }
         if center {
             positions = center_positions(positions);
         }
-        let mut file = match filepath {
-            Some(filepath) => Some(File::create(filepath)?),
-            None => None,
+        let bundle_dir = filepath
+            .as_ref()
+            .filter(|filepath| filepath.ends_with('/') || std::path::Path::new(filepath).is_dir())
+            .cloned();
+        if let Some(bundle_dir) = bundle_dir.as_ref() {
+            std::fs::create_dir_all(bundle_dir)?;
+            filepath = Some(bundle_file_path(bundle_dir, "fusion", compress));
+        }
+        let mut visualizer = Self {
+            filepath,
+            compress,
+            file: None,
+            #[cfg(not(feature = "minimal"))]
+            gz_file: None,
+            stream: None,
+            empty_snapshot: true,
+            options: VisualizerOptions::default(),
+            projection: None,
+            positions: vec![],
+            run_metadata: None,
+            initializer: None,
+            partition_config: None,
+            frame_metadata: None,
+            begin_time: None,
+            requested_snapshots: 0,
+            candidate_snapshots: 0,
+            limited_snapshots: 0,
+            limited_position: None,
+            limited_stride: 1,
+            limited_buffer: vec![],
+            saved_snapshots: 0,
+            previous_snapshot: None,
+            closed: false,
+            bundle_dir,
+            unit_visualizers: BTreeMap::new(),
+            snapshots: vec![],
+        };
+        visualizer.create_file(&positions)?;
+        Ok(visualizer)
+    }
+
+    /// whether the visualizer writes a bundle of files into a directory, see [`Visualizer::unit_visualizer`]
+    pub fn is_bundle(&self) -> bool {
+        self.bundle_dir.is_some()
+    }
+
+    /// the visualizer of a leaf unit of a parallel solver: a bundle writes the local snapshots of each leaf unit into
+    /// `unit_{unit_index}.json` next to `fusion.json`, which keeps the snapshots of the fusions and the final state, all
+    /// with the same header; otherwise it's this visualizer itself. the vertices and edges of a unit snapshot keep their
+    /// global indices, see [`VisualizeFile::load_bundle`]. note that the parallel solver only snapshots the units with
+    /// `debug_sequential` enabled
+    pub fn unit_visualizer(&mut self, unit_index: usize) -> std::io::Result<&mut Visualizer> {
+        let Some(bundle_dir) = self.bundle_dir.clone() else {
+            return Ok(self);
         };
-        if let Some(file) = file.as_mut() {
+        if !self.unit_visualizers.contains_key(&unit_index) {
+            let filepath = bundle_file_path(&bundle_dir, &format!("unit_{unit_index}"), self.compress);
+            let mut unit_visualizer = Self::new_with_compression(Some(filepath), vec![], false, self.compress)?;
+            unit_visualizer.options = self.options.clone();
+            unit_visualizer.projection.clone_from(&self.projection);
+            unit_visualizer.run_metadata.clone_from(&self.run_metadata);
+            unit_visualizer.initializer.clone_from(&self.initializer);
+            unit_visualizer.partition_config.clone_from(&self.partition_config);
+            unit_visualizer.frame_metadata.clone_from(&self.frame_metadata);
+            unit_visualizer.begin_time = self.begin_time;
+            unit_visualizer.create_file(&self.positions)?;
+            self.unit_visualizers.insert(unit_index, unit_visualizer);
+        }
+        Ok(self.unit_visualizers.get_mut(&unit_index).unwrap())
+    }
+
+    /// (re)create the file with the given positions and no snapshot
+    fn create_file(&mut self, positions: &[VisualizePosition]) -> std::io::Result<()> {
+        self.file = None;
+        #[cfg(not(feature = "minimal"))]
+        {
+            self.gz_file = None; // the previous content will be overwritten anyway
+        }
+        self.positions = positions.to_vec();
+        let mut header = json!({
+            "format": "fusion_blossom",
+            "version": env!("CARGO_PKG_VERSION"),
+            "positions": positions,
+        });
+        if let Some(run_metadata) = self.run_metadata.as_ref() {
+            header["run_metadata"] = run_metadata.clone();
+        }
+        if let Some(initializer) = self.initializer.as_ref() {
+            header["initializer"] = json!(initializer);
+        }
+        if let Some(partition_config) = self.partition_config.as_ref() {
+            header["partition_config"] = json!(partition_config);
+        }
+        if let Some(stream) = self.stream.as_ref() {
+            stream.send(header.clone());
+        }
+        let Some(filepath) = self.filepath.as_ref() else {
+            return Ok(());
+        };
+        // the snapshots are appended after the header, so the closing brace is removed
+        let mut header = header.to_string();
+        header.pop();
+        if self.compress {
+            // unreachable with the feature `minimal`, which disables the visualizer files
+            #[cfg(not(feature = "minimal"))]
+            {
+                let mut encoder = GzEncoder::new(File::create(filepath)?, Compression::default());
+                encoder.write_all(header.as_bytes())?;
+                encoder.write_all(b",\"snapshots\":[")?;
+                encoder.flush()?;
+                self.gz_file = Some(encoder);
+            }
+        } else {
+            let mut file = File::create(filepath)?;
             file.set_len(0)?; // truncate the file
             file.seek(SeekFrom::Start(0))?; // move the cursor to the front
-            file.write_all(
-                format!(
-                    "{{\"format\":\"fusion_blossom\",\"version\":\"{}\"",
-                    env!("CARGO_PKG_VERSION")
-                )
-                .as_bytes(),
-            )?;
-            file.write_all(b",\"positions\":")?;
-            file.write_all(json!(positions).to_string().as_bytes())?;
+            file.write_all(header.as_bytes())?;
             file.write_all(b",\"snapshots\":[]}")?;
             file.sync_all()?;
+            self.file = Some(file);
+        }
+        Ok(())
+    }
+
+    /// replace the positions with a 2D projection, where virtual vertices are shifted by `virtual_offset` along the dropped
+    /// axis to be distinguishable from the real vertices; this must be called before taking any snapshot
+    #[allow(clippy::unnecessary_cast)]
+    pub fn set_positions_projected(
+        &mut self,
+        positions: &[VisualizePosition],
+        axis: ProjectionAxis,
+        virtual_vertices: &[VertexIndex],
+        virtual_offset: f64,
+    ) -> std::io::Result<()> {
+        assert!(
+            self.snapshots.is_empty() && self.unit_visualizers.is_empty(),
+            "positions cannot change after taking snapshots"
+        );
+        let mut projected = VisualizePosition::project_2d(positions, axis);
+        for &virtual_vertex in virtual_vertices.iter() {
+            *projected[virtual_vertex as usize].coordinate_mut(axis) += virtual_offset;
+        }
+        self.projection = Some(json!({
+            "axis": axis,
+            "virtual_offset": virtual_offset,
+        }));
+        self.create_file(&center_positions(projected))
+    }
+
+    /// replace the positions with transformed ones, optionally centering the result afterwards;
+    /// this must be called before taking any snapshot
+    pub fn set_positions_transformed(
+        &mut self,
+        positions: &[VisualizePosition],
+        transform: &PositionTransform,
+        auto_center: bool,
+    ) -> std::io::Result<()> {
+        assert!(
+            self.snapshots.is_empty() && self.unit_visualizers.is_empty(),
+            "positions cannot change after taking snapshots"
+        );
+        let positions = transform.apply_all(positions);
+        self.create_file(&if auto_center { center_positions(positions) } else { positions })
+    }
+
+    /// record the parameters of the run in the file header, e.g. [`crate::example_codes::ExampleCode::run_metadata`]
+    /// together with the seed; an object value is completed with the crate `version` and a `timestamp` unless given.
+    /// this must be called before taking any snapshot
+    pub fn set_run_metadata(&mut self, mut run_metadata: serde_json::Value) -> std::io::Result<()> {
+        assert!(
+            self.snapshots.is_empty() && self.unit_visualizers.is_empty(),
+            "run metadata cannot change after taking snapshots"
+        );
+        if let Some(object) = run_metadata.as_object_mut() {
+            object.entry("version").or_insert_with(|| json!(env!("CARGO_PKG_VERSION")));
+            #[cfg(not(feature = "minimal"))]
+            object.entry("timestamp").or_insert_with(|| json!(Local::now().to_rfc3339()));
+        }
+        self.run_metadata = Some(run_metadata);
+        let positions = std::mem::take(&mut self.positions);
+        self.create_file(&positions)
+    }
+
+    /// embed the decoding graph in the file header, together with the partition of a parallel solver, so that the
+    /// recorded syndrome can be decoded again, see [`VisualizeFile::initializer`]; this must be called before taking
+    /// any snapshot
+    pub fn set_initializer(
+        &mut self,
+        initializer: &SolverInitializer,
+        partition_config: Option<&PartitionConfig>,
+    ) -> std::io::Result<()> {
+        assert!(
+            self.snapshots.is_empty() && self.unit_visualizers.is_empty(),
+            "initializer cannot change after taking snapshots"
+        );
+        self.initializer = Some(initializer.clone());
+        self.partition_config = partition_config.cloned();
+        let positions = std::mem::take(&mut self.positions);
+        self.create_file(&positions)
+    }
+
+    /// attach the metadata to every following snapshot until it's replaced; the solvers set the number of defects here
+    /// when solving with a visualizer. the metadata given to [`Visualizer::snapshot`] takes precedence over it
+    pub fn set_frame_metadata(&mut self, frame_metadata: Option<serde_json::Value>) {
+        for unit_visualizer in self.unit_visualizers.values_mut() {
+            unit_visualizer.set_frame_metadata(frame_metadata.clone());
         }
+        self.frame_metadata = frame_metadata;
+    }
+
+    /// create a new visualizer that decimates and filters the snapshots
+    pub fn new_with_options(
+        filepath: Option<String>,
+        positions: Vec<VisualizePosition>,
+        center: bool,
+        options: VisualizerOptions,
+    ) -> std::io::Result<Self> {
+        assert!(options.every_nth_snapshot > 0, "every_nth_snapshot must be positive");
+        let mut visualizer = Self::new(filepath, positions, center)?;
+        visualizer.options = options;
+        Ok(visualizer)
+    }
+
+    /// decide whether to keep a snapshot before computing it, returning its index if kept
+    pub fn accept_snapshot(&mut self, category: SnapshotCategory) -> Option<usize> {
+        let snapshot_index = self.requested_snapshots;
+        self.requested_snapshots += 1;
+        if !self.options.include.contains(category) {
+            return None;
+        }
+        if category != SnapshotCategory::Final {
+            let candidate_index = self.candidate_snapshots;
+            self.candidate_snapshots += 1;
+            if !candidate_index.is_multiple_of(self.options.every_nth_snapshot) {
+                return None;
+            }
+            if let Some(max_snapshots) = self.options.max_snapshots {
+                if max_snapshots == 0 {
+                    return None;
+                }
+                self.limited_position = Some(self.limited_snapshots);
+                self.limited_snapshots += 1;
+            }
+        }
+        Some(snapshot_index)
+    }
+
+    /// buffer a limited snapshot, dropping the ones off the stride to keep at most `max_snapshots` of them
+    fn buffer_limited_snapshot(&mut self, position: usize, name: String, value: serde_json::Value) {
+        let max_snapshots = self.options.max_snapshots.expect("only limited snapshots are buffered");
+        let capacity = max_snapshots - 1; // the latest snapshot is always kept
+        if let Some(&(latest_position, ..)) = self.limited_buffer.last() {
+            if capacity == 0 || !latest_position.is_multiple_of(self.limited_stride) {
+                self.limited_buffer.pop(); // no longer the latest one
+            }
+        }
+        self.limited_buffer.push((position, name, value));
+        while self.limited_buffer.len() - 1 > capacity {
+            self.limited_stride *= 2;
+            let latest = self.limited_buffer.pop().unwrap();
+            let limited_stride = self.limited_stride;
+            self.limited_buffer
+                .retain(|(position, ..)| position.is_multiple_of(limited_stride));
+            self.limited_buffer.push(latest);
+        }
+    }
+
+    /// save the buffered limited snapshots, after which the next decoding is limited on its own
+    fn flush_limited_snapshots(&mut self) -> std::io::Result<()> {
+        self.limited_snapshots = 0;
+        self.limited_stride = 1;
+        for (_, name, value) in std::mem::take(&mut self.limited_buffer) {
+            self.write_snapshot(name, value)?;
+        }
+        Ok(())
+    }
+
+    /// export the recorded snapshots of the selected vertices and the edges among them into a new visualization file,
+    /// where the vertices and edges are re-indexed; the filter receives the positions in the recorded file
+    #[cfg(not(feature = "minimal"))]
+    pub fn export_filtered(
+        &self,
+        output_path: &str,
+        vertex_filter: impl Fn(VertexIndex, &VisualizePosition) -> bool,
+    ) -> std::io::Result<()> {
+        let filepath = self.filepath.as_ref().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "the visualizer doesn't record to a file")
+        })?;
+        VisualizeFile::load(filepath)?.filtered(vertex_filter).save(output_path)
+    }
+
+    /// export the recorded snapshots into a single html file that can be opened offline; the data is gzip-compressed
+    /// and base64-encoded, and decompressed by the browser with `DecompressionStream`, and the libraries vendored in
+    /// [`visualize_vendor_folder`] by `visualize/vendor.sh` are inlined, so that only the fonts are loaded online
+    #[cfg(not(feature = "minimal"))]
+    pub fn export_standalone_html(&self, output_path: &str) -> std::io::Result<()> {
+        self.export_standalone_html_with_vendor(output_path, &visualize_vendor_folder())
+    }
+
+    /// the same as [`Visualizer::export_standalone_html`] but with the libraries vendored in `vendor_dir`
+    #[cfg(not(feature = "minimal"))]
+    pub fn export_standalone_html_with_vendor(&self, output_path: &str, vendor_dir: &str) -> std::io::Result<()> {
+        let filepath = self.filepath.as_ref().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "the visualizer doesn't record to a file")
+        })?;
+        let visualize_file = VisualizeFile::load(filepath)?;
+        let mut encoder = GzEncoder::new(vec![], Compression::best());
+        encoder.write_all(json!(visualize_file).to_string().as_bytes())?;
+        let payload = crate::base64::engine::general_purpose::STANDARD.encode(encoder.finish()?);
+        let html = standalone_html(&payload, vendor_dir)?;
+        File::create(output_path)?.write_all(html.as_bytes())?;
+        Ok(())
+    }
+
+    /// fix the missing fields and save the snapshot together with its index and metadata
+    fn save_snapshot(
+        &mut self,
+        name: String,
+        mut value: serde_json::Value,
+        snapshot_index: usize,
+        metadata: Option<serde_json::Value>,
+    ) -> std::io::Result<()> {
+        let abbrev = true;
+        snapshot_fix_missing_fields(&mut value, abbrev);
+        let object = value.as_object_mut().expect("snapshot must be an object");
+        object.insert("snapshot_index".to_string(), json!(snapshot_index));
+        let metadata = match (self.frame_metadata.clone(), metadata) {
+            (Some(serde_json::Value::Object(mut frame_metadata)), Some(serde_json::Value::Object(metadata))) => {
+                frame_metadata.extend(metadata);
+                Some(serde_json::Value::Object(frame_metadata))
+            }
+            (frame_metadata, metadata) => metadata.or(frame_metadata),
+        };
+        if let Some(metadata) = metadata {
+            object.insert("metadata".to_string(), metadata);
+        }
+        if !cfg!(target_arch = "wasm32") {
+            let begin_time = self.begin_time.get_or_insert_with(std::time::Instant::now);
+            object.insert("elapsed_us".to_string(), json!(begin_time.elapsed().as_micros() as u64));
+        }
+        if let Some(projection) = self.projection.as_ref() {
+            object.insert("projection".to_string(), projection.clone());
+        }
+        if let Some(position) = self.limited_position.take() {
+            self.buffer_limited_snapshot(position, name, value);
+            return Ok(());
+        }
+        self.flush_limited_snapshots()?;
+        self.write_snapshot(name, value)
+    }
+
+    /// save the snapshot, as a delta against the previous one if configured
+    fn write_snapshot(&mut self, name: String, value: serde_json::Value) -> std::io::Result<()> {
+        if !self.options.delta {
+            return self.incremental_save(name, value);
+        }
+        let saved_value = match self.previous_snapshot.as_ref() {
+            Some((base_index, previous)) => snapshot_delta(previous, &value, *base_index),
+            None => value.clone(),
+        };
+        let saved_index = self.saved_snapshots;
+        self.incremental_save(name, saved_value)?;
+        self.previous_snapshot = Some((saved_index, value));
+        Ok(())
+    }
+
+    pub fn incremental_save(&mut self, name: String, value: serde_json::Value) -> std::io::Result<()> {
+        if self.closed {
+            return Err(std::io::Error::other("cannot take snapshots after the visualizer is closed"));
+        }
+        self.saved_snapshots += 1;
+        #[cfg(not(feature = "minimal"))]
+        if let Some(encoder) = self.gz_file.as_mut() {
+            self.snapshots.push(name.clone());
+            // each snapshot starts from a new line so that an unfinished file can be truncated to the last complete one
+            encoder.write_all(if self.empty_snapshot { b"\n" } else { b",\n" })?;
+            self.empty_snapshot = false;
+            encoder.write_all(json!((name, value)).to_string().as_bytes())?;
+            encoder.flush()?; // sync flush, so that the snapshot can be decompressed even if the program crashes
+            return Ok(());
+        }
+        if let Some(file) = self.file.as_mut() {
+            self.snapshots.push(name.clone());
+            file.seek(SeekFrom::End(-2))?; // move the cursor before the ending ]}
+            if !self.empty_snapshot {
+                file.write_all(b",")?;
+            }
+            self.empty_snapshot = false;
+            file.write_all(json!((name, value)).to_string().as_bytes())?;
+            file.write_all(b"]}")?;
+            file.sync_all()?;
+        } else if let Some(stream) = self.stream.as_ref() {
+            self.snapshots.push(name.clone());
+            stream.send(json!((name, value)));
+        }
+        Ok(())
+    }
+
+    /// create a visualizer that serves the snapshots live over tcp instead of saving them to a file; see
+    /// [`VisualizeStream`] for the protocol. use port 0 to pick a free port and read it from [`Visualizer::stream_addr`]
+    pub fn new_streaming(addr: &str, positions: Vec<VisualizePosition>, center: bool) -> std::io::Result<Self> {
+        let mut visualizer = Self::new(None, vec![], false)?;
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(visualizer);
+        }
+        if cfg!(target_arch = "wasm32") {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "streaming visualizer is not available in WebAssembly which has no threads",
+            ));
+        }
+        visualizer.stream = Some(VisualizeStream::new(addr)?);
+        visualizer.create_file(&if center { center_positions(positions) } else { positions })?;
+        Ok(visualizer)
+    }
+
+    /// the address that a streaming visualizer listens on
+    pub fn stream_addr(&self) -> Option<std::net::SocketAddr> {
+        self.stream.as_ref().map(|stream| stream.local_addr)
+    }
+
+    /// the number of messages dropped because the client did not keep up
+    pub fn dropped_frames(&self) -> usize {
+        self.stream
+            .as_ref()
+            .map(|stream| stream.dropped_frames.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// append another snapshot of the fusion type, and also update the file in case
+    pub fn snapshot_combined(&mut self, name: String, fusion_algorithms: Vec<&dyn FusionVisualizer>) -> std::io::Result<()> {
+        self.snapshot_combined_category(name, SnapshotCategory::Other, fusion_algorithms)
+    }
+
+    /// append another snapshot of the given category, which might be dropped according to the options
+    pub fn snapshot_combined_category(
+        &mut self,
+        name: String,
+        category: SnapshotCategory,
+        fusion_algorithms: Vec<&dyn FusionVisualizer>,
+    ) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let Some(snapshot_index) = self.accept_snapshot(category) else {
+            return Ok(());
+        };
+        let abbrev = true;
+        let mut value = json!({});
+        for fusion_algorithm in fusion_algorithms.iter() {
+            let value_2 = fusion_algorithm.snapshot(abbrev);
+            snapshot_combine_values(&mut value, value_2, abbrev);
+        }
+        self.save_snapshot(name, value, snapshot_index, None)
+    }
+
+    /// append another snapshot of the fusion type, and also update the file in case; the optional metadata, e.g. the
+    /// seed or the round, is stored in the `metadata` field of the snapshot, see [`VisualizeFile::snapshot_metadata`]
+    pub fn snapshot(
+        &mut self,
+        name: String,
+        fusion_algorithm: &impl FusionVisualizer,
+        metadata: Option<serde_json::Value>,
+    ) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let Some(snapshot_index) = self.accept_snapshot(SnapshotCategory::Other) else {
+            return Ok(());
+        };
+        let abbrev = true;
+        let value = fusion_algorithm.snapshot(abbrev);
+        self.save_snapshot(name, value, snapshot_index, metadata)
+    }
+
+    /// append the final state of a solver, together with the matched pairs and the correction subgraph
+    pub fn snapshot_final(
+        &mut self,
+        name: String,
+        solver: &dyn FusionVisualizer,
+        perfect_matching: &PerfectMatching,
+        subgraph: &[EdgeIndex],
+    ) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let Some(snapshot_index) = self.accept_snapshot(SnapshotCategory::Final) else {
+            return Ok(());
+        };
+        let abbrev = true;
+        let mut value = solver.snapshot(abbrev);
+        let object = value.as_object_mut().expect("snapshot must be an object");
+        object.insert("matchings".to_string(), perfect_matching.snapshot_matchings(abbrev));
+        object.insert("subgraph".to_string(), json!(subgraph));
+        self.save_snapshot(name, value, snapshot_index, None)
+    }
+
+    pub fn snapshot_combined_value(&mut self, name: String, values: Vec<serde_json::Value>) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let Some(snapshot_index) = self.accept_snapshot(SnapshotCategory::Other) else {
+            return Ok(());
+        };
+        let abbrev = true;
+        let mut value = json!({});
+        for value_2 in values.into_iter() {
+            snapshot_combine_values(&mut value, value_2, abbrev);
+        }
+        self.save_snapshot(name, value, snapshot_index, None)
+    }
+
+    pub fn snapshot_value(&mut self, name: String, value: serde_json::Value) -> std::io::Result<()> {
+        if cfg!(feature = "disable_visualizer") {
+            return Ok(());
+        }
+        let Some(snapshot_index) = self.accept_snapshot(SnapshotCategory::Other) else {
+            return Ok(());
+        };
+        self.save_snapshot(name, value, snapshot_index, None)
+    }
+}
+
+impl Drop for Visualizer {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// the number of messages buffered for a slow client before dropping new ones
+pub const VISUALIZE_STREAM_QUEUE_SIZE: usize = 64;
+
+/// serves the messages of a visualizer to the first client that connects; each message is a big-endian `u32` length
+/// followed by that many bytes of json. the first message is `{"format", "version", "positions"}` like the header of
+/// a visualization file, and each of the following messages is a `[name, snapshot]` pair
+#[derive(Debug)]
+pub struct VisualizeStream {
+    local_addr: std::net::SocketAddr,
+    sender: Option<std::sync::mpsc::SyncSender<Vec<u8>>>,
+    dropped_frames: Arc<AtomicUsize>,
+    closed: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl VisualizeStream {
+    fn new(addr: &str) -> std::io::Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let (sender, receiver) = std::sync::mpsc::sync_channel::<Vec<u8>>(VISUALIZE_STREAM_QUEUE_SIZE);
+        let closed = Arc::new(AtomicBool::new(false));
+        let thread_closed = closed.clone();
+        let handle = std::thread::spawn(move || {
+            let mut stream = loop {
+                // read the flag before accepting, so that a client connected before closing is still served
+                let is_closed = thread_closed.load(Ordering::SeqCst);
+                match listener.accept() {
+                    Ok((stream, _)) => break stream,
+                    Err(error) if error.kind() == std::io::ErrorKind::WouldBlock && !is_closed => {
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                    }
+                    Err(_) => return,
+                }
+            };
+            if stream.set_nonblocking(false).is_err()
+                || stream.set_write_timeout(Some(std::time::Duration::from_secs(1))).is_err()
+            {
+                return;
+            }
+            for message in receiver.iter() {
+                let length = (message.len() as u32).to_be_bytes();
+                if stream.write_all(&length).and_then(|_| stream.write_all(&message)).is_err() {
+                    return; // client disconnected
+                }
+            }
+        });
         Ok(Self {
-            file,
-            empty_snapshot: true,
-            snapshots: vec![],
+            local_addr,
+            sender: Some(sender),
+            dropped_frames: Arc::new(AtomicUsize::new(0)),
+            closed,
+            handle: Some(handle),
+        })
+    }
+
+    /// queue a message without blocking the decoder, dropping it if the client cannot keep up
+    fn send(&self, value: serde_json::Value) {
+        let sender = self.sender.as_ref().expect("stream closed");
+        if sender.try_send(value.to_string().into_bytes()).is_err() {
+            self.dropped_frames.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// send the remaining messages and wait for the server thread to finish
+    fn close(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.sender = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+const STANDALONE_TEMPLATE: &str = include_str!("../visualize/index.html");
+#[cfg(not(feature = "minimal"))]
+const STANDALONE_MODULES: [(&str, &str); 4] = [
+    ("gui3d.js", include_str!("../visualize/gui3d.js")),
+    ("primal.js", include_str!("../visualize/primal.js")),
+    ("patches.js", include_str!("../visualize/patches.js")),
+    ("index.js", include_str!("../visualize/index.js")),
+];
+#[cfg(not(feature = "minimal"))]
+const STANDALONE_DECOMPRESSOR: &str = r#"window.fusion_embedded_data = (async () => {
+    const bytes = Uint8Array.from(atob(document.getElementById("fusion-embedded-data").textContent.trim()), c => c.charCodeAt(0))
+    const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream("gzip"))
+    return JSON.parse(await new Response(stream).text())
+})()"#;
+
+/// the external libraries of the front end that a standalone html inlines, i.e. all the urls in the template except
+/// the fonts, the same as downloaded by `visualize/vendor.sh`
+#[cfg(not(feature = "minimal"))]
+fn standalone_vendor_urls() -> Vec<&'static str> {
+    let mut urls: Vec<&str> = STANDALONE_TEMPLATE
+        .match_indices("https://")
+        .map(|(start, _)| {
+            let url = &STANDALONE_TEMPLATE[start..];
+            &url[..url.find(['"', ' ']).unwrap_or(url.len())]
+        })
+        .filter(|url| !url.starts_with("https://fonts.googleapis.com/"))
+        .collect();
+    urls.sort();
+    urls.dedup();
+    urls
+}
+
+/// read a library vendored at `vendor_dir/<host>/<path>` for its url `https://<host>/<path>`
+#[cfg(not(feature = "minimal"))]
+fn read_vendored(vendor_dir: &str, url: &str) -> std::io::Result<String> {
+    let path = std::path::Path::new(vendor_dir).join(url.trim_start_matches("https://"));
+    std::fs::read_to_string(&path).map_err(|error| {
+        std::io::Error::new(
+            error.kind(),
+            format!(
+                "{url} is not vendored at {}, run `visualize/vendor.sh` to download the libraries: {error}",
+                path.display()
+            ),
+        )
+    })
+}
+
+/// build the standalone html from the visualizer front end: local modules are inlined into the import map as data urls
+/// because relative imports cannot be resolved from a data url, and so are the vendored libraries, for every script,
+/// stylesheet and import map entry that refers to them
+#[cfg(not(feature = "minimal"))]
+fn standalone_html(payload: &str, vendor_dir: &str) -> std::io::Result<String> {
+    let engine = &crate::base64::engine::general_purpose::STANDARD;
+    let mut library_urls = BTreeMap::new();
+    for url in standalone_vendor_urls() {
+        let mime = if url.ends_with(".css") {
+            "text/css"
+        } else {
+            "text/javascript"
+        };
+        let source = read_vendored(vendor_dir, url)?;
+        library_urls.insert(url, format!("data:{mime};base64,{}", engine.encode(source)));
+    }
+    let mut html = STANDALONE_TEMPLATE.to_string();
+    // relative entries of the import map, e.g. `"./node_modules/...": "https://..."`, are replaced by the libraries directly
+    let relative_imports: Vec<(&str, &str)> = STANDALONE_TEMPLATE
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.trim().trim_end_matches(',').split_once("\": \"")?;
+            let key = key.strip_prefix('"')?;
+            key.starts_with("./").then(|| (key, value.trim_end_matches('"')))
         })
+        .collect();
+    let mut imports = String::new();
+    for (name, source) in STANDALONE_MODULES.iter() {
+        let mut source = source.to_string();
+        for (dependency, _) in STANDALONE_MODULES.iter() {
+            source = source.replace(&format!("'./{dependency}'"), &format!("'fusion-blossom/{dependency}'"));
+        }
+        for (key, url) in relative_imports.iter() {
+            let library_url = library_urls.get(url).map(String::as_str).unwrap_or(url);
+            source = source.replace(&format!("'{key}'"), &format!("'{library_url}'"));
+        }
+        imports.push_str(&format!(
+            "\n        \"fusion-blossom/{name}\": \"data:text/javascript;base64,{}\",",
+            engine.encode(source)
+        ));
+    }
+    for (url, library_url) in library_urls.iter() {
+        html = html.replace(&format!("\"{url}\""), &format!("\"{library_url}\""));
+    }
+    let import_map_start = "\"imports\": {";
+    assert!(html.contains(import_map_start), "import map not found in the template");
+    html = html.replacen(import_map_start, &format!("{import_map_start}{imports}"), 1);
+    let cmd_script = "<script src=\"./cmd.js\"></script>";
+    assert!(html.contains(cmd_script), "cmd.js not found in the template");
+    html = html.replacen(
+        cmd_script,
+        &format!("<script>\n{}\n</script>", include_str!("../visualize/cmd.js")),
+        1,
+    );
+    let index_script = "<script type=\"module\" src=\"./index.js\"></script>";
+    assert!(html.contains(index_script), "index.js not found in the template");
+    Ok(html.replacen(
+        index_script,
+        &format!(
+            "<script type=\"text/plain\" id=\"fusion-embedded-data\">\n{payload}\n</script>\n<script>\n{STANDALONE_DECOMPRESSOR}\n</script>\n<script type=\"module\">import 'fusion-blossom/index.js'</script>"
+        ),
+        1,
+    ))
+}
+
+/// errors when reading a visualization file
+#[derive(Debug)]
+pub enum VisualizeError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// the file is valid json but not a valid visualization
+    Format(String),
+}
+
+impl std::fmt::Display for VisualizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "cannot read visualization file: {}", error),
+            Self::Json(error) => write!(f, "invalid visualization json: {}", error),
+            Self::Format(message) => write!(f, "invalid visualization: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for VisualizeError {}
+
+impl From<std::io::Error> for VisualizeError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for VisualizeError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+impl From<VisualizeError> for std::io::Error {
+    fn from(error: VisualizeError) -> Self {
+        match error {
+            VisualizeError::Io(error) => error,
+            error => std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// the standard fields of a vertex in a snapshot, see [`VisualizeFile::snapshot_vertices`]
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotVertex {
+    pub is_virtual: bool,
+    pub is_defect: bool,
+    pub propagated_dual_node: Option<NodeIndex>,
+    pub propagated_grandson_dual_node: Option<NodeIndex>,
+    pub mirror_unit_index: Option<usize>,
+}
+
+/// the standard fields of an edge in a snapshot, see [`VisualizeFile::snapshot_edges`]
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEdge {
+    pub weight: Weight,
+    pub left: VertexIndex,
+    pub right: VertexIndex,
+    pub left_growth: Weight,
+    pub right_growth: Weight,
+    pub left_dual_node: Option<NodeIndex>,
+    pub right_dual_node: Option<NodeIndex>,
+    pub original_weight: Option<Weight>,
+    pub is_erasure: bool,
+}
+
+/// look up a field by either its abbreviated or its verbose key
+#[cfg(not(feature = "minimal"))]
+fn snapshot_field<'a>(
+    object: &'a serde_json::Map<String, serde_json::Value>,
+    abbrev: &str,
+    verbose: &str,
+) -> Option<&'a serde_json::Value> {
+    object.get(abbrev).or_else(|| object.get(verbose))
+}
+
+#[cfg(not(feature = "minimal"))]
+fn snapshot_field_bool(object: &serde_json::Map<String, serde_json::Value>, abbrev: &str, verbose: &str) -> bool {
+    match snapshot_field(object, abbrev, verbose) {
+        Some(serde_json::Value::Bool(value)) => *value,
+        Some(value) => value.as_i64().map(|value| value != 0).unwrap_or(false),
+        None => false,
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+fn snapshot_field_integer<T: TryFrom<i64>>(
+    object: &serde_json::Map<String, serde_json::Value>,
+    abbrev: &str,
+    verbose: &str,
+) -> Result<Option<T>, VisualizeError> {
+    match snapshot_field(object, abbrev, verbose) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => value
+            .as_i64()
+            .and_then(|value| T::try_from(value).ok())
+            .map(Some)
+            .ok_or_else(|| VisualizeError::Format(format!("field `{}` has invalid value {}", verbose, value))),
+    }
+}
+
+#[cfg(not(feature = "minimal"))]
+fn snapshot_field_required<T: TryFrom<i64>>(
+    object: &serde_json::Map<String, serde_json::Value>,
+    abbrev: &str,
+    verbose: &str,
+) -> Result<T, VisualizeError> {
+    snapshot_field_integer(object, abbrev, verbose)?
+        .ok_or_else(|| VisualizeError::Format(format!("missing field `{}`", verbose)))
+}
+
+/// the files of a bundle visualizer, see [`Visualizer::unit_visualizer`]
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisualizeBundle {
+    /// the snapshots of the fusions and the final state
+    pub fusion: VisualizeFile,
+    /// the local snapshots of each leaf unit that has any
+    pub units: BTreeMap<usize, VisualizeFile>,
+}
+
+/// the path of a file in the directory of a bundle visualizer
+fn bundle_file_path(bundle_dir: &str, name: &str, compress: bool) -> String {
+    let filename = format!("{name}.json{}", if compress { ".gz" } else { "" });
+    std::path::Path::new(bundle_dir).join(filename).to_string_lossy().into_owned()
+}
+
+/// the content of a visualization file
+#[cfg(not(feature = "minimal"))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisualizeFile {
+    pub format: String,
+    pub version: String,
+    pub positions: Vec<VisualizePosition>,
+    /// see [`Visualizer::set_run_metadata`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_metadata: Option<serde_json::Value>,
+    /// see [`Visualizer::set_initializer`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initializer: Option<SolverInitializer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partition_config: Option<PartitionConfig>,
+    pub snapshots: Vec<(String, serde_json::Value)>,
+}
+
+#[cfg(not(feature = "minimal"))]
+impl VisualizeFile {
+    /// save the file, compressed if the filename ends with `.json.gz`
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let content = json!(self).to_string();
+        if path.ends_with(".json.gz") {
+            let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+            encoder.write_all(content.as_bytes())?;
+            encoder.finish()?;
+        } else {
+            File::create(path)?.write_all(content.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// keep only the selected vertices and the edges among them, re-indexing the vertices and edges in every snapshot
+    #[allow(clippy::unnecessary_cast)]
+    pub fn filtered(&self, vertex_filter: impl Fn(VertexIndex, &VisualizePosition) -> bool) -> Self {
+        let mut vertex_map: Vec<Option<VertexIndex>> = vec![None; self.positions.len()];
+        let mut positions = vec![];
+        for (vertex_index, position) in self.positions.iter().enumerate() {
+            if vertex_filter(vertex_index as VertexIndex, position) {
+                vertex_map[vertex_index] = Some(positions.len() as VertexIndex);
+                positions.push(position.clone());
+            }
+        }
+        let map_vertex = |value: &serde_json::Value| -> Option<serde_json::Value> {
+            let vertex_index = value.as_u64()? as usize;
+            vertex_map
+                .get(vertex_index)
+                .cloned()
+                .flatten()
+                .map(|new_index| json!(new_index))
+        };
+        // edges are kept only if both endpoints are kept; the endpoints are found in any snapshot that has the edge
+        let mut edge_endpoints: Vec<Option<(usize, usize)>> = vec![];
+        for (_, snapshot) in self.snapshots.iter() {
+            if let Some(edges) = snapshot.get("edges").and_then(|edges| edges.as_array()) {
+                edge_endpoints.resize(std::cmp::max(edge_endpoints.len(), edges.len()), None);
+                for (edge_index, edge) in edges.iter().enumerate() {
+                    if let (Some(left), Some(right)) = (edge.get("l"), edge.get("r")) {
+                        edge_endpoints[edge_index] =
+                            Some((left.as_u64().unwrap() as usize, right.as_u64().unwrap() as usize));
+                    }
+                }
+            }
+        }
+        let mut edge_map: Vec<Option<EdgeIndex>> = vec![None; edge_endpoints.len()];
+        let mut kept_edge_num = 0;
+        for (edge_index, endpoints) in edge_endpoints.iter().enumerate() {
+            if let Some((left, right)) = endpoints {
+                if vertex_map[*left].is_some() && vertex_map[*right].is_some() {
+                    edge_map[edge_index] = Some(kept_edge_num as EdgeIndex);
+                    kept_edge_num += 1;
+                }
+            }
+        }
+        let map_edge = |value: &serde_json::Value| -> Option<serde_json::Value> {
+            let edge_index = value.as_u64()? as usize;
+            edge_map.get(edge_index).cloned().flatten().map(|new_index| json!(new_index))
+        };
+        let mut snapshots = Vec::with_capacity(self.snapshots.len());
+        for (name, snapshot) in self.snapshots.iter() {
+            let mut snapshot = snapshot.clone();
+            let object = snapshot.as_object_mut().expect("snapshot must be an object");
+            if let Some(edges) = object.get_mut("edges").and_then(|edges| edges.as_array_mut()) {
+                let mut new_edges = vec![serde_json::Value::Null; kept_edge_num];
+                for (edge_index, edge) in edges.iter_mut().enumerate() {
+                    if let Some(new_index) = edge_map[edge_index] {
+                        if let Some(edge) = edge.as_object_mut() {
+                            for key in ["l", "r"] {
+                                let vertex_index = edge[key].clone();
+                                edge.insert(key.to_string(), map_vertex(&vertex_index).unwrap());
+                            }
+                        }
+                        new_edges[new_index as usize] = edge.take();
+                    }
+                }
+                *edges = new_edges;
+            }
+            if let Some(vertices) = object.get_mut("vertices").and_then(|vertices| vertices.as_array_mut()) {
+                *vertices = vertices
+                    .iter()
+                    .enumerate()
+                    .filter(|(vertex_index, _)| vertex_map[*vertex_index].is_some())
+                    .map(|(_, vertex)| vertex.clone())
+                    .collect();
+            }
+            if let Some(subgraph) = object.get_mut("subgraph").and_then(|subgraph| subgraph.as_array_mut()) {
+                *subgraph = subgraph.iter().filter_map(map_edge).collect();
+            }
+            if let Some(matchings) = object.get_mut("matchings").and_then(|matchings| matchings.as_array_mut()) {
+                *matchings = matchings
+                    .iter()
+                    .filter_map(|matching| {
+                        let mut matching = matching.as_object()?.clone();
+                        for (_, vertex_index) in matching.iter_mut() {
+                            *vertex_index = map_vertex(vertex_index)?;
+                        }
+                        Some(serde_json::Value::Object(matching))
+                    })
+                    .collect();
+            }
+            if let Some(dual_nodes) = object.get_mut("dual_nodes").and_then(|dual_nodes| dual_nodes.as_array_mut()) {
+                for dual_node in dual_nodes.iter_mut().filter_map(|dual_node| dual_node.as_object_mut()) {
+                    if let Some(defect_vertex) = dual_node.get_mut("s") {
+                        *defect_vertex = map_vertex(defect_vertex).unwrap_or(serde_json::Value::Null);
+                    }
+                    if let Some(boundary) = dual_node.get_mut("b").and_then(|boundary| boundary.as_array_mut()) {
+                        *boundary = boundary
+                            .iter()
+                            .filter_map(|pair| {
+                                let (is_left, edge_index) = (pair.get(0)?, pair.get(1)?);
+                                Some(json!([is_left, map_edge(edge_index)?]))
+                            })
+                            .collect();
+                    }
+                }
+            }
+            snapshots.push((name.clone(), snapshot));
+        }
+        Self {
+            format: self.format.clone(),
+            version: self.version.clone(),
+            positions,
+            run_metadata: self.run_metadata.clone(),
+            // the vertices and edges are re-indexed
+            initializer: None,
+            partition_config: None,
+            snapshots,
+        }
     }
 
-    #[cfg(feature = "python_binding")]
-    #[pyo3(name = "snapshot_combined")]
-    pub fn snapshot_combined_py(&mut self, name: String, object_pys: Vec<&PyAny>) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
+    /// load a visualization file, decompressing it if it's gzip-compressed; an unfinished compressed file (e.g. when the
+    /// program crashes) is truncated to the last complete snapshot
+    pub fn load(path: &str) -> Result<Self, VisualizeError> {
+        let mut data = vec![];
+        File::open(path)?.read_to_end(&mut data)?;
+        let content = if data.starts_with(&[0x1f, 0x8b]) {
+            let mut decoder = GzDecoder::new(data.as_slice());
+            let mut decompressed = vec![];
+            let mut buffer = [0; 65536];
+            loop {
+                match decoder.read(&mut buffer) {
+                    Ok(0) => break,
+                    Ok(length) => decompressed.extend_from_slice(&buffer[..length]),
+                    Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => break, // unfinished file
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            let mut content = String::from_utf8_lossy(&decompressed).into_owned();
+            if !content.ends_with("]}") {
+                // the last snapshot might be incomplete, in which case it's dropped
+                if serde_json::from_str::<serde_json::Value>(&(content.clone() + "]}")).is_err() {
+                    let Some(last_complete) = content.rfind('\n') else {
+                        return Err(<serde_json::Error as serde::de::Error>::custom("incomplete header").into());
+                    };
+                    content.truncate(last_complete);
+                    if content.ends_with(',') {
+                        content.pop();
+                    }
+                }
+                content.push_str("]}");
+            }
+            content
+        } else {
+            String::from_utf8_lossy(&data).into_owned()
+        };
+        let mut file: Self = serde_json::from_str(&content)?;
+        if file.format != "fusion_blossom" {
+            return Err(VisualizeError::Format(format!("unknown format `{}`", file.format)));
         }
-        let mut values = Vec::<serde_json::Value>::with_capacity(object_pys.len());
-        for object_py in object_pys.into_iter() {
-            values.push(pyobject_to_json(object_py.call_method0("snapshot")?.extract::<PyObject>()?));
+        // reconstruct the full frames of delta snapshots, whose base is always an earlier snapshot
+        for snapshot_index in 0..file.snapshots.len() {
+            let Some(base_index) = file.snapshots[snapshot_index].1.get("base").map(|base| base.as_u64()) else {
+                continue;
+            };
+            match base_index.map(|base_index| base_index as usize) {
+                Some(base_index) if base_index < snapshot_index => {
+                    let value = snapshot_apply_delta(&file.snapshots[base_index].1, &file.snapshots[snapshot_index].1)?;
+                    file.snapshots[snapshot_index].1 = value;
+                }
+                _ => {
+                    let name = &file.snapshots[snapshot_index].0;
+                    return Err(VisualizeError::Format(format!("snapshot `{}` has invalid base", name)));
+                }
+            }
         }
-        self.snapshot_combined_value(name, values)
+        Ok(file)
     }
 
-    #[cfg(feature = "python_binding")]
-    #[pyo3(name = "snapshot")]
-    pub fn snapshot_py(&mut self, name: String, object_py: &PyAny) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
+    /// load the files written by a bundle visualizer into `dir`, see [`Visualizer::unit_visualizer`]
+    pub fn load_bundle(dir: &str) -> Result<VisualizeBundle, VisualizeError> {
+        let mut fusion = None;
+        let mut units = BTreeMap::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(filename) = path.file_name().and_then(|filename| filename.to_str()) else {
+                continue;
+            };
+            let Some(name) = filename.strip_suffix(".json").or_else(|| filename.strip_suffix(".json.gz")) else {
+                continue;
+            };
+            if name == "fusion" {
+                fusion = Some(Self::load(&path.to_string_lossy())?);
+            } else if let Some(unit_index) = name.strip_prefix("unit_").and_then(|index| index.parse::<usize>().ok()) {
+                units.insert(unit_index, Self::load(&path.to_string_lossy())?);
+            }
         }
-        let value = pyobject_to_json(object_py.call_method0("snapshot")?.extract::<PyObject>()?);
-        self.snapshot_value(name, value)
+        let fusion = fusion.ok_or_else(|| VisualizeError::Format(format!("missing `fusion.json` in {dir}")))?;
+        Ok(VisualizeBundle { fusion, units })
     }
 
-    #[cfg(feature = "python_binding")]
-    #[pyo3(name = "snapshot_combined_value")]
-    pub fn snapshot_combined_value_py(&mut self, name: String, value_pys: Vec<PyObject>) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
-        }
-        let values: Vec<_> = value_pys.into_iter().map(|value_py| pyobject_to_json(value_py)).collect();
-        self.snapshot_combined_value(name, values)
+    pub fn positions(&self) -> &[VisualizePosition] {
+        &self.positions
     }
 
-    #[cfg(feature = "python_binding")]
-    #[pyo3(name = "snapshot_value")]
-    pub fn snapshot_value_py(&mut self, name: String, value_py: PyObject) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
-        }
-        let value = pyobject_to_json(value_py);
-        self.snapshot_value(name, value)
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshots.len()
     }
-}
 
-impl Visualizer {
-    pub fn incremental_save(&mut self, name: String, value: serde_json::Value) -> std::io::Result<()> {
-        if let Some(file) = self.file.as_mut() {
-            self.snapshots.push(name.clone());
-            file.seek(SeekFrom::End(-2))?; // move the cursor before the ending ]}
-            if !self.empty_snapshot {
-                file.write_all(b",")?;
+    /// the name and the value of a snapshot
+    pub fn snapshot(&self, snapshot_index: usize) -> (&str, &serde_json::Value) {
+        let (name, value) = &self.snapshots[snapshot_index];
+        (name.as_str(), value)
+    }
+
+    /// the file-level metadata of the run, see [`Visualizer::set_run_metadata`]
+    pub fn run_metadata(&self) -> Option<&serde_json::Value> {
+        self.run_metadata.as_ref()
+    }
+
+    /// the decoding graph embedded by [`Visualizer::set_initializer`]
+    pub fn initializer(&self) -> Option<&SolverInitializer> {
+        self.initializer.as_ref()
+    }
+
+    /// the partition of the parallel solver embedded by [`Visualizer::set_initializer`]
+    pub fn partition_config(&self) -> Option<&PartitionConfig> {
+        self.partition_config.as_ref()
+    }
+
+    /// the syndrome pattern of the first snapshot with vertices, where an edge with an overridden weight is an erasure
+    /// if its weight is zero and a dynamic weight otherwise
+    #[allow(clippy::unnecessary_cast)]
+    pub fn recorded_syndrome_pattern(&self) -> Result<SyndromePattern, VisualizeError> {
+        let snapshot_index = (0..self.snapshots.len())
+            .find(|&snapshot_index| self.snapshots[snapshot_index].1.get("vertices").is_some())
+            .ok_or_else(|| VisualizeError::Format("no snapshot has vertices".to_string()))?;
+        let defect_vertices = (self.snapshot_vertices(snapshot_index)?.iter().enumerate())
+            .filter(|(_, vertex)| vertex.is_defect)
+            .map(|(vertex_index, _)| vertex_index as VertexIndex)
+            .collect();
+        let mut syndrome_pattern = SyndromePattern::new_vertices(defect_vertices);
+        if self.snapshots[snapshot_index].1.get("edges").is_some() {
+            for (edge_index, edge) in self.snapshot_edges(snapshot_index)?.iter().enumerate() {
+                if edge.is_erasure {
+                    syndrome_pattern.erasures.push(edge_index as EdgeIndex);
+                } else if edge.original_weight.is_some() {
+                    syndrome_pattern.dynamic_weights.push((edge_index as EdgeIndex, edge.weight));
+                }
             }
-            self.empty_snapshot = false;
-            file.write_all(json!((name, value)).to_string().as_bytes())?;
-            file.write_all(b"]}")?;
-            file.sync_all()?;
         }
-        Ok(())
+        Ok(syndrome_pattern)
     }
 
-    /// append another snapshot of the fusion type, and also update the file in case
-    pub fn snapshot_combined(&mut self, name: String, fusion_algorithms: Vec<&dyn FusionVisualizer>) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
-        }
-        let abbrev = true;
-        let mut value = json!({});
-        for fusion_algorithm in fusion_algorithms.iter() {
-            let value_2 = fusion_algorithm.snapshot(abbrev);
-            snapshot_combine_values(&mut value, value_2, abbrev);
-        }
-        snapshot_fix_missing_fields(&mut value, abbrev);
-        self.incremental_save(name, value)?;
-        Ok(())
+    /// the sum of dual variables of the last snapshot that records it, which is the weight of the final matching
+    pub fn recorded_sum_dual_variables(&self) -> Option<Weight> {
+        (self.snapshots.iter().rev())
+            .find_map(|(_, snapshot)| {
+                snapshot
+                    .get("interface")?
+                    .get("d")
+                    .or(snapshot["interface"].get("sum_dual_variables"))
+            })
+            .and_then(|sum_dual_variables| sum_dual_variables.as_i64())
+            .map(|sum_dual_variables| sum_dual_variables as Weight)
     }
 
-    /// append another snapshot of the fusion type, and also update the file in case
-    pub fn snapshot(&mut self, name: String, fusion_algorithm: &impl FusionVisualizer) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
-        }
-        let abbrev = true;
-        let mut value = fusion_algorithm.snapshot(abbrev);
-        snapshot_fix_missing_fields(&mut value, abbrev);
-        self.incremental_save(name, value)?;
-        Ok(())
+    /// the metadata of a snapshot, see [`Visualizer::snapshot`] and [`Visualizer::set_frame_metadata`]
+    pub fn snapshot_metadata(&self, snapshot_index: usize) -> Option<&serde_json::Value> {
+        self.snapshots[snapshot_index].1.get("metadata")
     }
 
-    pub fn snapshot_combined_value(&mut self, name: String, values: Vec<serde_json::Value>) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
-        }
-        let abbrev = true;
-        let mut value = json!({});
-        for value_2 in values.into_iter() {
-            snapshot_combine_values(&mut value, value_2, abbrev);
+    /// the microseconds elapsed from [`Visualizer::begin`] when the snapshot is taken, or `None` in older files
+    pub fn snapshot_elapsed_us(&self, snapshot_index: usize) -> Option<u64> {
+        self.snapshots[snapshot_index].1.get("elapsed_us")?.as_u64()
+    }
+
+    /// the vertices of a snapshot, accepting both abbreviated and verbose keys
+    pub fn snapshot_vertices(&self, snapshot_index: usize) -> Result<Vec<SnapshotVertex>, VisualizeError> {
+        let (name, value) = self.snapshot(snapshot_index);
+        let vertices = value
+            .get("vertices")
+            .and_then(|vertices| vertices.as_array())
+            .ok_or_else(|| VisualizeError::Format(format!("snapshot `{}` has no vertices", name)))?;
+        let mut result = Vec::with_capacity(vertices.len());
+        for vertex in vertices.iter() {
+            let vertex = vertex
+                .as_object()
+                .ok_or_else(|| VisualizeError::Format(format!("snapshot `{}` has invalid vertex {}", name, vertex)))?;
+            result.push(SnapshotVertex {
+                is_virtual: snapshot_field_bool(vertex, "v", "is_virtual"),
+                is_defect: snapshot_field_bool(vertex, "s", "is_defect"),
+                propagated_dual_node: snapshot_field_integer(vertex, "p", "propagated_dual_node")?,
+                propagated_grandson_dual_node: snapshot_field_integer(vertex, "pg", "propagated_grandson_dual_node")?,
+                mirror_unit_index: snapshot_field_integer(vertex, "mi", "mirror_unit_index")?,
+            });
         }
-        snapshot_fix_missing_fields(&mut value, abbrev);
-        self.incremental_save(name, value)?;
-        Ok(())
+        Ok(result)
     }
 
-    pub fn snapshot_value(&mut self, name: String, mut value: serde_json::Value) -> std::io::Result<()> {
-        if cfg!(feature = "disable_visualizer") {
-            return Ok(());
+    /// the edges of a snapshot, accepting both abbreviated and verbose keys
+    pub fn snapshot_edges(&self, snapshot_index: usize) -> Result<Vec<SnapshotEdge>, VisualizeError> {
+        let (name, value) = self.snapshot(snapshot_index);
+        let edges = value
+            .get("edges")
+            .and_then(|edges| edges.as_array())
+            .ok_or_else(|| VisualizeError::Format(format!("snapshot `{}` has no edges", name)))?;
+        let mut result = Vec::with_capacity(edges.len());
+        for edge in edges.iter() {
+            let edge = edge
+                .as_object()
+                .ok_or_else(|| VisualizeError::Format(format!("snapshot `{}` has invalid edge {}", name, edge)))?;
+            result.push(SnapshotEdge {
+                weight: snapshot_field_required(edge, "w", "weight")?,
+                left: snapshot_field_required(edge, "l", "left")?,
+                right: snapshot_field_required(edge, "r", "right")?,
+                left_growth: snapshot_field_integer(edge, "lg", "left_growth")?.unwrap_or(0),
+                right_growth: snapshot_field_integer(edge, "rg", "right_growth")?.unwrap_or(0),
+                left_dual_node: snapshot_field_integer(edge, "ld", "left_dual_node")?,
+                right_dual_node: snapshot_field_integer(edge, "rd", "right_dual_node")?,
+                original_weight: snapshot_field_integer(edge, "ow", "original_weight")?,
+                is_erasure: snapshot_field_bool(edge, "e", "is_erasure"),
+            });
         }
-        let abbrev = true;
-        snapshot_fix_missing_fields(&mut value, abbrev);
-        self.incremental_save(name, value)?;
-        Ok(())
+        Ok(result)
     }
 }
 
@@ -516,16 +2113,23 @@ pub fn visualize_data_folder() -> String {
     DEFAULT_VISUALIZE_DATA_FOLDER.to_string()
 }
 
+/// the folder of the front-end libraries downloaded by `visualize/vendor.sh`, see [`Visualizer::export_standalone_html`]
+pub fn visualize_vendor_folder() -> String {
+    concat!(env!("CARGO_MANIFEST_DIR"), "/visualize/vendor/").to_string()
+}
+
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn static_visualize_data_filename() -> String {
     "visualizer.json".to_string()
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn auto_visualize_data_filename() -> String {
     format!("{}.json", Local::now().format("%Y%m%d-%H-%M-%S%.3f"))
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn print_visualize_link_with_parameters(filename: String, parameters: Vec<(String, String)>) {
     let default_port = if cfg!(feature = "python_binding") { 51666 } else { 8066 };
@@ -546,6 +2150,7 @@ pub fn print_visualize_link_with_parameters(filename: String, parameters: Vec<(S
     }
 }
 
+#[cfg(not(feature = "minimal"))]
 #[cfg_attr(feature = "python_binding", pyfunction)]
 pub fn print_visualize_link(filename: String) {
     print_visualize_link_with_parameters(filename, Vec::new())
@@ -564,17 +2169,26 @@ pub(crate) fn register(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     Ok(())
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "minimal")))]
 mod tests {
     use super::super::dual_module::*;
     use super::super::dual_module_serial::*;
     use super::super::example_codes::*;
+    use super::super::mwpm_solver::*;
     use super::super::pointers::*;
     use super::super::primal_module::*;
     use super::super::primal_module_serial::*;
     use super::super::*;
     use super::*;
 
+    /// remove the wall-clock time of the snapshots, to compare the content of different runs
+    fn without_elapsed_time(mut file: VisualizeFile) -> VisualizeFile {
+        for (_, snapshot) in file.snapshots.iter_mut() {
+            snapshot.as_object_mut().unwrap().remove("elapsed_us");
+        }
+        file
+    }
+
     #[test]
     fn visualize_test_1() {
         // cargo test visualize_test_1 -- --nocapture
@@ -784,7 +2398,7 @@ mod tests {
             let mut dual_module = DualModuleSerial::new_empty(&initializer);
             // hardcode syndrome          1   2   0   3    5    4    6    7
             let defect_vertices = vec![25, 33, 20, 76, 203, 187, 243, 315];
-            code.set_defect_vertices(&defect_vertices);
+            code.set_defect_vertices(&defect_vertices).unwrap();
             // create dual nodes and grow them by half length
             let interface_ptr = DualModuleInterfacePtr::new_load(&code.get_syndrome(), &mut dual_module);
             // save snapshot
@@ -944,4 +2558,793 @@ mod tests {
                 .unwrap();
         }
     }
+
+    /// the final snapshot records a correction that matches the syndrome
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_snapshot_final_1() {
+        // cargo test visualize_snapshot_final_1 -- --nocapture
+        let visualize_filename = "visualize_snapshot_final_1.json".to_string();
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve(&syndrome_pattern);
+        let perfect_matching = solver.perfect_matching();
+        let subgraph = solver.subgraph();
+        let visualize_path = visualize_data_folder() + visualize_filename.as_str();
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+        print_visualize_link(visualize_filename.clone());
+        visualizer
+            .snapshot_final("final".to_string(), &solver, &perfect_matching, &subgraph)
+            .unwrap();
+        // re-parse the file and check the correction against the syndrome
+        let content = std::fs::read_to_string(visualize_path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let snapshot = &value["snapshots"].as_array().unwrap().last().unwrap()[1];
+        let matchings = snapshot["matchings"].as_array().unwrap();
+        assert_eq!(
+            matchings.len(),
+            perfect_matching.peer_matchings.len() + perfect_matching.virtual_matchings.len()
+        );
+        let mut parity = vec![false; initializer.vertex_num as usize];
+        for edge_index in snapshot["subgraph"].as_array().unwrap().iter() {
+            let (vertex_1, vertex_2, _) = initializer.weighted_edges[edge_index.as_u64().unwrap() as usize];
+            parity[vertex_1 as usize] ^= true;
+            parity[vertex_2 as usize] ^= true;
+        }
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            parity[virtual_vertex as usize] = false;
+        }
+        let odd_vertices: Vec<VertexIndex> = (0..initializer.vertex_num as VertexIndex)
+            .filter(|&vertex_index| parity[vertex_index as usize])
+            .collect();
+        let mut defect_vertices = syndrome_pattern.defect_vertices.clone();
+        defect_vertices.sort_unstable();
+        assert_eq!(odd_vertices, defect_vertices);
+    }
+
+    /// the dual variables in the last snapshot of a solve sum up to the weight of the matching
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_snapshot_dual_variables_1() {
+        // cargo test visualize_snapshot_dual_variables_1 -- --nocapture
+        let visualize_filename = "visualize_snapshot_dual_variables_1.json".to_string();
+        let visualize_path = visualize_data_folder() + visualize_filename.as_str();
+        print_visualize_link(visualize_filename.clone());
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        for seed in 0..10 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            assert!(syndrome_pattern.erasures.is_empty());
+            let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+            solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+            let content = std::fs::read_to_string(&visualize_path).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+            let snapshot = &value["snapshots"].as_array().unwrap().last().unwrap()[1];
+            let mut sum_dual_variables = 0;
+            for dual_node in snapshot["dual_nodes"].as_array().unwrap().iter() {
+                if !dual_node.is_null() {
+                    sum_dual_variables += dual_node["y"].as_i64().unwrap() as Weight;
+                }
+            }
+            let matching_weight: Weight = solver
+                .subgraph()
+                .iter()
+                .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+                .sum();
+            assert_eq!(sum_dual_variables, solver.sum_dual_variables());
+            assert_eq!(sum_dual_variables, matching_weight);
+            solver.clear();
+        }
+    }
+
+    /// the checked-in expectations of [`visualize_check_golden`]
+    const VISUALIZE_GOLDEN_FOLDER: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/visualize/golden/");
+
+    /// compare selected fields of the recorded snapshots against `visualize/golden/<name>.json`;
+    /// after an intended change of the algorithm, regenerate them with
+    /// `UPDATE_GOLDEN=1 cargo test visualize_golden` (or `FUSION_BLOSSOM_UPDATE_GOLDEN=1`)
+    fn visualize_check_golden(name: &str, file: &VisualizeFile) {
+        let mut summary = vec![];
+        for snapshot_index in 0..file.snapshot_count() {
+            let (snapshot_name, value) = file.snapshot(snapshot_index);
+            let vertices = file.snapshot_vertices(snapshot_index).unwrap();
+            let edges = file.snapshot_edges(snapshot_index).unwrap();
+            let defect_vertices: Vec<usize> = (0..vertices.len()).filter(|&i| vertices[i].is_defect).collect();
+            let tight_edges: Vec<usize> = (0..edges.len())
+                .filter(|&i| edges[i].left_growth + edges[i].right_growth == edges[i].weight)
+                .collect();
+            let dual_node_num = value["dual_nodes"]
+                .as_array()
+                .map(|dual_nodes| dual_nodes.iter().filter(|dual_node| !dual_node.is_null()).count())
+                .unwrap_or(0);
+            summary.push(json!({
+                "name": snapshot_name,
+                "defect_vertices": defect_vertices,
+                "tight_edges": tight_edges,
+                "dual_node_num": dual_node_num,
+                "matchings": value.get("matchings"),
+            }));
+        }
+        let summary = serde_json::to_string_pretty(&summary).unwrap() + "\n";
+        let golden_path = format!("{}{}.json", VISUALIZE_GOLDEN_FOLDER, name);
+        let update = ["UPDATE_GOLDEN", "FUSION_BLOSSOM_UPDATE_GOLDEN"]
+            .iter()
+            .any(|variable| std::env::var(variable).is_ok_and(|value| value == "1"));
+        if update {
+            std::fs::create_dir_all(VISUALIZE_GOLDEN_FOLDER).unwrap();
+            std::fs::write(&golden_path, summary).unwrap();
+            return;
+        }
+        let golden = std::fs::read_to_string(&golden_path)
+            .unwrap_or_else(|_| panic!("missing {}, run with UPDATE_GOLDEN=1 to generate it", golden_path));
+        assert!(
+            golden == summary,
+            "snapshots differ from {}, run with UPDATE_GOLDEN=1 to update it if the change is intended",
+            golden_path
+        );
+    }
+
+    fn visualize_golden_decode(name: &str, mut code: impl ExampleCode, seed: u64) {
+        let visualize_path = visualize_data_folder() + name + ".json";
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        let syndrome_pattern = code.generate_random_errors(seed);
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        solver.perfect_matching_visualizer(Some(&mut visualizer));
+        drop(visualizer);
+        let file = VisualizeFile::load(&visualize_path).unwrap();
+        assert!(file.snapshot_count() > 2);
+        visualize_check_golden(name, &file);
+    }
+
+    #[test]
+    fn visualize_golden_code_capacity_1() {
+        // cargo test visualize_golden_code_capacity_1 -- --nocapture
+        visualize_golden_decode(
+            "visualize_golden_code_capacity_1",
+            CodeCapacityPlanarCode::new(7, 0.1, 500),
+            1,
+        );
+    }
+
+    #[test]
+    fn visualize_golden_phenomenological_1() {
+        // cargo test visualize_golden_phenomenological_1 -- --nocapture
+        visualize_golden_decode(
+            "visualize_golden_phenomenological_1",
+            PhenomenologicalPlanarCode::new(5, 3, 0.02, 500),
+            2,
+        );
+    }
+
+    /// verbose and abbreviated snapshots are read into the same typed fields
+    #[test]
+    fn visualize_file_reader_1() {
+        // cargo test visualize_file_reader_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let syndrome_pattern = code.generate_random_errors(3);
+        code.set_syndrome(&syndrome_pattern).unwrap();
+        let mut read_back = vec![];
+        for abbrev in [true, false] {
+            let visualize_path = visualize_data_folder() + &format!("visualize_file_reader_1_{}.json", abbrev);
+            // the visualizer always abbreviates, but files written by other tools may not
+            VisualizeFile {
+                format: "fusion_blossom".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                positions: code.get_positions(),
+                run_metadata: None,
+                initializer: None,
+                partition_config: None,
+                snapshots: vec![("code".to_string(), code.snapshot(abbrev))],
+            }
+            .save(&visualize_path)
+            .unwrap();
+            let file = VisualizeFile::load(&visualize_path).unwrap();
+            assert_eq!(file.snapshot_count(), 1);
+            assert_eq!(file.snapshot(0).0, "code");
+            assert_eq!(file.snapshot_elapsed_us(0), None);
+            assert_eq!(file.positions().len(), code.vertices.len());
+            read_back.push((file.snapshot_vertices(0).unwrap(), file.snapshot_edges(0).unwrap()));
+        }
+        assert_eq!(read_back[0], read_back[1]);
+        let (vertices, edges) = &read_back[0];
+        for (vertex_index, vertex) in vertices.iter().enumerate() {
+            assert_eq!(
+                vertex.is_defect,
+                syndrome_pattern.defect_vertices.contains(&(vertex_index as VertexIndex))
+            );
+            assert_eq!(vertex.is_virtual, code.vertices[vertex_index].is_virtual);
+        }
+        for (edge_index, edge) in edges.iter().enumerate() {
+            assert_eq!(edge.weight, code.edges[edge_index].half_weight * 2);
+            assert_eq!((edge.left, edge.right), code.edges[edge_index].vertices);
+        }
+        // a file of another format is rejected
+        let invalid_path = visualize_data_folder() + "visualize_file_reader_1_invalid.json";
+        std::fs::write(
+            &invalid_path,
+            r#"{"format":"other","version":"","positions":[],"snapshots":[]}"#,
+        )
+        .unwrap();
+        assert!(matches!(VisualizeFile::load(&invalid_path), Err(VisualizeError::Format(_))));
+    }
+
+    /// a client watches the decoding live
+    #[test]
+    fn visualize_streaming_1() {
+        // cargo test visualize_streaming_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        let syndrome_pattern = code.generate_random_errors(2);
+        let mut visualizer = Visualizer::new_streaming("127.0.0.1:0", code.get_positions(), true).unwrap();
+        // connect before decoding, otherwise a fast decoding may close the visualizer before the client connects
+        let mut stream = std::net::TcpStream::connect(visualizer.stream_addr().unwrap()).unwrap();
+        let client = std::thread::spawn(move || {
+            let mut messages: Vec<serde_json::Value> = vec![];
+            let mut length = [0u8; 4];
+            while stream.read_exact(&mut length).is_ok() {
+                let mut message = vec![0u8; u32::from_be_bytes(length) as usize];
+                stream.read_exact(&mut message).unwrap();
+                messages.push(serde_json::from_slice(&message).unwrap());
+            }
+            messages
+        });
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        let snapshot_num = visualizer.snapshots.len();
+        assert_eq!(visualizer.dropped_frames(), 0);
+        drop(visualizer);
+        let messages = client.join().unwrap();
+        assert_eq!(messages[0]["format"], json!("fusion_blossom"));
+        assert_eq!(messages[0]["positions"].as_array().unwrap().len(), code.vertices.len());
+        assert_eq!(messages.len(), 1 + snapshot_num);
+        assert_eq!(messages.last().unwrap()[0], json!("solved"));
+        // without a client, the frames beyond the queue are dropped instead of blocking the decoder
+        let mut visualizer = Visualizer::new_streaming("127.0.0.1:0", code.get_positions(), true).unwrap();
+        for index in 0..100 {
+            visualizer.snapshot(format!("code {}", index), &code, None).unwrap();
+        }
+        assert_eq!(visualizer.dropped_frames(), 100 + 1 - VISUALIZE_STREAM_QUEUE_SIZE);
+    }
+
+    /// delta snapshots are reconstructed losslessly and are much smaller
+    #[test]
+    fn visualize_delta_snapshots_1() {
+        // cargo test visualize_delta_snapshots_1 -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(7, 7, 0.05, 500);
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(1);
+        let run = |filename: &str, options: VisualizerOptions| -> (VisualizeFile, u64) {
+            let filepath = visualize_data_folder() + filename;
+            let mut visualizer =
+                Visualizer::new_with_options(Some(filepath.clone()), code.get_positions(), true, options).unwrap();
+            let mut solver = SolverSerial::new(&initializer);
+            solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+            solver.perfect_matching_visualizer(Some(&mut visualizer));
+            drop(visualizer);
+            let size = std::fs::metadata(&filepath).unwrap().len();
+            (without_elapsed_time(VisualizeFile::load(&filepath).unwrap()), size)
+        };
+        let (full, full_size) = run("visualize_delta_snapshots_1_full.json", VisualizerOptions::default());
+        let delta_options = VisualizerOptions {
+            delta: true,
+            ..Default::default()
+        };
+        let (delta, delta_size) = run("visualize_delta_snapshots_1_delta.json", delta_options.clone());
+        assert!(full.snapshots.len() > 5);
+        assert_eq!(full, delta);
+        println!(
+            "{} snapshots: full {} bytes, delta {} bytes ({:.1}x smaller)",
+            full.snapshots.len(),
+            full_size,
+            delta_size,
+            full_size as f64 / delta_size as f64
+        );
+        assert!(delta_size * 2 < full_size);
+        // with decimation, each kept frame is a delta against the previously kept one
+        let decimate = |options: VisualizerOptions| VisualizerOptions {
+            every_nth_snapshot: 3,
+            ..options
+        };
+        let (decimated, _) = run("visualize_delta_snapshots_1_decimated.json", decimate(Default::default()));
+        let (decimated_delta, _) = run(
+            "visualize_delta_snapshots_1_decimated_delta.json",
+            decimate(delta_options.clone()),
+        );
+        assert!(decimated.snapshots.len() < full.snapshots.len());
+        assert_eq!(decimated, decimated_delta);
+        // a visualizer without a file also accepts delta snapshots
+        let mut visualizer = Visualizer::new_with_options(None, code.get_positions(), true, delta_options).unwrap();
+        let mut solver = SolverSerial::new(&initializer);
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        // a corrupted delta is an error instead of a panic
+        let corrupted_path = visualize_data_folder() + "visualize_delta_snapshots_1_corrupted.json";
+        let content = std::fs::read_to_string(visualize_data_folder() + "visualize_delta_snapshots_1_delta.json").unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        value["snapshots"][1][1]["delta"]["vertices"]["changed"] = json!({ "1000000": null });
+        std::fs::write(&corrupted_path, value.to_string()).unwrap();
+        assert!(matches!(VisualizeFile::load(&corrupted_path), Err(VisualizeError::Format(_))));
+    }
+
+    /// compressed and uncompressed files of the same run have identical content
+    #[test]
+    fn visualize_compressed_file_1() {
+        // cargo test visualize_compressed_file_1 -- --nocapture
+        let filepath = visualize_data_folder() + "visualize_compressed_file_1.json";
+        let compressed_filepath = filepath.clone() + ".gz";
+        let unfinished_filepath = visualize_data_folder() + "visualize_compressed_file_1_unfinished.json.gz";
+        let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+        let initializer = code.get_initializer();
+        let mut solver = SolverSerial::new(&initializer);
+        let mut visualizer = Visualizer::new(Some(filepath.clone()), code.get_positions(), true).unwrap();
+        let mut compressed_visualizer =
+            Visualizer::new(Some(compressed_filepath.clone()), code.get_positions(), true).unwrap();
+        let mut unfinished_visualizer =
+            Visualizer::new_with_compression(Some(unfinished_filepath.clone()), code.get_positions(), true, true).unwrap();
+        for seed in 0..5 {
+            let syndrome_pattern = code.generate_random_errors(seed);
+            solver.solve(&syndrome_pattern);
+            let name = format!("seed {seed}");
+            visualizer.snapshot(name.clone(), &solver, None).unwrap();
+            compressed_visualizer.snapshot(name.clone(), &solver, None).unwrap();
+            unfinished_visualizer.snapshot(name, &solver, None).unwrap();
+            solver.clear();
+        }
+        drop(visualizer);
+        drop(compressed_visualizer);
+        std::mem::forget(unfinished_visualizer); // as if the program crashes
+        let file = without_elapsed_time(VisualizeFile::load(&filepath).unwrap());
+        let compressed_file = VisualizeFile::load(&compressed_filepath).unwrap();
+        assert_eq!(file.snapshots.len(), 5);
+        assert_eq!(file, without_elapsed_time(compressed_file.clone()));
+        assert_eq!(file, without_elapsed_time(VisualizeFile::load(&unfinished_filepath).unwrap()));
+        // the compressed file is also a valid gzip file with the same content
+        let mut decoder = GzDecoder::new(File::open(&compressed_filepath).unwrap());
+        let mut content = String::new();
+        decoder.read_to_string(&mut content).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value, json!(compressed_file));
+        // a file truncated inside its header is an error instead of a panic
+        let truncated_filepath = visualize_data_folder() + "visualize_compressed_file_1_truncated.json.gz";
+        let mut encoder = GzEncoder::new(File::create(&truncated_filepath).unwrap(), Compression::default());
+        encoder.write_all(&content.as_bytes()[..10]).unwrap();
+        encoder.flush().unwrap();
+        std::mem::forget(encoder);
+        assert!(matches!(
+            VisualizeFile::load(&truncated_filepath),
+            Err(VisualizeError::Json(_))
+        ));
+    }
+
+    /// decimated snapshots keep the final ones and a monotonically increasing index
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_snapshot_decimation_1() {
+        // cargo test visualize_snapshot_decimation_1 -- --nocapture
+        let mut code = CodeCapacityPlanarCode::new(21, 0.2, 500);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let initializer = code.get_initializer();
+        let run = |filename: &str, options: VisualizerOptions| -> VisualizeFile {
+            let filepath = visualize_data_folder() + filename;
+            let mut visualizer =
+                Visualizer::new_with_options(Some(filepath.clone()), code.get_positions(), true, options).unwrap();
+            let mut solver = SolverSerial::new(&initializer);
+            solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+            solver.subgraph_visualizer(Some(&mut visualizer));
+            drop(visualizer);
+            without_elapsed_time(VisualizeFile::load(&filepath).unwrap())
+        };
+        let snapshot_index =
+            |snapshot: &(String, serde_json::Value)| snapshot.1["snapshot_index"].as_u64().unwrap() as usize;
+        let final_names = ["solved", "perfect matching", "perfect matching and subgraph"];
+        let full = run("visualize_snapshot_decimation_1_full.json", VisualizerOptions::default());
+        let total = full.snapshots.len();
+        let final_num = full
+            .snapshots
+            .iter()
+            .filter(|(name, _)| final_names.contains(&name.as_str()))
+            .count();
+        let growth_num = full.snapshots.iter().filter(|(name, _)| name.starts_with("grow")).count();
+        assert_eq!(final_num, 2);
+        assert!(total > 20, "the syndrome should take many snapshots");
+        for (index, snapshot) in full.snapshots.iter().enumerate() {
+            assert_eq!(snapshot_index(snapshot), index);
+        }
+        // decimation
+        let every_nth_snapshot = 5;
+        let decimated = run(
+            "visualize_snapshot_decimation_1_decimated.json",
+            VisualizerOptions {
+                every_nth_snapshot,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            decimated.snapshots.len(),
+            (total - final_num).div_ceil(every_nth_snapshot) + final_num
+        );
+        for pair in decimated.snapshots.windows(2) {
+            assert!(snapshot_index(&pair[0]) < snapshot_index(&pair[1]));
+        }
+        assert_eq!(decimated.snapshots.last(), full.snapshots.last());
+        // filter
+        let filtered = run(
+            "visualize_snapshot_decimation_1_filtered.json",
+            VisualizerOptions {
+                include: SnapshotFilter::only(&[SnapshotCategory::Growth, SnapshotCategory::Final]),
+                ..Default::default()
+            },
+        );
+        assert_eq!(filtered.snapshots.len(), growth_num + final_num);
+        assert!(filtered.snapshots[..growth_num]
+            .iter()
+            .all(|(name, _)| name.starts_with("grow")));
+        assert_eq!(filtered.snapshots.last(), full.snapshots.last());
+        // limit, spread over the whole decoding instead of only its beginning and ending at its last snapshot
+        let max_snapshots = 4;
+        let limited = run(
+            "visualize_snapshot_decimation_1_limited.json",
+            VisualizerOptions {
+                max_snapshots: Some(max_snapshots),
+                ..Default::default()
+            },
+        );
+        let candidates = &full.snapshots[..total - final_num];
+        assert!(candidates.len() > 4 * max_snapshots);
+        assert_eq!(limited.snapshots.len(), max_snapshots + final_num);
+        assert_eq!(limited.snapshots[0], candidates[0]);
+        assert!(snapshot_index(&limited.snapshots[1]) >= max_snapshots);
+        assert_eq!(limited.snapshots[max_snapshots - 1], candidates[candidates.len() - 1]);
+        assert_eq!(limited.snapshots[max_snapshots..], full.snapshots[total - final_num..]);
+    }
+
+    /// the standalone html embeds the recorded snapshots
+    #[test]
+    fn visualize_export_standalone_html_1() {
+        // cargo test visualize_export_standalone_html_1 -- --nocapture
+        let filepath = visualize_data_folder() + "visualize_export_standalone_html_1.json";
+        let html_path = visualize_data_folder() + "visualize_export_standalone_html_1.html";
+        let mut code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        let mut visualizer = Visualizer::new(Some(filepath.clone()), code.get_positions(), true).unwrap();
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        // the libraries are replaced by stubs, see `visualize/vendor.sh` for the real ones
+        let vendor_dir = visualize_data_folder() + "visualize_export_standalone_html_1_vendor/";
+        for url in standalone_vendor_urls() {
+            let path = std::path::Path::new(&vendor_dir).join(url.trim_start_matches("https://"));
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, format!("/* {url} */")).unwrap();
+        }
+        let missing_vendor_dir = visualize_data_folder() + "visualize_export_standalone_html_1_missing_vendor/";
+        let error = visualizer
+            .export_standalone_html_with_vendor(&html_path, &missing_vendor_dir)
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+        visualizer
+            .export_standalone_html_with_vendor(&html_path, &vendor_dir)
+            .unwrap();
+        let html = std::fs::read_to_string(&html_path).unwrap();
+        assert!(html.len() < 1024 * 1024, "unexpectedly large html: {} bytes", html.len());
+        // no script is loaded online, only the fonts are
+        assert!(!html.contains("src=\"http://") && !html.contains("src=\"https://"));
+        assert!(html
+            .match_indices("https://")
+            .all(|(start, _)| html[start..].starts_with("https://fonts.googleapis.com/")));
+        let payload_start = "<script type=\"text/plain\" id=\"fusion-embedded-data\">";
+        let payload = html.split(payload_start).nth(1).unwrap().split("</script>").next().unwrap();
+        let compressed = crate::base64::engine::general_purpose::STANDARD
+            .decode(payload.trim())
+            .unwrap();
+        let mut content = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut content).unwrap();
+        let embedded: VisualizeFile = serde_json::from_str(&content).unwrap();
+        assert_eq!(embedded, VisualizeFile::load(&filepath).unwrap());
+        assert!(!embedded.snapshots.is_empty());
+        assert!(embedded.snapshot_elapsed_us(0).is_some());
+        assert!(!html.contains("src=\"./index.js\""));
+    }
+
+    /// all the layers of a space-time graph collapse into the same 2D positions
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_project_2d_1() {
+        // cargo test visualize_project_2d_1 -- --nocapture
+        let visualize_filename = "visualize_project_2d_1.json".to_string();
+        let visualize_path = visualize_data_folder() + visualize_filename.as_str();
+        let code = PhenomenologicalPlanarCode::new(5, 5, 0.05, 500);
+        let positions = code.get_positions();
+        let initializer = code.get_initializer();
+        let projected = VisualizePosition::project_2d(&positions, ProjectionAxis::T);
+        for (a, position_a) in positions.iter().enumerate() {
+            for (b, position_b) in positions.iter().enumerate() {
+                if (position_a.i, position_a.j) == (position_b.i, position_b.j) {
+                    assert_eq!(projected[a], projected[b]);
+                }
+            }
+        }
+        let virtual_offset = 0.5;
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), positions.clone(), true).unwrap();
+        visualizer
+            .set_positions_projected(&positions, ProjectionAxis::T, &initializer.virtual_vertices, virtual_offset)
+            .unwrap();
+        print_visualize_link(visualize_filename.clone());
+        visualizer.snapshot("code".to_string(), &code, None).unwrap();
+        drop(visualizer);
+        let file = VisualizeFile::load(&visualize_path).unwrap();
+        let real_vertex = (0..initializer.vertex_num)
+            .find(|vertex_index| !initializer.virtual_vertices.contains(vertex_index))
+            .unwrap();
+        let virtual_vertex = initializer.virtual_vertices[0];
+        assert_eq!(
+            file.positions[virtual_vertex as usize].t - file.positions[real_vertex as usize].t,
+            virtual_offset
+        );
+        assert_eq!(file.snapshots[0].1["projection"]["axis"], json!("T"));
+    }
+
+    #[test]
+    fn visualize_position_transform_1() {
+        // cargo test visualize_position_transform_1 -- --nocapture
+        let visualize_filename = "visualize_position_transform_1.json".to_string();
+        let visualize_path = visualize_data_folder() + visualize_filename.as_str();
+        let mut code = CodeCapacityPlanarCode::new(3, 0.1, 500);
+        let positions = code.get_positions();
+        let transform = PositionTransform::new().rotate_t(90.).scale(2., 3., 1.);
+        // (i, j) -> (-j, i) -> (-2j, 3i)
+        let transformed = transform.apply_all(&positions);
+        assert_eq!(transformed[1], VisualizePosition::new(-2., 0., 0.));
+        assert_eq!(transformed[7], VisualizePosition::new(2., 3., 0.));
+        assert_eq!(transformed[10], VisualizePosition::new(-4., 6., 0.));
+        let swapped = PositionTransform::new().swap_axes(ProjectionAxis::I, ProjectionAxis::T);
+        assert_eq!(swapped.apply(&positions[10]), VisualizePosition::new(0., 2., 2.));
+        // centering happens after the transform
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), positions.clone(), true).unwrap();
+        visualizer.set_positions_transformed(&positions, &transform, true).unwrap();
+        print_visualize_link(visualize_filename.clone());
+        visualizer.snapshot("code".to_string(), &code, None).unwrap();
+        drop(visualizer);
+        let file = VisualizeFile::load(&visualize_path).unwrap();
+        assert_eq!(file.positions[1], VisualizePosition::new(-1., -3., 0.));
+        assert_eq!(file.positions[10], VisualizePosition::new(-3., 3., 0.));
+        // fixing the code positions once gives the same layout
+        code.transform_positions(&transform);
+        assert_eq!(code.get_positions(), transformed);
+    }
+
+    /// export the middle rounds of a phenomenological decoding
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_export_filtered_1() {
+        // cargo test visualize_export_filtered_1 -- --nocapture
+        let visualize_path = visualize_data_folder() + "visualize_export_filtered_1.json";
+        let filtered_filename = "visualize_export_filtered_1_filtered.json".to_string();
+        let filtered_path = visualize_data_folder() + filtered_filename.as_str();
+        let mut code = PhenomenologicalPlanarCode::new(5, 9, 0.05, 500);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        solver.subgraph_visualizer(Some(&mut visualizer));
+        let original = VisualizeFile::load(&visualize_path).unwrap();
+        let mut times: Vec<f64> = original.positions.iter().map(|position| position.t).collect();
+        times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        times.dedup();
+        let (t_min, t_max) = (times[3], times[6]);
+        let is_selected = |position: &VisualizePosition| position.t >= t_min && position.t <= t_max;
+        visualizer
+            .export_filtered(&filtered_path, |_, position| is_selected(position))
+            .unwrap();
+        print_visualize_link(filtered_filename.clone());
+        let filtered = VisualizeFile::load(&filtered_path).unwrap();
+        let selected_positions: Vec<_> = original.positions.iter().filter(|position| is_selected(position)).collect();
+        assert_eq!(filtered.positions.len(), selected_positions.len());
+        assert_eq!(filtered.snapshots.len(), original.snapshots.len());
+        for ((name, snapshot), (filtered_name, filtered_snapshot)) in
+            original.snapshots.iter().zip(filtered.snapshots.iter())
+        {
+            assert_eq!(name, filtered_name);
+            assert_eq!(
+                filtered_snapshot["vertices"].as_array().unwrap().len(),
+                filtered.positions.len()
+            );
+            // the kept edges connect the same positions as the original ones, in the same order
+            let endpoints = |file: &VisualizeFile, edge: &serde_json::Value| {
+                let (left, right) = (edge["l"].as_u64().unwrap() as usize, edge["r"].as_u64().unwrap() as usize);
+                (file.positions[left].clone(), file.positions[right].clone())
+            };
+            let expected_edges: Vec<_> = snapshot["edges"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|edge| endpoints(&original, edge))
+                .filter(|(left, right)| is_selected(left) && is_selected(right))
+                .collect();
+            let filtered_edges: Vec<_> = filtered_snapshot["edges"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|edge| endpoints(&filtered, edge))
+                .collect();
+            assert!(!filtered_edges.is_empty());
+            assert_eq!(filtered_edges, expected_edges);
+            if let Some(subgraph) = filtered_snapshot.get("subgraph") {
+                for edge_index in subgraph.as_array().unwrap().iter() {
+                    assert!((edge_index.as_u64().unwrap() as usize) < filtered_edges.len());
+                }
+            }
+        }
+    }
+
+    /// the run metadata and the metadata of each snapshot are read back, also from a compressed file of delta snapshots
+    #[test]
+    fn visualize_metadata_1() {
+        // cargo test visualize_metadata_1 -- --nocapture
+        let mut code = PhenomenologicalPlanarCode::new(5, 3, 0.05, 500);
+        let run_metadata = code.run_metadata();
+        assert_eq!(run_metadata["d"], json!(5));
+        assert_eq!(run_metadata["noisy_measurements"], json!(3));
+        assert_eq!(run_metadata["p"], json!(0.05));
+        let seed = 2;
+        let syndrome_pattern = code.generate_random_errors(seed);
+        code.set_syndrome(&syndrome_pattern).unwrap();
+        for (filename, delta) in [("visualize_metadata_1.json", false), ("visualize_metadata_1.json.gz", true)] {
+            let visualize_path = visualize_data_folder() + filename;
+            let options = VisualizerOptions {
+                delta,
+                ..Default::default()
+            };
+            let mut visualizer =
+                Visualizer::new_with_options(Some(visualize_path.clone()), code.get_positions(), true, options).unwrap();
+            let mut metadata = run_metadata.clone();
+            metadata["seed"] = json!(seed);
+            visualizer.set_run_metadata(metadata).unwrap();
+            visualizer
+                .snapshot("syndrome".to_string(), &code, Some(json!({ "round": 3 })))
+                .unwrap();
+            let mut solver = SolverSerial::new(&code.get_initializer());
+            solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+            solver.subgraph_visualizer(Some(&mut visualizer));
+            drop(visualizer);
+            let file = VisualizeFile::load(&visualize_path).unwrap();
+            let loaded_run_metadata = file.run_metadata().unwrap();
+            assert_eq!(loaded_run_metadata["d"], json!(5));
+            assert_eq!(loaded_run_metadata["seed"], json!(seed));
+            assert_eq!(loaded_run_metadata["version"], json!(env!("CARGO_PKG_VERSION")));
+            assert!(loaded_run_metadata["timestamp"].is_string());
+            assert_eq!(file.snapshot_metadata(0), Some(&json!({ "round": 3 })));
+            assert!(file.snapshot_count() > 2);
+            let defect_num = json!({ "defect_num": syndrome_pattern.defect_vertices.len() });
+            for snapshot_index in 1..file.snapshot_count() {
+                assert_eq!(file.snapshot_metadata(snapshot_index), Some(&defect_num));
+            }
+        }
+    }
+
+    /// the snapshots are stamped with the time elapsed since the solving begins
+    #[test]
+    fn visualize_elapsed_time_1() {
+        // cargo test visualize_elapsed_time_1 -- --nocapture
+        let visualize_path = visualize_data_folder() + "visualize_elapsed_time_1.json";
+        let mut code = PhenomenologicalPlanarCode::new(5, 5, 0.05, 500);
+        let syndrome_pattern = code.generate_random_errors(1);
+        let mut solver = SolverSerial::new(&code.get_initializer());
+        let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+        let begin = std::time::Instant::now();
+        visualizer.begin();
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        solver.perfect_matching_visualizer(Some(&mut visualizer));
+        let solve_duration = begin.elapsed().as_micros() as u64;
+        drop(visualizer);
+        let file = VisualizeFile::load(&visualize_path).unwrap();
+        assert!(file.snapshot_count() > 10);
+        let elapsed: Vec<u64> = (0..file.snapshot_count())
+            .map(|snapshot_index| file.snapshot_elapsed_us(snapshot_index).unwrap())
+            .collect();
+        assert!(elapsed.windows(2).all(|pair| pair[0] <= pair[1]), "{elapsed:?}");
+        let last = *elapsed.last().unwrap();
+        println!("last snapshot at {last}us of the solving duration {solve_duration}us");
+        // only the saving of the last snapshot happens after it's stamped
+        assert!(last <= solve_duration && 2 * last >= solve_duration);
+    }
+
+    /// the embedded initializer and the syndrome recorded in the snapshots decode to the recorded weight
+    #[test]
+    fn visualize_initializer_1() {
+        // cargo test visualize_initializer_1 -- --nocapture
+        use super::super::example_partition::*;
+        let d = 5;
+        for (is_parallel, is_dynamic) in [(false, false), (false, true), (true, false)] {
+            let mut code = CodeCapacityPlanarCode::new(d, 0.1, 500);
+            code.set_erasure_probability(0.1);
+            let partition_config =
+                is_parallel.then(|| CodeCapacityPlanarCodeVerticalPartitionHalf::new(d, 3).build_apply(&mut code));
+            let initializer = code.get_initializer();
+            let mut syndrome_pattern = code.generate_random_errors(4);
+            assert!(!syndrome_pattern.erasures.is_empty());
+            // the parallel solver doesn't support erasures, which cannot be given together with dynamic weights either
+            if is_parallel || is_dynamic {
+                syndrome_pattern.erasures.clear();
+            }
+            if is_dynamic {
+                syndrome_pattern.dynamic_weights = vec![(0, 200), (3, 100)];
+            }
+            let visualize_path =
+                visualize_data_folder() + &format!("visualize_initializer_1_{is_parallel}_{is_dynamic}.json");
+            let mut visualizer = Visualizer::new(Some(visualize_path.clone()), code.get_positions(), true).unwrap();
+            visualizer.set_initializer(&initializer, partition_config.as_ref()).unwrap();
+            let mut solver: Box<dyn PrimalDualSolver> = match partition_config.as_ref() {
+                Some(partition_config) => Box::new(SolverParallel::new(&initializer, &partition_config.info(), json!({}))),
+                None => Box::new(SolverSerial::new(&initializer)),
+            };
+            solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+            solver.subgraph_visualizer(Some(&mut visualizer));
+            drop(visualizer);
+            let file = VisualizeFile::load(&visualize_path).unwrap();
+            assert_eq!(file.initializer(), Some(&initializer));
+            assert_eq!(file.partition_config(), partition_config.as_ref());
+            let mut recorded = file.recorded_syndrome_pattern().unwrap();
+            recorded.defect_vertices.sort_unstable();
+            syndrome_pattern.defect_vertices.sort_unstable();
+            assert_eq!(recorded.defect_vertices, syndrome_pattern.defect_vertices);
+            assert_eq!(recorded.erasures, syndrome_pattern.erasures);
+            assert_eq!(recorded.dynamic_weights, syndrome_pattern.dynamic_weights);
+            assert_eq!(file.recorded_sum_dual_variables(), Some(solver.sum_dual_variables()));
+            let mut replayed = SolverSerial::new(file.initializer().unwrap());
+            replayed.solve(&recorded);
+            assert_eq!(replayed.sum_dual_variables(), solver.sum_dual_variables());
+            // the re-indexed file no longer matches the initializer
+            assert_eq!(file.filtered(|_, _| true).initializer(), None);
+        }
+    }
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn visualize_bundle_1() {
+        // cargo test visualize_bundle_1 -- --nocapture
+        use super::super::example_partition::*;
+        let d = 5;
+        let mut code = CodeCapacityPlanarCode::new(d, 0.1, 500);
+        let partition_config = CodeCapacityPlanarCodeVerticalPartitionHalf::new(d, 3).build_apply(&mut code);
+        let initializer = code.get_initializer();
+        let syndrome_pattern = code.generate_random_errors(4);
+        assert!(!syndrome_pattern.defect_vertices.is_empty());
+        let bundle_dir = visualize_data_folder() + "visualize_bundle_1/";
+        let _ = std::fs::remove_dir_all(&bundle_dir);
+        let mut visualizer = Visualizer::new(Some(bundle_dir.clone()), code.get_positions(), true).unwrap();
+        assert!(visualizer.is_bundle());
+        visualizer.set_initializer(&initializer, Some(&partition_config)).unwrap();
+        let primal_dual_config = json!({ "primal": { "debug_sequential": true } });
+        let mut solver = SolverParallel::new(&initializer, &partition_config.info(), primal_dual_config);
+        solver.solve_visualizer(&syndrome_pattern, Some(&mut visualizer));
+        solver.subgraph_visualizer(Some(&mut visualizer));
+        drop(visualizer);
+        let bundle = VisualizeFile::load_bundle(&bundle_dir).unwrap();
+        assert_eq!(bundle.units.keys().cloned().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(bundle.fusion.recorded_sum_dual_variables(), Some(solver.sum_dual_variables()));
+        assert!(bundle.fusion.snapshots.iter().any(|(name, _)| name == "unit solved"));
+        for unit in bundle.units.values() {
+            assert_eq!(unit.positions(), bundle.fusion.positions());
+            assert_eq!(unit.initializer(), Some(&initializer));
+        }
+        // each defect is recorded in exactly the leaf unit owning it at its global index, and the defects on the interface
+        // between the two leaf units are only recorded in the fusion
+        let mut leaf_defect_num = 0;
+        for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+            let recording_units: Vec<usize> = (bundle.units.iter())
+                .filter(|(_, unit)| {
+                    (unit.snapshots.iter())
+                        .any(|(_, snapshot)| snapshot["vertices"][defect_vertex as usize].get("s") == Some(&json!(1)))
+                })
+                .map(|(&unit_index, _)| unit_index)
+                .collect();
+            let owning_units: Vec<usize> = (0..partition_config.partitions.len())
+                .filter(|&unit_index| partition_config.partitions[unit_index].contains(defect_vertex))
+                .collect();
+            assert_eq!(recording_units, owning_units, "defect {defect_vertex}");
+            leaf_defect_num += owning_units.len();
+        }
+        assert!(leaf_defect_num > 0);
+    }
 }
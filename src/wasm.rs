@@ -0,0 +1,81 @@
+//! WebAssembly Bindings
+//!
+//! Run small decoding demos fully in the browser with the serial solver, enabled by the `wasm` feature and built with
+//! e.g. `wasm-pack build --target web --features wasm`. The decoding graph and the syndrome are passed as the json of
+//! [`SolverInitializer`] and [`SyndromePattern`], see `tests/wasm/example.js` for a usage example.
+//!
+//! In `wasm32-unknown-unknown` there is no file system, no threads and no wall clock, so the command line interface,
+//! [`crate::example_codes::ErrorPatternReader`], [`crate::example_codes::ExampleCodeParallel`] and
+//! [`crate::util::BenchmarkProfiler`] are not compiled, and a [`crate::visualize::Visualizer`] can only be created
+//! without a file. The parallel solvers compile but must not be used.
+//!
+
+use super::example_codes::*;
+use super::mwpm_solver::*;
+use super::util::*;
+use wasm_bindgen::prelude::*;
+
+/// solve the syndrome and return the minimum-weight parity subgraph as a json list of edge indices
+#[wasm_bindgen]
+pub fn decode(initializer_json: &str, syndrome_json: &str) -> Result<String, JsError> {
+    decode_json(initializer_json, syndrome_json).map_err(|message| JsError::new(&message))
+}
+
+/// the json of the initializer and the vertex positions of a code capacity planar code, to be used with [`decode`] and
+/// the visualizer
+#[wasm_bindgen]
+pub fn code_capacity_planar_code(d: usize, p: f64, max_half_weight: i32) -> Result<String, JsError> {
+    let code = CodeCapacityPlanarCode::try_new(d as VertexNum, p, max_half_weight as Weight)
+        .map_err(|error| JsError::new(&error.to_string()))?;
+    Ok(json!({
+        "initializer": code.get_initializer(),
+        "positions": code.get_positions(),
+    })
+    .to_string())
+}
+
+/// the same as [`decode`] but testable outside WebAssembly; every input is checked because a panic aborts the whole
+/// WebAssembly instance
+pub fn decode_json(initializer_json: &str, syndrome_json: &str) -> Result<String, String> {
+    let initializer: SolverInitializer =
+        serde_json::from_str(initializer_json).map_err(|error| format!("invalid initializer: {error}"))?;
+    let syndrome_pattern: SyndromePattern =
+        serde_json::from_str(syndrome_json).map_err(|error| format!("invalid syndrome: {error}"))?;
+    initializer.check()?;
+    initializer.check_syndrome_pattern(&syndrome_pattern)?;
+    let mut solver = SolverSerial::new(&initializer);
+    solver.solve(&syndrome_pattern);
+    Ok(json!(solver.subgraph()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::unnecessary_cast)]
+    fn wasm_decode_json_1() {
+        // cargo test --features wasm wasm_decode_json_1 -- --nocapture
+        let code = CodeCapacityPlanarCode::new(5, 0.1, 500);
+        let initializer = code.get_initializer();
+        let initializer_json = json!(initializer).to_string();
+        let subgraph: Vec<EdgeIndex> =
+            serde_json::from_str(&decode_json(&initializer_json, r#"{"defect_vertices":[7,14]}"#).unwrap()).unwrap();
+        // the subgraph must produce the same syndrome
+        let mut parity = vec![false; initializer.vertex_num as usize];
+        for &edge_index in subgraph.iter() {
+            let (vertex_1, vertex_2, _) = initializer.weighted_edges[edge_index as usize];
+            parity[vertex_1 as usize] ^= true;
+            parity[vertex_2 as usize] ^= true;
+        }
+        for &virtual_vertex in initializer.virtual_vertices.iter() {
+            parity[virtual_vertex as usize] = false;
+        }
+        let defect_vertices: Vec<_> = (0..parity.len()).filter(|&vertex_index| parity[vertex_index]).collect();
+        assert_eq!(defect_vertices, vec![7, 14]);
+        // invalid inputs are reported instead of panicking
+        assert!(decode_json(&initializer_json, r#"{"defect_vertices":[1000]}"#).is_err());
+        assert!(decode_json(&initializer_json, r#"{"defect_vertices":[7],"erasures":[1000]}"#).is_err());
+        assert!(decode_json("{}", r#"{"defect_vertices":[]}"#).is_err());
+    }
+}
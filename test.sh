@@ -8,6 +8,8 @@ cargo test --features disable_visualizer,u32_index
 cargo test --features unsafe_pointer
 cargo test --features unsafe_pointer,disable_visualizer
 cargo test
+cargo test --features ffi --test ffi
+cargo test --features tracing --test tracing_spans
 
 cargo run --release -- test serial
 cargo run --release -- test dual-parallel
@@ -0,0 +1,9 @@
+#!/bin/sh
+set -ex
+
+# requires `rustup target add wasm32-unknown-unknown`, wasm-pack and node
+
+wasm-pack test --node --features wasm --test wasm
+
+wasm-pack build --target nodejs --features wasm
+node tests/wasm/example.js
@@ -0,0 +1,1091 @@
+//! run the `fusion_blossom` binary and the library functions behind its subcommands
+#![cfg(feature = "full")]
+
+use assert_cmd::Command;
+use clap::Parser;
+use fusion_blossom::analysis::{dataset_statistics, DatasetStatistics};
+use fusion_blossom::cli::{BenchmarkParameters, BenchmarkRecord, LogicalErrorRate};
+use fusion_blossom::example_codes::{
+    shard_filename, CodeCapacityPlanarCode, ErrorPatternReader, ExampleCode, ShardPolicy, ShardedErrorPatternReader,
+    SyndromeFile,
+};
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverDebugDump, SolverSerial};
+use fusion_blossom::util::{PartitionConfig, ProgressEvent, SolverInitializer, VertexNum, Weight};
+use fusion_blossom::visualize::{static_visualize_data_filename, visualize_data_folder, VisualizeFile};
+use std::sync::{Arc, Mutex};
+
+/// a fresh file path in the temporary folder for each test
+fn temporary_output_file(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("fusion_blossom_cli_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path.to_str().unwrap().to_string()
+}
+
+fn benchmark(arguments: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+    command
+        .env("DISABLE_PROGRESS_BAR", "1")
+        .args(["benchmark", "--verifier", "none", "--use-deterministic-seed"])
+        .args(arguments);
+    command
+}
+
+#[test]
+fn cli_benchmark_text_output_1() {
+    let output = benchmark(&["5", "0.05", "-r", "20"]).assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("total: "), "text output should stay unchanged: {stdout}");
+}
+
+#[test]
+fn cli_benchmark_csv_output_1() {
+    let output_file = temporary_output_file("benchmark.csv");
+    for d in ["3", "5"] {
+        benchmark(&[d, "0.05", "-r", "20", "--output-format", "csv", "--output-file", &output_file])
+            .assert()
+            .success();
+    }
+    let content = std::fs::read_to_string(&output_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 3, "one header and a row per configuration");
+    assert_eq!(lines[0], BenchmarkRecord::CSV_HEADER);
+    let columns: Vec<&str> = lines[0].split(',').collect();
+    for (line, d) in lines[1..].iter().zip([3, 5]) {
+        let row: Vec<&str> = line.split(',').collect();
+        assert_eq!(row.len(), columns.len());
+        let field = |name: &str| row[columns.iter().position(|column| *column == name).unwrap()];
+        assert_eq!(field("d").parse::<usize>().unwrap(), d);
+        assert_eq!(field("shots"), "20");
+        assert_eq!(field("partitions"), "1");
+        assert!(field("throughput").parse::<f64>().unwrap() > 0.);
+        assert!(field("p50_latency").parse::<f64>().unwrap() <= field("max_latency").parse::<f64>().unwrap());
+    }
+}
+
+#[test]
+fn cli_benchmark_json_output_1() {
+    let output_file = temporary_output_file("benchmark.json");
+    let profiler_file = temporary_output_file("profiler.txt");
+    let run = |primal_dual_type: &str| {
+        benchmark(&[
+            "7",
+            "0.05",
+            "-r",
+            "20",
+            "--code-type",
+            "phenomenological-planar-code",
+            "--noisy-measurements",
+            "7",
+            "--partition-strategy",
+            "phenomenological-planar-code-time-partition",
+            "--partition-config",
+            r#"{"partition_num":2,"enable_tree_fusion":true}"#,
+            "--primal-dual-type",
+            primal_dual_type,
+            "--primal-dual-config",
+            r#"{"thread_pool_size":2}"#,
+            "--output-format",
+            "json",
+            "--output-file",
+            &output_file,
+            "--benchmark-profiler-output",
+            &profiler_file,
+        ])
+        .assert()
+        .success();
+    };
+    run("parallel");
+    run("dual-parallel");
+    let records: Vec<BenchmarkRecord> = serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+    assert_eq!(records.len(), 2);
+    for record in records.iter() {
+        assert_eq!((record.d, record.noisy_measurements, record.shots), (7, 7, 20));
+        assert_eq!((record.partitions, record.threads), (2, 2));
+        assert!(record.solver_profile.is_some());
+    }
+    // the same syndromes give the same total weight in both solvers
+    assert_eq!(records[0].total_weight, records[1].total_weight);
+    assert!(records[0].total_weight > 0);
+}
+
+#[test]
+fn cli_benchmark_seeding_v2_1() {
+    let total_weight = |code_type: &str, code_config: &str| {
+        let output = benchmark(&[
+            "5",
+            "0.03",
+            "-r",
+            "30",
+            "--noisy-measurements",
+            "3",
+            "--code-type",
+            code_type,
+            "--code-config",
+            code_config,
+            "--seeding",
+            "v2",
+            "--output-format",
+            "json",
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+        let records: Vec<BenchmarkRecord> = serde_json::from_slice(&output.stdout).unwrap();
+        records[0].total_weight
+    };
+    // the syndrome pattern of each shot doesn't depend on the number of workers
+    let expected = total_weight("phenomenological-planar-code", "{}");
+    assert!(expected > 0);
+    for code_config in [
+        r#"{"code_count":1}"#,
+        r#"{"code_count":4}"#,
+        r#"{"code_count":3,"prefetch_depth":2}"#,
+    ] {
+        assert_eq!(total_weight("phenomenological-planar-code-parallel", code_config), expected);
+    }
+}
+
+/// run the benchmark with `--compute-logical-error-rate` and read back the json record
+fn logical_error_rate(name: &str, arguments: &[&str]) -> LogicalErrorRate {
+    let output_file = temporary_output_file(name);
+    benchmark(arguments)
+        .args([
+            "--compute-logical-error-rate",
+            "--output-format",
+            "json",
+            "--output-file",
+            &output_file,
+        ])
+        .assert()
+        .success();
+    let records: Vec<BenchmarkRecord> = serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+    assert_eq!(records.len(), 1);
+    records[0].logical_error_rate.unwrap()
+}
+
+#[test]
+fn cli_logical_error_rate_1() {
+    // d=3 repetition code fails with probability 3p^2 - 2p^3 = 0.104 at p = 0.2
+    let result = logical_error_rate(
+        "logical_error_rate_1.json",
+        &["3", "0.2", "-r", "4000", "--code-type", "code-capacity-repetition-code"],
+    );
+    assert_eq!(result.shots, 4000);
+    assert!(result.rate > 0.08 && result.rate < 0.13, "{result:?}");
+    assert!(
+        result.confidence_lower < 0.104 && 0.104 < result.confidence_upper,
+        "{result:?}"
+    );
+    // the text mode prints an extra line
+    let output = benchmark(&[
+        "3",
+        "0.2",
+        "-r",
+        "100",
+        "--code-type",
+        "code-capacity-repetition-code",
+        "--compute-logical-error-rate",
+    ])
+    .assert()
+    .success()
+    .get_output()
+    .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("logical error rate: "), "{stdout}");
+}
+
+#[test]
+fn cli_logical_error_rate_2() {
+    // a parallel solver should give roughly the same logical error rate as the serial one
+    let arguments = ["5", "0.1", "-r", "1000", "--code-type", "code-capacity-planar-code"];
+    let serial = logical_error_rate("logical_error_rate_2_serial.json", &arguments);
+    let mut parallel_arguments = arguments.to_vec();
+    parallel_arguments.extend([
+        "--partition-strategy",
+        "code-capacity-planar-code-vertical-partition-half",
+        "--primal-dual-type",
+        "parallel",
+    ]);
+    let parallel = logical_error_rate("logical_error_rate_2_parallel.json", &parallel_arguments);
+    for result in [&serial, &parallel] {
+        assert!(result.rate > 0.05 && result.rate < 0.25, "{result:?}");
+    }
+    assert!(serial.failures.abs_diff(parallel.failures) <= 20, "{serial:?} {parallel:?}");
+}
+
+#[test]
+fn cli_logical_error_rate_max_failures_1() {
+    let result = logical_error_rate(
+        "logical_error_rate_max_failures_1.json",
+        &[
+            "3",
+            "0.2",
+            "-r",
+            "10000",
+            "--code-type",
+            "code-capacity-repetition-code",
+            "--max-failures",
+            "10",
+        ],
+    );
+    assert_eq!(result.failures, 10);
+    assert!(result.shots < 10000);
+}
+
+#[test]
+fn cli_logical_error_rate_pattern_file_1() {
+    // record the syndromes together with the error edges, then decode them from the file
+    let pattern_file = temporary_output_file("logical_error_rate_patterns.txt");
+    let code_arguments = ["3", "0.2", "-r", "500", "--code-type", "code-capacity-repetition-code"];
+    benchmark(&code_arguments)
+        .args(["--compute-logical-error-rate", "--primal-dual-type", "error-pattern-logger"])
+        .args(["--primal-dual-config", &format!(r#"{{"filename":"{pattern_file}"}}"#)])
+        .assert()
+        .success();
+    let from_file = logical_error_rate(
+        "logical_error_rate_pattern_file_1.json",
+        &[
+            "3",
+            "0.2",
+            "-r",
+            "500",
+            "--code-type",
+            "error-pattern-reader",
+            "--code-config",
+            &format!(r#"{{"filename":"{pattern_file}"}}"#),
+        ],
+    );
+    let direct = logical_error_rate("logical_error_rate_pattern_file_1_direct.json", &code_arguments);
+    assert_eq!(from_file, direct);
+}
+
+fn stim_fixture(filename: &str) -> String {
+    format!("{}/tests/stim/{filename}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn cli_benchmark_stim_1() {
+    // the repetition code fixture has 3 shots whose corrections flip the observable as [0, 1, 0], while the sampled
+    // observables are [0, 1, 1]
+    let stim_arguments = |events: &str| {
+        vec![
+            "--syndrome-format".to_string(),
+            if events.ends_with(".b8") { "stim-b8" } else { "stim-01" }.to_string(),
+            "--syndrome-file".to_string(),
+            stim_fixture(events),
+            "--dem".to_string(),
+            stim_fixture("repetition_10.dem"),
+            "--num-detectors".to_string(),
+            "10".to_string(),
+        ]
+    };
+    for events in ["repetition_10.b8", "repetition_10.01"] {
+        let output_file = temporary_output_file(&format!("benchmark_stim_{events}.json"));
+        benchmark(&["--output-format", "json", "--output-file", &output_file])
+            .args(stim_arguments(events))
+            .assert()
+            .success();
+        let records: Vec<BenchmarkRecord> = serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+        assert_eq!(records.len(), 1);
+        // the total rounds are capped by the shots in the file
+        assert_eq!(records[0].shots, 3);
+        assert_eq!(records[0].total_weight, 5000);
+        assert!(records[0].logical_error_rate.is_none());
+    }
+    let output = benchmark(&["--obs-file", &stim_fixture("repetition_10_obs.b8")])
+        .args(stim_arguments("repetition_10.b8"))
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("logical error rate: 3.333e-1 (1/3)"), "{stdout}");
+    // the number of detectors must match the detector error model
+    let mut arguments = stim_arguments("repetition_10.b8");
+    arguments[7] = "12".to_string();
+    let output = benchmark(&[]).args(arguments).assert().failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("`--num-detectors 12` does not match the 10 detectors of"),
+        "{stderr}"
+    );
+    // the observable flips need the same number of shots
+    let output = benchmark(&["--obs-file", &stim_fixture("repetition_10.b8")])
+        .args(stim_arguments("repetition_10.b8"))
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("has 6 shots but"), "{stderr}");
+}
+
+fn generate_syndromes(arguments: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+    command.args(["generate-syndromes"]).args(arguments);
+    command
+}
+
+#[test]
+fn cli_generate_syndromes_1() {
+    // the output does not depend on the number of threads
+    for (format, extension) in [("json", "txt"), ("bincode", "bin.gz")] {
+        let outputs: Vec<String> = ["1", "4"]
+            .iter()
+            .map(|threads| {
+                let output = temporary_output_file(&format!("syndromes_{threads}.{extension}"));
+                generate_syndromes(&[
+                    "5",
+                    "0.1",
+                    "-r",
+                    "1000",
+                    "--seed",
+                    "42",
+                    "--threads",
+                    threads,
+                    "--format",
+                    format,
+                    "--include-error-edges",
+                    "-o",
+                    &output,
+                ])
+                .assert()
+                .success();
+                output
+            })
+            .collect();
+        assert_eq!(std::fs::read(&outputs[0]).unwrap(), std::fs::read(&outputs[1]).unwrap());
+        // the generated file is readable by the benchmark
+        let result = logical_error_rate(
+            &format!("generate_syndromes_1_{format}.json"),
+            &[
+                "5",
+                "0.1",
+                "-r",
+                "1000",
+                "--code-type",
+                "error-pattern-reader",
+                "--code-config",
+                &format!(r#"{{"filename":"{}"}}"#, outputs[1]),
+            ],
+        );
+        assert_eq!(result.shots, 1000);
+        assert!(result.rate > 0.05 && result.rate < 0.25, "{result:?}");
+    }
+}
+
+#[test]
+fn cli_generate_syndromes_shards_1() {
+    let unsharded = temporary_output_file("shards_unsharded.txt");
+    let sharded = temporary_output_file("shards.txt");
+    for (output, shards) in [(&unsharded, "1"), (&sharded, "4")] {
+        generate_syndromes(&[
+            "5",
+            "0.1",
+            "-r",
+            "102",
+            "--seed",
+            "7",
+            "--threads",
+            "3",
+            "--include-error-edges",
+            "--shards",
+            shards,
+            "-o",
+            output,
+        ])
+        .assert()
+        .success();
+    }
+    let expected = SyndromeFile::load(&unsharded).unwrap();
+    let pattern = temporary_output_file("shards.*.txt");
+    let read_all = |policy: ShardPolicy| {
+        let mut reader = ShardedErrorPatternReader::new(std::slice::from_ref(&pattern), policy).unwrap();
+        assert_eq!(reader.shards.len(), 4);
+        assert_eq!(reader.get_initializer(), expected.initializer);
+        let mut records = vec![];
+        while let Ok((syndrome_pattern, error_edges)) = reader.next_syndrome_pattern() {
+            records.push(serde_json::json!([syndrome_pattern, error_edges]));
+        }
+        let error = reader.next_syndrome_pattern().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        records
+    };
+    let expected: Vec<_> = (0..102)
+        .map(|shot| serde_json::json!([expected.syndrome_patterns[shot], expected.error_edges[shot]]))
+        .collect();
+    // round-robin restores the unsharded order even though the last shards are one shorter
+    assert_eq!(read_all(ShardPolicy::RoundRobin), expected);
+    // shard-by-shard reads shot `i` of shard `s`, i.e. the original shot `4 i + s`, in order
+    let shard_by_shard_order: Vec<_> = (0..4)
+        .flat_map(|shard| (shard..102).step_by(4))
+        .map(|shot| expected[shot].clone())
+        .collect();
+    assert_eq!(read_all(ShardPolicy::ShardByShard), shard_by_shard_order);
+    // a shard of a different decoding graph is rejected with its name
+    let different = temporary_output_file("shards_different.txt");
+    generate_syndromes(&["3", "0.1", "-r", "10", "-o", &different])
+        .assert()
+        .success();
+    let error = ShardedErrorPatternReader::new(&[shard_filename(&sharded, 0), different.clone()], ShardPolicy::RoundRobin)
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains(&different), "{error}");
+}
+
+fn convert_syndromes(arguments: &[&str]) -> Command {
+    let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+    command.args(["convert-syndromes"]).args(arguments);
+    command
+}
+
+/// the parsed content of a syndrome pattern file, comparable regardless of the file format
+fn syndrome_file_content(filename: &str) -> serde_json::Value {
+    serde_json::to_value(SyndromeFile::load(filename).unwrap()).unwrap()
+}
+
+#[test]
+fn cli_convert_syndromes_1() {
+    // v1 json lines -> v2 bincode -> v1 json lines
+    let original = temporary_output_file("convert_original.txt");
+    generate_syndromes(&["5", "0.1", "-r", "300", "--include-error-edges", "-o", &original])
+        .assert()
+        .success();
+    let bincode = temporary_output_file("convert_bincode.bin.gz");
+    convert_syndromes(&[&original, &bincode, "--format", "bincode"])
+        .assert()
+        .success();
+    let converted = temporary_output_file("convert_json.txt");
+    convert_syndromes(&[&bincode, &converted]).assert().success();
+    assert_eq!(syndrome_file_content(&original), syndrome_file_content(&bincode));
+    assert_eq!(syndrome_file_content(&original), syndrome_file_content(&converted));
+    assert_eq!(std::fs::read(&original).unwrap(), std::fs::read(&converted).unwrap());
+    // stripping the error edges keeps everything else
+    let stripped = temporary_output_file("convert_stripped.txt");
+    convert_syndromes(&[&bincode, &stripped, "--strip-errors"]).assert().success();
+    let original = SyndromeFile::load(&original).unwrap();
+    let stripped = SyndromeFile::load(&stripped).unwrap();
+    assert!(stripped.error_edges.iter().all(|error_edges| error_edges.is_none()));
+    assert_eq!(
+        serde_json::to_value(&original.syndrome_patterns).unwrap(),
+        serde_json::to_value(&stripped.syndrome_patterns).unwrap()
+    );
+    assert_eq!(original.initializer, stripped.initializer);
+}
+
+#[test]
+fn cli_convert_syndromes_2() {
+    let original = temporary_output_file("convert_range_original.bin");
+    generate_syndromes(&[
+        "3",
+        "0.1",
+        "-r",
+        "100",
+        "--format",
+        "bincode",
+        "--include-error-edges",
+        "-o",
+        &original,
+    ])
+    .assert()
+    .success();
+    let original_content = syndrome_file_content(&original);
+    let shots = |content: &serde_json::Value| content["syndrome_patterns"].as_array().unwrap().clone();
+    let original_shots = shots(&original_content);
+    assert_eq!(original_shots.len(), 100);
+    // (skip, head, expected range of the original shots)
+    for (skip, head, expected) in [
+        (None, Some("10"), 0..10),
+        (Some("30"), None, 30..100),
+        (Some("30"), Some("50"), 30..80),
+        (Some("90"), Some("50"), 90..100),
+        (Some("100"), Some("5"), 100..100),
+        (Some("200"), None, 100..100),
+        (None, Some("0"), 0..0),
+    ] {
+        let converted = temporary_output_file("convert_range.txt");
+        let mut arguments = vec![original.as_str(), converted.as_str()];
+        if let Some(skip) = skip {
+            arguments.extend(["--skip", skip]);
+        }
+        if let Some(head) = head {
+            arguments.extend(["--head", head]);
+        }
+        convert_syndromes(&arguments).assert().success();
+        let content = syndrome_file_content(&converted);
+        assert_eq!(content["initializer"], original_content["initializer"]);
+        assert_eq!(content["positions"], original_content["positions"]);
+        assert_eq!(
+            shots(&content),
+            original_shots[expected.clone()],
+            "skip {skip:?}, head {head:?}"
+        );
+        assert_eq!(
+            content["error_edges"].as_array().unwrap()[..],
+            original_content["error_edges"].as_array().unwrap()[expected]
+        );
+    }
+}
+
+#[test]
+fn cli_inspect_syndromes_1() {
+    let filename = temporary_output_file("inspect.bin.gz");
+    generate_syndromes(&[
+        "5",
+        "0.02",
+        "-c",
+        "phenomenological-planar-code",
+        "-n",
+        "3",
+        "-r",
+        "500",
+        "--format",
+        "bincode",
+        "-o",
+        &filename,
+    ])
+    .assert()
+    .success();
+    let inspect = |output_format: &str| {
+        let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+        let output = command
+            .args(["inspect-syndromes", &filename, "--output-format", output_format])
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let statistics: DatasetStatistics = serde_json::from_str(&inspect("json")).unwrap();
+    let mut reader = ErrorPatternReader::new(serde_json::json!({ "filename": filename }));
+    let positions = reader.get_positions();
+    assert_eq!(statistics, dataset_statistics(&mut reader, Some(&positions)));
+    assert_eq!(statistics.shots, 500);
+    assert_eq!(statistics.layers.len(), 4);
+    let text = inspect("text");
+    assert_eq!(text, statistics.brief());
+    assert!(text.starts_with("shots: 500, "), "{text}");
+}
+
+#[test]
+fn cli_compare_1() {
+    let syndrome_file = temporary_output_file("compare_syndromes.txt");
+    generate_syndromes(&["5", "0.1", "-r", "200", "-o", &syndrome_file])
+        .assert()
+        .success();
+    let dump_folder = temporary_output_file("compare_dump");
+    let _ = std::fs::remove_dir_all(&dump_folder);
+    let compare = || {
+        let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+        command.args([
+            "compare",
+            &syndrome_file,
+            "-d",
+            "5",
+            "--partition-strategy",
+            "code-capacity-planar-code-vertical-partition-half",
+            "--dump-folder",
+            &dump_folder,
+        ]);
+        command
+    };
+    let output = compare().assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("shots: 200, mismatches: 0"), "{stdout}");
+    assert!(!std::path::Path::new(&dump_folder).exists());
+    // corrupt the matching of a shot with defects, which must be dumped
+    let content = std::fs::read_to_string(&syndrome_file).unwrap();
+    let shot = content
+        .lines()
+        .skip(3)
+        .position(|line| !line.contains(r#""defect_vertices":[]"#))
+        .unwrap();
+    let output = compare()
+        .args(["--corrupt-shot", &shot.to_string()])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("shots: 200, mismatches: 1"), "{stdout}");
+    let shot_folder = format!("{dump_folder}/shot_{shot}");
+    for name in ["initializer", "syndrome_pattern", "matching_0_serial", "matching_1_parallel"] {
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{shot_folder}/{name}.json")).unwrap()).unwrap();
+        assert!(value.is_object(), "{name}");
+    }
+    let corrupted: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(format!("{shot_folder}/matching_1_parallel.json")).unwrap()).unwrap();
+    assert_eq!(corrupted["is_perfect"], false);
+}
+
+#[test]
+fn cli_compare_stim_1() {
+    let output = Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .args(["compare", &stim_fixture("repetition_10.b8"), "--syndrome-format", "stim-b8"])
+        .args(["--dem", &stim_fixture("repetition_10.dem")])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("shots: 3, mismatches: 0"), "{stdout}");
+}
+
+#[test]
+fn cli_benchmark_warmup_1() {
+    let output = benchmark(&["5", "0.05", "-r", "200", "--warmup", "50", "--output-format", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let records: Vec<BenchmarkRecord> = serde_json::from_slice(&output.stdout).unwrap();
+    let record = &records[0];
+    assert_eq!(record.shots, 150, "warmup rounds are excluded");
+    assert!(record.p50_latency <= record.p90_latency && record.p90_latency <= record.p99_latency);
+    assert!(record.p99_latency <= record.p999_latency && record.p999_latency <= record.max_latency);
+}
+
+#[test]
+fn cli_benchmark_sweep_1() {
+    let sweep = || {
+        let output = Command::cargo_bin("fusion_blossom")
+            .unwrap()
+            .env("DISABLE_PROGRESS_BAR", "1")
+            .args([
+                "benchmark",
+                "--verifier",
+                "none",
+                "-r",
+                "20",
+                "--code-type",
+                "phenomenological-planar-code",
+                "--d-list",
+                "3,5",
+                "--p-list",
+                "0.01,0.03",
+                "--noisy-measurements-list",
+                "1,2",
+                "--output-format",
+                "json",
+            ])
+            .assert()
+            .success()
+            .get_output()
+            .clone();
+        serde_json::from_slice::<Vec<BenchmarkRecord>>(&output.stdout).unwrap()
+    };
+    let records = sweep();
+    assert_eq!(records.len(), 2 * 2 * 2, "one record per combination");
+    let mut expected = vec![];
+    for d in [3, 5] {
+        for p in [0.01, 0.03] {
+            for noisy_measurements in [1, 2] {
+                expected.push((d, p, noisy_measurements));
+            }
+        }
+    }
+    for (record, (d, p, noisy_measurements)) in records.iter().zip(expected) {
+        assert_eq!((record.d, record.p, record.noisy_measurements), (d, p, noisy_measurements));
+        assert_eq!(record.shots, 20);
+    }
+    // the seeds are derived from each combination, so a rerun decodes the same syndromes
+    let rerun = sweep();
+    for (record, rerun_record) in records.iter().zip(rerun.iter()) {
+        assert_eq!(record.total_weight, rerun_record.total_weight);
+    }
+}
+
+#[test]
+fn cli_threshold_1() {
+    // the threshold of the code capacity planar code under minimum-weight perfect matching is about 10%
+    let points_file = temporary_output_file("threshold.csv");
+    let output = Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .env("DISABLE_PROGRESS_BAR", "1")
+        .args([
+            "threshold",
+            "--verifier",
+            "none",
+            "-r",
+            "2000",
+            "--d-list",
+            "3,5,7",
+            "--p-list",
+            "0.07,0.085,0.1,0.115,0.13",
+            "--bootstrap-samples",
+            "100",
+            "--output-format",
+            "csv",
+            "--output-file",
+            &points_file,
+        ])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let field = |name: &str| -> f64 {
+        let start = stdout.find(name).unwrap_or_else(|| panic!("missing {name} in {stdout}")) + name.len();
+        stdout[start..].split([',', ']']).next().unwrap().parse().unwrap()
+    };
+    let threshold = field("threshold: ");
+    let confidence_lower = field("confidence interval: [");
+    assert!((0.08..0.12).contains(&threshold), "{stdout}");
+    assert!(confidence_lower <= threshold, "{stdout}");
+    let content = std::fs::read_to_string(&points_file).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 1 + 3 * 5, "one header and a row per point");
+    assert!(lines[0].ends_with(LogicalErrorRate::CSV_HEADER));
+}
+
+#[test]
+fn cli_benchmark_progress_callback_1() {
+    let mut parameters = BenchmarkParameters::parse_from([
+        "benchmark",
+        "--verifier",
+        "none",
+        "--quiet",
+        "-r",
+        "20",
+        "--d-list",
+        "3,5",
+        "--p-list",
+        "0.05",
+        "--output-format",
+        "json",
+    ]);
+    let events = Arc::new(Mutex::new(vec![]));
+    let recorded_events = events.clone();
+    parameters.set_progress_callback(Box::new(move |event: ProgressEvent| {
+        recorded_events.lock().unwrap().push(event)
+    }));
+    let records = parameters.run();
+    assert_eq!(records.len(), 2);
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2 * 20, "one event per round");
+    for (index, event) in events.iter().enumerate() {
+        assert_eq!((event.completed, event.total), (index % 20 + 1, 20));
+        let d = if index < 20 { 3 } else { 5 };
+        assert_eq!(event.current_config, format!("d: {d}, p: 0.05, noisy_measurements: 0"));
+    }
+}
+
+#[test]
+fn cli_benchmark_report_memory_1() {
+    let output = benchmark(&["5", "0.05", "-r", "20", "--report-memory", "--output-format", "json"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let records: Vec<BenchmarkRecord> = serde_json::from_slice(&output.stdout).unwrap();
+    let memory_usage = records[0].memory_usage.unwrap();
+    assert!(memory_usage.estimate.unwrap() > 0);
+    if cfg!(target_os = "linux") {
+        assert!(memory_usage.peak_resident_delta.is_some());
+    }
+}
+
+#[test]
+fn cli_benchmark_dry_run_1() {
+    let output = benchmark(&["--d-list", "3,5", "--p-list", "0.05", "-r", "20", "--dry-run"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "one estimate per combination: {stdout}");
+    assert!(lines[0].starts_with("d: 3, p: 0.05"), "{stdout}");
+}
+
+/// the results that do not depend on timing, which must be the same with or without interruption
+fn deterministic_results(records: &[BenchmarkRecord]) -> Vec<(VertexNum, usize, Weight, Option<LogicalErrorRate>)> {
+    records
+        .iter()
+        .map(|record| (record.d, record.shots, record.total_weight, record.logical_error_rate))
+        .collect()
+}
+
+const CHECKPOINT_BENCHMARK: [&str; 13] = [
+    "benchmark",
+    "--verifier",
+    "none",
+    "-r",
+    "3000",
+    "--d-list",
+    "5,7",
+    "--p-list",
+    "0.08",
+    "--compute-logical-error-rate",
+    "--output-format",
+    "json",
+    "--checkpoint-interval=200",
+];
+
+fn spawn_checkpoint_benchmark(checkpoint_file: &str, output_file: &str) -> std::process::Child {
+    std::process::Command::new(assert_cmd::cargo::cargo_bin("fusion_blossom"))
+        .env("DISABLE_PROGRESS_BAR", "1")
+        .args(CHECKPOINT_BENCHMARK)
+        .args(["--checkpoint-file", checkpoint_file, "--output-file", output_file])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap()
+}
+
+/// wait until the checkpoint file shows the given number of started combinations with some progress in the last one
+fn wait_for_checkpoint(child: &mut std::process::Child, checkpoint_file: &str, combinations: usize) {
+    loop {
+        assert!(
+            child.try_wait().unwrap().is_none(),
+            "the benchmark finished before being interrupted"
+        );
+        if let Ok(content) = std::fs::read_to_string(checkpoint_file) {
+            let checkpoint: serde_json::Value = serde_json::from_str(&content).unwrap();
+            let started = checkpoint["combinations"].as_array().unwrap();
+            if started.len() >= combinations && started.last().unwrap()["next_round"].as_u64().unwrap() > 0 {
+                return;
+            }
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+fn checkpoint_benchmark_reference(name: &str) -> Vec<BenchmarkRecord> {
+    let output = Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .env("DISABLE_PROGRESS_BAR", "1")
+        .args(CHECKPOINT_BENCHMARK)
+        .args(["--checkpoint-file", &temporary_output_file(name)])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn cli_benchmark_checkpoint_1() {
+    let reference = checkpoint_benchmark_reference("checkpoint_1_reference.json");
+    assert_eq!(reference.len(), 2);
+    // killed in the middle of the second combination, without the chance to save a checkpoint
+    let checkpoint_file = temporary_output_file("checkpoint_1.json");
+    let output_file = temporary_output_file("checkpoint_1_output.json");
+    let mut child = spawn_checkpoint_benchmark(&checkpoint_file, &output_file);
+    wait_for_checkpoint(&mut child, &checkpoint_file, 2);
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(
+        std::fs::metadata(&output_file).is_err(),
+        "records are written only when finished"
+    );
+    // a benchmark with different parameters refuses to resume
+    Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .env("DISABLE_PROGRESS_BAR", "1")
+        .args(CHECKPOINT_BENCHMARK)
+        .args(["--max-failures", "10", "--checkpoint-file", &checkpoint_file])
+        .assert()
+        .failure();
+    let mut child = spawn_checkpoint_benchmark(&checkpoint_file, &output_file);
+    assert!(child.wait().unwrap().success());
+    assert!(
+        std::fs::metadata(&checkpoint_file).is_err(),
+        "the checkpoint is removed once finished"
+    );
+    let resumed: Vec<BenchmarkRecord> = serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+    assert_eq!(deterministic_results(&reference), deterministic_results(&resumed));
+}
+
+#[cfg(unix)]
+#[test]
+fn cli_benchmark_checkpoint_2() {
+    let reference = checkpoint_benchmark_reference("checkpoint_2_reference.json");
+    // interrupted by SIGINT in the first combination, which saves the progress right away
+    let checkpoint_file = temporary_output_file("checkpoint_2.json");
+    let output_file = temporary_output_file("checkpoint_2_output.json");
+    let mut child = spawn_checkpoint_benchmark(&checkpoint_file, &output_file);
+    wait_for_checkpoint(&mut child, &checkpoint_file, 1);
+    let status = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(child.wait().unwrap().code(), Some(130));
+    assert!(std::fs::metadata(&checkpoint_file).is_ok());
+    let mut child = spawn_checkpoint_benchmark(&checkpoint_file, &output_file);
+    assert!(child.wait().unwrap().success());
+    let resumed: Vec<BenchmarkRecord> = serde_json::from_str(&std::fs::read_to_string(&output_file).unwrap()).unwrap();
+    assert_eq!(deterministic_results(&reference), deterministic_results(&resumed));
+}
+
+#[test]
+fn cli_serve_1() {
+    let code = fusion_blossom::example_codes::CodeCapacityPlanarCode::new(5, 0.1, 500);
+    let initializer = fusion_blossom::example_codes::ExampleCode::get_initializer(&code);
+    let messages = [
+        serde_json::json!({ "initializer": initializer }).to_string(),
+        r#"{"id":1,"syndrome":{"defect_vertices":[7,14]}}"#.to_string(),
+        r#"{"id":"malformed","syndrome":{"defect_vertices":[1000]}}"#.to_string(),
+        r#"{"id":3,"syndrome":{"defect_vertices":[]}}"#.to_string(),
+        "not json".to_string(),
+        r#"{"cmd":"shutdown"}"#.to_string(),
+        r#"{"id":4,"syndrome":{"defect_vertices":[]}}"#.to_string(),
+    ];
+    let output = Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .arg("serve")
+        .write_stdin(messages.join("\n") + "\n")
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 5, "nothing is answered after shutdown: {responses:?}");
+    assert_eq!(responses[0]["ready"], true);
+    assert_eq!(responses[0]["edge_num"], initializer.weighted_edges.len());
+    assert_eq!(responses[1]["id"], 1);
+    assert_eq!(responses[1]["subgraph"], serde_json::json!([15, 19]));
+    let weight: Weight = [15, 19]
+        .iter()
+        .map(|&edge_index| initializer.weighted_edges[edge_index].2)
+        .sum();
+    assert_eq!(responses[1]["weight"], weight);
+    assert!(responses[1]["time"].as_f64().unwrap() >= 0.);
+    assert_eq!(responses[2]["id"], "malformed");
+    assert!(
+        responses[2]["error"].as_str().unwrap().contains("out of range"),
+        "{}",
+        responses[2]
+    );
+    assert_eq!(responses[3]["id"], 3);
+    assert_eq!(responses[3]["subgraph"], serde_json::json!([]));
+    assert_eq!(responses[3]["weight"], 0);
+    assert!(responses[4]["id"].is_null());
+    assert!(responses[4]["error"].as_str().unwrap().starts_with("invalid json"));
+}
+
+#[test]
+fn cli_replay_dump_1() {
+    let mut code = CodeCapacityPlanarCode::new(7, 0.1, 500);
+    let initializer = code.get_initializer();
+    let syndrome_pattern = code.generate_random_errors(1);
+    let dump = SolverDebugDump {
+        version: "0.0.0".to_string(),
+        solver: "parallel".to_string(),
+        initializer: initializer.clone(),
+        syndrome_pattern: syndrome_pattern.clone(),
+        partition_config: Some(PartitionConfig::new(initializer.vertex_num)),
+        primal_dual_config: serde_json::json!({}),
+    };
+    let dump_file = temporary_output_file("replay_dump.json");
+    std::fs::write(&dump_file, serde_json::to_string(&dump).unwrap()).unwrap();
+    let mut solver = SolverSerial::new(&initializer);
+    solver.solve(&syndrome_pattern);
+    let subgraph = solver.subgraph();
+    let output = Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .args(["replay-dump", &dump_file])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("fusion blossom 0.0.0 with the parallel solver"), "{stdout}");
+    assert!(stdout.contains(&format!("subgraph: {subgraph:?}")), "{stdout}");
+    // an unknown solver is rejected instead of replayed with a different one
+    let mut dump = dump;
+    dump.solver = "unknown".to_string();
+    std::fs::write(&dump_file, serde_json::to_string(&dump).unwrap()).unwrap();
+    Command::cargo_bin("fusion_blossom")
+        .unwrap()
+        .args(["replay-dump", &dump_file])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_replay_visualization_1() {
+    let visualize_path = visualize_data_folder() + static_visualize_data_filename().as_str();
+    let replay = |visualize_path: &str| {
+        let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+        command.args(["replay-visualization", visualize_path]);
+        command
+    };
+    for arguments in [
+        vec!["5", "0.1", "-r", "1"],
+        vec![
+            "5",
+            "0.05",
+            "-r",
+            "1",
+            "--code-type",
+            "phenomenological-planar-code",
+            "--noisy-measurements",
+            "5",
+            "--partition-strategy",
+            "phenomenological-planar-code-time-partition",
+            "--partition-config",
+            r#"{"partition_num":2}"#,
+            "--primal-dual-type",
+            "parallel",
+        ],
+    ] {
+        benchmark(&[arguments.as_slice(), &["--enable-visualizer"]].concat())
+            .assert()
+            .success();
+        let file = VisualizeFile::load(&visualize_path).unwrap();
+        assert!(file.initializer().is_some());
+        assert_eq!(file.partition_config().is_some(), arguments.contains(&"parallel"));
+        let output = replay(&visualize_path).assert().success().get_output().clone();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let weight = file.recorded_sum_dual_variables().unwrap();
+        assert!(stdout.contains(&format!("weight: {weight}")), "{stdout}");
+    }
+    // a recorded weight that differs from the decoded one is reported
+    let mut file = VisualizeFile::load(&visualize_path).unwrap();
+    let (_, last_snapshot) = file.snapshots.last_mut().unwrap();
+    *last_snapshot["interface"].get_mut("d").unwrap() = serde_json::json!(-1);
+    let corrupted_path = temporary_output_file("replay_visualization_corrupted.json");
+    file.save(&corrupted_path).unwrap();
+    replay(&corrupted_path).assert().failure();
+    // a file without the initializer cannot be replayed
+    file.initializer = None;
+    file.save(&corrupted_path).unwrap();
+    replay(&corrupted_path).assert().failure();
+}
+
+#[test]
+fn cli_diff_initializers_1() {
+    let write_initializer = |name: &str, initializer: &SolverInitializer| {
+        let path = temporary_output_file(name);
+        std::fs::write(&path, serde_json::to_string(initializer).unwrap()).unwrap();
+        path
+    };
+    let diff = |first: &str, second: &str| {
+        let mut command = Command::cargo_bin("fusion_blossom").unwrap();
+        command.args(["diff-initializers", first, second]);
+        command
+    };
+    let initializer = CodeCapacityPlanarCode::new(5, 0.1, 500).get_initializer();
+    let first = write_initializer("diff_first.json", &initializer);
+    let mut reversed = initializer.clone();
+    reversed.weighted_edges.reverse();
+    let second = write_initializer("diff_second.json", &reversed);
+    let output = diff(&first, &second).assert().success().get_output().clone();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "no difference\n");
+    let mut modified = initializer.clone();
+    let (vertex_1, vertex_2, weight) = modified.weighted_edges.pop().unwrap();
+    let second = write_initializer("diff_second.json", &modified);
+    let output = diff(&first, &second).assert().code(1).get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (vertex_1, vertex_2) = (vertex_1.min(vertex_2), vertex_1.max(vertex_2));
+    assert_eq!(stdout, format!("- edge ({vertex_1}, {vertex_2}) weight {weight}\n"));
+}
@@ -0,0 +1,69 @@
+//! streaming a large syndrome pattern file must not grow the memory, which is checked by counting the allocated bytes;
+//! this file has a single test so that no other test allocates concurrently
+#![cfg(feature = "full")]
+
+use fusion_blossom::example_codes::*;
+use serde_json::json;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SHOT_NUM: u64 = 100_000;
+
+#[test]
+fn error_pattern_reader_streaming_memory() {
+    let filename = std::env::temp_dir().join(format!("fusion_blossom_streaming_{}.syndromes", std::process::id()));
+    let filename = filename.to_str().unwrap().to_string();
+    let mut code = CodeCapacityPlanarCode::new(7, 0.05, 500);
+    let mut writer =
+        SyndromeFileWriter::create(&filename, &code.get_initializer(), &code.get_positions(), false, false).unwrap();
+    for seed in 0..SHOT_NUM {
+        writer.write(&code.generate_random_errors(seed), None).unwrap();
+    }
+    writer.finish().unwrap().finish().unwrap();
+    let file_size = std::fs::metadata(&filename).unwrap().len() as usize;
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    let mut peak = baseline;
+    let mut reader = ErrorPatternReader::new(json!({ "filename": filename, "read_ahead": 100 }));
+    for seed in 0..SHOT_NUM {
+        assert_eq!(reader.generate_random_errors(0), code.generate_random_errors(seed));
+        peak = peak.max(ALLOCATED.load(Ordering::Relaxed));
+    }
+    assert!(
+        peak - baseline < file_size / 20,
+        "memory grows by {} bytes while streaming a file of {file_size} bytes",
+        peak - baseline
+    );
+    // reading past the end is an error with the counts
+    let error = reader.next_syndrome_pattern().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    let message = error.to_string();
+    assert!(
+        message.contains("reading syndrome pattern 100001") && message.contains("has only 100000"),
+        "{message}"
+    );
+    assert_eq!(reader.syndrome_pattern_num, Some(SHOT_NUM as usize));
+    std::fs::remove_file(&filename).unwrap();
+}
@@ -0,0 +1,55 @@
+//! compile and run the C smoke test in `tests/ffi` against the cdylib and the header generated by `build.rs`
+#![cfg(all(feature = "ffi", unix))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `cargo test` builds the cdylib next to this test binary in `target/<profile>/deps`, without copying it to
+/// `target/<profile>` like `cargo build` does
+fn library_dir() -> PathBuf {
+    let test_binary = std::env::current_exe().unwrap();
+    test_binary.parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn ffi_repetition_code() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let out_dir = std::env::temp_dir().join(format!("fusion-blossom-ffi-{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let executable = out_dir.join("repetition_code");
+    let target = env!("FUSION_BLOSSOM_TARGET");
+    let compiler = cc::Build::new()
+        .cargo_metadata(false)
+        .target(target)
+        .host(target)
+        .opt_level(0)
+        .out_dir(&out_dir)
+        .get_compiler();
+    let status = compiler
+        .to_command()
+        .arg(crate_dir.join("tests/ffi/repetition_code.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-lfusion_blossom")
+        .arg("-o")
+        .arg(&executable)
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to compile the C smoke test");
+    // `cargo test` puts `target/<profile>` first in the library path, which may hold an outdated cdylib
+    let output = Command::new(&executable)
+        .env("LD_LIBRARY_PATH", &library_dir)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&out_dir).unwrap();
+    assert!(
+        output.status.success(),
+        "C smoke test failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+}
@@ -0,0 +1,53 @@
+/* decode a syndrome of the distance-5 repetition code through the C API, see `tests/ffi.rs` */
+#include <stdio.h>
+#include "fusion_blossom.h"
+
+#define CHECK(condition)                                                                  \
+    if (!(condition)) {                                                                   \
+        const char *message = fb_last_error_message();                                    \
+        fprintf(stderr, "line %d: %s failed: %s\n", __LINE__, #condition,                 \
+                message ? message : "no error message");                                  \
+        return 1;                                                                         \
+    }
+
+int main(void) {
+    /* 0 - 1 - 2 - 3 - 4 - 5, where the vertices 0 and 5 are virtual */
+    FbWeightedEdge edges[5];
+    for (size_t i = 0; i < 5; ++i) {
+        edges[i].vertex_1 = i;
+        edges[i].vertex_2 = i + 1;
+        edges[i].weight = 100;
+    }
+    size_t virtual_vertices[2] = {0, 5};
+    FbInitializer *initializer = fb_initializer_new(6, edges, 5, virtual_vertices, 2);
+    CHECK(initializer != NULL);
+    FbSolver *solver = fb_solver_new(initializer);
+    CHECK(solver != NULL);
+    fb_initializer_free(initializer);
+
+    /* data qubits 1 and 2 flipped */
+    size_t defects[2] = {1, 3};
+    CHECK(fb_solver_solve(solver, defects, 2, NULL, 0) == FB_ERROR_CODE_OK);
+    size_t subgraph[5];
+    CHECK(fb_solver_subgraph(solver, subgraph, 5) == 2);
+    CHECK(subgraph[0] == 1 && subgraph[1] == 2);
+    CHECK(fb_solver_clear(solver) == FB_ERROR_CODE_OK);
+
+    /* erasing the boundary edges makes it cheaper to match both defects to the boundary */
+    size_t erasures[2] = {0, 4};
+    size_t defects_2[2] = {1, 4};
+    CHECK(fb_solver_solve(solver, defects_2, 2, erasures, 2) == FB_ERROR_CODE_OK);
+    CHECK(fb_solver_subgraph(solver, subgraph, 5) == 2);
+    CHECK(subgraph[0] == 0 && subgraph[1] == 4);
+    CHECK(fb_solver_clear(solver) == FB_ERROR_CODE_OK);
+
+    /* errors are reported instead of crashing */
+    size_t invalid_defects[1] = {6};
+    CHECK(fb_solver_solve(solver, invalid_defects, 1, NULL, 0) == FB_ERROR_CODE_INVALID_ARGUMENT);
+    CHECK(fb_last_error_code() == FB_ERROR_CODE_INVALID_ARGUMENT);
+    CHECK(fb_last_error_message() != NULL);
+
+    fb_solver_free(solver);
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,207 @@
+//! compare the decoders with the brute-force [`ExactMatcher`] on random small graphs and syndromes;
+//! run more cases with e.g. `FUSION_BLOSSOM_FUZZ_CASES=100000 cargo test --release --test fuzz_exact_matching`.
+//! A failing case is shrunk to a minimal graph and written to `tmp/fuzz_exact_matching_reproducer.json`, which can be
+//! replayed with `fusion_blossom replay-dump`
+#![cfg(feature = "full")]
+
+use fusion_blossom::exact_matcher::*;
+use fusion_blossom::example_codes::{CodeCapacityPlanarCode, ExampleCode};
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverDebugDump, SolverParallel, SolverSerial};
+use fusion_blossom::util::*;
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+
+const REPRODUCER_FILE: &str = "tmp/fuzz_exact_matching_reproducer.json";
+
+fn fuzz_config() -> ProptestConfig {
+    let cases = std::env::var("FUSION_BLOSSOM_FUZZ_CASES")
+        .map(|cases| cases.parse().expect("FUSION_BLOSSOM_FUZZ_CASES must be a number"))
+        .unwrap_or(64);
+    ProptestConfig {
+        cases,
+        // the minimal failing case is written to the reproducer file instead
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    }
+}
+
+/// the defects are the non-virtual vertices touched by an odd number of error edges, so that a perfect matching
+/// always exists
+#[allow(clippy::unnecessary_cast)]
+fn syndrome_of_errors(
+    initializer: &SolverInitializer,
+    error_edges: &BTreeSet<EdgeIndex>,
+    erasures: &BTreeSet<EdgeIndex>,
+) -> SyndromePattern {
+    let mut parity = vec![false; initializer.vertex_num as usize];
+    for &edge_index in error_edges.iter() {
+        let (vertex_1, vertex_2, _) = initializer.weighted_edges[edge_index as usize];
+        parity[vertex_1 as usize] ^= true;
+        parity[vertex_2 as usize] ^= true;
+    }
+    for &virtual_vertex in initializer.virtual_vertices.iter() {
+        parity[virtual_vertex as usize] = false;
+    }
+    let defect_vertices = (0..parity.len())
+        .filter(|&vertex_index| parity[vertex_index])
+        .map(|vertex_index| vertex_index as VertexIndex)
+        .collect();
+    SyndromePattern::new(defect_vertices, erasures.iter().cloned().collect())
+}
+
+/// a random simple graph with random virtual vertices; each raw edge is `(vertex_1, vertex_2, half_weight, is_error,
+/// is_erased)`, and self loops, duplicated edges and edges between virtual vertices are dropped
+#[allow(clippy::type_complexity)]
+fn random_graph() -> impl Strategy<Value = (SolverInitializer, SyndromePattern)> {
+    (2..=10usize)
+        .prop_flat_map(|vertex_num| {
+            (
+                prop::collection::vec(any::<bool>(), vertex_num),
+                prop::collection::vec(
+                    (
+                        0..vertex_num,
+                        0..vertex_num,
+                        1..=10 as Weight,
+                        any::<bool>(),
+                        prop::bool::weighted(0.1),
+                    ),
+                    1..=2 * vertex_num,
+                ),
+            )
+        })
+        .prop_map(|(is_virtual, raw_edges)| {
+            let mut weighted_edges = vec![];
+            let mut error_edges = BTreeSet::new();
+            let mut erasures = BTreeSet::new();
+            let mut existing = BTreeSet::new();
+            for (vertex_1, vertex_2, half_weight, is_error, is_erased) in raw_edges {
+                let key = (vertex_1.min(vertex_2), vertex_1.max(vertex_2));
+                if vertex_1 == vertex_2 || (is_virtual[vertex_1] && is_virtual[vertex_2]) || !existing.insert(key) {
+                    continue;
+                }
+                let edge_index = weighted_edges.len() as EdgeIndex;
+                weighted_edges.push((vertex_1 as VertexIndex, vertex_2 as VertexIndex, 2 * half_weight));
+                if is_error {
+                    error_edges.insert(edge_index);
+                }
+                if is_erased {
+                    erasures.insert(edge_index);
+                }
+            }
+            let virtual_vertices = (0..is_virtual.len())
+                .filter(|&vertex_index| is_virtual[vertex_index])
+                .map(|vertex_index| vertex_index as VertexIndex)
+                .collect();
+            let initializer = SolverInitializer::new(is_virtual.len() as VertexNum, weighted_edges, virtual_vertices);
+            let syndrome_pattern = syndrome_of_errors(&initializer, &error_edges, &erasures);
+            (initializer, syndrome_pattern)
+        })
+}
+
+/// a small planar code with random edge weights, a few errors and a few erasures
+#[allow(clippy::unnecessary_cast)]
+fn random_planar_patch() -> impl Strategy<Value = (SolverInitializer, SyndromePattern)> {
+    prop_oneof![Just(3 as VertexNum), Just(5 as VertexNum)]
+        .prop_flat_map(|d| {
+            let initializer = CodeCapacityPlanarCode::new(d, 0.1, 10).get_initializer();
+            let edge_num = initializer.weighted_edges.len();
+            (
+                Just(initializer),
+                prop::collection::vec(1..=10 as Weight, edge_num),
+                // at most 2 defects per error edge keeps the exact matcher fast
+                prop::collection::btree_set(0..edge_num as EdgeIndex, 0..=MAX_DEFECT_NUM / 2),
+                prop::collection::btree_set(0..edge_num as EdgeIndex, 0..=4),
+            )
+        })
+        .prop_map(|(mut initializer, half_weights, error_edges, erasures)| {
+            for (edge, half_weight) in initializer.weighted_edges.iter_mut().zip(half_weights) {
+                edge.2 = 2 * half_weight;
+            }
+            let syndrome_pattern = syndrome_of_errors(&initializer, &error_edges, &erasures);
+            (initializer, syndrome_pattern)
+        })
+}
+
+/// the weight of the subgraph, which must also produce the syndrome
+#[allow(clippy::unnecessary_cast)]
+fn subgraph_weight(initializer: &SolverInitializer, syndrome_pattern: &SyndromePattern, subgraph: &[EdgeIndex]) -> Weight {
+    let mut parity = vec![false; initializer.vertex_num as usize];
+    let mut weight = 0;
+    for &edge_index in subgraph.iter() {
+        let (vertex_1, vertex_2, edge_weight) = initializer.weighted_edges[edge_index as usize];
+        parity[vertex_1 as usize] ^= true;
+        parity[vertex_2 as usize] ^= true;
+        if !syndrome_pattern.erasures.contains(&edge_index) {
+            weight += edge_weight;
+        }
+    }
+    for &virtual_vertex in initializer.virtual_vertices.iter() {
+        parity[virtual_vertex as usize] = false;
+    }
+    for &defect_vertex in syndrome_pattern.defect_vertices.iter() {
+        assert!(parity[defect_vertex as usize], "the subgraph doesn't produce the syndrome");
+        parity[defect_vertex as usize] = false;
+    }
+    assert!(!parity.contains(&true), "the subgraph doesn't produce the syndrome");
+    weight
+}
+
+/// run the serial and, without erasures, the parallel solver on the case; any mismatch overwrites the reproducer file,
+/// so after shrinking it holds the minimal failing case
+fn check_against_exact_matcher(
+    initializer: SolverInitializer,
+    syndrome_pattern: SyndromePattern,
+) -> Result<(), TestCaseError> {
+    let exact_matching = ExactMatcher::new(&initializer)
+        .solve(&syndrome_pattern)
+        .expect("the errors form a perfect matching");
+    let partition_config = PartitionConfig::new(initializer.vertex_num);
+    let mut solvers: Vec<(&str, Box<dyn PrimalDualSolver>)> = vec![("serial", Box::new(SolverSerial::new(&initializer)))];
+    // the parallel solver doesn't support erasures yet
+    if syndrome_pattern.erasures.is_empty() {
+        solvers.push((
+            "parallel",
+            Box::new(SolverParallel::new(
+                &initializer,
+                &partition_config.info(),
+                serde_json::json!({}),
+            )),
+        ));
+    }
+    for (name, mut solver) in solvers {
+        solver.solve(&syndrome_pattern);
+        let sum_dual_variables = solver.sum_dual_variables();
+        let weight = subgraph_weight(&initializer, &syndrome_pattern, &solver.subgraph());
+        if sum_dual_variables != exact_matching.weight || weight != exact_matching.weight {
+            let dump = SolverDebugDump {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                solver: name.to_string(),
+                initializer: initializer.clone(),
+                syndrome_pattern: syndrome_pattern.clone(),
+                partition_config: (name == "parallel").then(|| partition_config.clone()),
+                primal_dual_config: serde_json::json!({}),
+            };
+            std::fs::create_dir_all("tmp").unwrap();
+            std::fs::write(REPRODUCER_FILE, serde_json::to_string(&dump).unwrap()).unwrap();
+            return Err(TestCaseError::fail(format!(
+                "{name} solver: sum of dual variables {sum_dual_variables}, subgraph weight {weight}, \
+                exact optimum {exact_matching:?}; reproducer written to {REPRODUCER_FILE}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(fuzz_config())]
+
+    #[test]
+    fn fuzz_exact_matching_random_graph((initializer, syndrome_pattern) in random_graph()) {
+        check_against_exact_matcher(initializer, syndrome_pattern)?;
+    }
+
+    #[test]
+    fn fuzz_exact_matching_planar_patch((initializer, syndrome_pattern) in random_planar_patch()) {
+        check_against_exact_matcher(initializer, syndrome_pattern)?;
+    }
+}
@@ -0,0 +1,59 @@
+//! decode the recorded syndromes in `tests/golden` and compare with the recorded results, see [`test_support::Golden`]
+#![cfg(feature = "full")]
+
+mod test_support;
+
+use fusion_blossom::example_codes::*;
+use fusion_blossom::example_partition::*;
+use test_support::Golden;
+
+#[test]
+fn golden_repetition_code() {
+    Golden::new(
+        "code_capacity_repetition_code_d11",
+        CodeCapacityRepetitionCode::new(11, 0.1, 500),
+    )
+    .shots(200)
+    .check();
+}
+
+#[test]
+fn golden_planar_code() {
+    Golden::new("code_capacity_planar_code_d7", CodeCapacityPlanarCode::new(7, 0.05, 500)).check();
+}
+
+#[test]
+fn golden_planar_code_erasures() {
+    let mut code = CodeCapacityPlanarCode::new(7, 0.03, 500);
+    code.set_erasure_probability(0.05);
+    Golden::new("code_capacity_planar_code_d7_erasures", code).check();
+}
+
+#[test]
+fn golden_phenomenological_planar_code() {
+    Golden::new(
+        "phenomenological_planar_code_d5",
+        PhenomenologicalPlanarCode::new(5, 5, 0.02, 500),
+    )
+    .check();
+}
+
+#[test]
+fn golden_planar_code_parallel() {
+    Golden::new(
+        "code_capacity_planar_code_d7_parallel",
+        CodeCapacityPlanarCode::new(7, 0.05, 500),
+    )
+    .partition(CodeCapacityPlanarCodeVerticalPartitionHalf::new(7, 4))
+    .check();
+}
+
+#[test]
+fn golden_phenomenological_planar_code_parallel() {
+    Golden::new(
+        "phenomenological_planar_code_d5_parallel",
+        PhenomenologicalPlanarCode::new(5, 5, 0.02, 500),
+    )
+    .partition(PhenomenologicalPlanarCodeTimePartition::new(5, 5, 2))
+    .check();
+}
@@ -0,0 +1,32 @@
+//! compare [`SolverInitializer::estimate_solver_memory`] with the increase of the resident set size of building a solver
+//! and decoding the densest syndrome; the only test of this file, so that no other test allocates in the same process
+
+use fusion_blossom::example_codes::{CircuitLevelPlanarCode, ExampleCode};
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverSerial, SolverType};
+use fusion_blossom::util::*;
+
+#[test]
+fn memory_estimate_resident_set_size() {
+    let code = CircuitLevelPlanarCode::new(21, 21, 0.005, 500);
+    let initializer = code.get_initializer();
+    let is_virtual: std::collections::BTreeSet<VertexIndex> = initializer.virtual_vertices.iter().cloned().collect();
+    let syndrome_pattern = SyndromePattern::new_vertices(
+        (0..initializer.vertex_num)
+            .filter(|vertex_index| !is_virtual.contains(vertex_index))
+            .collect(),
+    );
+    let estimate = initializer.estimate_solver_memory(SolverType::Serial, 1);
+    let Some(resident_before) = resident_memory() else {
+        eprintln!("the resident set size is only available on Linux, skipped");
+        return;
+    };
+    let mut solver = SolverSerial::new(&initializer);
+    solver.solve(&syndrome_pattern);
+    let resident_delta = resident_memory().unwrap() - resident_before;
+    let ratio = estimate.total() as f64 / resident_delta as f64;
+    println!(
+        "{}, resident set size increase: {resident_delta} bytes, ratio {ratio:.3}",
+        estimate.brief()
+    );
+    assert!((0.7..=1.3).contains(&ratio), "{estimate:?} vs {resident_delta} bytes");
+}
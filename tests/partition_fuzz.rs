@@ -0,0 +1,157 @@
+//! decode random syndromes with the parallel solver on random partitions of a phenomenological code and compare the
+//! matching weight with the serial solver; each sample draws the number of partitions, the split rounds, the fusion tree
+//! shape and a vertex ordering compatible with the partitions from its own seed. Run more samples with e.g.
+//! `FUSION_BLOSSOM_FUZZ_CASES=10000 cargo test --release --test partition_fuzz`, and reproduce a failing sample with
+//! `FUSION_BLOSSOM_FUZZ_SEED=<seed> FUSION_BLOSSOM_FUZZ_CASES=1`
+#![cfg(feature = "full")]
+
+use fusion_blossom::example_codes::{ExampleCode, PhenomenologicalPlanarCode};
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverParallel, SolverSerial};
+use fusion_blossom::ordering::VertexPermutation;
+use fusion_blossom::util::*;
+use rand::{Rng, SeedableRng};
+
+const D: VertexNum = 5;
+/// 32 rounds, so that each of the at most 16 partitions has at least 2 rounds and its first round can be the interface
+const NOISY_MEASUREMENTS: VertexNum = 31;
+const MAX_PARTITION_NUM: usize = 16;
+const SHOTS_PER_SAMPLE: usize = 3;
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .map(|value| value.parse().unwrap_or_else(|_| panic!("{name} must be a number")))
+        .unwrap_or(default)
+}
+
+/// a random binary tree over the sequential partitions, built by repeatedly fusing a random pair of neighboring units
+fn random_fusion_tree_shape(rng: &mut DeterministicRng, partition_num: usize) -> FusionTreeShape {
+    match rng.gen_range(0..3) {
+        0 => FusionTreeShape::BalancedBinary,
+        1 => FusionTreeShape::LinearChain,
+        _ => {
+            let mut pending_units: Vec<usize> = (0..partition_num).collect();
+            let mut fusions = vec![];
+            while pending_units.len() > 1 {
+                let index = rng.gen_range(0..pending_units.len() - 1);
+                fusions.push((pending_units[index], pending_units[index + 1]));
+                pending_units.remove(index + 1);
+                pending_units[index] = partition_num + fusions.len() - 1;
+            }
+            FusionTreeShape::Custom(fusions)
+        }
+    }
+}
+
+/// split the rounds into `partition_num` units of at least 2 rounds each; within each unit, the vertices in its first
+/// round are ordered before the others so that they can become the interface, and both groups are shuffled
+#[allow(clippy::unnecessary_cast)]
+fn random_partition(
+    rng: &mut DeterministicRng,
+    code: &PhenomenologicalPlanarCode,
+) -> (Vec<VertexIndex>, FusionTreeShape, Result<PartitionConfig, PartitionError>) {
+    let round_num = NOISY_MEASUREMENTS as usize + 1;
+    let partition_num = rng.gen_range(2..=MAX_PARTITION_NUM);
+    // choose the split rounds among the `round_num - partition_num` rounds left after giving each unit 2 rounds
+    let mut extra_rounds: Vec<usize> = (0..partition_num - 1)
+        .map(|_| rng.gen_range(0..=round_num - 2 * partition_num))
+        .collect();
+    extra_rounds.sort();
+    let unit_starts: Vec<usize> = std::iter::once(0)
+        .chain(extra_rounds.iter().enumerate().map(|(index, extra)| 2 * (index + 1) + extra))
+        .collect();
+    let unit_of_round = |round: usize| unit_starts.partition_point(|&start| start <= round) - 1;
+    let positions = code.get_positions();
+    let mut times: Vec<f64> = positions.iter().map(|position| position.t).collect();
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    times.dedup();
+    assert_eq!(times.len(), round_num, "one time coordinate per round");
+    let mut keys: Vec<(usize, bool, u64, VertexIndex)> = (0..positions.len())
+        .map(|vertex_index| {
+            let round = times.partition_point(|&t| t < positions[vertex_index].t);
+            let unit = unit_of_round(round);
+            (unit, round != unit_starts[unit], rng.gen(), vertex_index as VertexIndex)
+        })
+        .collect();
+    keys.sort();
+    let sequential_vertices: Vec<VertexIndex> = keys.iter().map(|key| key.3).collect();
+    let mut partition_config = PartitionConfig::new(positions.len() as VertexNum);
+    partition_config.partitions.clear();
+    for unit in 0..partition_num {
+        let start = keys.partition_point(|key| key.0 < unit);
+        let end = keys.partition_point(|key| key.0 <= unit);
+        partition_config
+            .partitions
+            .push(VertexRange::new(start as VertexIndex, end as VertexIndex));
+    }
+    let shape = random_fusion_tree_shape(rng, partition_num);
+    partition_config.fusions = shape.build_fusions(partition_num);
+    let permutation = VertexPermutation::new(sequential_vertices.clone());
+    let permuted_edges: Vec<_> = code
+        .get_initializer()
+        .weighted_edges
+        .iter()
+        .map(|&(vertex_1, vertex_2, weight)| (permutation.to_new(vertex_1), permutation.to_new(vertex_2), weight))
+        .collect();
+    let result = partition_config
+        .infer_interfaces(&permuted_edges)
+        .map(|_| partition_config.clone());
+    (sequential_vertices, shape, result)
+}
+
+#[allow(clippy::unnecessary_cast)]
+fn subgraph_weight(initializer: &SolverInitializer, subgraph: &[EdgeIndex]) -> Weight {
+    subgraph
+        .iter()
+        .map(|&edge_index| initializer.weighted_edges[edge_index as usize].2)
+        .sum()
+}
+
+fn check_sample(seed: u64) {
+    let mut rng = DeterministicRng::seed_from_u64(seed);
+    let mut code = PhenomenologicalPlanarCode::new(D, NOISY_MEASUREMENTS, 0.02, 500);
+    let (sequential_vertices, shape, partition_config) = random_partition(&mut rng, &code);
+    let partition_config = partition_config.unwrap_or_else(|error| {
+        panic!("sample seed {seed}: invalid partition with fusion tree shape {shape:?}: {error}");
+    });
+    code.reorder_vertices(&sequential_vertices).unwrap();
+    let initializer = code.get_initializer();
+    let partition_info = partition_config.info();
+    let report = |message: String| -> ! {
+        panic!(
+            "sample seed {seed}: {message}\npartition config: {}",
+            serde_json::to_string(&partition_config).unwrap()
+        )
+    };
+    if let Err(error) = partition_info.check_edges(&initializer.weighted_edges) {
+        report(format!("the vertex ordering disagrees with the partition: {error}"));
+    }
+    let mut serial_solver = SolverSerial::new(&initializer);
+    let mut parallel_solver = SolverParallel::new(&initializer, &partition_info, serde_json::json!({}));
+    code.set_probability(rng.gen_range(0.005..0.05));
+    for shot in 0..SHOTS_PER_SAMPLE {
+        let syndrome_pattern = code.generate_random_errors(rng.gen());
+        serial_solver.solve(&syndrome_pattern);
+        parallel_solver.solve(&syndrome_pattern);
+        let serial_weight = serial_solver.sum_dual_variables();
+        let parallel_weight = parallel_solver.sum_dual_variables();
+        let parallel_subgraph_weight = subgraph_weight(&initializer, &parallel_solver.subgraph());
+        if serial_weight != parallel_weight || serial_weight != parallel_subgraph_weight {
+            report(format!(
+                "shot {shot}: serial weight {serial_weight}, parallel weight {parallel_weight} and subgraph weight \
+                {parallel_subgraph_weight}, syndrome pattern {}",
+                serde_json::to_string(&syndrome_pattern).unwrap()
+            ));
+        }
+        serial_solver.clear();
+        parallel_solver.clear();
+    }
+}
+
+#[test]
+fn partition_fuzz_phenomenological_planar_code() {
+    let cases: u64 = env_or("FUSION_BLOSSOM_FUZZ_CASES", 200);
+    let first_seed: u64 = env_or("FUSION_BLOSSOM_FUZZ_SEED", 0);
+    for seed in first_seed..first_seed + cases {
+        check_sample(seed);
+    }
+}
@@ -0,0 +1,59 @@
+"""
+`SolverInitializer.from_check_matrix` builds the decoding graph directly from a scipy sparse check matrix
+"""
+
+import math
+import pytest
+import fusion_blossom as fb
+
+np = pytest.importorskip("numpy")
+sparse = pytest.importorskip("scipy.sparse")
+
+
+def repetition_code_check_matrix(n: int):
+    # column `j` is data qubit `j`, flipping checks `j - 1` and `j`
+    rows = [row for j in range(n) for row in (j - 1, j) if 0 <= row < n - 1]
+    columns = [j for j in range(n) for row in (j - 1, j) if 0 <= row < n - 1]
+    return sparse.coo_matrix((np.ones(len(rows), dtype=np.uint8), (rows, columns)), shape=(n - 1, n))
+
+
+@pytest.mark.parametrize("format", ["csc", "csr", "coo"])
+def test_check_matrix_repetition_code(format):
+    n = 5
+    H = repetition_code_check_matrix(n).asformat(format)
+    initializer = fb.SolverInitializer.from_check_matrix(H)
+    assert initializer.vertex_num == n - 1 + 2  # each boundary column gets its own virtual vertex
+    assert len(initializer.weighted_edges) == n
+    solver = fb.SolverSerial(initializer)
+    syndrome = np.zeros(n - 1, dtype=np.uint8)
+    syndrome[[1, 2]] = 1
+    correction = solver.decode(syndrome)
+    assert list(correction) == [0, 0, 1, 0, 0]
+    assert (H @ correction % 2 == syndrome).all()
+
+
+def test_check_matrix_error_probabilities():
+    n = 5
+    H = repetition_code_check_matrix(n).tocsr()
+    # the middle qubit is much less likely to flip, so the defects on both sides of it are matched to the boundaries
+    error_probabilities = [0.1, 0.1, 0.0001, 0.1, 0.1]
+    initializer = fb.SolverInitializer.from_check_matrix(H, error_probabilities=error_probabilities)
+    weights = [weight for (_, _, weight) in initializer.weighted_edges]
+    assert weights[2] == 2 * 500
+    assert weights[0] == 2 * round(500 * math.log(9) / math.log(9999))
+    solver = fb.SolverSerial(initializer)
+    correction, weight = solver.decode([0, 1, 1, 0], return_weight=True)
+    assert list(correction) == [1, 1, 0, 1, 1]
+    assert weight == 4 * weights[0]
+
+
+def test_check_matrix_invalid():
+    H = sparse.csc_matrix(np.array([[1, 1, 0], [1, 0, 1], [1, 0, 1]], dtype=np.uint8))
+    with pytest.raises(ValueError, match="column 0"):
+        fb.SolverInitializer.from_check_matrix(H)
+    with pytest.raises(ValueError):
+        fb.SolverInitializer.from_check_matrix(repetition_code_check_matrix(5), weights=[1, 1, 1])
+    with pytest.raises(ValueError):
+        fb.SolverInitializer.from_check_matrix(repetition_code_check_matrix(5), error_probabilities=[0.6] * 5)
+    with pytest.raises(TypeError):
+        fb.SolverInitializer.from_check_matrix([[1, 1], [0, 1]])
@@ -0,0 +1,70 @@
+"""
+`solver.decode` follows `pymatching.Matching.decode`: dense detection events in, dense correction over the edges out
+"""
+
+import pytest
+import fusion_blossom as fb
+
+np = pytest.importorskip("numpy")
+
+
+def prepare_code() -> fb.CodeCapacityPlanarCode:
+    return fb.CodeCapacityPlanarCode(d=7, p=0.1, max_half_weight=500)
+
+
+def prepare_solvers(initializer: fb.SolverInitializer) -> list:
+    partition_info = fb.PartitionConfig(initializer.vertex_num).info()
+    return [
+        fb.SolverSerial(initializer),
+        fb.SolverDualParallel(initializer, partition_info, {}),
+        fb.SolverParallel(initializer, partition_info, {}),
+    ]
+
+
+def dense_detection_events(initializer: fb.SolverInitializer, syndrome: fb.SyndromePattern, dtype) -> np.ndarray:
+    detection_events = np.zeros(initializer.vertex_num, dtype=dtype)
+    detection_events[syndrome.defect_vertices] = 1
+    return detection_events
+
+
+@pytest.mark.parametrize("dtype", [np.uint8, np.int64, bool])
+def test_decode_matches_sparse_api(dtype):
+    code = prepare_code()
+    initializer = code.get_initializer()
+    for solver in prepare_solvers(initializer):
+        for seed in range(10):
+            syndrome = code.generate_random_errors(seed=seed)
+            solver.solve(syndrome)
+            subgraph = solver.subgraph()
+            solver.clear()
+            detection_events = dense_detection_events(initializer, syndrome, dtype)
+            correction, weight = solver.decode(detection_events, return_weight=True)
+            assert correction.dtype == np.uint8
+            assert correction.shape == (len(initializer.weighted_edges),)
+            assert np.flatnonzero(correction).tolist() == subgraph
+            assert weight == sum(initializer.weighted_edges[edge_index][2] for edge_index in subgraph)
+            # the solver can decode again without explicitly clearing it
+            assert np.array_equal(solver.decode(detection_events), correction)
+
+
+def test_decode_list():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    solver = fb.SolverSerial(initializer)
+    syndrome = code.generate_random_errors(seed=0)
+    detection_events = dense_detection_events(initializer, syndrome, np.uint8)
+    assert np.array_equal(solver.decode(detection_events.tolist()), solver.decode(detection_events))
+    # trailing vertices can be omitted
+    assert solver.decode([]).sum() == 0
+
+
+def test_decode_invalid_input():
+    solver = fb.SolverSerial(prepare_code().get_initializer())
+    with pytest.raises(ValueError, match="0 and 1"):
+        solver.decode(np.array([0, 2, 1], dtype=np.uint8))
+    with pytest.raises(ValueError, match="1-D"):
+        solver.decode(np.zeros((2, 3), dtype=np.uint8))
+    with pytest.raises(ValueError, match="vertices"):
+        solver.decode(np.zeros(10000, dtype=np.uint8))
+    with pytest.raises(TypeError, match="boolean or integer"):
+        solver.decode(np.zeros(3, dtype=np.float64))
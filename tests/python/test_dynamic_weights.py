@@ -8,6 +8,7 @@ o --- * --- * --- o
 The weights determine the matching result
 """
 
+import pytest
 import fusion_blossom as fb
 
 def prepare_solver() -> fb.SolverSerial:
@@ -52,3 +53,42 @@ def test_dynamic_weight():
     solver.solve(fb.SyndromePattern([1, 2], erasures = [0, 2]))
     subgraph = solver.subgraph()
     assert subgraph == [0, 2]
+
+
+def test_dynamic_weight_restored():
+    solver = prepare_solver()
+    solver.solve(fb.SyndromePattern([1, 2]))
+    first = solver.subgraph()
+    solver.clear()
+    solver.solve(fb.SyndromePattern([1, 2], dynamic_weights = [(1, 400)]))
+    overridden = solver.subgraph()
+    solver.clear()
+    assert overridden != first
+    solver.solve(fb.SyndromePattern([1, 2]))
+    assert solver.subgraph() == first
+
+def test_update_weights():
+    solver = prepare_solver()
+    solver.update_weights([1], [400])
+    for _ in range(2):  # the change persists across decodings
+        solver.solve(fb.SyndromePattern([1, 2]))
+        assert solver.subgraph() == [0, 2]
+        solver.clear()
+    solver.update_weights([1], [100])
+    solver.solve(fb.SyndromePattern([1, 2]))
+    assert solver.subgraph() == [1]
+
+def test_invalid_edge_index():
+    solver = prepare_solver()
+    with pytest.raises(IndexError):
+        solver.solve(fb.SyndromePattern([1, 2], dynamic_weights = [(3, 48)]))
+    with pytest.raises(IndexError):
+        solver.solve(fb.SyndromePattern([1, 2], erasures = [5]))
+    with pytest.raises(IndexError):
+        solver.update_weights([3], [48])
+    with pytest.raises(ValueError):
+        solver.update_weights([0, 1], [48])
+    with pytest.raises(ValueError):
+        solver.update_weights([0], [47])
+    solver.solve(fb.SyndromePattern([1, 2]))  # the solver is still usable
+    assert solver.subgraph() == [1]
@@ -0,0 +1,98 @@
+"""
+The syndrome pattern can be given as numpy arrays instead of Python lists, see `py_indices` in `src/util.rs`.
+Uses the same graph as `test_dynamic_weights.py`:
+
+   1     2     3       edge
+o --- * --- * --- o
+0     1     2     3   vertex
+"""
+
+import pytest
+import fusion_blossom as fb
+
+np = pytest.importorskip("numpy")
+
+INTEGER_DTYPES = [np.int8, np.int16, np.int32, np.int64, np.uint8, np.uint16, np.uint32, np.uint64]
+
+
+def prepare_solver() -> fb.SolverSerial:
+    vertex_num = 4
+    weighted_edges = [(0, 1, 100), (1, 2, 100), (2, 3, 100)]
+    virtual_vertices = [0, 3]
+    initializer = fb.SolverInitializer(vertex_num, weighted_edges, virtual_vertices)
+    return fb.SolverSerial(initializer)
+
+
+@pytest.mark.parametrize("dtype", INTEGER_DTYPES)
+def test_integer_arrays(dtype):
+    syndrome = fb.SyndromePattern(defect_vertices=np.array([1, 2], dtype=dtype), erasures=np.array([0, 2], dtype=dtype))
+    assert syndrome.defect_vertices == [1, 2]
+    assert syndrome.erasures == [0, 2]
+    syndrome = fb.SyndromePattern(syndrome_vertices=np.array([1, 2], dtype=dtype))
+    assert syndrome.defect_vertices == [1, 2]
+    solver = prepare_solver()
+    solver.solve(fb.SyndromePattern(np.array([1, 2], dtype=dtype), erasures=np.array([0, 2], dtype=dtype)))
+    assert solver.subgraph() == [0, 2]
+
+
+@pytest.mark.parametrize("dtype", INTEGER_DTYPES)
+def test_solve_integer_array(dtype):
+    solver = prepare_solver()
+    solver.solve(np.array([1, 2], dtype=dtype))
+    assert solver.subgraph() == [1]
+    solver.clear()
+    solver.solve(np.array([], dtype=dtype))
+    assert solver.subgraph() == []
+
+
+def test_solve_list():
+    solver = prepare_solver()
+    solver.solve([1, 2])
+    assert solver.subgraph() == [1]
+
+
+def test_non_contiguous_array():
+    defect_vertices = np.array([1, 0, 2, 0], dtype=np.int64)[::2]
+    assert fb.SyndromePattern(defect_vertices).defect_vertices == [1, 2]
+
+
+def test_dense_boolean_array():
+    dense = np.array([False, True, True, False])
+    assert fb.SyndromePattern.from_dense(dense).defect_vertices == [1, 2]
+    assert fb.SyndromePattern.from_dense([False, True, True, False]).defect_vertices == [1, 2]
+    assert fb.SyndromePattern.from_dense(np.array([0, 1, 1, 0], dtype=np.uint8)).defect_vertices == [1, 2]
+    assert fb.SyndromePattern(dense).defect_vertices == [1, 2]
+    assert fb.SyndromePattern(erasures=np.array([True, False, True])).erasures == [0, 2]
+    solver = prepare_solver()
+    solver.solve(dense)
+    assert solver.subgraph() == [1]
+
+
+def test_wrong_dimension():
+    with pytest.raises(ValueError, match="1-D"):
+        fb.SyndromePattern(np.array([[1, 2]], dtype=np.uint32))
+    with pytest.raises(ValueError, match="1-D"):
+        prepare_solver().solve(np.zeros((2, 2), dtype=bool))
+
+
+def test_negative_index():
+    with pytest.raises(ValueError, match="negative"):
+        fb.SyndromePattern(np.array([1, -2], dtype=np.int32))
+
+
+def test_wrong_dtype():
+    with pytest.raises(TypeError, match="integer or boolean"):
+        fb.SyndromePattern(np.array([1.0, 2.0]))
+    with pytest.raises(TypeError, match="integer or boolean"):
+        prepare_solver().solve(np.array(["1", "2"]))
+    with pytest.raises(TypeError, match="boolean or integer"):
+        fb.SyndromePattern.from_dense(np.array([0.0, 1.0]))
+    with pytest.raises(ValueError, match="0 and 1"):
+        fb.SyndromePattern.from_dense(np.array([0, 2], dtype=np.uint8))
+
+
+def test_wrong_type():
+    with pytest.raises(TypeError, match="defect_vertices"):
+        fb.SyndromePattern("12")
+    with pytest.raises(TypeError, match="syndrome_pattern"):
+        prepare_solver().solve({1, 2})
@@ -0,0 +1,57 @@
+"""
+plan a partition from the positions and decode in parallel
+"""
+
+import pytest
+import fusion_blossom as fb
+
+
+def prepare_code() -> fb.PhenomenologicalPlanarCode:
+    return fb.PhenomenologicalPlanarCode(d=5, noisy_measurements=19, p=0.03, max_half_weight=500)
+
+
+@pytest.mark.parametrize("planner", ["from_positions_time_axis", "bisection"])
+def test_parallel_matches_serial(planner):
+    code = prepare_code()
+    positions = code.get_positions()
+    initializer = code.get_initializer()
+    plan = getattr(fb.PartitionPlan, planner)(positions, initializer, 4)
+    partition_info = plan.partition_info()
+    assert len(plan.partition_config.partitions) == 4
+    parallel_solver = fb.SolverParallel(plan.initializer(initializer), partition_info, {}, thread_pool_size=2)
+    serial_solver = fb.SolverSerial(initializer)
+    for seed in range(100):
+        syndrome = code.generate_random_errors(seed=seed)
+        serial_solver.solve(syndrome)
+        parallel_solver.solve(plan.permutation.map_syndrome(syndrome))
+        assert parallel_solver.sum_dual_variables() == serial_solver.sum_dual_variables()
+        serial_solver.clear()
+        parallel_solver.clear()
+
+
+def test_parallel_vertex_permutation():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    plan = fb.PartitionPlan.from_positions_time_axis(code.get_positions(), initializer, 4, fusion_tree="linear_chain")
+    permutation = plan.permutation
+    assert permutation.sequential_vertices == plan.sequential_vertices
+    for old_index in range(initializer.vertex_num):
+        assert permutation.to_old(permutation.to_new(old_index)) == old_index
+    syndrome = code.generate_random_errors(seed=0)
+    mapped = permutation.map_syndrome(syndrome.defect_vertices)
+    assert mapped.defect_vertices == sorted(permutation.to_new(vertex) for vertex in syndrome.defect_vertices)
+
+
+def test_parallel_incompatible_ordering():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    plan = fb.PartitionPlan.from_positions_time_axis(code.get_positions(), initializer, 4)
+    # an edge from the first to the last partition can never be handled by the parallel solver
+    permuted = plan.initializer(initializer)
+    permuted.weighted_edges = permuted.weighted_edges + [(0, initializer.vertex_num - 1, 2)]
+    with pytest.raises(ValueError, match="ordering"):
+        fb.SolverParallel(permuted, plan.partition_info(), {})
+    with pytest.raises(ValueError):
+        fb.PartitionPlan.from_positions_time_axis(code.get_positions(), initializer, 21)
+    with pytest.raises(ValueError):
+        fb.PartitionPlan.bisection(code.get_positions(), initializer, 4, fusion_tree="unknown")
@@ -0,0 +1,46 @@
+import copy
+import multiprocessing
+import pickle
+import fusion_blossom as fb
+
+
+def prepare_code() -> fb.PhenomenologicalPlanarCode:
+    return fb.PhenomenologicalPlanarCode(d=11, noisy_measurements=10, p=0.01, max_half_weight=500)
+
+
+def decode_in_worker(initializer: fb.SolverInitializer, syndrome: fb.SyndromePattern) -> int:
+    solver = fb.SolverSerial(initializer)
+    solver.solve(syndrome)
+    return solver.sum_dual_variables()
+
+
+def test_pickle_round_trip():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    syndrome = code.generate_random_errors(seed=1000)
+    partition_config = fb.PartitionConfig(initializer.vertex_num)
+    for value in [initializer, syndrome, partition_config, fb.SyndromePattern([1, 2], erasures=[3])]:
+        loaded = pickle.loads(pickle.dumps(value))
+        assert loaded == value
+        assert repr(loaded) == repr(value)
+        assert copy.deepcopy(value) == value
+        assert copy.copy(value) == value
+    assert fb.SyndromePattern([1, 2]) != fb.SyndromePattern([1, 3])
+    assert fb.SyndromePattern([1, 2]) != [1, 2]
+
+
+def test_deepcopy_is_independent():
+    syndrome = fb.SyndromePattern([1, 2])
+    copied = copy.deepcopy(syndrome)
+    copied.defect_vertices = [3]
+    assert syndrome.defect_vertices == [1, 2]
+
+
+def test_decode_in_worker_process():
+    code = prepare_code()
+    initializer = code.get_initializer()
+    syndrome = code.generate_random_errors(seed=1000)
+    expected = decode_in_worker(initializer, syndrome)
+    with multiprocessing.Pool(1) as pool:
+        weight = pool.apply(decode_in_worker, (initializer, syndrome))
+    assert weight == expected
@@ -0,0 +1,38 @@
+"""
+report the progress of the batch decoding of bit-packed `stim` detection events to a Python callable
+"""
+
+import fusion_blossom as fb
+
+SHOT_NUM = 20
+
+
+def write_bit_packed(filename: str, vertex_num: int, syndrome_patterns: list):
+    with open(filename, "wb") as f:
+        for syndrome_pattern in syndrome_patterns:
+            packed = bytearray((vertex_num + 7) // 8)
+            for vertex_index in syndrome_pattern.defect_vertices:
+                packed[vertex_index // 8] |= 1 << (vertex_index % 8)
+            f.write(packed)
+
+
+def test_progress_callback(tmp_path):
+    code = fb.CodeCapacityPlanarCode(d=5, p=0.1, max_half_weight=500)
+    initializer = code.get_initializer()
+    syndrome_patterns = [code.generate_random_errors(seed=seed) for seed in range(SHOT_NUM)]
+    in_file, out_file = str(tmp_path / "dets.b8"), str(tmp_path / "obs.b8")
+    write_bit_packed(in_file, initializer.vertex_num, syndrome_patterns)
+    edge_masks = [1] * len(initializer.weighted_edges)
+    partition_info = fb.PartitionConfig(initializer.vertex_num).info()
+    for solver in [fb.SolverSerial(initializer), fb.SolverParallel(initializer, partition_info, {})]:
+        events = []
+        solver.stim_integration_predict_bit_packed_data(
+            in_file, out_file, edge_masks, SHOT_NUM, initializer.vertex_num, 1, progress_callback=events.append
+        )
+        assert [event["completed"] for event in events] == list(range(1, SHOT_NUM + 1))
+        assert all(event["total"] == SHOT_NUM for event in events)
+        assert all(event["elapsed"] >= 0 for event in events)
+        with open(out_file, "rb") as f:
+            assert len(f.read()) == SHOT_NUM
+        # the callback is optional
+        solver.stim_integration_predict_bit_packed_data(in_file, out_file, edge_masks, SHOT_NUM, initializer.vertex_num, 1)
@@ -0,0 +1,85 @@
+"""
+iterate and index a syndrome pattern file with `ErrorPatternReader`
+"""
+
+import json
+import pytest
+import fusion_blossom as fb
+
+SHOT_NUM = 100
+
+
+def generate_syndrome_file(filename: str) -> list:
+    code = fb.PhenomenologicalPlanarCode(d=5, noisy_measurements=3, p=0.05, max_half_weight=500)
+    initializer = code.get_initializer()
+    positions = code.get_positions()
+    syndrome_patterns = [code.generate_random_errors(seed=seed) for seed in range(SHOT_NUM)]
+    with open(filename, "w") as f:
+        f.write("Syndrome Pattern v1.0   <initializer> <positions> <syndrome_pattern>*\n")
+        f.write(json.dumps({
+            "vertex_num": initializer.vertex_num,
+            "weighted_edges": initializer.weighted_edges,
+            "virtual_vertices": initializer.virtual_vertices,
+        }) + "\n")
+        f.write(json.dumps([{"i": position.i, "j": position.j, "t": position.t} for position in positions]) + "\n")
+        for syndrome_pattern in syndrome_patterns:
+            f.write(json.dumps({"defect_vertices": syndrome_pattern.defect_vertices}) + "\n")
+    return code, syndrome_patterns
+
+
+def test_syndrome_file_iterate(tmp_path):
+    filename = str(tmp_path / "test.syndromes")
+    code, syndrome_patterns = generate_syndrome_file(filename)
+    reader = fb.ErrorPatternReader(filename)
+    assert len(reader) == SHOT_NUM
+    assert reader.get_initializer() == code.get_initializer()
+    assert [(p.i, p.j, p.t) for p in reader.get_positions()] == [(p.i, p.j, p.t) for p in code.get_positions()]
+    iterated = [syndrome_pattern for syndrome_pattern in reader]
+    assert iterated == syndrome_patterns
+    # every iteration starts over from the beginning of the file
+    assert list(reader) == syndrome_patterns
+    iterator = iter(reader)
+    for syndrome_pattern in syndrome_patterns:
+        assert next(iterator) == syndrome_pattern
+    with pytest.raises(StopIteration):
+        next(iterator)
+
+
+def test_syndrome_file_index(tmp_path):
+    filename = str(tmp_path / "test.syndromes")
+    _, syndrome_patterns = generate_syndrome_file(filename)
+    reader = fb.ErrorPatternReader(filename)
+    for index in [0, 1, 42, SHOT_NUM - 1, -1, -SHOT_NUM]:
+        assert reader[index] == syndrome_patterns[index]
+    for index in [SHOT_NUM, -SHOT_NUM - 1]:
+        with pytest.raises(IndexError):
+            reader[index]
+    # indexing doesn't move the cursor of `generate_random_errors`
+    assert reader.generate_random_errors() == syndrome_patterns[0]
+    assert reader.generate_random_errors() == syndrome_patterns[1]
+
+
+def test_syndrome_file_benchmark_loop(tmp_path):
+    filename = str(tmp_path / "test.syndromes")
+    generate_syndrome_file(filename)
+    reader = fb.ErrorPatternReader(filename)
+    solver = fb.SolverSerial(reader.get_initializer())
+    for syndrome_pattern in reader:
+        solver.solve(syndrome_pattern)
+        solver.subgraph()
+        solver.clear()
+
+
+def test_syndrome_file_stream_index_and_cycle(tmp_path):
+    filename = str(tmp_path / "test.syndromes")
+    _, syndrome_patterns = generate_syndrome_file(filename)
+    reader = fb.ErrorPatternReader(filename, cyclic_syndrome=True, preload=False, read_ahead=7)
+    assert len(reader) == SHOT_NUM
+    for index in [SHOT_NUM - 1, 0, 42, -1]:
+        assert reader[index] == syndrome_patterns[index]
+    with pytest.raises(IndexError):
+        reader[SHOT_NUM]
+    # the syndrome patterns start over after the last one, which is counted
+    for shot in range(SHOT_NUM + 3):
+        assert reader.generate_random_errors() == syndrome_patterns[shot % SHOT_NUM]
+    assert reader.cycle_count == 1
@@ -0,0 +1,56 @@
+"""
+record the solving process with `Visualizer` used as a context manager
+"""
+
+import json
+import pytest
+import fusion_blossom as fb
+
+
+def solve_with_visualizer(filepath: str, **options):
+    code = fb.CodeCapacityPlanarCode(d=5, p=0.1, max_half_weight=500)
+    syndrome = code.generate_random_errors(seed=1)
+    assert len(syndrome.defect_vertices) > 0
+    solver = fb.SolverSerial(code.get_initializer())
+    with fb.Visualizer(filepath, positions=code.get_positions(), **options) as visualizer:
+        solver.solve_visualizer(syndrome, visualizer)
+        solver.subgraph()
+    return code, visualizer
+
+
+@pytest.mark.parametrize("filename", ["visualizer.json", "visualizer.json.gz"])
+def test_visualizer_context_manager(tmp_path, filename):
+    filepath = str(tmp_path / filename)
+    code, visualizer = solve_with_visualizer(filepath)
+    if filename.endswith(".gz"):
+        import gzip
+        with gzip.open(filepath, "rt") as f:
+            data = json.load(f)
+    else:
+        with open(filepath) as f:
+            data = json.load(f)
+    assert len(data["positions"]) == code.vertex_num()
+    assert len(data["snapshots"]) >= 1
+    assert len(data["snapshots"]) == len(visualizer.snapshots)
+    # no more snapshots after the visualizer is closed
+    with pytest.raises(OSError):
+        visualizer.snapshot("after closed", code)
+    html_path = str(tmp_path / "visualizer.html")
+    visualizer.standalone_html(html_path)
+    with open(html_path) as f:
+        assert "<html" in f.read()
+
+
+def test_visualizer_decimation(tmp_path):
+    _, all_snapshots = solve_with_visualizer(str(tmp_path / "all.json"))
+    _, decimated = solve_with_visualizer(str(tmp_path / "decimated.json"), every_nth_snapshot=1000, include=["Growth", "Final"])
+    assert 1 <= len(decimated.snapshots) < len(all_snapshots.snapshots)
+    with pytest.raises(ValueError):
+        fb.Visualizer(str(tmp_path / "invalid.json"), every_nth_snapshot=0)
+    with pytest.raises(ValueError):
+        fb.Visualizer(str(tmp_path / "invalid.json"), include=["NotACategory"])
+
+
+def test_print_visualize_link(capsys):
+    fb.print_visualize_link("visualizer.json")
+    assert "filename=visualizer.json" in capsys.readouterr().out
@@ -0,0 +1,156 @@
+//! shared helpers of the integration tests
+//!
+//! [`Golden`] is a regression test of a decoder on recorded syndromes: the syndrome file `tests/golden/<name>.syndromes`
+//! is decoded shot by shot, and the matching weight and the canonicalized matching of every shot are compared with
+//! `tests/golden/<name>.expected`. Run the tests with `UPDATE_GOLDEN=1` (or `FUSION_BLOSSOM_UPDATE_GOLDEN=1`) to write the
+//! expected values of the current decoder, which also generates a missing syndrome file; delete the syndrome file to record new syndromes.
+
+use fusion_blossom::example_codes::*;
+use fusion_blossom::example_partition::ExamplePartition;
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverParallel, SolverSerial};
+use fusion_blossom::util::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+/// the decoding result of a single shot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenShot {
+    pub weight: Weight,
+    /// sorted pairs of matched vertices, where a defect matched to the boundary is paired with a virtual vertex
+    pub matching: Vec<(VertexIndex, VertexIndex)>,
+}
+
+pub struct Golden {
+    name: String,
+    code: Box<dyn ExampleCode>,
+    /// decode with [`SolverParallel`] on this partition instead of [`SolverSerial`]
+    partition_config: Option<PartitionConfig>,
+    /// the number of shots when generating the syndrome file
+    shots: usize,
+}
+
+impl Golden {
+    pub fn new(name: &str, code: impl ExampleCode + 'static) -> Self {
+        Self {
+            name: name.to_string(),
+            code: Box::new(code),
+            partition_config: None,
+            shots: 100,
+        }
+    }
+
+    /// decode with the parallel solver; the partition may reorder the vertices of the code
+    pub fn partition(mut self, mut partition: impl ExamplePartition) -> Self {
+        self.partition_config = Some(partition.build_apply(self.code.as_mut()));
+        self
+    }
+
+    pub fn shots(mut self, shots: usize) -> Self {
+        self.shots = shots;
+        self
+    }
+
+    fn path(&self, extension: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("golden")
+            .join(format!("{}.{extension}", self.name))
+    }
+
+    pub fn check(mut self) {
+        let update = ["UPDATE_GOLDEN", "FUSION_BLOSSOM_UPDATE_GOLDEN"]
+            .iter()
+            .any(|variable| std::env::var(variable).is_ok_and(|value| value == "1"));
+        let syndrome_path = self.path("syndromes");
+        let expected_path = self.path("expected");
+        if !syndrome_path.exists() {
+            assert!(
+                update,
+                "missing {}, run with UPDATE_GOLDEN=1 to generate it",
+                syndrome_path.display()
+            );
+            let mut syndrome_file = SyndromeFile::new(self.code.get_initializer(), self.code.get_positions());
+            for seed in 0..self.shots {
+                syndrome_file.push(self.code.generate_random_errors(seed as u64), None);
+            }
+            std::fs::create_dir_all(syndrome_path.parent().unwrap()).unwrap();
+            syndrome_file.save(syndrome_path.to_str().unwrap(), false).unwrap();
+        }
+        let syndrome_file = SyndromeFile::load(syndrome_path.to_str().unwrap()).unwrap();
+        assert!(
+            syndrome_file.initializer == self.code.get_initializer(),
+            "the decoding graph of {} has changed, delete {} and run with UPDATE_GOLDEN=1 to record new syndromes",
+            self.name,
+            syndrome_path.display()
+        );
+        let shots = self.decode(&syndrome_file);
+        if update {
+            let mut output = std::fs::File::create(&expected_path).unwrap();
+            for shot in shots.iter() {
+                writeln!(output, "{}", serde_json::to_string(shot).unwrap()).unwrap();
+            }
+            return;
+        }
+        let expected: Vec<GoldenShot> =
+            std::io::BufReader::new(std::fs::File::open(&expected_path).unwrap_or_else(|error| {
+                panic!(
+                    "cannot open {}: {error}, run with UPDATE_GOLDEN=1 to generate it",
+                    expected_path.display()
+                )
+            }))
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+        assert_eq!(expected.len(), shots.len(), "the number of shots of {} differs", self.name);
+        let mismatches: Vec<_> = (0..shots.len()).filter(|&index| shots[index] != expected[index]).collect();
+        if let Some(&first) = mismatches.first() {
+            panic!(
+                "{} of {} shots of {} differ from the golden, e.g. shot {first}: expected {:?}, got {:?}; \
+                run with UPDATE_GOLDEN=1 if the change is intended",
+                mismatches.len(),
+                shots.len(),
+                self.name,
+                expected[first],
+                shots[first]
+            );
+        }
+    }
+
+    fn decode(&mut self, syndrome_file: &SyndromeFile) -> Vec<GoldenShot> {
+        let initializer = &syndrome_file.initializer;
+        let mut solver: Box<dyn PrimalDualSolver> = match &self.partition_config {
+            Some(partition_config) => Box::new(SolverParallel::new(
+                initializer,
+                &partition_config.info(),
+                serde_json::json!({}),
+            )),
+            None => Box::new(SolverSerial::new(initializer)),
+        };
+        let mut shots = vec![];
+        for syndrome_pattern in syndrome_file.syndrome_patterns.iter() {
+            solver.solve(syndrome_pattern);
+            let weight = solver.sum_dual_variables();
+            let mwpm_result = solver
+                .perfect_matching()
+                .legacy_get_mwpm_result(syndrome_pattern.defect_vertices.clone());
+            solver.clear();
+            shots.push(GoldenShot {
+                weight,
+                matching: canonical_matching(&syndrome_pattern.defect_vertices, &mwpm_result),
+            });
+        }
+        shots
+    }
+}
+
+/// each matched pair appears once with the smaller vertex first, in ascending order
+fn canonical_matching(defect_vertices: &[VertexIndex], mwpm_result: &[VertexIndex]) -> Vec<(VertexIndex, VertexIndex)> {
+    let matching: BTreeSet<_> = defect_vertices
+        .iter()
+        .zip(mwpm_result.iter())
+        .map(|(&a, &b)| (a.min(b), a.max(b)))
+        .collect();
+    matching.into_iter().collect()
+}
@@ -0,0 +1,90 @@
+//! the `tracing` spans of a parallel solve and of the syndrome file I/O, recorded by a minimal capturing subscriber;
+//! run with `cargo test --features tracing --test tracing_spans`
+#![cfg(feature = "tracing")]
+
+use fusion_blossom::example_codes::*;
+use fusion_blossom::example_partition::*;
+use fusion_blossom::mwpm_solver::{PrimalDualSolver, SolverParallel};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// the name and the fields of a span
+type CapturedSpan = (&'static str, BTreeMap<&'static str, String>);
+
+struct FieldVisitor<'a>(&'a mut BTreeMap<&'static str, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+/// records every created span, regardless of the thread, to capture the spans in the rayon thread pool
+#[derive(Default)]
+struct CapturingSubscriber {
+    spans: Arc<Mutex<Vec<CapturedSpan>>>,
+    next_id: AtomicU64,
+}
+
+impl Subscriber for CapturingSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = BTreeMap::new();
+        span.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().push((span.metadata().name(), fields));
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+    fn event(&self, _event: &Event<'_>) {}
+    fn enter(&self, _span: &Id) {}
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn tracing_spans_parallel_solve() {
+    let subscriber = CapturingSubscriber::default();
+    let spans = subscriber.spans.clone();
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+    let (d, noisy_measurements, partition_num) = (5, 11, 4);
+    let mut code = PhenomenologicalPlanarCode::new(d, noisy_measurements, 0.05, 500);
+    let partition_config =
+        PhenomenologicalPlanarCodeTimePartition::new(d, noisy_measurements, partition_num).build_apply(&mut code);
+    let initializer = code.get_initializer();
+    let mut solver = SolverParallel::new(&initializer, &partition_config.info(), serde_json::json!({}));
+    let mut syndrome_file = SyndromeFile::new(initializer.clone(), code.get_positions());
+    for seed in 0..3 {
+        syndrome_file.push(code.generate_random_errors(seed), None);
+    }
+    let mut content = vec![];
+    syndrome_file.write(&mut content, false).unwrap();
+    let syndrome_file = SyndromeFile::read(content.as_slice()).unwrap();
+    for syndrome_pattern in syndrome_file.syndrome_patterns.iter() {
+        solver.solve(syndrome_pattern);
+        solver.clear();
+    }
+    let spans = spans.lock().unwrap();
+    let count = |name: &str| spans.iter().filter(|(span_name, _)| *span_name == name).count();
+    assert_eq!(count("solver_new"), 1);
+    assert_eq!(count("syndrome_file_write_header"), 1);
+    assert_eq!(count("syndrome_file_write"), 3);
+    assert_eq!(count("syndrome_file_read_header"), 1);
+    // the last read finds the footer
+    assert_eq!(count("syndrome_file_read"), 4);
+    // 4 leaf units and 3 fusions per syndrome pattern
+    assert_eq!(count("solve_unit"), 3 * (2 * partition_num - 1));
+    assert_eq!(count("fuse"), 3 * (partition_num - 1));
+    assert_eq!(count("load_syndrome"), 3 * partition_num);
+    assert!(count("grow_resolve") >= 3 * (2 * partition_num - 1));
+    for (_, fields) in spans.iter().filter(|(name, _)| *name == "fuse") {
+        let unit_index: usize = fields["unit_index"].parse().unwrap();
+        assert!(unit_index >= partition_num && unit_index < 2 * partition_num - 1);
+        assert!(fields.contains_key("left_unit_index") && fields.contains_key("right_unit_index"));
+    }
+}
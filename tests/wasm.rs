@@ -0,0 +1,15 @@
+//! headless WebAssembly test, run by `test_wasm.sh` with `wasm-pack test --node --features wasm`
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use fusion_blossom::wasm::*;
+use wasm_bindgen_test::*;
+
+#[wasm_bindgen_test]
+fn wasm_decode_planar_code() {
+    let code: serde_json::Value = serde_json::from_str(&code_capacity_planar_code(5, 0.1, 500).unwrap()).unwrap();
+    let initializer_json = code["initializer"].to_string();
+    let subgraph = decode(&initializer_json, r#"{"defect_vertices":[7,14]}"#).unwrap();
+    assert_eq!(subgraph, "[15,19]");
+    // invalid input is reported to JavaScript instead of aborting the instance
+    assert!(decode(&initializer_json, r#"{"defect_vertices":[1000]}"#).is_err());
+}
@@ -0,0 +1,14 @@
+// decode a syndrome of the d = 5 planar code in JavaScript, run by `test_wasm.sh` after building the package with
+// `wasm-pack build --target nodejs --features wasm`; in the browser, build with `--target web` and import the same functions
+const assert = require("assert")
+const fb = require("../../pkg/fusion_blossom.js")
+
+// the initializer can also be written by hand, e.g. `{ vertex_num: 4, weighted_edges: [[0, 1, 100], ...], virtual_vertices: [0, 3] }`
+const code = JSON.parse(fb.code_capacity_planar_code(5, 0.1, 500))
+const syndrome = { defect_vertices: [7, 14] }
+const subgraph = JSON.parse(fb.decode(JSON.stringify(code.initializer), JSON.stringify(syndrome)))
+console.log(`Minimum Weight Parity Subgraph (MWPS): ${subgraph}`)
+assert.deepStrictEqual(subgraph, [15, 19])
+
+// invalid input throws an error instead of aborting the WebAssembly instance
+assert.throws(() => fb.decode(JSON.stringify(code.initializer), JSON.stringify({ defect_vertices: [1000] })))